@@ -0,0 +1,142 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use nlsn_delaunay::{Edge, Polyline, RefineParams, Triangulator, Vertex};
+
+use std::rc::Rc;
+
+/**
+ * Linear congruential generator, so that every run benchmarks the same
+ * points.
+ */
+fn random_points(count: usize) -> Vec<(f64, f64)> {
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        return (state >> 11) as f64 / (1u64 << 53) as f64;
+    };
+    return (0..count)
+        .map(|_| (0.001 + 0.998 * next(), 0.001 + 0.998 * next()))
+        .collect();
+}
+
+fn square(size: f64) -> Rc<Polyline> {
+    return Rc::new(
+        Polyline::new_closed(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(size, 0.0)),
+            Rc::new(Vertex::new(size, size)),
+            Rc::new(Vertex::new(0.0, size)),
+        ])
+        .unwrap(),
+    );
+}
+
+fn regular_polygon(center: (f64, f64), radius: f64, sides: usize) -> Rc<Polyline> {
+    let vertices = (0..sides)
+        .map(|index| {
+            let angle = 2.0 * std::f64::consts::PI * index as f64 / sides as f64;
+            return Rc::new(Vertex::new(
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            ));
+        })
+        .collect();
+    return Rc::new(Polyline::new_closed(vertices).unwrap());
+}
+
+fn random_points_triangulation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("random points");
+    group.sample_size(10);
+    for count in vec![1_000, 100_000, 1_000_000] {
+        let points = random_points(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &points, |b, points| {
+            b.iter(|| {
+                let mut triangulator = Triangulator::new(&square(1.0));
+                triangulator
+                    .insert_vertices_iter(points.iter().cloned())
+                    .unwrap();
+                return triangulator;
+            });
+        });
+    }
+    group.finish();
+}
+
+fn segment_insertion(c: &mut Criterion) {
+    /* disjoint horizontal segments, each crossing many triangles */
+    let segments: Vec<Rc<Edge>> = (1..50)
+        .map(|index| {
+            let y = index as f64 / 50.0 + 0.003;
+            return Rc::new(Edge::new(
+                &Rc::new(Vertex::new(0.05, y)),
+                &Rc::new(Vertex::new(0.95, y)),
+            ));
+        })
+        .collect();
+    let points = random_points(2_000);
+
+    c.bench_function("segment insertion", |b| {
+        b.iter(|| {
+            let mut triangulator = Triangulator::new(&square(1.0));
+            triangulator
+                .insert_segments(&segments.iter().cloned().collect())
+                .unwrap();
+            triangulator.try_triangulate().unwrap();
+            triangulator
+                .insert_vertices_iter(points.iter().cloned())
+                .unwrap();
+            return triangulator;
+        });
+    });
+}
+
+fn refinement(c: &mut Criterion) {
+    let mut group = c.benchmark_group("refinement");
+    group.sample_size(10);
+    for min_angle in vec![20.0_f64, 30.0] {
+        /* radius-edge ratio bounding the smallest angle */
+        let params = RefineParams {
+            max_area: None,
+            quality_ratio: 0.5 / min_angle.to_radians().sin(),
+            max_edge_length: None,
+            regions: Vec::new(),
+        };
+        let boundary = regular_polygon((0.0, 0.0), 1.0, 64);
+        let hole = regular_polygon((0.3, 0.1), 0.2, 16);
+        let id = BenchmarkId::from_parameter(format!("{} degrees", min_angle));
+        group.bench_with_input(id, &params, |b, params| {
+            b.iter(|| {
+                let mut triangulator = Triangulator::new(&boundary);
+                triangulator.insert_hole(&hole).unwrap();
+                triangulator.try_triangulate().unwrap();
+                triangulator.try_refine(params.clone()).unwrap();
+                return triangulator;
+            });
+        });
+    }
+    group.finish();
+}
+
+fn polyline_booleans(c: &mut Criterion) {
+    let p1 = regular_polygon((0.0, 0.0), 1.0, 64);
+    let p2 = regular_polygon((0.7, 0.3), 1.0, 64);
+
+    c.bench_function("polyline union", |b| b.iter(|| Polyline::union(&p1, &p2)));
+    c.bench_function("polyline intersection", |b| {
+        b.iter(|| Polyline::intersection(&p1, &p2))
+    });
+    c.bench_function("polyline subtraction", |b| {
+        b.iter(|| Polyline::subtraction(&p1, &p2))
+    });
+}
+
+criterion_group!(
+    benches,
+    random_points_triangulation,
+    segment_insertion,
+    refinement,
+    polyline_booleans
+);
+criterion_main!(benches);
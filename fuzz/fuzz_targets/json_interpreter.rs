@@ -0,0 +1,48 @@
+#![no_main]
+
+/* the json interpreter belongs to the command line tool, not the library */
+#[path = "../../src/json_serializar/mod.rs"]
+#[allow(dead_code)]
+mod json_serializar;
+#[path = "../../src/triangulator_interface/mod.rs"]
+#[allow(dead_code)]
+mod triangulator_interface;
+
+use json_serializar::models::input::TriangulationInput;
+use libfuzzer_sys::fuzz_target;
+
+/*
+ * Feeds arbitrary bytes to the json interpreter, as the command line tool
+ * reads its input files: schema validation, then interpretation into one
+ * triangulator per connected domain, then triangulation. Inputs rejected
+ * on the way are fine, while panics, hangs and triangulations breaking the
+ * invariants are not. Refinement is left out, so that slow refinements of
+ * tiny features are not reported as hangs.
+ */
+fuzz_target!(|data: &[u8]| {
+    let json: serde_json::Value = match serde_json::from_slice(data) {
+        Ok(json) => json,
+        Err(_) => return,
+    };
+    if !json_serializar::validation::validate(&json).is_empty() {
+        return;
+    }
+    let input: TriangulationInput = match serde_json::from_value(json) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+
+    let (components, _) = match triangulator_interface::parse(&input) {
+        Ok(parsed) => parsed,
+        Err(_) => return,
+    };
+    for mut triangulator in components.into_iter() {
+        if triangulator.try_triangulate().is_err() {
+            continue;
+        }
+        if let Err(violations) = triangulator.validate() {
+            let lines: Vec<String> = violations.iter().map(|v| format!("  {}", v)).collect();
+            panic!("invalid triangulation\n{}", lines.join("\n"));
+        }
+    }
+});
@@ -0,0 +1,67 @@
+/*
+ * C bindings of nlsn-delaunay, built with `cargo build --release --features ffi`
+ * into target/release/libnlsn_delaunay.so (.dylib, .dll).
+ *
+ * Geometry is given in flat arrays of doubles and triangles are returned as
+ * zero based int32 point indices, as in Triangle's triangulateio. Meshes are
+ * owned by the library and released by nlsn_free_mesh.
+ */
+#ifndef NLSN_DELAUNAY_H
+#define NLSN_DELAUNAY_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define NLSN_OK 0
+#define NLSN_INVALID_ARGUMENT 1
+#define NLSN_OUTSIDE_BOUNDARY 2
+#define NLSN_SELF_INTERSECTING 3
+#define NLSN_DEGENERATE_INPUT 4
+#define NLSN_UNRECOVERABLE_CONSTRAINT 5
+#define NLSN_INTERNAL_ERROR 6
+#define NLSN_NON_TERMINATING_REFINEMENT 7
+
+typedef struct nlsn_input {
+    /* boundary_count (x, y) vertices of the closed boundary */
+    const double *boundary;
+    int32_t boundary_count;
+    /* hole polygons, concatenated as (x, y) vertices, hole_sizes vertices each */
+    const double *holes;
+    const int32_t *hole_sizes;
+    int32_t hole_count;
+    /* vertex_count (x, y) vertices to be inserted */
+    const double *vertices;
+    int32_t vertex_count;
+    /* segment_count (x1, y1, x2, y2) constraint segments */
+    const double *segments;
+    int32_t segment_count;
+} nlsn_input;
+
+typedef struct nlsn_mesh {
+    /* point_count (x, y) points */
+    double *points;
+    int32_t point_count;
+    /* triangle_count triangles, three point indices each */
+    int32_t *triangles;
+    int32_t triangle_count;
+} nlsn_mesh;
+
+/* Constrained Delaunay triangulation of the input */
+int32_t nlsn_triangulate(const nlsn_input *input, nlsn_mesh *output);
+
+/* Refined triangulation, bounding areas only if max_area is positive,
+   for quality ratios above 1 / sqrt(3) */
+int32_t nlsn_refine(const nlsn_input *input, double max_area, double quality_ratio,
+                    nlsn_mesh *output);
+
+/* Releases the arrays of the mesh, resetting it to an empty mesh */
+void nlsn_free_mesh(nlsn_mesh *mesh);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* NLSN_DELAUNAY_H */
@@ -0,0 +1,182 @@
+use std::fmt::Write;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/**
+ * Outcome of one input of a batch: vertex and triangle counts, or the
+ * error message, and the time spent on it.
+ */
+pub struct Report {
+    pub name: String,
+    pub result: Result<(usize, usize), String>,
+    pub duration: Duration,
+}
+
+/**
+ * Json inputs of the directory, sorted by name.
+ */
+pub fn discover(input_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut inputs: Vec<PathBuf> = Vec::new();
+    for entry in std::fs::read_dir(input_dir)? {
+        let path = entry?.path();
+        if path.is_file()
+            && path
+                .extension()
+                .map_or(false, |extension| extension == "json")
+        {
+            inputs.push(path);
+        }
+    }
+    inputs.sort();
+    return Ok(inputs);
+}
+
+/**
+ * Output path matching the input name, in the output directory.
+ */
+pub fn output_path(input: &Path, output_dir: &Path, extension: &str) -> PathBuf {
+    let stem = input.file_stem().unwrap_or_default();
+    return output_dir.join(stem).with_extension(extension);
+}
+
+/**
+ * Processes every input on the given count of threads, each taking the
+ * next pending input, so that reports are in the order of the inputs.
+ */
+pub fn process<F>(inputs: &Vec<PathBuf>, jobs: usize, process: F) -> Vec<Report>
+where
+    F: Fn(&Path) -> Result<(usize, usize), String> + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let reports: Mutex<Vec<Option<Report>>> = Mutex::new(inputs.iter().map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.max(1).min(inputs.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                if index >= inputs.len() {
+                    break;
+                }
+                let input = &inputs[index];
+                let start = Instant::now();
+                let result = process(input);
+                let report = Report {
+                    name: input.file_name().unwrap().to_string_lossy().to_string(),
+                    result,
+                    duration: start.elapsed(),
+                };
+                reports.lock().unwrap()[index] = Some(report);
+            });
+        }
+    });
+
+    return reports
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|report| report.unwrap())
+        .collect();
+}
+
+/**
+ * Table of vertex and triangle counts and timings per input, with totals.
+ */
+pub fn summary(reports: &Vec<Report>) -> String {
+    let width = reports
+        .iter()
+        .map(|report| report.name.len())
+        .chain(std::iter::once(4))
+        .max()
+        .unwrap();
+
+    let mut table = String::new();
+    let _ = writeln!(
+        table,
+        "{:<width$} {:>10} {:>10} {:>12}",
+        "file",
+        "vertices",
+        "triangles",
+        "time (ms)",
+        width = width
+    );
+    for report in reports.iter() {
+        let milliseconds = report.duration.as_secs_f64() * 1000.0;
+        let _ = match &report.result {
+            Ok((vertices, triangles)) => writeln!(
+                table,
+                "{:<width$} {:>10} {:>10} {:>12.3}",
+                report.name,
+                vertices,
+                triangles,
+                milliseconds,
+                width = width
+            ),
+            Err(error) => writeln!(
+                table,
+                "{:<width$} failed: {}",
+                report.name,
+                error,
+                width = width
+            ),
+        };
+    }
+
+    let failed = reports
+        .iter()
+        .filter(|report| report.result.is_err())
+        .count();
+    let total: Duration = reports.iter().map(|report| report.duration).sum();
+    let _ = writeln!(
+        table,
+        "{} files, {} failed, {:.3} ms",
+        reports.len(),
+        failed,
+        total.as_secs_f64() * 1000.0
+    );
+    return table;
+}
+
+#[cfg(test)]
+mod batch {
+    use super::*;
+
+    #[test]
+    fn discover_and_process() {
+        let input_dir = std::env::temp_dir().join(format!("nlsn-batch-{}", std::process::id()));
+        std::fs::create_dir_all(&input_dir).unwrap();
+        for name in vec!["b.json", "a.json", "c.txt"] {
+            std::fs::write(input_dir.join(name), "{}").unwrap();
+        }
+
+        let inputs = discover(&input_dir).unwrap();
+        std::fs::remove_dir_all(&input_dir).unwrap();
+        assert_eq!(
+            inputs,
+            vec![input_dir.join("a.json"), input_dir.join("b.json")]
+        );
+        assert_eq!(
+            output_path(&inputs[0], Path::new("out"), "msh"),
+            Path::new("out").join("a.msh")
+        );
+
+        let reports = process(&inputs, 4, |input| {
+            if input.ends_with("b.json") {
+                return Err(String::from("degenerate"));
+            }
+            return Ok((3, 1));
+        });
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].name, "a.json");
+        assert_eq!(reports[0].result, Ok((3, 1)));
+        assert_eq!(reports[1].result, Err(String::from("degenerate")));
+
+        let table = summary(&reports);
+        assert!(table.starts_with("file     vertices  triangles    time (ms)\n"));
+        assert!(table.contains("\na.json          3          1"));
+        assert!(table.contains("\nb.json failed: degenerate\n"));
+        assert!(table.contains("2 files, 1 failed, "));
+    }
+} /* end - batch tests */
@@ -0,0 +1,277 @@
+extern crate serde;
+
+use serde::{Deserialize, Serialize};
+
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+
+use crate::json_serializar::{self, models::input::TriangulationInput};
+use crate::triangulator_interface;
+
+use nlsn_delaunay::planar::{quality_report::QualityReport, triangulator::Triangulator};
+
+/**
+ * Conformance corpus runner. Triangulates every input json in
+ * `<corpus>/inputs` and compares its mesh statistics against the golden
+ * results stored in `<corpus>/golden`, within the golden tolerances.
+ * Refinement order follows hashing, which is seeded per process, so each
+ * input is triangulated several times: the mean counts are compared with
+ * relative tolerances, the worst angle and quality with their bounds, while
+ * the meshed area must be kept.
+ *
+ *  - CONFORMANCE_DIR: corpus directory, defaults to tests/conformance
+ *  - CONFORMANCE_BLESS: if set, golden results are (re)written
+ *
+ * Run with `cargo test --features conformance`.
+ */
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Fingerprint {
+    pub vertices: usize,
+    pub triangles: usize,
+    pub area: f64,
+    pub min_angle: f64,
+    pub max_quality: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Tolerance {
+    #[serde(default = "default_count")]
+    pub count: f64, /* relative */
+
+    #[serde(default = "default_area")]
+    pub area: f64, /* relative */
+
+    #[serde(default = "default_angle")]
+    pub angle: f64, /* degrees */
+
+    #[serde(default = "default_quality")]
+    pub quality: f64, /* relative */
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Golden {
+    pub fingerprint: Fingerprint,
+
+    #[serde(default = "default_tolerance")]
+    pub tolerance: Tolerance,
+}
+
+/* Triangulations of each input, whose statistics are averaged */
+const RUNS: usize = 5;
+
+fn default_count() -> f64 {
+    0.1
+}
+
+fn default_area() -> f64 {
+    1.0E-9
+}
+
+fn default_angle() -> f64 {
+    1.0
+}
+
+fn default_quality() -> f64 {
+    0.05
+}
+
+fn default_tolerance() -> Tolerance {
+    Tolerance {
+        count: default_count(),
+        area: default_area(),
+        angle: default_angle(),
+        quality: default_quality(),
+    }
+}
+
+impl Fingerprint {
+    pub fn from_triangulator(triangulator: &Triangulator) -> Self {
+        let triangulation = triangulator.triangulation.borrow();
+
+        let report = QualityReport::new(&triangulation);
+
+        let mut area: f64 = 0.0;
+        let mut max_quality: f64 = 0.0;
+
+        for triangle in triangulation.solid_triangles() {
+            area += triangle.area().unwrap().abs();
+            max_quality = max_quality.max(triangle.quality().unwrap());
+        }
+
+        return Self {
+            vertices: triangulation.vertices().len(),
+            triangles: report.triangles,
+            area,
+            min_angle: report.min_angle().unwrap_or(0.0),
+            max_quality,
+        };
+    }
+
+    /**
+     * Mean counts and area of the runs, along with their worst angle and
+     * quality.
+     */
+    pub fn from_runs(runs: &Vec<Fingerprint>) -> Self {
+        let count = runs.len() as f64;
+        let mean = |value: fn(&Fingerprint) -> f64| runs.iter().map(value).sum::<f64>() / count;
+
+        return Self {
+            vertices: mean(|run| run.vertices as f64).round() as usize,
+            triangles: mean(|run| run.triangles as f64).round() as usize,
+            area: mean(|run| run.area),
+            min_angle: runs
+                .iter()
+                .map(|run| run.min_angle)
+                .fold(std::f64::INFINITY, f64::min),
+            max_quality: runs.iter().map(|run| run.max_quality).fold(0.0, f64::max),
+        };
+    }
+
+    /**
+     * Lists the statistics that drifted from the golden ones.
+     */
+    pub fn drifts(&self, golden: &Golden) -> Vec<String> {
+        let expected = &golden.fingerprint;
+        let tolerance = &golden.tolerance;
+        let mut drifts: Vec<String> = Vec::new();
+
+        if relative_drift(self.vertices as f64, expected.vertices as f64) > tolerance.count {
+            drifts.push(format!("vertices {} expected {}", self.vertices, expected.vertices));
+        }
+        if relative_drift(self.triangles as f64, expected.triangles as f64) > tolerance.count {
+            drifts.push(format!("triangles {} expected {}", self.triangles, expected.triangles));
+        }
+        if relative_drift(self.area, expected.area) > tolerance.area {
+            drifts.push(format!("area {} expected {}", self.area, expected.area));
+        }
+        if expected.min_angle - self.min_angle > tolerance.angle {
+            drifts.push(format!("min angle {} expected {}", self.min_angle, expected.min_angle));
+        }
+        if relative_drift(self.max_quality, expected.max_quality) > tolerance.quality
+            && self.max_quality > expected.max_quality
+        {
+            drifts.push(format!(
+                "max quality {} expected {}",
+                self.max_quality, expected.max_quality
+            ));
+        }
+
+        return drifts;
+    }
+} /* end - Fingerprint */
+
+fn relative_drift(actual: f64, expected: f64) -> f64 {
+    if expected == 0.0 {
+        return actual.abs();
+    }
+    return ((actual - expected) / expected).abs();
+}
+
+/**
+ * Triangulates and refines the input file, as the cli does, fingerprinting
+ * the runs.
+ */
+fn triangulate(path: &Path) -> Result<Fingerprint, String> {
+    let input = match json_serializar::io::read(path) {
+        Ok(input) => input,
+        Err(error) => return Err(format!("{}", error)),
+    };
+
+    let mut runs: Vec<Fingerprint> = Vec::new();
+    for _ in 0..RUNS {
+        runs.push(triangulate_input(&input)?);
+    }
+    return Ok(Fingerprint::from_runs(&runs));
+}
+
+fn triangulate_input(input: &TriangulationInput) -> Result<Fingerprint, String> {
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let (mut components, refine_params) = match triangulator_interface::parse(input) {
+            Ok(parsed) => parsed,
+            Err(error) => return Err(format!("{}", error)),
+        };
+        for component in components.iter_mut() {
+            if let Err(error) = component.try_triangulate() {
+                return Err(format!("{}", error));
+            }
+            if let Err(error) = component.try_refine(refine_params.clone()) {
+                return Err(format!("{}", error));
+            }
+        }
+        let mut triangulator = components.remove(0);
+        for component in components.into_iter() {
+            if let Err(error) = triangulator.include_component(component) {
+                return Err(format!("{}", error));
+            }
+        }
+        return Ok(Fingerprint::from_triangulator(&triangulator));
+    }));
+
+    match result {
+        Ok(fingerprint) => return fingerprint,
+        Err(_) => return Err(String::from("triangulation panicked")),
+    }
+}
+
+fn corpus_dir() -> PathBuf {
+    match std::env::var("CONFORMANCE_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/conformance"),
+    }
+}
+
+#[test]
+fn golden_corpus() {
+    let corpus = corpus_dir();
+    let bless = std::env::var("CONFORMANCE_BLESS").is_ok();
+
+    let mut inputs: Vec<PathBuf> = fs::read_dir(corpus.join("inputs"))
+        .expect("Missing conformance inputs directory")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |e| e == "json"))
+        .collect();
+    inputs.sort();
+    assert!(!inputs.is_empty());
+
+    let mut report: Vec<String> = Vec::new();
+    for input in inputs.iter() {
+        let name = input.file_name().unwrap().to_string_lossy().to_string();
+        let golden_path = corpus.join("golden").join(&name);
+
+        let fingerprint = match triangulate(input) {
+            Ok(fingerprint) => fingerprint,
+            Err(message) => {
+                report.push(format!("{}: {}", name, message));
+                continue;
+            }
+        };
+
+        if bless {
+            let golden = Golden {
+                fingerprint,
+                tolerance: default_tolerance(),
+            };
+            let json_string = serde_json::to_string_pretty(&golden).unwrap();
+            json_serializar::io::write(&golden_path, json_string).unwrap();
+            continue;
+        }
+
+        let golden: Golden = match fs::read_to_string(&golden_path) {
+            Ok(json_string) => serde_json::from_str(&json_string).unwrap(),
+            Err(_) => {
+                report.push(format!("{}: missing golden result", name));
+                continue;
+            }
+        };
+
+        for drift in fingerprint.drifts(&golden) {
+            report.push(format!("{}: {}", name, drift));
+        }
+    }
+
+    if !report.is_empty() {
+        panic!("Conformance drifts:\n{}", report.join("\n"));
+    }
+}
@@ -0,0 +1,594 @@
+use std::collections::HashMap;
+
+use crate::json_serializar::models::{action::Action, input, point::Point};
+
+use nlsn_delaunay::{elements::vertex::Vertex, planar::triangulation_error::TriangulationError};
+
+/* Intents an entity may be mapped onto */
+const INTENTS: [&str; 3] = ["include", "remove", "constraint"];
+
+/* Layer pattern of the layers with no mapping of their own */
+const ANY_LAYER: &str = "*";
+
+/* Profile of dxf inputs, since drawings carry no refine params */
+const DEFAULT_PROFILE: &str = "standard";
+
+/* Ends of lines, arcs and open polylines this close are chained */
+const CHAIN_TOLERANCE: f64 = 1.0E-9;
+
+/* Segments of a whole turn, as for arc actions */
+const TURN_RESOLUTION: f64 = 100.0;
+
+/**
+ * Intent of the entities of each layer, given as LAYER=INTENT mappings,
+ * where the * layer stands for the layers with no mapping of their own.
+ * Layers with no mapping at all are mapped by their name, if it is an
+ * intent, such as a layer named "remove", and ignored otherwise.
+ */
+pub struct LayerIntents {
+    intents: HashMap<String, String>,
+}
+
+impl LayerIntents {
+    pub fn parse(mappings: &[String]) -> Result<Self, TriangulationError> {
+        let mut intents: HashMap<String, String> = HashMap::new();
+        for mapping in mappings.iter() {
+            let (layer, intent) = match mapping.rfind('=') {
+                Some(index) => (&mapping[..index], &mapping[index + 1..]),
+                None => {
+                    return Err(TriangulationError::DegenerateInput(format!(
+                        "layer mapping {} is not LAYER=INTENT",
+                        mapping
+                    )))
+                }
+            };
+            if !INTENTS.contains(&intent) {
+                return Err(TriangulationError::DegenerateInput(format!(
+                    "unknown intent {} of layer {}",
+                    intent, layer
+                )));
+            }
+            intents.insert(String::from(layer), String::from(intent));
+        }
+        return Ok(Self { intents });
+    }
+
+    pub fn intent(&self, layer: &str) -> Option<&str> {
+        if let Some(intent) = self.intents.get(layer).or(self.intents.get(ANY_LAYER)) {
+            return Some(intent.as_str());
+        }
+        let name = layer.to_lowercase();
+        return INTENTS.iter().find(|intent| **intent == name).cloned();
+    }
+} /* end - LayerIntents */
+
+/**
+ * Planar shape of a drawing entity: closed loops, such as closed
+ * polylines, open paths, such as lines and arcs, and circles.
+ */
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Loop(Vec<(f64, f64)>),
+    Path(Vec<(f64, f64)>),
+    Circle((f64, f64), f64),
+}
+
+/**
+ * Reads the drawing file as a triangulation input, as parse does, named
+ * after the file.
+ */
+pub fn read(
+    path: &std::path::Path,
+    intents: &LayerIntents,
+) -> Result<input::TriangulationInput, TriangulationError> {
+    let dxf = std::fs::read_to_string(path)?;
+    let name = match path.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => String::from("dxf"),
+    };
+    return parse(&dxf, &name, intents);
+}
+
+/**
+ * Triangulation input of the LWPOLYLINE, LINE, CIRCLE and ARC entities of
+ * an ASCII drawing, mapped onto actions by the intent of their layers.
+ *  - include and remove layers: circles are kept as circle actions, while
+ * closed polylines, and lines, arcs and open polylines chained end to end
+ * into loops, become counterclockwise polyline actions. Open chains are
+ * rejected, since they bound no region.
+ *  - constraint layers: every shape becomes a segments action.
+ * Polyline bulges are discretized as arcs are. Refine params take the
+ * standard profile, which may be overridden from the command line.
+ */
+pub fn parse(
+    dxf: &str,
+    name: &str,
+    intents: &LayerIntents,
+) -> Result<input::TriangulationInput, TriangulationError> {
+    let mut layers: Vec<(String, Vec<Shape>)> = Vec::new();
+    for (layer, shape) in entities(&group_pairs(dxf)?)?.into_iter() {
+        match layers.iter_mut().find(|(name, _)| *name == layer) {
+            Some((_, shapes)) => shapes.push(shape),
+            None => layers.push((layer, vec![shape])),
+        }
+    }
+
+    let mut actions: Vec<Action> = Vec::new();
+    for (layer, shapes) in layers.into_iter() {
+        let intent = match intents.intent(&layer) {
+            Some(intent) => intent,
+            None => continue,
+        };
+
+        let mut paths: Vec<Vec<(f64, f64)>> = Vec::new();
+        let mut loops: Vec<Vec<(f64, f64)>> = Vec::new();
+        for shape in shapes.into_iter() {
+            match shape {
+                Shape::Loop(points) => loops.push(points),
+                Shape::Path(points) => paths.push(points),
+                Shape::Circle(center, radius) if intent != "constraint" => {
+                    actions.push(action(intent, "circle", vec![center], vec![radius]));
+                }
+                Shape::Circle(center, radius) => {
+                    let mut points = arc_points(center, radius, 0.0, 360.0);
+                    points.pop();
+                    loops.push(points);
+                }
+            }
+        }
+        let (closed_chains, open_chains) = chained(paths);
+        loops.extend(closed_chains.into_iter());
+
+        if intent == "constraint" {
+            for points in loops.iter_mut() {
+                points.push(points[0]);
+            }
+            for points in loops.into_iter().chain(open_chains.into_iter()) {
+                let pairs: Vec<(f64, f64)> = points
+                    .windows(2)
+                    .map(|pair| vec![pair[0], pair[1]])
+                    .flatten()
+                    .collect();
+                actions.push(action(intent, "segments", pairs, Vec::new()));
+            }
+            continue;
+        }
+
+        if !open_chains.is_empty() {
+            return Err(TriangulationError::DegenerateInput(format!(
+                "open outline on {} layer {}",
+                intent, layer
+            )));
+        }
+        for mut points in loops.into_iter() {
+            if signed_area(&points) < 0.0 {
+                points.reverse();
+            }
+            actions.push(action(intent, "polyline", points, Vec::new()));
+        }
+    }
+
+    if actions.is_empty() {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "no entity of the drawing is mapped onto an intent",
+        )));
+    }
+
+    return Ok(input::TriangulationInput::from_actions(
+        name,
+        actions,
+        DEFAULT_PROFILE,
+    ));
+} /* end - parse */
+
+/**
+ * Group code and value pairs, which drawings list in alternate lines.
+ */
+fn group_pairs(dxf: &str) -> Result<Vec<(i32, &str)>, TriangulationError> {
+    let lines: Vec<&str> = dxf.lines().map(|line| line.trim()).collect();
+    let mut pairs: Vec<(i32, &str)> = Vec::new();
+    for (index, pair) in lines.chunks(2).enumerate() {
+        if pair.len() < 2 {
+            if pair[0].is_empty() {
+                break;
+            }
+            return Err(invalid_dxf(index * 2 + 1, "group code with no value"));
+        }
+        match pair[0].parse::<i32>() {
+            Ok(code) => pairs.push((code, pair[1])),
+            Err(_) => return Err(invalid_dxf(index * 2 + 1, "group code is not an integer")),
+        }
+    }
+    return Ok(pairs);
+}
+
+/**
+ * Layers and shapes of the supported entities of the ENTITIES section.
+ * Other entities are ignored.
+ */
+fn entities(pairs: &[(i32, &str)]) -> Result<Vec<(String, Shape)>, TriangulationError> {
+    let section_start = pairs
+        .windows(2)
+        .position(|window| window[0] == (0, "SECTION") && window[1] == (2, "ENTITIES"));
+    let section: &[(i32, &str)] = match section_start {
+        Some(start) => {
+            let section = &pairs[start + 2..];
+            match section.iter().position(|pair| *pair == (0, "ENDSEC")) {
+                Some(end) => &section[..end],
+                None => section,
+            }
+        }
+        None => &[],
+    };
+
+    let mut shapes: Vec<(String, Shape)> = Vec::new();
+    let mut start = 0;
+    while start < section.len() {
+        let length = section[start + 1..]
+            .iter()
+            .position(|(code, _)| *code == 0)
+            .map_or(section.len() - start, |length| length + 1);
+        let entity = &section[start..start + length];
+        if let Some(shape) = shape(entity)? {
+            let layer = entity
+                .iter()
+                .find(|(code, _)| *code == 8)
+                .map_or("0", |pair| pair.1);
+            shapes.push((String::from(layer), shape));
+        }
+        start += length;
+    }
+    return Ok(shapes);
+}
+
+/**
+ * Shape of an entity, as its group pairs starting at the entity type.
+ */
+fn shape(entity: &[(i32, &str)]) -> Result<Option<Shape>, TriangulationError> {
+    let value = |code: i32| -> Result<f64, TriangulationError> {
+        let (_, value) = match entity.iter().find(|pair| pair.0 == code) {
+            Some(pair) => pair,
+            None => return Err(missing_code(entity[0].1, code)),
+        };
+        return number(value);
+    };
+
+    match entity[0].1 {
+        "LINE" => {
+            return Ok(Some(Shape::Path(vec![
+                (value(10)?, value(20)?),
+                (value(11)?, value(21)?),
+            ])))
+        }
+        "CIRCLE" => return Ok(Some(Shape::Circle((value(10)?, value(20)?), value(40)?))),
+        "ARC" => {
+            let points = arc_points((value(10)?, value(20)?), value(40)?, value(50)?, value(51)?);
+            return Ok(Some(Shape::Path(points)));
+        }
+        "LWPOLYLINE" => {
+            /* vertices, with the bulge of the segment they start */
+            let mut vertices: Vec<((f64, f64), f64)> = Vec::new();
+            let mut closed = false;
+            for (code, value) in entity.iter() {
+                match code {
+                    10 => vertices.push(((number(value)?, 0.0), 0.0)),
+                    20 | 42 => {
+                        let vertex = match vertices.last_mut() {
+                            Some(vertex) => vertex,
+                            None => return Err(missing_code("LWPOLYLINE", 10)),
+                        };
+                        match code {
+                            20 => (vertex.0).1 = number(value)?,
+                            _ => vertex.1 = number(value)?,
+                        }
+                    }
+                    70 => closed = number(value)? as i64 & 1 == 1,
+                    _ => {}
+                }
+            }
+            if vertices.len() < 2 {
+                return Err(missing_code("LWPOLYLINE", 10));
+            }
+
+            let mut points: Vec<(f64, f64)> = Vec::new();
+            let segments_count = if closed {
+                vertices.len()
+            } else {
+                vertices.len() - 1
+            };
+            for index in 0..segments_count {
+                let (p1, bulge) = vertices[index];
+                let (p2, _) = vertices[(index + 1) % vertices.len()];
+                points.push(p1);
+                if bulge != 0.0 {
+                    let arc = bulge_points(p1, p2, bulge);
+                    points.extend(arc[1..arc.len() - 1].iter());
+                }
+            }
+            if closed {
+                return Ok(Some(Shape::Loop(points)));
+            }
+            points.push(vertices[vertices.len() - 1].0);
+            return Ok(Some(Shape::Path(points)));
+        }
+        _ => return Ok(None),
+    }
+} /* end - shape */
+
+/**
+ * Points of the arc running counterclockwise from the start to the end
+ * angle, in degrees, both ends included.
+ */
+fn arc_points(center: (f64, f64), radius: f64, start: f64, end: f64) -> Vec<(f64, f64)> {
+    let turn = std::f64::consts::PI * 2.0;
+    let start = start.to_radians();
+    let mut sweep = end.to_radians() - start;
+    while sweep <= 0.0 {
+        sweep += turn;
+    }
+    return swept_points(center, radius, start, sweep.min(turn));
+}
+
+/**
+ * Points of the arc from p1 to p2 of the bulge, the tangent of a quarter
+ * of the arc angle, positive if counterclockwise, both ends included.
+ */
+fn bulge_points(p1: (f64, f64), p2: (f64, f64), bulge: f64) -> Vec<(f64, f64)> {
+    let sweep = 4.0 * bulge.atan();
+    let chord = ((p2.0 - p1.0).powi(2) + (p2.1 - p1.1).powi(2)).sqrt();
+    let radius = chord / (2.0 * (sweep / 2.0).sin()).abs();
+
+    /* center lies on the chord bisector, left of the chord if counterclockwise */
+    let offset = chord * (1.0 - bulge * bulge) / (4.0 * bulge);
+    let normal = ((p1.1 - p2.1) / chord, (p2.0 - p1.0) / chord);
+    let center = (
+        (p1.0 + p2.0) / 2.0 + offset * normal.0,
+        (p1.1 + p2.1) / 2.0 + offset * normal.1,
+    );
+
+    let start = (p1.1 - center.1).atan2(p1.0 - center.0);
+    let mut points = swept_points(center, radius, start, sweep);
+    let last = points.len() - 1;
+    points[0] = p1;
+    points[last] = p2;
+    return points;
+}
+
+fn swept_points(center: (f64, f64), radius: f64, start: f64, sweep: f64) -> Vec<(f64, f64)> {
+    let turn = std::f64::consts::PI * 2.0;
+    let resolution = (TURN_RESOLUTION * sweep.abs() / turn).ceil().max(2.0) as usize;
+    return (0..=resolution)
+        .map(|index| start + sweep * index as f64 / resolution as f64)
+        .map(|angle| {
+            (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            )
+        })
+        .collect();
+}
+
+/**
+ * Paths joined end to end, reversing them as needed. Returns the chains
+ * that close, with no repeated point, and the open ones.
+ */
+fn chained(mut paths: Vec<Vec<(f64, f64)>>) -> (Vec<Vec<(f64, f64)>>, Vec<Vec<(f64, f64)>>) {
+    let meet = |p1: (f64, f64), p2: (f64, f64)| {
+        return (p1.0 - p2.0).abs() <= CHAIN_TOLERANCE && (p1.1 - p2.1).abs() <= CHAIN_TOLERANCE;
+    };
+
+    let mut closed_chains: Vec<Vec<(f64, f64)>> = Vec::new();
+    let mut open_chains: Vec<Vec<(f64, f64)>> = Vec::new();
+    while let Some(mut chain) = paths.pop() {
+        loop {
+            if chain.len() > 2 && meet(chain[0], chain[chain.len() - 1]) {
+                break;
+            }
+            let end = chain[chain.len() - 1];
+            let next = paths
+                .iter()
+                .position(|path| meet(path[0], end) || meet(path[path.len() - 1], end));
+            match next {
+                Some(index) => {
+                    let mut path = paths.swap_remove(index);
+                    if !meet(path[0], end) {
+                        path.reverse();
+                    }
+                    chain.extend(path.into_iter().skip(1));
+                }
+                None => break,
+            }
+        }
+
+        if chain.len() > 2 && meet(chain[0], chain[chain.len() - 1]) {
+            chain.pop();
+            closed_chains.push(chain);
+            continue;
+        }
+
+        /* the chain may still grow backwards, from its first point */
+        chain.reverse();
+        let start = chain[chain.len() - 1];
+        match paths
+            .iter()
+            .position(|path| meet(path[0], start) || meet(path[path.len() - 1], start))
+        {
+            Some(_) => paths.push(chain),
+            None => open_chains.push(chain),
+        }
+    }
+    return (closed_chains, open_chains);
+} /* end - chained */
+
+fn signed_area(points: &[(f64, f64)]) -> f64 {
+    let mut area = 0.0;
+    for index in 0..points.len() {
+        let (x1, y1) = points[index];
+        let (x2, y2) = points[(index + 1) % points.len()];
+        area += x1 * y2 - x2 * y1;
+    }
+    return area / 2.0;
+}
+
+fn action(intent: &str, geometry: &str, points: Vec<(f64, f64)>, scalars: Vec<f64>) -> Action {
+    let points = points
+        .into_iter()
+        .map(|(x, y)| Point::from_vertex(&Vertex::new(x, y)))
+        .collect();
+    return Action::from_points(intent, geometry, points, scalars);
+}
+
+fn number(value: &str) -> Result<f64, TriangulationError> {
+    match value.parse::<f64>() {
+        Ok(number) => return Ok(number),
+        Err(_) => {
+            return Err(TriangulationError::DegenerateInput(format!(
+                "invalid dxf number {}",
+                value
+            )))
+        }
+    }
+}
+
+fn invalid_dxf(line: usize, reason: &str) -> TriangulationError {
+    return TriangulationError::DegenerateInput(format!(
+        "invalid dxf at line {}: {}",
+        line, reason
+    ));
+}
+
+fn missing_code(entity: &str, code: i32) -> TriangulationError {
+    return TriangulationError::DegenerateInput(format!(
+        "dxf {} with no group code {}",
+        entity, code
+    ));
+}
+
+#[cfg(test)]
+mod dxf {
+    use super::*;
+
+    fn drawing(entities: &[&str]) -> String {
+        let mut lines: Vec<&str> = vec!["0", "SECTION", "2", "HEADER", "0", "ENDSEC"];
+        lines.extend(["0", "SECTION", "2", "ENTITIES"].iter());
+        for entity in entities.iter() {
+            lines.extend(entity.split_whitespace());
+        }
+        lines.extend(["0", "ENDSEC", "0", "EOF"].iter());
+        return lines.join("\n");
+    }
+
+    fn no_mappings() -> LayerIntents {
+        return LayerIntents::parse(&[]).unwrap();
+    }
+
+    #[test]
+    fn layer_intents() {
+        let intents =
+            LayerIntents::parse(&[String::from("WALLS=include"), String::from("*=constraint")])
+                .unwrap();
+        assert_eq!(intents.intent("WALLS"), Some("include"));
+        assert_eq!(intents.intent("other"), Some("constraint"));
+
+        assert_eq!(no_mappings().intent("Remove"), Some("remove"));
+        assert_eq!(no_mappings().intent("0"), None);
+
+        assert!(LayerIntents::parse(&[String::from("WALLS")]).is_err());
+        assert!(LayerIntents::parse(&[String::from("WALLS=fill")]).is_err());
+    }
+
+    #[test]
+    fn closed_polyline_and_circle() {
+        /* clockwise square, and a hole */
+        let dxf = drawing(&[
+            "0 LWPOLYLINE 8 include 90 4 70 1 10 0 20 0 10 0 20 4 10 4 20 4 10 4 20 0",
+            "0 CIRCLE 8 remove 10 2 20 2 40 1",
+            "0 TEXT 8 include 10 0 20 0 1 label",
+        ]);
+        let input = parse(&dxf, "square.dxf", &no_mappings()).unwrap();
+        assert_eq!(input.name, "square.dxf");
+        assert_eq!(input.params.profile, Some(String::from("standard")));
+        assert_eq!(input.actions.len(), 2);
+
+        let outline = &input.actions[0];
+        assert_eq!(outline.intent, "include");
+        assert_eq!(outline.geometry, "polyline");
+        assert_eq!(outline.points.len(), 4);
+        let points: Vec<(f64, f64)> = outline.points.iter().map(|p| (p.x, p.y)).collect();
+        assert!(signed_area(&points) > 0.0);
+
+        let hole = &input.actions[1];
+        assert_eq!(
+            (hole.intent.as_str(), hole.geometry.as_str()),
+            ("remove", "circle")
+        );
+        assert_eq!(hole.scalars, vec![1.0]);
+    }
+
+    #[test]
+    fn chained_lines_and_arc() {
+        /* half disk of lines and an arc, listed out of order */
+        let dxf = drawing(&[
+            "0 ARC 8 OUTLINE 10 0 20 0 40 1 50 0 51 180",
+            "0 LINE 8 OUTLINE 10 0 20 0 11 1 21 0",
+            "0 LINE 8 OUTLINE 10 0 20 0 11 -1 21 0",
+            "0 LINE 8 FAULTS 10 -0.5 20 0.1 11 0.5 21 0.1",
+        ]);
+        let intents = LayerIntents::parse(&[
+            String::from("OUTLINE=include"),
+            String::from("FAULTS=constraint"),
+        ])
+        .unwrap();
+        let input = parse(&dxf, "half.dxf", &intents).unwrap();
+        assert_eq!(input.actions.len(), 2);
+
+        let outline = &input.actions[0];
+        assert_eq!(outline.geometry, "polyline");
+        assert_eq!(outline.points.len(), 50 + 1 + 1);
+        let points: Vec<(f64, f64)> = outline.points.iter().map(|p| (p.x, p.y)).collect();
+        assert!((signed_area(&points) - std::f64::consts::PI / 2.0).abs() < 1.0E-2);
+
+        let fault = &input.actions[1];
+        assert_eq!(
+            (fault.intent.as_str(), fault.geometry.as_str()),
+            ("constraint", "segments")
+        );
+        assert_eq!(fault.points.len(), 2);
+    }
+
+    #[test]
+    fn bulge() {
+        /* slot: two half turns joined by straight segments */
+        let dxf = drawing(&[
+            "0 LWPOLYLINE 8 include 70 1 10 0 20 0 10 2 20 0 42 1 10 2 20 2 10 0 20 2 42 1",
+        ]);
+        let input = parse(&dxf, "slot.dxf", &no_mappings()).unwrap();
+        let points: Vec<(f64, f64)> = input.actions[0].points.iter().map(|p| (p.x, p.y)).collect();
+        assert!(points
+            .iter()
+            .all(|(x, _)| *x > -1.0 - 1.0E-12 && *x < 3.0 + 1.0E-12));
+        assert!(points
+            .iter()
+            .any(|(x, y)| (*x - 3.0).abs() < 1.0E-12 && (*y - 1.0).abs() < 1.0E-12));
+
+        let expected_area = 4.0 + std::f64::consts::PI;
+        assert!((signed_area(&points) - expected_area).abs() < 1.0E-2);
+    }
+
+    #[test]
+    fn invalid_drawings() {
+        /* open outline bounds no region */
+        let dxf = drawing(&["0 LINE 8 include 10 0 20 0 11 1 21 0"]);
+        assert!(parse(&dxf, "line.dxf", &no_mappings()).is_err());
+
+        /* no mapped layer */
+        let dxf = drawing(&["0 CIRCLE 8 0 10 0 20 0 40 1"]);
+        assert!(parse(&dxf, "circle.dxf", &no_mappings()).is_err());
+
+        /* missing radius */
+        let dxf = drawing(&["0 CIRCLE 8 include 10 0 20 0"]);
+        assert!(parse(&dxf, "circle.dxf", &no_mappings()).is_err());
+
+        assert!(parse("0\nSECTION\n2", "broken.dxf", &no_mappings()).is_err());
+    }
+} /* end - dxf tests */
@@ -65,11 +65,14 @@ impl BoundingBox {
         return None;
     }
 
-    pub fn union(b1: &BoundingBox, b2: BoundingBox) -> Self {
-        let lower_x: f64 = min(b1.origin.x, b2.origin.x);
-        let upper_x: f64 = max(b1.destin.x, b2.destin.x);
-        let lower_y: f64 = min(b1.origin.y, b2.origin.y);
-        let upper_y: f64 = max(b1.destin.y, b2.destin.y);
+    /**
+     * Smallest box containing both boxes.
+     */
+    pub fn union(&self, other: &Self) -> Self {
+        let lower_x: f64 = min(self.origin.x, other.origin.x);
+        let upper_x: f64 = max(self.destin.x, other.destin.x);
+        let lower_y: f64 = min(self.origin.y, other.origin.y);
+        let upper_y: f64 = max(self.destin.y, other.destin.y);
 
         return Self {
             origin: Rc::new(Vertex::new(lower_x, lower_y)),
@@ -77,6 +80,41 @@ impl BoundingBox {
         };
     }
 
+    /**
+     * Checks whether the boxes overlap or touch, as intersection does, but
+     * without building the intersection box.
+     */
+    pub fn intersects(&self, other: &Self) -> bool {
+        return self.origin.x <= other.destin.x
+            && other.origin.x <= self.destin.x
+            && self.origin.y <= other.destin.y
+            && other.origin.y <= self.destin.y;
+    }
+
+    /**
+     * Box grown by the margin on every side. Negative margins shrink it.
+     */
+    pub fn expand(&self, margin: f64) -> Self {
+        return Self {
+            origin: Rc::new(Vertex::new(self.origin.x - margin, self.origin.y - margin)),
+            destin: Rc::new(Vertex::new(self.destin.x + margin, self.destin.y + margin)),
+        };
+    }
+
+    pub fn center(&self) -> Vertex {
+        return Vertex::new(
+            (self.origin.x + self.destin.x) / 2.0,
+            (self.origin.y + self.destin.y) / 2.0,
+        );
+    }
+
+    /**
+     * Width and height of the box.
+     */
+    pub fn extent(&self) -> (f64, f64) {
+        return (self.destin.x - self.origin.x, self.destin.y - self.origin.y);
+    }
+
     pub fn union_list(box_list: Vec<BoundingBox>) -> Option<Self> {
         if box_list.is_empty() {
             return None;
@@ -213,6 +251,52 @@ mod union {
     }
 }
 
+#[cfg(test)]
+mod queries {
+    use super::*;
+
+    fn bbox(x1: f64, y1: f64, x2: f64, y2: f64) -> BoundingBox {
+        return BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(x1, y1)),
+            Rc::new(Vertex::new(x2, y2)),
+        ])
+        .unwrap();
+    }
+
+    #[test]
+    fn intersects() {
+        let b1 = bbox(0.0, 0.0, 2.0, 2.0);
+        assert!(b1.intersects(&bbox(1.0, 1.0, 3.0, 3.0)));
+        assert!(b1.intersects(&bbox(2.0, 0.0, 3.0, 1.0)));
+        assert!(b1.intersects(&bbox(0.5, 0.5, 1.0, 1.0)));
+        assert!(!b1.intersects(&bbox(2.5, 0.0, 3.0, 1.0)));
+        assert!(!b1.intersects(&bbox(0.0, -2.0, 1.0, -1.0)));
+
+        for other in vec![bbox(1.0, 1.0, 3.0, 3.0), bbox(2.5, 0.0, 3.0, 1.0)] {
+            assert_eq!(
+                b1.intersects(&other),
+                BoundingBox::intersection(&b1, &other).is_some()
+            );
+        }
+    }
+
+    #[test]
+    fn union_and_expand() {
+        let b1 = bbox(0.0, 0.0, 2.0, 1.0);
+        let b2 = bbox(1.0, -1.0, 3.0, 0.5);
+        assert!(b1.union(&b2) == bbox(0.0, -1.0, 3.0, 1.0));
+        assert!(b1.expand(0.5) == bbox(-0.5, -0.5, 2.5, 1.5));
+        assert!(b1.expand(-0.25) == bbox(0.25, 0.25, 1.75, 0.75));
+    }
+
+    #[test]
+    fn accessors() {
+        let b1 = bbox(1.0, 2.0, 4.0, 3.0);
+        assert_eq!(b1.center(), Vertex::new(2.5, 2.5));
+        assert_eq!(b1.extent(), (3.0, 1.0));
+    }
+} /* end - queries tests */
+
 #[cfg(test)]
 mod edge_cases {
     use super::*;
@@ -14,6 +14,7 @@ use std::fmt;
 use std::fmt::Debug;
 
 #[derive(Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge {
     pub v1: Rc<Vertex>,
     pub v2: Rc<Vertex>,
@@ -3,6 +3,7 @@ use crate::elements::{bounding_box::*, edge::*, triangle::*, vertex::*};
 use crate::properties::angle::*;
 use crate::properties::area::area_segments;
 use crate::properties::continence::*;
+use crate::properties::distance::*;
 use crate::properties::dot::*;
 use crate::properties::intersection::*;
 use crate::properties::midpoint::*;
@@ -13,7 +14,8 @@ use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 use std::rc::Rc;
 
-#[derive(Hash)]
+#[derive(Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Polyline {
     pub vertices: Vec<Rc<Vertex>>,
     pub opened: bool,
@@ -31,6 +33,44 @@ impl PartialEq for Polyline {
 
 impl Eq for Polyline {}
 
+/**
+ * Joints of offset polylines at corners where offset edges move apart.
+ *  - Miter: offset edges are extended until they meet, and the corner is
+ * beveled when the miter is longer than MITER_LIMIT times the distance.
+ *  - Round: corners are arcs around the original vertex, approximated by
+ * the given number of segments per full turn.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OffsetJoin {
+    Miter,
+    Round(usize),
+}
+
+/**
+ * Boundary loops of a set of triangles: counterclockwise outer loops, one
+ * for each component, and clockwise hole loops.
+ */
+pub struct TrianglesHull {
+    pub outer: Vec<Polyline>,
+    pub holes: Vec<Polyline>,
+}
+
+impl TrianglesHull {
+    /**
+     * The outer loop of triangles occupying a single continuous domain,
+     * without holes. Returns None otherwise.
+     */
+    pub fn single(mut self) -> Option<Polyline> {
+        if self.outer.len() == 1 && self.holes.is_empty() {
+            return self.outer.pop();
+        }
+        return None;
+    }
+}
+
+/* Miter length limit, relative to the offset distance */
+pub const MITER_LIMIT: f64 = 4.0;
+
 impl Polyline {
     pub fn new_closed(vertex_list: Vec<Rc<Vertex>>) -> Option<Self> {
         if vertex_list.is_empty() || vertex_list.len() < 3 {
@@ -54,6 +94,34 @@ impl Polyline {
         });
     }
 
+    /**
+     * Creates a closed polyline, welding vertices closer than tolerance.
+     * Returns None if less than 3 vertices are left.
+     */
+    pub fn new_closed_welded(vertex_list: Vec<Rc<Vertex>>, tolerance: f64) -> Option<Self> {
+        return Self::new_closed(weld_vertices(&vertex_list, tolerance, false));
+    }
+
+    /**
+     * Creates an opened polyline, welding vertices closer than tolerance.
+     * Returns None if less than 2 vertices are left.
+     */
+    pub fn new_opened_welded(vertex_list: Vec<Rc<Vertex>>, tolerance: f64) -> Option<Self> {
+        return Self::new_opened(weld_vertices(&vertex_list, tolerance, true));
+    }
+
+    /**
+     * Welds consecutive vertices closer than tolerance, as well as the last
+     * vertex of a closed polyline against the first one, dropping zero
+     * length edges. Returns None if too few vertices are left.
+     */
+    pub fn weld(&self, tolerance: f64) -> Option<Self> {
+        if self.opened {
+            return Self::new_opened_welded(self.vertices.clone(), tolerance);
+        }
+        return Self::new_closed_welded(self.vertices.clone(), tolerance);
+    }
+
     /**
      * Returns first vertex if polyline is opened. Returns None otherwise.
      */
@@ -83,37 +151,279 @@ impl Polyline {
         BoundingBox::from_vertices(self.vertices.iter().cloned().collect())
     }
 
-    pub fn arrange(edges: &HashSet<Rc<Edge>>) -> Option<Self> {
-        let mut arranged_vertices: Vec<Rc<Vertex>> = Vec::new();
+    /**
+     * Winding of the closed polyline, by the sign of its area. Returns None
+     * for opened polylines and for polylines enclosing no area.
+     */
+    pub fn orientation(&self) -> Option<Orientation> {
+        let area = self.area()?;
+        if area > 0.0 {
+            return Some(Orientation::Counterclockwise);
+        }
+        if area < 0.0 {
+            return Some(Orientation::Clockwise);
+        }
+        return None;
+    }
+
+    /**
+     * Same polyline, with its vertices in the opposite order.
+     */
+    pub fn reversed(&self) -> Self {
+        let mut vertices = self.vertices.clone();
+        vertices.reverse();
+        return Self {
+            vertices,
+            opened: self.opened,
+        };
+    }
+
+    /**
+     * Same closed polyline, counterclockwise oriented. Opened polylines, and
+     * polylines enclosing no area, are kept as they are.
+     */
+    pub fn ensure_ccw(&self) -> Self {
+        match self.orientation() {
+            Some(Orientation::Clockwise) => return self.reversed(),
+            _ => {
+                return Self {
+                    vertices: self.vertices.clone(),
+                    opened: self.opened,
+                }
+            }
+        }
+    }
+
+    /**
+     * Same closed polyline, clockwise oriented. Opened polylines, and
+     * polylines enclosing no area, are kept as they are.
+     */
+    pub fn ensure_cw(&self) -> Self {
+        match self.orientation() {
+            Some(Orientation::Counterclockwise) => return self.reversed(),
+            _ => {
+                return Self {
+                    vertices: self.vertices.clone(),
+                    opened: self.opened,
+                }
+            }
+        }
+    }
+
+    /**
+     * Signed area enclosed by the closed polyline, positive for
+     * counterclockwise and negative for clockwise polylines. Returns None
+     * for opened polylines.
+     */
+    pub fn area(&self) -> Option<f64> {
+        if self.opened {
+            return None;
+        }
+        return Some(-area_segments(&vertex_pairs(&self.vertices, false)));
+    }
 
-        let mut hash_edges: HashMap<Rc<Vertex>, Rc<Vertex>> = HashMap::new();
+    /**
+     * Centroid of the area enclosed by the closed polyline. Returns None for
+     * opened polylines and for polylines enclosing no area.
+     */
+    pub fn centroid(&self) -> Option<Vertex> {
+        let (_, (x, y), _) = self.moments()?;
+        return Some(Vertex::new(x, y));
+    }
+
+    /**
+     * Second moments of the enclosed area about axes through the centroid,
+     * as (Ixx, Iyy, Ixy): the integrals of y², x² and xy over the area.
+     * Signed as the area, negative for clockwise polylines. Returns None for
+     * opened polylines and for polylines enclosing no area.
+     */
+    pub fn second_moments(&self) -> Option<(f64, f64, f64)> {
+        let (_, _, second_moments) = self.moments()?;
+        return Some(second_moments);
+    }
+
+    /**
+     * Signed area, centroid and centroidal second moments, by Green's
+     * theorem over the edges. Coordinates are taken relative to the first
+     * vertex, so that far away polylines do not lose precision.
+     */
+    fn moments(&self) -> Option<(f64, (f64, f64), (f64, f64, f64))> {
+        let area = self.area()?;
+        if area == 0.0 {
+            return None;
+        }
+
+        let origin = self.vertices.first()?;
+        let (mut sx, mut sy) = (0.0, 0.0);
+        let (mut ixx, mut iyy, mut ixy) = (0.0, 0.0, 0.0);
+        for (v1, v2) in vertex_pairs(&self.vertices, false) {
+            let (x1, y1) = (v1.x - origin.x, v1.y - origin.y);
+            let (x2, y2) = (v2.x - origin.x, v2.y - origin.y);
+            let cross = x1 * y2 - x2 * y1;
+            sx += (x1 + x2) * cross;
+            sy += (y1 + y2) * cross;
+            ixx += (y1 * y1 + y1 * y2 + y2 * y2) * cross;
+            iyy += (x1 * x1 + x1 * x2 + x2 * x2) * cross;
+            ixy += (x1 * y2 + 2.0 * x1 * y1 + 2.0 * x2 * y2 + x2 * y1) * cross;
+        }
+        let (cx, cy) = (sx / (6.0 * area), sy / (6.0 * area));
+
+        /* parallel axis theorem, from the first vertex to the centroid */
+        return Some((
+            area,
+            (origin.x + cx, origin.y + cy),
+            (
+                ixx / 12.0 - area * cy * cy,
+                iyy / 12.0 - area * cx * cx,
+                ixy / 24.0 - area * cx * cy,
+            ),
+        ));
+    }
+
+    /**
+     * Arranges directed edges into polylines: open chains, starting at the
+     * vertices with more outgoing than incoming edges, then closed loops,
+     * starting at their lowest vertex. At branch vertices, the outgoing edge
+     * of the leftmost turn is taken, turning back being the last resort, so
+     * that counterclockwise loops touching at vertices are arranged apart.
+     * Both the polylines and their head vertices are deterministic.
+     */
+    pub fn arrange(edges: &HashSet<Rc<Edge>>) -> Vec<Self> {
+        let mut outgoing: HashMap<Rc<Vertex>, Vec<Rc<Vertex>>> = HashMap::new();
+        let mut balance: HashMap<Rc<Vertex>, i64> = HashMap::new();
         for edge in edges.iter() {
-            hash_edges.insert(Rc::clone(&edge.v1), Rc::clone(&edge.v2));
+            outgoing
+                .entry(Rc::clone(&edge.v1))
+                .or_insert_with(Vec::new)
+                .push(Rc::clone(&edge.v2));
+            *balance.entry(Rc::clone(&edge.v1)).or_insert(0) += 1;
+            *balance.entry(Rc::clone(&edge.v2)).or_insert(0) -= 1;
+        }
+        for targets in outgoing.values_mut() {
+            targets.sort();
         }
 
-        let head: Rc<Vertex> = Rc::clone(hash_edges.keys().next().unwrap());
-        let mut tail: Rc<Vertex> = Rc::clone(&head);
-        arranged_vertices.push(Rc::clone(&head));
+        /* turn from the incoming direction, in (-PI, PI), turning back is -PI */
+        let turn = |from: &Vertex, at: &Vertex, to: &Vertex| -> f64 {
+            let (ax, ay) = (at.x - from.x, at.y - from.y);
+            let (bx, by) = (to.x - at.x, to.y - at.y);
+            let angle = (ax * by - ay * bx).atan2(ax * bx + ay * by);
+            if angle >= std::f64::consts::PI {
+                return -std::f64::consts::PI;
+            }
+            return angle;
+        };
+
+        let next_vertex = |outgoing: &mut HashMap<Rc<Vertex>, Vec<Rc<Vertex>>>,
+                               previous: Option<&Rc<Vertex>>,
+                               current: &Rc<Vertex>|
+         -> Option<Rc<Vertex>> {
+            let targets = outgoing.get_mut(current)?;
+            if targets.is_empty() {
+                return None;
+            }
+            let mut chosen = 0;
+            if let Some(previous) = previous {
+                for index in 1..targets.len() {
+                    if turn(previous, current, &targets[index])
+                        > turn(previous, current, &targets[chosen])
+                    {
+                        chosen = index;
+                    }
+                }
+            }
+            return Some(targets.remove(chosen));
+        };
+
+        let mut arranged: Vec<Self> = Vec::new();
+
+        let mut sources: Vec<Rc<Vertex>> = balance
+            .iter()
+            .filter(|(_, &count)| count > 0)
+            .map(|(vertex, _)| Rc::clone(vertex))
+            .collect();
+        sources.sort();
+        for source in sources.iter() {
+            for _ in 0..balance[source] {
+                let mut chain: Vec<Rc<Vertex>> = vec![Rc::clone(source)];
+                while let Some(next) =
+                    next_vertex(&mut outgoing, chain.iter().rev().nth(1), chain.last().unwrap())
+                {
+                    chain.push(next);
+                }
+                if let Some(polyline) = Self::new_opened(chain) {
+                    arranged.push(polyline);
+                }
+            }
+        }
 
         loop {
-            if let Some(next) = hash_edges.get(&tail) {
-                if next == &head {
+            let head = match outgoing
+                .iter()
+                .filter(|(_, targets)| !targets.is_empty())
+                .map(|(vertex, _)| vertex)
+                .min()
+            {
+                Some(head) => Rc::clone(head),
+                None => break,
+            };
+
+            let mut cycle: Vec<Rc<Vertex>> = vec![Rc::clone(&head)];
+            while let Some(next) =
+                next_vertex(&mut outgoing, cycle.iter().rev().nth(1), cycle.last().unwrap())
+            {
+                if next == head {
                     break;
                 }
-                arranged_vertices.push(Rc::clone(next));
-                tail = Rc::clone(next);
+                cycle.push(next);
+            }
+
+            /* edges back and forth do not close a loop */
+            if cycle.len() < 3 {
+                cycle.push(Rc::clone(&head));
+                arranged.push(Self::new_opened(cycle).unwrap());
                 continue;
             }
-            break;
+            arranged.push(Self::new_closed(cycle).unwrap());
         }
 
-        if arranged_vertices.len() == edges.len() {
-            return Some(Polyline::new_closed(arranged_vertices).unwrap());
-        }
+        return arranged;
+    }
 
-        return None;
+    /**
+     * Drops the vertices colinear with their neighbors, preserving the
+     * openness of the polyline. Heads and tails of opened polylines are
+     * kept, so that 2 vertex polylines are returned unchanged. Returns None
+     * if less than 3 vertices of a closed polyline are left, as it happens
+     * when all of them are colinear.
+     */
+    pub fn simplify_collinear(&self) -> Option<Self> {
+        let count = self.vertices.len();
+        let kept: Vec<Rc<Vertex>> = self
+            .vertices
+            .iter()
+            .enumerate()
+            .filter(|(index, vertex)| {
+                if self.opened && (*index == 0 || *index == count - 1) {
+                    return true;
+                }
+                let previous = &self.vertices[(index + count - 1) % count];
+                let next = &self.vertices[(index + 1) % count];
+                return orientation(previous, vertex, next) != Orientation::Colinear;
+            })
+            .map(|(_, vertex)| Rc::clone(vertex))
+            .collect();
+
+        if self.opened {
+            return Self::new_opened(kept);
+        }
+        return Self::new_closed(kept);
     }
 
+    #[deprecated(
+        since = "0.1.1",
+        note = "always returns a closed polyline, use simplify_collinear instead"
+    )]
     pub fn minified_noncolinear(&self) -> Self {
         let mut minified: Vec<Rc<Vertex>> = Vec::new();
         let mut possible_vertices: Vec<Rc<Vertex>> = self.vertices.iter().cloned().collect();
@@ -208,18 +518,31 @@ impl Polyline {
      * a Vec of segments that does not belong to the intersection boundary.
      */
     pub fn intersection(p1: &Self, p2: &Self) -> (Vec<Self>, HashSet<(Rc<Vertex>, Rc<Vertex>)>) {
+        return Self::intersection_snapped(p1, p2, 0.0);
+    }
+
+    /**
+     * Determines the intersection as intersection, with the crossings of the
+     * polylines snap rounded to the grid size, see split_intersections_snapped.
+     */
+    pub fn intersection_snapped(
+        p1: &Self,
+        p2: &Self,
+        grid: f64,
+    ) -> (Vec<Self>, HashSet<(Rc<Vertex>, Rc<Vertex>)>) {
         let mut polyline_intersection_list: Vec<Self> = Vec::new();
         let mut unused_segments: HashSet<(Rc<Vertex>, Rc<Vertex>)> = HashSet::new();
 
         let p1_segments = vertex_pairs(&p1.vertices, p1.opened);
         let p2_segments = vertex_pairs(&p2.vertices, p2.opened);
         /* splits segments at the beginning makes it easy to avoid outer boundary  */
-        let mut possible_segments: Vec<(Rc<Vertex>, Rc<Vertex>)> = split_intersections(
+        let mut possible_segments: Vec<(Rc<Vertex>, Rc<Vertex>)> = split_intersections_snapped(
             &p1_segments
                 .iter()
                 .chain(p2_segments.iter())
                 .cloned()
                 .collect(),
+            grid,
         );
 
         if p1.opened || p2.opened {
@@ -231,7 +554,7 @@ impl Polyline {
         let p1_bbox = p1.bounding_box().unwrap();
         let p2_bbox = p2.bounding_box().unwrap();
 
-        if !BoundingBox::intersection(&p1_bbox, &p2_bbox).is_none() {
+        if p1_bbox.intersects(&p2_bbox) {
             /*
                 Removes pairs of colinear segments in opposed direction
             */
@@ -389,6 +712,18 @@ impl Polyline {
      * there is no intersection.
      */
     pub fn union(p1: &Self, p2: &Self) -> Option<(Self, HashSet<(Rc<Vertex>, Rc<Vertex>)>)> {
+        return Self::union_snapped(p1, p2, 0.0);
+    }
+
+    /**
+     * Determines the union as union, with the crossings of the polylines
+     * snap rounded to the grid size, see split_intersections_snapped.
+     */
+    pub fn union_snapped(
+        p1: &Self,
+        p2: &Self,
+        grid: f64,
+    ) -> Option<(Self, HashSet<(Rc<Vertex>, Rc<Vertex>)>)> {
         let mut unused_segments: HashSet<(Rc<Vertex>, Rc<Vertex>)> = HashSet::new();
 
         if p1.opened || p2.opened {
@@ -398,19 +733,20 @@ impl Polyline {
         let p1_bbox = p1.bounding_box().unwrap();
         let p2_bbox = p2.bounding_box().unwrap();
 
-        if BoundingBox::intersection(&p1_bbox, &p2_bbox).is_none() {
+        if !p1_bbox.intersects(&p2_bbox) {
             return None;
         }
 
         /* splits segments at the beginning makes it easy to avoid outer boundary  */
         let p1_segments = vertex_pairs(&p1.vertices, p1.opened);
         let p2_segments = vertex_pairs(&p2.vertices, p2.opened);
-        let mut possible_segments: Vec<(Rc<Vertex>, Rc<Vertex>)> = split_intersections(
+        let mut possible_segments: Vec<(Rc<Vertex>, Rc<Vertex>)> = split_intersections_snapped(
             &p1_segments
                 .iter()
                 .chain(p2_segments.iter())
                 .cloned()
                 .collect(),
+            grid,
         );
         let mut read_segments: Vec<(Rc<Vertex>, Rc<Vertex>)> = Vec::new();
 
@@ -516,23 +852,36 @@ impl Polyline {
      * operation and a Vec of segments that does not belong to the result.
      */
     pub fn subtraction(p1: &Self, p2: &Self) -> (Vec<Rc<Self>>, HashSet<(Rc<Vertex>, Rc<Vertex>)>) {
+        return Self::subtraction_snapped(p1, p2, 0.0);
+    }
+
+    /**
+     * Determines the subtraction as subtraction, with the crossings of the
+     * polylines snap rounded to the grid size, see split_intersections_snapped.
+     */
+    pub fn subtraction_snapped(
+        p1: &Self,
+        p2: &Self,
+        grid: f64,
+    ) -> (Vec<Rc<Self>>, HashSet<(Rc<Vertex>, Rc<Vertex>)>) {
         let mut polyline_intersection_list: Vec<Rc<Self>> = Vec::new();
         let mut unused_segments: HashSet<(Rc<Vertex>, Rc<Vertex>)> = HashSet::new();
 
         let p1_segments = vertex_pairs(&p1.vertices, p1.opened);
         let p2_segments = vertex_pairs(&p2.vertices.iter().cloned().rev().collect(), p2.opened);
         /* splits segments at the beginning makes it easy to avoid outer boundary  */
-        let mut possible_segments: Vec<(Rc<Vertex>, Rc<Vertex>)> = split_intersections(
+        let mut possible_segments: Vec<(Rc<Vertex>, Rc<Vertex>)> = split_intersections_snapped(
             &p1_segments
                 .iter()
                 .chain(p2_segments.iter())
                 .cloned()
                 .collect(),
+            grid,
         );
 
         let p1_bbox = p1.bounding_box().unwrap();
         let p2_bbox = p2.bounding_box().unwrap();
-        let no_intersection_area = BoundingBox::intersection(&p1_bbox, &p2_bbox).is_none();
+        let no_intersection_area = !p1_bbox.intersects(&p2_bbox);
 
         let p2_contained_without_intersection =
             Self::continence(&p1, &p2) == Some((Continence::Inside, BoundaryInclusion::Open));
@@ -760,7 +1109,7 @@ impl Polyline {
         let p1_bbox = p1.bounding_box().unwrap();
         let p2_bbox = p2.bounding_box().unwrap();
 
-        if !BoundingBox::intersection(&p1_bbox, &p2_bbox).is_none() {
+        if p1_bbox.intersects(&p2_bbox) {
             for (v1, v2) in vertex_pairs(&p1.vertices, p1.opened) {
                 for (v3, v4) in vertex_pairs(&p2.vertices, p2.opened) {
                     /* calculates intersection and inserts it into the returning set */
@@ -804,11 +1153,288 @@ impl Polyline {
     }
 
     /**
-     * Detemines the hull that defines the boundary of the triangles set.
-     * If the triangles are adjacent in-between 2-by-2 and occupies a single
-     * continuous domain, the hull is returned. Else returns None.
+     * Inserts the vertices that lie on the polyline edges as polyline vertices,
+     * keeping them ordered along each edge. Vertices out of the edges are ignored.
      */
-    pub fn triangles_hull(triangles: &HashSet<Rc<Triangle>>) -> Option<Self> {
+    pub fn split_edges(&self, vertices: &Vec<Rc<Vertex>>) -> Self {
+        let mut split_vertices: Vec<Rc<Vertex>> = Vec::new();
+
+        for (v1, v2) in vertex_pairs(&self.vertices, self.opened) {
+            let edge = Edge::new(&v1, &v2);
+            split_vertices.push(Rc::clone(&v1));
+
+            let mut inner_vertices: Vec<Rc<Vertex>> = vertices
+                .iter()
+                .filter(|&v| v != &v1 && v != &v2 && edge.contains(v))
+                .cloned()
+                .collect::<HashSet<Rc<Vertex>>>()
+                .into_iter()
+                .collect();
+
+            inner_vertices.sort_by(|a, b| {
+                let distance_a = distance(&v1, a);
+                let distance_b = distance(&v1, b);
+                return distance_a.partial_cmp(&distance_b).unwrap();
+            });
+
+            split_vertices.append(&mut inner_vertices);
+        }
+
+        if self.opened {
+            split_vertices.push(self.tail().unwrap());
+            return Self::new_opened(split_vertices).unwrap();
+        }
+
+        return Self::new_closed(split_vertices).unwrap();
+    }
+
+    /**
+     * Splits every edge longer than max_length in equally sized pieces,
+     * no longer than max_length.
+     */
+    pub fn subdivide(&self, max_length: f64) -> Self {
+        let mut split_vertices: Vec<Rc<Vertex>> = Vec::new();
+
+        for (v1, v2) in vertex_pairs(&self.vertices, self.opened) {
+            split_vertices.push(Rc::clone(&v1));
+
+            let pieces = (distance(&v1, &v2) / max_length).ceil() as usize;
+            for index in 1..pieces {
+                let ratio = index as f64 / pieces as f64;
+                split_vertices.push(Rc::new(Vertex::new(
+                    v1.x + (v2.x - v1.x) * ratio,
+                    v1.y + (v2.y - v1.y) * ratio,
+                )));
+            }
+        }
+
+        if self.opened {
+            split_vertices.push(self.tail().unwrap());
+            return Self::new_opened(split_vertices).unwrap();
+        }
+
+        return Self::new_closed(split_vertices).unwrap();
+    }
+
+    /**
+     * Simplifies the polyline by Douglas-Peucker: edges replace the runs of
+     * vertices that lie within tolerance from them. Closed polylines are
+     * anchored at their first vertex and at the farthest one from it, and
+     * keep at least 3 vertices. Topology is preserved: while simplified
+     * edges intersect each other, their farthest dropped vertices are
+     * restored, so that simple polylines stay simple.
+     */
+    pub fn simplify(&self, tolerance: f64) -> Self {
+        let count = self.vertices.len();
+        let vertex = |index: usize| Rc::clone(&self.vertices[index % count]);
+        let farthest = |start: usize, end: usize| -> Option<(usize, f64)> {
+            let chord = Edge::new(&vertex(start), &vertex(end));
+            return ((start + 1)..end)
+                .map(|index| (index, edge_distance(&chord, &vertex(index))))
+                .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+        };
+
+        /* kept vertex indices, where count stands for the closing vertex */
+        let mut kept: Vec<bool> = vec![false; count + 1];
+        kept[0] = true;
+        if self.opened {
+            kept[count - 1] = true;
+        } else {
+            kept[count] = true;
+            let head = vertex(0);
+            let far = (1..count)
+                .max_by(|&a, &b| {
+                    let distance_a = distance(&head, &self.vertices[a]);
+                    let distance_b = distance(&head, &self.vertices[b]);
+                    return distance_a.partial_cmp(&distance_b).unwrap();
+                })
+                .unwrap();
+            kept[far] = true;
+        }
+
+        let last = if self.opened { count - 1 } else { count };
+        let mut spans: Vec<(usize, usize)> = Vec::new();
+        let anchors: Vec<usize> = (0..=last).filter(|&index| kept[index]).collect();
+        for pair in anchors.windows(2) {
+            spans.push((pair[0], pair[1]));
+        }
+        while let Some((start, end)) = spans.pop() {
+            if let Some((index, distance)) = farthest(start, end) {
+                if distance > tolerance {
+                    kept[index] = true;
+                    spans.push((start, index));
+                    spans.push((index, end));
+                }
+            }
+        }
+
+        /* closed polylines keep a triangle, around the widest span */
+        if !self.opened && (0..count).filter(|&index| kept[index]).count() < 3 {
+            let anchors: Vec<usize> = (0..=last).filter(|&index| kept[index]).collect();
+            if let Some((index, _)) = anchors
+                .windows(2)
+                .filter_map(|pair| farthest(pair[0], pair[1]))
+                .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            {
+                kept[index] = true;
+            }
+        }
+
+        loop {
+            let anchors: Vec<usize> = (0..=last).filter(|&index| kept[index]).collect();
+            let segments: Vec<(Rc<Vertex>, Rc<Vertex>)> = anchors
+                .windows(2)
+                .map(|pair| (vertex(pair[0]), vertex(pair[1])))
+                .collect();
+
+            let mut restored = false;
+            for (first, second, _) in intersecting_pairs(&segments) {
+                for segment in [first, second].iter() {
+                    let (start, end) = (anchors[*segment], anchors[*segment + 1]);
+                    if let Some((index, _)) = farthest(start, end) {
+                        restored = restored || !kept[index];
+                        kept[index] = true;
+                    }
+                }
+            }
+            if !restored {
+                break;
+            }
+        }
+
+        let vertices: Vec<Rc<Vertex>> = (0..count)
+            .filter(|&index| kept[index])
+            .map(|index| vertex(index))
+            .collect();
+        if self.opened {
+            return Self::new_opened(vertices).unwrap();
+        }
+        return Self::new_closed(vertices).unwrap();
+    }
+
+    /**
+     * Offsets a closed polyline by distance, outwards if positive and
+     * inwards if negative, whatever its orientation. Where offset edges
+     * overlap, they are trimmed at their intersection, and where they move
+     * apart, they are joined as requested. Returns None for opened
+     * polylines, and when the offset polyline collapses or self intersects,
+     * as it happens for inward distances larger than the polyline features.
+     */
+    pub fn offset(&self, distance: f64, join: OffsetJoin) -> Option<Self> {
+        if self.opened {
+            return None;
+        }
+
+        let segments = vertex_pairs(&weld_vertices(&self.vertices, 0.0, false), false);
+        let area = area_segments(&segments);
+        if segments.len() < 3 || area == 0.0 {
+            return None;
+        }
+
+        /* outwards normals lie to the right of counterclockwise edges */
+        let side = if area < 0.0 { 1.0 } else { -1.0 };
+        let normal = |(v1, v2): &(Rc<Vertex>, Rc<Vertex>)| -> (f64, f64) {
+            let length = (v2.x - v1.x).hypot(v2.y - v1.y);
+            return (
+                side * (v2.y - v1.y) / length,
+                side * (v1.x - v2.x) / length,
+            );
+        };
+
+        /* offset vertices of each corner, in order */
+        let mut corners: Vec<Vec<Rc<Vertex>>> = Vec::new();
+        for index in 0..segments.len() {
+            let previous = segments.get((index + segments.len() - 1) % segments.len()).unwrap();
+            let next = segments.get(index).unwrap();
+            let vertex = &next.0;
+
+            let (n1x, n1y) = normal(previous);
+            let (n2x, n2y) = normal(next);
+            let dot = n1x * n2x + n1y * n2y;
+            let cross = (previous.1.x - previous.0.x) * (next.1.y - next.0.y)
+                - (previous.1.y - previous.0.y) * (next.1.x - next.0.x);
+
+            let at = |nx: f64, ny: f64| -> Rc<Vertex> {
+                return Rc::new(Vertex::new(vertex.x + distance * nx, vertex.y + distance * ny));
+            };
+            let miter = || -> Rc<Vertex> {
+                return at((n1x + n2x) / (1.0 + dot), (n1y + n2y) / (1.0 + dot));
+            };
+
+            let mut offset_vertices: Vec<Rc<Vertex>> = Vec::new();
+
+            /* offset edges move apart on convex corners of outwards offsets */
+            let is_apart = side * cross * distance > 0.0;
+            if !is_apart {
+                if 1.0 + dot > std::f64::EPSILON {
+                    offset_vertices.push(miter());
+                } else {
+                    /* edge turning back over itself */
+                    offset_vertices.push(at(n1x, n1y));
+                    offset_vertices.push(at(n2x, n2y));
+                }
+                corners.push(offset_vertices);
+                continue;
+            }
+
+            match join {
+                OffsetJoin::Miter => {
+                    if 1.0 + dot >= 2.0 / (MITER_LIMIT * MITER_LIMIT) {
+                        offset_vertices.push(miter());
+                    } else {
+                        /* bevel */
+                        offset_vertices.push(at(n1x, n1y));
+                        offset_vertices.push(at(n2x, n2y));
+                    }
+                }
+                OffsetJoin::Round(resolution) => {
+                    let sweep = (n1x * n2y - n1y * n2x).atan2(dot);
+                    let step = 2.0 * std::f64::consts::PI / resolution.max(3) as f64;
+                    let pieces = (sweep.abs() / step).ceil().max(1.0) as usize;
+                    for piece in 0..=pieces {
+                        let angle = sweep * piece as f64 / pieces as f64;
+                        let (sin, cos) = angle.sin_cos();
+                        offset_vertices.push(at(n1x * cos - n1y * sin, n1x * sin + n1y * cos));
+                    }
+                }
+            }
+            corners.push(offset_vertices);
+        } /* end - corners loop */
+
+        /* offset edges turned backwards collapsed */
+        for (index, (v1, v2)) in segments.iter().enumerate() {
+            let start = corners.get(index).unwrap().last().unwrap();
+            let end = corners.get((index + 1) % corners.len()).unwrap().first().unwrap();
+            let dot = (end.x - start.x) * (v2.x - v1.x) + (end.y - start.y) * (v2.y - v1.y);
+            if dot <= 0.0 {
+                return None;
+            }
+        }
+
+        let offset_vertices = weld_vertices(&corners.concat(), 0.0, false);
+        if offset_vertices.len() < 3 {
+            return None;
+        }
+
+        /* offsets larger than the polyline features self intersect */
+        let offset_segments = vertex_pairs(&offset_vertices, false);
+        if split_intersections(&offset_segments).len() > offset_segments.len() {
+            return None;
+        }
+        if area_segments(&offset_segments) * area <= 0.0 {
+            return None;
+        }
+
+        return Self::new_closed(offset_vertices);
+    }
+
+    /**
+     * Detemines the loops that bound the set of counterclockwise triangles:
+     * edges not shared by two triangles are arranged into loops, whose
+     * orientation tells outer loops from holes. Components touching at a
+     * vertex are told apart.
+     */
+    pub fn triangles_hull(triangles: &HashSet<Rc<Triangle>>) -> TrianglesHull {
         let mut aux_segments: HashSet<Rc<Edge>> = triangles
             .iter()
             .map(|t| t.inner_edges())
@@ -830,10 +1456,55 @@ impl Polyline {
         }
         let boundary_edges = boundary_edges.values().cloned().collect();
 
-        return Self::arrange(&boundary_edges);
+        let mut hull = TrianglesHull {
+            outer: Vec::new(),
+            holes: Vec::new(),
+        };
+        for polyline in Self::arrange(&boundary_edges).into_iter() {
+            if polyline.opened {
+                continue;
+            }
+            match polyline.orientation() {
+                Some(Orientation::Counterclockwise) => hull.outer.push(polyline),
+                Some(Orientation::Clockwise) => hull.holes.push(polyline),
+                _ => continue,
+            }
+        }
+        return hull;
     }
 } /* end - impl */
 
+/**
+ * Drops every vertex closer than tolerance to the last kept vertex, keeping
+ * the first of them. If not opened, trailing vertices close to the first
+ * vertex are dropped as well.
+ */
+pub fn weld_vertices(
+    vertex_list: &Vec<Rc<Vertex>>,
+    tolerance: f64,
+    opened: bool,
+) -> Vec<Rc<Vertex>> {
+    let is_close = |v1: &Vertex, v2: &Vertex| v1 == v2 || distance(v1, v2) <= tolerance;
+
+    let mut welded: Vec<Rc<Vertex>> = Vec::new();
+    for vertex in vertex_list.iter() {
+        if let Some(last) = welded.last() {
+            if is_close(last, vertex) {
+                continue;
+            }
+        }
+        welded.push(Rc::clone(vertex));
+    }
+
+    if !opened {
+        while welded.len() > 1 && is_close(welded.last().unwrap(), welded.first().unwrap()) {
+            welded.pop();
+        }
+    }
+
+    return welded;
+}
+
 pub fn vertex_pairs(vertex_list: &Vec<Rc<Vertex>>, opened: bool) -> Vec<(Rc<Vertex>, Rc<Vertex>)> {
     let mut pair_list: Vec<(Rc<Vertex>, Rc<Vertex>)> = Vec::new();
 
@@ -854,42 +1525,74 @@ pub fn vertex_pairs(vertex_list: &Vec<Rc<Vertex>>, opened: bool) -> Vec<(Rc<Vert
     return pair_list;
 }
 
+/**
+ * Splits the segments at their intersections with each other, keeping the
+ * order of the segments and of the pieces along each one. Intersections are
+ * found by a sweep line, and a single vertex is shared by the pieces of
+ * every segment crossing at it.
+ */
 pub fn split_intersections(
     segments: &Vec<(Rc<Vertex>, Rc<Vertex>)>,
 ) -> Vec<(Rc<Vertex>, Rc<Vertex>)> {
-    let mut splited_segments: Vec<(Rc<Vertex>, Rc<Vertex>)> = Vec::new();
-    let mut aux_set: Vec<(Rc<Vertex>, Rc<Vertex>)> = segments.iter().cloned().collect();
-
-    while !aux_set.is_empty() {
-        let (v1, v2) = aux_set.pop().unwrap();
-        if let Some(index) = splited_segments.iter().position(|(v3, v4)| {
-            if !intersection(&v1, &v2, &v3, &v4).is_none() {
-                return &v1 != v3
-                    && &v1 != v4
-                    && &v2 != v3
-                    && &v2 != v4
-                    && !parallel(&v1, &v2, &v3, &v4);
-            }
-            return false;
-        }) {
-            let (v3, v4) = splited_segments.remove(index);
-            let intersection_vertex = intersection(&v1, &v2, &v3, &v4).unwrap();
-            let intersection_vertex = Rc::new(intersection_vertex);
-            if v3 != intersection_vertex {
-                aux_set.push((Rc::clone(&v3), Rc::clone(&intersection_vertex)));
-            }
-            if v4 != intersection_vertex {
-                aux_set.push((Rc::clone(&intersection_vertex), Rc::clone(&v4)));
-            }
-            if v1 != intersection_vertex {
-                aux_set.push((Rc::clone(&v1), Rc::clone(&intersection_vertex)));
-            }
-            if v2 != intersection_vertex {
-                aux_set.push((Rc::clone(&intersection_vertex), Rc::clone(&v2)));
+    return split_intersections_snapped(segments, 0.0);
+}
+
+/**
+ * Splits the segments as split_intersections, snap rounding the crossings.
+ * Each crossing is merged into the nearest vertex closer than the grid size,
+ * either an end of the crossing segments or one of their former crossings,
+ * else it is rounded to the grid. Segments are not split closer than the grid
+ * size to their own ends, so that nearly coincident crossings share a vertex
+ * instead of leaving micro segments. Non positive grid sizes keep the
+ * crossings as computed.
+ */
+pub fn split_intersections_snapped(
+    segments: &Vec<(Rc<Vertex>, Rc<Vertex>)>,
+    grid: f64,
+) -> Vec<(Rc<Vertex>, Rc<Vertex>)> {
+    let mut splitting_vertices: Vec<Vec<Rc<Vertex>>> = vec![Vec::new(); segments.len()];
+    for (i, j, vertex) in intersecting_pairs(segments) {
+        let (v1, v2) = &segments[i];
+        let (v3, v4) = &segments[j];
+        let nearest = vec![v1, v2, v3, v4]
+            .into_iter()
+            .chain(splitting_vertices[i].iter())
+            .chain(splitting_vertices[j].iter())
+            .filter(|v| ***v == vertex || distance(v, &vertex) < grid)
+            .min_by(|a, b| {
+                distance(a, &vertex)
+                    .partial_cmp(&distance(b, &vertex))
+                    .unwrap()
+            })
+            .cloned();
+        let vertex = match nearest {
+            Some(nearest) => nearest,
+            None if grid > 0.0 => Rc::new(Vertex::new(
+                (vertex.x / grid).round() * grid,
+                (vertex.y / grid).round() * grid,
+            )),
+            None => Rc::new(vertex),
+        };
+
+        for index in vec![i, j] {
+            let (v1, v2) = &segments[index];
+            let is_end = |end: &Rc<Vertex>| *end == vertex || distance(end, &vertex) < grid;
+            if !is_end(v1) && !is_end(v2) && !splitting_vertices[index].contains(&vertex) {
+                splitting_vertices[index].push(Rc::clone(&vertex));
             }
-        } else {
-            /* no intersection, just segment continuation */
-            splited_segments.push((Rc::clone(&v1), Rc::clone(&v2)));
+        }
+    }
+
+    let mut splited_segments: Vec<(Rc<Vertex>, Rc<Vertex>)> = Vec::new();
+    for ((v1, v2), mut vertices) in segments.iter().zip(splitting_vertices.into_iter()) {
+        /* orders the splitting vertices from v1 towards v2 */
+        let along = |v: &Rc<Vertex>| (v.x - v1.x) * (v2.x - v1.x) + (v.y - v1.y) * (v2.y - v1.y);
+        vertices.sort_by(|a, b| along(a).partial_cmp(&along(b)).unwrap());
+
+        let mut previous = Rc::clone(v1);
+        for vertex in vertices.into_iter().chain(vec![Rc::clone(v2)]) {
+            splited_segments.push((previous, Rc::clone(&vertex)));
+            previous = vertex;
         }
     }
 
@@ -1843,6 +2546,76 @@ mod split_intersections {
         assert_eq!(splited_segments.len(), 18);
     }
 
+    #[test]
+    fn snap_rounding() {
+        /* three lines crossing nearly at (1, 1), and one ending nearly on a line */
+        let segments = vec![
+            (Rc::new(Vertex::new(0.0, 0.0)), Rc::new(Vertex::new(2.0, 2.0))),
+            (Rc::new(Vertex::new(0.0, 2.0)), Rc::new(Vertex::new(2.0, 0.0))),
+            (
+                Rc::new(Vertex::new(0.0, 1.0 + 1.0E-9)),
+                Rc::new(Vertex::new(2.0, 1.0 - 1.0E-9)),
+            ),
+            (
+                Rc::new(Vertex::new(0.5, -1.0)),
+                Rc::new(Vertex::new(0.5, 0.5 + 1.0E-9)),
+            ),
+        ];
+
+        /* raw crossings leave micro segments */
+        let raw = split_intersections(&segments);
+        assert!(raw.iter().any(|(v1, v2)| distance(v1, v2) < 1.0E-6));
+
+        let snapped = split_intersections_snapped(&segments, 1.0E-6);
+        assert!(snapped.iter().all(|(v1, v2)| distance(v1, v2) > 1.0E-6));
+        let crossings: HashSet<*const Vertex> = snapped
+            .iter()
+            .map(|(v1, v2)| vec![v1, v2])
+            .flatten()
+            .filter(|v| distance(v, &Vertex::new(1.0, 1.0)) < 1.0E-6)
+            .map(|v| Rc::as_ptr(v))
+            .collect();
+        assert_eq!(crossings.len(), 1);
+
+        /* the first segment is split at the end of the last one */
+        assert!(snapped
+            .iter()
+            .any(|(v1, v2)| Rc::ptr_eq(v1, &segments[0].0) && Rc::ptr_eq(v2, &segments[3].1)));
+        assert_eq!(snapped.len(), 8);
+    }
+
+    #[test]
+    fn grid_crossings() {
+        /* n horizontal by n vertical segments, crossing at n² shared vertices */
+        let n = 40;
+        let segments: Vec<(Rc<Vertex>, Rc<Vertex>)> = (0..n)
+            .map(|i| {
+                let t = i as f64 + 0.5;
+                return vec![
+                    (Rc::new(Vertex::new(0.0, t)), Rc::new(Vertex::new(n as f64, t))),
+                    (Rc::new(Vertex::new(t, 0.0)), Rc::new(Vertex::new(t, n as f64))),
+                ];
+            })
+            .flatten()
+            .collect();
+
+        let splited_segments = split_intersections(&segments);
+        assert_eq!(splited_segments.len(), 2 * n * (n + 1));
+
+        /* pieces chain along each segment, sharing the crossing vertices */
+        let vertices: HashSet<*const Vertex> = splited_segments
+            .iter()
+            .map(|(v1, v2)| vec![Rc::as_ptr(v1), Rc::as_ptr(v2)])
+            .flatten()
+            .collect();
+        assert_eq!(vertices.len(), n * n + 4 * n);
+        for pieces in splited_segments.chunks(n + 1) {
+            for pair in pieces.windows(2) {
+                assert!(Rc::ptr_eq(&pair[0].1, &pair[1].0));
+            }
+        }
+    }
+
     #[test]
     fn exception_1() {
         /* Circle build */
@@ -1941,77 +2714,79 @@ mod arrange {
         .unwrap();
 
         let edges: HashSet<Rc<Edge>> = p1.into_edges().iter().cloned().collect();
-        let arranged_p1 = Polyline::arrange(&edges).unwrap();
+        let mut arranged = Polyline::arrange(&edges);
+        assert_eq!(arranged.len(), 1);
+        let arranged_p1 = arranged.pop().unwrap();
 
         assert_eq!(
-            (12 + p1.vertices.iter().position(|v| v == &v2).unwrap()
-                - p1.vertices.iter().position(|v| v == &v1).unwrap())
+            (12 + arranged_p1.vertices.iter().position(|v| v == &v2).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v1).unwrap())
                 % 12,
             1
         );
         assert_eq!(
-            (12 + p1.vertices.iter().position(|v| v == &v3).unwrap()
-                - p1.vertices.iter().position(|v| v == &v2).unwrap())
+            (12 + arranged_p1.vertices.iter().position(|v| v == &v3).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v2).unwrap())
                 % 12,
             1
         );
         assert_eq!(
-            (12 + p1.vertices.iter().position(|v| v == &v4).unwrap()
-                - p1.vertices.iter().position(|v| v == &v3).unwrap())
+            (12 + arranged_p1.vertices.iter().position(|v| v == &v4).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v3).unwrap())
                 % 12,
             1
         );
         assert_eq!(
-            (12 + p1.vertices.iter().position(|v| v == &v5).unwrap()
-                - p1.vertices.iter().position(|v| v == &v4).unwrap())
+            (12 + arranged_p1.vertices.iter().position(|v| v == &v5).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v4).unwrap())
                 % 12,
             1
         );
         assert_eq!(
-            (12 + p1.vertices.iter().position(|v| v == &v6).unwrap()
-                - p1.vertices.iter().position(|v| v == &v5).unwrap())
+            (12 + arranged_p1.vertices.iter().position(|v| v == &v6).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v5).unwrap())
                 % 12,
             1
         );
         assert_eq!(
-            (12 + p1.vertices.iter().position(|v| v == &v7).unwrap()
-                - p1.vertices.iter().position(|v| v == &v6).unwrap())
+            (12 + arranged_p1.vertices.iter().position(|v| v == &v7).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v6).unwrap())
                 % 12,
             1
         );
         assert_eq!(
-            (12 + p1.vertices.iter().position(|v| v == &v8).unwrap()
-                - p1.vertices.iter().position(|v| v == &v7).unwrap())
+            (12 + arranged_p1.vertices.iter().position(|v| v == &v8).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v7).unwrap())
                 % 12,
             1
         );
         assert_eq!(
-            (12 + p1.vertices.iter().position(|v| v == &v9).unwrap()
-                - p1.vertices.iter().position(|v| v == &v8).unwrap())
+            (12 + arranged_p1.vertices.iter().position(|v| v == &v9).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v8).unwrap())
                 % 12,
             1
         );
         assert_eq!(
-            (12 + p1.vertices.iter().position(|v| v == &v10).unwrap()
-                - p1.vertices.iter().position(|v| v == &v9).unwrap())
+            (12 + arranged_p1.vertices.iter().position(|v| v == &v10).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v9).unwrap())
                 % 12,
             1
         );
         assert_eq!(
-            (12 + p1.vertices.iter().position(|v| v == &v11).unwrap()
-                - p1.vertices.iter().position(|v| v == &v10).unwrap())
+            (12 + arranged_p1.vertices.iter().position(|v| v == &v11).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v10).unwrap())
                 % 12,
             1
         );
         assert_eq!(
-            (12 + p1.vertices.iter().position(|v| v == &v12).unwrap()
-                - p1.vertices.iter().position(|v| v == &v11).unwrap())
+            (12 + arranged_p1.vertices.iter().position(|v| v == &v12).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v11).unwrap())
                 % 12,
             1
         );
         assert_eq!(
-            (12 + p1.vertices.iter().position(|v| v == &v1).unwrap()
-                - p1.vertices.iter().position(|v| v == &v12).unwrap())
+            (12 + arranged_p1.vertices.iter().position(|v| v == &v1).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v12).unwrap())
                 % 12,
             1
         );
@@ -2041,61 +2816,148 @@ mod arrange {
         .unwrap();
 
         let edges: HashSet<Rc<Edge>> = p1.into_edges().iter().cloned().collect();
-        let arranged_p1 = Polyline::arrange(&edges).unwrap();
+        let mut arranged = Polyline::arrange(&edges);
+        assert_eq!(arranged.len(), 1);
+        let arranged_p1 = arranged.pop().unwrap();
 
         assert_eq!(
-            (8 + p1.vertices.iter().position(|v| v == &v2).unwrap()
-                - p1.vertices.iter().position(|v| v == &v1).unwrap())
+            (8 + arranged_p1.vertices.iter().position(|v| v == &v2).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v1).unwrap())
                 % 8,
             1
         );
         assert_eq!(
-            (8 + p1.vertices.iter().position(|v| v == &v3).unwrap()
-                - p1.vertices.iter().position(|v| v == &v2).unwrap())
+            (8 + arranged_p1.vertices.iter().position(|v| v == &v3).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v2).unwrap())
                 % 8,
             1
         );
         assert_eq!(
-            (8 + p1.vertices.iter().position(|v| v == &v4).unwrap()
-                - p1.vertices.iter().position(|v| v == &v3).unwrap())
+            (8 + arranged_p1.vertices.iter().position(|v| v == &v4).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v3).unwrap())
                 % 8,
             1
         );
         assert_eq!(
-            (8 + p1.vertices.iter().position(|v| v == &v5).unwrap()
-                - p1.vertices.iter().position(|v| v == &v4).unwrap())
+            (8 + arranged_p1.vertices.iter().position(|v| v == &v5).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v4).unwrap())
                 % 8,
             1
         );
         assert_eq!(
-            (8 + p1.vertices.iter().position(|v| v == &v6).unwrap()
-                - p1.vertices.iter().position(|v| v == &v5).unwrap())
+            (8 + arranged_p1.vertices.iter().position(|v| v == &v6).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v5).unwrap())
                 % 8,
             1
         );
         assert_eq!(
-            (8 + p1.vertices.iter().position(|v| v == &v7).unwrap()
-                - p1.vertices.iter().position(|v| v == &v6).unwrap())
+            (8 + arranged_p1.vertices.iter().position(|v| v == &v7).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v6).unwrap())
                 % 8,
             1
         );
         assert_eq!(
-            (8 + p1.vertices.iter().position(|v| v == &v8).unwrap()
-                - p1.vertices.iter().position(|v| v == &v7).unwrap())
+            (8 + arranged_p1.vertices.iter().position(|v| v == &v8).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v7).unwrap())
                 % 8,
             1
         );
         assert_eq!(
-            (8 + p1.vertices.iter().position(|v| v == &v1).unwrap()
-                - p1.vertices.iter().position(|v| v == &v8).unwrap())
+            (8 + arranged_p1.vertices.iter().position(|v| v == &v1).unwrap()
+                - arranged_p1.vertices.iter().position(|v| v == &v8).unwrap())
                 % 8,
             1
         );
     }
-}
+
+    fn edges(coordinates: Vec<((f64, f64), (f64, f64))>) -> HashSet<Rc<Edge>> {
+        return coordinates
+            .iter()
+            .map(|&((x1, y1), (x2, y2))| {
+                Rc::new(Edge::new(
+                    &Rc::new(Vertex::new(x1, y1)),
+                    &Rc::new(Vertex::new(x2, y2)),
+                ))
+            })
+            .collect();
+    }
+
+    #[test]
+    fn multiple_loops() {
+        let edges = edges(vec![
+            ((0.0, 0.0), (1.0, 0.0)),
+            ((1.0, 0.0), (0.0, 1.0)),
+            ((0.0, 1.0), (0.0, 0.0)),
+            ((5.0, 0.0), (6.0, 0.0)),
+            ((6.0, 0.0), (6.0, 1.0)),
+            ((6.0, 1.0), (5.0, 1.0)),
+            ((5.0, 1.0), (5.0, 0.0)),
+        ]);
+
+        let arranged = Polyline::arrange(&edges);
+        assert_eq!(arranged.len(), 2);
+        assert!(arranged.iter().all(|polyline| !polyline.opened));
+        assert_eq!(arranged[0].vertices.len(), 3);
+        assert_eq!(arranged[0].vertices[0], Rc::new(Vertex::new(0.0, 0.0)));
+        assert_eq!(arranged[1].vertices.len(), 4);
+        assert_eq!(arranged[1].vertices[0], Rc::new(Vertex::new(5.0, 0.0)));
+    }
+
+    #[test]
+    fn branching_loops() {
+        /* counterclockwise triangles touching at (0, 0) */
+        let edges = edges(vec![
+            ((-1.0, -1.0), (0.0, 0.0)),
+            ((0.0, 0.0), (-1.0, 1.0)),
+            ((-1.0, 1.0), (-1.0, -1.0)),
+            ((0.0, 0.0), (1.0, -1.0)),
+            ((1.0, -1.0), (1.0, 1.0)),
+            ((1.0, 1.0), (0.0, 0.0)),
+        ]);
+
+        let arranged = Polyline::arrange(&edges);
+        assert_eq!(arranged.len(), 2);
+        for polyline in arranged.iter() {
+            assert!(!polyline.opened);
+            assert_eq!(polyline.vertices.len(), 3);
+            assert_eq!(polyline.orientation(), Some(Orientation::Counterclockwise));
+        }
+    }
+
+    #[test]
+    fn open_chains() {
+        /* a chain running around a square, and a dangling edge */
+        let edges = edges(vec![
+            ((-2.0, 0.0), (-1.0, 0.0)),
+            ((-1.0, 0.0), (0.0, 0.0)),
+            ((0.0, 0.0), (1.0, 0.0)),
+            ((1.0, 0.0), (1.0, 1.0)),
+            ((1.0, 1.0), (0.0, 1.0)),
+            ((0.0, 1.0), (0.0, 0.0)),
+            ((3.0, 3.0), (4.0, 4.0)),
+        ]);
+
+        let arranged = Polyline::arrange(&edges);
+        assert_eq!(arranged.len(), 2);
+        assert!(arranged.iter().all(|polyline| polyline.opened));
+        assert_eq!(arranged[0].head(), Some(Rc::new(Vertex::new(-2.0, 0.0))));
+        assert_eq!(arranged[0].tail(), Some(Rc::new(Vertex::new(0.0, 0.0))));
+        let covered: usize = arranged
+            .iter()
+            .map(|polyline| polyline.into_edges().len())
+            .sum();
+        assert_eq!(covered, edges.len());
+
+        /* arrangement does not depend on the hash set order */
+        for _ in 0..8 {
+            let shuffled: HashSet<Rc<Edge>> = edges.iter().cloned().collect();
+            assert!(Polyline::arrange(&shuffled) == arranged);
+        }
+    }
+} /* end - arrange tests */
 
 #[cfg(test)]
-mod minified_noncolinear {
+mod simplify_collinear {
     use super::*;
 
     #[test]
@@ -2113,7 +2975,7 @@ mod minified_noncolinear {
         ])
         .unwrap();
 
-        let minified = p1.minified_noncolinear();
+        let minified = p1.simplify_collinear().unwrap();
 
         assert!(minified.vertices.contains(&v1));
         assert!(minified.vertices.contains(&v3));
@@ -2144,7 +3006,7 @@ mod minified_noncolinear {
         ])
         .unwrap();
 
-        let minified = p1.minified_noncolinear();
+        let minified = p1.simplify_collinear().unwrap();
 
         assert!(minified.vertices.contains(&v1));
         assert!(minified.vertices.contains(&v2));
@@ -2179,7 +3041,7 @@ mod minified_noncolinear {
         ])
         .unwrap();
 
-        let minified = p1.minified_noncolinear();
+        let minified = p1.simplify_collinear().unwrap();
 
         assert!(!minified.vertices.contains(&v1));
         assert!(!minified.vertices.contains(&v2));
@@ -2214,7 +3076,7 @@ mod minified_noncolinear {
         ])
         .unwrap();
 
-        let minified = p1.minified_noncolinear();
+        let minified = p1.simplify_collinear().unwrap();
 
         assert!(minified.vertices.contains(&v1));
         assert!(!minified.vertices.contains(&v2));
@@ -2224,8 +3086,46 @@ mod minified_noncolinear {
         assert!(!minified.vertices.contains(&v6));
         assert!(!minified.vertices.contains(&v7));
         assert!(minified.vertices.contains(&v8));
+        assert!(minified.opened);
     }
-}
+
+    #[test]
+    fn two_vertices() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let p1 = Polyline::new_opened(vec![Rc::clone(&v1), Rc::clone(&v2)]).unwrap();
+
+        let minified = p1.simplify_collinear().unwrap();
+        assert!(minified.opened);
+        assert_eq!(minified.vertices, vec![v1, v2]);
+    }
+
+    #[test]
+    fn all_colinear() {
+        let p1 = Polyline::new_closed(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(1.0, 0.0)),
+            Rc::new(Vertex::new(2.0, 0.0)),
+        ])
+        .unwrap();
+        assert!(p1.simplify_collinear().is_none());
+
+        let p2 = Polyline::new_opened(p1.vertices.clone()).unwrap();
+        assert_eq!(p2.simplify_collinear().unwrap().vertices.len(), 2);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_closes_opened() {
+        let p1 = Polyline::new_opened(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(1.0, 0.0)),
+            Rc::new(Vertex::new(1.0, 1.0)),
+        ])
+        .unwrap();
+        assert!(!p1.minified_noncolinear().opened);
+    }
+} /* end - simplify_collinear tests */
 
 #[cfg(test)]
 mod continence_self {
@@ -2481,6 +3381,7 @@ mod triangles_hull {
             .cloned()
             .collect(),
         )
+        .single()
         .unwrap();
 
         assert!(hull.vertices.contains(&v1));
@@ -2524,6 +3425,7 @@ mod triangles_hull {
             .cloned()
             .collect(),
         )
+        .single()
         .unwrap();
 
         assert!(hull.vertices.contains(&v1));
@@ -2534,4 +3436,480 @@ mod triangles_hull {
         assert!(hull.vertices.contains(&v10));
         assert!(hull.vertices.contains(&v4));
     }
+
+    fn square_cells(cells: Vec<(i32, i32)>) -> HashSet<Rc<Triangle>> {
+        return cells
+            .iter()
+            .map(|&(x, y)| {
+                let v1 = Rc::new(Vertex::new(x as f64, y as f64));
+                let v2 = Rc::new(Vertex::new(x as f64 + 1.0, y as f64));
+                let v3 = Rc::new(Vertex::new(x as f64 + 1.0, y as f64 + 1.0));
+                let v4 = Rc::new(Vertex::new(x as f64, y as f64 + 1.0));
+                return vec![
+                    Rc::new(Triangle::new(&v1, &v2, &v3)),
+                    Rc::new(Triangle::new(&v1, &v3, &v4)),
+                ];
+            })
+            .flatten()
+            .collect();
+    }
+
+    #[test]
+    fn hole_loops() {
+        /* 3 by 3 cells, without the center one */
+        let cells = (0..3)
+            .map(|x| (0..3).map(move |y| (x, y)))
+            .flatten()
+            .filter(|&cell| cell != (1, 1))
+            .collect();
+
+        let hull = Polyline::triangles_hull(&square_cells(cells));
+        assert_eq!(hull.outer.len(), 1);
+        assert_eq!(hull.holes.len(), 1);
+        assert_eq!(hull.outer[0].area(), Some(9.0));
+        assert_eq!(hull.holes[0].area(), Some(-1.0));
+        assert_eq!(hull.holes[0].vertices.len(), 4);
+        assert!(hull.single().is_none());
+    }
+
+    #[test]
+    fn multiple_components() {
+        /* cells apart, and cells touching at the (3, 1) corner */
+        let hull = Polyline::triangles_hull(&square_cells(vec![(0, 0), (2, 0), (3, 1)]));
+        assert_eq!(hull.outer.len(), 3);
+        assert!(hull.holes.is_empty());
+        for polyline in hull.outer.iter() {
+            assert_eq!(polyline.area(), Some(1.0));
+        }
+    }
 } /* end - triangles_hull */
+
+#[cfg(test)]
+mod split_edges {
+    use super::*;
+
+    #[test]
+    fn closed_polyline() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(4.0, 0.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(0.0, 4.0));
+
+        let v5 = Rc::new(Vertex::new(3.0, 0.0));
+        let v6 = Rc::new(Vertex::new(1.0, 0.0));
+        let v7 = Rc::new(Vertex::new(0.0, 2.0));
+        let v8 = Rc::new(Vertex::new(2.0, 2.0)); /* not at the edges */
+
+        let polyline = Polyline::new_closed(vec![
+            Rc::clone(&v1),
+            Rc::clone(&v2),
+            Rc::clone(&v3),
+            Rc::clone(&v4),
+        ])
+        .unwrap();
+
+        let split = polyline.split_edges(&vec![
+            Rc::clone(&v5),
+            Rc::clone(&v6),
+            Rc::clone(&v7),
+            Rc::clone(&v8),
+        ]);
+
+        assert!(!split.opened);
+        assert_eq!(split.vertices, vec![v1, v6, v5, v2, v3, v4, v7]);
+    }
+
+    #[test]
+    fn opened_polyline() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(4.0, 0.0));
+        let v3 = Rc::new(Vertex::new(2.0, 0.0));
+
+        let polyline = Polyline::new_opened(vec![Rc::clone(&v1), Rc::clone(&v2)]).unwrap();
+        let split = polyline.split_edges(&vec![Rc::clone(&v3)]);
+
+        assert!(split.opened);
+        assert_eq!(split.vertices, vec![v1, v3, v2]);
+    }
+} /* end - split_edges tests */
+
+#[cfg(test)]
+mod subdivide {
+    use super::*;
+
+    #[test]
+    fn closed_polyline() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(3.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+
+        let polyline = Polyline::new_closed(vec![
+            Rc::clone(&v1),
+            Rc::clone(&v2),
+            Rc::clone(&v3),
+        ])
+        .unwrap();
+
+        let split = polyline.subdivide(1.5);
+        assert!(!split.opened);
+        assert_eq!(
+            split.vertices,
+            vec![
+                Rc::clone(&v1),
+                Rc::new(Vertex::new(1.5, 0.0)),
+                Rc::clone(&v2),
+                Rc::new(Vertex::new(2.0, 1.0 / 3.0)),
+                Rc::new(Vertex::new(1.0, 2.0 / 3.0)),
+                Rc::clone(&v3),
+            ]
+        );
+        for edge in split.into_edges() {
+            assert!(edge.length() <= 1.5);
+        }
+    }
+
+    #[test]
+    fn opened_polyline() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(4.0, 0.0));
+
+        let polyline = Polyline::new_opened(vec![Rc::clone(&v1), Rc::clone(&v2)]).unwrap();
+
+        let split = polyline.subdivide(1.0);
+        assert!(split.opened);
+        assert_eq!(split.vertices.len(), 5);
+        assert_eq!(split.tail(), Some(v2));
+
+        let unchanged = polyline.subdivide(10.0);
+        assert_eq!(unchanged.vertices.len(), 2);
+    }
+} /* end - subdivide tests */
+
+#[cfg(test)]
+mod simplify {
+    use super::*;
+
+    fn polyline(coordinates: Vec<(f64, f64)>, opened: bool) -> Polyline {
+        let vertices = coordinates
+            .iter()
+            .map(|&(x, y)| Rc::new(Vertex::new(x, y)))
+            .collect();
+        if opened {
+            return Polyline::new_opened(vertices).unwrap();
+        }
+        return Polyline::new_closed(vertices).unwrap();
+    }
+
+    #[test]
+    fn closed_polyline() {
+        /* digitized square, with noise along its sides */
+        let mut coordinates: Vec<(f64, f64)> = Vec::new();
+        for index in 0..40 {
+            let noise = if index % 2 == 0 { 0.001 } else { -0.001 };
+            let t = (index % 10) as f64 / 10.0;
+            coordinates.push(match index / 10 {
+                0 => (t, noise),
+                1 => (1.0 + noise, t),
+                2 => (1.0 - t, 1.0 + noise),
+                _ => (noise, 1.0 - t),
+            });
+        }
+
+        let simplified = polyline(coordinates, false).simplify(0.01);
+        assert!(!simplified.opened);
+        assert_eq!(simplified.vertices.len(), 4);
+        assert!((simplified.area().unwrap() - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn opened_polyline() {
+        let zigzag = polyline(vec![(0.0, 0.0), (1.0, 0.1), (2.0, -0.1), (3.0, 0.0)], true);
+
+        let simplified = zigzag.simplify(0.2);
+        assert!(simplified.opened);
+        assert_eq!(simplified.vertices.len(), 2);
+        assert_eq!(simplified.head(), zigzag.head());
+        assert_eq!(simplified.tail(), zigzag.tail());
+
+        assert_eq!(zigzag.simplify(0.05).vertices.len(), 4);
+    }
+
+    #[test]
+    fn keeps_triangle() {
+        let thin = polyline(vec![(0.0, 0.0), (1.0, 0.0), (2.0, 0.01), (1.0, 0.02)], false);
+        assert_eq!(thin.simplify(1.0).vertices.len(), 3);
+    }
+
+    #[test]
+    fn preserves_topology() {
+        /*
+            The bottom dip is within tolerance, but dropping it would cross
+            the deep notch coming from the top
+        */
+        let notched = polyline(
+            vec![
+                (0.0, 1.0),
+                (5.0, 0.0),
+                (10.0, 1.0),
+                (10.0, 4.0),
+                (5.5, 4.0),
+                (5.0, 0.5),
+                (4.5, 4.0),
+                (0.0, 4.0),
+            ],
+            false,
+        );
+
+        let simplified = notched.simplify(1.5);
+        assert!(simplified.vertices.contains(&Rc::new(Vertex::new(5.0, 0.0))));
+        assert!(simplified.vertices.contains(&Rc::new(Vertex::new(5.0, 0.5))));
+        let segments = vertex_pairs(&simplified.vertices, false);
+        assert!(intersecting_pairs(&segments).is_empty());
+    }
+} /* end - simplify tests */
+
+#[cfg(test)]
+mod weld {
+    use super::*;
+
+    #[test]
+    fn closed_polyline() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0 + 1.0E-13, 0.0));
+        let v4 = Rc::new(Vertex::new(1.0, 1.0));
+        let v5 = Rc::new(Vertex::new(1.0, 1.0));
+        let v6 = Rc::new(Vertex::new(0.0, 1.0E-13));
+
+        let polyline = Polyline::new_closed_welded(
+            vec![
+                Rc::clone(&v1),
+                Rc::clone(&v2),
+                Rc::clone(&v3),
+                Rc::clone(&v4),
+                Rc::clone(&v5),
+                Rc::clone(&v6),
+            ],
+            1.0E-12,
+        )
+        .unwrap();
+
+        assert_eq!(polyline.vertices, vec![v1, v2, v4]);
+        for edge in polyline.into_edges() {
+            assert!(edge.length() > 1.0E-12);
+        }
+    }
+
+    #[test]
+    fn opened_polyline() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(0.0, 0.0));
+        let v3 = Rc::new(Vertex::new(2.0, 0.0));
+
+        let polyline = Polyline::new_opened(vec![Rc::clone(&v1), v2, Rc::clone(&v3)]).unwrap();
+        let welded = polyline.weld(1.0E-12).unwrap();
+        assert!(welded.opened);
+        assert_eq!(welded.vertices, vec![v1, v3]);
+    }
+
+    #[test]
+    fn degenerate_polyline() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0E-13, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0, 0.0));
+
+        assert!(Polyline::new_closed_welded(vec![v1, v2, v3], 1.0E-12).is_none());
+    }
+} /* end - weld tests */
+
+#[cfg(test)]
+mod offset {
+    use super::*;
+
+    fn square() -> Polyline {
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(3.0, 1.0));
+        let v3 = Rc::new(Vertex::new(3.0, 3.0));
+        let v4 = Rc::new(Vertex::new(1.0, 3.0));
+        return Polyline::new_closed(vec![v1, v2, v3, v4]).unwrap();
+    }
+
+    fn area(polyline: &Polyline) -> f64 {
+        return area_segments(&vertex_pairs(&polyline.vertices, false)).abs();
+    }
+
+    #[test]
+    fn miter_outwards() {
+        let offset = square().offset(1.0, OffsetJoin::Miter).unwrap();
+        assert_eq!(
+            offset.vertices,
+            vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn miter_inwards() {
+        let offset = square().offset(-0.5, OffsetJoin::Miter).unwrap();
+        assert_eq!(offset.vertices.len(), 4);
+        assert!((area(&offset) - 1.0).abs() < 1.0E-12);
+    }
+
+    #[test]
+    fn clockwise_polyline() {
+        let mut vertices = square().vertices;
+        vertices.reverse();
+        let polyline = Polyline::new_closed(vertices).unwrap();
+
+        let offset = polyline.offset(1.0, OffsetJoin::Miter).unwrap();
+        assert!((area(&offset) - 16.0).abs() < 1.0E-12);
+        assert_eq!(
+            segments_orientation(&vertex_pairs(&offset.vertices, false)),
+            Orientation::Clockwise
+        );
+    }
+
+    #[test]
+    fn round_outwards() {
+        let offset = square().offset(1.0, OffsetJoin::Round(64)).unwrap();
+        assert_eq!(offset.vertices.len(), 4 * 17);
+
+        /* square, four side bands and a full disc at the corners */
+        let expected = 4.0 + 8.0 + std::f64::consts::PI;
+        assert!((area(&offset) - expected).abs() < 0.01);
+        for vertex in offset.vertices.iter() {
+            assert!(square().contains(vertex) == Some(Continence::Outside));
+        }
+    }
+
+    #[test]
+    fn concave_corner() {
+        /* L shaped polyline, whose concave corner is trimmed outwards */
+        let polyline = Polyline::new_closed(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(2.0, 0.0)),
+            Rc::new(Vertex::new(2.0, 1.0)),
+            Rc::new(Vertex::new(1.0, 1.0)),
+            Rc::new(Vertex::new(1.0, 2.0)),
+            Rc::new(Vertex::new(0.0, 2.0)),
+        ])
+        .unwrap();
+
+        let offset = polyline.offset(0.5, OffsetJoin::Round(16)).unwrap();
+        assert!(offset.vertices.contains(&Rc::new(Vertex::new(1.5, 1.5))));
+    }
+
+    #[test]
+    fn collapsed_offset() {
+        assert!(square().offset(-1.0, OffsetJoin::Miter).is_none());
+        assert!(square().offset(-1.5, OffsetJoin::Miter).is_none());
+
+        let opened = Polyline::new_opened(square().vertices).unwrap();
+        assert!(opened.offset(1.0, OffsetJoin::Miter).is_none());
+    }
+} /* end - offset tests */
+
+#[cfg(test)]
+mod moments {
+    use super::*;
+
+    fn rectangle(x: f64, y: f64, width: f64, height: f64) -> Vec<Rc<Vertex>> {
+        return vec![
+            Rc::new(Vertex::new(x, y)),
+            Rc::new(Vertex::new(x + width, y)),
+            Rc::new(Vertex::new(x + width, y + height)),
+            Rc::new(Vertex::new(x, y + height)),
+        ];
+    }
+
+    #[test]
+    fn rectangles() {
+        let counterclockwise = Polyline::new_closed(rectangle(1.0, 2.0, 4.0, 2.0)).unwrap();
+        assert_eq!(counterclockwise.area(), Some(8.0));
+        assert_eq!(counterclockwise.centroid(), Some(Vertex::new(3.0, 3.0)));
+
+        /* b h³ / 12 and h b³ / 12, with no product moment by symmetry */
+        let (ixx, iyy, ixy) = counterclockwise.second_moments().unwrap();
+        assert!((ixx - 4.0 * 8.0 / 12.0).abs() < 1.0E-12);
+        assert!((iyy - 2.0 * 64.0 / 12.0).abs() < 1.0E-12);
+        assert!(ixy.abs() < 1.0E-12);
+
+        let mut vertices = rectangle(1.0, 2.0, 4.0, 2.0);
+        vertices.reverse();
+        let clockwise = Polyline::new_closed(vertices).unwrap();
+        assert_eq!(clockwise.area(), Some(-8.0));
+        assert_eq!(clockwise.centroid(), Some(Vertex::new(3.0, 3.0)));
+        let (ixx, _, _) = clockwise.second_moments().unwrap();
+        assert!((ixx + 4.0 * 8.0 / 12.0).abs() < 1.0E-12);
+    }
+
+    #[test]
+    fn right_triangle() {
+        let polyline = Polyline::new_closed(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(3.0, 0.0)),
+            Rc::new(Vertex::new(0.0, 3.0)),
+        ])
+        .unwrap();
+        assert_eq!(polyline.area(), Some(4.5));
+        assert_eq!(polyline.centroid(), Some(Vertex::new(1.0, 1.0)));
+
+        /* b h³ / 36 and -b² h² / 72 about the centroid */
+        let (ixx, iyy, ixy) = polyline.second_moments().unwrap();
+        assert!((ixx - 81.0 / 36.0).abs() < 1.0E-12);
+        assert!((iyy - 81.0 / 36.0).abs() < 1.0E-12);
+        assert!((ixy + 81.0 / 72.0).abs() < 1.0E-12);
+    }
+
+    #[test]
+    fn degenerate() {
+        let opened = Polyline::new_opened(rectangle(0.0, 0.0, 1.0, 1.0)).unwrap();
+        assert_eq!(opened.area(), None);
+        assert_eq!(opened.centroid(), None);
+        assert_eq!(opened.second_moments(), None);
+
+        let far = Polyline::new_closed(rectangle(1.0E8, 1.0E8, 1.0, 1.0)).unwrap();
+        assert_eq!(far.centroid(), Some(Vertex::new(1.0E8 + 0.5, 1.0E8 + 0.5)));
+    }
+} /* end - moments tests */
+
+#[cfg(test)]
+mod orientation {
+    use super::*;
+
+    fn square() -> Vec<Rc<Vertex>> {
+        return vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(1.0, 0.0)),
+            Rc::new(Vertex::new(1.0, 1.0)),
+            Rc::new(Vertex::new(0.0, 1.0)),
+        ];
+    }
+
+    #[test]
+    fn windings() {
+        let counterclockwise = Polyline::new_closed(square()).unwrap();
+        let clockwise = counterclockwise.reversed();
+        assert_eq!(counterclockwise.orientation(), Some(Orientation::Counterclockwise));
+        assert_eq!(clockwise.orientation(), Some(Orientation::Clockwise));
+        assert!(clockwise.reversed() == counterclockwise);
+
+        assert!(counterclockwise.ensure_ccw() == counterclockwise);
+        assert!(clockwise.ensure_ccw() == counterclockwise);
+        assert!(counterclockwise.ensure_cw() == clockwise);
+        assert!(clockwise.ensure_cw() == clockwise);
+    }
+
+    #[test]
+    fn opened() {
+        let opened = Polyline::new_opened(square()).unwrap();
+        assert_eq!(opened.orientation(), None);
+        assert!(opened.ensure_ccw() == opened);
+        assert!(opened.reversed().opened);
+        assert_eq!(opened.reversed().head(), opened.tail());
+    }
+} /* end - orientation tests */
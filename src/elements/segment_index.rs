@@ -0,0 +1,150 @@
+use crate::elements::{edge::*, vertex::*};
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/**
+ * Segments bucketed by vertical slabs, each listing the segments whose
+ * bounding box spans it, so that the segments containing a vertex are
+ * searched among the ones of a single slab instead of all of them. Slabs
+ * are about as wide as the average segment, thus each segment is listed
+ * by a couple of slabs in average.
+ */
+pub struct SegmentIndex {
+    pub slab_width: f64,
+    slabs: HashMap<i64, Vec<Rc<Edge>>>,
+}
+
+impl SegmentIndex {
+    pub fn new<'a, I>(segments: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Rc<Edge>>,
+    {
+        let segments: Vec<&Rc<Edge>> = segments.into_iter().collect();
+        let total_width: f64 = segments.iter().map(|s| (s.v2.x - s.v1.x).abs()).sum();
+        let mut slab_width = total_width / segments.len().max(1) as f64;
+        if !(slab_width > 0.0) {
+            slab_width = 1.0;
+        }
+
+        let mut index = Self {
+            slab_width,
+            slabs: HashMap::new(),
+        };
+        for segment in segments.into_iter() {
+            index.insert(segment);
+        }
+        return index;
+    }
+
+    pub fn insert(&mut self, segment: &Rc<Edge>) {
+        let first = self.slab(segment.v1.x.min(segment.v2.x));
+        let last = self.slab(segment.v1.x.max(segment.v2.x));
+        for slab in first..=last {
+            self.slabs
+                .entry(slab)
+                .or_insert(Vec::new())
+                .push(Rc::clone(segment));
+        }
+    }
+
+    /**
+     * Segments containing the vertex, including the ones ending at it.
+     */
+    pub fn containing(&self, vertex: &Vertex) -> Vec<Rc<Edge>> {
+        match self.slabs.get(&self.slab(vertex.x)) {
+            Some(segments) => {
+                return segments
+                    .iter()
+                    .filter(|segment| segment.contains(vertex))
+                    .cloned()
+                    .collect()
+            }
+            None => return Vec::new(),
+        }
+    }
+
+    fn slab(&self, x: f64) -> i64 {
+        return (x / self.slab_width).floor() as i64;
+    }
+} /* end - SegmentIndex */
+
+/**
+ * Splits the segment at the vertices lying on it, as a chain of subsegments
+ * from its first vertex to its last one.
+ */
+pub fn split_segment(segment: &Rc<Edge>, vertices: &[Rc<Vertex>]) -> Vec<Rc<Edge>> {
+    let mut inner_vertices: Vec<&Rc<Vertex>> = vertices
+        .iter()
+        .filter(|v| **v != segment.v1 && **v != segment.v2)
+        .collect();
+    if inner_vertices.is_empty() {
+        return vec![Rc::clone(segment)];
+    }
+    let squared_distance = |v: &Vertex| {
+        return (v.x - segment.v1.x).powi(2) + (v.y - segment.v1.y).powi(2);
+    };
+    inner_vertices.sort_by(|v1, v2| {
+        squared_distance(v1)
+            .partial_cmp(&squared_distance(v2))
+            .unwrap()
+    });
+    inner_vertices.dedup();
+
+    let mut chain: Vec<&Rc<Vertex>> = vec![&segment.v1];
+    chain.extend(inner_vertices);
+    chain.push(&segment.v2);
+    return chain
+        .windows(2)
+        .map(|pair| Rc::new(Edge::new(pair[0], pair[1])))
+        .collect();
+}
+
+#[cfg(test)]
+mod segment_index {
+    use super::*;
+
+    #[test]
+    fn containing() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(10.0, 0.0));
+        let v3 = Rc::new(Vertex::new(5.0, -1.0));
+        let v4 = Rc::new(Vertex::new(5.0, 1.0));
+        let long = Rc::new(Edge::new(&v1, &v2));
+        let vertical = Rc::new(Edge::new(&v3, &v4));
+        let index = SegmentIndex::new(vec![&long, &vertical]);
+
+        assert_eq!(index.containing(&Vertex::new(2.0, 0.0)), vec![Rc::clone(&long)]);
+        assert_eq!(index.containing(&Vertex::new(9.5, 0.0)), vec![Rc::clone(&long)]);
+        assert_eq!(index.containing(&Vertex::new(5.0, 0.5)), vec![Rc::clone(&vertical)]);
+        assert_eq!(index.containing(&Vertex::new(5.0, 0.0)).len(), 2);
+
+        /* ends are contained */
+        assert_eq!(index.containing(&v2), vec![Rc::clone(&long)]);
+
+        assert!(index.containing(&Vertex::new(2.0, 0.5)).is_empty());
+        assert!(index.containing(&Vertex::new(-2.0, 0.0)).is_empty());
+    }
+
+    #[test]
+    fn split() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(4.0, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0, 0.0));
+        let v4 = Rc::new(Vertex::new(3.0, 0.0));
+        let segment = Rc::new(Edge::new(&v1, &v2));
+
+        let subsegments =
+            split_segment(&segment, &vec![Rc::clone(&v4), Rc::clone(&v1), Rc::clone(&v3)]);
+        assert_eq!(
+            subsegments,
+            vec![
+                Rc::new(Edge::new(&v1, &v3)),
+                Rc::new(Edge::new(&v3, &v4)),
+                Rc::new(Edge::new(&v4, &v2)),
+            ]
+        );
+
+        assert_eq!(split_segment(&segment, &vec![Rc::clone(&v2)]), vec![segment]);
+    }
+} /* end - segment_index tests */
@@ -1,5 +1,8 @@
 use crate::elements::{edge::*, polyline::*, vertex::*};
-use crate::properties::{area::*, circumcenter::*, continence::*, distance::*, orientation::*};
+use crate::planar::stats;
+use crate::properties::{
+    angle::*, area::*, circumcenter::*, continence::*, distance::*, orientation::*,
+};
 
 use std::cmp::Eq;
 use std::fmt;
@@ -8,6 +11,7 @@ use std::hash::Hash;
 use std::rc::Rc;
 
 #[derive(Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Triangle {
     pub v1: Rc<Vertex>,
     pub v2: Rc<Vertex>,
@@ -32,6 +36,7 @@ impl fmt::Display for Triangle {
 
 impl Triangle {
     pub fn new(v1: &Rc<Vertex>, v2: &Rc<Vertex>, v3: &Rc<Vertex>) -> Triangle {
+        stats::count_triangle();
         Triangle {
             v1: Rc::clone(&v1),
             v2: Rc::clone(&v2),
@@ -48,6 +53,17 @@ impl Triangle {
         self.v1.is_ghost || self.v2.is_ghost || self.v3.is_ghost
     }
 
+    /**
+     * Ghost vertex of the triangle, identifying the loop it surrounds.
+     * Returns None if solid.
+     */
+    pub fn ghost_vertex(&self) -> Option<Rc<Vertex>> {
+        return vec![&self.v1, &self.v2, &self.v3]
+            .into_iter()
+            .find(|v| v.is_ghost)
+            .cloned();
+    }
+
     pub fn area(&self) -> Option<f64> {
         if self.is_ghost() {
             return None;
@@ -75,6 +91,58 @@ impl Triangle {
         }
     }
 
+    /**
+     * Checks whether the vertex lies inside the triangle, or on its edges.
+     * Ghost triangles contain no vertex.
+     */
+    pub fn contains(&self, vertex: &Vertex) -> Continence {
+        if self.is_ghost() {
+            return Continence::Outside;
+        }
+
+        let mut continence = Continence::Inside;
+        for (v1, v2) in vec![(&self.v1, &self.v2), (&self.v2, &self.v3), (&self.v3, &self.v1)] {
+            match orientation(v1, v2, vertex) {
+                Orientation::Clockwise => return Continence::Outside,
+                Orientation::Colinear => continence = Continence::Boundary,
+                Orientation::Counterclockwise => {}
+            }
+        }
+
+        return continence;
+    }
+
+    /**
+     * Barycentric coordinates of the vertex, as the weights of v1, v2 and v3
+     * that sum up to 1, all of them non negative for contained vertices.
+     * Returns None for ghost and degenerate triangles.
+     */
+    pub fn barycentric(&self, vertex: &Vertex) -> Option<(f64, f64, f64)> {
+        let area = self.area()?;
+        if area == 0.0 {
+            return None;
+        }
+
+        return Some((
+            area_triangle(vertex, &self.v2, &self.v3) / area,
+            area_triangle(&self.v1, vertex, &self.v3) / area,
+            area_triangle(&self.v1, &self.v2, vertex) / area,
+        ));
+    }
+
+    /**
+     * Point at the parameters along the edges from v1: v1 + u (v2 - v1) +
+     * v (v3 - v1). Points with non negative u and v, whose sum is at most 1,
+     * lie in the triangle, so that uniform samples of the parameters square
+     * folded on its diagonal sample the triangle uniformly.
+     */
+    pub fn sample(&self, u: f64, v: f64) -> Vertex {
+        return Vertex::new(
+            self.v1.x + u * (self.v2.x - self.v1.x) + v * (self.v3.x - self.v1.x),
+            self.v1.y + u * (self.v2.y - self.v1.y) + v * (self.v3.y - self.v1.y),
+        );
+    }
+
     /**
      * Determines the circumcenter.
      * Returns None, if ghost of colinear vertices.
@@ -86,6 +154,14 @@ impl Triangle {
         return circumcenter(&self.v1, &self.v2, &self.v3);
     }
 
+    /**
+     * Radius of the circumcircle. Returns None if ghost or degenerate.
+     */
+    pub fn circumradius(&self) -> Option<f64> {
+        let circumcenter = self.circumcenter()?;
+        return Some(distance(&circumcenter, &self.v1));
+    }
+
     pub fn quality(&self) -> Option<f64> {
         if self.is_ghost() {
             return None;
@@ -116,6 +192,70 @@ impl Triangle {
         }
     }
 
+    /**
+     * Inner angles at v1, v2 and v3, in radians.
+     * Returns None if ghost.
+     */
+    pub fn angles(&self) -> Option<(f64, f64, f64)> {
+        if self.is_ghost() {
+            return None;
+        }
+
+        let inner_angle = |a: &Vertex, b: &Vertex, c: &Vertex| -> f64 {
+            let theta = angle(a, b, c).unwrap();
+            return theta.min(2.0 * std::f64::consts::PI - theta);
+        };
+
+        return Some((
+            inner_angle(&self.v3, &self.v1, &self.v2),
+            inner_angle(&self.v1, &self.v2, &self.v3),
+            inner_angle(&self.v2, &self.v3, &self.v1),
+        ));
+    }
+
+    /**
+     * Smallest inner angle, in radians. Returns None if ghost.
+     */
+    pub fn min_angle(&self) -> Option<f64> {
+        let (a1, a2, a3) = self.angles()?;
+        return Some(a1.min(a2).min(a3));
+    }
+
+    /**
+     * Largest inner angle, in radians. Returns None if ghost.
+     */
+    pub fn max_angle(&self) -> Option<f64> {
+        let (a1, a2, a3) = self.angles()?;
+        return Some(a1.max(a2).max(a3));
+    }
+
+    /**
+     * Ratio of the longest edge to the diameter of the inscribed circle,
+     * scaled so that the equilateral triangle has aspect ratio 1.
+     * Returns None if ghost.
+     */
+    pub fn aspect_ratio(&self) -> Option<f64> {
+        if self.is_ghost() {
+            return None;
+        }
+
+        /*
+            Let a,b,c be the sides of a triangle, and A its area.
+            Then inradius is given by:
+
+                r = 2*A / (a+b+c)
+
+            and equilateral triangles have l_max = 2*sqrt(3)*r.
+        */
+        let a = distance(&self.v1, &self.v2);
+        let b = distance(&self.v2, &self.v3);
+        let c = distance(&self.v3, &self.v1);
+
+        let inradius = 2.0 * self.area().unwrap().abs() / (a + b + c);
+
+        return Some(a.max(b).max(c) / (2.0 * 3.0_f64.sqrt() * inradius));
+    }
+
     pub fn inner_edges(&self) -> (Rc<Edge>, Rc<Edge>, Rc<Edge>) {
         let e1 = Rc::new(Edge::new(&self.v1, &self.v2));
         let e2 = Rc::new(Edge::new(&self.v2, &self.v3));
@@ -184,6 +324,18 @@ impl Triangle {
             .unwrap(),
         )
     }
+
+    /**
+     * Same triangle, counterclockwise oriented, as exported. Ghost triangles,
+     * and triangles enclosing no area, are kept as they are.
+     */
+    pub fn ensure_ccw(&self) -> Triangle {
+        let clockwise = orientation(&self.v1, &self.v2, &self.v3) == Orientation::Clockwise;
+        if !self.is_ghost() && clockwise {
+            return Triangle::new(&self.v1, &self.v3, &self.v2);
+        }
+        return Triangle::new(&self.v1, &self.v2, &self.v3);
+    }
 }
 
 #[cfg(test)]
@@ -232,6 +384,19 @@ mod ghost_triangle {
         let t1 = Triangle::new(&v1, &v2, &v3);
         assert!(!t1.is_ghost());
     }
+
+    #[test]
+    fn test_ghost_vertex_of_loop() {
+        let v1 = Rc::new(Vertex::new(0.0, 1.0));
+        let v2 = Rc::new(Vertex::new(2.0, 3.0));
+        let ghost = Rc::new(Vertex::new_ghost_of(2));
+
+        let t1 = Triangle::new(&ghost, &v1, &v2);
+        assert_eq!(t1.ghost_vertex().unwrap().ghost_loop(), Some(2));
+        assert!(Triangle::new(&v1, &v2, &Rc::new(Vertex::new(1.0, 1.0)))
+            .ghost_vertex()
+            .is_none());
+    }
 }
 
 #[cfg(test)]
@@ -267,6 +432,47 @@ mod encircles {
     }
 }
 
+#[cfg(test)]
+mod contains {
+    use super::*;
+
+    #[test]
+    fn sample_1() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 2.0));
+        let triangle = Triangle::new(&v1, &v2, &v3);
+
+        assert_eq!(triangle.contains(&Vertex::new(0.5, 0.5)), Continence::Inside);
+        assert_eq!(triangle.contains(&Vertex::new(1.0, 1.0)), Continence::Boundary);
+        assert_eq!(triangle.contains(&Vertex::new(0.0, 0.0)), Continence::Boundary);
+        assert_eq!(triangle.contains(&Vertex::new(1.5, 1.5)), Continence::Outside);
+
+        let ghost = Rc::new(Vertex::new_ghost());
+        let ghost_triangle = Triangle::new(&v2, &v1, &ghost);
+        assert_eq!(ghost_triangle.contains(&Vertex::new(1.0, -1.0)), Continence::Outside);
+    }
+}
+
+#[cfg(test)]
+mod circumradius {
+    use super::*;
+
+    #[test]
+    fn rectangle_triangle() {
+        /* the circumcenter of a rectangle triangle is its hypotenuse midpoint */
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(3.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 4.0));
+
+        let triangle = Triangle::new(&v1, &v2, &v3);
+        assert!((triangle.circumradius().unwrap() - 2.5).abs() < 1.0E-12);
+
+        let ghost = Rc::new(Vertex::new_ghost());
+        assert_eq!(Triangle::new(&v1, &v2, &ghost).circumradius(), None);
+    }
+} /* end - circumradius tests */
+
 #[cfg(test)]
 mod quality_ratio {
     use super::*;
@@ -355,6 +561,45 @@ mod quality_ratio {
     }
 }
 
+#[cfg(test)]
+mod barycentric {
+    use super::*;
+
+    #[test]
+    fn sample_1() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 2.0));
+        let triangle = Triangle::new(&v1, &v2, &v3);
+
+        assert_eq!(triangle.barycentric(&v1), Some((1.0, 0.0, 0.0)));
+        assert_eq!(triangle.barycentric(&Vertex::new(1.0, 1.0)), Some((0.0, 0.5, 0.5)));
+        assert_eq!(triangle.barycentric(&Vertex::new(2.0, 2.0)), Some((-1.0, 1.0, 1.0)));
+
+        let ghost = Rc::new(Vertex::new_ghost());
+        assert_eq!(Triangle::new(&v2, &v1, &ghost).barycentric(&v1), None);
+        let v4 = Rc::new(Vertex::new(1.0, 0.0));
+        assert_eq!(Triangle::new(&v1, &v4, &v2).barycentric(&v1), None);
+    }
+
+    #[test]
+    fn samples() {
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(3.0, 1.0));
+        let v3 = Rc::new(Vertex::new(1.0, 4.0));
+        let triangle = Triangle::new(&v1, &v2, &v3);
+
+        assert_eq!(triangle.sample(0.0, 0.0), *v1);
+        assert_eq!(triangle.sample(1.0, 0.0), *v2);
+        assert_eq!(triangle.sample(0.0, 1.0), *v3);
+        assert_eq!(triangle.sample(0.5, 0.5), Vertex::new(2.0, 2.5));
+
+        let point = triangle.sample(0.25, 0.5);
+        assert_eq!(triangle.contains(&point), Continence::Inside);
+        assert_eq!(triangle.barycentric(&point), Some((0.25, 0.25, 0.5)));
+    }
+} /* end - barycentric tests */
+
 #[cfg(test)]
 mod center {
     use super::*;
@@ -401,3 +646,96 @@ mod as_polyline {
         assert!(triangle.as_polyline().unwrap().vertices.contains(&v3));
     }
 } /* end - as_polyline tests */
+
+#[cfg(test)]
+mod ensure_ccw {
+    use super::*;
+
+    #[test]
+    fn reorients_clockwise() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+
+        let clockwise = Triangle::new(&v1, &v3, &v2);
+        let ccw = clockwise.ensure_ccw();
+        assert_eq!(
+            orientation(&ccw.v1, &ccw.v2, &ccw.v3),
+            Orientation::Counterclockwise
+        );
+        assert_eq!(ccw, Triangle::new(&v1, &v2, &v3));
+        assert_eq!(Triangle::new(&v2, &v3, &v1).ensure_ccw().v1, v2);
+
+        /* ghost triangles keep their orientation */
+        let ghost = Rc::new(Vertex::new_ghost());
+        assert_eq!(Triangle::new(&v2, &v1, &ghost).ensure_ccw().v1, v2);
+    }
+} /* end - ensure_ccw tests */
+
+#[cfg(test)]
+mod angles {
+    use super::*;
+
+    #[test]
+    fn right_triangle() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+
+        let (a1, a2, a3) = Triangle::new(&v1, &v2, &v3).angles().unwrap();
+        assert!((a1 - std::f64::consts::FRAC_PI_2).abs() < 1.0e-10);
+        assert!((a2 - std::f64::consts::FRAC_PI_4).abs() < 1.0e-10);
+        assert!((a3 - std::f64::consts::FRAC_PI_4).abs() < 1.0e-10);
+
+        /* clockwise triangle has the same inner angles */
+        let t2 = Triangle::new(&v1, &v3, &v2);
+        assert!((t2.min_angle().unwrap() - std::f64::consts::FRAC_PI_4).abs() < 1.0e-10);
+        assert!((t2.max_angle().unwrap() - std::f64::consts::FRAC_PI_2).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn ghost_triangle() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let ghost = Rc::new(Vertex::new_ghost());
+
+        let triangle = Triangle::new(&v1, &v2, &ghost);
+        assert!(triangle.angles().is_none());
+        assert!(triangle.min_angle().is_none());
+    }
+} /* end - angles tests */
+
+#[cfg(test)]
+mod aspect_ratio {
+    use super::*;
+
+    #[test]
+    fn equilateral() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0, 3.0_f64.sqrt()));
+        let triangle = Triangle::new(&v1, &v2, &v3);
+        assert!((triangle.aspect_ratio().unwrap() - 1.0).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn right_isosceles() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+        let triangle = Triangle::new(&v1, &v2, &v3);
+
+        /* r = 1 / (2 + sqrt(2)), l_max = sqrt(2) */
+        let expected = 2.0_f64.sqrt() * (2.0 + 2.0_f64.sqrt()) / (2.0 * 3.0_f64.sqrt());
+        assert!((triangle.aspect_ratio().unwrap() - expected).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn ghost() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let ghost = Rc::new(Vertex::new_ghost());
+        let triangle = Triangle::new(&v1, &v2, &ghost);
+        assert!(triangle.aspect_ratio().is_none());
+    }
+} /* end - aspect_ratio tests */
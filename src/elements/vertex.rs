@@ -8,7 +8,15 @@ use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
+/**
+ * Point of the plane, or ghost vertex standing for the outside of a loop of
+ * border edges, the outer boundary or a hole. Ghost vertices have no
+ * coordinates, their x holds the index of their loop instead, so that each
+ * loop has its own fan of ghost triangles. Ghosts of the same loop are
+ * equal, whatever their references.
+ */
 #[derive(Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vertex {
     pub x: f64,
     pub y: f64,
@@ -34,7 +42,7 @@ impl Hash for Vertex {
 impl PartialEq for Vertex {
     fn eq(&self, other: &Self) -> bool {
         if self.is_ghost && other.is_ghost {
-            return true;
+            return self.x == other.x;
         }
 
         return self.is_ghost == other.is_ghost
@@ -48,7 +56,7 @@ impl Eq for Vertex {}
 impl Ord for Vertex {
     fn cmp(&self, other: &Self) -> Ordering {
         if self.is_ghost && other.is_ghost {
-            return Ordering::Equal;
+            return self.x.partial_cmp(&other.x).unwrap();
         }
 
         if self.x > other.x {
@@ -76,7 +84,10 @@ impl PartialOrd for Vertex {
 impl fmt::Display for Vertex {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.is_ghost {
-            return write!(f, "(ghost)");
+            if self.x == 0.0 {
+                return write!(f, "(ghost)");
+            }
+            return write!(f, "(ghost {})", self.x);
         }
         return write!(f, "({}, {})", self.x, self.y);
     }
@@ -91,14 +102,34 @@ impl Vertex {
         }
     }
 
+    /**
+     * Ghost vertex of the first loop, as of the outer boundary.
+     */
     pub fn new_ghost() -> Vertex {
+        return Vertex::new_ghost_of(0);
+    }
+
+    /**
+     * Ghost vertex of the loop of the index.
+     */
+    pub fn new_ghost_of(ghost_loop: usize) -> Vertex {
         Vertex {
-            x: 0.0,
+            x: ghost_loop as f64,
             y: 0.0,
             is_ghost: true,
         }
     }
 
+    /**
+     * Index of the loop of the ghost vertex. Returns None if solid.
+     */
+    pub fn ghost_loop(&self) -> Option<usize> {
+        if !self.is_ghost {
+            return None;
+        }
+        return Some(self.x as usize);
+    }
+
     pub fn from_coordinates(raw_array: &Vec<f64>) -> Vec<Rc<Vertex>> {
         if raw_array.len() % 2 != 0 {
             panic!("Vec must provide vertices by pair of x,y coordinates.");
@@ -139,6 +170,19 @@ mod ghost_vertex {
         let v = Vertex::new(0.0, 0.0);
         assert!(!v.is_ghost);
     }
+
+    #[test]
+    fn test_ghost_loops() {
+        let g0 = Vertex::new_ghost();
+        let g1 = Vertex::new_ghost_of(1);
+        assert_eq!(g0, Vertex::new_ghost_of(0));
+        assert_ne!(g0, g1);
+        assert!(g0 < g1);
+        assert_eq!(g1.ghost_loop(), Some(1));
+        assert_eq!(Vertex::new(1.0, 0.0).ghost_loop(), None);
+        assert_ne!(g1, Vertex::new(1.0, 0.0));
+        assert_eq!(format!("{} {}", g0, g1), "(ghost) (ghost 1)");
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,125 @@
+use crate::elements::vertex::*;
+use crate::properties::distance::*;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/**
+ * Set of vertices keyed by their coordinates snapped to a grid of tolerance
+ * sized cells, so that coincident vertices, which do not hash alike, are
+ * found and reused instead of being duplicated with separate identities.
+ */
+pub struct VertexPool {
+    pub tolerance: f64,
+    cells: HashMap<(i64, i64), Vec<Rc<Vertex>>>,
+}
+
+impl VertexPool {
+    pub fn new(tolerance: f64) -> Self {
+        Self {
+            tolerance,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn from_vertices<'a, I>(vertices: I, tolerance: f64) -> Self
+    where
+        I: IntoIterator<Item = &'a Rc<Vertex>>,
+    {
+        let mut pool = Self::new(tolerance);
+        for vertex in vertices.into_iter() {
+            pool.insert(vertex);
+        }
+        return pool;
+    }
+
+    pub fn insert(&mut self, vertex: &Rc<Vertex>) {
+        if vertex.is_ghost {
+            return;
+        }
+
+        self.cells
+            .entry(self.cell(vertex))
+            .or_insert(Vec::new())
+            .push(Rc::clone(vertex));
+    }
+
+    /**
+     * Pooled vertex coincident to the vertex, that is, not farther than
+     * tolerance. Neighbour cells are searched as well, as coincident
+     * vertices may be snapped to different cells.
+     */
+    pub fn find(&self, vertex: &Vertex) -> Option<Rc<Vertex>> {
+        if vertex.is_ghost {
+            return None;
+        }
+
+        let (i, j) = self.cell(vertex);
+        for di in -1..=1 {
+            for dj in -1..=1 {
+                if let Some(pooled) = self.cells.get(&(i + di, j + dj)) {
+                    let coincident = pooled
+                        .iter()
+                        .find(|v| ***v == *vertex || distance(v, vertex) <= self.tolerance);
+                    if let Some(coincident) = coincident {
+                        return Some(Rc::clone(coincident));
+                    }
+                }
+            }
+        }
+
+        return None;
+    }
+
+    /**
+     * Returns the pooled vertex coincident to the vertex, or pools the
+     * vertex itself.
+     */
+    pub fn get_or_insert(&mut self, vertex: Rc<Vertex>) -> Rc<Vertex> {
+        if let Some(pooled) = self.find(&vertex) {
+            return pooled;
+        }
+
+        self.insert(&vertex);
+        return vertex;
+    }
+
+    fn cell(&self, vertex: &Vertex) -> (i64, i64) {
+        let size = self.tolerance.max(std::f64::MIN_POSITIVE);
+        return (
+            (vertex.x / size).floor() as i64,
+            (vertex.y / size).floor() as i64,
+        );
+    }
+} /* end - VertexPool */
+
+#[cfg(test)]
+mod vertex_pool {
+    use super::*;
+
+    #[test]
+    fn find_coincident() {
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(2.0, 1.0));
+        let pool = VertexPool::from_vertices(vec![&v1, &v2], 1.0E-12);
+
+        let found = pool.find(&Vertex::new(1.0 + 1.0E-13, 1.0)).unwrap();
+        assert!(Rc::ptr_eq(&found, &v1));
+
+        /* coincident vertex snapped to a neighbour cell */
+        let found = pool.find(&Vertex::new(2.0 - 1.0E-13, 1.0)).unwrap();
+        assert!(Rc::ptr_eq(&found, &v2));
+
+        assert!(pool.find(&Vertex::new(1.5, 1.0)).is_none());
+    }
+
+    #[test]
+    fn get_or_insert() {
+        let mut pool = VertexPool::new(1.0E-12);
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(1.0, 1.0));
+
+        assert!(Rc::ptr_eq(&pool.get_or_insert(Rc::clone(&v1)), &v1));
+        assert!(Rc::ptr_eq(&pool.get_or_insert(Rc::clone(&v2)), &v1));
+    }
+} /* end - vertex_pool tests */
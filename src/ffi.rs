@@ -0,0 +1,347 @@
+use crate::elements::{edge::*, polyline::*, vertex::*};
+use crate::planar::{
+    refine_params::*, triangulation_data::*, triangulation_error::*, triangulator::*,
+};
+
+use std::collections::HashSet;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::rc::Rc;
+
+/* Status codes returned by nlsn_triangulate and nlsn_refine */
+pub const NLSN_OK: i32 = 0;
+pub const NLSN_INVALID_ARGUMENT: i32 = 1;
+pub const NLSN_OUTSIDE_BOUNDARY: i32 = 2;
+pub const NLSN_SELF_INTERSECTING: i32 = 3;
+pub const NLSN_DEGENERATE_INPUT: i32 = 4;
+pub const NLSN_UNRECOVERABLE_CONSTRAINT: i32 = 5;
+pub const NLSN_INTERNAL_ERROR: i32 = 6;
+pub const NLSN_NON_TERMINATING_REFINEMENT: i32 = 7;
+
+/**
+ * Input geometry, in flat arrays of doubles, as Triangle's triangulateio:
+ *  - boundary: boundary_count (x, y) vertices of the closed boundary.
+ *  - holes: closed hole polygons, concatenated as (x, y) vertices, with
+ * the vertex count of each of the hole_count holes in hole_sizes.
+ *  - vertices: vertex_count (x, y) vertices to be inserted.
+ *  - segments: segment_count (x1, y1, x2, y2) constraint segments.
+ * Arrays may be null when their count is zero.
+ */
+#[repr(C)]
+pub struct NlsnInput {
+    pub boundary: *const f64,
+    pub boundary_count: i32,
+    pub holes: *const f64,
+    pub hole_sizes: *const i32,
+    pub hole_count: i32,
+    pub vertices: *const f64,
+    pub vertex_count: i32,
+    pub segments: *const f64,
+    pub segment_count: i32,
+}
+
+/**
+ * Output mesh: point_count (x, y) points and triangle_count triangles, as
+ * three zero based point indices each. Arrays are owned by the library and
+ * released by nlsn_free_mesh.
+ */
+#[repr(C)]
+pub struct NlsnMesh {
+    pub points: *mut f64,
+    pub point_count: i32,
+    pub triangles: *mut i32,
+    pub triangle_count: i32,
+}
+
+/**
+ * Constrained Delaunay triangulation of the input. Writes the mesh to
+ * output and returns NLSN_OK, or returns an error code leaving output
+ * untouched.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn nlsn_triangulate(input: *const NlsnInput, output: *mut NlsnMesh) -> i32 {
+    return run(input, None, output);
+}
+
+/**
+ * Triangulation of the input refined until triangles meet the radius-edge
+ * ratio, and the area if max_area is positive. Ratios not above 1 / sqrt(3)
+ * cannot be met and are invalid arguments.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn nlsn_refine(
+    input: *const NlsnInput,
+    max_area: f64,
+    quality_ratio: f64,
+    output: *mut NlsnMesh,
+) -> i32 {
+    let params = RefineParams {
+        max_area: if max_area > 0.0 { Some(max_area) } else { None },
+        quality_ratio,
+        max_edge_length: None,
+        regions: Vec::new(),
+    };
+    return run(input, Some(params), output);
+}
+
+/**
+ * Releases the arrays of a mesh written by nlsn_triangulate or nlsn_refine,
+ * resetting it to an empty mesh. Freeing an empty mesh does nothing.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn nlsn_free_mesh(mesh: *mut NlsnMesh) {
+    if mesh.is_null() {
+        return;
+    }
+
+    let mesh = &mut *mesh;
+    if !mesh.points.is_null() {
+        let length = 2 * mesh.point_count as usize;
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            mesh.points,
+            length,
+        )));
+    }
+    if !mesh.triangles.is_null() {
+        let length = 3 * mesh.triangle_count as usize;
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            mesh.triangles,
+            length,
+        )));
+    }
+    *mesh = NlsnMesh {
+        points: ptr::null_mut(),
+        point_count: 0,
+        triangles: ptr::null_mut(),
+        triangle_count: 0,
+    };
+}
+
+unsafe fn run(input: *const NlsnInput, params: Option<RefineParams>, output: *mut NlsnMesh) -> i32 {
+    if input.is_null() || output.is_null() {
+        return NLSN_INVALID_ARGUMENT;
+    }
+    let params = match params.map(RefineParams::validated).transpose() {
+        Ok(params) => params,
+        Err(_) => return NLSN_INVALID_ARGUMENT,
+    };
+
+    /* panics must not unwind into the caller */
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut triangulator = triangulator(&*input)?;
+        triangulator
+            .try_triangulate()
+            .map_err(|error| error_code(&error))?;
+        if let Some(params) = params {
+            triangulator
+                .try_refine(params)
+                .map_err(|error| error_code(&error))?;
+        }
+        return Ok(TriangulationData::from_triangulation(
+            &triangulator.triangulation.borrow(),
+        ));
+    }));
+
+    let data = match result {
+        Ok(Ok(data)) => data,
+        Ok(Err(code)) => return code,
+        Err(_) => return NLSN_INTERNAL_ERROR,
+    };
+
+    let point_count = data.coordinates.len() / 2;
+    let triangle_count = data.triangles.len() / 3;
+    let triangles: Vec<i32> = data.triangles.iter().map(|&index| index as i32).collect();
+    *output = NlsnMesh {
+        points: Box::into_raw(data.coordinates.into_boxed_slice()) as *mut f64,
+        point_count: point_count as i32,
+        triangles: Box::into_raw(triangles.into_boxed_slice()) as *mut i32,
+        triangle_count: triangle_count as i32,
+    };
+    return NLSN_OK;
+}
+
+unsafe fn triangulator(input: &NlsnInput) -> Result<Triangulator, i32> {
+    let boundary = slice(input.boundary, input.boundary_count, 2)?;
+    let hole_sizes = slice(input.hole_sizes, input.hole_count, 1)?;
+    if hole_sizes.iter().any(|&size| size < 0) {
+        return Err(NLSN_INVALID_ARGUMENT);
+    }
+    let hole_vertices: i32 = hole_sizes.iter().sum();
+    let holes = slice(input.holes, hole_vertices, 2)?;
+    let vertices = slice(input.vertices, input.vertex_count, 2)?;
+    let segments = slice(input.segments, input.segment_count, 4)?;
+
+    let mut triangulator = Triangulator::new(&polygon(boundary)?);
+
+    let mut start: usize = 0;
+    for &size in hole_sizes.iter() {
+        let end = start + 2 * size as usize;
+        let hole = polygon(&holes[start..end])?;
+        triangulator
+            .insert_hole(&hole)
+            .map_err(|error| error_code(&error))?;
+        start = end;
+    }
+
+    let vertices: HashSet<Rc<Vertex>> = vertices
+        .chunks(2)
+        .map(|xy| Rc::new(Vertex::new(xy[0], xy[1])))
+        .collect();
+    triangulator
+        .insert_vertices(&vertices)
+        .map_err(|error| error_code(&error))?;
+
+    let segments: HashSet<Rc<Edge>> = segments
+        .chunks(4)
+        .map(|xy| {
+            let v1 = Rc::new(Vertex::new(xy[0], xy[1]));
+            let v2 = Rc::new(Vertex::new(xy[2], xy[3]));
+            return Rc::new(Edge::new(&v1, &v2));
+        })
+        .collect();
+    triangulator
+        .insert_segments(&segments)
+        .map_err(|error| error_code(&error))?;
+
+    return Ok(triangulator);
+}
+
+/**
+ * Array of count items of the given width, empty for a null pointer when
+ * count is zero.
+ */
+unsafe fn slice<'a, T>(pointer: *const T, count: i32, width: usize) -> Result<&'a [T], i32> {
+    if count < 0 || (count > 0 && pointer.is_null()) {
+        return Err(NLSN_INVALID_ARGUMENT);
+    }
+    if count == 0 {
+        return Ok(&[]);
+    }
+    return Ok(std::slice::from_raw_parts(pointer, width * count as usize));
+}
+
+fn polygon(coordinates: &[f64]) -> Result<Rc<Polyline>, i32> {
+    let vertices: Vec<Rc<Vertex>> = coordinates
+        .chunks(2)
+        .map(|xy| Rc::new(Vertex::new(xy[0], xy[1])))
+        .collect();
+    match Polyline::new_closed(vertices) {
+        Some(polyline) => return Ok(Rc::new(polyline)),
+        None => return Err(NLSN_DEGENERATE_INPUT),
+    }
+}
+
+fn error_code(error: &TriangulationError) -> i32 {
+    match error {
+        TriangulationError::OutsideBoundary { .. } => return NLSN_OUTSIDE_BOUNDARY,
+        TriangulationError::SelfIntersectingPolyline(_) => return NLSN_SELF_INTERSECTING,
+        TriangulationError::DegenerateInput(_) => return NLSN_DEGENERATE_INPUT,
+        TriangulationError::UnrecoverableConstraint { .. } => {
+            return NLSN_UNRECOVERABLE_CONSTRAINT;
+        }
+        TriangulationError::NonTerminatingRefinement { .. } => {
+            return NLSN_NON_TERMINATING_REFINEMENT;
+        }
+        _ => return NLSN_INTERNAL_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod ffi {
+    use super::*;
+
+    fn empty_mesh() -> NlsnMesh {
+        return NlsnMesh {
+            points: ptr::null_mut(),
+            point_count: 0,
+            triangles: ptr::null_mut(),
+            triangle_count: 0,
+        };
+    }
+
+    fn input(boundary: &[f64], holes: &[f64], hole_sizes: &[i32]) -> NlsnInput {
+        return NlsnInput {
+            boundary: boundary.as_ptr(),
+            boundary_count: (boundary.len() / 2) as i32,
+            holes: holes.as_ptr(),
+            hole_sizes: hole_sizes.as_ptr(),
+            hole_count: hole_sizes.len() as i32,
+            vertices: ptr::null(),
+            vertex_count: 0,
+            segments: ptr::null(),
+            segment_count: 0,
+        };
+    }
+
+    #[test]
+    fn triangulate_and_refine() {
+        let boundary = [0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0];
+        let hole = [1.0, 1.0, 2.0, 1.0, 2.0, 2.0, 1.0, 2.0];
+        let input = input(&boundary, &hole, &[4]);
+
+        let mut mesh = empty_mesh();
+        assert_eq!(unsafe { nlsn_triangulate(&input, &mut mesh) }, NLSN_OK);
+        assert_eq!((mesh.point_count, mesh.triangle_count), (8, 8));
+        let triangles = unsafe { std::slice::from_raw_parts(mesh.triangles, 24) };
+        assert!(triangles.iter().all(|&index| 0 <= index && index < 8));
+
+        let mut refined = empty_mesh();
+        let status = unsafe { nlsn_refine(&input, 0.5, 2.0_f64.sqrt(), &mut refined) };
+        assert_eq!(status, NLSN_OK);
+        assert!(refined.triangle_count > mesh.triangle_count);
+
+        unsafe {
+            nlsn_free_mesh(&mut mesh);
+            nlsn_free_mesh(&mut refined);
+            nlsn_free_mesh(&mut refined);
+        }
+        assert!(mesh.points.is_null() && mesh.triangles.is_null());
+        assert_eq!(mesh.triangle_count, 0);
+    }
+
+    #[test]
+    fn constraints() {
+        let boundary = [0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0];
+        let vertices = [1.0, 3.0];
+        let segments = [1.0, 1.0, 3.0, 1.0];
+        let input = NlsnInput {
+            vertices: vertices.as_ptr(),
+            vertex_count: 1,
+            segments: segments.as_ptr(),
+            segment_count: 1,
+            ..input(&boundary, &[], &[])
+        };
+
+        let mut mesh = empty_mesh();
+        assert_eq!(unsafe { nlsn_triangulate(&input, &mut mesh) }, NLSN_OK);
+        assert_eq!(mesh.point_count, 7);
+        unsafe { nlsn_free_mesh(&mut mesh) };
+    }
+
+    #[test]
+    fn invalid_input() {
+        let boundary = [0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0];
+        let mut mesh = empty_mesh();
+
+        let status = unsafe { nlsn_triangulate(ptr::null(), &mut mesh) };
+        assert_eq!(status, NLSN_INVALID_ARGUMENT);
+
+        let mut missing_holes = input(&boundary, &[], &[]);
+        missing_holes.hole_sizes = ptr::null();
+        missing_holes.hole_count = 1;
+        let status = unsafe { nlsn_triangulate(&missing_holes, &mut mesh) };
+        assert_eq!(status, NLSN_INVALID_ARGUMENT);
+
+        let status = unsafe { nlsn_triangulate(&input(&boundary[..4], &[], &[]), &mut mesh) };
+        assert_eq!(status, NLSN_DEGENERATE_INPUT);
+
+        let status = unsafe { nlsn_refine(&input(&boundary, &[], &[]), 0.0, 0.0, &mut mesh) };
+        assert_eq!(status, NLSN_INVALID_ARGUMENT);
+
+        let outside = [5.0, 5.0, 6.0, 5.0, 6.0, 6.0, 5.0, 6.0];
+        let status = unsafe { nlsn_triangulate(&input(&boundary, &outside, &[4]), &mut mesh) };
+        assert_ne!(status, NLSN_OK);
+
+        assert!(mesh.points.is_null());
+    }
+} /* end - ffi tests */
@@ -0,0 +1,202 @@
+use crate::glium_interface::vertex::ColoredVertex;
+
+use nlsn_delaunay::elements::triangle::Triangle;
+use nlsn_delaunay::planar::triangulation::Triangulation;
+
+/* Color bar of the legend, at the right of the window */
+const LEGEND_LEFT: f32 = 0.9;
+const LEGEND_RIGHT: f32 = 0.95;
+const LEGEND_BOTTOM: f32 = -0.9;
+const LEGEND_TOP: f32 = 0.9;
+const LEGEND_STEPS: usize = 32;
+
+/**
+ * Triangle measure mapped to colors. Areas are mapped in logarithmic scale,
+ * as refinement spreads them over orders of magnitude.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Metric {
+    Quality,
+    Area,
+}
+
+impl Metric {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "quality" => return Some(Metric::Quality),
+            "area" => return Some(Metric::Area),
+            _ => return None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Metric::Quality => return "quality ratio",
+            Metric::Area => return "area",
+        }
+    }
+
+    pub fn value(&self, triangle: &Triangle) -> Option<f64> {
+        match self {
+            Metric::Quality => return triangle.quality(),
+            Metric::Area => return triangle.area(),
+        }
+    }
+
+    fn scaled(&self, value: f64) -> f64 {
+        match self {
+            Metric::Quality => return value,
+            Metric::Area => return value.abs().max(std::f64::MIN_POSITIVE).log10(),
+        }
+    }
+}
+
+/**
+ * Maps t in [0, 1] from blue, through cyan, green and yellow, to red.
+ */
+pub fn color(t: f64) -> [f32; 3] {
+    let t = t.max(0.0).min(1.0) as f32;
+    let stops: [[f32; 3]; 5] = [
+        [0.0, 0.0, 1.0],
+        [0.0, 1.0, 1.0],
+        [0.0, 1.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [1.0, 0.0, 0.0],
+    ];
+    let position = t * (stops.len() - 1) as f32;
+    let index = (position.floor() as usize).min(stops.len() - 2);
+    let fraction = position - index as f32;
+
+    let mut mixed = [0.0; 3];
+    for channel in 0..3 {
+        mixed[channel] =
+            stops[index][channel] + fraction * (stops[index + 1][channel] - stops[index][channel]);
+    }
+    return mixed;
+}
+
+/**
+ * Solid triangles colored by the metric, from the lowest value in blue to
+ * the highest in red, along with the range of values.
+ */
+pub fn colored_triangles(
+    triangulation: &Triangulation,
+    metric: Metric,
+) -> (Vec<ColoredVertex>, Option<(f64, f64)>) {
+    let valued: Vec<(&Triangle, f64)> = triangulation
+        .triangles
+        .iter()
+        .filter_map(|t| metric.value(t).map(|value| (t.as_ref(), value)))
+        .collect();
+    if valued.is_empty() {
+        return (Vec::new(), None);
+    }
+
+    let min = valued
+        .iter()
+        .map(|(_, v)| *v)
+        .fold(std::f64::INFINITY, f64::min);
+    let max = valued
+        .iter()
+        .map(|(_, v)| *v)
+        .fold(std::f64::NEG_INFINITY, f64::max);
+    let (scaled_min, scaled_max) = (metric.scaled(min), metric.scaled(max));
+
+    let mut output: Vec<ColoredVertex> = Vec::new();
+    for (triangle, value) in valued.iter() {
+        let t = if scaled_max > scaled_min {
+            (metric.scaled(*value) - scaled_min) / (scaled_max - scaled_min)
+        } else {
+            0.0
+        };
+        for vertex in vec![&triangle.v1, &triangle.v2, &triangle.v3] {
+            output.push(ColoredVertex {
+                position: [vertex.x as f32, vertex.y as f32],
+                color: color(t),
+            });
+        }
+    }
+
+    return (output, Some((min, max)));
+}
+
+/**
+ * Vertical color bar, as triangles, from the lowest value at the bottom to
+ * the highest at the top.
+ */
+pub fn legend() -> Vec<ColoredVertex> {
+    let mut output: Vec<ColoredVertex> = Vec::new();
+    let step = (LEGEND_TOP - LEGEND_BOTTOM) / LEGEND_STEPS as f32;
+
+    for index in 0..LEGEND_STEPS {
+        let bottom = LEGEND_BOTTOM + step * index as f32;
+        let top = bottom + step;
+        let color = color((index as f64 + 0.5) / LEGEND_STEPS as f64);
+        for (x, y) in vec![
+            (LEGEND_LEFT, bottom),
+            (LEGEND_RIGHT, bottom),
+            (LEGEND_RIGHT, top),
+            (LEGEND_LEFT, bottom),
+            (LEGEND_RIGHT, top),
+            (LEGEND_LEFT, top),
+        ] {
+            output.push(ColoredVertex {
+                position: [x, y],
+                color,
+            });
+        }
+    }
+
+    return output;
+}
+
+/**
+ * Describes the legend range, as shown in the window title.
+ */
+pub fn legend_title(metric: Metric, range: Option<(f64, f64)>) -> String {
+    match range {
+        Some((min, max)) => {
+            return format!("{}: {:.4e} (blue) to {:.4e} (red)", metric.name(), min, max);
+        }
+        None => return format!("{}: no triangles", metric.name()),
+    }
+}
+
+#[cfg(test)]
+mod colormap {
+    use super::*;
+    use nlsn_delaunay::elements::vertex::Vertex;
+    use std::rc::Rc;
+
+    #[test]
+    fn color_stops() {
+        assert_eq!(color(0.0), [0.0, 0.0, 1.0]);
+        assert_eq!(color(0.5), [0.0, 1.0, 0.0]);
+        assert_eq!(color(1.0), [1.0, 0.0, 0.0]);
+        assert_eq!(color(2.0), [1.0, 0.0, 0.0]);
+        assert_eq!(color(0.125), [0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn colors_by_area() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+        let v4 = Rc::new(Vertex::new(10.0, 10.0));
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v1, &v2, &v3)));
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v2, &v4, &v3)));
+
+        let (vertices, range) = colored_triangles(&triangulation, Metric::Area);
+        assert_eq!(vertices.len(), 6);
+        assert_eq!(range, Some((0.5, 9.5)));
+        let colors: Vec<[f32; 3]> = vertices.iter().map(|v| v.color).collect();
+        assert!(colors.contains(&[0.0, 0.0, 1.0]) && colors.contains(&[1.0, 0.0, 0.0]));
+
+        assert_eq!(
+            legend_title(Metric::Area, range),
+            "area: 5.0000e-1 (blue) to 9.5000e0 (red)"
+        );
+        assert_eq!(legend().len(), 6 * LEGEND_STEPS);
+    }
+} /* end - colormap tests */
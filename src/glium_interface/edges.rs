@@ -1,30 +1,37 @@
 #![macro_use]
 extern crate glium;
-use crate::glium_interface::vertex;
+use crate::glium_interface::{vertex, view::View};
+
+use nlsn_delaunay::elements::triangle::Triangle;
 
 use glium::{glutin, Display, Program, Surface};
+use std::rc::Rc;
 
 /**
- *  Creates default triangle drawing program
+ *  Creates default edges drawing program, colored by uniform
  */
-fn get_program(display: &Display) -> Program {
+pub fn get_program(display: &Display) -> Program {
     let vertex_shader_src = r#"
         #version 140
 
         in vec2 position;
 
+        uniform mat4 view;
+
         void main() {
-            gl_Position = vec4(position, 0.0, 1.0);
+            gl_Position = view * vec4(position, 0.0, 1.0);
         }
     "#;
 
     let fragment_shader_src = r#"
         #version 140
 
+        uniform vec4 line_color;
+
         out vec4 color;
 
         void main() {
-            color = vec4(0.0, 0.0, 0.0, 1.0);
+            color = line_color;
         }
     "#;
 
@@ -34,31 +41,71 @@ fn get_program(display: &Display) -> Program {
     return program;
 }
 
+/* Mesh edges color */
+pub const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+/* Input geometry overlay color, contrasting the mesh */
+pub const RED: [f32; 4] = [0.9, 0.1, 0.1, 1.0];
+
 pub fn draw(
     (display, event_loop): (Display, glutin::event_loop::EventLoop<()>),
     shape: Vec<vertex::Vertex>,
+    triangles: Vec<Rc<Triangle>>,
+    line_width: f32,
+) {
+    draw_layers(
+        (display, event_loop),
+        vec![(shape, BLACK)],
+        triangles,
+        line_width,
+    );
+}
+
+/**
+ * Draws every layer of edges with its color, in order, so that later
+ * layers are drawn over the former ones. The mouse wheel zooms, dragging
+ * pans, and clicks print the picked vertex or triangle to stdout.
+ */
+pub fn draw_layers(
+    (display, event_loop): (Display, glutin::event_loop::EventLoop<()>),
+    layers: Vec<(Vec<vertex::Vertex>, [f32; 4])>,
+    triangles: Vec<Rc<Triangle>>,
     line_width: f32,
 ) {
-    let vertex_buffer = glium::VertexBuffer::new(&display, &shape).unwrap();
+    let mut view = View::fit(
+        &layers
+            .iter()
+            .map(|(shape, _)| shape.iter().map(|v| v.position))
+            .flatten()
+            .collect(),
+    );
+    let layers: Vec<(glium::VertexBuffer<vertex::Vertex>, [f32; 4])> = layers
+        .iter()
+        .map(|(shape, color)| (glium::VertexBuffer::new(&display, shape).unwrap(), *color))
+        .collect();
     let indices = glium::index::NoIndices(glium::index::PrimitiveType::LinesList);
 
     let program = get_program(&display);
 
     event_loop.run(move |ev, _, control_flow| {
         let mut target = display.draw();
+        let (width, height) = target.get_dimensions();
+        view.resize(width as f64, height as f64);
         target.clear_color(1.0, 1.0, 1.0, 1.0);
-        target
-            .draw(
-                &vertex_buffer,
-                &indices,
-                &program,
-                &glium::uniforms::EmptyUniforms,
-                &glium::DrawParameters {
-                    line_width: Some(line_width),
-                    ..Default::default()
-                },
-            )
-            .unwrap();
+        for (vertex_buffer, color) in layers.iter() {
+            target
+                .draw(
+                    vertex_buffer,
+                    &indices,
+                    &program,
+                    &glium::uniform! { line_color: *color, view: view.matrix() },
+                    &glium::DrawParameters {
+                        line_width: Some(line_width),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+        }
         target.finish().unwrap();
 
         let next_frame_time =
@@ -67,13 +114,20 @@ pub fn draw(
         *control_flow = glutin::event_loop::ControlFlow::WaitUntil(next_frame_time);
 
         match ev {
-            glutin::event::Event::WindowEvent { event, .. } => match event {
-                glutin::event::WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit;
-                    return;
+            glutin::event::Event::WindowEvent { event, .. } => {
+                if let Some(point) = view.handle(&event) {
+                    if let Some(description) = view.pick(&triangles, point) {
+                        println!("{}", description);
+                    }
+                }
+                match event {
+                    glutin::event::WindowEvent::CloseRequested => {
+                        *control_flow = glutin::event_loop::ControlFlow::Exit;
+                        return;
+                    }
+                    _ => return,
                 }
-                _ => return,
-            },
+            }
             _ => (),
         }
     });
@@ -1,4 +1,6 @@
+pub mod colormap;
 pub mod display;
 pub mod triangles;
 pub mod edges;
 pub mod vertex;
+pub mod view;
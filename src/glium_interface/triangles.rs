@@ -1,30 +1,41 @@
 #![macro_use]
 extern crate glium;
-use crate::glium_interface::vertex;
+use crate::glium_interface::{colormap, edges, vertex, view::View};
+
+use nlsn_delaunay::elements::triangle::Triangle;
 
 use glium::{glutin, Display, Program, Surface};
+use std::rc::Rc;
 
 /**
- *  Creates default triangle drawing program
+ *  Creates filled triangles drawing program, colored per vertex
  */
-fn get_program(display: &Display) -> Program {
+fn get_colored_program(display: &Display) -> Program {
     let vertex_shader_src = r#"
         #version 140
 
         in vec2 position;
+        in vec3 color;
+
+        uniform mat4 view;
+
+        out vec3 vertex_color;
 
         void main() {
-            gl_Position = vec4(position, 0.0, 1.0);
+            vertex_color = color;
+            gl_Position = view * vec4(position, 0.0, 1.0);
         }
     "#;
 
     let fragment_shader_src = r#"
         #version 140
 
+        in vec3 vertex_color;
+
         out vec4 color;
 
         void main() {
-            color = vec4(1.0, 0.0, 0.0, 1.0);
+            color = vec4(vertex_color, 1.0);
         }
     "#;
 
@@ -34,29 +45,95 @@ fn get_program(display: &Display) -> Program {
     return program;
 }
 
-pub fn draw(
+/**
+ * Draws the triangles filled, colored by each metric in turn, with the
+ * legend color bar, and the layers of edges over them. W toggles between
+ * filled and wireframe rendering, which draws every layer of edges alone.
+ * M switches the metric, whose range is shown in the window title. The
+ * mouse wheel zooms, dragging pans, and clicks print the picked vertex or
+ * triangle to stdout.
+ */
+pub fn draw_filled(
     (display, event_loop): (Display, glutin::event_loop::EventLoop<()>),
-    shape: Vec<vertex::Vertex>,
+    fills: Vec<(Vec<vertex::ColoredVertex>, String)>,
+    layers: Vec<(Vec<vertex::Vertex>, [f32; 4])>,
+    triangles: Vec<Rc<Triangle>>,
+    line_width: f32,
 ) {
-    let vertex_buffer = glium::VertexBuffer::new(&display, &shape).unwrap();
-    let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+    let mut view = View::fit(
+        &layers
+            .iter()
+            .map(|(shape, _)| shape.iter().map(|v| v.position))
+            .flatten()
+            .collect(),
+    );
+    /* the legend stays put, whatever the view */
+    let identity: [[f32; 4]; 4] = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ];
+    let fills: Vec<(glium::VertexBuffer<vertex::ColoredVertex>, String)> = fills
+        .iter()
+        .map(|(shape, title)| {
+            (
+                glium::VertexBuffer::new(&display, shape).unwrap(),
+                title.clone(),
+            )
+        })
+        .collect();
+    let legend = glium::VertexBuffer::new(&display, &colormap::legend()).unwrap();
+    let layers: Vec<(glium::VertexBuffer<vertex::Vertex>, [f32; 4])> = layers
+        .iter()
+        .map(|(shape, color)| (glium::VertexBuffer::new(&display, shape).unwrap(), *color))
+        .collect();
+    let triangle_indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+    let line_indices = glium::index::NoIndices(glium::index::PrimitiveType::LinesList);
 
-    let program = get_program(&display);
+    let colored_program = get_colored_program(&display);
+    let edges_program = edges::get_program(&display);
+
+    let mut wireframe = false;
+    let mut fill_index: usize = 0;
+    if let Some((_, title)) = fills.get(fill_index) {
+        display.gl_window().window().set_title(title);
+    }
 
     event_loop.run(move |ev, _, control_flow| {
         let mut target = display.draw();
+        let (width, height) = target.get_dimensions();
+        view.resize(width as f64, height as f64);
         target.clear_color(1.0, 1.0, 1.0, 1.0);
-        target
-            .draw(
-                &vertex_buffer,
-                &indices,
-                &program,
-                &glium::uniforms::EmptyUniforms,
-                &glium::DrawParameters {
-                    ..Default::default()
-                },
-            )
-            .unwrap();
+        if !wireframe {
+            if let Some((vertex_buffer, _)) = fills.get(fill_index) {
+                for (buffer, matrix) in vec![(vertex_buffer, view.matrix()), (&legend, identity)] {
+                    target
+                        .draw(
+                            buffer,
+                            &triangle_indices,
+                            &colored_program,
+                            &glium::uniform! { view: matrix },
+                            &Default::default(),
+                        )
+                        .unwrap();
+                }
+            }
+        }
+        for (vertex_buffer, color) in layers.iter() {
+            target
+                .draw(
+                    vertex_buffer,
+                    &line_indices,
+                    &edges_program,
+                    &glium::uniform! { line_color: *color, view: view.matrix() },
+                    &glium::DrawParameters {
+                        line_width: Some(line_width),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+        }
         target.finish().unwrap();
 
         let next_frame_time =
@@ -65,13 +142,34 @@ pub fn draw(
         *control_flow = glutin::event_loop::ControlFlow::WaitUntil(next_frame_time);
 
         match ev {
-            glutin::event::Event::WindowEvent { event, .. } => match event {
-                glutin::event::WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit;
-                    return;
+            glutin::event::Event::WindowEvent { event, .. } => {
+                if let Some(point) = view.handle(&event) {
+                    if let Some(description) = view.pick(&triangles, point) {
+                        println!("{}", description);
+                    }
+                }
+                match event {
+                    glutin::event::WindowEvent::CloseRequested => {
+                        *control_flow = glutin::event_loop::ControlFlow::Exit;
+                        return;
+                    }
+                    glutin::event::WindowEvent::KeyboardInput { input, .. } => {
+                        if input.state != glutin::event::ElementState::Pressed {
+                            return;
+                        }
+                        match input.virtual_keycode {
+                            Some(glutin::event::VirtualKeyCode::W) => wireframe = !wireframe,
+                            Some(glutin::event::VirtualKeyCode::M) if !fills.is_empty() => {
+                                fill_index = (fill_index + 1) % fills.len();
+                                let title = &fills[fill_index].1;
+                                display.gl_window().window().set_title(title);
+                            }
+                            _ => return,
+                        }
+                    }
+                    _ => return,
                 }
-                _ => return,
-            },
+            }
             _ => (),
         }
     });
@@ -1,6 +1,9 @@
 #![macro_use]
 extern crate glium;
 
+use crate::json_serializar::models::{input::TriangulationInput, point::Point};
+use crate::triangulator_interface::interpreter::{parse_shape, segments_parser};
+
 use nlsn_delaunay::elements::edge::Edge;
 use nlsn_delaunay::planar::triangulation::Triangulation;
 
@@ -14,37 +17,24 @@ pub struct Vertex {
 
 glium::implement_vertex!(Vertex, position);
 
-impl Vertex {
-    pub fn from_coordinates(coordinates: Vec<f32>) -> Vec<Self> {
-        let mut output: Vec<Self> = Vec::new();
+/**
+ * Vertex of filled triangles, colored per vertex.
+ */
+#[derive(Copy, Clone)]
+pub struct ColoredVertex {
+    pub position: [f32; 2],
+    pub color: [f32; 3],
+}
 
-        for index in (0..coordinates.len()).step_by(2) {
-            let x = coordinates.get(index).unwrap();
-            let y = coordinates.get(index + 1).unwrap();
-            output.push(Vertex { position: [*x, *y] });
-        }
+glium::implement_vertex!(ColoredVertex, position, color);
 
-        return output;
-    }
-
-    pub fn triangles_from_triangulation(triangulation: &Triangulation) -> Vec<Self> {
-        triangulation
-            .triangles
-            .iter()
-            .filter(|t| !t.is_ghost())
-            .map(|t| vec![Rc::clone(&t.v1), Rc::clone(&t.v2), Rc::clone(&t.v3)])
-            .flatten()
-            .map(|v| Vertex {
-                position: [v.x as f32, v.y as f32],
-            })
-            .collect()
-    }
+/* Circles are drawn finer than any reasonable discretization */
+const CIRCLE_RESOLUTION: usize = 512;
 
+impl Vertex {
     pub fn edges_from_triangulation(triangulation: &Triangulation) -> Vec<Self> {
         let mut aux_list: Vec<Rc<Edge>> = triangulation
-            .triangles
-            .iter()
-            .filter(|t| !t.is_ghost())
+            .solid_triangles()
             .map(|t| {
                 let (e1, e2, e3) = t.inner_edges();
                 vec![e1, e2, e3]
@@ -68,4 +58,85 @@ impl Vertex {
             })
             .collect()
     }
+
+    /**
+     * Edges of the input geometry, as described by the actions, before any
+     * discretization or triangulation. Circles are drawn with their exact
+     * center and radius, so that coarse discretizations become visible,
+     * while the remaining shapes are drawn as discretized.
+     */
+    pub fn edges_from_input(input: &TriangulationInput) -> Vec<Self> {
+        let mut output: Vec<Self> = Vec::new();
+
+        for action in input.actions.iter() {
+            match action.geometry.as_str() {
+                "polyline" if action.intent == "constraint" => {
+                    for (p1, p2) in action.points.iter().zip(action.points.iter().skip(1)) {
+                        output.push(Self::from_point(p1));
+                        output.push(Self::from_point(p2));
+                    }
+                }
+                "polyline" => {
+                    let loops = std::iter::once(&action.points).chain(action.loops.iter());
+                    for points in loops.filter(|points| !points.is_empty()) {
+                        let closing = points.iter().skip(1).chain(points.iter().take(1));
+                        for (p1, p2) in points.iter().zip(closing) {
+                            output.push(Self::from_point(p1));
+                            output.push(Self::from_point(p2));
+                        }
+                    }
+                }
+                "circle" => {
+                    let (center, radius) = match (action.points.get(0), action.scalars.get(0)) {
+                        (Some(center), Some(radius)) => (center, *radius),
+                        _ => continue,
+                    };
+                    let dphi = std::f64::consts::PI * 2.0 / CIRCLE_RESOLUTION as f64;
+                    for index in 0..CIRCLE_RESOLUTION {
+                        for angle in vec![dphi * index as f64, dphi * (index + 1) as f64] {
+                            output.push(Vertex {
+                                position: [
+                                    (center.x + radius * angle.cos()) as f32,
+                                    (center.y + radius * angle.sin()) as f32,
+                                ],
+                            });
+                        }
+                    }
+                }
+                "rectangle" | "ellipse" | "arc" | "bspline" => {
+                    if let Ok(polyline) = parse_shape(action) {
+                        for edge in polyline.into_edges().iter() {
+                            output.push(Vertex {
+                                position: [edge.v1.x as f32, edge.v1.y as f32],
+                            });
+                            output.push(Vertex {
+                                position: [edge.v2.x as f32, edge.v2.y as f32],
+                            });
+                        }
+                    }
+                }
+                "segments" => {
+                    if let Ok(segments) = segments_parser::parse(action) {
+                        for segment in segments.iter() {
+                            output.push(Vertex {
+                                position: [segment.v1.x as f32, segment.v1.y as f32],
+                            });
+                            output.push(Vertex {
+                                position: [segment.v2.x as f32, segment.v2.y as f32],
+                            });
+                        }
+                    }
+                }
+                _ => continue,
+            }
+        }
+
+        return output;
+    }
+
+    fn from_point(point: &Point) -> Self {
+        Vertex {
+            position: [point.x as f32, point.y as f32],
+        }
+    }
 }
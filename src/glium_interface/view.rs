@@ -0,0 +1,247 @@
+extern crate glium;
+
+use nlsn_delaunay::elements::triangle::Triangle;
+use nlsn_delaunay::properties::{continence::Continence, distance::distance};
+use nlsn_delaunay::Vertex;
+
+use glium::glutin::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+
+use std::rc::Rc;
+
+/* Zoom factor of each mouse wheel line */
+const ZOOM_PER_LINE: f64 = 1.2;
+
+/* Pixels of the mouse wheel that amount to a line */
+const PIXELS_PER_LINE: f64 = 40.0;
+
+/* Distance, in pixels, within which clicks pick vertices */
+const PICK_RADIUS: f64 = 5.0;
+
+/* Pixels the cursor may move between press and release of a click */
+const CLICK_SLACK: f64 = 3.0;
+
+/**
+ * Pan and zoom of the viewer, mapping world coordinates to the window.
+ * The center is shown at the middle of the window, and half the window
+ * height spans half_height world units, whatever the window aspect.
+ */
+pub struct View {
+    pub center: [f64; 2],
+    pub half_height: f64,
+    size: [f64; 2],
+    cursor: [f64; 2],
+    pressed_at: Option<[f64; 2]>,
+}
+
+impl View {
+    /**
+     * View fitting every position, with a margin around them.
+     */
+    pub fn fit(positions: &Vec<[f32; 2]>) -> Self {
+        let mut min = [std::f64::INFINITY; 2];
+        let mut max = [std::f64::NEG_INFINITY; 2];
+        for position in positions.iter() {
+            for axis in 0..2 {
+                min[axis] = min[axis].min(position[axis] as f64);
+                max[axis] = max[axis].max(position[axis] as f64);
+            }
+        }
+        if positions.is_empty() {
+            return Self::with_window([0.0, 0.0], 1.0);
+        }
+
+        let center = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+        let half_extent = ((max[0] - min[0]).max(max[1] - min[1]) / 2.0).max(1.0E-12);
+        return Self::with_window(center, 1.1 * half_extent);
+    }
+
+    fn with_window(center: [f64; 2], half_height: f64) -> Self {
+        return Self {
+            center,
+            half_height,
+            size: [1.0, 1.0],
+            cursor: [0.0, 0.0],
+            pressed_at: None,
+        };
+    }
+
+    /**
+     * Window size in pixels, updated on every frame.
+     */
+    pub fn resize(&mut self, width: f64, height: f64) {
+        self.size = [width.max(1.0), height.max(1.0)];
+    }
+
+    /**
+     * World to clip space transform, as a column major matrix.
+     */
+    pub fn matrix(&self) -> [[f32; 4]; 4] {
+        let aspect = self.size[0] / self.size[1];
+        let scale_x = 1.0 / (self.half_height * aspect);
+        let scale_y = 1.0 / self.half_height;
+        return [
+            [scale_x as f32, 0.0, 0.0, 0.0],
+            [0.0, scale_y as f32, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [
+                (-self.center[0] * scale_x) as f32,
+                (-self.center[1] * scale_y) as f32,
+                0.0,
+                1.0,
+            ],
+        ];
+    }
+
+    /**
+     * World coordinates under the pixel, counted from the top left corner.
+     */
+    pub fn to_world(&self, pixel: [f64; 2]) -> [f64; 2] {
+        let world_per_pixel = 2.0 * self.half_height / self.size[1];
+        return [
+            self.center[0] + (pixel[0] - self.size[0] / 2.0) * world_per_pixel,
+            self.center[1] - (pixel[1] - self.size[1] / 2.0) * world_per_pixel,
+        ];
+    }
+
+    /**
+     * Zooms in by the factor, keeping the world point under the pixel.
+     */
+    pub fn zoom(&mut self, pixel: [f64; 2], factor: f64) {
+        let anchor = self.to_world(pixel);
+        self.half_height /= factor;
+        let moved = self.to_world(pixel);
+        self.center[0] += anchor[0] - moved[0];
+        self.center[1] += anchor[1] - moved[1];
+    }
+
+    /**
+     * Pans so that the world point under the former pixel moves under the
+     * latter one.
+     */
+    pub fn pan(&mut self, from: [f64; 2], to: [f64; 2]) {
+        let (from, to) = (self.to_world(from), self.to_world(to));
+        self.center[0] -= to[0] - from[0];
+        self.center[1] -= to[1] - from[1];
+    }
+
+    /**
+     * Zooms on mouse wheel and pans on left button drag. Returns the world
+     * point of clicks, that is, left button releases close to their press.
+     */
+    pub fn handle(&mut self, event: &WindowEvent) -> Option<[f64; 2]> {
+        match event {
+            WindowEvent::CursorMoved { position, .. } => {
+                let cursor = [position.x, position.y];
+                if self.pressed_at.is_some() {
+                    self.pan(self.cursor, cursor);
+                }
+                self.cursor = cursor;
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y as f64,
+                    MouseScrollDelta::PixelDelta(position) => position.y / PIXELS_PER_LINE,
+                };
+                self.zoom(self.cursor, ZOOM_PER_LINE.powf(lines));
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => match state {
+                ElementState::Pressed => self.pressed_at = Some(self.cursor),
+                ElementState::Released => {
+                    let pressed_at = self.pressed_at.take()?;
+                    let moved =
+                        (self.cursor[0] - pressed_at[0]).hypot(self.cursor[1] - pressed_at[1]);
+                    if moved <= CLICK_SLACK {
+                        return Some(self.to_world(self.cursor));
+                    }
+                }
+            },
+            _ => (),
+        }
+        return None;
+    }
+
+    /**
+     * Describes the vertex within the pick radius of the point, or else the
+     * triangle containing it.
+     */
+    pub fn pick(&self, triangles: &Vec<Rc<Triangle>>, point: [f64; 2]) -> Option<String> {
+        let point = Vertex::new(point[0], point[1]);
+        let radius = PICK_RADIUS * 2.0 * self.half_height / self.size[1];
+        return pick(triangles, &point, radius);
+    }
+} /* end - View */
+
+/**
+ * Describes the vertex closest to the point within the radius, or else the
+ * triangle containing the point, with its area and quality ratio.
+ */
+pub fn pick(triangles: &Vec<Rc<Triangle>>, point: &Vertex, radius: f64) -> Option<String> {
+    let closest = triangles
+        .iter()
+        .map(|t| vec![&t.v1, &t.v2, &t.v3])
+        .flatten()
+        .map(|v| (distance(v, point), v))
+        .filter(|(d, _)| *d <= radius)
+        .min_by(|(d1, _), (d2, _)| d1.partial_cmp(d2).unwrap());
+    if let Some((_, vertex)) = closest {
+        return Some(format!("vertex {}", vertex));
+    }
+
+    let triangle = triangles
+        .iter()
+        .find(|t| t.contains(point) != Continence::Outside)?;
+    return Some(format!(
+        "triangle {} area {:e} quality {:.4}",
+        triangle,
+        triangle.area()?,
+        triangle.quality()?
+    ));
+}
+
+#[cfg(test)]
+mod view {
+    use super::*;
+
+    #[test]
+    fn zoom_and_pan() {
+        let mut view = View::fit(&vec![[0.0, 0.0], [10.0, 4.0]]);
+        view.resize(200.0, 100.0);
+        assert_eq!(view.center, [5.0, 2.0]);
+        assert!((view.half_height - 5.5).abs() < 1.0E-12);
+        assert_eq!(view.to_world([100.0, 50.0]), [5.0, 2.0]);
+
+        /* the point under the cursor stays put */
+        let anchor = view.to_world([150.0, 25.0]);
+        view.zoom([150.0, 25.0], 2.0);
+        let moved = view.to_world([150.0, 25.0]);
+        assert!((anchor[0] - moved[0]).abs() < 1.0E-12 && (anchor[1] - moved[1]).abs() < 1.0E-12);
+
+        let before = view.to_world([10.0, 10.0]);
+        view.pan([10.0, 10.0], [30.0, 20.0]);
+        let after = view.to_world([30.0, 20.0]);
+        assert!((before[0] - after[0]).abs() < 1.0E-12 && (before[1] - after[1]).abs() < 1.0E-12);
+
+        /* centers map to the clip space origin */
+        let matrix = view.matrix();
+        let x = matrix[0][0] as f64 * view.center[0] + matrix[3][0] as f64;
+        assert!(x.abs() < 1.0E-6);
+    }
+
+    #[test]
+    fn picks() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 2.0));
+        let triangles = vec![Rc::new(Triangle::new(&v1, &v2, &v3))];
+
+        let picked = pick(&triangles, &Vertex::new(1.95, 0.01), 0.1).unwrap();
+        assert!(picked.starts_with("vertex"));
+        let picked = pick(&triangles, &Vertex::new(0.5, 0.5), 0.1).unwrap();
+        assert!(picked.starts_with("triangle") && picked.contains("area 2e0"));
+        assert!(pick(&triangles, &Vertex::new(3.0, 3.0), 0.1).is_none());
+    }
+} /* end - view tests */
@@ -1,22 +1,54 @@
-use crate::json_serializar::models::input;
+use crate::json_serializar::{
+    models::{input, output},
+    validation,
+};
+
+use nlsn_delaunay::planar::triangulation_error::TriangulationError;
 
 use std::fs;
 use std::path;
 
-pub fn read(path: &path::Path) -> Option<input::TriangulationInput> {
-    match fs::read_to_string(path) {
-        Ok(json_string) => {
-            match serde_json::from_str(&json_string) {
-                Ok(data) => return Some(data),
-                Err(_) => return None,
-            };
-        }
-        Err(_) => {
-            return None;
-        }
+/**
+ * Reads the input file, validating it against the input schema first, so
+ * that invalid inputs are reported with every located diagnostic.
+ */
+pub fn read(path: &path::Path) -> Result<input::TriangulationInput, TriangulationError> {
+    let json_string = fs::read_to_string(path)?;
+    let json: serde_json::Value = match serde_json::from_str(&json_string) {
+        Ok(json) => json,
+        Err(error) => return Err(TriangulationError::IoError(error.into())),
+    };
+
+    let diagnostics = validation::validate(&json);
+    if !diagnostics.is_empty() {
+        let lines: Vec<String> = diagnostics
+            .iter()
+            .map(|diagnostic| format!("  {}", diagnostic))
+            .collect();
+        return Err(TriangulationError::DegenerateInput(format!(
+            "invalid input {}\n{}",
+            path.display(),
+            lines.join("\n")
+        )));
     }
+
+    match serde_json::from_value(json) {
+        Ok(data) => return Ok(data),
+        Err(error) => return Err(TriangulationError::IoError(error.into())),
+    };
+}
+
+/**
+ * Reads a triangulation output file, as written by the json format.
+ */
+pub fn read_output(path: &path::Path) -> Result<output::TriangulationOutput, TriangulationError> {
+    let json_string = fs::read_to_string(path)?;
+    match serde_json::from_str(&json_string) {
+        Ok(data) => return Ok(data),
+        Err(error) => return Err(TriangulationError::IoError(error.into())),
+    };
 }
 
-pub fn write(path: &path::Path, json_string: String) -> std::io::Result<()> {
-    fs::write(path, json_string)
+pub fn write<C: AsRef<[u8]>>(path: &path::Path, contents: C) -> std::io::Result<()> {
+    fs::write(path, contents)
 }
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use crate::json_serializar::models::point;
+use crate::json_serializar::models::{input, point, tag};
 
 /**
  * Triangulation domain is described as a composition of solids
@@ -11,6 +11,7 @@ pub struct Action {
             - include
             - remove
             - constraint
+            - hole, region (of seed points)
     */
     pub intent: String,
 
@@ -18,9 +19,16 @@ pub struct Action {
         Describes the geometric form:
             - polyline (rectangle, triangle, ...polygons)
             - circle (center + radius)
+            - rectangle (corner + width, height)
+            - ellipse (center + x and y radii)
+            - arc (center + radius, start and end angles in degrees)
+            - bspline (control points, as a closed uniform b-spline)
             - segments (as constraints)
             - vertices (as constraints)
-            - spline (todo?: not implemented)
+            - seed (as constraints, points filling the domain by a pattern)
+            - seed_point (single point marking the hole or the region
+            bounded by the constraints around it)
+        Shapes take their resolution as an optional last scalar.
     */
     pub geometry: String,
 
@@ -35,6 +43,74 @@ pub struct Action {
     /* Assembles points in 3D */
     #[serde(default = "empty_assemble")]
     pub assemble: Vec<Vec<usize>>,
+
+    /*
+        Multiple closed loops of a single polyline action, as nested arrays,
+        such as exported multipolygons. Outer and inner loops are classified
+        by nesting. Points, if any, are taken as one more loop.
+    */
+    #[serde(default = "empty_loops")]
+    pub loops: Vec<Vec<point::Point>>,
+
+    /*
+        Discretization of circles, ellipses and arcs: either the count of
+        segments, or the maximum distance between the curve and its chords,
+        so that the count adapts to the radius.
+    */
+    #[serde(default = "no_segments")]
+    pub segments: Option<usize>,
+
+    #[serde(default = "no_tolerance")]
+    pub tolerance: Option<f64>,
+
+    /* Seed points arrangement: grid, jittered or poisson */
+    #[serde(default = "no_pattern")]
+    pub pattern: Option<String>,
+
+    /* Distance between seed points */
+    #[serde(default = "no_spacing")]
+    pub spacing: Option<f64>,
+
+    /* Region tag, propagated to output triangles and constrained edges */
+    #[serde(default = "no_tag")]
+    pub tag: Option<tag::Tag>,
+
+    /* Longest edge allowed on the triangles of the action regions */
+    #[serde(default = "no_edge_length")]
+    pub max_edge_length: Option<f64>,
+
+    /* Refine params of the action regions: inside inclusions, around holes */
+    #[serde(default = "no_params")]
+    pub params: Option<input::RegionParams>,
+}
+
+impl Action {
+    /**
+     * Action of the geometry given by points and scalars alone, as built by
+     * the readers of other file formats.
+     */
+    pub fn from_points(
+        intent: &str,
+        geometry: &str,
+        points: Vec<point::Point>,
+        scalars: Vec<f64>,
+    ) -> Self {
+        Self {
+            intent: String::from(intent),
+            geometry: String::from(geometry),
+            scalars,
+            points,
+            assemble: empty_assemble(),
+            loops: empty_loops(),
+            segments: no_segments(),
+            tolerance: no_tolerance(),
+            pattern: no_pattern(),
+            spacing: no_spacing(),
+            tag: no_tag(),
+            max_edge_length: no_edge_length(),
+            params: no_params(),
+        }
+    }
 }
 
 /* default scalars vec */
@@ -52,6 +128,46 @@ fn empty_assemble() -> Vec<Vec<usize>> {
     Vec::new()
 }
 
+/* default loops vec vec */
+fn empty_loops() -> Vec<Vec<point::Point>> {
+    Vec::new()
+}
+
+/* default segments: by the resolution scalar or the tolerance */
+fn no_segments() -> Option<usize> {
+    None
+}
+
+/* default tolerance: by the resolution scalar or the segments */
+fn no_tolerance() -> Option<f64> {
+    None
+}
+
+/* default pattern: grid */
+fn no_pattern() -> Option<String> {
+    None
+}
+
+/* default spacing: none, required by seed actions */
+fn no_spacing() -> Option<f64> {
+    None
+}
+
+/* default tag: untagged */
+fn no_tag() -> Option<tag::Tag> {
+    None
+}
+
+/* default edge length: by the refine params */
+fn no_edge_length() -> Option<f64> {
+    None
+}
+
+/* default region params: the global ones */
+fn no_params() -> Option<input::RegionParams> {
+    None
+}
+
 #[test]
 fn parse_circle() {
     let serial = serde_json::from_str(
@@ -178,3 +294,52 @@ fn parse_segments() {
     assert_eq!(s2.get(0), Some(&2));
     assert_eq!(s2.get(1), Some(&3));
 }
+
+#[test]
+fn parse_polyline_loops() {
+    let serial = serde_json::from_str(
+        "{
+            \"intent\": \"include\",
+            \"geometry\": \"polyline\",
+            \"loops\": [
+                [
+                    { \"x\": 0.0,  \"y\": 0.0 },
+                    { \"x\": 3.0,  \"y\": 0.0 },
+                    { \"x\": 3.0,  \"y\": 3.0 },
+                    { \"x\": 0.0,  \"y\": 3.0 }
+                ],
+                [
+                    { \"x\": 1.0,  \"y\": 1.0 },
+                    { \"x\": 1.0,  \"y\": 2.0 },
+                    { \"x\": 2.0,  \"y\": 2.0 }
+                ]
+            ]
+        }",
+    );
+
+    assert!(serial.is_ok());
+
+    let polyline_intent: Action = serial.unwrap();
+    assert!(polyline_intent.points.is_empty());
+    assert_eq!(polyline_intent.loops.len(), 2);
+    assert_eq!(polyline_intent.loops.get(0).unwrap().len(), 4);
+    assert_eq!(polyline_intent.loops.get(1).unwrap().len(), 3);
+}
+
+#[test]
+fn parse_tagged_action() {
+    let serial = serde_json::from_str(
+        "{
+            \"intent\": \"include\",
+            \"geometry\": \"circle\",
+            \"scalars\": [ 1.0 ],
+            \"points\": [{ \"x\": 1.0,  \"y\": 1.0 }],
+            \"tag\": \"steel\"
+        }",
+    );
+
+    assert!(serial.is_ok());
+
+    let circle_intent: Action = serial.unwrap();
+    assert_eq!(circle_intent.tag, Some(tag::Tag::Name(String::from("steel"))));
+}
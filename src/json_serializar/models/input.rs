@@ -23,6 +23,31 @@ pub struct TriangulationInput {
     pub params: RefineParams,
 }
 
+impl TriangulationInput {
+    /**
+     * Input of the actions, refined by the named profile, as built by the
+     * readers of file formats carrying no refine params.
+     */
+    pub fn from_actions(name: &str, actions: Vec<action::Action>, profile: &str) -> Self {
+        Self {
+            id: new_uuid(),
+            name: String::from(name),
+            date: now(),
+            actions,
+            params: RefineParams {
+                profile: Some(String::from(profile)),
+                max_area: None,
+                quality: None,
+                welding_tolerance: default_welding_tolerance(),
+                snap_tolerance: None,
+                intersection_grid: None,
+                simplify_tolerance: None,
+                max_edge_length: None,
+            },
+        }
+    }
+}
+
 fn new_uuid() -> Uuid {
     Uuid::new_v4()
 }
@@ -34,8 +59,51 @@ fn now() -> String {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RefineParams {
+    /* named preset: draft, standard or fine */
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /* overrides the profile values, if any */
+    pub max_area: Option<f64>,
+    #[serde(default)]
+    pub quality: Option<f64>,
+
+    #[serde(default = "default_welding_tolerance")]
+    pub welding_tolerance: f64,
+
+    /* vertex constraints this close to the boundary or holes are snapped onto them */
+    #[serde(default)]
+    pub snap_tolerance: Option<f64>,
+
+    /* crossings of constraints are snap rounded to a grid of this size */
+    #[serde(default)]
+    pub intersection_grid: Option<f64>,
+
+    /* polylines are simplified, dropping vertices this close to their edges */
+    #[serde(default)]
+    pub simplify_tolerance: Option<f64>,
+
+    /* longest edge allowed on refined triangles, overridden by include actions */
+    #[serde(default)]
+    pub max_edge_length: Option<f64>,
+}
+
+/**
+ * Refine params of the regions added by an action, overriding the global
+ * ones inside included regions and around holes.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RegionParams {
+    #[serde(default)]
     pub max_area: Option<f64>,
-    pub quality: f64,
+    #[serde(default)]
+    pub quality: Option<f64>,
+    #[serde(default)]
+    pub max_edge_length: Option<f64>,
+}
+
+fn default_welding_tolerance() -> f64 {
+    nlsn_delaunay::planar::triangulator::DEFAULT_WELDING_TOLERANCE
 }
 
 #[test]
@@ -50,7 +118,21 @@ fn parse_refine_params() {
 
     let params: RefineParams = serial.unwrap();
     assert_eq!(params.max_area, Some(0.001));
-    assert_eq!(params.quality, 1.0);
+    assert_eq!(params.quality, Some(1.0));
+}
+
+#[test]
+fn parse_refine_params_max_edge_length() {
+    let serial = serde_json::from_str(
+        "{
+            \"quality\": 1.0,
+            \"max_edge_length\": 0.25
+        }",
+    );
+    assert!(serial.is_ok());
+
+    let params: RefineParams = serial.unwrap();
+    assert_eq!(params.max_edge_length, Some(0.25));
 }
 
 #[test]
@@ -64,7 +146,42 @@ fn parse_refine_params_no_max_area() {
 
     let params: RefineParams = serial.unwrap();
     assert!(params.max_area.is_none());
-    assert_eq!(params.quality, 1.0);
+    assert_eq!(params.quality, Some(1.0));
+}
+
+#[test]
+fn parse_refine_params_welding_tolerance() {
+    let serial = serde_json::from_str(
+        "{
+            \"quality\": 1.0,
+            \"welding_tolerance\": 0.001
+        }",
+    );
+    assert!(serial.is_ok());
+
+    let params: RefineParams = serial.unwrap();
+    assert_eq!(params.welding_tolerance, 0.001);
+
+    let params: RefineParams = serde_json::from_str("{ \"quality\": 1.0 }").unwrap();
+    assert_eq!(params.welding_tolerance, 1.0E-12);
+}
+
+#[test]
+fn parse_refine_params_simplify_tolerance() {
+    let params: RefineParams =
+        serde_json::from_str("{ \"simplify_tolerance\": 0.01 }").unwrap();
+    assert_eq!(params.simplify_tolerance, Some(0.01));
+
+    let params: RefineParams = serde_json::from_str("{ \"quality\": 1.0 }").unwrap();
+    assert!(params.simplify_tolerance.is_none());
+}
+
+#[test]
+fn parse_refine_params_profile() {
+    let params: RefineParams = serde_json::from_str("{ \"profile\": \"fine\" }").unwrap();
+    assert_eq!(params.profile, Some(String::from("fine")));
+    assert!(params.quality.is_none());
+    assert!(params.max_area.is_none());
 }
 
 #[test]
@@ -94,7 +211,7 @@ fn parse_triangulation() {
     assert_eq!(triangulation.name, "sample_1");
     assert_eq!(triangulation.date, "2020-09-03T00:09:27.591Z");
 
-    assert_eq!(triangulation.params.quality, 1.0);
+    assert_eq!(triangulation.params.quality, Some(1.0));
     assert_eq!(triangulation.params.max_area, Some(0.001));
 }
 
@@ -1,5 +1,6 @@
 pub mod action;
 pub mod point;
+pub mod tag;
 pub mod tesselations;
 
 pub mod input;
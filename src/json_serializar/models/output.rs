@@ -6,11 +6,18 @@ use chrono::{SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 use crate::json_serializar::models::{input::TriangulationInput, point, tesselations};
-use nlsn_delaunay::{elements::vertex::Vertex, planar::triangulator::Triangulator};
+use crate::triangulator_interface::{self, regions::Regions};
+use nlsn_delaunay::{
+    elements::{triangle as element, vertex::Vertex},
+    planar::{
+        dual_mesh::DualMesh, triangulation::Triangulation, triangulation_data::TriangulationData,
+        triangulation_error::TriangulationError, triangulator::Triangulator,
+    },
+};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TriangulationOutput {
@@ -27,8 +34,22 @@ pub struct TriangulationOutput {
     #[serde(default = "empty_triangles")]
     pub triangles: Vec<tesselations::Triangle>,
 
+    #[serde(default = "empty_edges")]
+    pub edges: Vec<tesselations::Edge>,
+
     #[serde(default = "empty_tetrahedrons")]
     pub tetrahedrons: Vec<tesselations::Tetrahedron>,
+
+    /* Voronoi cell area of each coordinate, if dual metrics are requested */
+    #[serde(default = "no_cell_areas", skip_serializing_if = "Option::is_none")]
+    pub cell_areas: Option<Vec<f64>>,
+
+    #[serde(default = "no_dual_faces", skip_serializing_if = "Option::is_none")]
+    pub dual_faces: Option<Vec<tesselations::DualFace>>,
+
+    /* Voronoi cells clipped by regions and holes, if requested */
+    #[serde(default = "no_voronoi_cells", skip_serializing_if = "Option::is_none")]
+    pub voronoi_cells: Option<Vec<tesselations::VoronoiCell>>,
 }
 
 fn new_uuid() -> Uuid {
@@ -45,6 +66,26 @@ fn empty_triangles() -> Vec<tesselations::Triangle> {
     Vec::new()
 }
 
+/* default empty constrained edges list */
+fn empty_edges() -> Vec<tesselations::Edge> {
+    Vec::new()
+}
+
+/* default cell areas: not requested */
+fn no_cell_areas() -> Option<Vec<f64>> {
+    None
+}
+
+/* default dual faces: not requested */
+fn no_dual_faces() -> Option<Vec<tesselations::DualFace>> {
+    None
+}
+
+/* default voronoi cells: not requested */
+fn no_voronoi_cells() -> Option<Vec<tesselations::VoronoiCell>> {
+    None
+}
+
 /* default date: now */
 fn now() -> String {
     Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
@@ -67,28 +108,677 @@ impl TriangulationOutput {
             vertices_map.insert(Rc::clone(v), index);
         }
 
+        let regions = Regions::from_input(input);
+        let seeded_tags = regions.seeded_tags(triangulator);
+
+        /* input vertex attributes, interpolated at Steiner points */
+        let attributes = if triangulator.attributes.is_empty() {
+            HashMap::new()
+        } else {
+            triangulator.vertex_attributes()
+        };
+
+        let (boundary_edges, holes_edges, segments_edges) =
+            triangulator_interface::constrained_edges(triangulator);
+
         return Self {
             id: input.id,
             name: input.name.clone(),
             date: input.date.clone(),
-            coordinates: vertices_map
-                .keys()
-                .map(|v| point::Point::from_vertex(v))
+            coordinates: vertices_vec
+                .iter()
+                .map(|v| point::Point::from_attributed_vertex(v, attributes.get(v)))
                 .collect(),
             triangles: triangulator
                 .triangulation
                 .borrow()
-                .triangles
-                .iter()
-                .filter(|t| !t.is_ghost())
+                .solid_triangles()
                 .map(|t| {
-                    let v1 = vertices_map.get(&t.v1).unwrap();
-                    let v2 = vertices_map.get(&t.v2).unwrap();
-                    let v3 = vertices_map.get(&t.v3).unwrap();
-                    return tesselations::Triangle::new(*v1, *v2, *v3);
+                    let ccw = t.ensure_ccw();
+                    let v1 = vertices_map.get(&ccw.v1).unwrap();
+                    let v2 = vertices_map.get(&ccw.v2).unwrap();
+                    let v3 = vertices_map.get(&ccw.v3).unwrap();
+                    let mut triangle = tesselations::Triangle::new(*v1, *v2, *v3);
+                    triangle.tag = match seeded_tags.get(t) {
+                        Some(tag) => Some(tag.clone()),
+                        None => regions.triangle_tag(t),
+                    };
+                    triangle.domain = triangulator.domain_of(t);
+                    if !triangulator.disjoint_boundaries.is_empty() {
+                        triangle.component = triangulator.component_of(t);
+                    }
+                    return triangle;
+                })
+                .collect(),
+            edges: boundary_edges
+                .iter()
+                .chain(holes_edges.iter())
+                .chain(segments_edges.iter())
+                .filter_map(|e| {
+                    let v1 = vertices_map.get(&e.v1)?;
+                    let v2 = vertices_map.get(&e.v2)?;
+                    let mut edge = tesselations::Edge::new(*v1, *v2);
+                    edge.tag = regions.edge_tag(e);
+                    return Some(edge);
                 })
                 .collect(),
             tetrahedrons: Vec::new(),
+            cell_areas: None,
+            dual_faces: None,
+        voronoi_cells: None,
         };
     } /* end - from triangulator */
+
+    /**
+     * Includes the Voronoi dual metrics of the triangulation: the cell area
+     * of every coordinate and the length of the face dual to every edge.
+     */
+    pub fn include_dual(&mut self, triangulator: &Triangulator) {
+        let dual = DualMesh::new(&triangulator.triangulation.borrow());
+
+        let indices: HashMap<Rc<Vertex>, usize> = self
+            .coordinates
+            .iter()
+            .enumerate()
+            .map(|(index, p)| (Rc::new(Vertex::new(p.x, p.y)), index))
+            .collect();
+
+        self.cell_areas = Some(
+            self.coordinates
+                .iter()
+                .map(|p| {
+                    let vertex = Rc::new(Vertex::new(p.x, p.y));
+                    return dual.cell_area(&vertex).unwrap_or(0.0);
+                })
+                .collect(),
+        );
+
+        let mut dual_faces: Vec<tesselations::DualFace> = dual
+            .face_lengths
+            .iter()
+            .filter_map(|(edge, length)| {
+                return Some(tesselations::DualFace {
+                    v1: *indices.get(&edge.v1)?,
+                    v2: *indices.get(&edge.v2)?,
+                    length: *length,
+                });
+            })
+            .collect();
+        dual_faces.sort_by_key(|face| (face.v1, face.v2));
+        self.dual_faces = Some(dual_faces);
+    }
+
+    /**
+     * Includes the Voronoi cells of the coordinates, clipped by the regions
+     * and holes of the triangulator, with their areas and neighbors, so that
+     * finite volume solvers may consume the dual mesh directly.
+     */
+    pub fn include_voronoi_cells(&mut self, triangulator: &Triangulator) {
+        let indices: HashMap<Rc<Vertex>, usize> = self
+            .coordinates
+            .iter()
+            .enumerate()
+            .map(|(index, p)| (Rc::new(Vertex::new(p.x, p.y)), index))
+            .collect();
+
+        let mut cells: Vec<tesselations::VoronoiCell> = triangulator
+            .voronoi_cells()
+            .iter()
+            .filter_map(|cell| {
+                return Some(tesselations::VoronoiCell {
+                    vertex: *indices.get(&cell.vertex)?,
+                    region: cell.region,
+                    area: cell.area,
+                    polygons: cell
+                        .polygons
+                        .iter()
+                        .map(|polygon| {
+                            polygon
+                                .vertices
+                                .iter()
+                                .map(|v| point::Point::from_vertex(v))
+                                .collect()
+                        })
+                        .collect(),
+                    neighbors: cell
+                        .neighbors
+                        .iter()
+                        .filter_map(|neighbor| indices.get(neighbor).cloned())
+                        .collect(),
+                });
+            })
+            .collect();
+        cells.sort_by_key(|cell| (cell.vertex, cell.region));
+        self.voronoi_cells = Some(cells);
+    }
+
+    /**
+     * Includes the metrics of every triangle, as computed from its
+     * coordinates. Triangles referring to missing coordinates, or having no
+     * area, are left without metrics.
+     */
+    pub fn include_metrics(&mut self) {
+        let coordinates = &self.coordinates;
+        for triangle in self.triangles.iter_mut() {
+            let vertices: Option<Vec<Rc<Vertex>>> = [triangle.v1, triangle.v2, triangle.v3]
+                .iter()
+                .map(|&index| {
+                    let p = coordinates.get(index)?;
+                    return Some(Rc::new(Vertex::new(p.x, p.y)));
+                })
+                .collect();
+            triangle.metrics = vertices.and_then(|vertices| {
+                let geometry = element::Triangle::new(&vertices[0], &vertices[1], &vertices[2]);
+                let area = geometry.area()?.abs();
+                if !(area > 0.0) {
+                    return None;
+                }
+                return Some(tesselations::TriangleMetrics {
+                    area,
+                    min_angle: geometry.min_angle()?.to_degrees(),
+                    quality: geometry.quality()?,
+                    circumradius: geometry.circumradius()?,
+                });
+            });
+        }
+    }
+
+    /**
+     * Canonicalizes triangles for importers: every triangle is counterclockwise
+     * oriented starting at its smallest index, duplicated triangles are
+     * dropped, as well as coordinates no triangle nor edge refers to. Returns
+     * an error if any triangle refers to missing coordinates or is degenerate.
+     */
+    pub fn canonicalize(&mut self) -> Result<(), TriangulationError> {
+        let coordinates_count = self.coordinates.len();
+        for triangle in self.triangles.iter() {
+            let indices = [triangle.v1, triangle.v2, triangle.v3];
+            if let Some(index) = indices.iter().find(|&&i| i >= coordinates_count) {
+                return Err(TriangulationError::DegenerateInput(format!(
+                    "triangle ({}, {}, {}) refers to missing vertex {}",
+                    triangle.v1, triangle.v2, triangle.v3, index
+                )));
+            }
+        }
+        for edge in self.edges.iter() {
+            if edge.v1 >= coordinates_count || edge.v2 >= coordinates_count {
+                return Err(TriangulationError::DegenerateInput(format!(
+                    "edge ({}, {}) refers to missing vertex",
+                    edge.v1, edge.v2
+                )));
+            }
+        }
+
+        /* orientation and rotation, so that duplicates have the same indices */
+        for triangle in self.triangles.iter_mut() {
+            let area = signed_area(&self.coordinates, triangle);
+            if area == 0.0 {
+                return Err(TriangulationError::DegenerateInput(format!(
+                    "triangle ({}, {}, {}) has no area",
+                    triangle.v1, triangle.v2, triangle.v3
+                )));
+            }
+            if area < 0.0 {
+                std::mem::swap(&mut triangle.v2, &mut triangle.v3);
+            }
+            while triangle.v1 > triangle.v2 || triangle.v1 > triangle.v3 {
+                let v1 = triangle.v1;
+                triangle.v1 = triangle.v2;
+                triangle.v2 = triangle.v3;
+                triangle.v3 = v1;
+            }
+        }
+
+        let mut existing_triangles: HashSet<(usize, usize, usize)> = HashSet::new();
+        self.triangles
+            .retain(|t| existing_triangles.insert((t.v1, t.v2, t.v3)));
+
+        /* drops unreferenced coordinates, keeping their order */
+        let mut is_used: Vec<bool> = vec![false; coordinates_count];
+        for triangle in self.triangles.iter() {
+            is_used[triangle.v1] = true;
+            is_used[triangle.v2] = true;
+            is_used[triangle.v3] = true;
+        }
+        for edge in self.edges.iter() {
+            is_used[edge.v1] = true;
+            is_used[edge.v2] = true;
+        }
+
+        let mut new_indices: Vec<usize> = vec![0; coordinates_count];
+        let mut used_count: usize = 0;
+        for index in 0..coordinates_count {
+            new_indices[index] = used_count;
+            if is_used[index] {
+                used_count += 1;
+            }
+        }
+
+        let mut index: usize = 0;
+        self.coordinates.retain(|_| {
+            index += 1;
+            return is_used[index - 1];
+        });
+        for triangle in self.triangles.iter_mut() {
+            triangle.v1 = new_indices[triangle.v1];
+            triangle.v2 = new_indices[triangle.v2];
+            triangle.v3 = new_indices[triangle.v3];
+        }
+        for edge in self.edges.iter_mut() {
+            edge.v1 = new_indices[edge.v1];
+            edge.v2 = new_indices[edge.v2];
+        }
+
+        if let Some(cell_areas) = self.cell_areas.as_mut() {
+            let mut index: usize = 0;
+            cell_areas.retain(|_| {
+                index += 1;
+                return index > coordinates_count || is_used[index - 1];
+            });
+        }
+        if let Some(voronoi_cells) = self.voronoi_cells.as_mut() {
+            voronoi_cells.retain(|cell| cell.vertex < coordinates_count && is_used[cell.vertex]);
+            for cell in voronoi_cells.iter_mut() {
+                cell.vertex = new_indices[cell.vertex];
+                cell.neighbors.retain(|&neighbor| neighbor < coordinates_count && is_used[neighbor]);
+                for neighbor in cell.neighbors.iter_mut() {
+                    *neighbor = new_indices[*neighbor];
+                }
+            }
+        }
+        if let Some(dual_faces) = self.dual_faces.as_mut() {
+            dual_faces.retain(|f| f.v1 < coordinates_count && f.v2 < coordinates_count);
+            for face in dual_faces.iter_mut() {
+                face.v1 = new_indices[face.v1];
+                face.v2 = new_indices[face.v2];
+            }
+        }
+
+        return Ok(());
+    } /* end - canonicalize */
+
+    /**
+     * Triangulation of the canonicalized triangles, such as for comparing
+     * outputs. Returns an error if they are not a valid triangulation.
+     */
+    pub fn to_triangulation(mut self) -> Result<Triangulation, TriangulationError> {
+        self.canonicalize()?;
+        let coordinates: Vec<f64> = self
+            .coordinates
+            .iter()
+            .map(|point| vec![point.x, point.y])
+            .flatten()
+            .collect();
+        let triangles: Vec<usize> = self
+            .triangles
+            .iter()
+            .map(|triangle| vec![triangle.v1, triangle.v2, triangle.v3])
+            .flatten()
+            .collect();
+        match TriangulationData::from(coordinates, triangles).to_triangulation() {
+            Some(triangulation) => return Ok(triangulation),
+            None => {
+                return Err(TriangulationError::DegenerateInput(format!(
+                    "output {} is not a triangulation",
+                    self.name
+                )))
+            }
+        }
+    }
 } /* end - TriangulatorOutput */
+
+/* positive if counterclockwise */
+fn signed_area(coordinates: &Vec<point::Point>, triangle: &tesselations::Triangle) -> f64 {
+    let p1 = &coordinates[triangle.v1];
+    let p2 = &coordinates[triangle.v2];
+    let p3 = &coordinates[triangle.v3];
+    return ((p2.x - p1.x) * (p3.y - p1.y) - (p3.x - p1.x) * (p2.y - p1.y)) / 2.0;
+}
+
+#[cfg(test)]
+fn sample_output(triangles: Vec<tesselations::Triangle>) -> TriangulationOutput {
+    let coordinates = vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (5.0, 5.0), (0.0, 1.0)];
+    return TriangulationOutput {
+        id: new_uuid(),
+        name: String::from("sample"),
+        date: now(),
+        coordinates: coordinates
+            .into_iter()
+            .map(|(x, y)| point::Point { x, y, z: 0.0, id: None, metadata: None })
+            .collect(),
+        triangles,
+        edges: vec![tesselations::Edge::new(4, 0)],
+        tetrahedrons: Vec::new(),
+        cell_areas: None,
+        dual_faces: None,
+        voronoi_cells: None,
+    };
+}
+
+#[test]
+fn canonicalize_triangles() {
+    let mut output = sample_output(vec![
+        tesselations::Triangle::new(2, 1, 0),
+        tesselations::Triangle::new(1, 2, 0),
+        tesselations::Triangle::new(0, 2, 4),
+    ]);
+    assert!(output.canonicalize().is_ok());
+
+    /* the unused (5, 5) coordinate is dropped */
+    assert_eq!(output.coordinates.len(), 4);
+    assert_eq!(output.coordinates[3], point::Point { x: 0.0, y: 1.0, z: 0.0, id: None, metadata: None });
+
+    let triangles: Vec<(usize, usize, usize)> =
+        output.triangles.iter().map(|t| (t.v1, t.v2, t.v3)).collect();
+    assert_eq!(triangles, vec![(0, 1, 2), (0, 2, 3)]);
+    assert_eq!((output.edges[0].v1, output.edges[0].v2), (3, 0));
+}
+
+#[test]
+fn canonicalize_missing_vertex() {
+    let mut output = sample_output(vec![tesselations::Triangle::new(0, 1, 7)]);
+    assert!(output.canonicalize().is_err());
+
+    let mut output = sample_output(vec![tesselations::Triangle::new(0, 1, 1)]);
+    assert!(output.canonicalize().is_err());
+}
+
+#[test]
+fn output_to_triangulation() {
+    /* clockwise triangles are reoriented */
+    let output = sample_output(vec![
+        tesselations::Triangle::new(2, 1, 0),
+        tesselations::Triangle::new(0, 2, 4),
+    ]);
+    let triangulation = output.to_triangulation().unwrap();
+    assert_eq!(triangulation.solid_triangles().count(), 2);
+    assert_eq!(triangulation.vertices().len(), 4);
+
+    let output = sample_output(vec![tesselations::Triangle::new(0, 1, 7)]);
+    assert!(output.to_triangulation().is_err());
+}
+
+#[test]
+fn include_dual() {
+    use nlsn_delaunay::elements::polyline::Polyline;
+
+    let input: TriangulationInput = serde_json::from_str(
+        "{
+            \"name\": \"square\",
+            \"actions\": [],
+            \"params\": { \"quality\": 1.0 }
+        }",
+    )
+    .unwrap();
+
+    let boundary = Rc::new(
+        Polyline::new_closed(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(2.0, 0.0)),
+            Rc::new(Vertex::new(2.0, 2.0)),
+            Rc::new(Vertex::new(0.0, 2.0)),
+        ])
+        .unwrap(),
+    );
+    let mut triangulator = Triangulator::new(&boundary);
+    triangulator.try_triangulate().unwrap();
+
+    let mut output = TriangulationOutput::from_triangulator(&input, &triangulator);
+    output.include_dual(&triangulator);
+    output.canonicalize().unwrap();
+
+    /* every corner cell is a quarter of the square */
+    assert_eq!(output.cell_areas.as_ref().unwrap(), &vec![1.0; 4]);
+
+    /* four sides with half faces at each end, and the diagonal */
+    let dual_faces = output.dual_faces.as_ref().unwrap();
+    assert_eq!(dual_faces.len(), 5);
+    let total_length: f64 = dual_faces.iter().map(|face| face.length).sum();
+    assert!((total_length - 4.0).abs() < 1.0E-12);
+
+    let serial = serde_json::to_string(&output).unwrap();
+    assert!(serial.contains("\"cell_areas\":[1.0,1.0,1.0,1.0]"));
+}
+
+#[test]
+fn vertex_attributes() {
+    let input: TriangulationInput = serde_json::from_str(
+        "{
+            \"name\": \"attributed square\",
+            \"actions\": [
+                {
+                    \"intent\": \"include\",
+                    \"geometry\": \"polyline\",
+                    \"points\": [
+                        { \"x\": 0.0, \"y\": 0.0, \"id\": \"sw\", \"metadata\": { \"elevation\": 0.0 } },
+                        { \"x\": 2.0, \"y\": 0.0, \"id\": \"se\", \"metadata\": { \"elevation\": 2.0 } },
+                        { \"x\": 2.0, \"y\": 2.0, \"id\": \"ne\", \"metadata\": { \"elevation\": 4.0 } },
+                        { \"x\": 0.0, \"y\": 2.0, \"id\": \"nw\", \"metadata\": { \"elevation\": 2.0 } }
+                    ]
+                }
+            ],
+            \"params\": { \"max_area\": 0.1, \"quality\": 1.5 }
+        }",
+    )
+    .unwrap();
+
+    let (mut triangulators, refine_params) = triangulator_interface::parse(&input).unwrap();
+    let mut triangulator = triangulators.remove(0);
+    triangulator.try_triangulate().unwrap();
+    triangulator.try_refine(refine_params).unwrap();
+
+    let output = TriangulationOutput::from_triangulator(&input, &triangulator);
+    assert!(output.coordinates.len() > 4);
+
+    let ids: HashSet<&str> = output
+        .coordinates
+        .iter()
+        .filter_map(|p| p.id.as_deref())
+        .collect();
+    assert_eq!(ids, vec!["sw", "se", "ne", "nw"].into_iter().collect());
+
+    /* Steiner points are given interpolated elevations */
+    for point in output.coordinates.iter() {
+        let elevation = point.metadata.as_ref().unwrap()["elevation"];
+        assert!(elevation >= 0.0 && elevation <= 4.0);
+    }
+}
+
+#[test]
+fn include_voronoi_cells() {
+    use nlsn_delaunay::elements::polyline::Polyline;
+
+    let input: TriangulationInput = serde_json::from_str(
+        "{
+            \"name\": \"square\",
+            \"actions\": [],
+            \"params\": { \"quality\": 1.0 }
+        }",
+    )
+    .unwrap();
+
+    let boundary = Rc::new(
+        Polyline::new_closed(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(2.0, 0.0)),
+            Rc::new(Vertex::new(2.0, 2.0)),
+            Rc::new(Vertex::new(0.0, 2.0)),
+        ])
+        .unwrap(),
+    );
+    let mut triangulator = Triangulator::new(&boundary);
+    triangulator.try_triangulate().unwrap();
+
+    let mut output = TriangulationOutput::from_triangulator(&input, &triangulator);
+    output.include_voronoi_cells(&triangulator);
+    output.canonicalize().unwrap();
+
+    /* every corner cell is a quarter of the square, neighboring the others */
+    let cells = output.voronoi_cells.as_ref().unwrap();
+    assert_eq!(cells.len(), 4);
+    for (index, cell) in cells.iter().enumerate() {
+        assert_eq!(cell.vertex, index);
+        assert!((cell.area - 1.0).abs() < 1.0E-12);
+        assert_eq!(cell.polygons.len(), 1);
+        assert!(cell.neighbors.len() >= 2);
+    }
+
+    let serial = serde_json::to_string(&output).unwrap();
+    assert!(serial.contains("\"voronoi_cells\""));
+}
+
+#[test]
+fn include_metrics() {
+    let mut output = sample_output(vec![
+        tesselations::Triangle::new(0, 1, 2),
+        tesselations::Triangle::new(0, 1, 7),
+    ]);
+    output.include_metrics();
+
+    /* half a unit square */
+    let metrics = output.triangles[0].metrics.as_ref().unwrap();
+    assert!((metrics.area - 0.5).abs() < 1.0E-12);
+    assert!((metrics.min_angle - 45.0).abs() < 1.0E-9);
+    assert!((metrics.circumradius - 2.0f64.sqrt() / 2.0).abs() < 1.0E-12);
+    assert!((metrics.quality - 2.0f64.sqrt() / 2.0).abs() < 1.0E-12);
+
+    /* missing coordinates */
+    assert!(output.triangles[1].metrics.is_none());
+
+    let serial = serde_json::to_string(&output.triangles[0]).unwrap();
+    assert!(serial.contains("\"metrics\":{\"area\":0.5"));
+    let serial = serde_json::to_string(&tesselations::Triangle::new(0, 1, 2)).unwrap();
+    assert!(!serial.contains("metrics"));
+}
+
+#[test]
+fn seed_points() {
+    use crate::json_serializar::models::tag::Tag;
+    use nlsn_delaunay::{elements::polyline::Polyline, properties::continence::Continence};
+
+    /* two walls of segments, one carved by a hole seed, one tagged by a region seed */
+    let input: TriangulationInput = serde_json::from_str(
+        "{
+            \"name\": \"seeded square\",
+            \"actions\": [
+                {
+                    \"intent\": \"include\",
+                    \"geometry\": \"polyline\",
+                    \"points\": [
+                        { \"x\": 0.0, \"y\": 0.0 },
+                        { \"x\": 6.0, \"y\": 0.0 },
+                        { \"x\": 6.0, \"y\": 6.0 },
+                        { \"x\": 0.0, \"y\": 6.0 }
+                    ]
+                },
+                {
+                    \"intent\": \"constraint\",
+                    \"geometry\": \"segments\",
+                    \"points\": [
+                        { \"x\": 1.1, \"y\": 0.9 }, { \"x\": 2.2, \"y\": 1.3 },
+                        { \"x\": 2.2, \"y\": 1.3 }, { \"x\": 1.6, \"y\": 2.1 },
+                        { \"x\": 1.6, \"y\": 2.1 }, { \"x\": 1.1, \"y\": 0.9 },
+                        { \"x\": 3.1, \"y\": 3.9 }, { \"x\": 4.7, \"y\": 3.3 },
+                        { \"x\": 4.7, \"y\": 3.3 }, { \"x\": 4.2, \"y\": 5.1 },
+                        { \"x\": 4.2, \"y\": 5.1 }, { \"x\": 3.1, \"y\": 3.9 }
+                    ]
+                },
+                {
+                    \"intent\": \"hole\",
+                    \"geometry\": \"seed_point\",
+                    \"points\": [{ \"x\": 1.6, \"y\": 1.4 }]
+                },
+                {
+                    \"intent\": \"region\",
+                    \"geometry\": \"seed_point\",
+                    \"points\": [{ \"x\": 4.0, \"y\": 4.0 }],
+                    \"tag\": \"pond\"
+                }
+            ],
+            \"params\": { \"quality\": 1.0 }
+        }",
+    )
+    .unwrap();
+
+    let (mut triangulators, _) = triangulator_interface::parse(&input).unwrap();
+    let mut triangulator = triangulators.remove(0);
+    triangulator.try_triangulate().unwrap();
+    assert_eq!(triangulator.holes.len(), 1);
+
+    let output = TriangulationOutput::from_triangulator(&input, &triangulator);
+    let pond = Some(Tag::Name(String::from("pond")));
+    let pond_wall = Polyline::new_closed(vec![
+        Rc::new(Vertex::new(3.1, 3.9)),
+        Rc::new(Vertex::new(4.7, 3.3)),
+        Rc::new(Vertex::new(4.2, 5.1)),
+    ])
+    .unwrap();
+    let mut area = 0.0;
+    for triangle in output.triangles.iter() {
+        let corners: Vec<&point::Point> = vec![triangle.v1, triangle.v2, triangle.v3]
+            .into_iter()
+            .map(|index| &output.coordinates[index])
+            .collect();
+        let x = corners.iter().map(|p| p.x).sum::<f64>() / 3.0;
+        let y = corners.iter().map(|p| p.y).sum::<f64>() / 3.0;
+        area += ((corners[1].x - corners[0].x) * (corners[2].y - corners[0].y)
+            - (corners[2].x - corners[0].x) * (corners[1].y - corners[0].y))
+            / 2.0;
+
+        let is_pond = pond_wall.contains(&Vertex::new(x, y)) == Some(Continence::Inside);
+        assert_eq!(triangle.tag.is_some(), is_pond);
+        if is_pond {
+            assert_eq!(triangle.tag, pond);
+        }
+    }
+
+    /* the carved wall is left out of the square */
+    assert!((area - 35.44).abs() < 1.0E-9);
+}
+
+#[test]
+fn counterclockwise_triangles() {
+    use nlsn_delaunay::elements::{polyline::Polyline, triangle::Triangle};
+
+    let input: TriangulationInput = serde_json::from_str(
+        "{
+            \"name\": \"pentagon\",
+            \"actions\": [],
+            \"params\": { \"quality\": 1.0 }
+        }",
+    )
+    .unwrap();
+
+    let boundary = Rc::new(
+        Polyline::new_closed(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(3.1, 0.4)),
+            Rc::new(Vertex::new(3.6, 2.9)),
+            Rc::new(Vertex::new(1.3, 4.2)),
+            Rc::new(Vertex::new(-0.7, 2.2)),
+        ])
+        .unwrap(),
+    );
+    let mut triangulator = Triangulator::new(&boundary);
+    triangulator.try_triangulate().unwrap();
+
+    /* every other triangle stored clockwise */
+    {
+        let mut triangulation = triangulator.triangulation.borrow_mut();
+        let triangles: Vec<Rc<Triangle>> = triangulation.solid_triangles().cloned().collect();
+        for triangle in triangles.iter().step_by(2) {
+            triangulation.remove_triangle(triangle);
+            triangulation.include_triangle(&Rc::new(Triangle::new(
+                &triangle.v1,
+                &triangle.v3,
+                &triangle.v2,
+            )));
+        }
+    }
+
+    let output = TriangulationOutput::from_triangulator(&input, &triangulator);
+    assert_eq!(output.triangles.len(), 3);
+    for triangle in output.triangles.iter() {
+        assert!(signed_area(&output.coordinates, triangle) > 0.0);
+    }
+}
@@ -1,7 +1,9 @@
 extern crate serde;
 
 use serde::{Deserialize, Serialize};
-use nlsn_delaunay::elements::vertex::Vertex;
+use nlsn_delaunay::{elements::vertex::Vertex, planar::vertex_attributes::VertexAttributes};
+
+use std::collections::BTreeMap;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Point {
@@ -10,6 +12,14 @@ pub struct Point {
 
     #[serde(default = "zero_f64")]
     pub z: f64,
+
+    /* external id, carried to the output coordinate of the vertex */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /* named values, interpolated at the vertices created by refinement */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BTreeMap<String, f64>>,
 }
 
 fn zero_f64() -> f64 {
@@ -31,7 +41,36 @@ impl Point {
             x: v.x,
             y: v.y,
             z: 0.0,
+            id: None,
+            metadata: None,
+        }
+    }
+
+    /**
+     * Point of the vertex, including its id and metadata, if any.
+     */
+    pub fn from_attributed_vertex(v: &Vertex, attributes: Option<&VertexAttributes>) -> Self {
+        let mut point = Self::from_vertex(v);
+        if let Some(attributes) = attributes {
+            point.id = attributes.id.clone();
+            if !attributes.metadata.is_empty() {
+                point.metadata = Some(attributes.metadata.clone());
+            }
+        }
+        return point;
+    }
+
+    /**
+     * Attributes given to the point, if any.
+     */
+    pub fn attributes(&self) -> Option<VertexAttributes> {
+        if self.id.is_none() && self.metadata.is_none() {
+            return None;
         }
+        return Some(VertexAttributes::new(
+            self.id.clone(),
+            self.metadata.clone().unwrap_or_default(),
+        ));
     }
 }
 
@@ -52,3 +91,28 @@ fn parse_point() {
 
     serde_json::to_string(&point).unwrap();
 }
+
+#[test]
+fn parse_point_attributes() {
+    let point: Point = serde_json::from_str(
+        "{
+            \"x\": 1.0,
+            \"y\": 1.0,
+            \"id\": \"well-7\",
+            \"metadata\": { \"elevation\": 12.5 }
+        }",
+    )
+    .unwrap();
+
+    let attributes = point.attributes().unwrap();
+    assert_eq!(attributes.id, Some(String::from("well-7")));
+    assert_eq!(attributes.metadata["elevation"], 12.5);
+
+    let serial = serde_json::to_string(&point).unwrap();
+    assert!(serial.contains("\"id\":\"well-7\""));
+
+    /* no attributes are serialized unless given */
+    let point: Point = serde_json::from_str("{ \"x\": 1.0, \"y\": 1.0 }").unwrap();
+    assert!(point.attributes().is_none());
+    assert!(!serde_json::to_string(&point).unwrap().contains("id"));
+}
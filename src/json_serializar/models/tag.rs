@@ -0,0 +1,28 @@
+extern crate serde;
+
+use serde::{Deserialize, Serialize};
+
+/**
+ * Physical tag of an input action, either an integer or a name,
+ * propagated to the output triangles and constrained edges.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Tag {
+    Integer(i64),
+    Name(String),
+}
+
+#[test]
+fn parse_integer_tag() {
+    let tag: Tag = serde_json::from_str("7").unwrap();
+    assert_eq!(tag, Tag::Integer(7));
+    assert_eq!(serde_json::to_string(&tag).unwrap(), "7");
+}
+
+#[test]
+fn parse_name_tag() {
+    let tag: Tag = serde_json::from_str("\"steel\"").unwrap();
+    assert_eq!(tag, Tag::Name(String::from("steel")));
+    assert_eq!(serde_json::to_string(&tag).unwrap(), "\"steel\"");
+}
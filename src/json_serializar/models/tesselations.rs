@@ -1,6 +1,6 @@
 extern crate serde;
 
-use crate::json_serializar::models::point;
+use crate::json_serializar::models::{point, tag};
 use nlsn_delaunay::elements::triangle;
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +9,71 @@ pub struct Triangle {
     pub v1: usize,
     pub v2: usize,
     pub v3: usize,
+
+    /* Tag of the inclusion region the triangle falls in */
+    #[serde(default = "no_tag", skip_serializing_if = "Option::is_none")]
+    pub tag: Option<tag::Tag>,
+
+    /* Index of the domain the triangle falls in, if meshed by domains */
+    #[serde(default = "no_domain", skip_serializing_if = "Option::is_none")]
+    pub domain: Option<usize>,
+
+    /* Index of the disconnected component the triangle falls in, if several */
+    #[serde(default = "no_component", skip_serializing_if = "Option::is_none")]
+    pub component: Option<usize>,
+
+    /* Geometric metrics of the triangle, if requested */
+    #[serde(default = "no_metrics", skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<TriangleMetrics>,
+}
+
+/**
+ * Geometry of a triangle, as post-processing scripts need it: area, smallest
+ * inner angle in degrees, circumradius to shortest edge ratio and
+ * circumradius.
+ */
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TriangleMetrics {
+    pub area: f64,
+    pub min_angle: f64,
+    pub quality: f64,
+    pub circumradius: f64,
+}
+
+/**
+ * Constrained edge, split at every output vertex lying on it.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Edge {
+    pub v1: usize,
+    pub v2: usize,
+
+    /* Tag of the input action the edge comes from */
+    #[serde(default = "no_tag", skip_serializing_if = "Option::is_none")]
+    pub tag: Option<tag::Tag>,
+}
+
+/**
+ * Voronoi face dual to an edge, between the cells of its vertices.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DualFace {
+    pub v1: usize,
+    pub v2: usize,
+    pub length: f64,
+}
+
+/**
+ * Voronoi cell of a vertex clipped by a region and its holes, made of one
+ * or more polygons, with the vertices whose cells it neighbors.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VoronoiCell {
+    pub vertex: usize,
+    pub region: usize,
+    pub area: f64,
+    pub polygons: Vec<Vec<point::Point>>,
+    pub neighbors: Vec<usize>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -19,12 +84,46 @@ pub struct Tetrahedron {
     pub v4: usize,
 }
 
+/* default tag: untagged */
+fn no_tag() -> Option<tag::Tag> {
+    None
+}
+
+/* default domain: single domain */
+fn no_domain() -> Option<usize> {
+    None
+}
+
+/* default component: single component */
+fn no_component() -> Option<usize> {
+    None
+}
+
+/* default metrics: not requested */
+fn no_metrics() -> Option<TriangleMetrics> {
+    None
+}
+
 impl Triangle {
     pub fn new(v1: usize, v2: usize, v3: usize) -> Self {
         Self {
             v1: v1,
             v2: v2,
             v3: v3,
+            tag: None,
+            domain: None,
+            component: None,
+            metrics: None,
+        }
+    }
+}
+
+impl Edge {
+    pub fn new(v1: usize, v2: usize) -> Self {
+        Self {
+            v1: v1,
+            v2: v2,
+            tag: None,
         }
     }
 }
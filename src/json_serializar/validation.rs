@@ -0,0 +1,1010 @@
+use serde_json::{Map, Value};
+
+use std::fmt;
+
+const INPUT_FIELDS: [&str; 5] = ["id", "name", "date", "actions", "params"];
+const ACTION_FIELDS: [&str; 13] = [
+    "intent",
+    "geometry",
+    "scalars",
+    "points",
+    "assemble",
+    "loops",
+    "segments",
+    "tolerance",
+    "pattern",
+    "spacing",
+    "tag",
+    "max_edge_length",
+    "params",
+];
+const REGION_PARAMS_FIELDS: [&str; 3] = ["max_area", "quality", "max_edge_length"];
+const POINT_FIELDS: [&str; 5] = ["x", "y", "z", "id", "metadata"];
+const PARAMS_FIELDS: [&str; 8] = [
+    "profile",
+    "max_area",
+    "quality",
+    "welding_tolerance",
+    "snap_tolerance",
+    "intersection_grid",
+    "simplify_tolerance",
+    "max_edge_length",
+];
+
+const GEOMETRIES: [&str; 10] = [
+    "polyline",
+    "circle",
+    "rectangle",
+    "ellipse",
+    "arc",
+    "bspline",
+    "segments",
+    "vertices",
+    "seed",
+    "seed_point",
+];
+const DOMAIN_INTENTS: [&str; 2] = ["include", "remove"];
+const CONSTRAINT_INTENTS: [&str; 1] = ["constraint"];
+const POLYLINE_INTENTS: [&str; 3] = ["include", "remove", "constraint"];
+const SEED_POINT_INTENTS: [&str; 2] = ["hole", "region"];
+const PROFILES: [&str; 3] = ["draft", "standard", "fine"];
+const SEED_PATTERNS: [&str; 3] = ["grid", "jittered", "poisson"];
+
+/**
+ * Schema violation of an input, located by its json path, such as
+ * `actions[2].points[0].y`.
+ */
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(formatter, "{}: {}", self.path, self.message);
+    }
+}
+
+/**
+ * Checks the input json against the input schema before it is deserialized,
+ * so that every typo, missing field and unknown value is reported at once,
+ * with its location. Returns no diagnostic for a valid input.
+ */
+pub fn validate(input: &Value) -> Vec<Diagnostic> {
+    let mut validator = Validator {
+        diagnostics: Vec::new(),
+    };
+    validator.input(input);
+    return validator.diagnostics;
+}
+
+struct Validator {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator {
+    fn report(&mut self, path: &str, message: String) {
+        self.diagnostics.push(Diagnostic {
+            path: String::from(path),
+            message,
+        });
+    }
+
+    fn input(&mut self, input: &Value) {
+        let input = match self.object("input", input, &INPUT_FIELDS) {
+            Some(input) => input,
+            None => return,
+        };
+
+        for field in ["id", "date"].iter() {
+            if let Some(value) = input.get(*field) {
+                self.string(field, value);
+            }
+        }
+        if let Some(name) = self.required("input", input, "name") {
+            self.string("name", name);
+        }
+        if let Some(params) = self.required("input", input, "params") {
+            self.params(params);
+        }
+        if let Some(actions) = self.required("input", input, "actions") {
+            if let Some(actions) = self.array("actions", actions) {
+                for (index, action) in actions.iter().enumerate() {
+                    self.action(&format!("actions[{}]", index), action);
+                }
+            }
+        }
+    }
+
+    fn params(&mut self, params: &Value) {
+        let params = match self.object("params", params, &PARAMS_FIELDS) {
+            Some(params) => params,
+            None => return,
+        };
+
+        if let Some(profile) = params.get("profile").filter(|value| !value.is_null()) {
+            if let Some(profile) = self.string("params.profile", profile) {
+                self.one_of("params.profile", profile, &PROFILES);
+            }
+        }
+        for field in ["max_area", "quality", "max_edge_length"].iter() {
+            if let Some(value) = params.get(*field).filter(|value| !value.is_null()) {
+                let path = format!("params.{}", field);
+                if let Some(number) = self.number(&path, value) {
+                    if !(number > 0.0) {
+                        self.report(
+                            &path,
+                            format!("expected a positive number, found {}", number),
+                        );
+                    }
+                }
+            }
+        }
+        if let Some(value) = params.get("welding_tolerance") {
+            if let Some(number) = self.number("params.welding_tolerance", value) {
+                if !(number >= 0.0) {
+                    self.report(
+                        "params.welding_tolerance",
+                        format!("expected a non negative number, found {}", number),
+                    );
+                }
+            }
+        }
+        for field in ["snap_tolerance", "intersection_grid", "simplify_tolerance"].iter() {
+            if let Some(value) = params.get(*field).filter(|value| !value.is_null()) {
+                let path = format!("params.{}", field);
+                if let Some(number) = self.number(&path, value) {
+                    if !(number >= 0.0) {
+                        self.report(
+                            &path,
+                            format!("expected a non negative number, found {}", number),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn action(&mut self, path: &str, action: &Value) {
+        let action = match self.object(path, action, &ACTION_FIELDS) {
+            Some(action) => action,
+            None => return,
+        };
+
+        let intent_path = format!("{}.intent", path);
+        let intent = match self.required(path, action, "intent") {
+            Some(intent) => self.string(&intent_path, intent),
+            None => None,
+        };
+        let geometry_path = format!("{}.geometry", path);
+        let geometry = match self.required(path, action, "geometry") {
+            Some(geometry) => match self.string(&geometry_path, geometry) {
+                Some(geometry) if self.one_of(&geometry_path, geometry, &GEOMETRIES) => {
+                    Some(geometry)
+                }
+                _ => None,
+            },
+            None => None,
+        };
+
+        let points = match action.get("points") {
+            Some(points) => self.points(&format!("{}.points", path), points),
+            None => 0,
+        };
+        let scalars = match action.get("scalars") {
+            Some(scalars) => self.scalars(&format!("{}.scalars", path), scalars),
+            None => Vec::new(),
+        };
+        let loops = match action.get("loops") {
+            Some(loops) => self.loops(&format!("{}.loops", path), loops),
+            None => Vec::new(),
+        };
+        let assemble = match action.get("assemble") {
+            Some(assemble) => self.assemble(&format!("{}.assemble", path), assemble),
+            None => Vec::new(),
+        };
+        if let Some(tag) = action.get("tag") {
+            if !(tag.is_null() || tag.is_string() || tag.is_i64()) {
+                self.report(
+                    &format!("{}.tag", path),
+                    format!("expected an integer or a name, found {}", kind(tag)),
+                );
+            }
+        }
+
+        let geometry = match geometry {
+            Some(geometry) => geometry,
+            None => return,
+        };
+        let intents: &[&str] = match geometry {
+            "polyline" => &POLYLINE_INTENTS,
+            "segments" | "vertices" | "seed" => &CONSTRAINT_INTENTS,
+            "seed_point" => &SEED_POINT_INTENTS,
+            _ => &DOMAIN_INTENTS,
+        };
+        if let Some(intent) = intent {
+            self.one_of(&intent_path, intent, intents);
+        }
+
+        let minimum_segments = match geometry {
+            "circle" | "ellipse" => Some(3),
+            "arc" => Some(2),
+            _ => None,
+        };
+        self.discretization(path, action, minimum_segments);
+        self.arrangement(path, action, geometry == "seed");
+
+        self.region_params(path, action, intent == Some("include") || intent == Some("remove"));
+
+        match geometry {
+            "polyline" if intent == Some("constraint") => {
+                if points < 2 {
+                    self.report(
+                        &format!("{}.points", path),
+                        format!("expected at least 2 points, found {}", points),
+                    );
+                }
+                if !loops.is_empty() {
+                    self.report(
+                        &format!("{}.loops", path),
+                        String::from("open polyline constraints have no loops"),
+                    );
+                }
+            }
+            "polyline" => {
+                if loops.is_empty() && points < 3 {
+                    self.report(
+                        &format!("{}.points", path),
+                        format!("expected at least 3 points, found {}", points),
+                    );
+                }
+                for (index, &size) in loops.iter().enumerate() {
+                    if size < 3 {
+                        self.report(
+                            &format!("{}.loops[{}]", path, index),
+                            format!("expected at least 3 points, found {}", size),
+                        );
+                    }
+                }
+            }
+            "seed_point" => {
+                self.center(path, points, "seed");
+                let is_tagged = action.get("tag").map_or(false, |tag| !tag.is_null());
+                if intent == Some("region") && !is_tagged {
+                    self.report(
+                        &format!("{}.tag", path),
+                        String::from("expected the tag of the region"),
+                    );
+                }
+            }
+            "circle" => {
+                self.center(path, points, "center");
+                self.shape_scalars(path, &scalars, &["radius"]);
+                self.positive(path, &scalars, 0, "radius");
+                self.resolution(path, &scalars, 1, 3);
+            }
+            "rectangle" => {
+                self.center(path, points, "corner");
+                self.shape_scalars(path, &scalars, &["width", "height"]);
+                self.positive(path, &scalars, 0, "width");
+                self.positive(path, &scalars, 1, "height");
+                self.resolution(path, &scalars, 2, 1);
+            }
+            "ellipse" => {
+                self.center(path, points, "center");
+                self.shape_scalars(path, &scalars, &["x radius", "y radius"]);
+                self.positive(path, &scalars, 0, "radius");
+                self.positive(path, &scalars, 1, "radius");
+                self.resolution(path, &scalars, 2, 3);
+            }
+            "arc" => {
+                self.center(path, points, "center");
+                self.shape_scalars(path, &scalars, &["radius", "start angle", "end angle"]);
+                self.positive(path, &scalars, 0, "radius");
+                self.resolution(path, &scalars, 3, 2);
+            }
+            "bspline" => {
+                if points < 3 {
+                    self.report(
+                        &format!("{}.points", path),
+                        format!("expected at least 3 control points, found {}", points),
+                    );
+                }
+                self.resolution(path, &scalars, 0, 3);
+                if let Some(&degree) = scalars.get(1) {
+                    let degree = degree.round();
+                    if !(degree >= 1.0 && degree < points.max(2) as f64) {
+                        self.report(
+                            &format!("{}.scalars[1]", path),
+                            format!(
+                                "expected a degree from 1 to {}, found {}",
+                                points.max(2) - 1,
+                                degree
+                            ),
+                        );
+                    }
+                }
+            }
+            "segments" => {
+                if assemble.is_empty() && points % 2 != 0 {
+                    self.report(
+                        &format!("{}.points", path),
+                        format!("expected an even number of points, found {}", points),
+                    );
+                }
+                for (index, set) in assemble.iter().enumerate() {
+                    let set_path = format!("{}.assemble[{}]", path, index);
+                    if set.len() < 2 {
+                        self.report(
+                            &set_path,
+                            format!("expected 2 point indices, found {}", set.len()),
+                        );
+                    }
+                    for point_index in set.iter().filter(|&&point_index| point_index >= points) {
+                        self.report(
+                            &set_path,
+                            format!("point index {} out of the {} points", point_index, points),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /**
+     * The pattern and the required spacing of seed points.
+     */
+    fn arrangement(&mut self, path: &str, action: &Map<String, Value>, is_seed: bool) {
+        let pattern = action.get("pattern").filter(|value| !value.is_null());
+        let spacing = action.get("spacing").filter(|value| !value.is_null());
+        if !is_seed {
+            for (field, value) in vec![("pattern", pattern), ("spacing", spacing)] {
+                if value.is_some() {
+                    self.report(
+                        &format!("{}.{}", path, field),
+                        String::from("only seeds are arranged by it"),
+                    );
+                }
+            }
+            return;
+        }
+
+        if let Some(pattern) = pattern {
+            let pattern_path = format!("{}.pattern", path);
+            if let Some(pattern) = self.string(&pattern_path, pattern) {
+                self.one_of(&pattern_path, pattern, &SEED_PATTERNS);
+            }
+        }
+        let spacing_path = format!("{}.spacing", path);
+        match spacing {
+            Some(spacing) => {
+                if let Some(number) = self.number(&spacing_path, spacing) {
+                    if !(number > 0.0) {
+                        self.report(
+                            &spacing_path,
+                            format!("expected a positive spacing, found {}", number),
+                        );
+                    }
+                }
+            }
+            None => self.report(&spacing_path, String::from("missing field")),
+        }
+    }
+
+    /**
+     * The refine params of the regions added by domain actions, as positive
+     * numbers, along with the max_edge_length shorthand.
+     */
+    fn region_params(&mut self, path: &str, action: &Map<String, Value>, is_domain: bool) {
+        let length = action.get("max_edge_length").filter(|value| !value.is_null());
+        let params = action.get("params").filter(|value| !value.is_null());
+        if !is_domain {
+            for (field, value) in vec![("max_edge_length", length), ("params", params)] {
+                if value.is_some() {
+                    self.report(
+                        &format!("{}.{}", path, field),
+                        String::from("only included or removed regions are refined by it"),
+                    );
+                }
+            }
+            return;
+        }
+
+        let mut numbers: Vec<(String, &Value)> = Vec::new();
+        if let Some(length) = length {
+            numbers.push((format!("{}.max_edge_length", path), length));
+        }
+        if let Some(params) = params {
+            let params_path = format!("{}.params", path);
+            if let Some(params) = self.object(&params_path, params, &REGION_PARAMS_FIELDS) {
+                for field in REGION_PARAMS_FIELDS.iter() {
+                    if let Some(value) = params.get(*field).filter(|value| !value.is_null()) {
+                        numbers.push((format!("{}.{}", params_path, field), value));
+                    }
+                }
+            }
+        }
+        for (number_path, value) in numbers {
+            if let Some(number) = self.number(&number_path, value) {
+                if !(number > 0.0) {
+                    self.report(
+                        &number_path,
+                        format!("expected a positive number, found {}", number),
+                    );
+                }
+            }
+        }
+    }
+
+    /**
+     * Either the count of segments or the chord tolerance of the curved
+     * shapes, which are discretized by at least the minimum segments.
+     */
+    fn discretization(
+        &mut self,
+        path: &str,
+        action: &Map<String, Value>,
+        minimum_segments: Option<usize>,
+    ) {
+        let segments = action.get("segments").filter(|value| !value.is_null());
+        let tolerance = action.get("tolerance").filter(|value| !value.is_null());
+        let minimum_segments = match minimum_segments {
+            Some(minimum_segments) => minimum_segments,
+            None => {
+                for (field, value) in vec![("segments", segments), ("tolerance", tolerance)] {
+                    if value.is_some() {
+                        self.report(
+                            &format!("{}.{}", path, field),
+                            String::from("only circles, ellipses and arcs are discretized by it"),
+                        );
+                    }
+                }
+                return;
+            }
+        };
+
+        if let Some(segments) = segments {
+            let segments_path = format!("{}.segments", path);
+            match segments.as_u64() {
+                Some(count) if count < minimum_segments as u64 => self.report(
+                    &segments_path,
+                    format!("expected at least {} segments, found {}", minimum_segments, count),
+                ),
+                Some(_) => {}
+                None => self.report(
+                    &segments_path,
+                    format!("expected a count of segments, found {}", segments),
+                ),
+            }
+        }
+        if let Some(tolerance) = tolerance {
+            let tolerance_path = format!("{}.tolerance", path);
+            if let Some(number) = self.number(&tolerance_path, tolerance) {
+                if !(number > 0.0) {
+                    self.report(
+                        &tolerance_path,
+                        format!("expected a positive tolerance, found {}", number),
+                    );
+                }
+            }
+            if segments.is_some() {
+                self.report(
+                    &tolerance_path,
+                    String::from("expected either segments or a tolerance, found both"),
+                );
+            }
+        }
+    }
+
+    /**
+     * The single point locating a shape, such as the center of a circle.
+     */
+    fn center(&mut self, path: &str, points: usize, name: &str) {
+        if points != 1 {
+            self.report(
+                &format!("{}.points", path),
+                format!("expected the {} point, found {} points", name, points),
+            );
+        }
+    }
+
+    /**
+     * The scalars of a shape: the required ones, followed by the optional
+     * resolution.
+     */
+    fn shape_scalars(&mut self, path: &str, scalars: &Vec<f64>, required: &[&str]) {
+        if scalars.len() < required.len() {
+            let names: Vec<String> = required.iter().map(|name| format!("the {}", name)).collect();
+            self.report(
+                &format!("{}.scalars", path),
+                format!("expected {}, and optionally the resolution", names.join(", ")),
+            );
+        }
+    }
+
+    fn positive(&mut self, path: &str, scalars: &Vec<f64>, index: usize, name: &str) {
+        if let Some(&value) = scalars.get(index) {
+            if !(value > 0.0) {
+                self.report(
+                    &format!("{}.scalars[{}]", path, index),
+                    format!("expected a positive {}, found {}", name, value),
+                );
+            }
+        }
+    }
+
+    fn resolution(&mut self, path: &str, scalars: &Vec<f64>, index: usize, minimum: usize) {
+        if let Some(&resolution) = scalars.get(index) {
+            if !(resolution.round() >= minimum as f64) {
+                self.report(
+                    &format!("{}.scalars[{}]", path, index),
+                    format!(
+                        "expected a resolution of at least {}, found {}",
+                        minimum, resolution
+                    ),
+                );
+            }
+        }
+    }
+
+    /**
+     * Count of points, valid or not.
+     */
+    fn points(&mut self, path: &str, points: &Value) -> usize {
+        let points = match self.array(path, points) {
+            Some(points) => points,
+            None => return 0,
+        };
+        for (index, point) in points.iter().enumerate() {
+            let point_path = format!("{}[{}]", path, index);
+            if let Some(point) = self.object(&point_path, point, &POINT_FIELDS) {
+                for field in ["x", "y"].iter() {
+                    if let Some(value) = self.required(&point_path, point, field) {
+                        self.number(&format!("{}.{}", point_path, field), value);
+                    }
+                }
+                if let Some(value) = point.get("z") {
+                    self.number(&format!("{}.z", point_path), value);
+                }
+                if let Some(value) = point.get("id") {
+                    self.string(&format!("{}.id", point_path), value);
+                }
+                if let Some(value) = point.get("metadata") {
+                    let metadata_path = format!("{}.metadata", point_path);
+                    match value.as_object() {
+                        Some(metadata) => {
+                            for (name, value) in metadata.iter() {
+                                self.number(&child(&metadata_path, name), value);
+                            }
+                        }
+                        None => self.report(
+                            &metadata_path,
+                            format!("expected an object, found {}", kind(value)),
+                        ),
+                    }
+                }
+            }
+        }
+        return points.len();
+    }
+
+    /**
+     * Point counts of the loops.
+     */
+    fn loops(&mut self, path: &str, loops: &Value) -> Vec<usize> {
+        let loops = match self.array(path, loops) {
+            Some(loops) => loops,
+            None => return Vec::new(),
+        };
+        return loops
+            .iter()
+            .enumerate()
+            .map(|(index, points)| self.points(&format!("{}[{}]", path, index), points))
+            .collect();
+    }
+
+    fn scalars(&mut self, path: &str, scalars: &Value) -> Vec<f64> {
+        let scalars = match self.array(path, scalars) {
+            Some(scalars) => scalars,
+            None => return Vec::new(),
+        };
+        return scalars
+            .iter()
+            .enumerate()
+            .filter_map(|(index, scalar)| self.number(&format!("{}[{}]", path, index), scalar))
+            .collect();
+    }
+
+    fn assemble(&mut self, path: &str, assemble: &Value) -> Vec<Vec<usize>> {
+        let sets = match self.array(path, assemble) {
+            Some(sets) => sets,
+            None => return Vec::new(),
+        };
+        let mut assemble: Vec<Vec<usize>> = Vec::new();
+        for (index, set) in sets.iter().enumerate() {
+            let set_path = format!("{}[{}]", path, index);
+            let set = match self.array(&set_path, set) {
+                Some(set) => set,
+                None => continue,
+            };
+            let mut indices: Vec<usize> = Vec::new();
+            for (position, value) in set.iter().enumerate() {
+                match value.as_u64() {
+                    Some(point_index) => indices.push(point_index as usize),
+                    None => self.report(
+                        &format!("{}[{}]", set_path, position),
+                        format!("expected a point index, found {}", kind(value)),
+                    ),
+                }
+            }
+            assemble.push(indices);
+        }
+        return assemble;
+    }
+
+    /**
+     * The object, reporting its unknown fields, as typos of the expected
+     * ones when they are close enough.
+     */
+    fn object<'a>(
+        &mut self,
+        path: &str,
+        value: &'a Value,
+        fields: &[&str],
+    ) -> Option<&'a Map<String, Value>> {
+        let object = match value.as_object() {
+            Some(object) => object,
+            None => {
+                self.report(path, format!("expected an object, found {}", kind(value)));
+                return None;
+            }
+        };
+        for field in object
+            .keys()
+            .filter(|field| !fields.contains(&field.as_str()))
+        {
+            self.report(
+                &child(path, field),
+                format!("unknown field, {}", expected(field, fields)),
+            );
+        }
+        return Some(object);
+    }
+
+    fn required<'a>(
+        &mut self,
+        path: &str,
+        object: &'a Map<String, Value>,
+        field: &str,
+    ) -> Option<&'a Value> {
+        let value = object.get(field);
+        if value.is_none() {
+            self.report(&child(path, field), String::from("missing field"));
+        }
+        return value;
+    }
+
+    fn array<'a>(&mut self, path: &str, value: &'a Value) -> Option<&'a Vec<Value>> {
+        let array = value.as_array();
+        if array.is_none() {
+            self.report(path, format!("expected an array, found {}", kind(value)));
+        }
+        return array;
+    }
+
+    fn string<'a>(&mut self, path: &str, value: &'a Value) -> Option<&'a str> {
+        let string = value.as_str();
+        if string.is_none() {
+            self.report(path, format!("expected a string, found {}", kind(value)));
+        }
+        return string;
+    }
+
+    fn number(&mut self, path: &str, value: &Value) -> Option<f64> {
+        let number = value.as_f64();
+        if number.is_none() {
+            self.report(path, format!("expected a number, found {}", kind(value)));
+        }
+        return number;
+    }
+
+    fn one_of(&mut self, path: &str, value: &str, allowed: &[&str]) -> bool {
+        if allowed.contains(&value) {
+            return true;
+        }
+        self.report(
+            path,
+            format!("unknown value \"{}\", {}", value, expected(value, allowed)),
+        );
+        return false;
+    }
+} /* end - Validator */
+
+/**
+ * Path of the field of the object at the path, fields of the input being
+ * at the root.
+ */
+fn child(path: &str, field: &str) -> String {
+    if path == "input" {
+        return String::from(field);
+    }
+    return format!("{}.{}", path, field);
+}
+
+/**
+ * Expected values, suggesting the closest one to a typo.
+ */
+fn expected(value: &str, allowed: &[&str]) -> String {
+    let mut message = format!("expected one of {}", allowed.join(", "));
+    let closest = allowed
+        .iter()
+        .map(|candidate| (edit_distance(value, candidate), candidate))
+        .min();
+    if let Some((distance, candidate)) = closest {
+        if distance <= 2 {
+            message.push_str(&format!("; did you mean {}?", candidate));
+        }
+    }
+    return message;
+}
+
+/**
+ * Levenshtein distance between the strings.
+ */
+fn edit_distance(s1: &str, s2: &str) -> usize {
+    let s2: Vec<char> = s2.chars().collect();
+    let mut row: Vec<usize> = (0..=s2.len()).collect();
+    for (i, c1) in s1.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, c2) in s2.iter().enumerate() {
+            let substitution = diagonal + if c1 == *c2 { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    return row[s2.len()];
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => return "null",
+        Value::Bool(_) => return "a boolean",
+        Value::Number(_) => return "a number",
+        Value::String(_) => return "a string",
+        Value::Array(_) => return "an array",
+        Value::Object(_) => return "an object",
+    }
+}
+
+#[cfg(test)]
+mod validate {
+    use super::*;
+
+    fn diagnostics(json: &str) -> Vec<String> {
+        let input: Value = serde_json::from_str(json).unwrap();
+        return validate(&input).iter().map(|d| d.to_string()).collect();
+    }
+
+    #[test]
+    fn valid_input() {
+        let json = "{
+            \"name\": \"square\",
+            \"actions\": [
+                {
+                    \"intent\": \"include\",
+                    \"geometry\": \"polyline\",
+                    \"points\": [
+                        { \"x\": 0.0, \"y\": 0.0 },
+                        { \"x\": 1.0, \"y\": 0.0 },
+                        { \"x\": 1.0, \"y\": 1.0 }
+                    ],
+                    \"tag\": 7,
+                    \"max_edge_length\": 0.2
+                },
+                {
+                    \"intent\": \"constraint\",
+                    \"geometry\": \"segments\",
+                    \"points\": [{ \"x\": 0.2, \"y\": 0.1 }, { \"x\": 0.8, \"y\": 0.1 }],
+                    \"assemble\": [[0, 1]]
+                },
+                {
+                    \"intent\": \"constraint\",
+                    \"geometry\": \"polyline\",
+                    \"points\": [{ \"x\": 0.0, \"y\": 0.5 }, { \"x\": 0.5, \"y\": 0.6 }]
+                },
+                {
+                    \"intent\": \"constraint\",
+                    \"geometry\": \"seed\",
+                    \"pattern\": \"poisson\",
+                    \"spacing\": 0.1
+                },
+                {
+                    \"intent\": \"region\",
+                    \"geometry\": \"seed_point\",
+                    \"points\": [{ \"x\": 0.7, \"y\": 0.2 }],
+                    \"tag\": \"fill\"
+                }
+            ],
+            \"params\": { \"profile\": \"fine\", \"max_area\": null, \"max_edge_length\": 0.5 }
+        }";
+        assert!(diagnostics(json).is_empty());
+    }
+
+    #[test]
+    fn located_diagnostics() {
+        let json = "{
+            \"name\": \"typos\",
+            \"actions\": [
+                {
+                    \"intent\": \"include\",
+                    \"geometry\": \"polilyne\",
+                    \"points\": []
+                },
+                {
+                    \"intent\": \"constraint\",
+                    \"geometry\": \"circle\",
+                    \"point\": [{ \"x\": 0.0, \"y\": 0.0 }],
+                    \"scalars\": [1.0]
+                },
+                {
+                    \"intent\": \"constraint\",
+                    \"geometry\": \"segments\",
+                    \"points\": [{ \"x\": 0.0 }, { \"x\": 1.0, \"y\": \"1\" }],
+                    \"assemble\": [[0, 2]]
+                }
+            ],
+            \"params\": { \"quality\": -1 }
+        }";
+
+        assert_eq!(
+            diagnostics(json),
+            vec![
+                "params.quality: expected a positive number, found -1",
+                "actions[0].geometry: unknown value \"polilyne\", expected one of polyline, \
+                 circle, rectangle, ellipse, arc, bspline, segments, vertices, seed, \
+                 seed_point; did you mean polyline?",
+                "actions[1].point: unknown field, expected one of intent, geometry, scalars, \
+                 points, assemble, loops, segments, tolerance, pattern, spacing, tag, \
+                 max_edge_length, params; did you mean points?",
+                "actions[1].intent: unknown value \"constraint\", expected one of include, remove",
+                "actions[1].points: expected the center point, found 0 points",
+                "actions[2].points[0].y: missing field",
+                "actions[2].points[1].y: expected a number, found a string",
+                "actions[2].assemble[0]: point index 2 out of the 2 points",
+            ]
+        );
+    }
+
+    #[test]
+    fn shape_diagnostics() {
+        let json = "{
+            \"name\": \"shapes\",
+            \"actions\": [
+                {
+                    \"intent\": \"include\",
+                    \"geometry\": \"rectangle\",
+                    \"points\": [{ \"x\": 0.0, \"y\": 0.0 }],
+                    \"scalars\": [4.0, 2.0, 8]
+                },
+                {
+                    \"intent\": \"remove\",
+                    \"geometry\": \"arc\",
+                    \"points\": [{ \"x\": 1.0, \"y\": 1.0 }],
+                    \"scalars\": [0.5, 90],
+                    \"params\": { \"max_area\": 0.0, \"qualty\": 1.0 }
+                },
+                {
+                    \"intent\": \"remove\",
+                    \"geometry\": \"ellipse\",
+                    \"points\": [{ \"x\": 3.0, \"y\": 1.0 }],
+                    \"scalars\": [0.5, 0.0, 2],
+                    \"segments\": 12,
+                    \"tolerance\": -0.1
+                },
+                {
+                    \"intent\": \"include\",
+                    \"geometry\": \"bspline\",
+                    \"points\": [
+                        { \"x\": 5.0, \"y\": 0.0 },
+                        { \"x\": 6.0, \"y\": 0.0 },
+                        { \"x\": 6.0, \"y\": 1.0 }
+                    ],
+                    \"scalars\": [50, 3],
+                    \"segments\": 1.5
+                },
+                {
+                    \"intent\": \"constraint\",
+                    \"geometry\": \"seed\",
+                    \"pattern\": \"hexagonal\",
+                    \"max_edge_length\": 0.1
+                },
+                {
+                    \"intent\": \"region\",
+                    \"geometry\": \"seed_point\",
+                    \"points\": []
+                }
+            ],
+            \"params\": {}
+        }";
+
+        assert_eq!(
+            diagnostics(json),
+            vec![
+                "actions[1].params.qualty: unknown field, expected one of max_area, quality, \
+                 max_edge_length; did you mean quality?",
+                "actions[1].params.max_area: expected a positive number, found 0",
+                "actions[1].scalars: expected the radius, the start angle, the end angle, \
+                 and optionally the resolution",
+                "actions[2].tolerance: expected a positive tolerance, found -0.1",
+                "actions[2].tolerance: expected either segments or a tolerance, found both",
+                "actions[2].scalars[1]: expected a positive radius, found 0",
+                "actions[2].scalars[2]: expected a resolution of at least 3, found 2",
+                "actions[3].segments: only circles, ellipses and arcs are discretized by it",
+                "actions[3].scalars[1]: expected a degree from 1 to 2, found 3",
+                "actions[4].pattern: unknown value \"hexagonal\", expected one of grid, \
+                 jittered, poisson",
+                "actions[4].spacing: missing field",
+                "actions[4].max_edge_length: only included or removed regions are refined by it",
+                "actions[5].points: expected the seed point, found 0 points",
+                "actions[5].tag: expected the tag of the region",
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_input() {
+        assert_eq!(
+            diagnostics("[]"),
+            vec!["input: expected an object, found an array"]
+        );
+        assert_eq!(
+            diagnostics("{ \"name\": 1, \"actions\": {}, \"nmae\": \"\" }"),
+            vec![
+                "nmae: unknown field, expected one of id, name, date, actions, params; \
+                 did you mean name?",
+                "name: expected a string, found a number",
+                "params: missing field",
+                "actions: expected an array, found an object",
+            ]
+        );
+    }
+
+    #[test]
+    fn point_attributes() {
+        let json = "{
+            \"name\": \"attributes\",
+            \"actions\": [
+                {
+                    \"intent\": \"constraint\",
+                    \"geometry\": \"vertices\",
+                    \"points\": [
+                        { \"x\": 0.5, \"y\": 0.5, \"id\": \"a\", \"metadata\": { \"z0\": 1.0 } },
+                        { \"x\": 0.6, \"y\": 0.5, \"id\": 2, \"metadata\": { \"z0\": \"1\" } },
+                        { \"x\": 0.7, \"y\": 0.5, \"metadata\": [1.0] }
+                    ]
+                }
+            ],
+            \"params\": { \"quality\": 1.0 }
+        }";
+
+        assert_eq!(
+            diagnostics(json),
+            vec![
+                "actions[0].points[1].id: expected a string, found a number",
+                "actions[0].points[1].metadata.z0: expected a number, found a string",
+                "actions[0].points[2].metadata: expected an object, found an array",
+            ]
+        );
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("polilyne", "polyline"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("circle", "circle"), 0);
+        assert_eq!(edit_distance("segment", "segments"), 1);
+    }
+} /* end - validate tests */
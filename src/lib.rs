@@ -7,8 +7,10 @@ pub mod elements {
     pub mod bounding_box;
     pub mod edge;
     pub mod polyline;
+    pub mod segment_index;
     pub mod triangle;
     pub mod vertex;
+    pub mod vertex_pool;
 }
 
 /* Geometric Behaviour/properties implementation */
@@ -17,6 +19,7 @@ pub mod properties {
     pub mod area;
     pub mod circumcenter;
     pub mod continence;
+    pub mod convex_hull;
     pub mod distance;
     pub mod dot;
     pub mod encroachment;
@@ -28,10 +31,41 @@ pub mod properties {
 
 /* Data structure that resumes lib main output */
 pub mod planar {
+    pub mod adjacency;
+    pub mod binary_mesh;
+    pub mod checkpoint;
+    pub mod compact_triangulation;
+    pub mod comparison;
+    pub mod conforming_mode;
+    pub mod constraint_chain;
+    pub mod debug_dump;
+    pub mod dual_mesh;
+    pub mod generators;
+    pub mod insertion_order;
+    pub mod interpolation;
+    pub mod io;
+    #[cfg(feature = "websocket")]
+    pub mod live_view;
+    pub mod progress;
+    #[cfg(all(test, feature = "properties"))]
+    mod property_tests;
+    pub mod provenance;
+    pub mod quality_report;
     pub mod refine_params;
+    pub mod render;
+    pub mod shapes;
+    pub mod smoothing;
+    pub mod spatial_index;
+    pub mod stats;
+    pub mod structured;
+    pub mod tiling;
+    pub mod timings;
     pub mod triangulation;
     pub mod triangulation_data;
+    pub mod triangulation_error;
     pub mod triangulator;
+    pub mod validation;
+    pub mod vertex_attributes;
     pub mod triangulation_procedures {
         pub mod boundary;
         pub mod hole;
@@ -44,6 +78,13 @@ pub mod planar {
     }
 }
 
+/* Javascript bindings */
+pub mod wasm;
+
+/* C bindings */
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
 // ================= //
 //      EXPORTS      //
 // ================= //
@@ -52,11 +93,21 @@ pub use crate::elements::{
     polyline::Polyline,
     triangle::Triangle,
     vertex::Vertex,
+    vertex_pool::VertexPool,
     bounding_box::BoundingBox,
 };
 
 pub use crate::planar::{
     triangulation::Triangulation, 
+    compact_triangulation::CompactTriangulation,
+    comparison::Comparison,
     triangulator::Triangulator,
-    refine_params::RefineParams,
+    refine_params::{RefineParams, RefineProfile},
+    conforming_mode::ConformingMode,
+    quality_report::QualityReport,
+    provenance::Provenance,
+    spatial_index::SpatialIndex,
+    stats::Stats,
+    triangulation_error::TriangulationError,
+    validation::InvariantViolation,
 };
@@ -2,71 +2,284 @@ extern crate structopt;
 extern crate structopt_derive;
 use structopt::StructOpt;
 
+use nlsn_delaunay::planar::{binary_mesh::BinaryMesh, io, triangulation_error::TriangulationError};
+
+use std::io::Write;
+
 #[derive(StructOpt, Debug)]
 #[structopt(
     name = "nlsn-delaunay",
     author = "Nelson Kenzo Tamashiro <nelsonkenzotamashiro@gmail.com>",
     version = "0.1.0",
-    about = "Delaunay Triangulation and Refinement cli-tool"
+    about = "Delaunay Triangulation and Refinement cli-tool",
+    setting = structopt::clap::AppSettings::SubcommandsNegateReqs
 )]
 pub struct CliOptions {
-    #[structopt(short, long, help = "input filename")]
-    input: String,
+    #[structopt(short, long, required_unless = "input-dir", help = "input filename: json, dxf or shp")]
+    input: Option<String>,
 
     #[structopt(short, long, help = "output filename")]
     output: Option<String>,
 
+    #[structopt(
+        long,
+        conflicts_with = "input",
+        requires = "output-dir",
+        help = "processes every json input of this directory"
+    )]
+    input_dir: Option<String>,
+
+    #[structopt(long, help = "writes batch outputs, named after their inputs, to this directory")]
+    output_dir: Option<String>,
+
+    #[structopt(
+        long,
+        default_value = "1",
+        help = "inputs processed in parallel in batch mode, or tiles"
+    )]
+    jobs: usize,
+
     #[structopt(short, long, help = "displays triangulation result in opengl window")]
     show: bool,
+
+    #[structopt(long, help = "draws the input geometry over the displayed triangulation")]
+    overlay: bool,
+
+    #[structopt(
+        long,
+        possible_values = &["quality", "area"],
+        help = "fills displayed triangles colored by quality or area; W toggles wireframe, M metric"
+    )]
+    fill: Option<String>,
+
+    #[structopt(
+        short,
+        long,
+        help = "refines square tiles of this size on threads, then the stitched mesh by quality"
+    )]
+    tile_size: Option<f64>,
+
+    #[structopt(
+        long,
+        help = "keeps inclusion polylines as domains sharing interfaces, labeling triangles"
+    )]
+    domains: bool,
+
+    #[structopt(
+        long,
+        help = "pre-splits constraints longer than this length before triangulation"
+    )]
+    max_constraint_length: Option<f64>,
+
+    #[structopt(
+        long,
+        help = "keeps adjacency in u32 handles, saving memory on meshes of millions of triangles"
+    )]
+    compact_adjacency: bool,
+
+    #[structopt(
+        long,
+        help = "optimizes the refined mesh by this many ODT smoothing iterations"
+    )]
+    odt: Option<usize>,
+
+    #[structopt(
+        long,
+        help = "refine profile: draft, standard or fine, overriding the input profile"
+    )]
+    profile: Option<String>,
+
+    #[structopt(long, help = "maximum triangle area, overriding the input params")]
+    max_area: Option<f64>,
+
+    #[structopt(long, help = "radius-edge ratio bound, overriding the input params")]
+    quality_ratio: Option<f64>,
+
+    #[structopt(
+        long,
+        conflicts_with = "quality-ratio",
+        help = "minimum angle in degrees, as a radius-edge ratio overriding the input params"
+    )]
+    min_angle: Option<f64>,
+
+    #[structopt(
+        long,
+        help = "persists refinement progress to this file, resuming from it when it exists"
+    )]
+    checkpoint: Option<String>,
+
+    #[structopt(
+        long,
+        help = "renders the triangulation to this svg or png file, without opening a window"
+    )]
+    render: Option<String>,
+
+    #[structopt(
+        long,
+        help = "dumps numbered svg and json snapshots after each phase to this directory"
+    )]
+    debug_dir: Option<String>,
+
+    #[structopt(long, help = "prints mesh quality statistics to stderr")]
+    stats: bool,
+
+    #[structopt(long, help = "prints time spent on each triangulation phase to stderr")]
+    timings: bool,
+
+    #[structopt(
+        long,
+        help = "validates the triangulation invariants, failing on any violation"
+    )]
+    check: bool,
+
+    #[structopt(long, help = "includes Voronoi cell areas and dual face lengths in json output")]
+    dual: bool,
+
+    #[structopt(
+        long,
+        help = "includes Voronoi cells clipped by regions and holes in json output"
+    )]
+    voronoi: bool,
+
+    #[structopt(
+        long,
+        help = "includes area, min angle, quality and circumradius of each triangle in json output, and vertex quality in ply output"
+    )]
+    with_metrics: bool,
+
+    #[structopt(
+        short,
+        long,
+        default_value = "json",
+        possible_values = &["json", "msh", "msh4", "msh41", "msh2", "msh22", "bin", "stl", "ply"],
+        help = "output format: json, msh (gmsh 4.1), msh22 (gmsh 2.2), bin (see BinaryMesh), stl or ply"
+    )]
+    format: String,
+
+    #[structopt(
+        long,
+        number_of_values = 1,
+        help = "maps a dxf input layer onto an intent, as LAYER=INTENT, * standing for other layers"
+    )]
+    dxf_layer: Vec<String>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(StructOpt, Debug)]
+enum Command {
+    #[structopt(
+        about = "compares two json triangulation outputs, exiting with status 3 if they differ"
+    )]
+    Compare {
+        #[structopt(help = "first output filename")]
+        first: String,
+
+        #[structopt(help = "second output filename")]
+        second: String,
+    },
 }
 
+mod batch;
+#[cfg(all(test, feature = "conformance"))]
+mod conformance;
+mod dxf_serializar;
 mod glium_interface;
 mod json_serializar;
+mod msh_serializar;
+mod shp_serializar;
 mod triangulator_interface;
 
+/* Triangle splits between checkpoint writes */
+const CHECKPOINT_INTERVAL: usize = 1000;
+
+/* Exit status of errors, of batches with failed inputs, and of compared outputs that differ */
+const EXIT_SUCCESS: i32 = 0;
+const EXIT_ERROR: i32 = 1;
+const EXIT_BATCH_FAILED: i32 = 2;
+const EXIT_DIFFERENT: i32 = 3;
+
 fn main() {
+    /* RUST_LOG=nlsn_delaunay=trace logs the library internals to stderr */
+    env_logger::init();
     let options: CliOptions = CliOptions::from_args();
-    
-    let file_path_string = options.input;
 
-    let file_path = std::path::Path::new(&file_path_string);
-    let triangulation_input = match json_serializar::io::read(file_path) {
-        Some(triangulation_input) => triangulation_input,
-        None => {
-            panic!("Failed to deserialize triangulation json data");
+    match run(options) {
+        Ok(EXIT_SUCCESS) => {}
+        Ok(status) => std::process::exit(status),
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(EXIT_ERROR);
         }
-    };
+    }
+}
 
-    let (mut triangulator, refine_params) =
-        match triangulator_interface::parse(&triangulation_input) {
-            Ok((triangulator, refine_params)) => (triangulator, refine_params),
-            Err(_) => {
-                panic!("Failed to parse triangulation input data");
-            }
-        };
+/**
+ * Runs the options, returning the exit status of runs that completed.
+ */
+fn run(options: CliOptions) -> Result<i32, TriangulationError> {
+    if let Some(Command::Compare { first, second }) = &options.command {
+        return compare(std::path::Path::new(first), std::path::Path::new(second));
+    }
+    if let Some(input_dir) = &options.input_dir {
+        return run_batch(&options, std::path::Path::new(input_dir));
+    }
 
-    triangulator.triangulate();
-    triangulator.refine(refine_params);
+    let file_path_string = options.input.clone().unwrap();
+    let file_path = std::path::Path::new(&file_path_string);
+    let (triangulation_input, triangulator, refine_params) = triangulate(&options, file_path)?;
 
-    let output_triangulation =
-        json_serializar::models::output::TriangulationOutput::from_triangulator(
-            &triangulation_input,
-            &triangulator,
+    if options.stats {
+        let report = triangulator.triangulation.borrow().quality_report();
+        let min_angle = refine_params.min_angle();
+        eprint!("{}", report);
+        eprintln!(
+            "Triangles below {:.4} degrees: {}",
+            min_angle,
+            report.count_triangles_below_angle(min_angle)
         );
+        if let Some(max_area) = refine_params.max_area {
+            eprintln!(
+                "Triangles above {:e} area: {}",
+                max_area,
+                report.count_triangles_above_area(max_area)
+            );
+        }
+        eprint!("{}", region_properties("Boundary", &triangulator.boundary));
+        for boundary in triangulator.disjoint_boundaries.iter() {
+            eprint!("{}", region_properties("Boundary", boundary));
+        }
+        for hole in triangulator.holes.iter() {
+            eprint!("{}", region_properties("Hole", hole));
+        }
+    }
 
-    let output_string = serde_json::to_string_pretty(&output_triangulation).unwrap();
+    if options.timings {
+        eprint!("{}", triangulator.timings());
+    }
+    let output_bytes = output_bytes(&options, &triangulation_input, &triangulator)?;
 
     if let Some(output_path_string) = options.output {
         let file_path = std::path::Path::new(&output_path_string);
-
-        match json_serializar::io::write(&file_path, output_string) {
-            Ok(_) => {}
-            Err(_) => {
-                panic!("Failed to write triangulation output to file");
-            }
-        }
+        json_serializar::io::write(&file_path, output_bytes)?;
     } else {
-        println!("{}", output_string);
+        let mut stdout = std::io::stdout();
+        stdout.write_all(&output_bytes)?;
+        if !output_bytes.ends_with(b"\n") && options.format != "bin" {
+            writeln!(stdout)?;
+        }
+    }
+
+    if let Some(render_path_string) = options.render {
+        render(std::path::Path::new(&render_path_string), &triangulator)?;
+    }
+
+    if options.check {
+        triangulator
+            .validate()
+            .map_err(TriangulationError::InvalidTriangulation)?;
+        eprintln!("Triangulation invariants hold");
     }
 
     if options.show {
@@ -74,7 +287,404 @@ fn main() {
         let edges_data = glium_interface::vertex::Vertex::edges_from_triangulation(
             &triangulator.triangulation.borrow(),
         );
-        glium_interface::edges::draw((display, event_loop), edges_data, 1.0);
-        
+        let triangles: Vec<std::rc::Rc<nlsn_delaunay::Triangle>> = triangulator
+            .triangulation
+            .borrow()
+            .solid_triangles()
+            .cloned()
+            .collect();
+        if let Some(metric_name) = options.fill {
+            let metric = match glium_interface::colormap::Metric::from_name(&metric_name) {
+                Some(metric) => metric,
+                None => {
+                    return Err(invalid_input(format!("unknown fill metric {}", metric_name)));
+                }
+            };
+            /* the chosen metric first, then the others, as switched by M */
+            let mut metrics = vec![
+                glium_interface::colormap::Metric::Quality,
+                glium_interface::colormap::Metric::Area,
+            ];
+            metrics.retain(|m| *m != metric);
+            metrics.insert(0, metric);
+            let fills = metrics
+                .into_iter()
+                .map(|metric| {
+                    let (vertices, range) = glium_interface::colormap::colored_triangles(
+                        &triangulator.triangulation.borrow(),
+                        metric,
+                    );
+                    let title = glium_interface::colormap::legend_title(metric, range);
+                    eprintln!("{}", title);
+                    return (vertices, title);
+                })
+                .collect();
+            let mut layers = vec![(edges_data, glium_interface::edges::BLACK)];
+            if options.overlay {
+                let input_data =
+                    glium_interface::vertex::Vertex::edges_from_input(&triangulation_input);
+                layers.push((input_data, glium_interface::edges::RED));
+            }
+            glium_interface::triangles::draw_filled(
+                (display, event_loop),
+                fills,
+                layers,
+                triangles,
+                1.0,
+            );
+        } else if options.overlay {
+            let input_data =
+                glium_interface::vertex::Vertex::edges_from_input(&triangulation_input);
+            glium_interface::edges::draw_layers(
+                (display, event_loop),
+                vec![
+                    (edges_data, glium_interface::edges::BLACK),
+                    (input_data, glium_interface::edges::RED),
+                ],
+                triangles,
+                1.0,
+            );
+        } else {
+            glium_interface::edges::draw((display, event_loop), edges_data, triangles, 1.0);
+        }
+    }
+
+    return Ok(EXIT_SUCCESS);
+}
+
+/**
+ * Area, centroid and centroidal second moments of a closed input polyline,
+ * as reported by --stats.
+ */
+fn region_properties(name: &str, polyline: &nlsn_delaunay::Polyline) -> String {
+    match (polyline.area(), polyline.centroid(), polyline.second_moments()) {
+        (Some(area), Some(centroid), Some((ixx, iyy, ixy))) => {
+            return format!(
+                "{} area: {:.6}, centroid: {}, Ixx: {:.6}, Iyy: {:.6}, Ixy: {:.6}\n",
+                name,
+                area.abs(),
+                centroid,
+                ixx.abs(),
+                iyy.abs(),
+                ixy * area.signum()
+            );
+        }
+        _ => return format!("{} area: 0\n", name),
     }
 }
+
+/**
+ * Reads, triangulates and refines the input file, as set by the options.
+ */
+fn triangulate(
+    options: &CliOptions,
+    file_path: &std::path::Path,
+) -> Result<
+    (
+        json_serializar::models::input::TriangulationInput,
+        nlsn_delaunay::Triangulator,
+        nlsn_delaunay::RefineParams,
+    ),
+    TriangulationError,
+> {
+    let mut triangulation_input = match file_path.extension() {
+        Some(extension) if extension.eq_ignore_ascii_case("dxf") => {
+            let intents = dxf_serializar::LayerIntents::parse(&options.dxf_layer)?;
+            dxf_serializar::read(file_path, &intents)?
+        }
+        Some(extension) if extension.eq_ignore_ascii_case("shp") => {
+            shp_serializar::read(file_path)?
+        }
+        _ => json_serializar::io::read(file_path)?,
+    };
+    if let Some(profile) = &options.profile {
+        triangulation_input.params.profile = Some(profile.clone());
+    }
+    triangulator_interface::interpreter::refine_params_parser::override_params(
+        &mut triangulation_input.params,
+        options.max_area,
+        options.quality_ratio,
+        options.min_angle,
+    )?;
+
+    let (mut components, refine_params) = triangulator_interface::parse(&triangulation_input)?;
+    if components.len() > 1 && options.checkpoint.is_some() {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "--checkpoint applies to a single connected domain",
+        )));
+    }
+    let domains = match options.domains {
+        true => Some(triangulator_interface::inclusion_domains(&triangulation_input)?),
+        false => None,
+    };
+
+    /* disconnected domains are meshed apart, then joined as components */
+    let is_disconnected = components.len() > 1;
+    for (index, component) in components.iter_mut().enumerate() {
+        let debug_dir = options.debug_dir.as_ref().map(|debug_dir| {
+            let debug_dir = std::path::Path::new(debug_dir);
+            match is_disconnected {
+                true => debug_dir.join(format!("component_{}", index)),
+                false => debug_dir.to_path_buf(),
+            }
+        });
+        triangulate_component(options, component, &refine_params, &domains, debug_dir)?;
+    }
+    let mut triangulator = components.remove(0);
+    for component in components.into_iter() {
+        triangulator.include_component(component)?;
+    }
+
+    return Ok((triangulation_input, triangulator, refine_params));
+}
+
+/**
+ * Triangulates, refines and optimizes a connected component of the input,
+ * as set by the options.
+ */
+fn triangulate_component(
+    options: &CliOptions,
+    triangulator: &mut nlsn_delaunay::Triangulator,
+    refine_params: &nlsn_delaunay::RefineParams,
+    domains: &Option<Vec<std::rc::Rc<nlsn_delaunay::Polyline>>>,
+    debug_dir: Option<std::path::PathBuf>,
+) -> Result<(), TriangulationError> {
+    triangulator.max_constraint_length = options.max_constraint_length;
+    triangulator.compact_adjacency = options.compact_adjacency;
+    if let Some(debug_dir) = &debug_dir {
+        /* fails early on directories that cannot be written */
+        std::fs::create_dir_all(debug_dir)?;
+        triangulator.debug_observer = Some(Box::new(
+            nlsn_delaunay::planar::debug_dump::DirectoryDump::new(debug_dir),
+        ));
+    }
+
+    if let Some(domains) = domains {
+        triangulator.insert_domains(domains)?;
+    }
+
+    if let Some(tile_size) = options.tile_size {
+        triangulator.try_triangulate_tiled(tile_size, refine_params.clone(), options.jobs)?;
+    } else if let Some(checkpoint_path_string) = &options.checkpoint {
+        let checkpoint_path = std::path::Path::new(checkpoint_path_string);
+        if checkpoint_path.exists() {
+            let mut file = std::fs::File::open(checkpoint_path)?;
+            triangulator.resume(&mut file)?;
+        } else {
+            triangulator.try_triangulate()?;
+        }
+
+        let refined = triangulator
+            .try_refine_observed(refine_params.clone(), &mut |triangulation, splits| {
+                if splits % CHECKPOINT_INTERVAL == 0 {
+                    if let Err(error) =
+                        write_checkpoint(checkpoint_path, triangulation, refine_params)
+                    {
+                        eprintln!("failed to write checkpoint: {}", error);
+                    }
+                }
+            })
+            .map(|_| ());
+        let mut file = std::fs::File::create(checkpoint_path)?;
+        triangulator.save_checkpoint(&mut file)?;
+        refined?;
+    } else {
+        triangulator.try_triangulate()?;
+        triangulator.try_refine(refine_params.clone())?;
+    }
+
+    if let Some(iterations) = options.odt {
+        triangulator.optimize(iterations);
+    }
+    return Ok(());
+}
+
+/**
+ * Prints the differences between two json outputs, returning the different
+ * status if they differ.
+ */
+fn compare(first: &std::path::Path, second: &std::path::Path) -> Result<i32, TriangulationError> {
+    let first_triangulation = json_serializar::io::read_output(first)?.to_triangulation()?;
+    let second_triangulation = json_serializar::io::read_output(second)?.to_triangulation()?;
+    let comparison = nlsn_delaunay::Comparison::new(&first_triangulation, &second_triangulation);
+    print!("{}", comparison);
+
+    if !comparison.is_identical() {
+        eprintln!("{} and {} differ", first.display(), second.display());
+        return Ok(EXIT_DIFFERENT);
+    }
+    return Ok(EXIT_SUCCESS);
+}
+
+/**
+ * Triangulation serialized in the output format of the options, as text
+ * except for the bin format.
+ */
+fn output_bytes(
+    options: &CliOptions,
+    triangulation_input: &json_serializar::models::input::TriangulationInput,
+    triangulator: &nlsn_delaunay::Triangulator,
+) -> Result<Vec<u8>, TriangulationError> {
+    return Ok(match options.format.as_str() {
+        "json" => {
+            let mut output_triangulation =
+                json_serializar::models::output::TriangulationOutput::from_triangulator(
+                    &triangulation_input,
+                    &triangulator,
+                );
+            if options.dual {
+                output_triangulation.include_dual(&triangulator);
+            }
+            if options.voronoi {
+                output_triangulation.include_voronoi_cells(&triangulator);
+            }
+            if options.with_metrics {
+                output_triangulation.include_metrics();
+            }
+            output_triangulation.canonicalize()?;
+            serde_json::to_string_pretty(&output_triangulation)
+                .unwrap()
+                .into_bytes()
+        }
+        "bin" => {
+            let mesh = BinaryMesh::from_triangulation(&triangulator.triangulation.borrow())?;
+            let mut bytes: Vec<u8> = Vec::new();
+            mesh.write(&mut bytes)?;
+            bytes
+        }
+        "stl" => {
+            let mut bytes: Vec<u8> = Vec::new();
+            io::write_stl(&triangulator.triangulation.borrow(), &mut bytes)?;
+            bytes
+        }
+        "ply" => {
+            let triangulation = triangulator.triangulation.borrow();
+            let quality = if options.with_metrics {
+                Some(io::VertexScalar::quality(&triangulation))
+            } else {
+                None
+            };
+            let mut bytes: Vec<u8> = Vec::new();
+            io::write_ply(&triangulation, quality.as_ref(), &mut bytes)?;
+            bytes
+        }
+        format => match msh_serializar::MshVersion::from_format(format) {
+            Some(version) => msh_serializar::MshMesh::from_triangulator(&triangulator)
+                .to_string(version)
+                .into_bytes(),
+            None => {
+                return Err(invalid_input(format!("unknown output format {}", format)));
+            }
+        },
+    });
+}
+
+/**
+ * Processes every json input of the directory into the output directory,
+ * printing a summary table. Returns the batch failed status if any input
+ * fails.
+ */
+fn run_batch(options: &CliOptions, input_dir: &std::path::Path) -> Result<i32, TriangulationError> {
+    if options.checkpoint.is_some()
+        || options.render.is_some()
+        || options.debug_dir.is_some()
+        || options.show
+    {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "--checkpoint, --render, --debug-dir and --show apply to a single input",
+        )));
+    }
+    let extension = match options.format.as_str() {
+        "json" => "json",
+        "bin" => "bin",
+        "stl" => "stl",
+        "ply" => "ply",
+        _ => "msh",
+    };
+
+    let output_dir = std::path::Path::new(options.output_dir.as_ref().unwrap());
+    std::fs::create_dir_all(output_dir)?;
+    let inputs = batch::discover(input_dir)?;
+
+    let reports = batch::process(&inputs, options.jobs, |input| {
+        let process = || -> Result<(usize, usize), TriangulationError> {
+            let (triangulation_input, triangulator, _) = triangulate(options, input)?;
+            let output_bytes = output_bytes(options, &triangulation_input, &triangulator)?;
+            let output_path = batch::output_path(input, output_dir, extension);
+            json_serializar::io::write(&output_path, output_bytes)?;
+            if options.check {
+                triangulator
+                    .validate()
+                    .map_err(TriangulationError::InvalidTriangulation)?;
+            }
+
+            let triangulation = triangulator.triangulation.borrow();
+            let triangles = triangulation.solid_triangles().count();
+            return Ok((triangulation.vertices().len(), triangles));
+        };
+        return process().map_err(|error| error.to_string());
+    });
+    print!("{}", batch::summary(&reports));
+
+    let failed = reports.iter().filter(|report| report.result.is_err()).count();
+    if failed > 0 {
+        eprintln!("{} of {} inputs failed", failed, reports.len());
+        return Ok(EXIT_BATCH_FAILED);
+    }
+    return Ok(EXIT_SUCCESS);
+}
+
+fn render(
+    path: &std::path::Path,
+    triangulator: &nlsn_delaunay::Triangulator,
+) -> Result<(), TriangulationError> {
+    use nlsn_delaunay::planar::render;
+
+    let options = render::RenderOptions::default();
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("svg") => {
+            std::fs::write(path, render::svg(triangulator, &options))?;
+        }
+        #[cfg(feature = "raster")]
+        Some("png") => {
+            let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+            render::png(triangulator, &options, &mut file)?;
+        }
+        #[cfg(not(feature = "raster"))]
+        Some("png") => {
+            return Err(invalid_input(String::from(
+                "png rendering requires the raster feature",
+            )));
+        }
+        _ => {
+            return Err(invalid_input(format!("unknown render format {}", path.display())));
+        }
+    }
+    return Ok(());
+}
+
+/**
+ * Error of options that cannot be carried out, as found past the option
+ * parsing.
+ */
+fn invalid_input(message: String) -> TriangulationError {
+    return TriangulationError::IoError(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        message,
+    ));
+}
+
+/**
+ * Writes the checkpoint next to its path first, then replaces the former
+ * checkpoint, so that an interrupted write keeps the former one.
+ */
+fn write_checkpoint(
+    path: &std::path::Path,
+    triangulation: &nlsn_delaunay::planar::triangulation::Triangulation,
+    params: &nlsn_delaunay::planar::refine_params::RefineParams,
+) -> std::io::Result<()> {
+    let temporary_path = path.with_extension("tmp");
+    let mut file = std::fs::File::create(&temporary_path)?;
+    nlsn_delaunay::planar::checkpoint::write(&mut file, triangulation, Some(params))?;
+    return std::fs::rename(&temporary_path, path);
+}
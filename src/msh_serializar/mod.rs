@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::rc::Rc;
+
+use crate::triangulator_interface;
+
+use nlsn_delaunay::{
+    elements::{edge::*, vertex::*},
+    planar::triangulator::Triangulator,
+};
+
+/**
+ * Gmsh mesh file versions, named as gmsh `-format` option does.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MshVersion {
+    V22,
+    V41,
+}
+
+impl MshVersion {
+    pub fn from_format(format: &str) -> Option<Self> {
+        match format {
+            "msh" | "msh4" | "msh41" => return Some(MshVersion::V41),
+            "msh2" | "msh22" => return Some(MshVersion::V22),
+            _ => return None,
+        }
+    }
+}
+
+/* Physical group tags, which are also used as entity tags */
+const BOUNDARY_TAG: usize = 1;
+const HOLES_TAG: usize = 2;
+const SEGMENTS_TAG: usize = 3;
+const DOMAIN_TAG: usize = 4;
+
+/* Gmsh element types */
+const LINE_TYPE: usize = 1;
+const TRIANGLE_TYPE: usize = 2;
+
+/**
+ * Indexed mesh, as written to gmsh files. Constrained segments are written
+ * as 2-node lines, split at every mesh vertex lying on them, and grouped
+ * into the boundary, holes and segments physical curves. Triangles are
+ * grouped into the domain physical surface. Node tags start at 1.
+ */
+pub struct MshMesh {
+    pub nodes: Vec<Rc<Vertex>>,
+    pub curves: Vec<(usize, &'static str, Vec<(usize, usize)>)>,
+    pub triangles: Vec<(usize, usize, usize)>,
+}
+
+impl MshMesh {
+    pub fn from_triangulator(triangulator: &Triangulator) -> Self {
+        let triangulation = triangulator.triangulation.borrow();
+
+        let nodes: Vec<Rc<Vertex>> = triangulation.vertices().iter().cloned().collect();
+        let node_tags: HashMap<Rc<Vertex>, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, vertex)| (Rc::clone(vertex), index + 1))
+            .collect();
+
+        let edge_nodes = |edges: Vec<Rc<Edge>>| -> Vec<(usize, usize)> {
+            return edges
+                .iter()
+                .filter_map(|e| Some((*node_tags.get(&e.v1)?, *node_tags.get(&e.v2)?)))
+                .collect();
+        };
+
+        let (boundary_edges, holes_edges, segments_edges) =
+            triangulator_interface::constrained_edges(triangulator);
+
+        let curves = vec![
+            (BOUNDARY_TAG, "boundary", edge_nodes(boundary_edges)),
+            (HOLES_TAG, "holes", edge_nodes(holes_edges)),
+            (SEGMENTS_TAG, "segments", edge_nodes(segments_edges)),
+        ]
+        .into_iter()
+        .filter(|(_, _, lines)| !lines.is_empty())
+        .collect();
+
+        let triangles = triangulation
+            .solid_triangles()
+            .map(|t| {
+                let t = t.ensure_ccw();
+                let v1 = node_tags.get(&t.v1).unwrap();
+                let v2 = node_tags.get(&t.v2).unwrap();
+                let v3 = node_tags.get(&t.v3).unwrap();
+                return (*v1, *v2, *v3);
+            })
+            .collect();
+
+        return Self {
+            nodes,
+            curves,
+            triangles,
+        };
+    } /* end - from_triangulator */
+
+    pub fn to_string(&self, version: MshVersion) -> String {
+        let mut msh = String::new();
+        match version {
+            MshVersion::V22 => self.write_v22(&mut msh).unwrap(),
+            MshVersion::V41 => self.write_v41(&mut msh).unwrap(),
+        }
+        return msh;
+    }
+
+    fn write_physical_names(&self, msh: &mut String) -> std::fmt::Result {
+        writeln!(msh, "$PhysicalNames")?;
+        writeln!(msh, "{}", self.curves.len() + 1)?;
+        for (tag, name, _) in self.curves.iter() {
+            writeln!(msh, "1 {} \"{}\"", tag, name)?;
+        }
+        writeln!(msh, "2 {} \"domain\"", DOMAIN_TAG)?;
+        return writeln!(msh, "$EndPhysicalNames");
+    }
+
+    fn write_v22(&self, msh: &mut String) -> std::fmt::Result {
+        writeln!(msh, "$MeshFormat\n2.2 0 8\n$EndMeshFormat")?;
+        self.write_physical_names(msh)?;
+
+        writeln!(msh, "$Nodes\n{}", self.nodes.len())?;
+        for (index, vertex) in self.nodes.iter().enumerate() {
+            writeln!(msh, "{} {} {} 0", index + 1, vertex.x, vertex.y)?;
+        }
+        writeln!(msh, "$EndNodes")?;
+
+        let lines_count: usize = self.curves.iter().map(|(_, _, lines)| lines.len()).sum();
+        writeln!(msh, "$Elements\n{}", lines_count + self.triangles.len())?;
+
+        /* element tags: physical and elementary entity */
+        let mut element_tag: usize = 0;
+        for (tag, _, lines) in self.curves.iter() {
+            for (v1, v2) in lines.iter() {
+                element_tag += 1;
+                writeln!(msh, "{} {} 2 {} {} {} {}", element_tag, LINE_TYPE, tag, tag, v1, v2)?;
+            }
+        }
+        for (v1, v2, v3) in self.triangles.iter() {
+            element_tag += 1;
+            writeln!(
+                msh,
+                "{} {} 2 {} {} {} {} {}",
+                element_tag, TRIANGLE_TYPE, DOMAIN_TAG, DOMAIN_TAG, v1, v2, v3
+            )?;
+        }
+        return writeln!(msh, "$EndElements");
+    }
+
+    fn write_v41(&self, msh: &mut String) -> std::fmt::Result {
+        writeln!(msh, "$MeshFormat\n4.1 0 8\n$EndMeshFormat")?;
+        self.write_physical_names(msh)?;
+
+        let (min_x, min_y, max_x, max_y) = self.bounding_box();
+
+        /* one curve entity per physical curve, and a single surface */
+        writeln!(msh, "$Entities\n0 {} 1 0", self.curves.len())?;
+        for (tag, _, _) in self.curves.iter() {
+            writeln!(
+                msh,
+                "{} {} {} 0 {} {} 0 1 {} 0",
+                tag, min_x, min_y, max_x, max_y, tag
+            )?;
+        }
+        write!(
+            msh,
+            "{} {} {} 0 {} {} 0 1 {} {}",
+            DOMAIN_TAG,
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            DOMAIN_TAG,
+            self.curves.len()
+        )?;
+        for (tag, _, _) in self.curves.iter() {
+            write!(msh, " {}", tag)?;
+        }
+        writeln!(msh, "\n$EndEntities")?;
+
+        /* every node is classified on the surface entity */
+        writeln!(msh, "$Nodes")?;
+        writeln!(msh, "1 {} 1 {}", self.nodes.len(), self.nodes.len())?;
+        writeln!(msh, "2 {} 0 {}", DOMAIN_TAG, self.nodes.len())?;
+        for index in 0..self.nodes.len() {
+            writeln!(msh, "{}", index + 1)?;
+        }
+        for vertex in self.nodes.iter() {
+            writeln!(msh, "{} {} 0", vertex.x, vertex.y)?;
+        }
+        writeln!(msh, "$EndNodes")?;
+
+        let lines_count: usize = self.curves.iter().map(|(_, _, lines)| lines.len()).sum();
+        let elements_count = lines_count + self.triangles.len();
+        writeln!(msh, "$Elements")?;
+        writeln!(
+            msh,
+            "{} {} 1 {}",
+            self.curves.len() + 1,
+            elements_count,
+            elements_count
+        )?;
+
+        let mut element_tag: usize = 0;
+        for (tag, _, lines) in self.curves.iter() {
+            writeln!(msh, "1 {} {} {}", tag, LINE_TYPE, lines.len())?;
+            for (v1, v2) in lines.iter() {
+                element_tag += 1;
+                writeln!(msh, "{} {} {}", element_tag, v1, v2)?;
+            }
+        }
+        writeln!(msh, "2 {} {} {}", DOMAIN_TAG, TRIANGLE_TYPE, self.triangles.len())?;
+        for (v1, v2, v3) in self.triangles.iter() {
+            element_tag += 1;
+            writeln!(msh, "{} {} {} {}", element_tag, v1, v2, v3)?;
+        }
+        return writeln!(msh, "$EndElements");
+    }
+
+    fn bounding_box(&self) -> (f64, f64, f64, f64) {
+        return self.nodes.iter().fold(
+            (
+                std::f64::INFINITY,
+                std::f64::INFINITY,
+                std::f64::NEG_INFINITY,
+                std::f64::NEG_INFINITY,
+            ),
+            |(min_x, min_y, max_x, max_y), v| {
+                (min_x.min(v.x), min_y.min(v.y), max_x.max(v.x), max_y.max(v.y))
+            },
+        );
+    }
+} /* end - MshMesh */
+
+#[cfg(test)]
+mod msh_mesh {
+    use super::*;
+    use nlsn_delaunay::elements::polyline::*;
+
+    fn square_triangulator() -> Triangulator {
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(3.0, 1.0));
+        let v3 = Rc::new(Vertex::new(3.0, 3.0));
+        let v4 = Rc::new(Vertex::new(1.0, 3.0));
+        let boundary = Rc::new(Polyline::new_closed(vec![v1, v2, v3, v4]).unwrap());
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.try_triangulate().unwrap();
+        return triangulator;
+    }
+
+    #[test]
+    fn from_triangulator() {
+        let mesh = MshMesh::from_triangulator(&square_triangulator());
+        assert_eq!(mesh.nodes.len(), 4);
+        assert_eq!(mesh.triangles.len(), 2);
+        assert_eq!(mesh.curves.len(), 1);
+
+        let (tag, name, lines) = mesh.curves.get(0).unwrap();
+        assert_eq!(*tag, BOUNDARY_TAG);
+        assert_eq!(*name, "boundary");
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn version_22() {
+        let msh = MshMesh::from_triangulator(&square_triangulator()).to_string(MshVersion::V22);
+        assert!(msh.starts_with("$MeshFormat\n2.2 0 8\n$EndMeshFormat\n"));
+        assert!(msh.contains("$PhysicalNames\n2\n1 1 \"boundary\"\n2 4 \"domain\"\n"));
+        assert!(msh.contains("$Nodes\n4\n"));
+        assert!(msh.contains("$Elements\n6\n"));
+        assert!(msh.contains("\n5 2 2 4 4 "));
+        assert!(msh.ends_with("$EndElements\n"));
+    }
+
+    #[test]
+    fn version_41() {
+        let msh = MshMesh::from_triangulator(&square_triangulator()).to_string(MshVersion::V41);
+        assert!(msh.starts_with("$MeshFormat\n4.1 0 8\n$EndMeshFormat\n"));
+        assert!(msh.contains("$Entities\n0 1 1 0\n1 1 1 0 3 3 0 1 1 0\n4 1 1 0 3 3 0 1 4 1 1\n"));
+        assert!(msh.contains("$Nodes\n1 4 1 4\n2 4 0 4\n"));
+        assert!(msh.contains("$Elements\n2 6 1 6\n1 1 1 4\n"));
+        assert!(msh.contains("\n2 4 2 2\n"));
+        assert!(msh.ends_with("$EndElements\n"));
+    }
+
+    #[test]
+    fn from_format() {
+        assert_eq!(MshVersion::from_format("msh"), Some(MshVersion::V41));
+        assert_eq!(MshVersion::from_format("msh22"), Some(MshVersion::V22));
+        assert_eq!(MshVersion::from_format("json"), None);
+    }
+} /* end - msh_mesh tests */
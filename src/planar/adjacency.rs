@@ -0,0 +1,431 @@
+use crate::elements::{edge::*, triangle::*, vertex::*};
+use crate::planar::compact_triangulation::pack;
+
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::rc::Rc;
+
+/**
+ * Adjacency of a triangulation, mapping each directed edge to the triangle
+ * having it. It is kept either as a map of reference counted edges, the
+ * default, or compact, as an opt-in backend for meshes of millions of
+ * triangles, where each edge is packed from u32 vertex handles and mapped
+ * to the u32 handle of a triangle slot, instead of being allocated.
+ */
+pub enum Adjacency {
+    Map(HashMap<Rc<Edge>, Rc<Triangle>>),
+    Compact(CompactAdjacency),
+}
+
+/**
+ * Handle based adjacency. Vertices get a handle while any edge refers to
+ * them, and triangles a slot while any of their edges is mapped, so that
+ * handles and slots of removed ones are reused by later insertions.
+ */
+pub struct CompactAdjacency {
+    edges: HashMap<u64, u32>,
+    vertex_handles: HashMap<Rc<Vertex>, u32>,
+    vertices: Vec<Option<Rc<Vertex>>>,
+    vertex_edges: Vec<u32>,
+    free_vertices: Vec<u32>,
+    triangles: Vec<Option<Rc<Triangle>>>,
+    triangle_edges: Vec<u8>,
+    free_triangles: Vec<u32>,
+}
+
+impl Adjacency {
+    pub fn new() -> Self {
+        return Adjacency::Map(HashMap::new());
+    }
+
+    pub fn new_compact() -> Self {
+        return Adjacency::Compact(CompactAdjacency::new());
+    }
+
+    /**
+     * Empty adjacency of the same backend.
+     */
+    pub fn new_like(&self) -> Self {
+        return match self {
+            Adjacency::Map(_) => Self::new(),
+            Adjacency::Compact(_) => Self::new_compact(),
+        };
+    }
+
+    pub fn is_compact(&self) -> bool {
+        return match self {
+            Adjacency::Map(_) => false,
+            Adjacency::Compact(_) => true,
+        };
+    }
+
+    /**
+     * Copy of the adjacency into the compact backend, if compact, or else
+     * into the map one.
+     */
+    pub fn converted(&self, compact: bool) -> Self {
+        let mut adjacency = match compact {
+            true => Self::new_compact(),
+            false => Self::new(),
+        };
+        for (edge, triangle) in self.iter() {
+            adjacency.insert(edge, Rc::clone(triangle));
+        }
+        return adjacency;
+    }
+
+    pub fn get(&self, edge: &Edge) -> Option<&Rc<Triangle>> {
+        return match self {
+            Adjacency::Map(map) => map.get(edge),
+            Adjacency::Compact(compact) => compact.get(edge),
+        };
+    }
+
+    pub fn contains_key(&self, edge: &Edge) -> bool {
+        return self.get(edge).is_some();
+    }
+
+    /**
+     * Maps the edge to the triangle, returning the triangle it was mapped
+     * to, if any.
+     */
+    pub fn insert(&mut self, edge: Rc<Edge>, triangle: Rc<Triangle>) -> Option<Rc<Triangle>> {
+        return match self {
+            Adjacency::Map(map) => map.insert(edge, triangle),
+            Adjacency::Compact(compact) => compact.insert(&edge, triangle),
+        };
+    }
+
+    pub fn remove(&mut self, edge: &Edge) -> Option<Rc<Triangle>> {
+        return match self {
+            Adjacency::Map(map) => map.remove(edge),
+            Adjacency::Compact(compact) => compact.remove(edge),
+        };
+    }
+
+    pub fn len(&self) -> usize {
+        return match self {
+            Adjacency::Map(map) => map.len(),
+            Adjacency::Compact(compact) => compact.edges.len(),
+        };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    /**
+     * Mapped edges. Compact edges are unpacked into new reference counted
+     * ones, thus they are given owned.
+     */
+    pub fn keys(&self) -> Box<dyn Iterator<Item = Rc<Edge>> + '_> {
+        return match self {
+            Adjacency::Map(map) => Box::new(map.keys().cloned()),
+            Adjacency::Compact(compact) => {
+                Box::new(compact.edges.keys().map(move |&key| compact.unpack(key)))
+            }
+        };
+    }
+
+    /**
+     * Mapped edges along with their triangles, edges owned as by keys.
+     */
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (Rc<Edge>, &Rc<Triangle>)> + '_> {
+        return match self {
+            Adjacency::Map(map) => Box::new(
+                map.iter()
+                    .map(|(edge, triangle)| (Rc::clone(edge), triangle)),
+            ),
+            Adjacency::Compact(compact) => {
+                Box::new(compact.edges.iter().map(move |(&key, &slot)| {
+                    return (
+                        compact.unpack(key),
+                        compact.triangles[slot as usize].as_ref().unwrap(),
+                    );
+                }))
+            }
+        };
+    }
+
+    /**
+     * Owned copy of the edges, for callers mutating the triangulation while
+     * going through them.
+     */
+    pub fn to_set(&self) -> HashSet<Rc<Edge>> {
+        return self.keys().collect();
+    }
+
+    /**
+     * Approximate heap usage in bytes, edges and triangles shared with the
+     * triangulation not included.
+     */
+    pub fn memory_size(&self) -> usize {
+        return match self {
+            Adjacency::Map(map) => {
+                map.capacity() * (mem::size_of::<Rc<Edge>>() + mem::size_of::<Rc<Triangle>>())
+                    + map.len() * (2 * mem::size_of::<usize>() + mem::size_of::<Edge>())
+            }
+            Adjacency::Compact(compact) => compact.memory_size(),
+        };
+    }
+} /* end - Adjacency */
+
+impl CompactAdjacency {
+    pub fn new() -> Self {
+        Self {
+            edges: HashMap::new(),
+            vertex_handles: HashMap::new(),
+            vertices: Vec::new(),
+            vertex_edges: Vec::new(),
+            free_vertices: Vec::new(),
+            triangles: Vec::new(),
+            triangle_edges: Vec::new(),
+            free_triangles: Vec::new(),
+        }
+    }
+
+    fn get(&self, edge: &Edge) -> Option<&Rc<Triangle>> {
+        let slot = self.edges.get(&self.key(edge)?)?;
+        return self.triangles[*slot as usize].as_ref();
+    }
+
+    fn insert(&mut self, edge: &Rc<Edge>, triangle: Rc<Triangle>) -> Option<Rc<Triangle>> {
+        let slot = self.slot_of(edge, triangle);
+        self.triangle_edges[slot as usize] += 1;
+
+        let key = match self.key(edge) {
+            Some(key) => key,
+            None => {
+                let v1 = self.acquire_vertex(&edge.v1);
+                let v2 = self.acquire_vertex(&edge.v2);
+                pack(v1, v2)
+            }
+        };
+        return match self.edges.insert(key, slot) {
+            Some(former_slot) => self.release_triangle(former_slot),
+            None => {
+                self.vertex_edges[(key >> 32) as usize] += 1;
+                self.vertex_edges[(key & 0xFFFF_FFFF) as usize] += 1;
+                None
+            }
+        };
+    }
+
+    fn remove(&mut self, edge: &Edge) -> Option<Rc<Triangle>> {
+        let key = self.key(edge)?;
+        let slot = self.edges.remove(&key)?;
+        self.release_vertex((key >> 32) as u32);
+        self.release_vertex((key & 0xFFFF_FFFF) as u32);
+
+        let triangle = self.triangles[slot as usize].clone();
+        self.release_triangle(slot);
+        return triangle;
+    }
+
+    /* Key of the edge, if both of its vertices have handles */
+    fn key(&self, edge: &Edge) -> Option<u64> {
+        let v1 = self.vertex_handles.get(&edge.v1)?;
+        let v2 = self.vertex_handles.get(&edge.v2)?;
+        return Some(pack(*v1, *v2));
+    }
+
+    fn unpack(&self, key: u64) -> Rc<Edge> {
+        let v1 = self.vertices[(key >> 32) as usize].as_ref().unwrap();
+        let v2 = self.vertices[(key & 0xFFFF_FFFF) as usize]
+            .as_ref()
+            .unwrap();
+        return Rc::new(Edge::new(v1, v2));
+    }
+
+    /* Slot of the triangle, shared with its other edges if already mapped */
+    fn slot_of(&mut self, edge: &Edge, triangle: Rc<Triangle>) -> u32 {
+        let (e1, e2, e3) = triangle.inner_edges();
+        for other_edge in vec![e1, e2, e3].into_iter().filter(|e| **e != *edge) {
+            if let Some(&slot) = self.key(&other_edge).and_then(|key| self.edges.get(&key)) {
+                if self.triangles[slot as usize].as_ref() == Some(&triangle) {
+                    return slot;
+                }
+            }
+        }
+
+        return match self.free_triangles.pop() {
+            Some(slot) => {
+                self.triangles[slot as usize] = Some(triangle);
+                slot
+            }
+            None => {
+                self.triangles.push(Some(triangle));
+                self.triangle_edges.push(0);
+                (self.triangles.len() - 1) as u32
+            }
+        };
+    }
+
+    /* Frees the slot once none of the triangle edges is mapped to it */
+    fn release_triangle(&mut self, slot: u32) -> Option<Rc<Triangle>> {
+        let triangle = self.triangles[slot as usize].clone();
+        self.triangle_edges[slot as usize] -= 1;
+        if self.triangle_edges[slot as usize] == 0 {
+            self.triangles[slot as usize] = None;
+            self.free_triangles.push(slot);
+        }
+        return triangle;
+    }
+
+    fn acquire_vertex(&mut self, vertex: &Rc<Vertex>) -> u32 {
+        if let Some(&handle) = self.vertex_handles.get(vertex) {
+            return handle;
+        }
+        let handle = match self.free_vertices.pop() {
+            Some(handle) => {
+                self.vertices[handle as usize] = Some(Rc::clone(vertex));
+                handle
+            }
+            None => {
+                self.vertices.push(Some(Rc::clone(vertex)));
+                self.vertex_edges.push(0);
+                (self.vertices.len() - 1) as u32
+            }
+        };
+        self.vertex_handles.insert(Rc::clone(vertex), handle);
+        return handle;
+    }
+
+    /* Frees the handle once no edge refers to the vertex */
+    fn release_vertex(&mut self, handle: u32) {
+        self.vertex_edges[handle as usize] -= 1;
+        if self.vertex_edges[handle as usize] == 0 {
+            if let Some(vertex) = self.vertices[handle as usize].take() {
+                self.vertex_handles.remove(&vertex);
+            }
+            self.free_vertices.push(handle);
+        }
+    }
+
+    fn memory_size(&self) -> usize {
+        return self.edges.capacity() * (mem::size_of::<u64>() + mem::size_of::<u32>())
+            + self.vertex_handles.capacity()
+                * (mem::size_of::<Rc<Vertex>>() + mem::size_of::<u32>())
+            + self.vertices.capacity() * mem::size_of::<Option<Rc<Vertex>>>()
+            + self.vertex_edges.capacity() * mem::size_of::<u32>()
+            + self.free_vertices.capacity() * mem::size_of::<u32>()
+            + self.triangles.capacity() * mem::size_of::<Option<Rc<Triangle>>>()
+            + self.triangle_edges.capacity() * mem::size_of::<u8>()
+            + self.free_triangles.capacity() * mem::size_of::<u32>();
+    }
+} /* end - CompactAdjacency */
+
+#[cfg(test)]
+mod adjacency {
+    use super::*;
+    use crate::elements::polyline::*;
+    use crate::planar::{refine_params::*, triangulator::*};
+
+    fn square(min: f64, max: f64) -> Rc<Polyline> {
+        return Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(min, min)),
+                Rc::new(Vertex::new(max, min)),
+                Rc::new(Vertex::new(max, max)),
+                Rc::new(Vertex::new(min, max)),
+            ])
+            .unwrap(),
+        );
+    }
+
+    fn refined(compact_adjacency: bool) -> Triangulator {
+        let mut triangulator = Triangulator::new(&square(0.0, 4.0));
+        triangulator.compact_adjacency = compact_adjacency;
+        triangulator.insert_hole(&square(1.0, 2.0)).unwrap();
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(0.05),
+                quality_ratio: 1.0,
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .unwrap();
+        return triangulator;
+    }
+
+    #[test]
+    fn compact_slots() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+        let v4 = Rc::new(Vertex::new(1.0, 1.0));
+        let t1 = Rc::new(Triangle::new(&v1, &v2, &v3));
+        let t2 = Rc::new(Triangle::new(&v2, &v4, &v3));
+
+        let mut adjacency = Adjacency::new_compact();
+        for triangle in vec![&t1, &t2] {
+            let (e1, e2, e3) = triangle.inner_edges();
+            for edge in vec![e1, e2, e3] {
+                assert!(adjacency.insert(edge, Rc::clone(triangle)).is_none());
+            }
+        }
+        assert_eq!(adjacency.len(), 6);
+        assert_eq!(adjacency.get(&Edge::new(&v2, &v3)), Some(&t1));
+        assert_eq!(adjacency.get(&Edge::new(&v3, &v2)), Some(&t2));
+        assert!(adjacency.get(&Edge::new(&v1, &v4)).is_none());
+
+        /* edges of a triangle share its slot, freed along with the last one */
+        let (e1, e2, e3) = t1.inner_edges();
+        for edge in vec![e1, e2, e3] {
+            assert_eq!(adjacency.remove(&edge), Some(Rc::clone(&t1)));
+        }
+        assert_eq!(adjacency.remove(&Edge::new(&v1, &v2)), None);
+        if let Adjacency::Compact(compact) = &adjacency {
+            assert_eq!(compact.free_triangles.len(), 1);
+            /* v1 is on no mapped edge anymore */
+            assert_eq!(compact.free_vertices.len(), 1);
+            assert!(!compact.vertex_handles.contains_key(&v1));
+        }
+
+        /* removed slots and handles are reused */
+        let (e1, e2, e3) = t1.inner_edges();
+        for edge in vec![e1, e2, e3] {
+            adjacency.insert(edge, Rc::clone(&t1));
+        }
+        if let Adjacency::Compact(compact) = &adjacency {
+            assert_eq!(compact.triangles.len(), 2);
+            assert_eq!(compact.vertices.len(), 4);
+        }
+        assert_eq!(adjacency.get(&Edge::new(&v2, &v3)), Some(&t1));
+
+        let map = adjacency.converted(false);
+        assert!(!map.is_compact());
+        assert_eq!(map.to_set(), adjacency.to_set());
+        assert_eq!(map.get(&Edge::new(&v3, &v2)), Some(&t2));
+    }
+
+    #[test]
+    fn triangulates_compact() {
+        let triangulator = refined(true);
+        assert!(triangulator.validate().is_ok());
+
+        let triangulation = triangulator.triangulation.borrow();
+        assert!(triangulation.adjacency.is_compact());
+        let area: f64 = triangulation
+            .solid_triangles()
+            .map(|t| t.area().unwrap())
+            .sum();
+        assert!(float_cmp::approx_eq!(f64, area, 15.0, epsilon = 1.0E-10f64));
+        assert!(!triangulation.constraints().is_empty());
+    }
+
+    #[test]
+    fn memory_size() {
+        let triangulator = refined(false);
+        let triangulation = triangulator.triangulation.borrow();
+        let map = &triangulation.adjacency;
+        let compact = map.converted(true);
+
+        assert_eq!(compact.len(), map.len());
+        for (edge, triangle) in map.iter() {
+            assert_eq!(compact.get(&edge), Some(triangle));
+        }
+        assert!(compact.memory_size() < map.memory_size());
+    }
+} /* end - adjacency tests */
@@ -0,0 +1,247 @@
+use crate::elements::{edge::*, vertex::*};
+use crate::planar::{triangulation::*, triangulation_data::*, triangulation_error::*};
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::rc::Rc;
+
+/* Leading bytes of every binary mesh */
+pub const MAGIC: [u8; 4] = *b"NLSN";
+
+/* Layout version, increased on any layout change */
+pub const VERSION: u32 = 1;
+
+/**
+ * Compact binary form of a triangulation, for meshes too large for JSON.
+ * Every number is little endian, laid out as:
+ *  - header: the MAGIC bytes, then the VERSION, the points count, the
+ * triangles count and the constrained edges count, each as u32;
+ *  - points: x and y of each point, as f64;
+ *  - triangles: the three point indices of each counterclockwise triangle,
+ * as u32;
+ *  - edges: the two point indices of each constrained edge, as u32.
+ * Points and triangles are sorted as in TriangulationData, so that equal
+ * triangulations write equal bytes.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct BinaryMesh {
+    pub coordinates: Vec<f64>,
+    pub triangles: Vec<u32>,
+    pub edges: Vec<u32>,
+}
+
+impl BinaryMesh {
+    /**
+     * Binary mesh of the solid triangles and of the constrained edges. Fails
+     * if there are more points than u32 indices.
+     */
+    pub fn from_triangulation(triangulation: &Triangulation) -> Result<Self, TriangulationError> {
+        let data = TriangulationData::from_triangulation(triangulation);
+        if data.coordinates.len() / 2 > std::u32::MAX as usize {
+            return Err(invalid_mesh("points count"));
+        }
+
+        let indices: HashMap<Rc<Vertex>, u32> = data
+            .coordinates
+            .chunks(2)
+            .enumerate()
+            .map(|(index, xy)| (Rc::new(Vertex::new(xy[0], xy[1])), index as u32))
+            .collect();
+        let mut edges: Vec<[u32; 2]> = triangulation
+            .constrained_edges()
+            .filter_map(|edge| Some([*indices.get(&edge.v1)?, *indices.get(&edge.v2)?]))
+            .collect();
+        edges.sort();
+
+        return Ok(Self {
+            coordinates: data.coordinates,
+            triangles: data.triangles.iter().map(|&index| index as u32).collect(),
+            edges: edges.iter().map(|e| e.to_vec()).flatten().collect(),
+        });
+    }
+
+    pub fn points_count(&self) -> usize {
+        return self.coordinates.len() / 2;
+    }
+
+    pub fn triangles_count(&self) -> usize {
+        return self.triangles.len() / 3;
+    }
+
+    pub fn edges_count(&self) -> usize {
+        return self.edges.len() / 2;
+    }
+
+    /**
+     * Triangulation of the mesh, with its edges marked as constraints.
+     * Returns None if any index is out of the points.
+     */
+    pub fn to_triangulation(&self) -> Option<Triangulation> {
+        let data = TriangulationData::from(
+            self.coordinates.clone(),
+            self.triangles.iter().map(|&index| index as usize).collect(),
+        );
+        let mut triangulation = data.to_triangulation()?;
+
+        let vertices: Vec<Rc<Vertex>> = self
+            .coordinates
+            .chunks(2)
+            .map(|xy| Rc::new(Vertex::new(xy[0], xy[1])))
+            .collect();
+        for pair in self.edges.chunks(2) {
+            let v1 = vertices.get(pair[0] as usize)?;
+            let v2 = vertices.get(pair[1] as usize)?;
+            triangulation.constrain(&Rc::new(Edge::new(v1, v2)));
+        }
+        return Some(triangulation);
+    }
+
+    pub fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+        for count in [self.points_count(), self.triangles_count(), self.edges_count()].iter() {
+            if *count > std::u32::MAX as usize {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "count out of u32"));
+            }
+        }
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(self.points_count() as u32).to_le_bytes())?;
+        writer.write_all(&(self.triangles_count() as u32).to_le_bytes())?;
+        writer.write_all(&(self.edges_count() as u32).to_le_bytes())?;
+
+        for value in self.coordinates.iter() {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        for index in self.triangles.iter().chain(self.edges.iter()) {
+            writer.write_all(&index.to_le_bytes())?;
+        }
+        return Ok(());
+    }
+
+    /**
+     * Reads a binary mesh as written by write. Fails on unknown magic bytes
+     * or versions, on truncated data and on indices out of the points.
+     */
+    pub fn read(reader: &mut dyn Read) -> Result<Self, TriangulationError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_mesh("magic bytes"));
+        }
+        if read_u32(reader)? != VERSION {
+            return Err(invalid_mesh("version"));
+        }
+        let points_count = read_u32(reader)? as usize;
+        let triangles_count = read_u32(reader)? as usize;
+        let edges_count = read_u32(reader)? as usize;
+
+        /* counts are not trusted for preallocation, data may be truncated */
+        let mut coordinates: Vec<f64> = Vec::new();
+        for _ in 0..points_count * 2 {
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes)?;
+            coordinates.push(f64::from_le_bytes(bytes));
+        }
+        let mut triangles: Vec<u32> = Vec::new();
+        for _ in 0..triangles_count * 3 {
+            triangles.push(read_u32(reader)?);
+        }
+        let mut edges: Vec<u32> = Vec::new();
+        for _ in 0..edges_count * 2 {
+            edges.push(read_u32(reader)?);
+        }
+
+        if triangles
+            .iter()
+            .chain(edges.iter())
+            .any(|&index| index as usize >= points_count)
+        {
+            return Err(invalid_mesh("indices"));
+        }
+
+        return Ok(Self {
+            coordinates,
+            triangles,
+            edges,
+        });
+    }
+} /* end - BinaryMesh */
+
+fn read_u32(reader: &mut dyn Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    return Ok(u32::from_le_bytes(bytes));
+}
+
+fn invalid_mesh(section: &str) -> TriangulationError {
+    return TriangulationError::DegenerateInput(format!("invalid binary mesh {}", section));
+}
+
+#[cfg(test)]
+mod binary_mesh {
+    use super::*;
+    use crate::elements::bounding_box::*;
+    use crate::planar::structured;
+
+    fn grid() -> Triangulation {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(2.0, 1.0)),
+        ])
+        .unwrap();
+        let mut triangulation = structured::rectangle(&bounding_box, 2, 1).unwrap();
+        let edge = Rc::new(Edge::new(
+            &Rc::new(Vertex::new(1.0, 0.0)),
+            &Rc::new(Vertex::new(1.0, 1.0)),
+        ));
+        triangulation.constrain(&edge);
+        return triangulation;
+    }
+
+    #[test]
+    fn round_trip() {
+        let triangulation = grid();
+        let mesh = BinaryMesh::from_triangulation(&triangulation).unwrap();
+        assert_eq!(mesh.points_count(), 6);
+        assert_eq!(mesh.triangles_count(), 4);
+        assert_eq!(mesh.edges_count(), 1);
+
+        let mut bytes: Vec<u8> = Vec::new();
+        mesh.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 20 + 6 * 16 + 4 * 12 + 8);
+        assert_eq!(&bytes[0..4], b"NLSN");
+        assert_eq!(&bytes[8..12], &6u32.to_le_bytes());
+
+        let read = BinaryMesh::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(read, mesh);
+
+        let rebuilt = read.to_triangulation().unwrap();
+        assert_eq!(
+            TriangulationData::from_triangulation(&rebuilt).triangles,
+            TriangulationData::from_triangulation(&triangulation).triangles
+        );
+        assert_eq!(rebuilt.constraints().len(), 1);
+        assert!(rebuilt.validate().is_ok());
+    }
+
+    #[test]
+    fn invalid_data() {
+        let mesh = BinaryMesh::from_triangulation(&grid()).unwrap();
+        let mut bytes: Vec<u8> = Vec::new();
+        mesh.write(&mut bytes).unwrap();
+
+        /* truncated */
+        let truncated = &bytes[..bytes.len() - 3];
+        assert!(BinaryMesh::read(&mut &truncated[..]).is_err());
+
+        /* unknown magic */
+        let mut unknown = bytes.clone();
+        unknown[0] = b'X';
+        assert!(BinaryMesh::read(&mut unknown.as_slice()).is_err());
+
+        /* index out of the points */
+        let mut out_of_points = bytes.clone();
+        let last = out_of_points.len() - 4;
+        out_of_points[last..].copy_from_slice(&99u32.to_le_bytes());
+        assert!(BinaryMesh::read(&mut out_of_points.as_slice()).is_err());
+    }
+} /* end - binary_mesh tests */
@@ -0,0 +1,367 @@
+use crate::elements::{polyline::*, triangle::*, vertex::*};
+use crate::planar::{
+    refine_params::*, refine_procedures::triangle_split, triangulation::*, triangulation_data::*,
+    triangulation_error::*,
+};
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::rc::Rc;
+
+/**
+ * Refinement progress, as read from a checkpoint: the triangulation, the
+ * refine params, if refinement had begun, and the triangles pending
+ * refinement.
+ */
+pub struct Checkpoint {
+    pub triangulation: Triangulation,
+    pub params: Option<RefineParams>,
+    pub pending: Vec<Rc<Triangle>>,
+}
+
+/**
+ * Writes the checkpoint as text, whose sections are the params, along with
+ * the regions of their own params, the points, the triangles and the
+ * triangles pending refinement for the params, as indices of points.
+ * Coordinates are written with round trip precision, and unset params as -1.
+ */
+pub fn write(
+    writer: &mut dyn Write,
+    triangulation: &Triangulation,
+    params: Option<&RefineParams>,
+) -> std::io::Result<()> {
+    let data = TriangulationData::from_triangulation(triangulation);
+
+    writeln!(writer, "checkpoint 3")?;
+    match params {
+        Some(params) => {
+            writeln!(
+                writer,
+                "params {:?} {:?} {:?}",
+                params.max_area.unwrap_or(-1.0),
+                params.quality_ratio,
+                params.max_edge_length.unwrap_or(-1.0)
+            )?;
+            writeln!(writer, "regions {}", params.regions.len())?;
+            for region in params.regions.iter() {
+                write!(
+                    writer,
+                    "{} {:?} {:?} {:?} {}",
+                    region.hole as usize,
+                    region.max_area.unwrap_or(-1.0),
+                    region.quality_ratio.unwrap_or(-1.0),
+                    region.max_edge_length.unwrap_or(-1.0),
+                    region.boundary.vertices.len()
+                )?;
+                for vertex in region.boundary.vertices.iter() {
+                    write!(writer, " {:?} {:?}", vertex.x, vertex.y)?;
+                }
+                writeln!(writer)?;
+            }
+        }
+        None => writeln!(writer, "params none")?,
+    }
+
+    writeln!(writer, "points {}", data.coordinates.len() / 2)?;
+    for xy in data.coordinates.chunks(2) {
+        writeln!(writer, "{:?} {:?}", xy[0], xy[1])?;
+    }
+    writeln!(writer, "triangles {}", data.triangles.len() / 3)?;
+    for corners in data.triangles.chunks(3) {
+        writeln!(writer, "{} {} {}", corners[0], corners[1], corners[2])?;
+    }
+
+    let indices: HashMap<Rc<Vertex>, usize> = sorted_vertices(triangulation)
+        .into_iter()
+        .enumerate()
+        .map(|(index, v)| (v, index))
+        .collect();
+    let mut pending: Vec<[usize; 3]> = match params {
+        Some(params) => pending_triangles(triangulation, params)
+            .iter()
+            .map(|t| lowest_first([indices[&t.v1], indices[&t.v2], indices[&t.v3]]))
+            .collect(),
+        None => Vec::new(),
+    };
+    pending.sort();
+
+    writeln!(writer, "pending {}", pending.len())?;
+    for corners in pending.iter() {
+        writeln!(writer, "{} {} {}", corners[0], corners[1], corners[2])?;
+    }
+
+    return Ok(());
+}
+
+/**
+ * Reads a checkpoint written by write, or by its former versions: without
+ * edge lengths, and with regions of edge lengths only. Ghost triangles are
+ * restored at the border edges.
+ */
+pub fn read(reader: &mut dyn Read) -> Result<Checkpoint, TriangulationError> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut tokens = text.split_whitespace();
+    let mut next = |section: &str| tokens.next().ok_or_else(|| invalid_checkpoint(section));
+
+    if next("header")? != "checkpoint" {
+        return Err(invalid_checkpoint("header"));
+    }
+    let version = match next("header")? {
+        "1" => 1,
+        "2" => 2,
+        "3" => 3,
+        _ => return Err(invalid_checkpoint("header")),
+    };
+
+    if next("params")? != "params" {
+        return Err(invalid_checkpoint("params"));
+    }
+    let params = match next("params")? {
+        "none" => None,
+        max_area => {
+            let max_area = parse_number(max_area, "params")?;
+            let quality_ratio = parse_number(next("params")?, "params")?;
+            let mut max_edge_length = -1.0;
+            let mut regions: Vec<RegionParams> = Vec::new();
+            if version > 1 {
+                max_edge_length = parse_number(next("params")?, "params")?;
+                if next("regions")? != "regions" {
+                    return Err(invalid_checkpoint("regions"));
+                }
+                let count = parse_number(next("regions")?, "regions")? as usize;
+                for _ in 0..count {
+                    /* hole, max area, quality and edge length, only the latter before */
+                    let mut values = [0.0, -1.0, -1.0, -1.0];
+                    let first = if version > 2 { 0 } else { 3 };
+                    for value in values.iter_mut().skip(first) {
+                        *value = parse_number(next("regions")?, "regions")?;
+                    }
+                    let vertex_count = parse_number(next("regions")?, "regions")? as usize;
+                    let mut vertices: Vec<Rc<Vertex>> = Vec::new();
+                    for _ in 0..vertex_count {
+                        let x = parse_number(next("regions")?, "regions")?;
+                        let y = parse_number(next("regions")?, "regions")?;
+                        vertices.push(Rc::new(Vertex::new(x, y)));
+                    }
+                    let region = Polyline::new_closed(vertices)
+                        .ok_or_else(|| invalid_checkpoint("regions"))?;
+                    regions.push(RegionParams {
+                        boundary: Rc::new(region),
+                        hole: values[0] > 0.0,
+                        max_area: optional(values[1]),
+                        quality_ratio: optional(values[2]),
+                        max_edge_length: optional(values[3]),
+                    });
+                }
+            }
+            Some(RefineParams {
+                max_area: optional(max_area),
+                quality_ratio,
+                max_edge_length: optional(max_edge_length),
+                regions,
+            })
+        }
+    };
+
+    let mut section = |name: &str, width: usize| -> Result<Vec<f64>, TriangulationError> {
+        if next(name)? != name {
+            return Err(invalid_checkpoint(name));
+        }
+        let count: usize = next(name)?.parse().map_err(|_| invalid_checkpoint(name))?;
+        return (0..count * width)
+            .map(|_| next(name)?.parse().map_err(|_| invalid_checkpoint(name)))
+            .collect();
+    };
+
+    let coordinates = section("points", 2)?;
+    let triangles: Vec<usize> = section("triangles", 3)?
+        .iter()
+        .map(|i| *i as usize)
+        .collect();
+    let pending_indices: Vec<usize> = section("pending", 3)?.iter().map(|i| *i as usize).collect();
+
+    let triangulation = TriangulationData::from(coordinates, triangles)
+        .to_triangulation()
+        .ok_or_else(|| invalid_checkpoint("triangles"))?;
+
+    let vertices = sorted_vertices(&triangulation);
+    let mut pending: Vec<Rc<Triangle>> = Vec::new();
+    for corners in pending_indices.chunks(3) {
+        let corners = lowest_first([corners[0], corners[1], corners[2]]);
+        let corner = |index: usize| {
+            vertices
+                .get(index)
+                .ok_or_else(|| invalid_checkpoint("pending"))
+        };
+        let triangle = Triangle::new(
+            corner(corners[0])?,
+            corner(corners[1])?,
+            corner(corners[2])?,
+        );
+        match triangulation.triangles.get(&triangle) {
+            Some(existing) => pending.push(Rc::clone(existing)),
+            None => return Err(invalid_checkpoint("pending")),
+        }
+    }
+
+    return Ok(Checkpoint {
+        triangulation,
+        params,
+        pending,
+    });
+}
+
+/**
+ * Solid triangles that refinement would split for the params.
+ */
+pub fn pending_triangles(
+    triangulation: &Triangulation,
+    params: &RefineParams,
+) -> Vec<Rc<Triangle>> {
+    return triangulation
+        .solid_triangles()
+        .filter(|t| {
+            triangle_split::is_irregular_triangle(t, params)
+                || triangle_split::is_large_triangle(t, params)
+        })
+        .cloned()
+        .collect();
+}
+
+/* triangulation vertices, in the order of their triangulation data points */
+fn sorted_vertices(triangulation: &Triangulation) -> Vec<Rc<Vertex>> {
+    let mut vertices: Vec<Rc<Vertex>> = triangulation.vertices().iter().cloned().collect();
+    vertices.sort_by(|v1, v2| v1.partial_cmp(v2).unwrap());
+    return vertices;
+}
+
+/* triangle corners rotated as in triangulation data */
+fn lowest_first(corners: [usize; 3]) -> [usize; 3] {
+    let lowest = (0..3).min_by_key(|&i| corners[i]).unwrap();
+    return [
+        corners[lowest],
+        corners[(lowest + 1) % 3],
+        corners[(lowest + 2) % 3],
+    ];
+}
+
+fn parse_number(token: &str, section: &str) -> Result<f64, TriangulationError> {
+    return token.parse().map_err(|_| invalid_checkpoint(section));
+}
+
+/* unset params are written as -1 */
+fn optional(value: f64) -> Option<f64> {
+    if value < 0.0 {
+        return None;
+    }
+    return Some(value);
+}
+
+fn invalid_checkpoint(section: &str) -> TriangulationError {
+    return TriangulationError::DegenerateInput(format!("invalid checkpoint {}", section));
+}
+
+#[cfg(test)]
+mod checkpoint {
+    use super::*;
+    use crate::elements::bounding_box::*;
+    use crate::planar::structured;
+
+    #[test]
+    fn round_trip() {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(3.0, 1.0)),
+        ])
+        .unwrap();
+        let triangulation = structured::rectangle(&bounding_box, 3, 1).unwrap();
+        let params = RefineParams {
+            max_area: Some(0.4),
+            quality_ratio: 2.0,
+            max_edge_length: None,
+            regions: Vec::new(),
+        };
+
+        let mut buffer: Vec<u8> = Vec::new();
+        write(&mut buffer, &triangulation, Some(&params)).unwrap();
+        let text = String::from_utf8(buffer.clone()).unwrap();
+        assert!(text.starts_with("checkpoint 3\nparams 0.4 2.0 -1.0\nregions 0\npoints 8\n0.0 0.0\n"));
+        assert!(text.contains("\ntriangles 6\n0 2 3\n"));
+        assert!(text.ends_with("pending 6\n0 2 3\n0 3 1\n2 4 5\n2 5 3\n4 6 7\n4 7 5\n"));
+
+        let checkpoint = read(&mut buffer.as_slice()).unwrap();
+        assert_eq!(checkpoint.triangulation.triangles, triangulation.triangles);
+        assert_eq!(checkpoint.params.unwrap().max_area, Some(0.4));
+        assert_eq!(checkpoint.pending.len(), 6);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        write(&mut buffer, &triangulation, None).unwrap();
+        let checkpoint = read(&mut buffer.as_slice()).unwrap();
+        assert!(checkpoint.params.is_none());
+        assert!(checkpoint.pending.is_empty());
+
+        assert!(read(&mut "checkpoint 1\nparams none\npoints 2\n0.0".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn edge_lengths() {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(3.0, 1.0)),
+        ])
+        .unwrap();
+        let triangulation = structured::rectangle(&bounding_box, 3, 1).unwrap();
+        let region = Polyline::new_closed(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(1.0, 0.0)),
+            Rc::new(Vertex::new(1.0, 1.0)),
+        ])
+        .unwrap();
+        let mut region = RegionParams::new(Rc::new(region), true);
+        region.quality_ratio = Some(1.5);
+        region.max_edge_length = Some(0.25);
+        let params = RefineParams {
+            max_area: None,
+            quality_ratio: 2.0,
+            max_edge_length: Some(1.5),
+            regions: vec![region],
+        };
+
+        let mut buffer: Vec<u8> = Vec::new();
+        write(&mut buffer, &triangulation, Some(&params)).unwrap();
+        let text = String::from_utf8(buffer.clone()).unwrap();
+        assert!(text.contains(
+            "params -1.0 2.0 1.5\nregions 1\n1 -1.0 1.5 0.25 3 0.0 0.0 1.0 0.0 1.0 1.0\n"
+        ));
+
+        let read_params = read(&mut buffer.as_slice()).unwrap().params.unwrap();
+        assert_eq!(read_params.max_edge_length, Some(1.5));
+        assert_eq!(read_params.regions.len(), 1);
+        let read_region = &read_params.regions[0];
+        assert!(read_region.hole);
+        assert_eq!(read_region.max_area, None);
+        assert_eq!(read_region.quality_ratio, Some(1.5));
+        assert_eq!(read_region.max_edge_length, Some(0.25));
+        assert!(read_region.boundary == params.regions[0].boundary);
+
+        /* regions of edge lengths only, by the former version */
+        let former = text
+            .replacen("checkpoint 3", "checkpoint 2", 1)
+            .replacen("1 -1.0 1.5 0.25 3", "0.25 3", 1);
+        let read_params = read(&mut former.as_bytes()).unwrap().params.unwrap();
+        let read_region = &read_params.regions[0];
+        assert!(!read_region.hole);
+        assert_eq!(read_region.quality_ratio, None);
+        assert_eq!(read_region.max_edge_length, Some(0.25));
+
+        /* former checkpoints have no edge lengths */
+        let mut buffer: Vec<u8> = Vec::new();
+        write(&mut buffer, &triangulation, None).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        let former = text.replacen("checkpoint 3\nparams none", "checkpoint 1\nparams 0.4 2.0", 1);
+        let read_params = read(&mut former.as_bytes()).unwrap().params.unwrap();
+        assert_eq!(read_params.max_area, Some(0.4));
+        assert!(read_params.max_edge_length.is_none());
+    }
+} /* end - checkpoint tests */
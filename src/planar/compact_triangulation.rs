@@ -0,0 +1,365 @@
+use crate::elements::{triangle::*, vertex::*};
+use crate::planar::{structured, triangulation::*};
+
+use std::collections::HashMap;
+use std::mem;
+use std::rc::Rc;
+
+/*
+    Handle of the ghost vertex of the first loop. Ghost vertices are not
+    stored, and those of further loops count down from it.
+*/
+pub const GHOST: u32 = std::u32::MAX;
+
+/* Marks removed triangle slots, which are reused by later insertions */
+const REMOVED: [u32; 3] = [GHOST, GHOST, GHOST];
+
+/**
+ * Handle based copy of a triangulation, for keeping and querying meshes
+ * with millions of triangles once triangulated. While triangulating, the
+ * triangulator keeps its adjacency in handles as well when its
+ * compact_adjacency is set, see Adjacency. Vertices and triangles are kept
+ * in vectors and referred by u32 handles, while the adjacency maps each
+ * directed edge, packed from its vertex handles, to the handle of the
+ * triangle having it. Triangles are counterclockwise, as in Triangulation,
+ * and ghost triangles refer to the ghost handle of their loop.
+ */
+pub struct CompactTriangulation {
+    pub vertices: Vec<(f64, f64)>,
+    pub triangles: Vec<[u32; 3]>,
+    pub adjacency: HashMap<u64, u32>,
+    free_triangles: Vec<u32>,
+}
+
+impl CompactTriangulation {
+    pub fn new() -> Self {
+        Self {
+            vertices: Vec::new(),
+            triangles: Vec::new(),
+            adjacency: HashMap::new(),
+            free_triangles: Vec::new(),
+        }
+    }
+
+    /**
+     * Copies the triangulation into handles. Vertex handles follow the
+     * order of the triangulation vertices set.
+     */
+    pub fn from_triangulation(triangulation: &Triangulation) -> Self {
+        let mut compact = Self::new();
+        let mut handles: HashMap<Rc<Vertex>, u32> = HashMap::new();
+
+        for vertex in triangulation.vertices().iter() {
+            let handle = compact.add_vertex(vertex.x, vertex.y);
+            handles.insert(Rc::clone(vertex), handle);
+        }
+
+        let handle_of = |vertex: &Rc<Vertex>| -> u32 {
+            if let Some(ghost_loop) = vertex.ghost_loop() {
+                return ghost_handle(ghost_loop);
+            }
+            return *handles.get(vertex).unwrap();
+        };
+
+        for triangle in triangulation.triangles.iter() {
+            compact.include_triangle([
+                handle_of(&triangle.v1),
+                handle_of(&triangle.v2),
+                handle_of(&triangle.v3),
+            ]);
+        }
+
+        return compact;
+    }
+
+    /**
+     * Rebuilds the reference counted triangulation, ghost triangles keeping
+     * the ghost vertex of their loop. Without ghost triangles, as for
+     * triangles included by hand, they are included around the border, a
+     * ghost vertex for each loop.
+     */
+    pub fn to_triangulation(&self) -> Triangulation {
+        let vertices: Vec<Rc<Vertex>> = self
+            .vertices
+            .iter()
+            .map(|&(x, y)| Rc::new(Vertex::new(x, y)))
+            .collect();
+        let mut ghosts: HashMap<u32, Rc<Vertex>> = HashMap::new();
+
+        let mut triangulation = Triangulation::new();
+        let mut has_ghosts = false;
+        for triangle in self.triangles.iter().filter(|t| **t != REMOVED) {
+            let corners: Vec<Rc<Vertex>> = triangle
+                .iter()
+                .map(|&handle| match self.ghost_loop(handle) {
+                    Some(ghost_loop) => Rc::clone(
+                        ghosts
+                            .entry(handle)
+                            .or_insert_with(|| Rc::new(Vertex::new_ghost_of(ghost_loop))),
+                    ),
+                    None => Rc::clone(&vertices[handle as usize]),
+                })
+                .collect();
+            has_ghosts |= corners.iter().any(|v| v.is_ghost);
+            triangulation.include_triangle(&Rc::new(Triangle::new(
+                &corners[0],
+                &corners[1],
+                &corners[2],
+            )));
+        }
+        if !has_ghosts {
+            structured::include_ghosts(&mut triangulation);
+        }
+
+        return triangulation;
+    }
+
+    pub fn add_vertex(&mut self, x: f64, y: f64) -> u32 {
+        self.vertices.push((x, y));
+        return (self.vertices.len() - 1) as u32;
+    }
+
+    /**
+     * Includes the triangle, given by counterclockwise vertex handles, and
+     * returns its handle.
+     */
+    pub fn include_triangle(&mut self, triangle: [u32; 3]) -> u32 {
+        let handle = match self.free_triangles.pop() {
+            Some(handle) => {
+                self.triangles[handle as usize] = triangle;
+                handle
+            }
+            None => {
+                self.triangles.push(triangle);
+                (self.triangles.len() - 1) as u32
+            }
+        };
+
+        for index in 0..3 {
+            let edge = pack(triangle[index], triangle[(index + 1) % 3]);
+            self.adjacency.insert(edge, handle);
+        }
+
+        return handle;
+    }
+
+    pub fn remove_triangle(&mut self, handle: u32) -> bool {
+        let triangle = match self.triangles.get(handle as usize) {
+            Some(triangle) if *triangle != REMOVED => *triangle,
+            _ => return false,
+        };
+
+        for index in 0..3 {
+            self.adjacency
+                .remove(&pack(triangle[index], triangle[(index + 1) % 3]));
+        }
+        self.triangles[handle as usize] = REMOVED;
+        self.free_triangles.push(handle);
+        return true;
+    }
+
+    /**
+     * Triangle having the directed edge v1 -> v2.
+     */
+    pub fn triangle_at(&self, v1: u32, v2: u32) -> Option<u32> {
+        return self.adjacency.get(&pack(v1, v2)).cloned();
+    }
+
+    /**
+     * Triangle across the edge starting at the edge_index-th vertex.
+     */
+    pub fn neighbor(&self, handle: u32, edge_index: usize) -> Option<u32> {
+        let triangle = self.triangles.get(handle as usize)?;
+        return self.triangle_at(triangle[(edge_index + 1) % 3], triangle[edge_index]);
+    }
+
+    pub fn is_ghost(&self, handle: u32) -> bool {
+        return self.triangles[handle as usize]
+            .iter()
+            .any(|&v| self.ghost_loop(v).is_some());
+    }
+
+    /**
+     * Index of the loop of the ghost vertex handle. Returns None for the
+     * handles of stored vertices.
+     */
+    pub fn ghost_loop(&self, vertex: u32) -> Option<usize> {
+        if (vertex as usize) < self.vertices.len() {
+            return None;
+        }
+        return Some((GHOST - vertex) as usize);
+    }
+
+    /**
+     * Number of triangles, including ghost triangles.
+     */
+    pub fn triangles_count(&self) -> usize {
+        return self.triangles.len() - self.free_triangles.len();
+    }
+
+    /**
+     * Walks from triangle to triangle towards the point, and returns the
+     * solid triangle containing it. Returns None if the point is out of the
+     * triangulation.
+     */
+    pub fn locate(&self, x: f64, y: f64) -> Option<u32> {
+        let mut current = (0..self.triangles.len() as u32)
+            .find(|&handle| self.triangles[handle as usize] != REMOVED && !self.is_ghost(handle))?;
+
+        for _ in 0..self.triangles_count() {
+            let triangle = self.triangles[current as usize];
+            let crossed_edge = (0..3).find(|&index| {
+                let (ax, ay) = self.vertices[triangle[index] as usize];
+                let (bx, by) = self.vertices[triangle[(index + 1) % 3] as usize];
+                /* point to the right of the edge */
+                return (bx - ax) * (y - ay) - (by - ay) * (x - ax) < 0.0;
+            });
+
+            match crossed_edge {
+                None => return Some(current),
+                Some(index) => {
+                    current = self.neighbor(current, index)?;
+                    if self.is_ghost(current) {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        return None;
+    }
+
+    /**
+     * Approximate heap usage in bytes.
+     */
+    pub fn memory_size(&self) -> usize {
+        return self.vertices.capacity() * mem::size_of::<(f64, f64)>()
+            + self.triangles.capacity() * mem::size_of::<[u32; 3]>()
+            + self.adjacency.capacity() * (mem::size_of::<u64>() + mem::size_of::<u32>())
+            + self.free_triangles.capacity() * mem::size_of::<u32>();
+    }
+} /* end - CompactTriangulation */
+
+/**
+ * Handle of the ghost vertex of the loop.
+ */
+pub fn ghost_handle(ghost_loop: usize) -> u32 {
+    return GHOST - ghost_loop as u32;
+}
+
+/* Packs a directed edge into a single adjacency key */
+pub(crate) fn pack(v1: u32, v2: u32) -> u64 {
+    return ((v1 as u64) << 32) | v2 as u64;
+}
+
+#[cfg(test)]
+mod compact_triangulation {
+    use super::*;
+    use crate::elements::polyline::*;
+    use crate::planar::{triangulation_procedures, triangulator::*};
+    use std::collections::HashSet;
+
+    fn sample_triangulation() -> Triangulation {
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(3.0, 1.0));
+        let v3 = Rc::new(Vertex::new(3.0, 3.0));
+        let v4 = Rc::new(Vertex::new(1.0, 3.0));
+        let v5 = Rc::new(Vertex::new(2.0, 2.5));
+
+        let mut triangulation = Triangulation::from_initial_segment((&v1, &v2));
+        triangulation_procedures::vertices::include(
+            &mut triangulation,
+            vec![v3, v4, v5],
+            &HashSet::new(),
+            &None,
+            &HashSet::new(),
+        );
+        return triangulation;
+    }
+
+    #[test]
+    fn round_trip() {
+        let triangulation = sample_triangulation();
+        let compact = CompactTriangulation::from_triangulation(&triangulation);
+        assert_eq!(compact.vertices.len(), 5);
+        assert_eq!(compact.triangles_count(), triangulation.triangles.len());
+        assert_eq!(compact.adjacency.len(), triangulation.adjacency.len());
+
+        let rebuilt = compact.to_triangulation();
+        assert_eq!(rebuilt.triangles, triangulation.triangles);
+        assert_eq!(rebuilt.adjacency.len(), triangulation.adjacency.len());
+    }
+
+    #[test]
+    fn round_trip_holes() {
+        let square = |min: f64, max: f64| {
+            Rc::new(
+                Polyline::new_closed(vec![
+                    Rc::new(Vertex::new(min, min)),
+                    Rc::new(Vertex::new(max, min)),
+                    Rc::new(Vertex::new(max, max)),
+                    Rc::new(Vertex::new(min, max)),
+                ])
+                .unwrap(),
+            )
+        };
+        let mut triangulator = Triangulator::new(&square(0.0, 9.0));
+        triangulator.insert_hole(&square(1.0, 3.0)).unwrap();
+        triangulator.insert_hole(&square(5.0, 7.0)).unwrap();
+        triangulator.try_triangulate().unwrap();
+        let triangulation = triangulator.triangulation.borrow();
+
+        let compact = CompactTriangulation::from_triangulation(&triangulation);
+        let loops: HashSet<usize> = compact
+            .triangles
+            .iter()
+            .flatten()
+            .filter_map(|&vertex| compact.ghost_loop(vertex))
+            .collect();
+        assert_eq!(loops.len(), 3);
+
+        /* ghost triangles keep the loop of their ghost vertex */
+        let rebuilt = compact.to_triangulation();
+        assert_eq!(rebuilt.triangles, triangulation.triangles);
+        assert_eq!(rebuilt.adjacency.len(), triangulation.adjacency.len());
+        assert_eq!(rebuilt.ghost_loops().len(), 3);
+        assert!(rebuilt.validate().is_ok());
+    }
+
+    #[test]
+    fn adjacency() {
+        let mut compact = CompactTriangulation::new();
+        let v1 = compact.add_vertex(0.0, 0.0);
+        let v2 = compact.add_vertex(1.0, 0.0);
+        let v3 = compact.add_vertex(0.0, 1.0);
+        let v4 = compact.add_vertex(1.0, 1.0);
+
+        let t1 = compact.include_triangle([v1, v2, v3]);
+        let t2 = compact.include_triangle([v2, v4, v3]);
+        assert_eq!(compact.triangle_at(v2, v3), Some(t1));
+        assert_eq!(compact.neighbor(t1, 1), Some(t2));
+        assert_eq!(compact.neighbor(t2, 2), Some(t1));
+        assert_eq!(compact.neighbor(t1, 0), None);
+
+        assert!(compact.remove_triangle(t1));
+        assert!(!compact.remove_triangle(t1));
+        assert_eq!(compact.neighbor(t2, 2), None);
+
+        /* removed slots are reused */
+        assert_eq!(compact.include_triangle([v1, v2, v3]), t1);
+        assert_eq!(compact.triangles_count(), 2);
+    }
+
+    #[test]
+    fn locate() {
+        let compact = CompactTriangulation::from_triangulation(&sample_triangulation());
+
+        let handle = compact.locate(2.9, 1.1).unwrap();
+        assert!(!compact.is_ghost(handle));
+        assert!(compact.triangles[handle as usize]
+            .iter()
+            .any(|&v| compact.vertices[v as usize] == (3.0, 1.0)));
+
+        assert!(compact.locate(5.0, 5.0).is_none());
+    }
+} /* end - compact_triangulation tests */
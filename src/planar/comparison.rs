@@ -0,0 +1,187 @@
+use crate::elements::{edge::*, triangle::*, vertex::*};
+use crate::planar::triangulation::*;
+use crate::properties::distance::*;
+
+use std::collections::HashSet;
+use std::fmt;
+use std::rc::Rc;
+
+/**
+ * Differences between two triangulations of the same input, such as the
+ * outputs of two versions of the mesher: vertex and triangle counts, the
+ * Hausdorff distance between their boundaries, and the triangles of each
+ * one missing from the other.
+ */
+#[derive(Debug)]
+pub struct Comparison {
+    pub vertices_count: (usize, usize),
+    pub triangles_count: (usize, usize),
+    pub boundary_distance: f64,
+    pub only_in_first: Vec<Rc<Triangle>>,
+    pub only_in_second: Vec<Rc<Triangle>>,
+}
+
+impl Comparison {
+    /**
+     * Compares the solid triangles of both triangulations. Triangles match
+     * if they have the same vertices, wherever they start. The boundary
+     * distance is sampled at the boundary vertices of each triangulation,
+     * against the boundary edges of the other one.
+     */
+    pub fn new(first: &Triangulation, second: &Triangulation) -> Self {
+        let first_triangles: HashSet<[Rc<Vertex>; 3]> =
+            first.solid_triangles().map(|t| corners(t)).collect();
+        let second_triangles: HashSet<[Rc<Vertex>; 3]> =
+            second.solid_triangles().map(|t| corners(t)).collect();
+        let missing = |triangulation: &Triangulation, other: &HashSet<[Rc<Vertex>; 3]>| {
+            let mut triangles: Vec<Rc<Triangle>> = triangulation
+                .solid_triangles()
+                .filter(|t| !other.contains(&corners(t)))
+                .cloned()
+                .collect();
+            triangles.sort_by(|t1, t2| corners(t1).cmp(&corners(t2)));
+            return triangles;
+        };
+
+        let first_boundary: Vec<Rc<Edge>> = first.boundary_edges().collect();
+        let second_boundary: Vec<Rc<Edge>> = second.boundary_edges().collect();
+        let boundary_distance = directed_distance(&first_boundary, &second_boundary)
+            .max(directed_distance(&second_boundary, &first_boundary));
+
+        return Self {
+            vertices_count: (first.vertices().len(), second.vertices().len()),
+            triangles_count: (first_triangles.len(), second_triangles.len()),
+            boundary_distance,
+            only_in_first: missing(first, &second_triangles),
+            only_in_second: missing(second, &first_triangles),
+        };
+    }
+
+    /**
+     * Whether both triangulations have the same triangles, thus the same
+     * vertices and boundaries.
+     */
+    pub fn is_identical(&self) -> bool {
+        return self.only_in_first.is_empty() && self.only_in_second.is_empty();
+    }
+} /* end - Comparison */
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (first, second) = self.vertices_count;
+        writeln!(
+            formatter,
+            "Vertices: {} {} ({:+})",
+            first,
+            second,
+            second as i64 - first as i64
+        )?;
+        let (first, second) = self.triangles_count;
+        writeln!(
+            formatter,
+            "Triangles: {} {} ({:+})",
+            first,
+            second,
+            second as i64 - first as i64
+        )?;
+        writeln!(
+            formatter,
+            "Boundary Hausdorff distance: {:e}",
+            self.boundary_distance
+        )?;
+        writeln!(
+            formatter,
+            "Triangles only in first: {}",
+            self.only_in_first.len()
+        )?;
+        for triangle in self.only_in_first.iter() {
+            writeln!(formatter, "  {}", triangle)?;
+        }
+        writeln!(
+            formatter,
+            "Triangles only in second: {}",
+            self.only_in_second.len()
+        )?;
+        for triangle in self.only_in_second.iter() {
+            writeln!(formatter, "  {}", triangle)?;
+        }
+        return Ok(());
+    }
+}
+
+/* vertices of the triangle, starting at the lowest one, keeping orientation */
+fn corners(triangle: &Triangle) -> [Rc<Vertex>; 3] {
+    let vertices = [&triangle.v1, &triangle.v2, &triangle.v3];
+    let lowest = (0..3)
+        .min_by(|&i, &j| vertices[i].cmp(vertices[j]))
+        .unwrap();
+    return [
+        Rc::clone(vertices[lowest]),
+        Rc::clone(vertices[(lowest + 1) % 3]),
+        Rc::clone(vertices[(lowest + 2) % 3]),
+    ];
+}
+
+/* farthest distance from the vertices of the edges to the other edges */
+fn directed_distance(edges: &[Rc<Edge>], others: &[Rc<Edge>]) -> f64 {
+    if edges.is_empty() || others.is_empty() {
+        return match edges.is_empty() && others.is_empty() {
+            true => 0.0,
+            false => std::f64::INFINITY,
+        };
+    }
+    return edges
+        .iter()
+        .map(|edge| {
+            others
+                .iter()
+                .map(|other| edge_distance(other, &edge.v1))
+                .fold(std::f64::INFINITY, f64::min)
+        })
+        .fold(0.0, f64::max);
+}
+
+#[cfg(test)]
+mod comparison {
+    use super::*;
+    use crate::elements::bounding_box::*;
+    use crate::planar::structured;
+
+    fn rectangle(width: f64, columns: usize) -> Triangulation {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(width, 1.0)),
+        ])
+        .unwrap();
+        return structured::rectangle(&bounding_box, columns, 1).unwrap();
+    }
+
+    #[test]
+    fn identical() {
+        let comparison = Comparison::new(&rectangle(2.0, 2), &rectangle(2.0, 2));
+        assert!(comparison.is_identical());
+        assert_eq!(comparison.vertices_count, (6, 6));
+        assert_eq!(comparison.triangles_count, (4, 4));
+        assert_eq!(comparison.boundary_distance, 0.0);
+    }
+
+    #[test]
+    fn differences() {
+        /* second one is stretched by one more column */
+        let comparison = Comparison::new(&rectangle(2.0, 2), &rectangle(3.0, 3));
+        assert!(!comparison.is_identical());
+        assert_eq!(comparison.vertices_count, (6, 8));
+        assert_eq!(comparison.triangles_count, (4, 6));
+
+        /* right side moved by 1 */
+        assert!((comparison.boundary_distance - 1.0).abs() < 1.0E-12);
+
+        /* first one is the left part of the second one */
+        assert!(comparison.only_in_first.is_empty());
+        assert_eq!(comparison.only_in_second.len(), 2);
+
+        let report = format!("{}", comparison);
+        assert!(report.contains("Vertices: 6 8 (+2)"));
+        assert!(report.contains("Triangles only in second: 2"));
+    }
+} /* end - comparison tests */
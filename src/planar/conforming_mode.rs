@@ -0,0 +1,13 @@
+/**
+ * Determines how segment constraints are recovered by the triangulator.
+ *  - Constrained: segments are forced into the triangulation, which may not
+ * be Delaunay around them.
+ *  - Conforming: segments are split by Steiner points placed on the segments
+ * themselves, until every subsegment is a Delaunay edge. Vertices are never
+ * inserted in the interior of the domain.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConformingMode {
+    Constrained,
+    Conforming,
+}
@@ -0,0 +1,148 @@
+use crate::elements::{edge::*, polyline::*, triangle::*, vertex::*};
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChainKind {
+    Boundary,
+    Hole,
+    Segment,
+}
+
+/**
+ * Constraint polyline, as the ordered sub-edges it is currently split in.
+ * Boundary and holes chains are closed, segment chains are closed only if
+ * the segments make a loop.
+ */
+pub struct ConstraintChain {
+    pub kind: ChainKind,
+    pub polyline: Polyline,
+}
+
+impl ConstraintChain {
+    pub fn edges(&self) -> Vec<Rc<Edge>> {
+        return self.polyline.into_edges();
+    }
+} /* end - ConstraintChain */
+
+/**
+ * Closed loop of constrained segments inside the boundary, as found on the
+ * triangulation: the counterclockwise polyline of its sub-edges, a seed
+ * point inside it, and the triangles it encloses. It may be carved as a
+ * hole, or its triangles tagged as a region.
+ */
+pub struct ConstraintLoop {
+    pub polyline: Polyline,
+    pub seed: Rc<Vertex>,
+    pub triangles: HashSet<Rc<Triangle>>,
+}
+
+/**
+ * Joins the segments into the polylines they make, whatever their
+ * orientations, breaking them at vertices not shared by exactly two
+ * segments. Chains are walked from the leftmost, then lowest, free vertex,
+ * so that they are the same for the same segments.
+ */
+pub fn chain_segments(segments: &HashSet<Rc<Edge>>) -> Vec<Polyline> {
+    let mut neighbors: HashMap<Rc<Vertex>, Vec<Rc<Vertex>>> = HashMap::new();
+    for segment in segments.iter() {
+        neighbors
+            .entry(Rc::clone(&segment.v1))
+            .or_insert(Vec::new())
+            .push(Rc::clone(&segment.v2));
+        neighbors
+            .entry(Rc::clone(&segment.v2))
+            .or_insert(Vec::new())
+            .push(Rc::clone(&segment.v1));
+    }
+
+    let mut starts: Vec<Rc<Vertex>> = neighbors.keys().cloned().collect();
+    starts.sort_by(|v1, v2| {
+        return v1
+            .x
+            .partial_cmp(&v2.x)
+            .unwrap()
+            .then(v1.y.partial_cmp(&v2.y).unwrap());
+    });
+    /* open chains start at free ends, loops anywhere */
+    starts.sort_by_key(|v| neighbors.get(v).unwrap().len() == 2);
+
+    let mut visited: HashSet<Edge> = HashSet::new();
+    let mut chains: Vec<Polyline> = Vec::new();
+    for start in starts.iter() {
+        for first in neighbors.get(start).unwrap().iter() {
+            if visited.contains(&Edge::new(start, first)) {
+                continue;
+            }
+
+            let mut chain: Vec<Rc<Vertex>> = vec![Rc::clone(start)];
+            let mut current = Rc::clone(first);
+            visited.insert(Edge::new(start, first));
+            visited.insert(Edge::new(first, start));
+            loop {
+                chain.push(Rc::clone(&current));
+                if current == *start {
+                    break;
+                }
+                let next = match neighbors.get(&current) {
+                    Some(next) if next.len() == 2 => next
+                        .iter()
+                        .find(|v| !visited.contains(&Edge::new(&current, v)))
+                        .cloned(),
+                    _ => None,
+                };
+                match next {
+                    Some(next) => {
+                        visited.insert(Edge::new(&current, &next));
+                        visited.insert(Edge::new(&next, &current));
+                        current = next;
+                    }
+                    None => break,
+                }
+            }
+
+            if chain.len() > 3 && chain.first() == chain.last() {
+                chain.pop();
+                chains.push(Polyline::new_closed(chain).unwrap());
+            } else {
+                chains.push(Polyline::new_opened(chain).unwrap());
+            }
+        }
+    }
+
+    return chains;
+}
+
+#[cfg(test)]
+mod chain_segments {
+    use super::*;
+
+    #[test]
+    fn open_and_closed_chains() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(2.0, 1.0));
+        let v4 = Rc::new(Vertex::new(5.0, 5.0));
+        let v5 = Rc::new(Vertex::new(6.0, 5.0));
+        let v6 = Rc::new(Vertex::new(6.0, 6.0));
+
+        let segments: HashSet<Rc<Edge>> = vec![
+            Edge::new(&v2, &v1),
+            Edge::new(&v2, &v3),
+            Edge::new(&v4, &v5),
+            Edge::new(&v5, &v6),
+            Edge::new(&v4, &v6),
+        ]
+        .into_iter()
+        .map(Rc::new)
+        .collect();
+
+        let chains = chain_segments(&segments);
+        assert_eq!(chains.len(), 2);
+        assert!(chains[0].opened);
+        assert_eq!(chains[0].vertices, vec![v1, v2, v3]);
+        assert!(!chains[1].opened);
+        assert_eq!(chains[1].vertices.len(), 3);
+    }
+} /* end - chain_segments tests */
@@ -0,0 +1,189 @@
+use crate::elements::{edge::*, vertex::*};
+use crate::planar::{render, render::RenderOptions, timings::*, triangulation::*};
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/**
+ * Receives the intermediate triangulations of triangulation and refinement,
+ * after the boundary, each hole and each segment are included, after the
+ * remaining vertices are inserted, and after each unencroachment and
+ * triangle split phase, along with the constraints recovered so far.
+ */
+pub trait DebugObserver {
+    fn snapshot(
+        &mut self,
+        phase: Phase,
+        triangulation: &Triangulation,
+        constraints: &HashSet<Rc<Edge>>,
+    );
+}
+
+/**
+ * Hands the triangulation to the observer, if any.
+ */
+pub fn dump(
+    observer: &mut Option<Box<dyn DebugObserver>>,
+    phase: Phase,
+    triangulation: &Triangulation,
+    constraints: &HashSet<Rc<Edge>>,
+) {
+    if let Some(observer) = observer {
+        observer.snapshot(phase, triangulation, constraints);
+    }
+}
+
+/**
+ * Index based snapshot, as written to json: points sorted by coordinates,
+ * solid triangles and constrained edges as indices of points.
+ */
+#[derive(Serialize)]
+struct Snapshot {
+    phase: &'static str,
+    coordinates: Vec<f64>,
+    triangles: Vec<[usize; 3]>,
+    constraints: Vec<[usize; 2]>,
+}
+
+impl Snapshot {
+    fn new(phase: Phase, triangulation: &Triangulation, constraints: &HashSet<Rc<Edge>>) -> Self {
+        let mut vertices: Vec<Rc<Vertex>> = triangulation.vertices().iter().cloned().collect();
+        vertices.sort_by(|v1, v2| v1.partial_cmp(v2).unwrap());
+        let indices: HashMap<Rc<Vertex>, usize> = vertices
+            .iter()
+            .enumerate()
+            .map(|(index, v)| (Rc::clone(v), index))
+            .collect();
+
+        let mut triangles: Vec<[usize; 3]> = triangulation
+            .solid_triangles()
+            .map(|t| [indices[&t.v1], indices[&t.v2], indices[&t.v3]])
+            .collect();
+        triangles.sort();
+
+        /* constraints not recovered yet have ends out of the triangulation */
+        let mut constraints: Vec<[usize; 2]> = constraints
+            .iter()
+            .filter_map(|e| Some([*indices.get(&e.v1)?, *indices.get(&e.v2)?]))
+            .collect();
+        constraints.sort();
+
+        return Self {
+            phase: phase.name(),
+            coordinates: vertices.iter().map(|v| vec![v.x, v.y]).flatten().collect(),
+            triangles,
+            constraints,
+        };
+    }
+} /* end - Snapshot */
+
+/**
+ * Writes each snapshot to the directory as a numbered pair of files, an SVG
+ * render and its json data, named after the count of snapshots so far and
+ * the phase, as 0003_segment_recovery.svg. The directory is created at the
+ * first snapshot. Snapshots go on after a failed write, whose error is kept.
+ */
+pub struct DirectoryDump {
+    pub directory: PathBuf,
+    pub options: RenderOptions,
+    count: usize,
+    error: Option<io::Error>,
+}
+
+impl DirectoryDump {
+    pub fn new(directory: &Path) -> Self {
+        Self {
+            directory: directory.to_path_buf(),
+            options: RenderOptions::default(),
+            count: 0,
+            error: None,
+        }
+    }
+
+    /**
+     * Count of snapshots taken so far.
+     */
+    pub fn count(&self) -> usize {
+        return self.count;
+    }
+
+    /**
+     * First error writing the snapshots, if any.
+     */
+    pub fn error(&self) -> Option<&io::Error> {
+        return self.error.as_ref();
+    }
+
+    fn write(
+        &self,
+        phase: Phase,
+        triangulation: &Triangulation,
+        constraints: &HashSet<Rc<Edge>>,
+    ) -> io::Result<()> {
+        std::fs::create_dir_all(&self.directory)?;
+        let stem = format!("{:04}_{}", self.count, phase.name().replace(' ', "_"));
+
+        let svg = render::triangulation_svg(triangulation, constraints, &self.options);
+        std::fs::write(self.directory.join(format!("{}.svg", stem)), svg)?;
+
+        let snapshot = Snapshot::new(phase, triangulation, constraints);
+        let json = serde_json::to_string(&snapshot)?;
+        std::fs::write(self.directory.join(format!("{}.json", stem)), json)?;
+        return Ok(());
+    }
+} /* end - DirectoryDump */
+
+impl DebugObserver for DirectoryDump {
+    fn snapshot(
+        &mut self,
+        phase: Phase,
+        triangulation: &Triangulation,
+        constraints: &HashSet<Rc<Edge>>,
+    ) {
+        if let Err(error) = self.write(phase, triangulation, constraints) {
+            if self.error.is_none() {
+                self.error = Some(error);
+            }
+        }
+        self.count += 1;
+    }
+}
+
+#[cfg(test)]
+mod directory_dump {
+    use super::*;
+    use crate::elements::triangle::*;
+
+    #[test]
+    fn writes_numbered_snapshots() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v1, &v2, &v3)));
+        let constraints: HashSet<Rc<Edge>> =
+            vec![Rc::new(Edge::new(&v1, &v2))].into_iter().collect();
+
+        let directory = std::env::temp_dir().join(format!(
+            "nlsn_delaunay_debug_dump_{}",
+            std::process::id()
+        ));
+        let mut observer: Option<Box<dyn DebugObserver>> =
+            Some(Box::new(DirectoryDump::new(&directory)));
+        dump(&mut observer, Phase::BoundaryInclusion, &triangulation, &constraints);
+        dump(&mut observer, Phase::TriangleSplit, &triangulation, &HashSet::new());
+
+        let svg = std::fs::read_to_string(directory.join("0000_boundary_inclusion.svg")).unwrap();
+        assert!(svg.starts_with("<svg"));
+        let json = std::fs::read_to_string(directory.join("0001_triangle_split.json")).unwrap();
+        assert_eq!(
+            json,
+            "{\"phase\":\"triangle split\",\"coordinates\":[0.0,0.0,0.0,1.0,1.0,0.0],\
+             \"triangles\":[[0,2,1]],\"constraints\":[]}"
+        );
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+} /* end - directory_dump tests */
@@ -0,0 +1,374 @@
+use crate::elements::{edge::*, polyline::*, triangle::*, vertex::*};
+use crate::planar::triangulation::*;
+use crate::properties::continence::*;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/**
+ * Voronoi dual metrics of a triangulation, as finite volume schemes need:
+ * the area of the Voronoi cell of each vertex, and the length of the
+ * Voronoi face dual to each edge. Cells are clipped at the triangulation
+ * border, where the face dual to a border edge ends at the edge midpoint.
+ * Each triangle splits into three kites, joining its circumcenter to its
+ * edges midpoints, whose areas are signed, so that circumcenters falling
+ * out of their triangles are accounted for, and cell areas sum up to the
+ * triangulation area.
+ */
+pub struct DualMesh {
+    pub cell_areas: HashMap<Rc<Vertex>, f64>,
+    pub face_lengths: HashMap<Rc<Edge>, f64>,
+}
+
+impl DualMesh {
+    pub fn new(triangulation: &Triangulation) -> Self {
+        let mut cell_areas: HashMap<Rc<Vertex>, f64> = HashMap::new();
+        let mut face_lengths: HashMap<Rc<Edge>, f64> = HashMap::new();
+
+        for triangle in triangulation.solid_triangles() {
+            let circumcenter = match triangle.circumcenter() {
+                Some(circumcenter) => circumcenter,
+                None => continue,
+            };
+
+            let (e1, e2, e3) = triangle.inner_edges();
+            for edge in vec![e1, e2, e3] {
+                /* signed distance from the edge to the circumcenter, inwards */
+                let length = edge.length();
+                let height = ((edge.v2.x - edge.v1.x) * (circumcenter.y - edge.v1.y)
+                    - (edge.v2.y - edge.v1.y) * (circumcenter.x - edge.v1.x))
+                    / length;
+
+                /* each edge end takes half of the triangle between edge and circumcenter */
+                let kite_area = length * height / 4.0;
+                *cell_areas.entry(Rc::clone(&edge.v1)).or_insert(0.0) += kite_area;
+                *cell_areas.entry(Rc::clone(&edge.v2)).or_insert(0.0) += kite_area;
+
+                let key = match face_lengths.contains_key(&edge.opposite()) {
+                    true => Rc::new(edge.opposite()),
+                    false => Rc::clone(&edge),
+                };
+                *face_lengths.entry(key).or_insert(0.0) += height;
+            }
+        }
+
+        return Self {
+            cell_areas,
+            face_lengths,
+        };
+    }
+
+    pub fn cell_area(&self, vertex: &Rc<Vertex>) -> Option<f64> {
+        return self.cell_areas.get(vertex).cloned();
+    }
+
+    /**
+     * Length of the face dual to the edge, whatever its orientation.
+     */
+    pub fn face_length(&self, v1: &Rc<Vertex>, v2: &Rc<Vertex>) -> Option<f64> {
+        if let Some(length) = self.face_lengths.get(&Edge::new(v1, v2)) {
+            return Some(*length);
+        }
+        return self.face_lengths.get(&Edge::new(v2, v1)).cloned();
+    }
+} /* end - DualMesh */
+
+/**
+ * Voronoi cell of a vertex within a region, as clipped by the region and
+ * its holes, so that finite volume solvers may integrate over it. Its
+ * neighbors are the vertices sharing a triangulation edge with it, whose
+ * cells are clipped by the same region.
+ */
+#[derive(Clone, Debug)]
+pub struct VoronoiCell {
+    pub vertex: Rc<Vertex>,
+    pub region: usize,
+    pub polygons: Vec<Rc<Polyline>>,
+    pub area: f64,
+    pub neighbors: Vec<Rc<Vertex>>,
+}
+
+/**
+ * Voronoi cells of the triangulation vertices, clipped by each region they
+ * overlap, and subtracting the holes. Cells of vertices on the border of the
+ * triangulation are closed by the vertex and the midpoints of its border
+ * edges, as in DualMesh. Vertices on the interface of regions have a cell in
+ * each of them, while cells out of every region are dropped.
+ */
+pub fn clipped_cells(
+    triangulation: &Triangulation,
+    regions: &[Rc<Polyline>],
+    holes: &[Rc<Polyline>],
+) -> Vec<VoronoiCell> {
+    let mut vertex_triangles: HashMap<Rc<Vertex>, Vec<Rc<Triangle>>> = HashMap::new();
+    for triangle in triangulation.solid_triangles() {
+        for vertex in vec![&triangle.v1, &triangle.v2, &triangle.v3] {
+            vertex_triangles
+                .entry(Rc::clone(vertex))
+                .or_insert(Vec::new())
+                .push(Rc::clone(triangle));
+        }
+    }
+
+    let mut cells: Vec<VoronoiCell> = Vec::new();
+    for (vertex, triangles) in vertex_triangles.iter() {
+        let (cell, neighbors) = match raw_cell(triangulation, vertex, triangles) {
+            Some(raw_cell) => raw_cell,
+            None => continue,
+        };
+
+        for (region_index, region) in regions.iter().enumerate() {
+            let polygons = clip(&cell, region, holes);
+            if polygons.is_empty() {
+                continue;
+            }
+            let area = polygons.iter().map(|p| p.area().unwrap().abs()).sum();
+            cells.push(VoronoiCell {
+                vertex: Rc::clone(vertex),
+                region: region_index,
+                polygons,
+                area,
+                neighbors: neighbors.clone(),
+            });
+        }
+    }
+
+    /* neighbors out of the region are dropped */
+    let mut region_vertices: HashMap<usize, Vec<Rc<Vertex>>> = HashMap::new();
+    for cell in cells.iter() {
+        region_vertices
+            .entry(cell.region)
+            .or_insert(Vec::new())
+            .push(Rc::clone(&cell.vertex));
+    }
+    for cell in cells.iter_mut() {
+        let vertices = &region_vertices[&cell.region];
+        cell.neighbors.retain(|neighbor| vertices.contains(neighbor));
+    }
+
+    return cells;
+} /* end - clipped_cells */
+
+/**
+ * Unclipped Voronoi cell of the vertex, joining the circumcenters of its
+ * triangles counterclockwise, and its neighbors along. Returns None if its
+ * triangles do not make a single fan, or if the cell has no area.
+ */
+fn raw_cell(
+    triangulation: &Triangulation,
+    vertex: &Rc<Vertex>,
+    triangles: &Vec<Rc<Triangle>>,
+) -> Option<(Rc<Polyline>, Vec<Rc<Vertex>>)> {
+    /* the other vertices of the triangle, counterclockwise after the vertex */
+    let others = |triangle: &Triangle| {
+        if triangle.v1 == *vertex {
+            return (Rc::clone(&triangle.v2), Rc::clone(&triangle.v3));
+        } else if triangle.v2 == *vertex {
+            return (Rc::clone(&triangle.v3), Rc::clone(&triangle.v1));
+        }
+        return (Rc::clone(&triangle.v1), Rc::clone(&triangle.v2));
+    };
+    let solid_neighbor = |edge: &Edge| match triangulation.adjacency.get(edge) {
+        Some(triangle) if !triangle.is_ghost() => Some(Rc::clone(triangle)),
+        _ => None,
+    };
+
+    /* starts at the clockwise end of the fan, if on the border */
+    let first = triangles
+        .iter()
+        .find(|t| solid_neighbor(&Edge::new(&others(t).0, vertex)).is_none())
+        .unwrap_or(&triangles[0]);
+    let is_border = solid_neighbor(&Edge::new(&others(first).0, vertex)).is_none();
+
+    let mut fan: Vec<Rc<Triangle>> = vec![Rc::clone(first)];
+    loop {
+        let (_, c) = others(fan.last().unwrap());
+        match solid_neighbor(&Edge::new(vertex, &c)) {
+            Some(next) if next == *first => break,
+            Some(next) => fan.push(next),
+            None => break,
+        }
+        if fan.len() > triangles.len() {
+            return None;
+        }
+    }
+    if fan.len() != triangles.len() {
+        return None;
+    }
+
+    let mut cell_vertices: Vec<Rc<Vertex>> = Vec::new();
+    let mut neighbors: Vec<Rc<Vertex>> = Vec::new();
+    if is_border {
+        let (b, _) = others(first);
+        cell_vertices.push(Rc::clone(vertex));
+        cell_vertices.push(Rc::new(Edge::new(vertex, &b).midpoint()));
+        neighbors.push(b);
+    }
+    for triangle in fan.iter() {
+        cell_vertices.push(Rc::new(triangle.circumcenter()?));
+        neighbors.push(others(triangle).1);
+    }
+    if is_border {
+        let (_, c) = others(fan.last().unwrap());
+        cell_vertices.push(Rc::new(Edge::new(vertex, &c).midpoint()));
+    } else {
+        /* the last neighbor closes the ring at the first one */
+        neighbors.pop();
+        neighbors.insert(0, others(first).0);
+    }
+
+    let cell = Polyline::new_closed_welded(cell_vertices, 0.0)?;
+    if !(cell.area()?.abs() > 0.0) {
+        return None;
+    }
+    return Some((Rc::new(cell.ensure_ccw()), neighbors));
+} /* end - raw_cell */
+
+/**
+ * Pieces of the cell within the region and out of the holes.
+ */
+fn clip(cell: &Rc<Polyline>, region: &Polyline, holes: &[Rc<Polyline>]) -> Vec<Rc<Polyline>> {
+    let mut pieces: Vec<Rc<Polyline>> = match Polyline::continence(region, cell) {
+        Some((Continence::Inside, _)) => vec![Rc::clone(cell)],
+        _ => Polyline::intersection(&region.ensure_ccw(), cell)
+            .0
+            .into_iter()
+            .map(Rc::new)
+            .collect(),
+    };
+
+    for hole in holes.iter() {
+        let hole = hole.ensure_ccw();
+        pieces = pieces
+            .into_iter()
+            .map(|piece| match Polyline::continence(&hole, &piece) {
+                Some((Continence::Outside, _)) => vec![piece],
+                _ => Polyline::subtraction(&piece, &hole).0,
+            })
+            .flatten()
+            .collect();
+    }
+
+    return pieces
+        .into_iter()
+        .filter(|piece| piece.area().map_or(false, |area| area.abs() > 0.0))
+        .collect();
+} /* end - clip */
+
+#[cfg(test)]
+mod dual_mesh {
+    use super::*;
+    use crate::elements::bounding_box::*;
+    use crate::planar::structured;
+
+    #[test]
+    fn unit_grid() {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(3.0, 3.0)),
+        ])
+        .unwrap();
+        let triangulation = structured::rectangle(&bounding_box, 3, 3).unwrap();
+        let dual = DualMesh::new(&triangulation);
+
+        let interior = Rc::new(Vertex::new(1.0, 1.0));
+        let border = Rc::new(Vertex::new(1.0, 0.0));
+        let corner = Rc::new(Vertex::new(0.0, 0.0));
+        assert!((dual.cell_area(&interior).unwrap() - 1.0).abs() < 1.0E-12);
+        assert!((dual.cell_area(&border).unwrap() - 0.5).abs() < 1.0E-12);
+        assert!((dual.cell_area(&corner).unwrap() - 0.25).abs() < 1.0E-12);
+
+        let total: f64 = dual.cell_areas.values().sum();
+        assert!((total - 9.0).abs() < 1.0E-12);
+
+        /* grid faces are unit long, diagonals are dual to a single point */
+        let right = Rc::new(Vertex::new(2.0, 1.0));
+        let diagonal = Rc::new(Vertex::new(2.0, 2.0));
+        assert!((dual.face_length(&interior, &right).unwrap() - 1.0).abs() < 1.0E-12);
+        assert!((dual.face_length(&right, &interior).unwrap() - 1.0).abs() < 1.0E-12);
+        assert!(dual.face_length(&interior, &diagonal).unwrap().abs() < 1.0E-12);
+        assert!((dual.face_length(&corner, &border).unwrap() - 0.5).abs() < 1.0E-12);
+        assert_eq!(dual.face_length(&corner, &diagonal), None);
+    }
+
+    fn square(x1: f64, y1: f64, x2: f64, y2: f64) -> Rc<Polyline> {
+        return Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(x1, y1)),
+                Rc::new(Vertex::new(x2, y1)),
+                Rc::new(Vertex::new(x2, y2)),
+                Rc::new(Vertex::new(x1, y2)),
+            ])
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn clipped_cells() {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(3.0, 3.0)),
+        ])
+        .unwrap();
+        let triangulation = structured::rectangle(&bounding_box, 3, 3).unwrap();
+
+        let cells = super::clipped_cells(&triangulation, &[square(0.0, 0.0, 3.0, 3.0)], &[]);
+        assert_eq!(cells.len(), 16);
+        let total: f64 = cells.iter().map(|cell| cell.area).sum();
+        assert!((total - 9.0).abs() < 1.0E-12);
+
+        let interior = Rc::new(Vertex::new(1.0, 1.0));
+        let corner = Rc::new(Vertex::new(0.0, 0.0));
+        let cell_of = |cells: &Vec<VoronoiCell>, vertex: &Rc<Vertex>| -> VoronoiCell {
+            return cells.iter().find(|cell| cell.vertex == *vertex).unwrap().clone();
+        };
+        assert!((cell_of(&cells, &interior).area - 1.0).abs() < 1.0E-12);
+        assert_eq!(cell_of(&cells, &interior).neighbors.len(), 6);
+        assert!((cell_of(&cells, &corner).area - 0.25).abs() < 1.0E-12);
+
+        /* a hole overlapping a quarter of the interior cell */
+        let cells = super::clipped_cells(
+            &triangulation,
+            &[square(0.0, 0.0, 3.0, 3.0)],
+            &[square(1.25, 1.25, 1.75, 1.75)],
+        );
+        assert!((cell_of(&cells, &interior).area - 0.9375).abs() < 1.0E-12);
+        let total: f64 = cells.iter().map(|cell| cell.area).sum();
+        assert!((total - 8.75).abs() < 1.0E-12);
+    }
+
+    #[test]
+    fn clipped_cells_per_region() {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(3.0, 3.0)),
+        ])
+        .unwrap();
+        let triangulation = structured::rectangle(&bounding_box, 3, 3).unwrap();
+        let regions = [square(0.0, 0.0, 1.0, 3.0), square(1.0, 0.0, 3.0, 3.0)];
+
+        let cells = super::clipped_cells(&triangulation, &regions, &[]);
+        let area_of = |region: usize| -> f64 {
+            return cells
+                .iter()
+                .filter(|cell| cell.region == region)
+                .map(|cell| cell.area)
+                .sum();
+        };
+        assert!((area_of(0) - 3.0).abs() < 1.0E-12);
+        assert!((area_of(1) - 6.0).abs() < 1.0E-12);
+
+        /* interface vertices have a cell on each side */
+        let interface = Rc::new(Vertex::new(1.0, 1.0));
+        let interface_cells: Vec<&VoronoiCell> =
+            cells.iter().filter(|cell| cell.vertex == interface).collect();
+        assert_eq!(interface_cells.len(), 2);
+        for cell in interface_cells.iter() {
+            assert!((cell.area - 0.5).abs() < 1.0E-12);
+        }
+
+        /* neighbors are kept within the region */
+        let left_cell = interface_cells.iter().find(|cell| cell.region == 0).unwrap();
+        assert!(left_cell.neighbors.iter().all(|v| v.x <= 1.0));
+    }
+} /* end - dual_mesh tests */
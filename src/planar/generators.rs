@@ -0,0 +1,309 @@
+use crate::elements::{bounding_box::*, edge::*, polyline::*, vertex::*};
+use crate::planar::insertion_order::XorShift;
+use crate::properties::{continence::*, distance::*};
+
+use std::collections::HashSet;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+/**
+ * Arrangements of seed points filling a domain, so that refinement starts
+ * from a uniform background instead of the boundary alone.
+ *  - Grid: vertices of a square grid of the spacing.
+ *  - Jittered: one vertex at random inside each cell of the grid.
+ *  - Poisson: Poisson-disk sampling, random vertices no closer than the
+ * spacing to each other.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SeedPattern {
+    Grid,
+    Jittered,
+    Poisson,
+}
+
+impl SeedPattern {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "grid" => return Some(SeedPattern::Grid),
+            "jittered" => return Some(SeedPattern::Jittered),
+            "poisson" => return Some(SeedPattern::Poisson),
+            _ => return None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SeedPattern::Grid => return "grid",
+            SeedPattern::Jittered => return "jittered",
+            SeedPattern::Poisson => return "poisson",
+        }
+    }
+} /* end - SeedPattern */
+
+/* Candidates tried around each active sample of Poisson-disk sampling */
+const POISSON_CANDIDATES: usize = 30;
+
+/**
+ * Seed points of the pattern inside the boundary and outside the holes.
+ * Points closer than half the spacing to the boundary, holes or segments
+ * are dropped, so that they do not encroach the constraints. Random
+ * patterns are reproducible for the seed. Returns no points for non
+ * positive spacings.
+ */
+pub fn seed_points(
+    boundary: &Polyline,
+    holes: &HashSet<Rc<Polyline>>,
+    segments: &HashSet<Rc<Edge>>,
+    pattern: SeedPattern,
+    spacing: f64,
+    seed: u64,
+) -> Vec<Rc<Vertex>> {
+    let bounding_box = match boundary.bounding_box() {
+        Some(bounding_box) => bounding_box,
+        None => return Vec::new(),
+    };
+    if !(spacing > 0.0) {
+        return Vec::new();
+    }
+
+    let constraints: Vec<Rc<Edge>> = boundary
+        .into_edges()
+        .into_iter()
+        .chain(holes.iter().map(|hole| hole.into_edges()).flatten())
+        .chain(segments.iter().cloned())
+        .collect();
+    let is_admissible = |vertex: &Vertex| {
+        return boundary.contains(vertex) == Some(Continence::Inside)
+            && holes
+                .iter()
+                .all(|hole| hole.contains(vertex) == Some(Continence::Outside))
+            && constraints
+                .iter()
+                .all(|edge| edge_distance(edge, vertex) >= spacing / 2.0);
+    };
+
+    let mut random = XorShift::new(seed);
+    let candidates: Vec<Vertex> = match pattern {
+        SeedPattern::Grid => grid(&bounding_box, spacing, &mut |_| 0.5),
+        SeedPattern::Jittered => grid(&bounding_box, spacing, &mut |_| random.next_unit()),
+        SeedPattern::Poisson => {
+            return poisson(&bounding_box, spacing, &mut random, &is_admissible)
+                .into_iter()
+                .map(Rc::new)
+                .collect();
+        }
+    };
+
+    return candidates
+        .into_iter()
+        .filter(|vertex| is_admissible(vertex))
+        .map(Rc::new)
+        .collect();
+} /* end - seed_points */
+
+/**
+ * One vertex per cell of the grid of the spacing, centered in the bounding
+ * box, placed at the cell fractions given by offset for each coordinate.
+ */
+fn grid(
+    bounding_box: &BoundingBox,
+    spacing: f64,
+    offset: &mut dyn FnMut(usize) -> f64,
+) -> Vec<Vertex> {
+    let width = bounding_box.destin.x - bounding_box.origin.x;
+    let height = bounding_box.destin.y - bounding_box.origin.y;
+    let nx = (width / spacing).floor() as usize;
+    let ny = (height / spacing).floor() as usize;
+    let x0 = bounding_box.origin.x + (width - nx as f64 * spacing) / 2.0;
+    let y0 = bounding_box.origin.y + (height - ny as f64 * spacing) / 2.0;
+
+    let mut vertices: Vec<Vertex> = Vec::new();
+    for i in 0..nx {
+        for j in 0..ny {
+            let x = x0 + (i as f64 + offset(0)) * spacing;
+            let y = y0 + (j as f64 + offset(1)) * spacing;
+            vertices.push(Vertex::new(x, y));
+        }
+    }
+    return vertices;
+}
+
+/**
+ * Bridson's Poisson-disk sampling, whose neighbor queries are answered by
+ * a background grid of cells holding at most one sample each. Sampling
+ * restarts from random points while any is admissible, so that regions
+ * not reachable from the first sample are filled as well.
+ */
+fn poisson(
+    bounding_box: &BoundingBox,
+    spacing: f64,
+    random: &mut XorShift,
+    is_admissible: &dyn Fn(&Vertex) -> bool,
+) -> Vec<Vertex> {
+    let width = bounding_box.destin.x - bounding_box.origin.x;
+    let height = bounding_box.destin.y - bounding_box.origin.y;
+    let cell = spacing / 2.0_f64.sqrt();
+    let columns = (width / cell).ceil() as usize + 1;
+    let rows = (height / cell).ceil() as usize + 1;
+    let mut cells: Vec<Option<usize>> = vec![None; columns * rows];
+
+    let cell_of = |vertex: &Vertex| {
+        let i = ((vertex.x - bounding_box.origin.x) / cell) as usize;
+        let j = ((vertex.y - bounding_box.origin.y) / cell) as usize;
+        return (i.min(columns - 1), j.min(rows - 1));
+    };
+
+    let mut samples: Vec<Vertex> = Vec::new();
+    let mut active: Vec<usize> = Vec::new();
+    let mut restarts = 0;
+
+    while restarts < POISSON_CANDIDATES {
+        let candidate = match active.last() {
+            Some(&index) => {
+                let sample = &samples[index];
+                let radius = spacing * (1.0 + random.next_unit());
+                let angle = 2.0 * PI * random.next_unit();
+                Vertex::new(
+                    sample.x + radius * angle.cos(),
+                    sample.y + radius * angle.sin(),
+                )
+            }
+            None => Vertex::new(
+                bounding_box.origin.x + width * random.next_unit(),
+                bounding_box.origin.y + height * random.next_unit(),
+            ),
+        };
+
+        let is_far = bounding_box.contains(&candidate) && {
+            let (i, j) = cell_of(&candidate);
+            let neighbors = (i.saturating_sub(2)..(i + 3).min(columns))
+                .map(|ni| (j.saturating_sub(2)..(j + 3).min(rows)).map(move |nj| (ni, nj)))
+                .flatten();
+            neighbors
+                .filter_map(|(ni, nj)| cells[ni + nj * columns])
+                .all(|index| distance(&samples[index], &candidate) >= spacing)
+        };
+
+        if is_far && is_admissible(&candidate) {
+            let (i, j) = cell_of(&candidate);
+            cells[i + j * columns] = Some(samples.len());
+            active.push(samples.len());
+            samples.push(candidate);
+            restarts = 0;
+            continue;
+        }
+
+        match active.last() {
+            Some(_) => {
+                /* retires the sample after as many failures as candidates */
+                restarts += 1;
+                if restarts == POISSON_CANDIDATES {
+                    active.pop();
+                    restarts = 0;
+                }
+            }
+            None => restarts += 1,
+        }
+    }
+
+    return samples;
+} /* end - poisson */
+
+#[cfg(test)]
+mod generators {
+    use super::*;
+
+    fn square_with_hole() -> (Polyline, HashSet<Rc<Polyline>>) {
+        let boundary = Polyline::new_closed(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(10.0, 0.0)),
+            Rc::new(Vertex::new(10.0, 10.0)),
+            Rc::new(Vertex::new(0.0, 10.0)),
+        ])
+        .unwrap();
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(6.0, 4.0)),
+                Rc::new(Vertex::new(6.0, 6.0)),
+                Rc::new(Vertex::new(4.0, 6.0)),
+            ])
+            .unwrap(),
+        );
+        return (boundary, vec![hole].into_iter().collect());
+    }
+
+    fn assert_inside(points: &Vec<Rc<Vertex>>, holes: &HashSet<Rc<Polyline>>, spacing: f64) {
+        for point in points.iter() {
+            assert!(point.x >= spacing / 2.0 && point.x <= 10.0 - spacing / 2.0);
+            assert!(point.y >= spacing / 2.0 && point.y <= 10.0 - spacing / 2.0);
+            for hole in holes.iter() {
+                assert_eq!(hole.contains(point), Some(Continence::Outside));
+                for edge in hole.into_edges() {
+                    assert!(edge_distance(&edge, point) >= spacing / 2.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn patterns() {
+        for pattern in vec![SeedPattern::Grid, SeedPattern::Jittered, SeedPattern::Poisson] {
+            assert_eq!(SeedPattern::from_name(pattern.name()), Some(pattern));
+        }
+        assert_eq!(SeedPattern::from_name("hexagonal"), None);
+    }
+
+    #[test]
+    fn grid_points() {
+        let (boundary, holes) = square_with_hole();
+        let points = seed_points(&boundary, &holes, &HashSet::new(), SeedPattern::Grid, 1.0, 0);
+
+        /* 10 x 10 cell centers, but the 2 x 2 inside the hole */
+        assert_eq!(points.len(), 96);
+        assert_inside(&points, &holes, 1.0);
+    }
+
+    #[test]
+    fn jittered_points() {
+        let (boundary, holes) = square_with_hole();
+        let points =
+            seed_points(&boundary, &holes, &HashSet::new(), SeedPattern::Jittered, 1.0, 3);
+        assert!(points.len() > 50 && points.len() < 100);
+        assert_inside(&points, &holes, 1.0);
+
+        let again =
+            seed_points(&boundary, &holes, &HashSet::new(), SeedPattern::Jittered, 1.0, 3);
+        assert_eq!(points, again);
+    }
+
+    #[test]
+    fn poisson_points() {
+        let (boundary, holes) = square_with_hole();
+        let points =
+            seed_points(&boundary, &holes, &HashSet::new(), SeedPattern::Poisson, 1.0, 5);
+        assert_inside(&points, &holes, 1.0);
+
+        for (index, point) in points.iter().enumerate() {
+            for other in points.iter().skip(index + 1) {
+                assert!(distance(point, other) >= 1.0);
+            }
+        }
+        /* maximal: about as dense as a hexagonal packing of the spacing */
+        assert!(points.len() > 40);
+    }
+
+    #[test]
+    fn clear_of_segments() {
+        let (boundary, _) = square_with_hole();
+        let segment = Rc::new(Edge::new(
+            &Rc::new(Vertex::new(2.0, 5.0)),
+            &Rc::new(Vertex::new(8.0, 5.0)),
+        ));
+        let segments: HashSet<Rc<Edge>> = vec![Rc::clone(&segment)].into_iter().collect();
+        let points = seed_points(&boundary, &HashSet::new(), &segments, SeedPattern::Grid, 1.0, 0);
+        assert!(points.iter().all(|p| edge_distance(&segment, p) >= 0.5));
+        assert!(seed_points(&boundary, &HashSet::new(), &segments, SeedPattern::Grid, 0.0, 0)
+            .is_empty());
+    }
+} /* end - generators tests */
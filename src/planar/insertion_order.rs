@@ -0,0 +1,211 @@
+use crate::elements::vertex::*;
+
+use std::rc::Rc;
+
+/* Bits of each coordinate in spatial sorting keys */
+const KEY_BITS: u32 = 16;
+
+/**
+ * Biased randomized insertion order. Vertices are shuffled into rounds,
+ * landing in the last round with probability 1/2, in the one before with
+ * probability 1/4, and so on. Each round is spatially sorted, so that
+ * consecutive insertions are close to each other, while the rounds keep
+ * the triangulation growing evenly over the domain.
+ */
+pub fn brio(vertices: Vec<Rc<Vertex>>, seed: u64) -> Vec<Rc<Vertex>> {
+    if vertices.len() < 2 {
+        return vertices;
+    }
+
+    let mut random = XorShift::new(seed);
+    let last_round = (usize::BITS - vertices.len().leading_zeros()) as usize;
+    let mut rounds: Vec<Vec<Rc<Vertex>>> = vec![Vec::new(); last_round + 1];
+    for vertex in vertices.into_iter() {
+        let coin_flips = (random.next().trailing_ones() as usize).min(last_round);
+        rounds[last_round - coin_flips].push(vertex);
+    }
+
+    return rounds.into_iter().map(hilbert_sort).flatten().collect();
+}
+
+/**
+ * Sorts vertices along a Morton curve over their bounding box.
+ */
+pub fn spatial_sort(vertices: Vec<Rc<Vertex>>) -> Vec<Rc<Vertex>> {
+    return curve_sort(vertices, morton_key);
+}
+
+/**
+ * Sorts vertices along a Hilbert curve over their bounding box. Unlike the
+ * Morton curve, consecutive cells of the Hilbert curve are always adjacent,
+ * so that consecutive vertices are closer.
+ */
+pub fn hilbert_sort(vertices: Vec<Rc<Vertex>>) -> Vec<Rc<Vertex>> {
+    return curve_sort(vertices, hilbert_key);
+}
+
+/* sorts vertices by the curve key of their cells over the bounding box */
+fn curve_sort(vertices: Vec<Rc<Vertex>>, key: fn(u64, u64) -> u64) -> Vec<Rc<Vertex>> {
+    if vertices.len() < 2 {
+        return vertices;
+    }
+
+    let (min_x, max_x, min_y, max_y) = vertices.iter().fold(
+        (
+            std::f64::INFINITY,
+            std::f64::NEG_INFINITY,
+            std::f64::INFINITY,
+            std::f64::NEG_INFINITY,
+        ),
+        |(min_x, max_x, min_y, max_y), v| {
+            (
+                min_x.min(v.x),
+                max_x.max(v.x),
+                min_y.min(v.y),
+                max_y.max(v.y),
+            )
+        },
+    );
+    let cells = ((1u64 << KEY_BITS) - 1) as f64;
+    let quantize = |value: f64, min: f64, max: f64| -> u64 {
+        if max > min {
+            return ((value - min) / (max - min) * cells) as u64;
+        }
+        return 0;
+    };
+
+    let mut keyed: Vec<(u64, Rc<Vertex>)> = vertices
+        .into_iter()
+        .map(|v| {
+            let i = quantize(v.x, min_x, max_x);
+            let j = quantize(v.y, min_y, max_y);
+            return (key(i, j), v);
+        })
+        .collect();
+    keyed.sort_by_key(|(key, _)| *key);
+
+    return keyed.into_iter().map(|(_, v)| v).collect();
+}
+
+/* interleaves the bits of i and j */
+fn morton_key(i: u64, j: u64) -> u64 {
+    let mut key: u64 = 0;
+    for bit in 0..KEY_BITS {
+        key |= ((i >> bit) & 1) << (2 * bit);
+        key |= ((j >> bit) & 1) << (2 * bit + 1);
+    }
+    return key;
+}
+
+/* distance of the cell (i, j) along the Hilbert curve */
+fn hilbert_key(i: u64, j: u64) -> u64 {
+    let (mut i, mut j) = (i, j);
+    let mut key: u64 = 0;
+    let mut side: u64 = 1 << (KEY_BITS - 1);
+    while side > 0 {
+        let quadrant_i = (i & side > 0) as u64;
+        let quadrant_j = (j & side > 0) as u64;
+        key += side * side * ((3 * quadrant_i) ^ quadrant_j);
+
+        /* rotates the quadrant, so that the curve enters at its origin */
+        if quadrant_j == 0 {
+            if quadrant_i == 1 {
+                i = side - 1 - (i & (side - 1));
+                j = side - 1 - (j & (side - 1));
+            }
+            std::mem::swap(&mut i, &mut j);
+        }
+        side /= 2;
+    }
+    return key;
+}
+
+/**
+ * Xorshift pseudorandom generator, so that insertion orders are
+ * reproducible for a seed.
+ */
+pub(crate) struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    pub(crate) fn new(seed: u64) -> Self {
+        /* zero state would only generate zeros */
+        return Self {
+            state: seed ^ 0x9E37_79B9_7F4A_7C15,
+        };
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        return self.state;
+    }
+
+    /**
+     * Uniform sample of [0, 1).
+     */
+    pub(crate) fn next_unit(&mut self) -> f64 {
+        return (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+    }
+}
+
+#[cfg(test)]
+mod insertion_order {
+    use super::*;
+    use crate::properties::distance::*;
+
+    fn grid(size: usize) -> Vec<Rc<Vertex>> {
+        return (0..size * size)
+            .map(|index| Rc::new(Vertex::new((index % size) as f64, (index / size) as f64)))
+            .collect();
+    }
+
+    #[test]
+    fn spatial_sort_sample() {
+        let sorted = spatial_sort(grid(2));
+        let coordinates: Vec<(f64, f64)> = sorted.iter().map(|v| (v.x, v.y)).collect();
+        assert_eq!(
+            coordinates,
+            vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn hilbert_sort_sample() {
+        let sorted = hilbert_sort(grid(4));
+        let coordinates: Vec<(f64, f64)> = sorted.iter().map(|v| (v.x, v.y)).collect();
+        assert_eq!(
+            coordinates[..4],
+            [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+        );
+
+        /* consecutive vertices are adjacent grid points */
+        for pair in sorted.windows(2) {
+            assert_eq!(distance(&pair[0], &pair[1]), 1.0);
+        }
+    }
+
+    #[test]
+    fn brio_permutes() {
+        let vertices = grid(20);
+        let ordered = brio(vertices.clone(), 7);
+        assert_eq!(ordered.len(), vertices.len());
+        for vertex in vertices.iter() {
+            assert!(ordered.iter().any(|v| Rc::ptr_eq(v, vertex)));
+        }
+
+        /* same seed, same order */
+        let again = brio(vertices.clone(), 7);
+        assert!(ordered
+            .iter()
+            .zip(again.iter())
+            .all(|(v1, v2)| Rc::ptr_eq(v1, v2)));
+
+        /* about half of the vertices are in the last round, spatially sorted */
+        let last_half = &ordered[ordered.len() / 2..];
+        let path: f64 = last_half.windows(2).map(|w| distance(&w[0], &w[1])).sum();
+        assert!(path < 3.0 * last_half.len() as f64);
+    }
+} /* end - insertion_order tests */
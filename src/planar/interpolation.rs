@@ -0,0 +1,276 @@
+use crate::elements::{edge::*, triangle::*, vertex::*};
+use crate::planar::triangulation::*;
+use crate::properties::{circumcenter::*, continence::*};
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/**
+ * Interpolation schemes of per-vertex scalar values.
+ *  - Linear: barycentric interpolation over the containing triangle.
+ *  - NaturalNeighbor: Sibson interpolation, weighting natural neighbors by
+ * the area their Voronoi cells would lose to the interpolated vertex.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InterpolationMethod {
+    Linear,
+    NaturalNeighbor,
+}
+
+/**
+ * Interpolates the per-vertex values at the vertex. Returns None if the
+ * vertex is out of the triangulation, or if any vertex involved has no
+ * value.
+ */
+pub fn interpolate(
+    triangulation: &Triangulation,
+    values: &HashMap<Rc<Vertex>, f64>,
+    vertex: &Vertex,
+    method: InterpolationMethod,
+) -> Option<f64> {
+    let weights = match method {
+        InterpolationMethod::Linear => barycentric_weights(triangulation, vertex)?,
+        InterpolationMethod::NaturalNeighbor => natural_neighbor_weights(triangulation, vertex)?,
+    };
+
+    let mut value = 0.0;
+    for (neighbor, weight) in weights.iter() {
+        value += weight * values.get(neighbor)?;
+    }
+    return Some(value);
+}
+
+/**
+ * Barycentric coordinates of the vertex relative to its containing
+ * triangle. Returns None if the vertex is out of the triangulation.
+ */
+pub fn barycentric_weights(
+    triangulation: &Triangulation,
+    vertex: &Vertex,
+) -> Option<Vec<(Rc<Vertex>, f64)>> {
+    let triangle = triangulation.locate(vertex)?;
+    let (w1, w2, w3) = triangle.barycentric(vertex)?;
+
+    return Some(vec![
+        (Rc::clone(&triangle.v1), w1),
+        (Rc::clone(&triangle.v2), w2),
+        (Rc::clone(&triangle.v3), w3),
+    ]);
+}
+
+/**
+ * Sibson coordinates of the vertex: the area each natural neighbor's
+ * Voronoi cell would lose if the vertex were inserted, relative to the area
+ * of the vertex's own cell. Natural neighbors are the vertices of the
+ * solid triangles whose circumcircles contain the vertex. Falls back to
+ * barycentric coordinates on the triangulation border, where the vertex's
+ * cell is unbounded. Returns None if the vertex is out of the triangulation.
+ */
+pub fn natural_neighbor_weights(
+    triangulation: &Triangulation,
+    vertex: &Vertex,
+) -> Option<Vec<(Rc<Vertex>, f64)>> {
+    let located = triangulation.locate(vertex)?;
+    for corner in vec![&located.v1, &located.v2, &located.v3] {
+        if **corner == *vertex {
+            return Some(vec![(Rc::clone(corner), 1.0)]);
+        }
+    }
+
+    /* Bowyer-Watson cavity of the vertex */
+    let mut cavity: HashSet<Rc<Triangle>> = HashSet::new();
+    let mut pending: Vec<Rc<Triangle>> = vec![Rc::clone(&located)];
+    cavity.insert(Rc::clone(&located));
+    while let Some(triangle) = pending.pop() {
+        let (e1, e2, e3) = triangle.outer_edges();
+        for edge in vec![e1, e2, e3] {
+            if let Some(neighbor) = triangulation.adjacency.get(&edge) {
+                if !neighbor.is_ghost()
+                    && !cavity.contains(neighbor)
+                    && neighbor.encircles(vertex) == Continence::Inside
+                {
+                    cavity.insert(Rc::clone(neighbor));
+                    pending.push(Rc::clone(neighbor));
+                }
+            }
+        }
+    }
+
+    /* cell of the vertex, from the circumcenters of the new triangles */
+    let cavity_edges: Vec<Rc<Edge>> = cavity
+        .iter()
+        .map(|t| {
+            let (e1, e2, e3) = t.inner_edges();
+            return vec![e1, e2, e3];
+        })
+        .flatten()
+        .filter(|e| match triangulation.adjacency.get(&e.opposite()) {
+            Some(neighbor) => !cavity.contains(neighbor),
+            None => true,
+        })
+        .collect();
+
+    let mut cell: Vec<(f64, f64)> = Vec::new();
+    for edge in cavity_edges.iter() {
+        match circumcenter(vertex, &edge.v1, &edge.v2) {
+            Some(center) => cell.push((center.x, center.y)),
+            None => return barycentric_weights(triangulation, vertex),
+        }
+    }
+    cell.sort_by(|(x1, y1), (x2, y2)| {
+        let angle1 = (y1 - vertex.y).atan2(x1 - vertex.x);
+        let angle2 = (y2 - vertex.y).atan2(x2 - vertex.x);
+        return angle1.partial_cmp(&angle2).unwrap();
+    });
+
+    let cell_area = polygon_area(&cell);
+    if !(cell_area > 0.0) {
+        return barycentric_weights(triangulation, vertex);
+    }
+
+    let neighbors: Vec<Rc<Vertex>> = cavity_edges
+        .iter()
+        .map(|e| Rc::clone(&e.v1))
+        .collect::<HashSet<Rc<Vertex>>>()
+        .into_iter()
+        .collect();
+
+    /* each neighbor takes the part of the cell closer to it than to the others */
+    let weights = neighbors
+        .iter()
+        .map(|neighbor| {
+            let stolen = neighbors
+                .iter()
+                .filter(|other| !Rc::ptr_eq(other, neighbor))
+                .fold(cell.clone(), |polygon, other| clip(&polygon, neighbor, other));
+            return (Rc::clone(neighbor), polygon_area(&stolen) / cell_area);
+        })
+        .collect();
+
+    return Some(weights);
+}
+
+/**
+ * Clips the convex polygon to the half plane closer to v1 than to v2.
+ */
+fn clip(polygon: &Vec<(f64, f64)>, v1: &Vertex, v2: &Vertex) -> Vec<(f64, f64)> {
+    /* points are kept where (v2 - v1) . p <= (|v2|^2 - |v1|^2) / 2 */
+    let (nx, ny) = (v2.x - v1.x, v2.y - v1.y);
+    let offset = (v2.x * v2.x + v2.y * v2.y - v1.x * v1.x - v1.y * v1.y) / 2.0;
+    let side = |(x, y): (f64, f64)| nx * x + ny * y - offset;
+
+    let mut clipped: Vec<(f64, f64)> = Vec::new();
+    for index in 0..polygon.len() {
+        let p1 = polygon[index];
+        let p2 = polygon[(index + 1) % polygon.len()];
+        let (s1, s2) = (side(p1), side(p2));
+
+        if s1 <= 0.0 {
+            clipped.push(p1);
+        }
+        if (s1 < 0.0 && s2 > 0.0) || (s1 > 0.0 && s2 < 0.0) {
+            let ratio = s1 / (s1 - s2);
+            clipped.push((p1.0 + (p2.0 - p1.0) * ratio, p1.1 + (p2.1 - p1.1) * ratio));
+        }
+    }
+
+    return clipped;
+}
+
+fn polygon_area(polygon: &Vec<(f64, f64)>) -> f64 {
+    let mut area = 0.0;
+    for index in 0..polygon.len() {
+        let (x1, y1) = polygon[index];
+        let (x2, y2) = polygon[(index + 1) % polygon.len()];
+        area += x1 * y2 - x2 * y1;
+    }
+    return area / 2.0;
+}
+
+#[cfg(test)]
+mod interpolation {
+    use super::*;
+    use crate::elements::bounding_box::*;
+    use crate::planar::structured;
+
+    fn grid() -> Triangulation {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(4.0, 4.0)),
+        ])
+        .unwrap();
+        return structured::rectangle(&bounding_box, 4, 4).unwrap();
+    }
+
+    fn linear_field(triangulation: &Triangulation) -> HashMap<Rc<Vertex>, f64> {
+        return triangulation
+            .vertices()
+            .iter()
+            .map(|v| {
+                let value = 2.0 * v.x - 3.0 * v.y + 1.0;
+                return (Rc::clone(v), value);
+            })
+            .collect();
+    }
+
+    #[test]
+    fn linear() {
+        let triangulation = grid();
+        let values = linear_field(&triangulation);
+
+        let value = interpolate(
+            &triangulation,
+            &values,
+            &Vertex::new(1.3, 2.6),
+            InterpolationMethod::Linear,
+        )
+        .unwrap();
+        assert!((value - (2.6 - 7.8 + 1.0)).abs() < 1.0E-12);
+
+        let outside = interpolate(
+            &triangulation,
+            &values,
+            &Vertex::new(5.0, 2.0),
+            InterpolationMethod::Linear,
+        );
+        assert!(outside.is_none());
+    }
+
+    #[test]
+    fn natural_neighbor() {
+        let triangulation = grid();
+        let values = linear_field(&triangulation);
+
+        /* Sibson interpolation reproduces linear fields */
+        for (x, y) in vec![(1.3, 2.6), (2.5, 2.5), (0.2, 3.9), (2.0, 2.0)] {
+            let value = interpolate(
+                &triangulation,
+                &values,
+                &Vertex::new(x, y),
+                InterpolationMethod::NaturalNeighbor,
+            )
+            .unwrap();
+            assert!((value - (2.0 * x - 3.0 * y + 1.0)).abs() < 1.0E-9);
+        }
+
+        let weights = natural_neighbor_weights(&triangulation, &Vertex::new(2.5, 2.5)).unwrap();
+        let sum: f64 = weights.iter().map(|(_, weight)| weight).sum();
+        assert!((sum - 1.0).abs() < 1.0E-12);
+        assert!(weights.len() >= 4);
+    }
+
+    #[test]
+    fn missing_values() {
+        let triangulation = grid();
+        let mut values = linear_field(&triangulation);
+        values.remove(&Rc::new(Vertex::new(2.0, 2.0)));
+
+        let value = interpolate(
+            &triangulation,
+            &values,
+            &Vertex::new(2.1, 2.2),
+            InterpolationMethod::Linear,
+        );
+        assert!(value.is_none());
+    }
+} /* end - interpolation tests */
@@ -0,0 +1,183 @@
+use crate::elements::vertex::*;
+use crate::planar::{triangulation::*, triangulation_data::*};
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+/**
+ * Named value at each vertex, written as an extra PLY vertex property.
+ * Vertices with no value are written as zero.
+ */
+pub struct VertexScalar {
+    pub name: String,
+    pub values: HashMap<Rc<Vertex>, f64>,
+}
+
+impl VertexScalar {
+    pub fn new(name: &str, values: HashMap<Rc<Vertex>, f64>) -> Self {
+        Self {
+            name: String::from(name),
+            values,
+        }
+    }
+
+    /**
+     * Average quality, as radius-edge ratio, of the solid triangles around
+     * each vertex.
+     */
+    pub fn quality(triangulation: &Triangulation) -> Self {
+        let mut sums: HashMap<Rc<Vertex>, (f64, usize)> = HashMap::new();
+        for triangle in triangulation.solid_triangles() {
+            let quality = match triangle.quality() {
+                Some(quality) => quality,
+                None => continue,
+            };
+            for vertex in [&triangle.v1, &triangle.v2, &triangle.v3].iter() {
+                let sum = sums.entry(Rc::clone(vertex)).or_insert((0.0, 0));
+                sum.0 += quality;
+                sum.1 += 1;
+            }
+        }
+
+        let values = sums
+            .into_iter()
+            .map(|(vertex, (sum, count))| (vertex, sum / count as f64))
+            .collect();
+        return Self::new("quality", values);
+    }
+} /* end - VertexScalar */
+
+/**
+ * Writes the solid triangles as an ASCII STL solid, lying on the z = 0
+ * plane with upward normals.
+ */
+pub fn write_stl(triangulation: &Triangulation, writer: &mut dyn Write) -> io::Result<()> {
+    let data = TriangulationData::from_triangulation(triangulation);
+
+    writeln!(writer, "solid nlsn-delaunay")?;
+    for corners in data.triangles.chunks(3) {
+        writeln!(writer, "  facet normal 0 0 1")?;
+        writeln!(writer, "    outer loop")?;
+        for &index in corners.iter() {
+            let x = data.coordinates[2 * index];
+            let y = data.coordinates[2 * index + 1];
+            writeln!(writer, "      vertex {} {} 0", x, y)?;
+        }
+        writeln!(writer, "    endloop")?;
+        writeln!(writer, "  endfacet")?;
+    }
+    writeln!(writer, "endsolid nlsn-delaunay")?;
+    return Ok(());
+}
+
+/**
+ * Writes the solid triangles as an ASCII PLY mesh, lying on the z = 0
+ * plane, with the scalar, if any, as a vertex property.
+ */
+pub fn write_ply(
+    triangulation: &Triangulation,
+    scalar: Option<&VertexScalar>,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    let data = TriangulationData::from_triangulation(triangulation);
+
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format ascii 1.0")?;
+    writeln!(writer, "comment nlsn-delaunay")?;
+    writeln!(writer, "element vertex {}", data.coordinates.len() / 2)?;
+    writeln!(writer, "property double x")?;
+    writeln!(writer, "property double y")?;
+    writeln!(writer, "property double z")?;
+    if let Some(scalar) = scalar {
+        writeln!(writer, "property double {}", scalar.name)?;
+    }
+    writeln!(writer, "element face {}", data.triangles.len() / 3)?;
+    writeln!(writer, "property list uchar int vertex_indices")?;
+    writeln!(writer, "end_header")?;
+
+    for xy in data.coordinates.chunks(2) {
+        match scalar {
+            Some(scalar) => {
+                let vertex = Vertex::new(xy[0], xy[1]);
+                let value = scalar.values.get(&vertex).cloned().unwrap_or(0.0);
+                writeln!(writer, "{} {} 0 {}", xy[0], xy[1], value)?;
+            }
+            None => writeln!(writer, "{} {} 0", xy[0], xy[1])?,
+        }
+    }
+    for corners in data.triangles.chunks(3) {
+        writeln!(writer, "3 {} {} {}", corners[0], corners[1], corners[2])?;
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod io_tests {
+    use super::*;
+    use crate::elements::bounding_box::*;
+    use crate::planar::structured;
+
+    fn square() -> Triangulation {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(1.0, 1.0)),
+        ])
+        .unwrap();
+        return structured::rectangle(&bounding_box, 1, 1).unwrap();
+    }
+
+    #[test]
+    fn stl() {
+        let mut bytes: Vec<u8> = Vec::new();
+        write_stl(&square(), &mut bytes).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+
+        assert!(text.starts_with("solid nlsn-delaunay\n"));
+        assert!(text.ends_with("endsolid nlsn-delaunay\n"));
+        assert_eq!(text.matches("facet normal 0 0 1").count(), 2);
+        assert_eq!(text.matches("vertex ").count(), 6);
+        assert!(text.contains("vertex 0 0 0\n"));
+    }
+
+    #[test]
+    fn ply() {
+        let triangulation = square();
+        let mut bytes: Vec<u8> = Vec::new();
+        write_ply(&triangulation, None, &mut bytes).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], "ply");
+        assert!(lines.contains(&"element vertex 4"));
+        assert!(lines.contains(&"element face 2"));
+        let header_end = lines.iter().position(|l| *l == "end_header").unwrap();
+        assert_eq!(lines.len(), header_end + 1 + 4 + 2);
+        assert_eq!(lines[header_end + 1], "0 0 0");
+        assert!(lines[header_end + 5].starts_with("3 0 "));
+    }
+
+    #[test]
+    fn ply_scalar() {
+        let triangulation = square();
+        let scalar = VertexScalar::quality(&triangulation);
+        assert_eq!(scalar.values.len(), 4);
+
+        let mut bytes: Vec<u8> = Vec::new();
+        write_ply(&triangulation, Some(&scalar), &mut bytes).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert!(lines.contains(&"property double quality"));
+        let header_end = lines.iter().position(|l| *l == "end_header").unwrap();
+
+        /* right isosceles triangles have radius-edge ratio 1/sqrt(2) */
+        let value: f64 = lines[header_end + 1]
+            .split_whitespace()
+            .nth(3)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((value - 1.0 / 2f64.sqrt()).abs() < 1.0E-12);
+    }
+} /* end - io tests */
@@ -0,0 +1,195 @@
+use crate::elements::triangle::*;
+use crate::planar::triangulation::*;
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::rc::Rc;
+use tungstenite::{Message, WebSocket};
+
+/**
+ * Solid triangles added to and removed from the triangulation since the
+ * last patch, each one as its counterclockwise coordinates
+ * [x1, y1, x2, y2, x3, y3].
+ */
+#[derive(Serialize, Debug, PartialEq)]
+pub struct TrianglePatch {
+    pub added: Vec<[f64; 6]>,
+    pub removed: Vec<[f64; 6]>,
+}
+
+impl TrianglePatch {
+    pub fn is_empty(&self) -> bool {
+        return self.added.is_empty() && self.removed.is_empty();
+    }
+
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string(self).unwrap();
+    }
+} /* end - TrianglePatch */
+
+/**
+ * WebSocket server streaming the triangulation to browser front ends, as
+ * json patches of added and removed triangles. Triangulations are not
+ * shared between threads, so that the server does not run on its own:
+ * each publish accepts pending clients, sending them the whole published
+ * state, then sends the changes since the last publish to every client.
+ */
+pub struct LiveView {
+    listener: TcpListener,
+    clients: Vec<WebSocket<TcpStream>>,
+    published: HashSet<Rc<Triangle>>,
+}
+
+impl LiveView {
+    pub fn bind<A: ToSocketAddrs>(address: A) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        return Ok(Self {
+            listener,
+            clients: Vec::new(),
+            published: HashSet::new(),
+        });
+    }
+
+    pub fn local_addr(&self) -> io::Result<std::net::SocketAddr> {
+        return self.listener.local_addr();
+    }
+
+    pub fn client_count(&self) -> usize {
+        return self.clients.len();
+    }
+
+    /**
+     * Changes of the triangulation since the last patch. The triangulation
+     * is then taken as published.
+     */
+    pub fn patch(&mut self, triangulation: &Triangulation) -> TrianglePatch {
+        let current: HashSet<Rc<Triangle>> = triangulation
+            .solid_triangles()
+            .cloned()
+            .collect();
+
+        let patch = TrianglePatch {
+            added: current.difference(&self.published).map(|t| coordinates(t)).collect(),
+            removed: self.published.difference(&current).map(|t| coordinates(t)).collect(),
+        };
+        self.published = current;
+        return patch;
+    }
+
+    /**
+     * Accepts pending clients and sends them the changes of the
+     * triangulation. Clients failing to receive are dropped. Returns the
+     * count of connected clients.
+     */
+    pub fn publish(&mut self, triangulation: &Triangulation) -> io::Result<usize> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    stream.set_nonblocking(false)?;
+                    let mut client = match tungstenite::accept(stream) {
+                        Ok(client) => client,
+                        Err(_) => continue,
+                    };
+                    let state = TrianglePatch {
+                        added: self.published.iter().map(|t| coordinates(t)).collect(),
+                        removed: Vec::new(),
+                    };
+                    if client.send(Message::Text(state.to_json())).is_ok() {
+                        self.clients.push(client);
+                    }
+                }
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+                Err(error) => return Err(error),
+            }
+        }
+
+        let patch = self.patch(triangulation);
+        if !patch.is_empty() {
+            let message = patch.to_json();
+            self.clients
+                .retain_mut(|client| client.send(Message::Text(message.clone())).is_ok());
+        }
+
+        return Ok(self.clients.len());
+    }
+} /* end - LiveView */
+
+fn coordinates(triangle: &Triangle) -> [f64; 6] {
+    return [
+        triangle.v1.x,
+        triangle.v1.y,
+        triangle.v2.x,
+        triangle.v2.y,
+        triangle.v3.x,
+        triangle.v3.y,
+    ];
+}
+
+#[cfg(test)]
+mod live_view {
+    use super::*;
+    use crate::elements::vertex::*;
+
+    fn square() -> (Triangulation, Rc<Triangle>, Rc<Triangle>) {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0, 1.0));
+        let v4 = Rc::new(Vertex::new(0.0, 1.0));
+        let t1 = Rc::new(Triangle::new(&v1, &v2, &v3));
+        let t2 = Rc::new(Triangle::new(&v1, &v3, &v4));
+
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&t1);
+        return (triangulation, t1, t2);
+    }
+
+    #[test]
+    fn patches() {
+        let (mut triangulation, t1, t2) = square();
+        let mut view = LiveView::bind("127.0.0.1:0").unwrap();
+
+        let patch = view.patch(&triangulation);
+        assert_eq!(patch.added, vec![coordinates(&t1)]);
+        assert!(patch.removed.is_empty());
+        assert!(view.patch(&triangulation).is_empty());
+
+        triangulation.remove_triangle(&t1);
+        triangulation.include_triangle(&t2);
+        let patch = view.patch(&triangulation);
+        assert_eq!(patch.added, vec![coordinates(&t2)]);
+        assert_eq!(patch.removed, vec![coordinates(&t1)]);
+        assert_eq!(
+            patch.to_json(),
+            "{\"added\":[[0.0,0.0,1.0,1.0,0.0,1.0]],\"removed\":[[0.0,0.0,1.0,0.0,1.0,1.0]]}"
+        );
+    }
+
+    #[test]
+    fn streams_to_clients() {
+        let (mut triangulation, _, t2) = square();
+        let mut view = LiveView::bind("127.0.0.1:0").unwrap();
+        view.publish(&triangulation).unwrap();
+
+        let address = format!("ws://{}", view.local_addr().unwrap());
+        let client = std::thread::spawn(move || {
+            let (mut socket, _) = tungstenite::connect(address).unwrap();
+            let state = socket.read().unwrap().into_text().unwrap();
+            let patch = socket.read().unwrap().into_text().unwrap();
+            return (state, patch);
+        });
+
+        while view.client_count() == 0 {
+            view.publish(&triangulation).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        triangulation.include_triangle(&t2);
+        assert_eq!(view.publish(&triangulation).unwrap(), 1);
+
+        let (state, patch) = client.join().unwrap();
+        assert_eq!(state, "{\"added\":[[0.0,0.0,1.0,0.0,1.0,1.0]],\"removed\":[]}");
+        assert_eq!(patch, "{\"added\":[[0.0,0.0,1.0,1.0,0.0,1.0]],\"removed\":[]}");
+    }
+} /* end - live_view tests */
@@ -0,0 +1,77 @@
+use crate::planar::timings::*;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/**
+ * Receives the progress of the main loops of triangulation and refinement,
+ * as the count of items processed in the phase and an estimate of their
+ * total, which may grow while refining.
+ */
+pub trait ProgressObserver {
+    fn progress(&mut self, phase: Phase, processed: usize, total: usize);
+}
+
+/**
+ * Shared flag aborting triangulation and refinement. Clones share the flag,
+ * so that it may be cancelled from another thread.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        return self.cancelled.load(Ordering::SeqCst);
+    }
+}
+
+/**
+ * Reports the progress to the observer, if any. Returns whether to go on,
+ * that is, whether the token, if any, was not cancelled.
+ */
+pub fn report(
+    observer: &mut Option<Box<dyn ProgressObserver>>,
+    cancellation: &Option<CancellationToken>,
+    phase: Phase,
+    processed: usize,
+    total: usize,
+) -> bool {
+    if let Some(observer) = observer {
+        observer.progress(phase, processed, total);
+    }
+    return !is_cancelled(cancellation);
+}
+
+pub fn is_cancelled(cancellation: &Option<CancellationToken>) -> bool {
+    match cancellation {
+        Some(token) => return token.is_cancelled(),
+        None => return false,
+    }
+}
+
+#[cfg(test)]
+mod cancellation_token {
+    use super::*;
+
+    #[test]
+    fn clones_share_flag() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+
+        token.cancel();
+        assert!(clone.is_cancelled());
+        assert!(!report(&mut None, &Some(clone), Phase::TriangleSplit, 1, 2));
+        assert!(report(&mut None, &None, Phase::TriangleSplit, 1, 2));
+    }
+} /* end - cancellation_token tests */
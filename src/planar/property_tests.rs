@@ -0,0 +1,226 @@
+use crate::elements::{edge::*, polyline::*, vertex::*};
+use crate::planar::triangulator::*;
+use crate::properties::{continence::*, convex_hull::*};
+
+use proptest::prelude::*;
+use std::collections::HashSet;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+/**
+ * Property based tests of the triangulation pipeline, run with the
+ * properties feature. Inputs are random convex polygons, point clouds,
+ * holes and segment sets. Polygons have lattice vertices, where collinear
+ * and cocircular vertices abound, while points and segment ends are either
+ * continuous or snapped to the lattice. Points and segment ends are kept
+ * in a disc inside every boundary, as segments leaving the domain are
+ * rejected by insertion. Every triangulation is expected to
+ * recover its constraints, to keep the Delaunay and adjacency invariants,
+ * to cover exactly the domain, so that no triangles overlap, and to leave
+ * holes empty. Cases running longer than the timeout fail as hanging.
+ */
+
+/* Spacing of lattice snapped coordinates */
+const LATTICE: f64 = 0.25;
+
+/* Milliseconds after which a case is failed as hanging */
+const CASE_TIMEOUT: u32 = 10_000;
+
+/**
+ * Star shaped polygon around the center, counterclockwise, whose vertices
+ * are placed at increasing angles, jittered inside even sectors, and at
+ * radii of the range. Sectors keep angles apart, so that it is simple.
+ */
+fn star_polygon(
+    center: (f64, f64),
+    radii: std::ops::Range<f64>,
+    sizes: std::ops::Range<usize>,
+) -> impl Strategy<Value = Vec<(f64, f64)>> {
+    return prop::collection::vec((0.1..0.9_f64, radii), sizes).prop_map(move |sectors| {
+        let count = sectors.len() as f64;
+        return sectors
+            .iter()
+            .enumerate()
+            .map(|(index, (jitter, radius))| {
+                let angle = 2.0 * PI * (index as f64 + jitter) / count;
+                return (
+                    center.0 + radius * angle.cos(),
+                    center.1 + radius * angle.sin(),
+                );
+            })
+            .collect();
+    });
+}
+
+fn snap(points: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    return points
+        .into_iter()
+        .map(|(x, y)| ((x / LATTICE).round() * LATTICE, (y / LATTICE).round() * LATTICE))
+        .collect();
+}
+
+/**
+ * Convex polygon of lattice vertices, as the convex hull of a star shaped
+ * polygon snapped to the lattice. Lattice coordinates keep the predicates
+ * exact, while their collinear and cocircular vertices are the degenerate
+ * configurations triangulations must handle.
+ */
+fn convex_polygon(
+    radii: std::ops::Range<f64>,
+    sizes: std::ops::Range<usize>,
+) -> impl Strategy<Value = Vec<(f64, f64)>> {
+    return star_polygon((0.0, 0.0), radii, sizes).prop_filter_map("colinear", |points| {
+        let vertices = snap(points)
+            .iter()
+            .map(|&(x, y)| Rc::new(Vertex::new(x, y)))
+            .collect();
+        let hull = convex_hull(&vertices)?;
+        return Some(hull.vertices.iter().map(|v| (v.x, v.y)).collect());
+    });
+}
+
+/**
+ * Points of the disc of the radius around the origin, either continuous or
+ * snapped to the lattice, which may take them slightly farther.
+ */
+fn point_cloud(
+    radius: f64,
+    sizes: std::ops::Range<usize>,
+) -> impl Strategy<Value = Vec<(f64, f64)>> {
+    let point = (0.0..radius, 0.0..2.0 * PI)
+        .prop_map(|(distance, angle)| (distance * angle.cos(), distance * angle.sin()));
+    return prop_oneof![
+        prop::collection::vec(point.clone(), sizes.clone()),
+        prop::collection::vec(point, sizes).prop_map(snap),
+    ];
+}
+
+fn polyline(points: &Vec<(f64, f64)>) -> Rc<Polyline> {
+    let vertices = points
+        .iter()
+        .map(|&(x, y)| Rc::new(Vertex::new(x, y)))
+        .collect();
+    return Rc::new(Polyline::new_closed(vertices).unwrap());
+}
+
+/**
+ * Boundary of vertices at radii from 5 to 10, whose edges stay farther
+ * than 3.5 from the origin.
+ */
+fn boundary() -> impl Strategy<Value = Vec<(f64, f64)>> {
+    return convex_polygon(5.0..10.0, 8..24);
+}
+
+/**
+ * Points inside the boundary and outside the holes.
+ */
+fn admissible_vertices(
+    points: &Vec<(f64, f64)>,
+    boundary: &Polyline,
+    holes: &Vec<Rc<Polyline>>,
+) -> HashSet<Rc<Vertex>> {
+    return points
+        .iter()
+        .map(|&(x, y)| Rc::new(Vertex::new(x, y)))
+        .filter(|v| boundary.contains(v) == Some(Continence::Inside))
+        .filter(|v| {
+            holes
+                .iter()
+                .all(|hole| hole.contains(v) == Some(Continence::Outside))
+        })
+        .collect();
+}
+
+fn check_invariants(
+    triangulator: &Triangulator,
+    holes: &Vec<Rc<Polyline>>,
+) -> Result<(), TestCaseError> {
+    if let Err(violations) = triangulator.validate() {
+        let lines: Vec<String> = violations.iter().map(|v| format!("{}", v)).collect();
+        return Err(TestCaseError::fail(lines.join("\n")));
+    }
+
+    let triangulation = triangulator.triangulation.borrow();
+    let area: f64 = triangulation
+        .solid_triangles()
+        .filter_map(|t| t.area())
+        .sum();
+    let domain_area = triangulator.boundary.area().unwrap().abs()
+        - holes.iter().map(|h| h.area().unwrap().abs()).sum::<f64>();
+    prop_assert!(
+        (area - domain_area).abs() <= 1e-9 * domain_area,
+        "triangles cover {} of a domain of {}",
+        area,
+        domain_area
+    );
+
+    for triangle in triangulation.solid_triangles() {
+        let center = triangle.center();
+        for hole in holes.iter() {
+            prop_assert_ne!(hole.contains(&center), Some(Continence::Inside));
+        }
+    }
+    return Ok(());
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 64,
+        timeout: CASE_TIMEOUT,
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn triangulates_polygons(boundary in boundary()) {
+        let boundary = polyline(&boundary);
+        let mut triangulator = Triangulator::new(&boundary);
+        prop_assert!(triangulator.try_triangulate().is_ok());
+        check_invariants(&triangulator, &Vec::new())?;
+    }
+
+    #[test]
+    fn triangulates_point_clouds(boundary in boundary(), points in point_cloud(3.5, 0..64)) {
+        let boundary = polyline(&boundary);
+        let mut triangulator = Triangulator::new(&boundary);
+        let vertices = admissible_vertices(&points, &boundary, &Vec::new());
+        prop_assert!(triangulator.insert_vertices(&vertices).is_ok());
+        prop_assert!(triangulator.try_triangulate().is_ok());
+        check_invariants(&triangulator, &Vec::new())?;
+    }
+
+    #[test]
+    fn triangulates_holes(
+        boundary in boundary(),
+        hole in convex_polygon(0.5..2.0, 3..12),
+        points in point_cloud(3.5, 0..32),
+    ) {
+        let boundary = polyline(&boundary);
+        let hole = polyline(&hole);
+        let holes = vec![Rc::clone(&hole)];
+        let mut triangulator = Triangulator::new(&boundary);
+        prop_assert!(triangulator.insert_hole(&hole).is_ok());
+        let vertices = admissible_vertices(&points, &boundary, &holes);
+        prop_assert!(triangulator.insert_vertices(&vertices).is_ok());
+        prop_assert!(triangulator.try_triangulate().is_ok());
+        check_invariants(&triangulator, &holes)?;
+    }
+
+    #[test]
+    fn recovers_segments(boundary in boundary(), ends in point_cloud(3.25, 2..16)) {
+        let boundary = polyline(&boundary);
+        let mut triangulator = Triangulator::new(&boundary);
+        let segments: HashSet<Rc<Edge>> = ends
+            .chunks(2)
+            .filter(|pair| pair.len() == 2)
+            .map(|pair| {
+                Rc::new(Edge::new(
+                    &Rc::new(Vertex::new(pair[0].0, pair[0].1)),
+                    &Rc::new(Vertex::new(pair[1].0, pair[1].1)),
+                ))
+            })
+            .collect();
+        prop_assert!(triangulator.insert_segments(&segments).is_ok());
+        prop_assert!(triangulator.try_triangulate().is_ok());
+        check_invariants(&triangulator, &Vec::new())?;
+    }
+}
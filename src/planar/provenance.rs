@@ -0,0 +1,209 @@
+use crate::elements::{edge::*, triangle::*, vertex::*};
+use crate::planar::triangulation::*;
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/**
+ * Input constraint whose insertion, or whose splitting during refinement,
+ * drove the creation of a triangle.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum Provenance {
+    Boundary(Rc<Edge>),
+    Hole(Rc<Edge>),
+    Segment(Rc<Edge>),
+}
+
+impl Provenance {
+    pub fn edge(&self) -> &Rc<Edge> {
+        match self {
+            Provenance::Boundary(edge) => return edge,
+            Provenance::Hole(edge) => return edge,
+            Provenance::Segment(edge) => return edge,
+        }
+    }
+}
+
+/**
+ * Maps solid triangles to the constraint that drove their creation, for
+ * diagnostics. Triangles created by vertices insertion or by quality
+ * refinement away from constraints are not mapped. Removed triangles are
+ * dropped from the map, so that it only refers to existing triangles. The
+ * triangulator records it only with record_provenance set, as every step
+ * then copies the triangles it started from.
+ */
+pub struct ProvenanceMap {
+    pub triangles: HashMap<Rc<Triangle>, Provenance>,
+}
+
+impl ProvenanceMap {
+    pub fn new() -> Self {
+        Self {
+            triangles: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, triangle: &Rc<Triangle>) -> Option<&Provenance> {
+        return self.triangles.get(triangle);
+    }
+
+    /**
+     * Triangles driven by the constraint.
+     */
+    pub fn triangles_of(&self, provenance: &Provenance) -> HashSet<Rc<Triangle>> {
+        return self
+            .triangles
+            .iter()
+            .filter(|(_, p)| *p == provenance)
+            .map(|(t, _)| Rc::clone(t))
+            .collect();
+    }
+
+    /**
+     * Records the triangles created by a constraint insertion step, that is,
+     * solid triangles of the triangulation missing from the former triangles.
+     * Each of them is mapped to the candidate constraint that contains most of
+     * its vertices.
+     */
+    pub fn record(
+        &mut self,
+        former_triangles: &HashSet<Rc<Triangle>>,
+        triangulation: &Triangulation,
+        candidates: &Vec<Provenance>,
+    ) {
+        self.record_filtered(former_triangles, triangulation, candidates, |_| true);
+    }
+
+    /**
+     * Records the triangles created by a refinement step, mapping them to the
+     * candidate constraint that contains most of its new vertices, which are
+     * the Steiner points of split constraints. Triangles without new vertices
+     * on constraints are not mapped.
+     */
+    pub fn record_steiner(
+        &mut self,
+        former_triangles: &HashSet<Rc<Triangle>>,
+        triangulation: &Triangulation,
+        candidates: &Vec<Provenance>,
+    ) {
+        let former_vertices: HashSet<Rc<Vertex>> = former_triangles
+            .iter()
+            .map(|t| vec![Rc::clone(&t.v1), Rc::clone(&t.v2), Rc::clone(&t.v3)])
+            .flatten()
+            .collect();
+
+        self.record_filtered(former_triangles, triangulation, candidates, |v| {
+            !former_vertices.contains(v)
+        });
+    }
+
+    /**
+     * Drops every triangle that is no longer in the triangulation.
+     */
+    pub fn retain(&mut self, triangulation: &Triangulation) {
+        self.triangles
+            .retain(|triangle, _| triangulation.triangles.contains(triangle));
+    }
+
+    fn record_filtered<F>(
+        &mut self,
+        former_triangles: &HashSet<Rc<Triangle>>,
+        triangulation: &Triangulation,
+        candidates: &Vec<Provenance>,
+        counts_vertex: F,
+    ) where
+        F: Fn(&Rc<Vertex>) -> bool,
+    {
+        self.retain(triangulation);
+
+        for triangle in triangulation
+            .solid_triangles()
+            .filter(|t| !former_triangles.contains(*t))
+        {
+            let vertices: Vec<&Rc<Vertex>> = vec![&triangle.v1, &triangle.v2, &triangle.v3]
+                .into_iter()
+                .filter(|v| counts_vertex(v))
+                .collect();
+
+            let mut best: Option<(usize, &Provenance)> = None;
+            for candidate in candidates.iter() {
+                let edge = candidate.edge();
+                let count = vertices.iter().filter(|v| edge.contains(v)).count();
+                let is_better = match best {
+                    Some((best_count, _)) => count > best_count,
+                    None => count > 0,
+                };
+                if is_better {
+                    best = Some((count, candidate));
+                }
+            }
+
+            if let Some((_, provenance)) = best {
+                self.triangles
+                    .insert(Rc::clone(triangle), provenance.clone());
+            }
+        }
+    }
+} /* end - ProvenanceMap */
+
+#[cfg(test)]
+mod provenance_map {
+    use super::*;
+
+    #[test]
+    fn record() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(2.0, 2.0));
+        let v4 = Rc::new(Vertex::new(0.0, 2.0));
+
+        let bottom = Provenance::Boundary(Rc::new(Edge::new(&v1, &v2)));
+        let top = Provenance::Boundary(Rc::new(Edge::new(&v3, &v4)));
+
+        let t1 = Rc::new(Triangle::new(&v1, &v2, &v3));
+        let t2 = Rc::new(Triangle::new(&v1, &v3, &v4));
+
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&t1);
+
+        let mut provenance = ProvenanceMap::new();
+        provenance.record(&HashSet::new(), &triangulation, &vec![bottom.clone(), top.clone()]);
+        assert_eq!(provenance.get(&t1), Some(&bottom));
+
+        let former_triangles = triangulation.triangles.clone();
+        triangulation.remove_triangle(&t1);
+        triangulation.include_triangle(&t2);
+        provenance.record(&former_triangles, &triangulation, &vec![bottom.clone(), top.clone()]);
+
+        assert_eq!(provenance.get(&t1), None);
+        assert_eq!(provenance.get(&t2), Some(&top));
+        assert_eq!(provenance.triangles_of(&top).len(), 1);
+    }
+
+    #[test]
+    fn record_steiner() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0, 2.0));
+        let steiner = Rc::new(Vertex::new(1.0, 0.0));
+
+        let segment = Provenance::Segment(Rc::new(Edge::new(&v1, &v2)));
+        let t0 = Rc::new(Triangle::new(&v1, &v2, &v3));
+
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&t0);
+        let former_triangles = triangulation.triangles.clone();
+
+        let t1 = Rc::new(Triangle::new(&v1, &steiner, &v3));
+        let t2 = Rc::new(Triangle::new(&steiner, &v2, &v3));
+        triangulation.remove_triangle(&t0);
+        triangulation.include_triangle(&t1);
+        triangulation.include_triangle(&t2);
+
+        let mut provenance = ProvenanceMap::new();
+        provenance.record_steiner(&former_triangles, &triangulation, &vec![segment.clone()]);
+        assert_eq!(provenance.get(&t1), Some(&segment));
+        assert_eq!(provenance.get(&t2), Some(&segment));
+    }
+} /* end - provenance_map tests */
@@ -0,0 +1,389 @@
+use crate::planar::triangulation::*;
+
+use std::fmt;
+
+/**
+ * Summarizes the quality of the solid triangles of a triangulation, so that
+ * mesh quality contracts may be asserted directly against it, and refinement
+ * parameters may be compared. Angles are given in degrees, three per
+ * triangle, while aspect ratios and areas are given one per triangle.
+ */
+pub struct QualityReport {
+    pub triangles: usize,
+    pub angles: Vec<f64>,
+    pub aspect_ratios: Vec<f64>,
+    pub areas: Vec<f64>,
+}
+
+impl QualityReport {
+    pub fn new(triangulation: &Triangulation) -> Self {
+        let mut angles: Vec<f64> = Vec::new();
+        let mut aspect_ratios: Vec<f64> = Vec::new();
+        let mut areas: Vec<f64> = Vec::new();
+        let mut triangles: usize = 0;
+
+        for triangle in triangulation.solid_triangles() {
+            let (a1, a2, a3) = triangle.angles().unwrap();
+            angles.push(a1.to_degrees());
+            angles.push(a2.to_degrees());
+            angles.push(a3.to_degrees());
+            aspect_ratios.push(triangle.aspect_ratio().unwrap());
+            areas.push(triangle.area().unwrap().abs());
+            triangles += 1;
+        }
+
+        return Self {
+            triangles,
+            angles,
+            aspect_ratios,
+            areas,
+        };
+    }
+
+    /**
+     * Smallest inner angle. Returns None if there is no solid triangle.
+     */
+    pub fn min_angle(&self) -> Option<f64> {
+        return min(&self.angles);
+    }
+
+    /**
+     * Largest inner angle. Returns None if there is no solid triangle.
+     */
+    pub fn max_angle(&self) -> Option<f64> {
+        return max(&self.angles);
+    }
+
+    /**
+     * Mean of the smallest inner angle of each triangle. Returns None if
+     * there is no solid triangle.
+     */
+    pub fn mean_min_angle(&self) -> Option<f64> {
+        if self.triangles == 0 {
+            return None;
+        }
+
+        let sum: f64 = self
+            .angles
+            .chunks(3)
+            .map(|angles| angles.iter().cloned().fold(std::f64::INFINITY, f64::min))
+            .sum();
+
+        return Some(sum / self.triangles as f64);
+    }
+
+    /**
+     * Counts inner angles strictly below the threshold.
+     */
+    pub fn count_angles_below(&self, degrees: f64) -> usize {
+        return self.angles.iter().filter(|&&angle| angle < degrees).count();
+    }
+
+    /**
+     * Counts inner angles strictly above the threshold.
+     */
+    pub fn count_angles_above(&self, degrees: f64) -> usize {
+        return self.angles.iter().filter(|&&angle| angle > degrees).count();
+    }
+
+    /**
+     * Counts inner angles in bins of bin_width degrees, from 0 to 180.
+     * The last bin holds 180 degrees angles as well. Empty unless the bin
+     * width is positive.
+     */
+    pub fn angle_histogram(&self, bin_width: f64) -> Vec<usize> {
+        if !(bin_width > 0.0) {
+            return Vec::new();
+        }
+        let bins = (180.0 / bin_width).ceil() as usize;
+        let mut histogram: Vec<usize> = vec![0; bins];
+
+        for angle in self.angles.iter() {
+            let bin = ((angle / bin_width).floor() as usize).min(bins - 1);
+            histogram[bin] += 1;
+        }
+
+        return histogram;
+    }
+
+    /**
+     * Counts triangles having any inner angle strictly below the threshold.
+     */
+    pub fn count_triangles_below_angle(&self, degrees: f64) -> usize {
+        return self
+            .angles
+            .chunks(3)
+            .filter(|angles| angles.iter().any(|&angle| angle < degrees))
+            .count();
+    }
+
+    /**
+     * Counts triangles whose aspect ratio is strictly above the threshold.
+     */
+    pub fn count_triangles_above_aspect_ratio(&self, ratio: f64) -> usize {
+        return self.aspect_ratios.iter().filter(|&&r| r > ratio).count();
+    }
+
+    /**
+     * Counts triangles whose area is strictly above the threshold.
+     */
+    pub fn count_triangles_above_area(&self, area: f64) -> usize {
+        return self.areas.iter().filter(|&&a| a > area).count();
+    }
+
+    /**
+     * Largest aspect ratio. Returns None if there is no solid triangle.
+     */
+    pub fn max_aspect_ratio(&self) -> Option<f64> {
+        return max(&self.aspect_ratios);
+    }
+
+    /**
+     * Counts aspect ratios in bins of bin_width, starting at 1, which is the
+     * equilateral aspect ratio. The last bin holds every larger ratio.
+     */
+    pub fn aspect_ratio_histogram(&self, bin_width: f64, bins: usize) -> Vec<usize> {
+        let mut histogram: Vec<usize> = vec![0; bins];
+        if bins == 0 {
+            return histogram;
+        }
+
+        for ratio in self.aspect_ratios.iter() {
+            let bin = (((ratio - 1.0).max(0.0) / bin_width).floor() as usize).min(bins - 1);
+            histogram[bin] += 1;
+        }
+
+        return histogram;
+    }
+
+    pub fn min_area(&self) -> Option<f64> {
+        return min(&self.areas);
+    }
+
+    pub fn max_area(&self) -> Option<f64> {
+        return max(&self.areas);
+    }
+
+    pub fn mean_area(&self) -> Option<f64> {
+        if self.triangles == 0 {
+            return None;
+        }
+        return Some(self.areas.iter().sum::<f64>() / self.triangles as f64);
+    }
+
+    /**
+     * Counts areas in equally sized bins from the smallest to the largest
+     * area. The last bin holds the largest area as well.
+     */
+    pub fn area_histogram(&self, bins: usize) -> Vec<usize> {
+        let mut histogram: Vec<usize> = vec![0; bins];
+        if bins == 0 || self.triangles == 0 {
+            return histogram;
+        }
+
+        let min_area = self.min_area().unwrap();
+        let bin_width = (self.max_area().unwrap() - min_area) / bins as f64;
+
+        for area in self.areas.iter() {
+            let bin = if bin_width > 0.0 {
+                (((area - min_area) / bin_width).floor() as usize).min(bins - 1)
+            } else {
+                0
+            };
+            histogram[bin] += 1;
+        }
+
+        return histogram;
+    }
+
+    /**
+     * Panics if any inner angle is below the threshold, reporting how many
+     * angles violate it and the smallest one.
+     */
+    pub fn assert_min_angle(&self, degrees: f64) {
+        let violations = self.count_angles_below(degrees);
+        if violations > 0 {
+            panic!(
+                "{} of {} angles below {} degrees, smallest is {} degrees",
+                violations,
+                self.angles.len(),
+                degrees,
+                self.min_angle().unwrap()
+            );
+        }
+    }
+
+    /**
+     * Panics if any inner angle is above the threshold, reporting how many
+     * angles violate it and the largest one.
+     */
+    pub fn assert_max_angle(&self, degrees: f64) {
+        let violations = self.count_angles_above(degrees);
+        if violations > 0 {
+            panic!(
+                "{} of {} angles above {} degrees, largest is {} degrees",
+                violations,
+                self.angles.len(),
+                degrees,
+                self.max_angle().unwrap()
+            );
+        }
+    }
+} /* end - QualityReport */
+
+impl fmt::Display for QualityReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(formatter, "Triangles: {}", self.triangles)?;
+        if self.triangles == 0 {
+            return Ok(());
+        }
+
+        writeln!(
+            formatter,
+            "Angles (degrees): min {:.4}, max {:.4}, mean min {:.4}",
+            self.min_angle().unwrap(),
+            self.max_angle().unwrap(),
+            self.mean_min_angle().unwrap()
+        )?;
+        writeln!(
+            formatter,
+            "Angle histogram (10 degrees bins): {:?}",
+            self.angle_histogram(10.0)
+        )?;
+        writeln!(
+            formatter,
+            "Aspect ratio: max {:.4}",
+            self.max_aspect_ratio().unwrap()
+        )?;
+        writeln!(
+            formatter,
+            "Aspect ratio histogram (0.25 bins from 1): {:?}",
+            self.aspect_ratio_histogram(0.25, 8)
+        )?;
+        writeln!(
+            formatter,
+            "Areas: min {:e}, max {:e}, mean {:e}",
+            self.min_area().unwrap(),
+            self.max_area().unwrap(),
+            self.mean_area().unwrap()
+        )?;
+        return writeln!(
+            formatter,
+            "Area histogram (10 bins from min to max): {:?}",
+            self.area_histogram(10)
+        );
+    }
+}
+
+fn min(values: &Vec<f64>) -> Option<f64> {
+    return values.iter().cloned().fold(None, |acc, value| match acc {
+        Some(min) => Some(value.min(min)),
+        None => Some(value),
+    });
+}
+
+fn max(values: &Vec<f64>) -> Option<f64> {
+    return values.iter().cloned().fold(None, |acc, value| match acc {
+        Some(max) => Some(value.max(max)),
+        None => Some(value),
+    });
+}
+
+#[cfg(test)]
+mod quality_report {
+    use super::*;
+    use crate::elements::{triangle::*, vertex::*};
+    use std::rc::Rc;
+
+    fn sample_triangulation() -> Triangulation {
+        /* right isosceles triangle and equilateral triangle */
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+
+        let v4 = Rc::new(Vertex::new(2.0, 0.0));
+        let v5 = Rc::new(Vertex::new(4.0, 0.0));
+        let v6 = Rc::new(Vertex::new(3.0, 3.0_f64.sqrt()));
+
+        let ghost = Rc::new(Vertex::new_ghost());
+
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v1, &v2, &v3)));
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v4, &v5, &v6)));
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v2, &v1, &ghost)));
+        return triangulation;
+    }
+
+    #[test]
+    fn collects_solid_angles() {
+        let report = QualityReport::new(&sample_triangulation());
+        assert_eq!(report.triangles, 2);
+        assert_eq!(report.angles.len(), 6);
+        assert!((report.min_angle().unwrap() - 45.0).abs() < 1.0e-10);
+        assert!((report.max_angle().unwrap() - 90.0).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn empty_triangulation() {
+        let report = QualityReport::new(&Triangulation::new());
+        assert!(report.min_angle().is_none());
+        assert!(report.max_angle().is_none());
+        report.assert_min_angle(20.0);
+    }
+
+    #[test]
+    fn histogram() {
+        let report = QualityReport::new(&sample_triangulation());
+        let histogram = report.angle_histogram(25.0);
+        assert_eq!(histogram, vec![0, 2, 3, 1, 0, 0, 0, 0]);
+        assert_eq!(report.count_angles_below(50.0), 2);
+        assert_eq!(report.count_angles_above(60.5), 1);
+
+        assert!(report.angle_histogram(0.0).is_empty());
+        assert!(report.angle_histogram(-10.0).is_empty());
+        assert!(report.angle_histogram(f64::NAN).is_empty());
+    }
+
+    #[test]
+    fn statistics() {
+        let report = QualityReport::new(&sample_triangulation());
+        assert!((report.mean_min_angle().unwrap() - 52.5).abs() < 1.0e-10);
+        assert!((report.min_area().unwrap() - 0.5).abs() < 1.0e-10);
+        assert!((report.max_area().unwrap() - 3.0_f64.sqrt()).abs() < 1.0e-10);
+        assert_eq!(report.area_histogram(2), vec![1, 1]);
+        assert_eq!(report.aspect_ratio_histogram(0.25, 4), vec![1, 1, 0, 0]);
+        assert_eq!(report.count_triangles_below_angle(50.0), 1);
+        assert_eq!(report.count_triangles_above_aspect_ratio(1.1), 1);
+        assert_eq!(report.count_triangles_above_area(1.0), 1);
+    }
+
+    #[test]
+    fn display() {
+        let report = QualityReport::new(&sample_triangulation());
+        let summary = format!("{}", report);
+        assert!(summary.starts_with("Triangles: 2"));
+        assert!(summary.contains("min 45.0000, max 90.0000"));
+        assert_eq!(format!("{}", QualityReport::new(&Triangulation::new())), "Triangles: 0\n");
+    }
+
+    #[test]
+    fn assert_min_angle() {
+        let report = QualityReport::new(&sample_triangulation());
+        report.assert_min_angle(20.0);
+        report.assert_max_angle(90.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "below 50 degrees")]
+    fn assert_min_angle_violation() {
+        let report = QualityReport::new(&sample_triangulation());
+        report.assert_min_angle(50.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "above 80 degrees")]
+    fn assert_max_angle_violation() {
+        let report = QualityReport::new(&sample_triangulation());
+        report.assert_max_angle(80.0);
+    }
+} /* end - quality_report tests */
@@ -1,4 +1,502 @@
+use crate::elements::{polyline::*, triangle::*};
+use crate::properties::continence::*;
+
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+/**
+ * Refinement params. Triangles are split until their radius-edge ratio,
+ * the circumradius over the shortest edge, is below quality_ratio, which
+ * bounds their smallest angle by asin(1 / 2B):
+ *  - sqrt(2), about 20.7 degrees, the bound that always terminates;
+ *  - 1, 30 degrees, terminating in practice for most domains;
+ *  - 1 / sqrt(3), 60 degrees, the equilateral triangle, never reached.
+ * Triangles are also split while larger than max_area, or while their
+ * longest edge exceeds max_edge_length, when given.
+ */
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct RefineParams {
     pub max_area: Option<f64>,
     pub quality_ratio: f64,
+
+    /* longest edge allowed, as wave propagation models bound it by the wavelength */
+    pub max_edge_length: Option<f64>,
+
+    /* regions refined by their own params, the latest matching a triangle prevails */
+    pub regions: Vec<RegionParams>,
 }
+
+/**
+ * Refinement params of a region, overriding the global ones where set.
+ * Triangles whose center lies inside the boundary belong to the region, or,
+ * for holes, the triangles touching the hole.
+ */
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegionParams {
+    pub boundary: Rc<Polyline>,
+    pub hole: bool,
+    pub max_area: Option<f64>,
+    pub quality_ratio: Option<f64>,
+    pub max_edge_length: Option<f64>,
+}
+
+impl RegionParams {
+    pub fn new(boundary: Rc<Polyline>, hole: bool) -> Self {
+        return Self {
+            boundary,
+            hole,
+            max_area: None,
+            quality_ratio: None,
+            max_edge_length: None,
+        };
+    }
+
+    pub fn matches(&self, triangle: &Triangle) -> bool {
+        if self.hole {
+            return vec![&triangle.v1, &triangle.v2, &triangle.v3]
+                .into_iter()
+                .any(|v| self.boundary.contains(v) == Some(Continence::Boundary));
+        }
+        return self.boundary.contains(&triangle.center()) == Some(Continence::Inside);
+    }
+} /* end - RegionParams */
+
+/**
+ * Invalid refinement params, as found by RefineParams::validated.
+ *  - UnattainableQuality: radius-edge ratio not above the one of the
+ * equilateral triangle, as for minimum angles of 60 degrees or more.
+ *  - NonPositiveArea: max area that is not a positive number.
+ *  - NonPositiveEdgeLength: max edge length that is not a positive number.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParamError {
+    UnattainableQuality(f64),
+    NonPositiveArea(f64),
+    NonPositiveEdgeLength(f64),
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::UnattainableQuality(quality_ratio) => {
+                return write!(
+                    f,
+                    "Quality ratio {} is not above 1 / sqrt(3), of equilateral triangles",
+                    quality_ratio
+                );
+            }
+            ParamError::NonPositiveArea(max_area) => {
+                return write!(f, "Max area {} is not positive", max_area);
+            }
+            ParamError::NonPositiveEdgeLength(max_edge_length) => {
+                return write!(f, "Max edge length {} is not positive", max_edge_length);
+            }
+        }
+    }
+}
+
+impl Error for ParamError {}
+
+impl RefineParams {
+    /**
+     * Params bounding the smallest angle, in degrees, by the radius-edge
+     * ratio 1 / (2 sin(angle)).
+     */
+    pub fn from_min_angle(min_angle: f64) -> Self {
+        return Self {
+            max_area: None,
+            quality_ratio: 0.5 / min_angle.to_radians().sin(),
+            max_edge_length: None,
+            regions: Vec::new(),
+        };
+    }
+
+    /**
+     * Params of the radius-edge ratio sqrt(2), bounding angles by about
+     * 20.7 degrees, for which refinement always terminates.
+     */
+    pub fn quality_20deg() -> Self {
+        return Self {
+            quality_ratio: 2.0_f64.sqrt(),
+            ..Self::from_min_angle(20.0)
+        };
+    }
+
+    /**
+     * Params of the radius-edge ratio 1, bounding angles by 30 degrees.
+     * Refinement may not terminate for small input angles, as reported by
+     * Triangulator::try_refine.
+     */
+    pub fn quality_30deg() -> Self {
+        return Self {
+            quality_ratio: 1.0,
+            ..Self::from_min_angle(30.0)
+        };
+    }
+
+    /**
+     * Params bounding triangle areas, along with the angles as quality_20deg.
+     */
+    pub fn max_area(max_area: f64) -> Self {
+        return Self::quality_20deg().with_max_area(max_area);
+    }
+
+    pub fn with_max_area(self, max_area: f64) -> Self {
+        return Self {
+            max_area: Some(max_area),
+            ..self
+        };
+    }
+
+    pub fn with_max_edge_length(self, max_edge_length: f64) -> Self {
+        return Self {
+            max_edge_length: Some(max_edge_length),
+            ..self
+        };
+    }
+
+    /**
+     * Smallest angle bounded by the quality ratio, in degrees.
+     */
+    pub fn min_angle(&self) -> f64 {
+        return (0.5 / self.quality_ratio).min(1.0).asin().to_degrees();
+    }
+
+    /**
+     * Checks that the params can be met: quality ratios above the one of
+     * equilateral triangles, and positive areas and edge lengths, for the
+     * global params as for the region ones.
+     */
+    pub fn validated(self) -> Result<Self, ParamError> {
+        let quality_ratios = self
+            .regions
+            .iter()
+            .filter_map(|region| region.quality_ratio)
+            .chain(Some(self.quality_ratio));
+        for quality_ratio in quality_ratios {
+            /* NaN fails every comparison */
+            if !(quality_ratio > 1.0 / 3.0_f64.sqrt()) || quality_ratio.is_infinite() {
+                return Err(ParamError::UnattainableQuality(quality_ratio));
+            }
+        }
+
+        let max_areas = self.regions.iter().map(|region| region.max_area);
+        for max_area in max_areas.chain(Some(self.max_area)).flatten() {
+            if !(max_area > 0.0) {
+                return Err(ParamError::NonPositiveArea(max_area));
+            }
+        }
+
+        let max_edge_lengths = self.regions.iter().map(|region| region.max_edge_length);
+        for max_edge_length in max_edge_lengths.chain(Some(self.max_edge_length)).flatten() {
+            if !(max_edge_length > 0.0) {
+                return Err(ParamError::NonPositiveEdgeLength(max_edge_length));
+            }
+        }
+
+        return Ok(self);
+    }
+
+    /**
+     * Maximum area of the triangle: the one of the latest region matching
+     * it with an area of its own, else the global one.
+     */
+    pub fn max_area_of(&self, triangle: &Triangle) -> Option<f64> {
+        return self
+            .region_value(triangle, |region| region.max_area)
+            .or(self.max_area);
+    }
+
+    /**
+     * Radius-edge ratio bound of the triangle, resolved as max_area_of.
+     */
+    pub fn quality_ratio_of(&self, triangle: &Triangle) -> f64 {
+        return self
+            .region_value(triangle, |region| region.quality_ratio)
+            .unwrap_or(self.quality_ratio);
+    }
+
+    /**
+     * Longest edge allowed for the triangle, resolved as max_area_of.
+     */
+    pub fn max_edge_length_of(&self, triangle: &Triangle) -> Option<f64> {
+        return self
+            .region_value(triangle, |region| region.max_edge_length)
+            .or(self.max_edge_length);
+    }
+
+    fn region_value(
+        &self,
+        triangle: &Triangle,
+        value: impl Fn(&RegionParams) -> Option<f64>,
+    ) -> Option<f64> {
+        return self
+            .regions
+            .iter()
+            .rev()
+            .filter(|region| value(region).is_some())
+            .find(|region| region.matches(triangle))
+            .and_then(|region| value(region));
+    }
+} /* end - RefineParams */
+
+/**
+ * Named refinement presets, for users not familiar with radius-edge ratios.
+ * Each profile bounds the radius-edge ratio, thus the smallest angle by
+ * asin(1 / 2B), and the triangle area, relative to the domain size.
+ *  - Draft: coarse meshes, about 14.5 degrees.
+ *  - Standard: about 20.7 degrees, the bound that always terminates.
+ *  - Fine: dense meshes, about 27 degrees.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum RefineProfile {
+    Draft,
+    Standard,
+    Fine,
+}
+
+impl RefineProfile {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "draft" => return Some(RefineProfile::Draft),
+            "standard" => return Some(RefineProfile::Standard),
+            "fine" => return Some(RefineProfile::Fine),
+            _ => return None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RefineProfile::Draft => return "draft",
+            RefineProfile::Standard => return "standard",
+            RefineProfile::Fine => return "fine",
+        }
+    }
+
+    pub fn quality_ratio(&self) -> f64 {
+        match self {
+            RefineProfile::Draft => return 2.0,
+            RefineProfile::Standard => return 2.0_f64.sqrt(),
+            RefineProfile::Fine => return 1.1,
+        }
+    }
+
+    /**
+     * Maximum triangle area, as a fraction of the domain area.
+     */
+    pub fn area_fraction(&self) -> f64 {
+        match self {
+            RefineProfile::Draft => return 1.0 / 50.0,
+            RefineProfile::Standard => return 1.0 / 200.0,
+            RefineProfile::Fine => return 1.0 / 1000.0,
+        }
+    }
+
+    /**
+     * Refinement parameters of the profile, for a domain of the given area.
+     */
+    pub fn params(&self, domain_area: f64) -> RefineParams {
+        return RefineParams {
+            max_area: Some(domain_area * self.area_fraction()),
+            quality_ratio: self.quality_ratio(),
+            max_edge_length: None,
+            regions: Vec::new(),
+        };
+    }
+} /* end - RefineProfile */
+
+#[cfg(test)]
+mod refine_params {
+    use super::*;
+    use crate::elements::vertex::*;
+
+    #[test]
+    fn max_edge_length_of() {
+        let square = |x: f64, y: f64, size: f64| {
+            return Rc::new(
+                Polyline::new_closed(vec![
+                    Rc::new(Vertex::new(x, y)),
+                    Rc::new(Vertex::new(x + size, y)),
+                    Rc::new(Vertex::new(x + size, y + size)),
+                    Rc::new(Vertex::new(x, y + size)),
+                ])
+                .unwrap(),
+            );
+        };
+        let triangle_at = |x: f64, y: f64| {
+            return Triangle::new(
+                &Rc::new(Vertex::new(x, y)),
+                &Rc::new(Vertex::new(x + 0.1, y)),
+                &Rc::new(Vertex::new(x, y + 0.1)),
+            );
+        };
+
+        let region = |boundary: Rc<Polyline>, max_edge_length: f64| {
+            let mut region = RegionParams::new(boundary, false);
+            region.max_edge_length = Some(max_edge_length);
+            return region;
+        };
+
+        let params = RefineParams {
+            max_area: None,
+            quality_ratio: 2.0,
+            max_edge_length: Some(1.0),
+            regions: vec![region(square(0.0, 0.0, 4.0), 0.5), region(square(1.0, 1.0, 1.0), 0.1)],
+        };
+        assert_eq!(params.max_edge_length_of(&triangle_at(10.0, 10.0)), Some(1.0));
+        assert_eq!(params.max_edge_length_of(&triangle_at(3.0, 3.0)), Some(0.5));
+        assert_eq!(params.max_edge_length_of(&triangle_at(1.5, 1.5)), Some(0.1));
+    }
+
+    #[test]
+    fn region_overrides() {
+        let square = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(1.0, 1.0)),
+                Rc::new(Vertex::new(2.0, 1.0)),
+                Rc::new(Vertex::new(2.0, 2.0)),
+                Rc::new(Vertex::new(1.0, 2.0)),
+            ])
+            .unwrap(),
+        );
+        let triangle = |v1: (f64, f64), v2: (f64, f64), v3: (f64, f64)| {
+            return Triangle::new(
+                &Rc::new(Vertex::new(v1.0, v1.1)),
+                &Rc::new(Vertex::new(v2.0, v2.1)),
+                &Rc::new(Vertex::new(v3.0, v3.1)),
+            );
+        };
+
+        let mut inside = RegionParams::new(Rc::clone(&square), false);
+        inside.max_area = Some(0.1);
+        let mut hole = RegionParams::new(Rc::clone(&square), true);
+        hole.quality_ratio = Some(1.0);
+
+        let params = RefineParams {
+            max_area: Some(1.0),
+            quality_ratio: 2.0,
+            max_edge_length: None,
+            regions: vec![inside, hole],
+        };
+
+        /* inside the square: its area, and the global quality */
+        let inner = triangle((1.2, 1.2), (1.4, 1.2), (1.2, 1.4));
+        assert_eq!(params.max_area_of(&inner), Some(0.1));
+        assert_eq!(params.quality_ratio_of(&inner), 2.0);
+
+        /* touching the square as a hole: its quality */
+        let touching = triangle((2.0, 1.5), (3.0, 1.5), (3.0, 2.5));
+        assert_eq!(params.max_area_of(&touching), Some(1.0));
+        assert_eq!(params.quality_ratio_of(&touching), 1.0);
+
+        let apart = triangle((3.0, 3.0), (4.0, 3.0), (4.0, 4.0));
+        assert_eq!(params.max_area_of(&apart), Some(1.0));
+        assert_eq!(params.quality_ratio_of(&apart), 2.0);
+        assert_eq!(params.max_edge_length_of(&apart), None);
+    }
+} /* end - refine_params tests */
+
+#[cfg(test)]
+mod presets {
+    use super::*;
+    use crate::elements::vertex::*;
+
+    #[test]
+    fn min_angles() {
+        let params = RefineParams::quality_20deg();
+        assert_eq!(params.quality_ratio, 2.0_f64.sqrt());
+        assert!((params.min_angle() - 20.7).abs() < 0.01);
+
+        let params = RefineParams::quality_30deg();
+        assert_eq!(params.quality_ratio, 1.0);
+        assert!((params.min_angle() - 30.0).abs() < 1e-12);
+
+        let params = RefineParams::from_min_angle(25.0);
+        assert!((params.min_angle() - 25.0).abs() < 1e-12);
+        assert_eq!(params.max_area, None);
+
+        let params = RefineParams::max_area(0.5).with_max_edge_length(1.0);
+        assert_eq!(params.max_area, Some(0.5));
+        assert_eq!(params.max_edge_length, Some(1.0));
+        assert_eq!(params.quality_ratio, 2.0_f64.sqrt());
+    }
+
+    #[test]
+    fn validated() {
+        assert!(RefineParams::quality_30deg().validated().is_ok());
+        assert!(RefineParams::max_area(0.1).validated().is_ok());
+
+        assert_eq!(
+            RefineParams::from_min_angle(60.0).validated().unwrap_err(),
+            ParamError::UnattainableQuality(0.5 / 60.0_f64.to_radians().sin())
+        );
+        let params = RefineParams {
+            quality_ratio: std::f64::NAN,
+            ..RefineParams::quality_20deg()
+        };
+        assert!(params.validated().is_err());
+
+        assert_eq!(
+            RefineParams::max_area(0.0).validated().unwrap_err(),
+            ParamError::NonPositiveArea(0.0)
+        );
+        assert_eq!(
+            RefineParams::quality_20deg()
+                .with_max_edge_length(-1.0)
+                .validated()
+                .unwrap_err(),
+            ParamError::NonPositiveEdgeLength(-1.0)
+        );
+
+        /* region params are validated as well */
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(1.0, 0.0)),
+                Rc::new(Vertex::new(0.0, 1.0)),
+            ])
+            .unwrap(),
+        );
+        let mut params = RefineParams::quality_20deg();
+        params.regions.push(RegionParams {
+            quality_ratio: Some(0.5),
+            ..RegionParams::new(boundary, false)
+        });
+        assert_eq!(
+            params.validated().unwrap_err(),
+            ParamError::UnattainableQuality(0.5)
+        );
+
+        assert_eq!(
+            format!("{}", ParamError::NonPositiveArea(0.0)),
+            "Max area 0 is not positive"
+        );
+    }
+} /* end - presets tests */
+
+#[cfg(test)]
+mod refine_profile {
+    use super::*;
+
+    #[test]
+    fn profiles() {
+        for profile in vec![RefineProfile::Draft, RefineProfile::Standard, RefineProfile::Fine] {
+            assert_eq!(RefineProfile::from_name(profile.name()), Some(profile));
+        }
+        assert_eq!(RefineProfile::from_name("coarse"), None);
+
+        let params = RefineProfile::Standard.params(400.0);
+        assert_eq!(params.max_area, Some(2.0));
+        assert_eq!(params.quality_ratio, 2.0_f64.sqrt());
+
+        /* finer profiles are stricter */
+        let draft = RefineProfile::Draft.params(1.0);
+        let fine = RefineProfile::Fine.params(1.0);
+        assert!(fine.quality_ratio < draft.quality_ratio);
+        assert!(fine.max_area.unwrap() < draft.max_area.unwrap());
+    }
+} /* end - refine_profile tests */
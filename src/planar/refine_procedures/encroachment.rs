@@ -1,10 +1,19 @@
-use crate::elements::{edge::*, polyline::*, triangle::*, vertex::*};
-use crate::planar::{triangulation::*, triangulation_procedures};
-use crate::properties::continence::*;
+use crate::elements::{edge::*, polyline::*, triangle::*, vertex::*, vertex_pool::*};
+use crate::planar::{
+    triangulation::*, triangulation_procedures, triangulator::DEFAULT_WELDING_TOLERANCE,
+};
+use crate::properties::{angle::*, continence::*};
 
 use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
 use std::rc::Rc;
 
+/* Input angles below which segments are split on concentric shells */
+const SMALL_ANGLE: f64 = PI / 3.0;
+
+/* Angles below which segments sharing a vertex are taken as the same one */
+const COLINEAR_ANGLE: f64 = 1.0E-6;
+
 /**
  * Find encroached segments and unencroaches them by spliting segments
  */
@@ -26,13 +35,18 @@ pub fn unencroach(
 
     distribute_encroachments(
         segment_contraints,
-        &triangulation.vertices(),
+        triangulation.vertices(),
         &mut encroach_map,
     );
 
     while !encroach_map.is_empty() {
         let encroached_edge = Rc::clone(encroach_map.keys().next().unwrap());
         let mut encroaching_vertices = encroach_map.remove(&encroached_edge).unwrap();
+        log::debug!(
+            "unencroaching segment {}, encroached by {} vertices",
+            encroached_edge,
+            encroaching_vertices.len()
+        );
 
         let (new_edges, new_triangles, old_triangles) = unencroach_segment(
             triangulation,
@@ -121,6 +135,11 @@ pub fn unencroach_segment(
             .collect::<HashSet<Rc<Vertex>>>()
             .iter()
         {
+            /* vertices on the segment encroach every subsegment around them, thus split it once */
+            if h1.contains(v) || h2.contains(v) {
+                encroaching_vertices.remove(v);
+                continue;
+            }
             let mut v_encroaches_any = false;
             if h1.encroach(&v) == Continence::Inside {
                 is_h1_encroached = true;
@@ -155,21 +174,22 @@ pub fn unencroach_segment(
  * Populates encroach_map with encroachments of segments against a collection of vertices.
  * A vertex will be copied to more than a collection if it is encroached more than once.
  */
-pub fn distribute_encroachments(
+pub fn distribute_encroachments<'a, I>(
     segments: &HashSet<Rc<Edge>>,
-    vertices: &HashSet<Rc<Vertex>>,
+    vertices: I,
     encroach_map: &mut HashMap<Rc<Edge>, HashSet<Rc<Vertex>>>,
-) {
-    for edge in segments.iter() {
-        let mut possible_encroached_vertices: HashSet<Rc<Vertex>> = HashSet::new();
-        for vertex in vertices.iter() {
+) where
+    I: IntoIterator<Item = &'a Rc<Vertex>>,
+{
+    for vertex in vertices.into_iter() {
+        for edge in segments.iter() {
             if edge.encroach(vertex) == Continence::Inside {
-                possible_encroached_vertices.insert(Rc::clone(vertex));
+                encroach_map
+                    .entry(Rc::clone(edge))
+                    .or_insert_with(HashSet::new)
+                    .insert(Rc::clone(vertex));
             }
         }
-        if !possible_encroached_vertices.is_empty() {
-            encroach_map.insert(Rc::clone(edge), possible_encroached_vertices);
-        }
     }
 }
 
@@ -196,13 +216,26 @@ fn split_segment(
         .cloned()
         .collect();
 
-    let segment_midpoint = Rc::new(segment.midpoint());
-    let half_1 = Rc::new(Edge::new(&segment.v1, &segment_midpoint));
-    let half_2 = Rc::new(Edge::new(&segment_midpoint, &segment.v2));
+    let split_vertex = split_point(segment, &segment_constraints);
+    log::debug!("splitting encroached segment {} at {}", segment, split_vertex);
+
+    /* reuses an existing vertex placed at the split point */
+    let pool = VertexPool::from_vertices(triangulation.vertices(), DEFAULT_WELDING_TOLERANCE);
+    if let Some(existing_vertex) = pool.find(&split_vertex) {
+        let half_1 = Rc::new(Edge::new(&segment.v1, &existing_vertex));
+        let half_2 = Rc::new(Edge::new(&existing_vertex, &segment.v2));
+        triangulation.split_constraint(segment, &half_1, &half_2);
+        return (half_1, half_2, HashSet::new(), HashSet::new());
+    }
+
+    let split_vertex = Rc::new(split_vertex);
+    let half_1 = Rc::new(Edge::new(&segment.v1, &split_vertex));
+    let half_2 = Rc::new(Edge::new(&split_vertex, &segment.v2));
+    triangulation.split_constraint(segment, &half_1, &half_2);
 
     let (included_triangles, removed_triangles) = triangulation_procedures::vertices::include(
         triangulation,
-        vec![segment_midpoint],
+        vec![split_vertex],
         &segment_constraints,
         boundary,
         holes,
@@ -210,6 +243,68 @@ fn split_segment(
     return (half_1, half_2, included_triangles, removed_triangles);
 } /* end - split_segment */
 
+/**
+ * Point splitting the segment. Segments having a single end shared with
+ * other segments at a small angle are split on concentric shells around
+ * that end, at the power of two distance splitting them most evenly, so
+ * that subsegments at both sides of the angle get the same lengths and
+ * stop encroaching each other. Other segments are split at the midpoint.
+ */
+fn split_point(segment: &Edge, segment_constraints: &HashSet<Rc<Edge>>) -> Vertex {
+    let is_v1_apex = is_small_angle_apex(&segment.v1, &segment.v2, segment_constraints);
+    let is_v2_apex = is_small_angle_apex(&segment.v2, &segment.v1, segment_constraints);
+    if is_v1_apex == is_v2_apex {
+        return segment.midpoint();
+    }
+
+    let (apex, end) = match is_v1_apex {
+        true => (&segment.v1, &segment.v2),
+        false => (&segment.v2, &segment.v1),
+    };
+
+    /* worst case split is 1:2 */
+    let length = segment.length();
+    let mut shell_radius = 1.0;
+    while length > 3.0 * shell_radius {
+        shell_radius *= 2.0;
+    }
+    while length < 1.5 * shell_radius {
+        shell_radius *= 0.5;
+    }
+
+    let ratio = shell_radius / length;
+    return Vertex::new(
+        apex.x + ratio * (end.x - apex.x),
+        apex.y + ratio * (end.y - apex.y),
+    );
+} /* end - split_point */
+
+/**
+ * Checks whether any segment leaving the apex makes a small angle with the
+ * segment from the apex to the end.
+ */
+fn is_small_angle_apex(
+    apex: &Rc<Vertex>,
+    end: &Rc<Vertex>,
+    segment_constraints: &HashSet<Rc<Edge>>,
+) -> bool {
+    return segment_constraints
+        .iter()
+        .filter_map(|edge| {
+            if &edge.v1 == apex {
+                return Some(&edge.v2);
+            }
+            if &edge.v2 == apex {
+                return Some(&edge.v1);
+            }
+            return None;
+        })
+        .filter(|&other_end| other_end != end)
+        .filter_map(|other_end| angle(end, apex, other_end))
+        .map(|theta| theta.min(2.0 * PI - theta))
+        .any(|theta| theta > COLINEAR_ANGLE && theta < SMALL_ANGLE);
+}
+
 #[cfg(test)]
 mod vertices_inclusion {
     use super::*;
@@ -286,7 +381,7 @@ mod vertices_inclusion {
         /* Creates triangulation with boundary and hole */
         let mut triangulation = Triangulation::from_initial_segment((&v1, &v2));
         triangulation_procedures::boundary::include(&mut triangulation, &boundary, &HashSet::new());
-        triangulation_procedures::hole::include(&mut triangulation, &hole, &HashSet::new());
+        triangulation_procedures::hole::include(&mut triangulation, &hole, &HashSet::new()).unwrap();
 
         /* Inserts vertex at hole segment */
         let splittable_edge: Rc<Edge> = Rc::new(Edge::new(&v6, &v7));
@@ -314,9 +409,7 @@ mod vertices_inclusion {
 
         assert_eq!(
             triangulation
-                .triangles
-                .iter()
-                .filter(|t| t.is_ghost())
+                .ghost_triangles()
                 .cloned()
                 .collect::<HashSet<Rc<Triangle>>>()
                 .len(),
@@ -324,9 +417,7 @@ mod vertices_inclusion {
         );
         assert_eq!(
             triangulation
-                .triangles
-                .iter()
-                .filter(|t| !t.is_ghost())
+                .solid_triangles()
                 .cloned()
                 .collect::<HashSet<Rc<Triangle>>>()
                 .len(),
@@ -419,7 +510,7 @@ mod split {
         /* Creates triangulation with boundary and hole */
         let mut triangulation = Triangulation::from_initial_segment((&v1, &v2));
         triangulation_procedures::boundary::include(&mut triangulation, &boundary, &HashSet::new());
-        triangulation_procedures::hole::include(&mut triangulation, &hole, &HashSet::new());
+        triangulation_procedures::hole::include(&mut triangulation, &hole, &HashSet::new()).unwrap();
 
         /* Inserts vertex at hole segment */
         let splittable_edge: Rc<Edge> = Rc::new(Edge::new(&v6, &v7));
@@ -451,9 +542,7 @@ mod split {
 
         assert_eq!(
             triangulation
-                .triangles
-                .iter()
-                .filter(|t| t.is_ghost())
+                .ghost_triangles()
                 .cloned()
                 .collect::<HashSet<Rc<Triangle>>>()
                 .len(),
@@ -461,9 +550,7 @@ mod split {
         );
         assert_eq!(
             triangulation
-                .triangles
-                .iter()
-                .filter(|t| !t.is_ghost())
+                .solid_triangles()
                 .cloned()
                 .collect::<HashSet<Rc<Triangle>>>()
                 .len(),
@@ -595,4 +682,127 @@ mod unencroach {
             &Rc::new(Vertex::new(8.0, 0.0)),
         )));
     } /* sample_2 */
+
+    #[test]
+    fn vertex_on_segment() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(8.0, 0.0));
+        let v3 = Rc::new(Vertex::new(4.0, 8.0));
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![Rc::clone(&v1), Rc::clone(&v2), Rc::clone(&v3)]).unwrap(),
+        );
+
+        let mut triangulation = Triangulation::from_initial_segment((&v1, &v2));
+        triangulation_procedures::boundary::include(&mut triangulation, &boundary, &HashSet::new());
+
+        /* circumcenter on the segment, off every split point */
+        let mut encroaching_vertices: HashSet<Rc<Vertex>> =
+            vec![Rc::new(Vertex::new(10.0 / 3.0, 0.0))].into_iter().collect();
+        let (new_edges, _, _) = unencroach_segment(
+            &mut triangulation,
+            &Rc::new(Edge::new(&v1, &v2)),
+            &mut encroaching_vertices,
+            &boundary.into_edges().iter().cloned().collect(),
+            &Some(Rc::clone(&boundary)),
+            &HashSet::new(),
+        );
+
+        assert_eq!(new_edges.len(), 2);
+        assert!(encroaching_vertices.is_empty());
+    } /* vertex_on_segment */
 } /* end - unencroach tests */
+
+#[cfg(test)]
+mod midpoint_reuse {
+    use super::*;
+
+    #[test]
+    fn reuses_existing_vertex() {
+        /*
+            Segment whose midpoint was already
+            inserted as a vertex
+        */
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0, 2.0));
+        let v4 = Rc::new(Vertex::new(1.0, 0.0));
+
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![Rc::clone(&v1), Rc::clone(&v2), Rc::clone(&v3)]).unwrap(),
+        );
+
+        let mut triangulation = Triangulation::from_initial_segment((&v1, &v2));
+        triangulation_procedures::boundary::include(&mut triangulation, &boundary, &HashSet::new());
+        triangulation_procedures::vertices::include(
+            &mut triangulation,
+            vec![Rc::clone(&v4)],
+            &HashSet::new(),
+            &Some(Rc::clone(&boundary)),
+            &HashSet::new(),
+        );
+        let triangles = triangulation.triangles.clone();
+
+        let segment = Rc::new(Edge::new(&v1, &v2));
+        let (h1, h2, included_triangles, removed_triangles) = split_segment(
+            &mut triangulation,
+            &segment,
+            &HashSet::new(),
+            &Some(boundary),
+            &HashSet::new(),
+        );
+
+        assert!(Rc::ptr_eq(&h1.v2, &v4));
+        assert!(Rc::ptr_eq(&h2.v1, &v4));
+        assert!(included_triangles.is_empty());
+        assert!(removed_triangles.is_empty());
+        assert_eq!(triangulation.triangles, triangles);
+        assert_eq!(triangulation.vertices().len(), 4);
+    }
+} /* end - midpoint_reuse tests */
+
+#[cfg(test)]
+mod concentric_shells {
+    use super::*;
+    use crate::properties::distance::*;
+
+    #[test]
+    fn splits_on_shells_around_small_angles() {
+        let apex = Rc::new(Vertex::new(0.0, 0.0));
+        let v1 = Rc::new(Vertex::new(5.0, 0.0));
+        let v2 = Rc::new(Vertex::new(5.0, 1.0));
+        let s1 = Rc::new(Edge::new(&apex, &v1));
+        let s2 = Rc::new(Edge::new(&v2, &apex));
+        let segments: HashSet<Rc<Edge>> = vec![Rc::clone(&s1), Rc::clone(&s2)]
+            .into_iter()
+            .collect();
+
+        /* both sides of the angle are split on the shell of radius 2 */
+        let p1 = split_point(&s1, &segments);
+        let p2 = split_point(&s2, &segments);
+        assert_eq!(p1, Vertex::new(2.0, 0.0));
+        assert!((distance(&apex, &p2) - 2.0).abs() < 1.0E-12);
+
+        /* subsegments keep on the shells */
+        let h1 = Edge::new(&apex, &Rc::new(p1));
+        assert_eq!(split_point(&h1, &segments), Vertex::new(1.0, 0.0));
+
+        /* apex far from the subsegment, or at both ends */
+        let inner = Edge::new(&Rc::new(Vertex::new(2.0, 0.0)), &v1);
+        assert_eq!(split_point(&inner, &segments), inner.midpoint());
+        let closing = Edge::new(&v1, &v2);
+        assert_eq!(split_point(&closing, &segments), closing.midpoint());
+    }
+
+    #[test]
+    fn splits_at_midpoint_for_wide_angles() {
+        let apex = Rc::new(Vertex::new(0.0, 0.0));
+        let v1 = Rc::new(Vertex::new(5.0, 0.0));
+        let v2 = Rc::new(Vertex::new(0.0, 5.0));
+        let s1 = Rc::new(Edge::new(&apex, &v1));
+        let segments: HashSet<Rc<Edge>> = vec![Rc::clone(&s1), Rc::new(Edge::new(&v2, &apex))]
+            .into_iter()
+            .collect();
+
+        assert_eq!(split_point(&s1, &segments), s1.midpoint());
+    }
+} /* end - concentric_shells tests */
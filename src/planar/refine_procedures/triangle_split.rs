@@ -1,35 +1,38 @@
-use crate::elements::{edge::*, polyline::*, triangle::*, vertex::*};
+use crate::elements::{bounding_box::*, edge::*, polyline::*, triangle::*, vertex::*};
 use crate::planar::{
     refine_params::RefineParams, refine_procedures::encroachment, triangulation::*,
     triangulation_procedures,
 };
 
-use crate::properties::continence::*;
+use crate::properties::{continence::*, distance::*};
 
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 /**
- * Determines if the triangle is irregular according to quality ratio
+ * Determines if the triangle is irregular according to quality ratio,
+ * of its region if any
  */
-fn is_irregular_triangle(triangle: &Triangle, params: &RefineParams) -> bool {
+pub(crate) fn is_irregular_triangle(triangle: &Triangle, params: &RefineParams) -> bool {
     let this_quality = triangle.quality().unwrap();
+    let quality_ratio = params.quality_ratio_of(triangle);
     let no_quality = float_cmp::approx_eq!(
         f64,
         this_quality,
-        params.quality_ratio,
+        quality_ratio,
         epsilon = 1.0E-14f64
-    ) || this_quality >= params.quality_ratio;
+    ) || this_quality >= quality_ratio;
 
     return no_quality;
 }
 
 /**
- * Determines if the triangle is larger than threshould
+ * Determines if the triangle is larger than threshould, either by its area
+ * or by its longest edge, of its region if any
  */
-fn is_large_triangle(triangle: &Triangle, params: &RefineParams) -> bool {
+pub(crate) fn is_large_triangle(triangle: &Triangle, params: &RefineParams) -> bool {
     let this_area = triangle.area().unwrap();
-    let greater_area: bool = match params.max_area {
+    let greater_area: bool = match params.max_area_of(triangle) {
         Some(max_area) => {
             float_cmp::approx_eq!(f64, this_area, max_area, epsilon = 1.0E-14f64)
                 || this_area >= max_area
@@ -37,7 +40,16 @@ fn is_large_triangle(triangle: &Triangle, params: &RefineParams) -> bool {
         _ => false,
     };
 
-    return greater_area;
+    let longer_edge: bool = match params.max_edge_length_of(triangle) {
+        Some(max_edge_length) => {
+            let (e1, e2, e3) = triangle.inner_edges();
+            let this_length = e1.length().max(e2.length()).max(e3.length());
+            this_length > max_edge_length
+        }
+        _ => false,
+    };
+
+    return greater_area || longer_edge;
 }
 
 /**
@@ -53,12 +65,61 @@ pub fn split_irregular(
     boundary: &Option<Rc<Polyline>>,
     holes: &HashSet<Rc<Polyline>>,
 ) -> HashMap<Rc<Edge>, Rc<Edge>> {
+    return split_irregular_observed(
+        triangulation,
+        params,
+        segment_contraints,
+        boundary,
+        holes,
+        &mut |_, _, _| true,
+    );
+}
+
+/**
+ * Refines as split_irregular, calling the observer after each split with
+ * the triangulation, the count of splits so far and the count of triangles
+ * pending. Refinement stops early once the observer returns false.
+ */
+pub fn split_irregular_observed(
+    triangulation: &mut Triangulation,
+    params: &RefineParams,
+    segment_contraints: &HashSet<Rc<Edge>>,
+    boundary: &Option<Rc<Polyline>>,
+    holes: &HashSet<Rc<Polyline>>,
+    observer: &mut dyn FnMut(&Triangulation, usize, usize) -> bool,
+) -> HashMap<Rc<Edge>, Rc<Edge>> {
+    return split_irregular_recording(
+        triangulation,
+        params,
+        segment_contraints,
+        boundary,
+        holes,
+        observer,
+        &mut Vec::new(),
+    );
+}
+
+/**
+ * Refines as split_irregular_observed, stopping once any split creates an
+ * edge shorter than the edge length floor, which only happens when
+ * refinement does not terminate, such as for too strict quality ratios.
+ * The triangles around the short edges are recorded into stalled, keeping
+ * the triangulation valid.
+ */
+pub fn split_irregular_recording(
+    triangulation: &mut Triangulation,
+    params: &RefineParams,
+    segment_contraints: &HashSet<Rc<Edge>>,
+    boundary: &Option<Rc<Polyline>>,
+    holes: &HashSet<Rc<Polyline>>,
+    observer: &mut dyn FnMut(&Triangulation, usize, usize) -> bool,
+    stalled: &mut Vec<Rc<Triangle>>,
+) -> HashMap<Rc<Edge>, Rc<Edge>> {
+    let floor = edge_length_floor(triangulation, segment_contraints);
     let mut segment_contraints: HashSet<Rc<Edge>> = segment_contraints.iter().cloned().collect();
 
     let critical_triangles = triangulation
-        .triangles
-        .iter()
-        .filter(|t| !t.is_ghost())
+        .solid_triangles()
         .filter(|t| is_irregular_triangle(t, params) || is_large_triangle(t, params))
         .cloned()
         .collect::<HashSet<Rc<Triangle>>>();
@@ -76,8 +137,18 @@ pub fn split_irregular(
         .collect();
 
     let mut split_map: HashMap<Rc<Edge>, Rc<Edge>> = HashMap::new();
+    let mut split_count: usize = 0;
 
     loop {
+        if !stalled.is_empty() {
+            break;
+        }
+        let pending = irregular_triangles.len() + large_triangles.len();
+        if split_count > 0 && !observer(triangulation, split_count, pending) {
+            break;
+        }
+        split_count += 1;
+
         let triangle;
         if let Some(irregular_triangle) = irregular_triangles.iter().next() {
             triangle = Rc::clone(&irregular_triangle);
@@ -89,18 +160,13 @@ pub fn split_irregular(
             break;
         }
 
-        // Uncomment to debug
-        // println!(
-        //     "\nSplitting Triangle (Total: {} + {}) - {}",
-        //     irregular_triangles.len(),
-        //     large_triangles.len(),
-        //     triangle.quality().unwrap()
-        // );
-
-        // println!("\nTriangles");
-        // for t in triangulation.triangles.iter().filter(|t| !t.is_ghost()) {
-        //     println!("{}", t);
-        // }
+        log::debug!(
+            "splitting triangle {} of quality {:?}, pending {} irregular and {} large",
+            triangle,
+            triangle.quality(),
+            irregular_triangles.len(),
+            large_triangles.len()
+        );
 
         match try_circumcenter_insertion(
             triangulation,
@@ -110,6 +176,12 @@ pub fn split_irregular(
             holes,
         ) {
             Ok((included_triangles, removed_triangles)) => {
+                stalled.extend(shorter_than(&included_triangles, floor));
+                /* removed first, as cavities may include triangles equal to removed ones */
+                for old_triangle in removed_triangles.iter() {
+                    irregular_triangles.remove(old_triangle);
+                    large_triangles.remove(old_triangle);
+                }
                 for new_triangle in included_triangles.iter().filter(|t| !t.is_ghost()) {
                     if is_irregular_triangle(new_triangle, params) {
                         irregular_triangles.insert(Rc::clone(new_triangle));
@@ -120,10 +192,6 @@ pub fn split_irregular(
                         continue;
                     }
                 }
-                for old_triangle in removed_triangles.iter() {
-                    irregular_triangles.remove(old_triangle);
-                    large_triangles.remove(old_triangle);
-                }
             }
             Err(encroachments) => {
                 let mut vertices = vec![Rc::new(triangle.circumcenter().unwrap())]
@@ -149,6 +217,11 @@ pub fn split_irregular(
                         segment_contraints.insert(Rc::clone(subsegment));
                     }
 
+                    stalled.extend(shorter_than(&included_triangles, floor));
+                    for old_triangle in removed_triangles.iter() {
+                        irregular_triangles.remove(old_triangle);
+                        large_triangles.remove(old_triangle);
+                    }
                     for new_triangle in included_triangles.iter().filter(|t| !t.is_ghost()) {
                         if is_irregular_triangle(new_triangle, params) {
                             irregular_triangles.insert(Rc::clone(new_triangle));
@@ -159,9 +232,14 @@ pub fn split_irregular(
                             continue;
                         }
                     }
-                    for old_triangle in removed_triangles.iter() {
-                        irregular_triangles.remove(old_triangle);
-                        large_triangles.remove(old_triangle);
+                }
+
+                /* the triangle is split again if the segment splits left it */
+                if triangulation.triangles.contains(&triangle) {
+                    if is_irregular_triangle(&triangle, params) {
+                        irregular_triangles.insert(Rc::clone(&triangle));
+                    } else if is_large_triangle(&triangle, params) {
+                        large_triangles.insert(Rc::clone(&triangle));
                     }
                 }
             }
@@ -170,13 +248,59 @@ pub fn split_irregular(
     return split_map;
 } /* end - split */
 
+/* Fraction of the shortest constraint below which edges mean non termination */
+const FEATURE_FRACTION: f64 = 1.0 / 4096.0;
+
+/* Multiples of the machine epsilon, relative to the domain size, edges never go below */
+const EPSILON_MULTIPLES: f64 = 1.0E6;
+
+/**
+ * Length below which refinement edges are taken as non terminating: a
+ * fraction of the shortest constraint, which bounds the local feature size
+ * from below, but never closer to the domain size roundoff.
+ */
+pub fn edge_length_floor(triangulation: &Triangulation, segment_constraints: &HashSet<Rc<Edge>>) -> f64 {
+    let vertices: Vec<Rc<Vertex>> = triangulation
+        .vertices()
+        .iter()
+        .cloned()
+        .collect();
+    let diameter = match BoundingBox::from_vertices(vertices) {
+        Some(bounding_box) => distance(&bounding_box.origin, &bounding_box.destin),
+        None => return 0.0,
+    };
+
+    let shortest_constraint = segment_constraints
+        .iter()
+        .map(|segment| segment.length())
+        .fold(diameter, f64::min);
+
+    return (shortest_constraint * FEATURE_FRACTION).max(diameter * EPSILON_MULTIPLES * f64::EPSILON);
+}
+
+/**
+ * Solid triangles having any edge shorter than the length.
+ */
+fn shorter_than(triangles: &HashSet<Rc<Triangle>>, length: f64) -> Vec<Rc<Triangle>> {
+    return triangles
+        .iter()
+        .filter(|t| !t.is_ghost())
+        .filter(|t| {
+            let (e1, e2, e3) = t.inner_edges();
+            return vec![e1, e2, e3].iter().any(|e| e.length() < length);
+        })
+        .cloned()
+        .collect();
+}
+
 /**
  * Tries to insert a triangle's circumcenter.
  * If any edge of the triangle is constrained and encroaches the circumcenter,
  * it is returned in the hashset. The circumcenter will be inserted through constrained
  * insertion. Among the included triangles, if any is composed by a constrained segment
  * that encroaches the circumcenter, the segment is returned in the hashset. If there is
- * no encroachments, the returnable is empty.
+ * no encroachments, the returnable is empty. Circumcenters falling on constraint
+ * vertices, such as hole corners, are not inserted, returning no encroachments.
  */
 fn try_circumcenter_insertion(
     triangulation: &mut Triangulation,
@@ -186,6 +310,29 @@ fn try_circumcenter_insertion(
     holes: &HashSet<Rc<Polyline>>,
 ) -> Result<(HashSet<Rc<Triangle>>, HashSet<Rc<Triangle>>), HashSet<Rc<Edge>>> {
     let circumcenter = Rc::new(triangle.circumcenter().unwrap());
+
+    let is_constraint_vertex = segment_constraints
+        .iter()
+        .any(|s| s.v1 == circumcenter || s.v2 == circumcenter)
+        || boundary
+            .iter()
+            .chain(holes.iter())
+            .any(|polyline| polyline.vertices.contains(&circumcenter));
+    if is_constraint_vertex {
+        return Err(HashSet::new());
+    }
+
+    /* circumcenters on, or beyond, the triangle constrained edges may not be distributed */
+    let (e1, e2, e3) = triangle.inner_edges();
+    let own_encroachments: HashSet<Rc<Edge>> = vec![e1, e2, e3]
+        .into_iter()
+        .filter(|e| segment_constraints.contains(e) || segment_constraints.contains(&e.opposite()))
+        .filter(|e| e.encroach(&circumcenter) == Continence::Inside)
+        .collect();
+    if !own_encroachments.is_empty() {
+        return Err(own_encroachments);
+    }
+
     let mut conflict_map: HashMap<Rc<Triangle>, Vec<Rc<Vertex>>> = HashMap::new();
 
     triangulation_procedures::vertices::distribute_conflicts_over_triangulation(
@@ -228,6 +375,39 @@ fn try_circumcenter_insertion(
     return Ok((included_triangles, removed_triangles));
 }
 
+#[cfg(test)]
+mod circumcenter_on_corner {
+    use super::*;
+
+    #[test]
+    fn rejects_insertion() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 2.0));
+        let triangle = Rc::new(Triangle::new(&v1, &v2, &v3));
+
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&triangle);
+
+        /* constraint ending at the circumcenter */
+        let corner = Rc::new(Vertex::new(1.0, 1.0));
+        let far = Rc::new(Vertex::new(5.0, 5.0));
+        let segment_constraints: HashSet<Rc<Edge>> =
+            vec![Rc::new(Edge::new(&corner, &far))].into_iter().collect();
+
+        let result = try_circumcenter_insertion(
+            &mut triangulation,
+            &triangle,
+            &segment_constraints,
+            &None,
+            &HashSet::new(),
+        );
+        assert_eq!(result, Err(HashSet::new()));
+        assert_eq!(triangulation.triangles.len(), 1);
+        assert!(triangulation.triangles.contains(&triangle));
+    }
+} /* end - circumcenter_on_corner tests */
+
 #[cfg(test)]
 mod split {
     use super::*;
@@ -287,6 +467,8 @@ mod split {
             &RefineParams {
                 max_area: None, /* not used */
                 quality_ratio: 1.0,
+                max_edge_length: None,
+                regions: Vec::new(),
             },
             &segment_constraints,
             &Some(Rc::clone(&boundary)),
@@ -294,9 +476,7 @@ mod split {
         );
 
         let solid_triangles: HashSet<Rc<Triangle>> = triangulation
-            .triangles
-            .iter()
-            .filter(|t| !t.is_ghost())
+            .solid_triangles()
             .cloned()
             .collect();
 
@@ -474,7 +654,7 @@ mod try_circumcenter_insertion {
             .unwrap(),
         );
 
-        let t1 = Rc::clone(triangulation.triangles.iter().filter(|t| !t.is_ghost()).next().unwrap());
+        let t1 = Rc::clone(triangulation.solid_triangles().next().unwrap());
 
         let result = try_circumcenter_insertion(
             &mut triangulation,
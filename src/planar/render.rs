@@ -0,0 +1,465 @@
+use crate::elements::{bounding_box::*, edge::*, polyline::*, vertex::*};
+use crate::planar::{triangulation::*, triangulator::*};
+
+use std::collections::HashSet;
+use std::fmt::Write as FmtWrite;
+use std::rc::Rc;
+
+const BACKGROUND: [u8; 3] = [255, 255, 255];
+const EDGE_COLOR: [u8; 3] = [0, 0, 0];
+const CONSTRAINT_COLOR: [u8; 3] = [200, 30, 30];
+const HOLE_COLOR: [u8; 3] = [200, 200, 200];
+
+/**
+ * Options of headless rendering. The image height follows the aspect ratio
+ * of the triangulation bounding box, so that only the width is given.
+ * Widths are given in pixels.
+ */
+#[derive(Clone, Debug)]
+pub struct RenderOptions {
+    pub width: u32,
+    pub margin: u32,
+    pub edge_width: f64,
+    /* constrained edges are drawn over the others with this width, if any */
+    pub constraint_width: Option<f64>,
+    pub shade_holes: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        return Self {
+            width: 800,
+            margin: 10,
+            edge_width: 1.0,
+            constraint_width: Some(3.0),
+            shade_holes: true,
+        };
+    }
+}
+
+/**
+ * What is drawn, in pixel coordinates: hole polygons, triangle edges and
+ * constrained edges, each sorted so that the same triangulation always
+ * renders the same image.
+ */
+struct Scene {
+    width: u32,
+    height: u32,
+    holes: Vec<Vec<[f64; 2]>>,
+    edges: Vec<[f64; 4]>,
+    constraints: Vec<[f64; 4]>,
+}
+
+impl Scene {
+    fn new(triangulator: &Triangulator, options: &RenderOptions) -> Self {
+        let constraints: Vec<Rc<Edge>> = triangulator
+            .constraint_chains()
+            .iter()
+            .map(|chain| chain.edges())
+            .flatten()
+            .collect();
+        return Self::from_parts(
+            &triangulator.triangulation.borrow(),
+            &triangulator.boundary.vertices,
+            &constraints,
+            &triangulator.holes.iter().map(|hole| &**hole).collect(),
+            options,
+        );
+    }
+
+    /**
+     * Scene of the triangulation, whose bounds include the extra vertices,
+     * with the constrained edges and hole polygons given.
+     */
+    fn from_parts(
+        triangulation: &Triangulation,
+        extra_vertices: &Vec<Rc<Vertex>>,
+        constraint_edges: &Vec<Rc<Edge>>,
+        hole_polylines: &Vec<&Polyline>,
+        options: &RenderOptions,
+    ) -> Self {
+        let vertices: Vec<Rc<Vertex>> = triangulation
+            .vertices()
+            .iter()
+            .cloned()
+            .chain(extra_vertices.iter().cloned())
+            .collect();
+
+        let margin = options.margin as f64;
+        let inner_width = (options.width as f64 - 2.0 * margin).max(1.0);
+        let (origin, extent) = match BoundingBox::from_vertices(vertices) {
+            Some(bbox) => (
+                [bbox.origin.x, bbox.destin.y],
+                [bbox.destin.x - bbox.origin.x, bbox.destin.y - bbox.origin.y],
+            ),
+            None => ([0.0, 0.0], [0.0, 0.0]),
+        };
+        let span = extent[0].max(extent[1]);
+        let scale = if extent[0] > 0.0 {
+            inner_width / extent[0]
+        } else if span > 0.0 {
+            inner_width / span
+        } else {
+            1.0
+        };
+        let height = (extent[1] * scale + 2.0 * margin).ceil().max(1.0) as u32;
+
+        /* y grows downwards in images */
+        let pixel = |vertex: &Vertex| -> [f64; 2] {
+            return [
+                margin + (vertex.x - origin[0]) * scale,
+                margin + (origin[1] - vertex.y) * scale,
+            ];
+        };
+        let segment = |v1: &Vertex, v2: &Vertex| -> [f64; 4] {
+            let (p1, p2) = (pixel(v1), pixel(v2));
+            if (p1[0], p1[1]) <= (p2[0], p2[1]) {
+                return [p1[0], p1[1], p2[0], p2[1]];
+            }
+            return [p2[0], p2[1], p1[0], p1[1]];
+        };
+
+        let mut edges: Vec<[f64; 4]> = Vec::new();
+        for triangle in triangulation.solid_triangles() {
+            edges.push(segment(&triangle.v1, &triangle.v2));
+            edges.push(segment(&triangle.v2, &triangle.v3));
+            edges.push(segment(&triangle.v3, &triangle.v1));
+        }
+        sort_segments(&mut edges);
+
+        let mut constraints: Vec<[f64; 4]> = Vec::new();
+        if options.constraint_width.is_some() {
+            for edge in constraint_edges.iter() {
+                constraints.push(segment(&edge.v1, &edge.v2));
+            }
+            sort_segments(&mut constraints);
+        }
+
+        let mut holes: Vec<Vec<[f64; 2]>> = Vec::new();
+        if options.shade_holes {
+            holes = hole_polylines
+                .iter()
+                .map(|hole| hole.vertices.iter().map(|v| pixel(v)).collect())
+                .collect();
+            holes.sort_by(|h1, h2| h1.partial_cmp(h2).unwrap());
+        }
+
+        return Self {
+            width: options.width,
+            height,
+            holes,
+            edges,
+            constraints,
+        };
+    }
+} /* end - Scene */
+
+fn sort_segments(segments: &mut Vec<[f64; 4]>) {
+    segments.sort_by(|s1, s2| s1.partial_cmp(s2).unwrap());
+    segments.dedup();
+}
+
+fn hex(color: [u8; 3]) -> String {
+    return format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2]);
+}
+
+fn svg_segments(svg: &mut String, segments: &Vec<[f64; 4]>, width: f64, color: [u8; 3]) {
+    if segments.is_empty() {
+        return;
+    }
+    let _ = write!(
+        svg,
+        "<path fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\" d=\"",
+        hex(color),
+        width
+    );
+    for s in segments.iter() {
+        let _ = write!(svg, "M{:.3} {:.3}L{:.3} {:.3}", s[0], s[1], s[2], s[3]);
+    }
+    svg.push_str("\"/>\n");
+}
+
+/**
+ * Renders the solid triangles of the triangulation to an SVG document,
+ * with holes shaded beneath them and constrained edges drawn thicker over
+ * them, as set by the options.
+ */
+pub fn svg(triangulator: &Triangulator, options: &RenderOptions) -> String {
+    return scene_svg(&Scene::new(triangulator, options), options);
+}
+
+/**
+ * Renders the solid triangles of a triangulation on its own, as svg does,
+ * drawing the given constrained edges thicker. Holes are not shaded.
+ */
+pub fn triangulation_svg(
+    triangulation: &Triangulation,
+    constraints: &HashSet<Rc<Edge>>,
+    options: &RenderOptions,
+) -> String {
+    let constraints: Vec<Rc<Edge>> = constraints.iter().cloned().collect();
+    let scene = Scene::from_parts(triangulation, &Vec::new(), &constraints, &Vec::new(), options);
+    return scene_svg(&scene, options);
+}
+
+fn scene_svg(scene: &Scene, options: &RenderOptions) -> String {
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+         viewBox=\"0 0 {0} {1}\">",
+        scene.width, scene.height
+    );
+    let _ = writeln!(
+        svg,
+        "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+        scene.width,
+        scene.height,
+        hex(BACKGROUND)
+    );
+    for hole in scene.holes.iter() {
+        let _ = write!(svg, "<path fill=\"{}\" d=\"", hex(HOLE_COLOR));
+        for (index, point) in hole.iter().enumerate() {
+            let command = if index == 0 { "M" } else { "L" };
+            let _ = write!(svg, "{}{:.3} {:.3}", command, point[0], point[1]);
+        }
+        svg.push_str("Z\"/>\n");
+    }
+    svg_segments(&mut svg, &scene.edges, options.edge_width, EDGE_COLOR);
+    if let Some(width) = options.constraint_width {
+        svg_segments(&mut svg, &scene.constraints, width, CONSTRAINT_COLOR);
+    }
+    svg.push_str("</svg>\n");
+
+    return svg;
+}
+
+/**
+ * RGB raster image, three bytes per pixel, row by row from the top.
+ */
+pub struct Raster {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Raster {
+    fn new(width: u32, height: u32, color: [u8; 3]) -> Self {
+        let pixels = color
+            .iter()
+            .cloned()
+            .cycle()
+            .take(3 * width as usize * height as usize)
+            .collect();
+        return Self {
+            width,
+            height,
+            pixels,
+        };
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        let index = 3 * (y as usize * self.width as usize + x as usize);
+        return [
+            self.pixels[index],
+            self.pixels[index + 1],
+            self.pixels[index + 2],
+        ];
+    }
+
+    fn blend(&mut self, x: i64, y: i64, color: [u8; 3], alpha: f64) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let index = 3 * (y as usize * self.width as usize + x as usize);
+        for channel in 0..3 {
+            let current = self.pixels[index + channel] as f64;
+            let blended = current + (color[channel] as f64 - current) * alpha;
+            self.pixels[index + channel] = blended.round() as u8;
+        }
+    }
+
+    /**
+     * Fills the polygon by the even-odd rule, sampling pixel centers.
+     */
+    fn fill_polygon(&mut self, polygon: &Vec<[f64; 2]>, color: [u8; 3]) {
+        for y in 0..self.height {
+            let center = y as f64 + 0.5;
+            let mut crossings: Vec<f64> = Vec::new();
+            for index in 0..polygon.len() {
+                let p1 = polygon[index];
+                let p2 = polygon[(index + 1) % polygon.len()];
+                if (p1[1] <= center) != (p2[1] <= center) {
+                    let t = (center - p1[1]) / (p2[1] - p1[1]);
+                    crossings.push(p1[0] + t * (p2[0] - p1[0]));
+                }
+            }
+            crossings.sort_by(|x1, x2| x1.partial_cmp(x2).unwrap());
+            for pair in crossings.chunks(2).filter(|pair| pair.len() == 2) {
+                let start = (pair[0] - 0.5).ceil().max(0.0) as i64;
+                let end = (pair[1] - 0.5).floor() as i64;
+                for x in start..=end {
+                    self.blend(x, y as i64, color, 1.0);
+                }
+            }
+        }
+    }
+
+    /**
+     * Strokes the segment with round caps, antialiased by the distance of
+     * pixel centers to it.
+     */
+    fn stroke_segment(&mut self, segment: &[f64; 4], width: f64, color: [u8; 3]) {
+        let radius = width / 2.0;
+        let (x1, y1, x2, y2) = (segment[0], segment[1], segment[2], segment[3]);
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length_sq = dx * dx + dy * dy;
+
+        let x_min = (x1.min(x2) - radius - 1.0).floor() as i64;
+        let x_max = (x1.max(x2) + radius + 1.0).ceil() as i64;
+        let y_min = (y1.min(y2) - radius - 1.0).floor() as i64;
+        let y_max = (y1.max(y2) + radius + 1.0).ceil() as i64;
+        for y in y_min.max(0)..=y_max.min(self.height as i64 - 1) {
+            for x in x_min.max(0)..=x_max.min(self.width as i64 - 1) {
+                let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+                let t = if length_sq > 0.0 {
+                    (((px - x1) * dx + (py - y1) * dy) / length_sq)
+                        .max(0.0)
+                        .min(1.0)
+                } else {
+                    0.0
+                };
+                let distance = (px - x1 - t * dx).hypot(py - y1 - t * dy);
+                let alpha = (radius + 0.5 - distance).max(0.0).min(1.0);
+                if alpha > 0.0 {
+                    self.blend(x, y, color, alpha);
+                }
+            }
+        }
+    }
+} /* end - Raster */
+
+/**
+ * Rasterizes the same picture as `svg`, without any graphics backend.
+ */
+pub fn raster(triangulator: &Triangulator, options: &RenderOptions) -> Raster {
+    let scene = Scene::new(triangulator, options);
+    let mut raster = Raster::new(scene.width, scene.height, BACKGROUND);
+
+    for hole in scene.holes.iter() {
+        raster.fill_polygon(hole, HOLE_COLOR);
+    }
+    for edge in scene.edges.iter() {
+        raster.stroke_segment(edge, options.edge_width, EDGE_COLOR);
+    }
+    if let Some(width) = options.constraint_width {
+        for constraint in scene.constraints.iter() {
+            raster.stroke_segment(constraint, width, CONSTRAINT_COLOR);
+        }
+    }
+
+    return raster;
+}
+
+/**
+ * Encodes the rasterized triangulation as PNG.
+ */
+#[cfg(feature = "raster")]
+pub fn png(
+    triangulator: &Triangulator,
+    options: &RenderOptions,
+    writer: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    let raster = raster(triangulator, options);
+    let mut encoder = png::Encoder::new(writer, raster.width, raster.height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut png_writer = encoder.write_header()?;
+    png_writer.write_image_data(&raster.pixels)?;
+    return Ok(());
+}
+
+#[cfg(test)]
+mod render {
+    use super::*;
+
+    fn square_with_hole() -> Triangulator {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 2.0)),
+                Rc::new(Vertex::new(0.0, 2.0)),
+            ])
+            .unwrap(),
+        );
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(1.0, 0.5)),
+                Rc::new(Vertex::new(2.0, 0.5)),
+                Rc::new(Vertex::new(2.0, 1.5)),
+                Rc::new(Vertex::new(1.0, 1.5)),
+            ])
+            .unwrap(),
+        );
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_hole(&hole).unwrap();
+        triangulator.try_triangulate().unwrap();
+        return triangulator;
+    }
+
+    #[test]
+    fn svg_document() {
+        let triangulator = square_with_hole();
+        let options = RenderOptions {
+            width: 420,
+            ..RenderOptions::default()
+        };
+        let document = svg(&triangulator, &options);
+
+        /* 2:1 aspect ratio inside the 10 pixels margin */
+        assert!(document
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"420\" height=\"220\""));
+        assert!(document.ends_with("</svg>\n"));
+        assert!(document.contains("<path fill=\"#c8c8c8\" d=\"M"));
+        assert!(document.contains("stroke-width=\"3\""));
+        /* y is flipped: the boundary corner (0, 0) is at the bottom left */
+        assert!(document.contains("M10.000 210.000L"));
+
+        /* sorted, whatever the hash set iteration order */
+        assert_eq!(document, svg(&triangulator, &options));
+    }
+
+    #[test]
+    fn svg_without_constraints_and_holes() {
+        let triangulator = square_with_hole();
+        let options = RenderOptions {
+            constraint_width: None,
+            shade_holes: false,
+            ..RenderOptions::default()
+        };
+        let document = svg(&triangulator, &options);
+
+        assert!(!document.contains("#c8c8c8"));
+        assert!(!document.contains("#c81e1e"));
+        assert_eq!(document.matches("<path").count(), 1);
+    }
+
+    #[test]
+    fn raster_image() {
+        let triangulator = square_with_hole();
+        let options = RenderOptions {
+            width: 420,
+            ..RenderOptions::default()
+        };
+        let image = raster(&triangulator, &options);
+
+        assert_eq!((image.width, image.height), (420, 220));
+        assert_eq!(image.pixels.len(), 3 * 420 * 220);
+        /* margin, hole center and a constrained boundary corner */
+        assert_eq!(image.pixel(2, 2), BACKGROUND);
+        assert_eq!(image.pixel(160, 110), HOLE_COLOR);
+        assert_eq!(image.pixel(10, 210), CONSTRAINT_COLOR);
+    }
+} /* end - render tests */
@@ -0,0 +1,126 @@
+use crate::elements::{polyline::*, triangle::*, vertex::*};
+use crate::planar::triangulation::*;
+use crate::properties::distance::*;
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/**
+ * Alpha shape of the triangulation: the region of the solid triangles whose
+ * circumradius does not exceed alpha, outlined by its border loops. Outer
+ * loops are counterclockwise, and loops around the gaps they enclose are
+ * clockwise. Vertices shared by two loops are kept in both of them.
+ */
+pub fn alpha_shape(triangulation: &Triangulation, alpha: f64) -> Vec<Polyline> {
+    let kept: HashSet<Rc<Triangle>> = triangulation
+        .solid_triangles()
+        .filter(|t| match t.circumcenter() {
+            Some(center) => distance(&center, &t.v1) <= alpha,
+            None => false,
+        })
+        .cloned()
+        .collect();
+
+    /* border edges, leaving the kept region at their right */
+    let mut border: HashMap<Rc<Vertex>, Vec<Rc<Vertex>>> = HashMap::new();
+    for triangle in kept.iter() {
+        let (e1, e2, e3) = triangle.inner_edges();
+        for edge in vec![e1, e2, e3] {
+            let is_border = match triangulation.adjacency.get(&edge.opposite()) {
+                Some(neighbor) => !kept.contains(neighbor),
+                None => true,
+            };
+            if is_border {
+                border
+                    .entry(Rc::clone(&edge.v1))
+                    .or_insert(Vec::new())
+                    .push(Rc::clone(&edge.v2));
+            }
+        }
+    }
+
+    let mut starts: Vec<Rc<Vertex>> = border.keys().cloned().collect();
+    starts.sort_by(|v1, v2| {
+        return v1
+            .x
+            .partial_cmp(&v2.x)
+            .unwrap()
+            .then(v1.y.partial_cmp(&v2.y).unwrap());
+    });
+
+    let mut loops: Vec<Polyline> = Vec::new();
+    for start in starts.iter() {
+        while let Some(mut current) = border.get_mut(start).and_then(|next| next.pop()) {
+            let mut vertices: Vec<Rc<Vertex>> = vec![Rc::clone(start)];
+            while current != *start {
+                vertices.push(Rc::clone(&current));
+                current = match border.get_mut(&current).and_then(|next| next.pop()) {
+                    Some(next) => next,
+                    None => break,
+                };
+            }
+            if let Some(polyline) = Polyline::new_closed(vertices) {
+                loops.push(polyline);
+            }
+        }
+    }
+
+    return loops;
+}
+
+#[cfg(test)]
+mod alpha_shape {
+    use super::*;
+    use crate::elements::bounding_box::*;
+    use crate::planar::structured;
+
+    fn signed_area(polyline: &Polyline) -> f64 {
+        return polyline
+            .into_edges()
+            .iter()
+            .map(|e| e.v1.x * e.v2.y - e.v2.x * e.v1.y)
+            .sum::<f64>()
+            / 2.0;
+    }
+
+    #[test]
+    fn drops_large_triangles() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0, 1.0));
+        let v4 = Rc::new(Vertex::new(0.0, 1.0));
+        let v5 = Rc::new(Vertex::new(3.0, 0.5));
+
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v1, &v2, &v3)));
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v1, &v3, &v4)));
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v2, &v5, &v3)));
+
+        let shape = alpha_shape(&triangulation, 0.8);
+        assert_eq!(shape.len(), 1);
+        assert_eq!(shape[0].vertices, vec![v1, v2, v3, v4]);
+        assert!((signed_area(&shape[0]) - 1.0).abs() < 1.0E-12);
+
+        let shape = alpha_shape(&triangulation, 2.0);
+        assert_eq!(shape.len(), 1);
+        assert_eq!(shape[0].vertices.len(), 5);
+
+        assert!(alpha_shape(&triangulation, 0.5).is_empty());
+    }
+
+    #[test]
+    fn gaps() {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(4.0, 4.0)),
+        ])
+        .unwrap();
+        let mut triangulation = structured::rectangle(&bounding_box, 4, 4).unwrap();
+        triangulation.remove_vertex(&Rc::new(Vertex::new(2.0, 2.0)));
+
+        let shape = alpha_shape(&triangulation, 0.75);
+        assert_eq!(shape.len(), 2);
+        assert!((signed_area(&shape[0]) - 16.0).abs() < 1.0E-12);
+        assert!(signed_area(&shape[1]) < 0.0);
+    }
+} /* end - alpha_shape tests */
@@ -0,0 +1,352 @@
+use crate::elements::{edge::*, polyline::vertex_pairs, triangle::*, vertex::*};
+use crate::planar::triangulation::*;
+use crate::properties::orientation::*;
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/**
+ * Lloyd relaxation. Moves every free vertex to the centroid of its Voronoi
+ * cell, the polygon of the circumcenters of the triangles around it.
+ * Vertices on the hull, ending constrained edges or in fixed_vertices are
+ * kept. Delaunay property is restored by edge flips after each iteration.
+ */
+pub fn lloyd(
+    triangulation: &mut Triangulation,
+    fixed_vertices: &HashSet<Rc<Vertex>>,
+    iterations: usize,
+) {
+    for _ in 0..iterations {
+        relax(triangulation, fixed_vertices, &cell_centroid);
+        let region: Vec<Rc<Triangle>> = triangulation.triangles.iter().cloned().collect();
+        triangulation.restore_delaunay(&region);
+    }
+}
+
+/**
+ * Laplacian smoothing. Moves every free vertex to the average of its
+ * neighbours. Vertices on the hull, ending constrained edges or in
+ * fixed_vertices are kept. Delaunay property is restored by edge flips
+ * after each iteration.
+ */
+pub fn laplacian(
+    triangulation: &mut Triangulation,
+    fixed_vertices: &HashSet<Rc<Vertex>>,
+    iterations: usize,
+) {
+    for _ in 0..iterations {
+        relax(triangulation, fixed_vertices, &neighbours_average);
+        let region: Vec<Rc<Triangle>> = triangulation.triangles.iter().cloned().collect();
+        triangulation.restore_delaunay(&region);
+    }
+}
+
+/**
+ * Optimal Delaunay Triangulation smoothing. Moves every free vertex to the
+ * area weighted average of the circumcenters of the triangles around it,
+ * which minimizes the interpolation error of quadratic functions. Vertices
+ * on the hull, ending constrained edges or in fixed_vertices are kept.
+ * Delaunay property is restored by edge flips after each iteration.
+ */
+pub fn odt(
+    triangulation: &mut Triangulation,
+    fixed_vertices: &HashSet<Rc<Vertex>>,
+    iterations: usize,
+) {
+    for _ in 0..iterations {
+        relax(triangulation, fixed_vertices, &weighted_circumcenter);
+        let region: Vec<Rc<Triangle>> = triangulation.triangles.iter().cloned().collect();
+        triangulation.restore_delaunay(&region);
+    }
+}
+
+/**
+ * Moves free vertices, one at a time, to the position given by target.
+ * A move is rejected if it inverts any triangle of the vertex star.
+ */
+fn relax(
+    triangulation: &mut Triangulation,
+    fixed_vertices: &HashSet<Rc<Vertex>>,
+    target: &dyn Fn(&Rc<Vertex>, &Vec<Rc<Triangle>>) -> Option<Vertex>,
+) {
+    let mut stars: HashMap<Rc<Vertex>, Vec<Rc<Triangle>>> = HashMap::new();
+    for triangle in triangulation.triangles.iter() {
+        for vertex in vec![&triangle.v1, &triangle.v2, &triangle.v3] {
+            stars
+                .entry(Rc::clone(vertex))
+                .or_insert(Vec::new())
+                .push(Rc::clone(triangle));
+        }
+    }
+
+    let free_vertices: Vec<Rc<Vertex>> = stars
+        .iter()
+        .filter(|(vertex, star)| {
+            let is_hull = star.iter().any(|t| t.is_ghost());
+            let is_constrained = star.iter().any(|t| {
+                return vec![&t.v1, &t.v2, &t.v3]
+                    .into_iter()
+                    .any(|v| triangulation.is_constrained(&Edge::new(vertex, v)));
+            });
+            return !vertex.is_ghost && !is_hull && !is_constrained && !fixed_vertices.contains(*vertex);
+        })
+        .map(|(vertex, _)| Rc::clone(vertex))
+        .collect();
+
+    for vertex in free_vertices.iter() {
+        let star = stars.get(vertex).unwrap().clone();
+        let new_vertex = match target(vertex, &star) {
+            Some(new_vertex) => Rc::new(new_vertex),
+            None => continue,
+        };
+
+        let new_star: Vec<Rc<Triangle>> = star
+            .iter()
+            .map(|t| Rc::new(replace_vertex(t, vertex, &new_vertex)))
+            .collect();
+
+        let keeps_orientation = new_star.iter().all(|t| {
+            orientation(&t.v1, &t.v2, &t.v3) == Orientation::Counterclockwise
+        });
+        if !keeps_orientation {
+            continue;
+        }
+
+        for (old_triangle, new_triangle) in star.iter().zip(new_star.iter()) {
+            triangulation.remove_triangle(old_triangle);
+            triangulation.include_triangle(new_triangle);
+
+            for neighbour in vec![&old_triangle.v1, &old_triangle.v2, &old_triangle.v3] {
+                if neighbour == vertex {
+                    continue;
+                }
+                if let Some(neighbour_star) = stars.get_mut(neighbour) {
+                    for t in neighbour_star.iter_mut().filter(|t| t == &old_triangle) {
+                        *t = Rc::clone(new_triangle);
+                    }
+                }
+            }
+        }
+        stars.remove(vertex);
+        stars.insert(new_vertex, new_star);
+    }
+}
+
+/**
+ * Replaces old_vertex by new_vertex, keeping the vertices order.
+ */
+fn replace_vertex(triangle: &Triangle, old_vertex: &Rc<Vertex>, new_vertex: &Rc<Vertex>) -> Triangle {
+    let pick = |v: &Rc<Vertex>| {
+        if v == old_vertex {
+            return Rc::clone(new_vertex);
+        }
+        return Rc::clone(v);
+    };
+    return Triangle::new(&pick(&triangle.v1), &pick(&triangle.v2), &pick(&triangle.v3));
+}
+
+/**
+ * Average of the vertices around the vertex.
+ */
+fn neighbours_average(vertex: &Rc<Vertex>, star: &Vec<Rc<Triangle>>) -> Option<Vertex> {
+    let neighbours: HashSet<Rc<Vertex>> = star
+        .iter()
+        .map(|t| vec![Rc::clone(&t.v1), Rc::clone(&t.v2), Rc::clone(&t.v3)])
+        .flatten()
+        .filter(|v| v != vertex)
+        .collect();
+
+    if neighbours.is_empty() {
+        return None;
+    }
+
+    let count = neighbours.len() as f64;
+    let x = neighbours.iter().map(|v| v.x).sum::<f64>() / count;
+    let y = neighbours.iter().map(|v| v.y).sum::<f64>() / count;
+
+    return Some(Vertex::new(x, y));
+}
+
+/**
+ * Centroid of the Voronoi cell of the vertex, whose corners are the
+ * circumcenters of the star triangles, taken around the vertex.
+ */
+fn cell_centroid(vertex: &Rc<Vertex>, star: &Vec<Rc<Triangle>>) -> Option<Vertex> {
+    let mut ordered_star: Vec<&Rc<Triangle>> = star.iter().collect();
+    ordered_star.sort_by(|t1, t2| {
+        let c1 = t1.center();
+        let c2 = t2.center();
+        let a1 = (c1.y - vertex.y).atan2(c1.x - vertex.x);
+        let a2 = (c2.y - vertex.y).atan2(c2.x - vertex.x);
+        return a1.partial_cmp(&a2).unwrap();
+    });
+
+    let mut corners: Vec<Rc<Vertex>> = Vec::new();
+    for triangle in ordered_star {
+        corners.push(Rc::new(triangle.circumcenter()?));
+    }
+
+    let pairs: Vec<(Rc<Vertex>, Rc<Vertex>)> = vertex_pairs(&corners, false);
+    let mut signed_area: f64 = 0.0;
+    let mut x: f64 = 0.0;
+    let mut y: f64 = 0.0;
+    for (v1, v2) in pairs.iter() {
+        let cross = v1.x * v2.y - v2.x * v1.y;
+        signed_area += cross / 2.0;
+        x += (v1.x + v2.x) * cross;
+        y += (v1.y + v2.y) * cross;
+    }
+
+    if signed_area.abs() < 1.0E-14 {
+        return None;
+    }
+
+    return Some(Vertex::new(x / (6.0 * signed_area), y / (6.0 * signed_area)));
+}
+
+/**
+ * Average of the circumcenters of the star triangles, weighted by their area.
+ */
+fn weighted_circumcenter(_vertex: &Rc<Vertex>, star: &Vec<Rc<Triangle>>) -> Option<Vertex> {
+    let mut total_area: f64 = 0.0;
+    let mut x: f64 = 0.0;
+    let mut y: f64 = 0.0;
+    for triangle in star.iter() {
+        let area = triangle.area()?.abs();
+        let circumcenter = triangle.circumcenter()?;
+        total_area += area;
+        x += area * circumcenter.x;
+        y += area * circumcenter.y;
+    }
+
+    if total_area < 1.0E-14 {
+        return None;
+    }
+
+    return Some(Vertex::new(x / total_area, y / total_area));
+}
+
+#[cfg(test)]
+mod smoothing {
+    use super::*;
+    use crate::elements::polyline::Polyline;
+    use crate::planar::triangulation_procedures;
+
+    /**
+     * Square boundary with a single inner vertex, off its center
+     */
+    fn sample_triangulation() -> Triangulation {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(2.0, 2.0));
+        let v4 = Rc::new(Vertex::new(0.0, 2.0));
+        let v5 = Rc::new(Vertex::new(0.5, 0.6));
+
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v1),
+                Rc::clone(&v2),
+                Rc::clone(&v3),
+                Rc::clone(&v4),
+            ])
+            .unwrap(),
+        );
+
+        let mut triangulation = Triangulation::from_initial_segment((&v1, &v2));
+        triangulation_procedures::boundary::include(&mut triangulation, &boundary, &HashSet::new());
+        let segment_constraints: HashSet<Rc<Edge>> = boundary.into_edges().iter().cloned().collect();
+        triangulation_procedures::vertices::include(
+            &mut triangulation,
+            vec![v5],
+            &segment_constraints,
+            &Some(boundary),
+            &HashSet::new(),
+        );
+        for segment in segment_constraints.iter() {
+            triangulation.constrain(segment);
+        }
+
+        return triangulation;
+    }
+
+    #[test]
+    fn laplacian_centers_inner_vertex() {
+        let mut triangulation = sample_triangulation();
+        laplacian(&mut triangulation, &HashSet::new(), 10);
+
+        let vertices = triangulation.vertices();
+        assert_eq!(vertices.len(), 5);
+        assert!(vertices.contains(&Rc::new(Vertex::new(0.0, 0.0))));
+        assert!(vertices.contains(&Rc::new(Vertex::new(2.0, 2.0))));
+
+        let inner = vertices
+            .iter()
+            .find(|v| v.x > 0.0 && v.x < 2.0 && v.y > 0.0 && v.y < 2.0)
+            .unwrap();
+        assert!((inner.x - 1.0).abs() < 1.0e-3);
+        assert!((inner.y - 1.0).abs() < 1.0e-3);
+    }
+
+    #[test]
+    fn lloyd_centers_inner_vertex() {
+        let mut triangulation = sample_triangulation();
+        lloyd(&mut triangulation, &HashSet::new(), 20);
+
+        let vertices = triangulation.vertices();
+        let inner = vertices
+            .iter()
+            .find(|v| v.x > 0.0 && v.x < 2.0 && v.y > 0.0 && v.y < 2.0)
+            .unwrap();
+        assert!((inner.x - 1.0).abs() < 1.0e-2);
+        assert!((inner.y - 1.0).abs() < 1.0e-2);
+
+        let area: f64 = triangulation
+            .solid_triangles()
+            .map(|t| t.area().unwrap())
+            .sum();
+        assert!((area - 4.0).abs() < 1.0e-10);
+    }
+
+    /**
+     * Interpolation error of |x|^2 over the solid triangles, which is
+     * |T| / 12 times the sum of the squared edges lengths of each triangle.
+     */
+    fn interpolation_error(triangulation: &Triangulation) -> f64 {
+        return triangulation
+            .solid_triangles()
+            .map(|t| {
+                let (e1, e2, e3) = t.inner_edges();
+                let squares = e1.length().powi(2) + e2.length().powi(2) + e3.length().powi(2);
+                return t.area().unwrap().abs() * squares / 12.0;
+            })
+            .sum();
+    }
+
+    #[test]
+    fn odt_reduces_interpolation_error() {
+        let mut triangulation = sample_triangulation();
+        let error_before = interpolation_error(&triangulation);
+
+        odt(&mut triangulation, &HashSet::new(), 10);
+        let error_after = interpolation_error(&triangulation);
+        assert!(error_after < error_before);
+
+        let inner = triangulation
+            .vertices()
+            .into_iter()
+            .find(|v| v.x > 0.0 && v.x < 2.0 && v.y > 0.0 && v.y < 2.0)
+            .unwrap();
+        assert!((inner.x - 1.0).abs() < 1.0e-2);
+        assert!((inner.y - 1.0).abs() < 1.0e-2);
+    }
+
+    #[test]
+    fn keeps_fixed_vertices() {
+        let mut triangulation = sample_triangulation();
+        let v5 = Rc::new(Vertex::new(0.5, 0.6));
+        let fixed_vertices: HashSet<Rc<Vertex>> = vec![Rc::clone(&v5)].into_iter().collect();
+
+        laplacian(&mut triangulation, &fixed_vertices, 5);
+        assert!(triangulation.vertices().contains(&v5));
+    }
+} /* end - smoothing tests */
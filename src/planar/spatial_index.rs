@@ -0,0 +1,269 @@
+use crate::elements::{edge::*, triangle::*, vertex::*};
+use crate::planar::triangulation::*;
+use crate::properties::{continence::*, distance::*};
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/**
+ * Uniform grid over the solid triangles of a triangulation. Each cell lists
+ * the triangles whose bounding box overlaps it, so that point queries only
+ * inspect the triangles of a single cell. The index is a snapshot: it is not
+ * updated as the triangulation changes.
+ */
+pub struct SpatialIndex {
+    pub cell_size: f64,
+    cells: HashMap<(i64, i64), Vec<Rc<Triangle>>>,
+    extent: Option<((i64, i64), (i64, i64))>,
+}
+
+impl SpatialIndex {
+    /**
+     * Builds the index with cells about the size of the average triangle.
+     */
+    pub fn new(triangulation: &Triangulation) -> Self {
+        let solid_triangles: Vec<&Rc<Triangle>> =
+            triangulation.solid_triangles().collect();
+
+        let area: f64 = solid_triangles
+            .iter()
+            .map(|t| t.area().unwrap().abs())
+            .sum();
+        let mut cell_size = (area / solid_triangles.len().max(1) as f64).sqrt();
+        if !(cell_size > 0.0) {
+            cell_size = 1.0;
+        }
+
+        return Self::with_cell_size(triangulation, cell_size);
+    }
+
+    pub fn with_cell_size(triangulation: &Triangulation, cell_size: f64) -> Self {
+        let mut index = Self {
+            cell_size,
+            cells: HashMap::new(),
+            extent: None,
+        };
+
+        for triangle in triangulation.solid_triangles() {
+            let vertices = vec![&triangle.v1, &triangle.v2, &triangle.v3];
+            let (min_i, min_j) = index.cell(&Vertex::new(
+                vertices.iter().map(|v| v.x).fold(std::f64::INFINITY, f64::min),
+                vertices.iter().map(|v| v.y).fold(std::f64::INFINITY, f64::min),
+            ));
+            let (max_i, max_j) = index.cell(&Vertex::new(
+                vertices.iter().map(|v| v.x).fold(std::f64::NEG_INFINITY, f64::max),
+                vertices.iter().map(|v| v.y).fold(std::f64::NEG_INFINITY, f64::max),
+            ));
+
+            index.extent = match index.extent {
+                Some(((lower_i, lower_j), (upper_i, upper_j))) => Some((
+                    (lower_i.min(min_i), lower_j.min(min_j)),
+                    (upper_i.max(max_i), upper_j.max(max_j)),
+                )),
+                None => Some(((min_i, min_j), (max_i, max_j))),
+            };
+
+            for i in min_i..=max_i {
+                for j in min_j..=max_j {
+                    index
+                        .cells
+                        .entry((i, j))
+                        .or_insert(Vec::new())
+                        .push(Rc::clone(triangle));
+                }
+            }
+        }
+
+        return index;
+    }
+
+    /**
+     * Triangles whose bounding box overlaps the cell of the vertex.
+     */
+    pub fn candidates(&self, vertex: &Vertex) -> &[Rc<Triangle>] {
+        match self.cells.get(&self.cell(vertex)) {
+            Some(triangles) => return triangles,
+            None => return &[],
+        }
+    }
+
+    /**
+     * Solid triangle containing the vertex, inside or on its edges.
+     */
+    pub fn locate(&self, vertex: &Vertex) -> Option<Rc<Triangle>> {
+        return self
+            .candidates(vertex)
+            .iter()
+            .find(|t| t.contains(vertex) != Continence::Outside)
+            .cloned();
+    }
+
+    /**
+     * Solid triangle vertex closest to the vertex, searching rings of cells
+     * around the cell of the vertex, until no closer vertex may lie in the
+     * outer rings. Returns None if no triangle is indexed.
+     */
+    pub fn nearest_vertex(&self, vertex: &Vertex) -> Option<Rc<Vertex>> {
+        let ((lower_i, lower_j), (upper_i, upper_j)) = self.extent?;
+        let (i, j) = self.cell(vertex);
+        let max_ring = (i - lower_i)
+            .abs()
+            .max((upper_i - i).abs())
+            .max((j - lower_j).abs())
+            .max((upper_j - j).abs());
+
+        let mut nearest: Option<(f64, Rc<Vertex>)> = None;
+        for ring in 0..=max_ring {
+            for di in -ring..=ring {
+                for dj in -ring..=ring {
+                    if di.abs() != ring && dj.abs() != ring {
+                        continue;
+                    }
+                    let triangles = match self.cells.get(&(i + di, j + dj)) {
+                        Some(triangles) => triangles,
+                        None => continue,
+                    };
+                    for triangle in triangles.iter() {
+                        for candidate in vec![&triangle.v1, &triangle.v2, &triangle.v3] {
+                            let candidate_distance = distance(candidate, vertex);
+                            let is_closer = match &nearest {
+                                Some((nearest_distance, _)) => {
+                                    candidate_distance < *nearest_distance
+                                }
+                                None => true,
+                            };
+                            if is_closer {
+                                nearest = Some((candidate_distance, Rc::clone(candidate)));
+                            }
+                        }
+                    }
+                }
+            }
+
+            /* vertices beyond this ring are at least ring cells away */
+            if let Some((nearest_distance, _)) = &nearest {
+                if *nearest_distance <= ring as f64 * self.cell_size {
+                    break;
+                }
+            }
+        }
+
+        return nearest.map(|(_, vertex)| vertex);
+    }
+
+    /**
+     * Local feature size at the vertex, collecting the constrained edges of
+     * the triangles in rings of cells around the cell of the vertex, until
+     * no closer feature may lie in the outer rings.
+     */
+    pub fn local_feature_size(
+        &self,
+        vertex: &Vertex,
+        segment_constraints: &HashSet<Rc<Edge>>,
+    ) -> Option<f64> {
+        let ((lower_i, lower_j), (upper_i, upper_j)) = self.extent?;
+        let (i, j) = self.cell(vertex);
+        let max_ring = (i - lower_i)
+            .abs()
+            .max((upper_i - i).abs())
+            .max((j - lower_j).abs())
+            .max((upper_j - j).abs());
+
+        let mut segments: Vec<Rc<Edge>> = Vec::new();
+        let mut size: Option<f64> = None;
+        for ring in 0..=max_ring {
+            let found = segments.len();
+            for di in -ring..=ring {
+                for dj in -ring..=ring {
+                    if di.abs() != ring && dj.abs() != ring {
+                        continue;
+                    }
+                    let triangles = match self.cells.get(&(i + di, j + dj)) {
+                        Some(triangles) => triangles,
+                        None => continue,
+                    };
+                    for triangle in triangles.iter() {
+                        let (e1, e2, e3) = triangle.inner_edges();
+                        for edge in vec![e1, e2, e3] {
+                            let opposite = edge.opposite();
+                            let is_constrained = segment_constraints.contains(&edge)
+                                || segment_constraints.contains(&opposite);
+                            let is_found = segments.iter().any(|s| *s == edge || **s == opposite);
+                            if is_constrained && !is_found {
+                                segments.push(edge);
+                            }
+                        }
+                    }
+                }
+            }
+
+            if segments.len() > found {
+                size = feature_size(vertex, &segments);
+            }
+
+            /* features beyond this ring are at least ring cells away */
+            if let Some(size) = size {
+                if size <= ring as f64 * self.cell_size {
+                    break;
+                }
+            }
+        }
+
+        return size;
+    }
+
+    fn cell(&self, vertex: &Vertex) -> (i64, i64) {
+        return (
+            (vertex.x / self.cell_size).floor() as i64,
+            (vertex.y / self.cell_size).floor() as i64,
+        );
+    }
+} /* end - SpatialIndex */
+
+#[cfg(test)]
+mod spatial_index {
+    use super::*;
+
+    #[test]
+    fn locate() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(2.0, 2.0));
+        let v4 = Rc::new(Vertex::new(0.0, 2.0));
+        let t1 = Rc::new(Triangle::new(&v1, &v2, &v3));
+        let t2 = Rc::new(Triangle::new(&v1, &v3, &v4));
+
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&t1);
+        triangulation.include_triangle(&t2);
+
+        let index = SpatialIndex::with_cell_size(&triangulation, 0.5);
+        assert_eq!(index.locate(&Vertex::new(1.5, 0.5)), Some(Rc::clone(&t1)));
+        assert_eq!(index.locate(&Vertex::new(0.5, 1.5)), Some(Rc::clone(&t2)));
+        assert_eq!(index.locate(&Vertex::new(3.0, 1.0)), None);
+
+        let index = SpatialIndex::new(&triangulation);
+        assert_eq!(index.cell_size, 2.0_f64.sqrt());
+        assert_eq!(index.locate(&Vertex::new(1.5, 0.5)), Some(t1));
+    }
+
+    #[test]
+    fn nearest_vertex() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(4.0, 0.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 1.0));
+
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v1, &v2, &v4)));
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v2, &v3, &v4)));
+
+        let index = SpatialIndex::with_cell_size(&triangulation, 0.5);
+        assert_eq!(index.nearest_vertex(&Vertex::new(1.2, 0.9)), Some(Rc::clone(&v4)));
+        assert_eq!(index.nearest_vertex(&Vertex::new(3.0, 3.5)), Some(Rc::clone(&v3)));
+        assert_eq!(index.nearest_vertex(&Vertex::new(-9.0, -1.0)), Some(v1));
+
+        let empty = SpatialIndex::new(&Triangulation::new());
+        assert_eq!(empty.nearest_vertex(&Vertex::new(0.0, 0.0)), None);
+    }
+} /* end - spatial_index tests */
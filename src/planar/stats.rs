@@ -0,0 +1,115 @@
+#[cfg(feature = "stats")]
+use std::cell::Cell;
+use std::fmt;
+
+/**
+ * Counters of the core operations: triangles created, orientation tests
+ * and incircle tests. Counters are kept per thread, and only with the
+ * `stats` feature, as counting slows the predicates down. Without it,
+ * every counter stays zero.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub triangles_created: u64,
+    pub orientation_tests: u64,
+    pub incircle_tests: u64,
+}
+
+#[cfg(feature = "stats")]
+thread_local! {
+    static COUNTERS: Cell<Stats> = Cell::new(Stats::default());
+}
+
+impl Stats {
+    /**
+     * Counters of the current thread since the last reset.
+     */
+    pub fn current() -> Self {
+        #[cfg(feature = "stats")]
+        return COUNTERS.with(|counters| counters.get());
+        #[cfg(not(feature = "stats"))]
+        return Self::default();
+    }
+
+    pub fn reset() {
+        #[cfg(feature = "stats")]
+        COUNTERS.with(|counters| counters.set(Self::default()));
+    }
+
+    /**
+     * Counters of the current thread since the former snapshot.
+     */
+    pub fn since(former: &Self) -> Self {
+        let current = Self::current();
+        return Self {
+            triangles_created: current.triangles_created - former.triangles_created,
+            orientation_tests: current.orientation_tests - former.orientation_tests,
+            incircle_tests: current.incircle_tests - former.incircle_tests,
+        };
+    }
+} /* end - Stats */
+
+impl fmt::Display for Stats {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(formatter, "triangles created: {}", self.triangles_created)?;
+        writeln!(formatter, "orientation tests: {}", self.orientation_tests)?;
+        return writeln!(formatter, "incircle tests: {}", self.incircle_tests);
+    }
+}
+
+#[cfg(feature = "stats")]
+fn count(update: fn(&mut Stats)) {
+    COUNTERS.with(|counters| {
+        let mut stats = counters.get();
+        update(&mut stats);
+        counters.set(stats);
+    });
+}
+
+#[cfg(not(feature = "stats"))]
+fn count(_update: fn(&mut Stats)) {}
+
+pub(crate) fn count_triangle() {
+    count(|stats| stats.triangles_created += 1);
+}
+
+pub(crate) fn count_orientation() {
+    count(|stats| stats.orientation_tests += 1);
+}
+
+pub(crate) fn count_incircle() {
+    count(|stats| stats.incircle_tests += 1);
+}
+
+#[cfg(test)]
+mod stats {
+    use super::*;
+    use crate::elements::{triangle::*, vertex::*};
+    use std::rc::Rc;
+
+    #[test]
+    fn counts() {
+        let former = Stats::current();
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+        let triangle = Triangle::new(&v1, &v2, &v3);
+        triangle.encircles(&Vertex::new(0.5, 0.5));
+        triangle.contains(&Vertex::new(0.2, 0.2));
+
+        let stats = Stats::since(&former);
+        if cfg!(feature = "stats") {
+            assert_eq!(stats.triangles_created, 1);
+            assert_eq!(stats.incircle_tests, 1);
+            assert_eq!(stats.orientation_tests, 3);
+        } else {
+            assert_eq!(stats, Stats::default());
+        }
+
+        let display = format!("{}", Stats::default());
+        assert_eq!(
+            display,
+            "triangles created: 0\norientation tests: 0\nincircle tests: 0\n"
+        );
+    }
+} /* end - stats tests */
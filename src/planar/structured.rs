@@ -0,0 +1,216 @@
+use crate::elements::{bounding_box::*, edge::*, triangle::*, vertex::*};
+use crate::planar::triangulation::*;
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+/**
+ * Structured triangulation of the rectangle, with nx columns and ny rows of
+ * cells, each one split by its lower left to upper right diagonal. Ghost
+ * triangles surround the rectangle, as in Delaunay triangulations.
+ * Returns None if there are no cells or the rectangle is degenerate.
+ */
+pub fn rectangle(bounding_box: &BoundingBox, nx: usize, ny: usize) -> Option<Triangulation> {
+    let width = bounding_box.destin.x - bounding_box.origin.x;
+    let height = bounding_box.destin.y - bounding_box.origin.y;
+    if nx == 0 || ny == 0 || !(width > 0.0) || !(height > 0.0) {
+        return None;
+    }
+
+    let grid: Vec<Vec<Rc<Vertex>>> = (0..=nx)
+        .map(|i| {
+            return (0..=ny)
+                .map(|j| {
+                    Rc::new(Vertex::new(
+                        bounding_box.origin.x + width * i as f64 / nx as f64,
+                        bounding_box.origin.y + height * j as f64 / ny as f64,
+                    ))
+                })
+                .collect();
+        })
+        .collect();
+
+    let mut triangulation = Triangulation::new();
+    for i in 0..nx {
+        for j in 0..ny {
+            let v1 = &grid[i][j];
+            let v2 = &grid[i + 1][j];
+            let v3 = &grid[i + 1][j + 1];
+            let v4 = &grid[i][j + 1];
+            triangulation.include_triangle(&Rc::new(Triangle::new(v1, v2, v3)));
+            triangulation.include_triangle(&Rc::new(Triangle::new(v1, v3, v4)));
+        }
+    }
+
+    include_ghosts(&mut triangulation);
+    return Some(triangulation);
+}
+
+/**
+ * Structured triangulation of the annulus around center, with n_radial
+ * layers between the radii and n_angular cells around each layer. Ghost
+ * triangles surround both the outer and the inner circles, as they do
+ * with holes. Returns None for less than 3 angular cells, no radial layers
+ * or radii not satisfying 0 < inner_radius < outer_radius.
+ */
+pub fn annulus(
+    center: &Vertex,
+    inner_radius: f64,
+    outer_radius: f64,
+    n_radial: usize,
+    n_angular: usize,
+) -> Option<Triangulation> {
+    if n_radial == 0 || n_angular < 3 || !(inner_radius > 0.0) || !(outer_radius > inner_radius) {
+        return None;
+    }
+
+    let grid: Vec<Vec<Rc<Vertex>>> = (0..=n_radial)
+        .map(|i| {
+            let radius =
+                inner_radius + (outer_radius - inner_radius) * i as f64 / n_radial as f64;
+            return (0..n_angular)
+                .map(|j| {
+                    let angle = 2.0 * PI * j as f64 / n_angular as f64;
+                    Rc::new(Vertex::new(
+                        center.x + radius * angle.cos(),
+                        center.y + radius * angle.sin(),
+                    ))
+                })
+                .collect();
+        })
+        .collect();
+
+    let mut triangulation = Triangulation::new();
+    for i in 0..n_radial {
+        for j in 0..n_angular {
+            let next = (j + 1) % n_angular;
+            let v1 = &grid[i][j];
+            let v2 = &grid[i + 1][j];
+            let v3 = &grid[i + 1][next];
+            let v4 = &grid[i][next];
+            triangulation.include_triangle(&Rc::new(Triangle::new(v1, v2, v3)));
+            triangulation.include_triangle(&Rc::new(Triangle::new(v1, v3, v4)));
+        }
+    }
+
+    include_ghosts(&mut triangulation);
+    return Some(triangulation);
+}
+
+/**
+ * Includes a ghost triangle at every solid edge without adjacent triangle.
+ * Border edges sharing vertices make a loop, which has a ghost vertex of
+ * its own. Loops are numbered after their lowest vertex, so that the first
+ * one is an outer boundary.
+ */
+pub(crate) fn include_ghosts(triangulation: &mut Triangulation) {
+    let border_edges: Vec<Rc<Edge>> = triangulation
+        .adjacency
+        .keys()
+        .filter(|e| !triangulation.adjacency.contains_key(&e.opposite()))
+        .collect();
+
+    let mut neighbours: HashMap<Rc<Vertex>, Vec<Rc<Vertex>>> = HashMap::new();
+    for edge in border_edges.iter() {
+        neighbours.entry(Rc::clone(&edge.v1)).or_insert(Vec::new()).push(Rc::clone(&edge.v2));
+        neighbours.entry(Rc::clone(&edge.v2)).or_insert(Vec::new()).push(Rc::clone(&edge.v1));
+    }
+
+    /* loops as connected vertices, sorted by their lowest vertex */
+    let mut vertices: Vec<Rc<Vertex>> = neighbours.keys().cloned().collect();
+    vertices.sort();
+    let mut loop_of: HashMap<Rc<Vertex>, Rc<Vertex>> = HashMap::new();
+    let mut ghost_loop: usize = 0;
+    for vertex in vertices.iter() {
+        if loop_of.contains_key(vertex) {
+            continue;
+        }
+        let ghost_vertex = Rc::new(Vertex::new_ghost_of(ghost_loop));
+        ghost_loop += 1;
+
+        let mut pending: Vec<Rc<Vertex>> = vec![Rc::clone(vertex)];
+        loop_of.insert(Rc::clone(vertex), Rc::clone(&ghost_vertex));
+        while let Some(next) = pending.pop() {
+            for neighbour in neighbours[&next].iter() {
+                if !loop_of.contains_key(neighbour) {
+                    loop_of.insert(Rc::clone(neighbour), Rc::clone(&ghost_vertex));
+                    pending.push(Rc::clone(neighbour));
+                }
+            }
+        }
+    }
+
+    for edge in border_edges.iter() {
+        triangulation.include_triangle(&Rc::new(Triangle::new(
+            &edge.v2,
+            &edge.v1,
+            &loop_of[&edge.v1],
+        )));
+    }
+}
+
+#[cfg(test)]
+mod structured {
+    use super::*;
+
+    fn solid_area(triangulation: &Triangulation) -> f64 {
+        return triangulation
+            .triangles
+            .iter()
+            .filter_map(|t| t.area())
+            .map(|area| area.abs())
+            .sum();
+    }
+
+    #[test]
+    fn rectangle_cells() {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(1.0, 1.0)),
+            Rc::new(Vertex::new(5.0, 4.0)),
+        ])
+        .unwrap();
+
+        let triangulation = rectangle(&bounding_box, 2, 3).unwrap();
+        let ghosts = triangulation.ghost_triangles().count();
+        assert_eq!(triangulation.triangles.len() - ghosts, 12);
+        assert_eq!(ghosts, 10);
+        assert_eq!(triangulation.vertices().len(), 12);
+        assert!((solid_area(&triangulation) - 12.0).abs() < 1.0E-12);
+
+        /* every solid edge has a triangle at each side */
+        for edge in triangulation.adjacency.keys() {
+            assert!(triangulation.adjacency.contains_key(&edge.opposite()));
+        }
+
+        assert!(rectangle(&bounding_box, 0, 3).is_none());
+    }
+
+    #[test]
+    fn annulus_cells() {
+        let triangulation = annulus(&Vertex::new(0.0, 0.0), 1.0, 2.0, 2, 8).unwrap();
+        let ghosts = triangulation.ghost_triangles().count();
+        assert_eq!(triangulation.triangles.len() - ghosts, 32);
+        assert_eq!(ghosts, 16);
+        assert_eq!(triangulation.vertices().len(), 24);
+
+        /* outer and inner loops, the outer one first */
+        let loops = triangulation.ghost_loops();
+        assert_eq!(loops.len(), 2);
+        assert!(triangulation.loop_edges(&loops[0]).iter().all(|e| e.length() > 1.5));
+        assert!(triangulation.loop_edges(&loops[1]).iter().all(|e| e.length() < 1.0));
+        assert!(triangulation.validate().is_ok());
+
+        /* counterclockwise solid triangles */
+        assert!(triangulation
+            .triangles
+            .iter()
+            .filter_map(|t| t.area())
+            .all(|area| area > 0.0));
+
+        assert!(triangulation.locate(&Vertex::new(1.5, 0.1)).is_some());
+        assert!(triangulation.locate(&Vertex::new(0.0, 0.0)).is_none());
+
+        assert!(annulus(&Vertex::new(0.0, 0.0), 2.0, 1.0, 2, 8).is_none());
+    }
+} /* end - structured tests */
@@ -0,0 +1,1192 @@
+use crate::elements::{bounding_box::*, edge::*, polyline::*, triangle::*, vertex::*};
+use crate::planar::triangulation_procedures::vertices::ON_EDGE_TOLERANCE;
+use crate::planar::{
+    refine_params::*, structured, triangulation::*, triangulation_error::*, triangulator::*,
+};
+use crate::properties::{continence::*, distance::*, intersection::*, orientation::*};
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+/**
+ * A piece of the domain, cut by a tile rectangle. It holds the constraints
+ * of the original domain that belong to it, so that it may be triangulated
+ * independently of the other tiles.
+ */
+pub struct Tile {
+    pub boundary: Rc<Polyline>,
+    pub holes: HashSet<Rc<Polyline>>,
+    pub segments: HashSet<Rc<Edge>>,
+    pub vertices: HashSet<Rc<Vertex>>,
+}
+
+/**
+ * Splits a domain into a grid of tiles, sharing their cut lines. Each tile
+ * may be triangulated and refined on its own, one at a time or on several
+ * threads of the same process, and is stitched along the cut lines as soon
+ * as it is done, resulting in a single conforming mesh. Tile triangulations
+ * are dropped once stitched, but the stitched mesh is kept in memory, so
+ * that tiling bounds the memory of refining rather than the size of the
+ * mesh. Tiles do not overlap: both sides of a cut line are split alike
+ * when stitched, instead of being trimmed from overlapping margins, and the
+ * seams are left to Triangulator::try_triangulate_tiled, which legalizes
+ * and refines the stitched mesh again.
+ */
+pub struct Tiling {
+    pub tile_size: f64,
+    pub tiles: Vec<Tile>,
+}
+
+impl Tiling {
+    /**
+     * Cuts the triangulator domain in square tiles with side tile_size.
+     * Raises the degenerate input error if tile_size is not positive, or
+     * if the domain could not be cut.
+     */
+    pub fn new(triangulator: &Triangulator, tile_size: f64) -> Result<Self, TriangulationError> {
+        if !(tile_size > 0.0) {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "tile size must be positive",
+            )));
+        }
+
+        let bbox = triangulator.boundary.bounding_box().unwrap();
+        let columns = ((bbox.destin.x - bbox.origin.x) / tile_size)
+            .ceil()
+            .max(1.0) as usize;
+        let rows = ((bbox.destin.y - bbox.origin.y) / tile_size)
+            .ceil()
+            .max(1.0) as usize;
+
+        let mut tiles: Vec<Tile> = Vec::new();
+        for row in 0..rows {
+            for column in 0..columns {
+                let x1 = bbox.origin.x + column as f64 * tile_size;
+                let y1 = bbox.origin.y + row as f64 * tile_size;
+                let x2 = bbox.origin.x + (column + 1) as f64 * tile_size;
+                let y2 = bbox.origin.y + (row + 1) as f64 * tile_size;
+
+                let rectangle = BoundingBox::from_vertices(vec![
+                    Rc::new(Vertex::new(x1, y1)),
+                    Rc::new(Vertex::new(x2, y2)),
+                ])
+                .unwrap();
+
+                let pieces = match domain_pieces(triangulator, &rectangle) {
+                    Some(pieces) => pieces,
+                    None => {
+                        return Err(TriangulationError::DegenerateInput(String::from(
+                            "domain could not be cut in tiles",
+                        )));
+                    }
+                };
+                for (piece, holes) in pieces.into_iter() {
+                    tiles.push(Tile::from_domain(triangulator, piece, holes, &rectangle));
+                }
+            }
+        }
+
+        return Ok(Self { tile_size, tiles });
+    }
+
+    /**
+     * Triangulates and refines a single tile, by the areas and edge lengths
+     * of the params only, as cut lines may meet the domain constraints at
+     * angles too small for quality refinement to terminate. Raises the degenerate input
+     * error if index is out of range, and the error of the tile
+     * triangulator if it rejects any constraint or fails to triangulate or
+     * refine the tile.
+     */
+    pub fn triangulate_tile(
+        &self,
+        index: usize,
+        params: &RefineParams,
+    ) -> Result<Triangulation, TriangulationError> {
+        let tile = match self.tiles.get(index) {
+            Some(tile) => tile,
+            None => {
+                return Err(TriangulationError::DegenerateInput(format!(
+                    "tile {} out of {} tiles",
+                    index,
+                    self.tiles.len()
+                )));
+            }
+        };
+
+        let mut triangulator = Triangulator::new(&tile.boundary);
+        for hole in tile.holes.iter() {
+            triangulator.insert_hole(hole)?;
+        }
+        /* segments cut by the tile, or interfaces of domains, end on its boundary */
+        for segment in tile.segments.iter() {
+            triangulator.insert_polyline_constraint(&Rc::new(segment.as_polyline().unwrap()))?;
+        }
+        triangulator.insert_vertices(&tile.vertices)?;
+
+        triangulator.try_triangulate()?;
+        triangulator.try_refine(size_params(params))?;
+
+        return Ok(triangulator.triangulation.into_inner());
+    }
+
+    /**
+     * Triangulates every tile, one at a time, stitching each one as soon
+     * as it is done. Raises the error of the first tile that fails.
+     */
+    pub fn triangulate(&self, params: &RefineParams) -> Result<Triangulation, TriangulationError> {
+        let mut stitching = Stitching::new();
+        for index in 0..self.tiles.len() {
+            stitching.include(self.triangulate_tile(index, params)?);
+        }
+
+        return Ok(stitching.finish());
+    }
+
+    /**
+     * Triangulates the tiles on the given count of threads, each taking the
+     * next pending tile. Tiles are sent to the threads, and back, as plain
+     * coordinates, since reference counted elements may not be shared, and
+     * are stitched in their order, whichever thread finishes first, as by
+     * triangulate. Errors may not be sent back either, thus the first tile
+     * that fails is triangulated again on the calling thread, raising its
+     * error as triangulate does.
+     */
+    pub fn triangulate_parallel(
+        &self,
+        params: &RefineParams,
+        jobs: usize,
+    ) -> Result<Triangulation, TriangulationError> {
+        let tile_jobs: Vec<TileJob> = self
+            .tiles
+            .iter()
+            .map(|tile| TileJob::new(tile, params))
+            .collect();
+        let next = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel::<(usize, Option<Vec<[(f64, f64); 3]>>)>();
+
+        return std::thread::scope(|scope| {
+            for _ in 0..jobs.max(1).min(tile_jobs.len()) {
+                let sender = sender.clone();
+                let (next, tile_jobs) = (&next, &tile_jobs);
+                scope.spawn(move || loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    if index >= tile_jobs.len() {
+                        break;
+                    }
+                    if sender
+                        .send((index, tile_jobs[index].triangulate()))
+                        .is_err()
+                    {
+                        break;
+                    }
+                });
+            }
+            drop(sender);
+
+            /* tiles done ahead of their turn wait as coordinates */
+            let mut vertices: HashMap<(u64, u64), Rc<Vertex>> = HashMap::new();
+            let mut pending: HashMap<usize, Vec<[(f64, f64); 3]>> = HashMap::new();
+            let mut stitching = Stitching::new();
+            for index in 0..tile_jobs.len() {
+                while !pending.contains_key(&index) {
+                    /* threads only stop sending by panicking, leaving the tile to this one */
+                    let (done, triangles) = receiver.recv().unwrap_or((index, None));
+                    let triangles = match triangles {
+                        Some(triangles) => triangles,
+                        None => solid_coordinates(&self.triangulate_tile(done, params)?),
+                    };
+                    pending.insert(done, triangles);
+                }
+                let triangles = pending.remove(&index).unwrap();
+                stitching.include(from_coordinates(&triangles, &mut vertices));
+            }
+
+            return Ok(stitching.finish());
+        });
+    }
+
+    /**
+     * Stitches tile triangulations into a single one, draining them. Hull
+     * edges that are crossed by hull vertices of neighbour tiles are split,
+     * so that both sides of a cut line share the same vertices, by fans
+     * which conform without being Delaunay across the cut line. Cut line
+     * ghosts are dropped and the ghosts of the resulting hull are rebuilt.
+     */
+    pub fn stitch(triangulations: &mut Vec<Triangulation>) -> Triangulation {
+        let mut stitching = Stitching::new();
+        for triangulation in triangulations.drain(..) {
+            stitching.include(triangulation);
+        }
+
+        return stitching.finish();
+    }
+} /* end - Tiling */
+
+/**
+ * Plain coordinates of a tile, and of the refine params, which may be sent
+ * to another thread.
+ */
+struct TileJob {
+    boundary: Vec<(f64, f64)>,
+    holes: Vec<Vec<(f64, f64)>>,
+    segments: Vec<((f64, f64), (f64, f64))>,
+    vertices: Vec<(f64, f64)>,
+    max_area: Option<f64>,
+    quality_ratio: f64,
+    max_edge_length: Option<f64>,
+    regions: Vec<RegionJob>,
+}
+
+struct RegionJob {
+    boundary: Vec<(f64, f64)>,
+    is_opened: bool,
+    hole: bool,
+    max_area: Option<f64>,
+    quality_ratio: Option<f64>,
+    max_edge_length: Option<f64>,
+}
+
+impl TileJob {
+    fn new(tile: &Tile, params: &RefineParams) -> Self {
+        let point = |vertex: &Rc<Vertex>| (vertex.x, vertex.y);
+        let points = |polyline: &Polyline| polyline.vertices.iter().map(point).collect();
+        return Self {
+            boundary: points(&tile.boundary),
+            holes: tile.holes.iter().map(|hole| points(hole)).collect(),
+            segments: tile
+                .segments
+                .iter()
+                .map(|segment| (point(&segment.v1), point(&segment.v2)))
+                .collect(),
+            vertices: tile.vertices.iter().map(point).collect(),
+            max_area: params.max_area,
+            quality_ratio: params.quality_ratio,
+            max_edge_length: params.max_edge_length,
+            regions: params
+                .regions
+                .iter()
+                .map(|region| RegionJob {
+                    boundary: points(&region.boundary),
+                    is_opened: region.boundary.opened,
+                    hole: region.hole,
+                    max_area: region.max_area,
+                    quality_ratio: region.quality_ratio,
+                    max_edge_length: region.max_edge_length,
+                })
+                .collect(),
+        };
+    }
+
+    /**
+     * Triangulates and refines the tile, as Tiling::triangulate_tile, and
+     * returns the coordinates of its solid triangles. Returns None if it
+     * fails, as errors hold reference counted elements.
+     */
+    fn triangulate(&self) -> Option<Vec<[(f64, f64); 3]>> {
+        let vertex = |&(x, y): &(f64, f64)| Rc::new(Vertex::new(x, y));
+        let polyline = |points: &Vec<(f64, f64)>, is_opened: bool| {
+            let vertices = points.iter().map(vertex).collect();
+            if is_opened {
+                return Polyline::new_opened(vertices).map(Rc::new);
+            }
+            return Polyline::new_closed(vertices).map(Rc::new);
+        };
+
+        let mut regions: Vec<RegionParams> = Vec::new();
+        for region in self.regions.iter() {
+            let boundary = polyline(&region.boundary, region.is_opened)?;
+            let mut params = RegionParams::new(boundary, region.hole);
+            params.max_area = region.max_area;
+            params.quality_ratio = region.quality_ratio;
+            params.max_edge_length = region.max_edge_length;
+            regions.push(params);
+        }
+        let params = RefineParams {
+            max_area: self.max_area,
+            quality_ratio: self.quality_ratio,
+            max_edge_length: self.max_edge_length,
+            regions,
+        };
+
+        let mut holes: HashSet<Rc<Polyline>> = HashSet::new();
+        for hole in self.holes.iter() {
+            holes.insert(polyline(hole, false)?);
+        }
+        let tile = Tile {
+            boundary: polyline(&self.boundary, false)?,
+            holes,
+            segments: self
+                .segments
+                .iter()
+                .map(|(v1, v2)| Rc::new(Edge::new(&vertex(v1), &vertex(v2))))
+                .collect(),
+            vertices: self.vertices.iter().map(vertex).collect(),
+        };
+        let tiling = Tiling {
+            tile_size: 0.0,
+            tiles: vec![tile],
+        };
+
+        let triangulation = tiling.triangulate_tile(0, &params).ok()?;
+        return Some(solid_coordinates(&triangulation));
+    }
+} /* end - TileJob */
+
+/**
+ * Params of the areas and edge lengths only, of the whole domain as of its
+ * regions, which never split triangles for their quality.
+ */
+fn size_params(params: &RefineParams) -> RefineParams {
+    let mut regions = params.regions.clone();
+    for region in regions.iter_mut() {
+        region.quality_ratio = None;
+    }
+    return RefineParams {
+        quality_ratio: f64::INFINITY,
+        regions,
+        ..params.clone()
+    };
+}
+
+/**
+ * Coordinates of the solid triangles of the triangulation.
+ */
+fn solid_coordinates(triangulation: &Triangulation) -> Vec<[(f64, f64); 3]> {
+    return triangulation
+        .solid_triangles()
+        .map(|t| [(t.v1.x, t.v1.y), (t.v2.x, t.v2.y), (t.v3.x, t.v3.y)])
+        .collect();
+}
+
+/**
+ * Triangulation of the triangles coordinates, without ghost triangles.
+ * Vertices are shared with the former tiles through the map, keyed by the
+ * bits of their coordinates.
+ */
+fn from_coordinates(
+    triangles: &Vec<[(f64, f64); 3]>,
+    vertices: &mut HashMap<(u64, u64), Rc<Vertex>>,
+) -> Triangulation {
+    let mut vertex = |(x, y): (f64, f64)| {
+        return Rc::clone(
+            vertices
+                .entry((x.to_bits(), y.to_bits()))
+                .or_insert_with(|| Rc::new(Vertex::new(x, y))),
+        );
+    };
+
+    let mut triangulation = Triangulation::new();
+    for &[v1, v2, v3] in triangles.iter() {
+        let triangle = Triangle::new(&vertex(v1), &vertex(v2), &vertex(v3));
+        triangulation.include_triangle(&Rc::new(triangle));
+    }
+    return triangulation;
+}
+
+/**
+ * Triangulation stitched from tiles, kept without ghost triangles, and the
+ * hull edges of its solid triangles, along with the triangle holding them,
+ * so that each tile is stitched against the hull instead of the whole mesh.
+ */
+struct Stitching {
+    triangulation: Triangulation,
+    hull: HashMap<Rc<Edge>, Rc<Triangle>>,
+}
+
+impl Stitching {
+    fn new() -> Self {
+        Self {
+            triangulation: Triangulation::new(),
+            hull: HashMap::new(),
+        }
+    }
+
+    /**
+     * Stitches the tile triangulation. Hull edges of either one crossed by
+     * hull vertices of the other are split, and the solid triangles of the
+     * tile are moved into the stitched triangulation. Hull edges met by the
+     * other side are no longer hull edges.
+     */
+    fn include(&mut self, mut tile: Triangulation) {
+        let mut tile_hull = hull_edges(&tile);
+        let tile_vertices = hull_vertices(&tile_hull);
+        let bbox = BoundingBox::from_vertices(tile_vertices.iter().cloned().collect());
+        let bbox = match bbox {
+            Some(bbox) => bbox,
+            None => return,
+        };
+
+        /* only the stitched hull around the tile may meet it */
+        let stitched_vertices: HashSet<Rc<Vertex>> = hull_vertices(&self.hull)
+            .into_iter()
+            .filter(|vertex| bbox.contains(vertex))
+            .collect();
+
+        conform_hull(&mut tile, &mut tile_hull, &stitched_vertices);
+        conform_hull(&mut self.triangulation, &mut self.hull, &tile_vertices);
+
+        for triangle in tile.solid_triangles() {
+            self.triangulation.include_triangle(triangle);
+        }
+        for (edge, triangle) in tile_hull.into_iter() {
+            if self.hull.remove(&edge.opposite()).is_none() {
+                self.hull.insert(edge, triangle);
+            }
+        }
+    }
+
+    /**
+     * The stitched triangulation, with the ghosts of its hull.
+     */
+    fn finish(mut self) -> Triangulation {
+        structured::include_ghosts(&mut self.triangulation);
+        return self.triangulation;
+    }
+} /* end - Stitching */
+
+impl Tile {
+    /**
+     * Takes the constraints of the triangulator that belong to the piece,
+     * cut from the domain by the rectangle, along with the holes inside it.
+     * Segments are cut by the piece boundary and vertices lying on the
+     * boundary are included as boundary vertices.
+     */
+    fn from_domain(
+        triangulator: &Triangulator,
+        piece: Rc<Polyline>,
+        holes: HashSet<Rc<Polyline>>,
+        rectangle: &BoundingBox,
+    ) -> Self {
+        let mut boundary_vertices: Vec<Rc<Vertex>> = Vec::new();
+        let mut segments: HashSet<Rc<Edge>> = HashSet::new();
+        for segment in triangulator.segments.iter() {
+            for sub_segment in clip_segment(&piece, segment, rectangle) {
+                for vertex in vec![&sub_segment.v1, &sub_segment.v2] {
+                    if piece.contains(vertex) == Some(Continence::Boundary) {
+                        boundary_vertices.push(Rc::clone(vertex));
+                    }
+                }
+                segments.insert(sub_segment);
+            }
+        }
+
+        let mut vertices: HashSet<Rc<Vertex>> = HashSet::new();
+        for vertex in triangulator.vertices.iter() {
+            match piece.contains(vertex) {
+                Some(Continence::Inside) => {
+                    vertices.insert(Rc::clone(vertex));
+                }
+                Some(Continence::Boundary) => {
+                    boundary_vertices.push(Rc::clone(vertex));
+                }
+                _ => {}
+            }
+        }
+
+        return Self {
+            boundary: Rc::new(piece.split_edges(&boundary_vertices)),
+            holes,
+            segments,
+            vertices,
+        };
+    }
+} /* end - Tile */
+
+/**
+ * Determines the pieces of the domain inside the rectangle, along with the
+ * holes inside each of them. Holes crossing the rectangle are cut out of
+ * the pieces boundaries. Returns None if the domain could not be clipped.
+ */
+fn domain_pieces(
+    triangulator: &Triangulator,
+    rectangle: &BoundingBox,
+) -> Option<Vec<(Rc<Polyline>, HashSet<Rc<Polyline>>)>> {
+    let mut rings: Vec<Ring> = vec![Ring::from_polyline(&triangulator.boundary.ensure_ccw())];
+    for hole in triangulator.holes.iter() {
+        rings.push(Ring::from_polyline(&hole.ensure_cw()));
+    }
+
+    let size =
+        (rectangle.destin.x - rectangle.origin.x).max(rectangle.destin.y - rectangle.origin.y);
+    let tolerance = ON_EDGE_TOLERANCE * size;
+    let half_planes = vec![
+        HalfPlane::new(true, rectangle.origin.x, 1.0, tolerance),
+        HalfPlane::new(true, rectangle.destin.x, -1.0, tolerance),
+        HalfPlane::new(false, rectangle.origin.y, 1.0, tolerance),
+        HalfPlane::new(false, rectangle.destin.y, -1.0, tolerance),
+    ];
+    for half_plane in half_planes.iter() {
+        rings = half_plane.clip(&rings)?;
+    }
+
+    let (outer_rings, inner_rings): (Vec<Polyline>, Vec<Polyline>) = rings
+        .into_iter()
+        .filter_map(|ring| ring.into_polyline())
+        .partition(|polyline| polyline.orientation() == Some(Orientation::Counterclockwise));
+
+    let mut pieces: Vec<(Rc<Polyline>, HashSet<Rc<Polyline>>)> = outer_rings
+        .into_iter()
+        .map(|piece| (Rc::new(piece), HashSet::new()))
+        .collect();
+    for hole in inner_rings.into_iter() {
+        let piece = pieces.iter_mut().find(|(piece, _)| {
+            return hole
+                .vertices
+                .iter()
+                .any(|vertex| piece.contains(vertex) == Some(Continence::Inside));
+        });
+        if let Some((_, holes)) = piece {
+            holes.insert(Rc::new(hole.ensure_ccw()));
+        }
+    }
+
+    return Some(pieces);
+}
+
+/**
+ * Closed chain of vertices of a domain being clipped, along with the ends
+ * of the domain edge, or the cut line, that the edge starting at each
+ * vertex lies on, so that cut vertices are computed from them alike in
+ * every tile. The domain boundary chain is counterclockwise and the hole
+ * ones are clockwise, thus the domain is always at their left.
+ */
+#[derive(Clone)]
+struct Ring {
+    vertices: Vec<Rc<Vertex>>,
+    sources: Vec<(Rc<Vertex>, Rc<Vertex>)>,
+}
+
+impl Ring {
+    fn from_polyline(polyline: &Polyline) -> Self {
+        return Self {
+            vertices: polyline.vertices.clone(),
+            sources: vertex_pairs(&polyline.vertices, false),
+        };
+    }
+
+    /**
+     * Polyline of the ring, without repeated vertices nor spikes, which
+     * are left by vertices lying on cut lines. Returns None if nothing but
+     * spikes is left.
+     */
+    fn into_polyline(self) -> Option<Polyline> {
+        let mut vertices: Vec<Rc<Vertex>> = self.vertices;
+        vertices.dedup();
+        while vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+
+        let mut index = 0;
+        while vertices.len() >= 3 && index < vertices.len() {
+            let count = vertices.len();
+            let previous = &vertices[(index + count - 1) % count];
+            let next = &vertices[(index + 1) % count];
+            let vertex = &vertices[index];
+            let is_spike = orientation(previous, vertex, next) == Orientation::Colinear
+                && (previous.x - vertex.x) * (next.x - vertex.x)
+                    + (previous.y - vertex.y) * (next.y - vertex.y)
+                    > 0.0;
+            if !is_spike {
+                index += 1;
+                continue;
+            }
+
+            vertices.remove(index);
+            vertices.dedup();
+            while vertices.len() > 1 && vertices.first() == vertices.last() {
+                vertices.pop();
+            }
+            index = 0;
+        }
+
+        if vertices.len() < 3 {
+            return None;
+        }
+        return Polyline::new_closed(vertices);
+    }
+} /* end - Ring */
+
+/**
+ * Half plane of a rectangle side, of the points whose x coordinate, if
+ * vertical, or else y coordinate, is above the value for a positive sign,
+ * or below it for a negative one. Points closer than the tolerance to the
+ * line are taken on it, so that vertices of the domain are not cut off by
+ * roundoff of the line value.
+ */
+struct HalfPlane {
+    is_vertical: bool,
+    value: f64,
+    sign: f64,
+    tolerance: f64,
+}
+
+/**
+ * Vertex where a ring edge crosses the line, the position of the vertex
+ * along the line, and how fast it moves along it as the line is moved
+ * outwards of the half plane.
+ */
+struct Crossing {
+    vertex: Rc<Vertex>,
+    position: f64,
+    slope: f64,
+    ring: usize,
+    edge: usize,
+    is_exit: bool,
+}
+
+impl HalfPlane {
+    fn new(is_vertical: bool, value: f64, sign: f64, tolerance: f64) -> Self {
+        Self {
+            is_vertical,
+            value,
+            sign,
+            tolerance,
+        }
+    }
+
+    /**
+     * Signed distance of the vertex to the line, positive inside, and zero
+     * within the tolerance.
+     */
+    fn side(&self, vertex: &Vertex) -> f64 {
+        let side = match self.is_vertical {
+            true => self.sign * (vertex.x - self.value),
+            false => self.sign * (vertex.y - self.value),
+        };
+        if side.abs() <= self.tolerance {
+            return 0.0;
+        }
+        return side;
+    }
+
+    /**
+     * Position of the vertex along the line, in the direction that has the
+     * half plane at its left.
+     */
+    fn along(&self, vertex: &Vertex) -> f64 {
+        return match self.is_vertical {
+            true => -self.sign * vertex.y,
+            false => self.sign * vertex.x,
+        };
+    }
+
+    /**
+     * Vertex where the edge crosses the line, computed from its ends in a
+     * fixed order, so that it lies on horizontal and vertical edges
+     * exactly.
+     */
+    fn cut(&self, (v1, v2): &(Rc<Vertex>, Rc<Vertex>)) -> Rc<Vertex> {
+        let (a, b) = match (v1.x, v1.y) < (v2.x, v2.y) {
+            true => (v1, v2),
+            false => (v2, v1),
+        };
+        let value = self.value;
+        return match self.is_vertical {
+            true => Rc::new(Vertex::new(
+                value,
+                a.y + (value - a.x) * (b.y - a.y) / (b.x - a.x),
+            )),
+            false => Rc::new(Vertex::new(
+                a.x + (value - a.y) * (b.x - a.x) / (b.y - a.y),
+                value,
+            )),
+        };
+    }
+
+    /**
+     * Clips the rings by the half plane. Rings crossing the line are
+     * joined along it, from each crossing out of the half plane to the
+     * next crossing into it. Vertices on the line are taken inside, as if
+     * the line were moved outwards by an infinitesimal distance, which
+     * orders the crossings at the same vertex and leaves spikes along the
+     * line, dropped as the rings turn into polylines. Returns None if the
+     * crossings do not alternate, which rings bounding a domain do.
+     */
+    fn clip(&self, rings: &Vec<Ring>) -> Option<Vec<Ring>> {
+        let mut clipped: Vec<Ring> = Vec::new();
+        let mut crossings: Vec<Crossing> = Vec::new();
+
+        for (ring_index, ring) in rings.iter().enumerate() {
+            let count = ring.vertices.len();
+            let mut is_crossed = false;
+            for edge in 0..count {
+                let (v1, v2) = (&ring.vertices[edge], &ring.vertices[(edge + 1) % count]);
+                let (side1, side2) = (self.side(v1), self.side(v2));
+                if (side1 >= 0.0) == (side2 >= 0.0) {
+                    continue;
+                }
+
+                let (inner, outer, inner_side, outer_side) = match side1 >= 0.0 {
+                    true => (v1, v2, side1, side2),
+                    false => (v2, v1, side2, side1),
+                };
+                let vertex = match inner_side == 0.0 {
+                    true => Rc::clone(inner),
+                    false => self.cut(&ring.sources[edge]),
+                };
+                crossings.push(Crossing {
+                    position: self.along(&vertex),
+                    slope: (self.along(outer) - self.along(inner)) / (inner_side - outer_side),
+                    vertex,
+                    ring: ring_index,
+                    edge,
+                    is_exit: side1 >= 0.0,
+                });
+                is_crossed = true;
+            }
+
+            if !is_crossed && self.side(&ring.vertices[0]) >= 0.0 {
+                clipped.push(ring.clone());
+            }
+        }
+
+        crossings.sort_by(|a, b| {
+            return (a.position, a.slope)
+                .partial_cmp(&(b.position, b.slope))
+                .unwrap();
+        });
+        for pair in crossings.chunks(2) {
+            if pair.len() != 2 || !pair[0].is_exit || pair[1].is_exit {
+                return None;
+            }
+        }
+        let exits: HashMap<(usize, usize), usize> = crossings
+            .iter()
+            .enumerate()
+            .filter(|(_, crossing)| crossing.is_exit)
+            .map(|(index, crossing)| ((crossing.ring, crossing.edge), index))
+            .collect();
+
+        let mut visited: Vec<bool> = vec![false; crossings.len()];
+        for start in (0..crossings.len()).step_by(2) {
+            let mut piece = Ring {
+                vertices: Vec::new(),
+                sources: Vec::new(),
+            };
+            let mut current = start;
+            while !visited[current] {
+                visited[current] = true;
+                let (exit, entry) = (&crossings[current], &crossings[current + 1]);
+                let ring = &rings[entry.ring];
+                let count = ring.vertices.len();
+
+                piece.vertices.push(Rc::clone(&exit.vertex));
+                piece
+                    .sources
+                    .push((Rc::clone(&exit.vertex), Rc::clone(&entry.vertex)));
+                piece.vertices.push(Rc::clone(&entry.vertex));
+                piece.sources.push(ring.sources[entry.edge].clone());
+
+                let mut index = (entry.edge + 1) % count;
+                loop {
+                    piece.vertices.push(Rc::clone(&ring.vertices[index]));
+                    piece.sources.push(ring.sources[index].clone());
+                    if let Some(&exit) = exits.get(&(entry.ring, index)) {
+                        current = exit;
+                        break;
+                    }
+                    index = (index + 1) % count;
+                }
+            }
+            if !piece.vertices.is_empty() {
+                clipped.push(piece);
+            }
+        }
+
+        return Some(clipped);
+    }
+} /* end - HalfPlane */
+
+/**
+ * Vertex cutting the edge at the rectangle side it lies on, computed from
+ * the edge ends in a fixed order, so that the pieces on both sides of a cut
+ * line share it, and so that it lies on horizontal and vertical edges
+ * exactly. Returns the vertex itself if it lies on no side, or if it is
+ * an edge end.
+ */
+fn snap_to_cut(vertex: &Rc<Vertex>, edge: &Edge, rectangle: &BoundingBox) -> Rc<Vertex> {
+    let (a, b) = match (edge.v1.x, edge.v1.y) < (edge.v2.x, edge.v2.y) {
+        true => (&edge.v1, &edge.v2),
+        false => (&edge.v2, &edge.v1),
+    };
+    if vertex == a || vertex == b {
+        return Rc::clone(vertex);
+    }
+    let tolerance = ON_EDGE_TOLERANCE * edge.length();
+
+    for &x in [rectangle.origin.x, rectangle.destin.x].iter() {
+        if (vertex.x - x).abs() <= tolerance && a.x != b.x {
+            return Rc::new(Vertex::new(x, a.y + (x - a.x) * (b.y - a.y) / (b.x - a.x)));
+        }
+    }
+    for &y in [rectangle.origin.y, rectangle.destin.y].iter() {
+        if (vertex.y - y).abs() <= tolerance && a.y != b.y {
+            return Rc::new(Vertex::new(a.x + (y - a.y) * (b.x - a.x) / (b.y - a.y), y));
+        }
+    }
+    return Rc::clone(vertex);
+}
+
+/**
+ * Splits the segment by the polyline edges, keeping only the sub segments
+ * inside the polyline. Vertices cutting it along the rectangle sides are
+ * snapped to them.
+ */
+fn clip_segment(polyline: &Polyline, segment: &Rc<Edge>, rectangle: &BoundingBox) -> Vec<Rc<Edge>> {
+    let mut cut_vertices: Vec<Rc<Vertex>> = vec![Rc::clone(&segment.v1), Rc::clone(&segment.v2)];
+
+    for edge in polyline.into_edges().iter() {
+        if let Some(vertex) = intersection(&segment.v1, &segment.v2, &edge.v1, &edge.v2) {
+            let vertex = snap_to_cut(&Rc::new(vertex), segment, rectangle);
+            if !cut_vertices.contains(&vertex) {
+                cut_vertices.push(vertex);
+            }
+        }
+    }
+
+    cut_vertices.sort_by(|a, b| {
+        let distance_a = distance(&segment.v1, a);
+        let distance_b = distance(&segment.v1, b);
+        return distance_a.partial_cmp(&distance_b).unwrap();
+    });
+
+    let mut sub_segments: Vec<Rc<Edge>> = Vec::new();
+    for index in 1..cut_vertices.len() {
+        let sub_segment = Rc::new(Edge::new(
+            cut_vertices.get(index - 1).unwrap(),
+            cut_vertices.get(index).unwrap(),
+        ));
+
+        if polyline.contains(&sub_segment.midpoint()) == Some(Continence::Inside) {
+            sub_segments.push(sub_segment);
+        }
+    }
+
+    return sub_segments;
+}
+
+/**
+ * Determines the solid triangles edges that are not shared by any other
+ * solid triangle, along with the triangle that holds them.
+ */
+fn hull_edges(triangulation: &Triangulation) -> HashMap<Rc<Edge>, Rc<Triangle>> {
+    let mut hull: HashMap<Rc<Edge>, Rc<Triangle>> = HashMap::new();
+
+    for triangle in triangulation.solid_triangles() {
+        let (e1, e2, e3) = triangle.inner_edges();
+        for edge in vec![e1, e2, e3] {
+            let is_hull_edge = match triangulation.adjacency.get(&edge.opposite()) {
+                Some(neighbour) => neighbour.is_ghost(),
+                None => true,
+            };
+            if is_hull_edge {
+                hull.insert(edge, Rc::clone(triangle));
+            }
+        }
+    }
+
+    return hull;
+}
+
+/**
+ * Vertices of the hull edges.
+ */
+fn hull_vertices(hull: &HashMap<Rc<Edge>, Rc<Triangle>>) -> HashSet<Rc<Vertex>> {
+    return hull
+        .keys()
+        .map(|edge| vec![Rc::clone(&edge.v1), Rc::clone(&edge.v2)])
+        .flatten()
+        .collect();
+}
+
+/**
+ * Splits the hull triangles whose hull edge contains any of the vertices,
+ * keeping the hull edges up to date. The triangle is replaced by a fan
+ * from its opposite vertex.
+ */
+fn conform_hull(
+    triangulation: &mut Triangulation,
+    hull: &mut HashMap<Rc<Edge>, Rc<Triangle>>,
+    vertices: &HashSet<Rc<Vertex>>,
+) {
+    if vertices.is_empty() {
+        return;
+    }
+    let bbox = BoundingBox::from_vertices(vertices.iter().cloned().collect()).unwrap();
+    let mut pending_edges: Vec<Rc<Edge>> = hull
+        .keys()
+        .filter(|edge| {
+            let edge_bbox =
+                BoundingBox::from_vertices(vec![Rc::clone(&edge.v1), Rc::clone(&edge.v2)]);
+            return edge_bbox.unwrap().intersects(&bbox);
+        })
+        .cloned()
+        .collect();
+
+    while let Some(edge) = pending_edges.pop() {
+        let triangle = match hull.get(&edge) {
+            Some(triangle) => Rc::clone(triangle),
+            None => continue,
+        };
+
+        let mut inner_vertices: Vec<Rc<Vertex>> = vertices
+            .iter()
+            .filter(|&v| v != &edge.v1 && v != &edge.v2 && edge.contains(v))
+            .cloned()
+            .collect();
+
+        if inner_vertices.is_empty() {
+            continue;
+        }
+
+        inner_vertices.sort_by(|a, b| {
+            let distance_a = distance(&edge.v1, a);
+            let distance_b = distance(&edge.v1, b);
+            return distance_a.partial_cmp(&distance_b).unwrap();
+        });
+
+        let opposite_vertex = triangle.opposite_vertex(&edge).unwrap();
+        let mut fan_vertices: Vec<Rc<Vertex>> = vec![Rc::clone(&edge.v1)];
+        fan_vertices.append(&mut inner_vertices);
+        fan_vertices.push(Rc::clone(&edge.v2));
+
+        if let Some(ghost_triangle) = triangulation.adjacency.get(&edge.opposite()) {
+            let ghost_triangle = Rc::clone(ghost_triangle);
+            triangulation.remove_triangle(&ghost_triangle);
+        }
+        triangulation.remove_triangle(&triangle);
+        hull.remove(&edge);
+
+        for index in 1..fan_vertices.len() {
+            let v1 = fan_vertices.get(index - 1).unwrap();
+            let v2 = fan_vertices.get(index).unwrap();
+            let new_triangle = Rc::new(Triangle::new(v1, v2, &opposite_vertex));
+            triangulation.include_triangle(&new_triangle);
+
+            /* the other edges of the fan may be hull edges as well */
+            let (e1, e2, e3) = new_triangle.inner_edges();
+            hull.insert(e1, Rc::clone(&new_triangle));
+            for other_edge in vec![e2, e3] {
+                if hull.contains_key(&other_edge) {
+                    hull.insert(Rc::clone(&other_edge), Rc::clone(&new_triangle));
+                    pending_edges.push(other_edge);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tiling {
+    use super::*;
+    use crate::planar::validation::InvariantViolation;
+
+    fn square_triangulator(size: f64) -> Triangulator {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(size, 0.0)),
+                Rc::new(Vertex::new(size, size)),
+                Rc::new(Vertex::new(0.0, size)),
+            ])
+            .unwrap(),
+        );
+        return Triangulator::new(&boundary);
+    }
+
+    fn solid_area(triangulation: &Triangulation) -> f64 {
+        return triangulation
+            .solid_triangles()
+            .map(|t| t.area().unwrap())
+            .sum();
+    }
+
+    #[test]
+    fn rejects_non_positive_tile_size() {
+        let triangulator = square_triangulator(4.0);
+        assert!(Tiling::new(&triangulator, 0.0).is_err());
+        assert!(Tiling::new(&triangulator, -1.0).is_err());
+    }
+
+    #[test]
+    fn single_tile_domain() {
+        let triangulator = square_triangulator(4.0);
+        let tiling = Tiling::new(&triangulator, 10.0).unwrap();
+        assert_eq!(tiling.tiles.len(), 1);
+    }
+
+    #[test]
+    fn error_on_missing_tile() {
+        let triangulator = square_triangulator(4.0);
+        let tiling = Tiling::new(&triangulator, 10.0).unwrap();
+        let params = RefineParams {
+            max_area: None,
+            quality_ratio: 1.0,
+            max_edge_length: None,
+            regions: Vec::new(),
+        };
+
+        assert!(tiling.triangulate_tile(0, &params).is_ok());
+        match tiling.triangulate_tile(1, &params) {
+            Err(TriangulationError::DegenerateInput(_)) => {}
+            _ => panic!("missing tile is degenerate input"),
+        }
+    }
+
+    #[test]
+    fn splits_domain_in_grid() {
+        let triangulator = square_triangulator(4.0);
+        let tiling = Tiling::new(&triangulator, 2.0).unwrap();
+        assert_eq!(tiling.tiles.len(), 4);
+
+        for tile in tiling.tiles.iter() {
+            let bbox = tile.boundary.bounding_box().unwrap();
+            assert!(bbox.destin.x - bbox.origin.x <= 2.0 + 1.0E-10);
+            assert!(bbox.destin.y - bbox.origin.y <= 2.0 + 1.0E-10);
+        }
+    }
+
+    #[test]
+    fn clips_segments_by_tiles() {
+        let mut triangulator = square_triangulator(4.0);
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(3.0, 1.0));
+        let segments: HashSet<Rc<Edge>> = vec![Rc::new(Edge::new(&v1, &v2))].into_iter().collect();
+        triangulator.insert_segments(&segments).ok();
+
+        let tiling = Tiling::new(&triangulator, 2.0).unwrap();
+        let sub_segments: Vec<Rc<Edge>> = tiling
+            .tiles
+            .iter()
+            .map(|tile| tile.segments.iter().cloned().collect::<Vec<Rc<Edge>>>())
+            .flatten()
+            .collect();
+
+        assert_eq!(sub_segments.len(), 2);
+        let cut_vertex = Rc::new(Vertex::new(2.0, 1.0));
+        for tile in tiling.tiles.iter().filter(|t| !t.segments.is_empty()) {
+            assert!(tile.boundary.vertices.contains(&cut_vertex));
+        }
+    }
+
+    #[test]
+    fn cuts_holes_crossing_tiles() {
+        let mut triangulator = square_triangulator(4.0);
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(1.5, 1.5)),
+                Rc::new(Vertex::new(2.5, 1.5)),
+                Rc::new(Vertex::new(2.5, 2.5)),
+                Rc::new(Vertex::new(1.5, 2.5)),
+            ])
+            .unwrap(),
+        );
+        triangulator.insert_hole(&hole).unwrap();
+
+        /* the hole is carved from the boundaries of the four tiles */
+        let tiling = Tiling::new(&triangulator, 2.0).unwrap();
+        assert_eq!(tiling.tiles.len(), 4);
+        let corner = Rc::new(Vertex::new(2.0, 2.0));
+        for tile in tiling.tiles.iter() {
+            assert!(tile.holes.is_empty());
+            assert!(!tile.boundary.vertices.contains(&corner));
+            assert_eq!(tile.boundary.vertices.len(), 6);
+        }
+
+        let params = RefineParams {
+            max_area: Some(0.1),
+            quality_ratio: 1.0,
+            max_edge_length: None,
+            regions: Vec::new(),
+        };
+        let triangulation = tiling.triangulate(&params).unwrap();
+        assert!(float_cmp::approx_eq!(
+            f64,
+            solid_area(&triangulation),
+            15.0,
+            epsilon = 1.0E-10f64
+        ));
+        assert_eq!(triangulation.ghost_loops().len(), 2);
+    }
+
+    #[test]
+    fn stitches_conforming_mesh() {
+        let triangulator = square_triangulator(4.0);
+        let tiling = Tiling::new(&triangulator, 2.0).unwrap();
+
+        let params = RefineParams {
+            max_area: Some(0.1),
+            quality_ratio: 1.0,
+            max_edge_length: None,
+            regions: Vec::new(),
+        };
+        let triangulation = tiling.triangulate(&params).unwrap();
+
+        assert!(float_cmp::approx_eq!(
+            f64,
+            solid_area(&triangulation),
+            16.0,
+            epsilon = 1.0E-10f64
+        ));
+
+        /* every solid edge is either shared or at the outer boundary */
+        let boundary = &triangulator.boundary;
+        for (edge, _) in hull_edges(&triangulation).iter() {
+            assert_eq!(
+                boundary.contains(&edge.midpoint()),
+                Some(Continence::Boundary)
+            );
+        }
+
+        /* outer hull is surrounded by ghosts */
+        for triangle in triangulation.solid_triangles() {
+            let (e1, e2, e3) = triangle.inner_edges();
+            for edge in vec![e1, e2, e3] {
+                assert!(triangulation.adjacency.contains_key(&edge.opposite()));
+            }
+        }
+    }
+
+    #[test]
+    fn stitches_tiles_in_parallel() {
+        let mut triangulator = square_triangulator(6.0);
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.5, 0.5)),
+                Rc::new(Vertex::new(1.5, 0.5)),
+                Rc::new(Vertex::new(1.5, 1.5)),
+                Rc::new(Vertex::new(0.5, 1.5)),
+            ])
+            .unwrap(),
+        );
+        triangulator.insert_hole(&hole).unwrap();
+        let tiling = Tiling::new(&triangulator, 2.0).unwrap();
+        assert_eq!(tiling.tiles.len(), 9);
+
+        let params = RefineParams {
+            max_area: Some(0.1),
+            quality_ratio: 1.0,
+            max_edge_length: None,
+            regions: Vec::new(),
+        };
+        for jobs in vec![1, 4] {
+            let triangulation = tiling.triangulate_parallel(&params, jobs).unwrap();
+            assert!(float_cmp::approx_eq!(
+                f64,
+                solid_area(&triangulation),
+                35.0,
+                epsilon = 1.0E-10f64
+            ));
+            assert_eq!(triangulation.ghost_loops().len(), 2);
+
+            /* cut lines are stitched by fans, which conform but may not be Delaunay */
+            let violations = triangulation.validate().err().unwrap_or(Vec::new());
+            assert!(violations.iter().all(|violation| match violation {
+                InvariantViolation::NonDelaunayEdge { .. } => true,
+                _ => false,
+            }));
+        }
+    }
+} /* end - tiling tests */
@@ -0,0 +1,156 @@
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub use std::time::Instant;
+
+/**
+ * There is no clock on wasm32-unknown-unknown, where std::time::Instant
+ * panics, so that phases are not timed there.
+ */
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+#[derive(Clone, Copy, Debug)]
+pub struct Instant;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+impl Instant {
+    pub fn now() -> Self {
+        return Instant;
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        return Duration::from_secs(0);
+    }
+}
+
+/**
+ * Major phases of triangulation and refinement.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Phase {
+    BoundaryInclusion,
+    HoleInclusion,
+    SegmentRecovery,
+    VertexInsertion,
+    Unencroachment,
+    TriangleSplit,
+}
+
+impl Phase {
+    pub fn all() -> Vec<Phase> {
+        return vec![
+            Phase::BoundaryInclusion,
+            Phase::HoleInclusion,
+            Phase::SegmentRecovery,
+            Phase::VertexInsertion,
+            Phase::Unencroachment,
+            Phase::TriangleSplit,
+        ];
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::BoundaryInclusion => return "boundary inclusion",
+            Phase::HoleInclusion => return "hole inclusion",
+            Phase::SegmentRecovery => return "segment recovery",
+            Phase::VertexInsertion => return "vertex insertion",
+            Phase::Unencroachment => return "unencroachment",
+            Phase::TriangleSplit => return "triangle split",
+        }
+    }
+}
+
+/**
+ * Wall clock time spent on each phase, accumulated over every span of
+ * the phase, along with the count of spans.
+ */
+pub struct Timings {
+    spans: Vec<(Phase, Duration, usize)>,
+}
+
+impl Timings {
+    pub fn new() -> Self {
+        Self {
+            spans: Phase::all()
+                .into_iter()
+                .map(|phase| (phase, Duration::from_secs(0), 0))
+                .collect(),
+        }
+    }
+
+    pub fn record(&mut self, phase: Phase, duration: Duration) {
+        let span = self.spans.iter_mut().find(|(p, _, _)| *p == phase).unwrap();
+        span.1 += duration;
+        span.2 += 1;
+    }
+
+    /**
+     * Records the time elapsed since start.
+     */
+    pub fn record_since(&mut self, phase: Phase, start: Instant) {
+        self.record(phase, start.elapsed());
+    }
+
+    pub fn duration(&self, phase: Phase) -> Duration {
+        return self.spans.iter().find(|(p, _, _)| *p == phase).unwrap().1;
+    }
+
+    pub fn count(&self, phase: Phase) -> usize {
+        return self.spans.iter().find(|(p, _, _)| *p == phase).unwrap().2;
+    }
+
+    pub fn total(&self) -> Duration {
+        return self.spans.iter().map(|(_, duration, _)| *duration).sum();
+    }
+
+    /**
+     * Accumulates the spans of other timings, as of separate triangulations.
+     */
+    pub fn merge(&mut self, other: &Timings) {
+        for (span, (_, duration, count)) in self.spans.iter_mut().zip(other.spans.iter()) {
+            span.1 += *duration;
+            span.2 += *count;
+        }
+    }
+} /* end - Timings */
+
+impl fmt::Display for Timings {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (phase, duration, count) in self.spans.iter() {
+            writeln!(
+                formatter,
+                "{}: {:.3} ms ({} spans)",
+                phase.name(),
+                duration.as_secs_f64() * 1000.0,
+                count
+            )?;
+        }
+        return writeln!(
+            formatter,
+            "total: {:.3} ms",
+            self.total().as_secs_f64() * 1000.0
+        );
+    }
+}
+
+#[cfg(test)]
+mod timings {
+    use super::*;
+
+    #[test]
+    fn record() {
+        let mut timings = Timings::new();
+        timings.record(Phase::HoleInclusion, Duration::from_millis(2));
+        timings.record(Phase::HoleInclusion, Duration::from_millis(3));
+        timings.record(Phase::TriangleSplit, Duration::from_millis(1));
+
+        assert_eq!(timings.duration(Phase::HoleInclusion), Duration::from_millis(5));
+        assert_eq!(timings.count(Phase::HoleInclusion), 2);
+        assert_eq!(timings.count(Phase::BoundaryInclusion), 0);
+        assert_eq!(timings.total(), Duration::from_millis(6));
+
+        let display = format!("{}", timings);
+        assert!(display.contains("hole inclusion: 5.000 ms (2 spans)\n"));
+        assert!(display.ends_with("total: 6.000 ms\n"));
+    }
+} /* end - timings tests */
@@ -1,13 +1,26 @@
-use crate::elements::{edge::*, triangle::*, vertex::*};
+use crate::elements::{edge::*, polyline::*, triangle::*, vertex::*};
+use crate::planar::{
+    adjacency::Adjacency, quality_report::QualityReport, spatial_index::SpatialIndex, structured,
+    triangulation_error::TriangulationError, triangulation_procedures, validation,
+    validation::InvariantViolation,
+};
+use crate::properties::{continence::*, convex_hull, distance::*, orientation::*};
 
-use std::collections::{HashMap, HashSet};
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::collections::{hash_map, HashMap, HashSet};
 
 use std::fmt;
+use std::hash::Hash;
 use std::rc::Rc;
 
 pub struct Triangulation {
     pub triangles: HashSet<Rc<Triangle>>,
-    pub adjacency: HashMap<Rc<Edge>, Rc<Triangle>>,
+    pub adjacency: Adjacency,
+    vertex_triangles: HashMap<Rc<Vertex>, usize>,
+    constraints: HashSet<Rc<Edge>>,
+    spatial_index: Option<SpatialIndex>,
+    last_located: RefCell<Option<Rc<Triangle>>>,
 }
 
 impl fmt::Display for Triangulation {
@@ -27,11 +40,126 @@ impl fmt::Display for Triangulation {
     }
 }
 
+/**
+ * Borrowed view of the keys of an index kept by the triangulation, such as
+ * its vertices or edges, read as a set without rebuilding it.
+ */
+pub struct IndexView<'a, K, V> {
+    index: &'a HashMap<K, V>,
+}
+
+impl<'a, K: Hash + Eq + Clone, V> IndexView<'a, K, V> {
+    pub fn len(&self) -> usize {
+        return self.index.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.index.is_empty();
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.index.contains_key(key);
+    }
+
+    pub fn iter(&self) -> hash_map::Keys<'a, K, V> {
+        return self.index.keys();
+    }
+
+    /**
+     * Owned copy of the keys, for callers mutating the triangulation while
+     * going through them.
+     */
+    pub fn to_set(&self) -> HashSet<K> {
+        return self.index.keys().cloned().collect();
+    }
+}
+
+impl<'a, K, V> IntoIterator for IndexView<'a, K, V> {
+    type Item = &'a K;
+    type IntoIter = hash_map::Keys<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.index.keys();
+    }
+}
+
+/**
+ * Borrowed view of the oriented edges of a triangulation, read as a set as
+ * IndexView does. Edges are given owned, as compact adjacencies unpack them.
+ */
+pub struct EdgesView<'a> {
+    adjacency: &'a Adjacency,
+}
+
+impl<'a> EdgesView<'a> {
+    pub fn len(&self) -> usize {
+        return self.adjacency.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.adjacency.is_empty();
+    }
+
+    pub fn contains(&self, edge: &Edge) -> bool {
+        return self.adjacency.contains_key(edge);
+    }
+
+    pub fn iter(&self) -> Box<dyn Iterator<Item = Rc<Edge>> + 'a> {
+        return self.adjacency.keys();
+    }
+
+    /**
+     * Owned copy of the edges, for callers mutating the triangulation while
+     * going through them.
+     */
+    pub fn to_set(&self) -> HashSet<Rc<Edge>> {
+        return self.adjacency.to_set();
+    }
+}
+
+impl<'a> IntoIterator for EdgesView<'a> {
+    type Item = Rc<Edge>;
+    type IntoIter = Box<dyn Iterator<Item = Rc<Edge>> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.adjacency.keys();
+    }
+}
+
 impl Triangulation {
     pub fn new() -> Self {
         Self {
             triangles: HashSet::new(),
-            adjacency: HashMap::new(),
+            adjacency: Adjacency::new(),
+            vertex_triangles: HashMap::new(),
+            constraints: HashSet::new(),
+            spatial_index: None,
+            last_located: RefCell::new(None),
+        }
+    }
+
+    /**
+     * Empty triangulation keeping its adjacency in the compact backend, for
+     * meshes of millions of triangles.
+     */
+    pub fn new_compact() -> Self {
+        return Self {
+            adjacency: Adjacency::new_compact(),
+            ..Self::new()
+        };
+    }
+
+    /**
+     * Moves the adjacency into the compact backend, if compact, or else
+     * into the map one. Triangles and marks are kept as they are.
+     */
+    pub fn set_compact_adjacency(&mut self, compact: bool) {
+        if self.adjacency.is_compact() != compact {
+            self.adjacency = self.adjacency.converted(compact);
         }
     }
 
@@ -51,10 +179,16 @@ impl Triangulation {
         if self.triangles.contains(triangle) {
             return false;
         }
+        self.spatial_index = None;
         let (e12, e23, e31) = triangle.inner_edges();
         self.adjacency.insert(e12, Rc::clone(triangle));
         self.adjacency.insert(e23, Rc::clone(triangle));
         self.adjacency.insert(e31, Rc::clone(triangle));
+        for vertex in vec![&triangle.v1, &triangle.v2, &triangle.v3] {
+            if !vertex.is_ghost {
+                *self.vertex_triangles.entry(Rc::clone(vertex)).or_insert(0) += 1;
+            }
+        }
         return self.triangles.insert(Rc::clone(triangle));
     }
 
@@ -62,31 +196,974 @@ impl Triangulation {
         if !self.triangles.contains(triangle) {
             return false;
         }
+        self.spatial_index = None;
         let (e12, e23, e31) = triangle.inner_edges();
         self.adjacency.remove(&e12);
         self.adjacency.remove(&e23);
         self.adjacency.remove(&e31);
+        for vertex in vec![&triangle.v1, &triangle.v2, &triangle.v3] {
+            if let Some(count) = self.vertex_triangles.get_mut(vertex) {
+                *count -= 1;
+                if *count == 0 {
+                    self.vertex_triangles.remove(vertex);
+                }
+            }
+        }
         return self.triangles.remove(triangle);
     }
 
-    pub fn vertices(&self) -> HashSet<Rc<Vertex>> {
-        self.triangles
-            .iter()
-            .map(|t| vec![Rc::clone(&t.v1), Rc::clone(&t.v2), Rc::clone(&t.v3)])
-            .flatten()
-            .filter(|vertex| !vertex.is_ghost)
+    /**
+     * Solid vertices, as indexed by include_triangle and remove_triangle
+     * along with the count of triangles around each.
+     */
+    pub fn vertices(&self) -> IndexView<'_, Rc<Vertex>, usize> {
+        return IndexView {
+            index: &self.vertex_triangles,
+        };
+    }
+
+    /**
+     * Oriented edges of the triangles, ghost ones included, as indexed by
+     * the adjacency.
+     */
+    pub fn edges(&self) -> EdgesView<'_> {
+        return EdgesView {
+            adjacency: &self.adjacency,
+        };
+    }
+
+    /**
+     * Triangles not incident to any ghost vertex.
+     */
+    pub fn solid_triangles(&self) -> impl Iterator<Item = &Rc<Triangle>> {
+        return self.triangles.iter().filter(|t| !t.is_ghost());
+    }
+
+    /**
+     * Triangles joining the border of the solid triangles to the ghost
+     * vertices of its loops.
+     */
+    pub fn ghost_triangles(&self) -> impl Iterator<Item = &Rc<Triangle>> {
+        return self.triangles.iter().filter(|t| t.is_ghost());
+    }
+
+    /**
+     * Ghost vertices of the triangulation, one for each loop of border
+     * edges, sorted by loop index.
+     */
+    pub fn ghost_loops(&self) -> Vec<Rc<Vertex>> {
+        let mut ghosts: Vec<Rc<Vertex>> = self
+            .ghost_triangles()
+            .filter_map(|t| t.ghost_vertex())
             .collect::<HashSet<Rc<Vertex>>>()
+            .into_iter()
+            .collect();
+        ghosts.sort();
+        return ghosts;
+    }
+
+    /**
+     * Ghost vertex of a new loop, whose index follows every loop of the
+     * triangulation.
+     */
+    pub fn new_ghost_loop(&self) -> Rc<Vertex> {
+        let next_loop = match self.ghost_loops().last() {
+            Some(ghost) => ghost.ghost_loop().unwrap() + 1,
+            None => 0,
+        };
+        return Rc::new(Vertex::new_ghost_of(next_loop));
+    }
+
+    /**
+     * Solid edges of the ghost triangles of the loop, oriented with the
+     * outside of the loop at their left.
+     */
+    pub fn loop_edges(&self, ghost: &Rc<Vertex>) -> Vec<Rc<Edge>> {
+        return self
+            .ghost_triangles()
+            .filter_map(|t| t.opposite_edge(ghost))
+            .collect();
+    }
+
+    /**
+     * Includes the triangles of a triangulation that shares no vertex with
+     * this one, as a disconnected component. Its loops are renumbered after
+     * the existing ones, so that every loop keeps its own ghost vertex.
+     * Returns false, including nothing, if the triangulations share any
+     * vertex.
+     */
+    pub fn include_component(&mut self, component: &Triangulation) -> bool {
+        let vertices = self.vertices();
+        if component.vertices().iter().any(|v| vertices.contains(v)) {
+            return false;
+        }
+
+        let first_loop = self.new_ghost_loop().ghost_loop().unwrap();
+        let ghosts: HashMap<Rc<Vertex>, Rc<Vertex>> = component
+            .ghost_loops()
+            .into_iter()
+            .enumerate()
+            .map(|(index, ghost)| (ghost, Rc::new(Vertex::new_ghost_of(first_loop + index))))
+            .collect();
+        let renumbered = |v: &Rc<Vertex>| match ghosts.get(v) {
+            Some(ghost) => Rc::clone(ghost),
+            None => Rc::clone(v),
+        };
+
+        for triangle in component.triangles.iter() {
+            self.include_triangle(&Rc::new(Triangle::new(
+                &renumbered(&triangle.v1),
+                &renumbered(&triangle.v2),
+                &renumbered(&triangle.v3),
+            )));
+        }
+        for constraint in component.constraints.iter() {
+            self.constrain(constraint);
+        }
+        return true;
+    }
+
+    /**
+     * Edges shared by two solid triangles, each one in a single orientation.
+     */
+    pub fn interior_edges(&self) -> impl Iterator<Item = Rc<Edge>> + '_ {
+        return self
+            .solid_edges()
+            .filter(move |edge| edge.v1 < edge.v2 && self.is_solid_edge(&edge.opposite()));
+    }
+
+    /**
+     * Edges on the border of the solid triangles, around the boundary and the
+     * holes, oriented as in their solid triangle.
+     */
+    pub fn boundary_edges(&self) -> impl Iterator<Item = Rc<Edge>> + '_ {
+        return self
+            .solid_edges()
+            .filter(move |edge| !self.is_solid_edge(&edge.opposite()));
+    }
+
+    /**
+     * Edges of solid triangles marked as constraints, each one reported in a
+     * single orientation.
+     */
+    pub fn constrained_edges(&self) -> impl Iterator<Item = Rc<Edge>> + '_ {
+        return self.solid_edges().filter(move |edge| {
+            return self.is_constrained(edge)
+                && (edge.v1 < edge.v2 || !self.is_solid_edge(&edge.opposite()));
+        });
+    }
+
+    /**
+     * Whether the edge is marked as a constraint, in either orientation.
+     */
+    pub fn is_constrained(&self, edge: &Edge) -> bool {
+        return self.constraints.contains(edge) || self.constraints.contains(&edge.opposite());
+    }
+
+    /**
+     * Edges marked as constraints, as they were marked or split.
+     */
+    pub fn constraints(&self) -> &HashSet<Rc<Edge>> {
+        return &self.constraints;
+    }
+
+    /**
+     * Marks the edge as a constraint. Returns false if it is already marked,
+     * in either orientation.
+     */
+    pub fn constrain(&mut self, edge: &Rc<Edge>) -> bool {
+        if self.is_constrained(edge) {
+            return false;
+        }
+        return self.constraints.insert(Rc::clone(edge));
+    }
+
+    /**
+     * Unmarks the edge, in both orientations. Returns false if it was not
+     * marked.
+     */
+    pub fn unconstrain(&mut self, edge: &Edge) -> bool {
+        let removed = self.constraints.remove(edge);
+        return self.constraints.remove(&edge.opposite()) || removed;
+    }
+
+    /**
+     * Replaces the constraint mark of the edge by marks on its halves, as
+     * the edge is split at a Steiner point. Returns false, marking nothing,
+     * if the edge is not a constraint.
+     */
+    pub fn split_constraint(&mut self, edge: &Edge, half_1: &Rc<Edge>, half_2: &Rc<Edge>) -> bool {
+        if !self.unconstrain(edge) {
+            return false;
+        }
+        self.constraints.insert(Rc::clone(half_1));
+        self.constraints.insert(Rc::clone(half_2));
+        return true;
+    }
+
+    /* edges of solid triangles, as oriented in them */
+    fn solid_edges(&self) -> impl Iterator<Item = Rc<Edge>> + '_ {
+        return self
+            .adjacency
+            .iter()
+            .filter(|(_, triangle)| !triangle.is_ghost())
+            .map(|(edge, _)| edge);
+    }
+
+    fn is_solid_edge(&self, edge: &Edge) -> bool {
+        return match self.adjacency.get(edge) {
+            Some(triangle) => !triangle.is_ghost(),
+            None => false,
+        };
+    }
+
+    /**
+     * Angle, aspect ratio and area statistics of the solid triangles.
+     */
+    pub fn quality_report(&self) -> QualityReport {
+        return QualityReport::new(self);
+    }
+
+    /**
+     * Checks the triangulation invariants: adjacency consistency, counter
+     * clockwise solid triangles, Delaunay unconstrained edges, closed rings
+     * of ghost triangles and constrained edges present. Returns every
+     * violation found.
+     */
+    pub fn validate(&self) -> Result<(), Vec<InvariantViolation>> {
+        let violations = validation::validate(self);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        return Err(violations);
+    }
+
+    /**
+     * Precomputes a spatial index for repeated point location. The index is
+     * dropped as soon as triangles are included or removed.
+     */
+    pub fn build_spatial_index(&mut self) {
+        self.spatial_index = Some(SpatialIndex::new(self));
+    }
+
+    pub fn spatial_index(&self) -> Option<&SpatialIndex> {
+        return self.spatial_index.as_ref();
+    }
+
+    /**
+     * Solid triangle containing the vertex, inside or on its edges. Returns
+     * None if the vertex is out of the triangulation. Uses the spatial index
+     * if built, else walks towards the vertex from the last located triangle,
+     * searching every triangle when the walk leaves the triangulation, as it
+     * may happen around holes and concavities.
+     */
+    pub fn locate(&self, vertex: &Vertex) -> Option<Rc<Triangle>> {
+        let located = match &self.spatial_index {
+            Some(index) => index.locate(vertex),
+            None => self.walk(vertex).or_else(|| {
+                self.triangles
+                    .iter()
+                    .find(|t| t.contains(vertex) != Continence::Outside)
+                    .cloned()
+            }),
+        };
+
+        if let Some(triangle) = &located {
+            self.last_located.replace(Some(Rc::clone(triangle)));
+        }
+        return located;
+    }
+
+    /**
+     * Convex hull of the triangulation vertices. Ghost triangles surround it
+     * only when there is no boundary nor holes, thus it is computed from the
+     * vertices themselves. Returns None if there are less than 3 non colinear
+     * vertices.
+     */
+    pub fn convex_hull(&self) -> Option<Polyline> {
+        return convex_hull::convex_hull(&self.vertices().iter().cloned().collect());
+    }
+
+    /**
+     * Extracts the region of the solid triangles satisfying the predicate.
+     * Returns the loops bounding the region, counterclockwise outer loops
+     * followed by clockwise hole loops, and its triangles, sorted by their
+     * centers.
+     */
+    pub fn extract_region<F>(&self, predicate: F) -> (Vec<Polyline>, Vec<Rc<Triangle>>)
+    where
+        F: Fn(&Triangle) -> bool,
+    {
+        let triangles: HashSet<Rc<Triangle>> = self
+            .solid_triangles()
+            .filter(|triangle| predicate(triangle))
+            .cloned()
+            .collect();
+        let hull = Polyline::triangles_hull(&triangles);
+
+        let mut region: Vec<Rc<Triangle>> = triangles.into_iter().collect();
+        region.sort_by(|t1, t2| t1.center().cmp(&t2.center()));
+        let boundaries = hull.outer.into_iter().chain(hull.holes.into_iter()).collect();
+        return (boundaries, region);
+    }
+
+    /**
+     * Solid triangles reached from the start one across the edges for which
+     * crossable holds, the start one included. Edges are given as oriented
+     * in the triangle being left, such as to classify triangles by the
+     * constraints or materials between them. Ghost triangles are never
+     * reached. Returns an empty set if the start is a ghost triangle or not
+     * in the triangulation.
+     */
+    pub fn flood_fill<F>(&self, start: &Rc<Triangle>, crossable: F) -> HashSet<Rc<Triangle>>
+    where
+        F: Fn(&Edge) -> bool,
+    {
+        let mut region: HashSet<Rc<Triangle>> = HashSet::new();
+        if start.is_ghost() || !self.triangles.contains(start) {
+            return region;
+        }
+
+        let mut pending: Vec<Rc<Triangle>> = vec![Rc::clone(start)];
+        region.insert(Rc::clone(start));
+        while let Some(triangle) = pending.pop() {
+            let (e1, e2, e3) = triangle.inner_edges();
+            for edge in [e1, e2, e3].iter() {
+                if !crossable(edge) {
+                    continue;
+                }
+                if let Some(neighbor) = self.adjacency.get(&edge.opposite()) {
+                    if !neighbor.is_ghost() && region.insert(Rc::clone(neighbor)) {
+                        pending.push(Rc::clone(neighbor));
+                    }
+                }
+            }
+        }
+        return region;
+    }
+
+    /**
+     * Independent triangulation of the selected solid triangles, on copies
+     * of their vertices. Adjacency is rebuilt, ghost triangles surround
+     * every border loop of the selection, and constraints lying on selected
+     * triangles keep their marks. Selected ghost triangles are skipped.
+     */
+    pub fn submesh(&self, triangles: &HashSet<Rc<Triangle>>) -> Triangulation {
+        let mut copies: HashMap<Rc<Vertex>, Rc<Vertex>> = HashMap::new();
+        let mut copy = |vertex: &Rc<Vertex>| -> Rc<Vertex> {
+            return Rc::clone(
+                copies
+                    .entry(Rc::clone(vertex))
+                    .or_insert_with(|| Rc::new(Vertex::new(vertex.x, vertex.y))),
+            );
+        };
+
+        let mut submesh = Triangulation::new();
+        submesh.adjacency = self.adjacency.new_like();
+        for triangle in triangles.iter().filter(|t| !t.is_ghost()) {
+            let (v1, v2, v3) = (copy(&triangle.v1), copy(&triangle.v2), copy(&triangle.v3));
+            submesh.include_triangle(&Rc::new(Triangle::new(&v1, &v2, &v3)));
+        }
+        structured::include_ghosts(&mut submesh);
+
+        for constraint in self.constraints.iter() {
+            if let (Some(v1), Some(v2)) = (copies.get(&constraint.v1), copies.get(&constraint.v2)) {
+                let edge = Rc::new(Edge::new(v1, v2));
+                if submesh.adjacency.contains_key(&edge) {
+                    submesh.constrain(&edge);
+                }
+            }
+        }
+        return submesh;
+    }
+
+    /**
+     * Stitches two conforming triangulations, whose borders share the
+     * vertex chain of the shared boundary, into a new one. Every edge of the
+     * chain must be a border edge of both triangulations, in opposite
+     * orientations, and the triangulations must not meet anywhere else.
+     * Ghost loops are rebuilt around the stitched border, and constraints of
+     * both triangulations are kept.
+     */
+    pub fn merge(
+        a: &Triangulation,
+        b: &Triangulation,
+        shared_boundary: &Polyline,
+    ) -> Result<Triangulation, TriangulationError> {
+        let a_border: HashSet<Rc<Edge>> = a.boundary_edges().collect();
+        let b_border: HashSet<Rc<Edge>> = b.boundary_edges().collect();
+
+        let mut shared: HashSet<Rc<Edge>> = HashSet::new();
+        for edge in shared_boundary.into_edges().into_iter() {
+            let opposite = Rc::new(edge.opposite());
+            let stitched = if a_border.contains(&edge) && b_border.contains(&opposite) {
+                edge
+            } else if a_border.contains(&opposite) && b_border.contains(&edge) {
+                opposite
+            } else {
+                return Err(TriangulationError::DegenerateInput(format!(
+                    "shared boundary edge {} is not on both borders",
+                    edge
+                )));
+            };
+            shared.insert(stitched);
+        }
+
+        for edge in a_border.iter() {
+            if b_border.contains(&edge.opposite()) && !shared.contains(edge) {
+                return Err(TriangulationError::DegenerateInput(format!(
+                    "triangulations meet at edge {}, out of the shared boundary",
+                    edge
+                )));
+            }
+        }
+
+        let mut merged = Triangulation::new();
+        merged.adjacency = a.adjacency.new_like();
+        for triangle in a.solid_triangles().chain(b.solid_triangles()) {
+            merged.include_triangle(triangle);
+        }
+        structured::include_ghosts(&mut merged);
+        for constraint in a.constraints.iter().chain(b.constraints.iter()) {
+            merged.constrain(constraint);
+        }
+        return Ok(merged);
+    }
+
+    /**
+     * Inserts the vertex by Bowyer-Watson insertion from its containing
+     * triangle, whose cavity does not cross the constrained edges. Returns
+     * false, leaving the triangulation unchanged, if the vertex is out of
+     * the triangulation, already exists, or lies on a constrained edge.
+     */
+    pub fn insert_vertex(&mut self, vertex: &Rc<Vertex>) -> bool {
+        let triangle = match self.locate(vertex) {
+            Some(triangle) => triangle,
+            None => return false,
+        };
+
+        let (e1, e2, e3) = triangle.inner_edges();
+        for edge in vec![e1, e2, e3] {
+            if edge.v1 == *vertex || edge.v2 == *vertex {
+                return false;
+            }
+            let is_constrained = self.is_constrained(&edge);
+            if is_constrained && orientation(&edge.v1, &edge.v2, vertex) == Orientation::Colinear {
+                return false;
+            }
+        }
+
+        /* marks are lent to the conflicts solving, which does not change them */
+        let constraints = std::mem::take(&mut self.constraints);
+        let mut conflict_map: HashMap<Rc<Triangle>, Vec<Rc<Vertex>>> = HashMap::new();
+        conflict_map.insert(triangle, vec![Rc::clone(vertex)]);
+        let (included_triangles, _) = triangulation_procedures::vertices::solve_conflicts(
+            self,
+            &mut conflict_map,
+            &mut Vec::new(),
+            &constraints,
+            &None,
+            &HashSet::new(),
+        );
+        self.constraints = constraints;
+
+        /* next walks start around the vertex, as nearby insertions are likely */
+        let next_start = included_triangles.into_iter().find(|t| !t.is_ghost());
+        self.last_located.replace(next_start);
+
+        return true;
+    }
+
+    /**
+     * Removes the vertex, retriangulating the polygon of its neighbours by
+     * clipping Delaunay ears, that is, ears whose circumcircle contains no
+     * other polygon vertex. Returns false, leaving the triangulation
+     * unchanged, if the vertex is an end of any constrained edge, or if it is
+     * not surrounded by solid triangles, as border vertices are.
+     */
+    pub fn remove_vertex(&mut self, vertex: &Rc<Vertex>) -> bool {
+        let start = match self.locate(vertex) {
+            Some(triangle) => triangle,
+            None => return false,
+        };
+        if start.opposite_edge(vertex).is_none() {
+            return false;
+        }
+
+        /* star triangles, rotating counterclockwise, and the polygon of neighbours */
+        let mut star: Vec<Rc<Triangle>> = Vec::new();
+        let mut polygon: Vec<Rc<Vertex>> = Vec::new();
+        let mut current = Rc::clone(&start);
+        loop {
+            let link = current.opposite_edge(vertex).unwrap();
+            if self.is_constrained(&Edge::new(vertex, &link.v2)) {
+                return false;
+            }
+            star.push(Rc::clone(&current));
+            polygon.push(Rc::clone(&link.v2));
+
+            current = match self.adjacency.get(&Edge::new(vertex, &link.v2)) {
+                Some(next) if !next.is_ghost() => Rc::clone(next),
+                _ => return false,
+            };
+            if current == start {
+                break;
+            }
+            if star.len() > self.triangles.len() {
+                return false;
+            }
+        }
+
+        /* Delaunay ears */
+        let mut new_triangles: Vec<Rc<Triangle>> = Vec::new();
+        while polygon.len() > 3 {
+            let count = polygon.len();
+            let ear = (0..count).find(|&index| {
+                let v1 = &polygon[index];
+                let v2 = &polygon[(index + 1) % count];
+                let v3 = &polygon[(index + 2) % count];
+                if orientation(v1, v2, v3) != Orientation::Counterclockwise {
+                    return false;
+                }
+                let ear = Triangle::new(v1, v2, v3);
+                return polygon
+                    .iter()
+                    .filter(|v| !Rc::ptr_eq(v, v1) && !Rc::ptr_eq(v, v2) && !Rc::ptr_eq(v, v3))
+                    .all(|v| ear.encircles(v) != Continence::Inside);
+            });
+
+            let index = match ear {
+                Some(index) => index,
+                None => return false,
+            };
+            new_triangles.push(Rc::new(Triangle::new(
+                &polygon[index],
+                &polygon[(index + 1) % count],
+                &polygon[(index + 2) % count],
+            )));
+            polygon.remove((index + 1) % count);
+        }
+        new_triangles.push(Rc::new(Triangle::new(&polygon[0], &polygon[1], &polygon[2])));
+
+        for triangle in star.iter() {
+            self.remove_triangle(triangle);
+        }
+        for triangle in new_triangles.iter() {
+            self.include_triangle(triangle);
+        }
+
+        return true;
+    }
+
+    /**
+     * Moves the vertex to the position, replacing it by a new vertex in the
+     * triangles of its star, which are then re-legalized by edge flips. The
+     * position must lie in the kernel of the star, that is, no star triangle
+     * may be inverted by the move. Else the triangulation is left unchanged
+     * and the star triangles that would be inverted are returned. Vertices on
+     * the hull or ending constrained edges are not moved, returning their
+     * whole star, as are vertices out of the triangulation, with no star.
+     */
+    pub fn move_vertex(
+        &mut self,
+        vertex: &Rc<Vertex>,
+        position: Vertex,
+    ) -> Result<Rc<Vertex>, Vec<Rc<Triangle>>> {
+        let start = match self.locate(vertex) {
+            Some(triangle) if triangle.opposite_edge(vertex).is_some() => triangle,
+            _ => return Err(Vec::new()),
+        };
+
+        /* star triangles, rotating counterclockwise */
+        let mut star: Vec<Rc<Triangle>> = Vec::new();
+        let mut current = Rc::clone(&start);
+        loop {
+            let link = current.opposite_edge(vertex).unwrap();
+            star.push(Rc::clone(&current));
+
+            current = match self.adjacency.get(&Edge::new(vertex, &link.v2)) {
+                Some(next) => Rc::clone(next),
+                None => return Err(star),
+            };
+            if current == start || star.len() > self.triangles.len() {
+                break;
+            }
+        }
+
+        let is_fixed = star.iter().any(|t| t.is_ghost())
+            || star.iter().any(|t| {
+                let link = t.opposite_edge(vertex).unwrap();
+                return self.is_constrained(&Edge::new(vertex, &link.v2));
+            });
+        if is_fixed {
+            return Err(star);
+        }
+
+        let new_vertex = Rc::new(position);
+        let pick = |v: &Rc<Vertex>| match v == vertex {
+            true => Rc::clone(&new_vertex),
+            false => Rc::clone(v),
+        };
+        let new_star: Vec<Rc<Triangle>> = star
+            .iter()
+            .map(|t| Rc::new(Triangle::new(&pick(&t.v1), &pick(&t.v2), &pick(&t.v3))))
+            .collect();
+
+        let conflicting: Vec<Rc<Triangle>> = star
+            .iter()
+            .zip(new_star.iter())
+            .filter(|(_, t)| orientation(&t.v1, &t.v2, &t.v3) != Orientation::Counterclockwise)
+            .map(|(t, _)| Rc::clone(t))
+            .collect();
+        if !conflicting.is_empty() {
+            return Err(conflicting);
+        }
+
+        for triangle in star.iter() {
+            self.remove_triangle(triangle);
+        }
+        for triangle in new_star.iter() {
+            self.include_triangle(triangle);
+        }
+        self.restore_delaunay(&new_star);
+
+        return Ok(new_vertex);
+    }
+
+    /**
+     * Replaces the two solid triangles sharing the edge by the two triangles
+     * sharing the other diagonal of their quadrilateral. Returns false,
+     * leaving the triangulation unchanged, if the edge is not shared by two
+     * solid triangles or if the quadrilateral is not strictly convex.
+     * Constraints are not checked, so that any edge may be flipped.
+     */
+    pub fn flip_edge(&mut self, edge: &Edge) -> bool {
+        let (t1, t2) = match (self.adjacency.get(edge), self.adjacency.get(&edge.opposite())) {
+            (Some(t1), Some(t2)) if !t1.is_ghost() && !t2.is_ghost() => {
+                (Rc::clone(t1), Rc::clone(t2))
+            }
+            _ => return false,
+        };
+
+        let v3 = t1.opposite_vertex(&Rc::new(Edge::new(&edge.v1, &edge.v2))).unwrap();
+        let v4 = t2.opposite_vertex(&Rc::new(edge.opposite())).unwrap();
+
+        /* (v1, v4, v2, v3) is the counterclockwise quadrilateral */
+        let n1 = Rc::new(Triangle::new(&edge.v1, &v4, &v3));
+        let n2 = Rc::new(Triangle::new(&v4, &edge.v2, &v3));
+        let is_convex = orientation(&n1.v1, &n1.v2, &n1.v3) == Orientation::Counterclockwise
+            && orientation(&n2.v1, &n2.v2, &n2.v3) == Orientation::Counterclockwise;
+        if !is_convex {
+            return false;
+        }
+
+        self.remove_triangle(&t1);
+        self.remove_triangle(&t2);
+        self.include_triangle(&n1);
+        self.include_triangle(&n2);
+        return true;
     }
 
-    pub fn edges(&self) -> HashSet<Rc<Edge>> {
-        self.triangles
+    /**
+     * Restores the Delaunay property around the region, such as the
+     * triangles around vertices moved by external code, flipping its edges,
+     * and the edges around every flip, while they are not locally Delaunay.
+     * Constrained edges are not flipped. Returns the count of flips.
+     */
+    pub fn restore_delaunay(&mut self, region: &[Rc<Triangle>]) -> usize {
+        let pending: Vec<Rc<Edge>> = region
             .iter()
             .map(|t| {
                 let (e1, e2, e3) = t.inner_edges();
-                return vec![Rc::clone(&e1), Rc::clone(&e2), Rc::clone(&e3)];
+                return vec![e1, e2, e3];
             })
             .flatten()
-            .collect::<HashSet<Rc<Edge>>>()
+            .collect();
+        return self.legalize(pending);
+    }
+
+    /**
+     * Flips the pending edges, and the edges around every flip, while they
+     * are not locally Delaunay nor constrained. Returns the count of flips.
+     */
+    pub(crate) fn legalize(&mut self, mut pending: Vec<Rc<Edge>>) -> usize {
+        let mut flips = 0;
+        while let Some(edge) = pending.pop() {
+            let (t1, t2) = match (self.adjacency.get(&edge), self.adjacency.get(&edge.opposite())) {
+                (Some(t1), Some(t2)) if !t1.is_ghost() && !t2.is_ghost() => (t1, t2),
+                _ => continue,
+            };
+            let v3 = t1.opposite_vertex(&edge).unwrap();
+            let v4 = t2.opposite_vertex(&Rc::new(edge.opposite())).unwrap();
+            if t1.encircles(&v4) != Continence::Inside {
+                continue;
+            }
+            if self.is_constrained(&edge) || !self.flip_edge(&edge) {
+                continue;
+            }
+            flips += 1;
+
+            pending.push(Rc::new(Edge::new(&edge.v1, &v4)));
+            pending.push(Rc::new(Edge::new(&v4, &edge.v2)));
+            pending.push(Rc::new(Edge::new(&edge.v2, &v3)));
+            pending.push(Rc::new(Edge::new(&v3, &edge.v1)));
+        }
+        return flips;
+    }
+
+    /**
+     * Triangulation vertex closest to the vertex. Searches the spatial index
+     * if built, which is exact. Else walks from the containing triangle
+     * towards closer neighbour vertices, which is exact for Delaunay
+     * triangulations, while constrained edges may stop it at a vertex closer
+     * than its neighbours only. Returns None if there are no solid triangles.
+     */
+    pub fn nearest_vertex(&self, vertex: &Vertex) -> Option<Rc<Vertex>> {
+        if let Some(index) = &self.spatial_index {
+            return index.nearest_vertex(vertex);
+        }
+
+        let (nearest, _) = self.nearest_vertex_star(vertex)?;
+        return Some(nearest);
+    }
+
+    /**
+     * The k triangulation vertices closest to the vertex, sorted by distance.
+     * Vertices are expanded from the nearest one to the neighbours of those
+     * already found, closest first, which is exact for Delaunay
+     * triangulations, as the k-th nearest vertex is always adjacent to a
+     * closer one.
+     */
+    pub fn k_nearest(&self, vertex: &Vertex, k: usize) -> Vec<Rc<Vertex>> {
+        let mut nearest_vertices: Vec<Rc<Vertex>> = Vec::new();
+        if k == 0 {
+            return nearest_vertices;
+        }
+        let (start, triangle) = match self.nearest_vertex_star(vertex) {
+            Some(start) => start,
+            None => return nearest_vertices,
+        };
+
+        let mut visited: HashSet<Rc<Vertex>> = HashSet::new();
+        let mut frontier: Vec<(f64, Rc<Vertex>, Rc<Triangle>)> = Vec::new();
+        visited.insert(Rc::clone(&start));
+        frontier.push((distance(&start, vertex), start, triangle));
+
+        while nearest_vertices.len() < k && !frontier.is_empty() {
+            let closest = (0..frontier.len())
+                .min_by(|&i, &j| frontier[i].0.partial_cmp(&frontier[j].0).unwrap())
+                .unwrap();
+            let (_, closest_vertex, triangle) = frontier.swap_remove(closest);
+
+            for (neighbour, neighbour_triangle) in self.vertex_star(&closest_vertex, &triangle) {
+                if visited.insert(Rc::clone(&neighbour)) {
+                    frontier.push((distance(&neighbour, vertex), neighbour, neighbour_triangle));
+                }
+            }
+            nearest_vertices.push(closest_vertex);
+        }
+
+        return nearest_vertices;
+    }
+
+    /**
+     * Local feature size at the vertex: radius of the smallest disk centered
+     * at the vertex touching two non incident features, which are the
+     * segment constraints and their end vertices. Searches the constrained
+     * edges of the spatial index if built, else every segment constraint.
+     * Pass the input segments, before refinement splits them, for Ruppert's
+     * local feature size. Returns None if there are no two non incident
+     * features.
+     */
+    pub fn local_feature_size(
+        &self,
+        vertex: &Vertex,
+        segment_constraints: &HashSet<Rc<Edge>>,
+    ) -> Option<f64> {
+        if let Some(index) = &self.spatial_index {
+            return index.local_feature_size(vertex, segment_constraints);
+        }
+
+        let segments: Vec<Rc<Edge>> = segment_constraints.iter().cloned().collect();
+        return feature_size(vertex, &segments);
+    }
+
+    /**
+     * Nearest vertex by greedy walk, along with a triangle having it.
+     */
+    fn nearest_vertex_star(&self, vertex: &Vertex) -> Option<(Rc<Vertex>, Rc<Triangle>)> {
+        let triangle = match self.locate(vertex) {
+            Some(triangle) => triangle,
+            None => Rc::clone(self.triangles.iter().find(|t| !t.is_ghost())?),
+        };
+
+        let mut current = vec![&triangle.v1, &triangle.v2, &triangle.v3]
+            .into_iter()
+            .min_by(|v1, v2| distance(v1, vertex).partial_cmp(&distance(v2, vertex)).unwrap())
+            .map(|v| (Rc::clone(v), Rc::clone(&triangle)))
+            .unwrap();
+
+        loop {
+            let current_distance = distance(&current.0, vertex);
+            let closer = self
+                .vertex_star(&current.0, &current.1)
+                .into_iter()
+                .filter(|(neighbour, _)| distance(neighbour, vertex) < current_distance)
+                .min_by(|(v1, _), (v2, _)| {
+                    distance(v1, vertex).partial_cmp(&distance(v2, vertex)).unwrap()
+                });
+
+            match closer {
+                Some(closer) => current = closer,
+                None => return Some(current),
+            }
+        }
+    }
+
+    /**
+     * Solid vertices adjacent to the vertex, each one along with a triangle
+     * having it, found by rotating around the vertex from the triangle. If
+     * the rotation is interrupted by a missing triangle, it is resumed from
+     * the triangle in the opposite direction.
+     */
+    fn vertex_star(
+        &self,
+        vertex: &Rc<Vertex>,
+        triangle: &Rc<Triangle>,
+    ) -> Vec<(Rc<Vertex>, Rc<Triangle>)> {
+        let mut star: Vec<(Rc<Vertex>, Rc<Triangle>)> = Vec::new();
+
+        /* clockwise rotation, across edges from the preceding vertices */
+        let mut current = Rc::clone(triangle);
+        for _ in 0..self.triangles.len() {
+            let previous = match current.opposite_edge(vertex) {
+                Some(edge) => Rc::clone(&edge.v2),
+                None => return star,
+            };
+            if !previous.is_ghost {
+                star.push((Rc::clone(&previous), Rc::clone(&current)));
+            }
+
+            current = match self.adjacency.get(&Edge::new(vertex, &previous)) {
+                Some(next) => Rc::clone(next),
+                None => break,
+            };
+            if current == *triangle {
+                return star;
+            }
+        }
+
+        /* counterclockwise rotation, across edges to the following vertices */
+        let mut current = Rc::clone(triangle);
+        for _ in 0..self.triangles.len() {
+            let next = match current.opposite_edge(vertex) {
+                Some(edge) => Rc::clone(&edge.v1),
+                None => break,
+            };
+            if !next.is_ghost {
+                star.push((Rc::clone(&next), Rc::clone(&current)));
+            }
+
+            current = match self.adjacency.get(&Edge::new(&next, vertex)) {
+                Some(previous) => Rc::clone(previous),
+                None => break,
+            };
+        }
+
+        return star;
+    }
+
+    /**
+     * Walks across the edges that have the vertex on their outer side, until
+     * the containing triangle is found, or until a ghost triangle is reached.
+     */
+    fn walk(&self, vertex: &Vertex) -> Option<Rc<Triangle>> {
+        let last_located = self
+            .last_located
+            .borrow()
+            .clone()
+            .filter(|t| self.triangles.contains(t));
+
+        let mut current = match last_located {
+            Some(triangle) => triangle,
+            None => Rc::clone(self.triangles.iter().find(|t| !t.is_ghost())?),
+        };
+
+        for _ in 0..self.triangles.len() {
+            let (e1, e2, e3) = current.inner_edges();
+            let crossed_edge = vec![e1, e2, e3]
+                .into_iter()
+                .find(|e| orientation(&e.v1, &e.v2, vertex) == Orientation::Clockwise);
+
+            match crossed_edge {
+                None => return Some(current),
+                Some(edge) => {
+                    current = Rc::clone(self.adjacency.get(&Rc::new(edge.opposite()))?);
+                    if current.is_ghost() {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        return None;
+    }
+}
+
+/**
+ * Local feature size at the vertex for the segments and their end vertices.
+ * Features are sorted by distance, so that the size is the distance of the
+ * first feature not incident to some closer one.
+ */
+pub(crate) fn feature_size(vertex: &Vertex, segments: &[Rc<Edge>]) -> Option<f64> {
+    let mut ends: Vec<Rc<Vertex>> = Vec::new();
+    for segment in segments.iter() {
+        for end in vec![&segment.v1, &segment.v2] {
+            if !ends.contains(end) {
+                ends.push(Rc::clone(end));
+            }
+        }
+    }
+
+    let mut features: Vec<(f64, Feature)> = segments
+        .iter()
+        .map(|s| (edge_distance(s, vertex), Feature::Segment(s)))
+        .chain(ends.iter().map(|v| (distance(v, vertex), Feature::Vertex(v))))
+        .collect();
+    features.sort_by(|(d1, _), (d2, _)| d1.partial_cmp(d2).unwrap());
+
+    for (index, (feature_distance, feature)) in features.iter().enumerate() {
+        if features[..index]
+            .iter()
+            .any(|(_, closer)| !closer.is_incident(feature))
+        {
+            return Some(*feature_distance);
+        }
+    }
+    return None;
+}
+
+enum Feature<'a> {
+    Segment(&'a Rc<Edge>),
+    Vertex(&'a Rc<Vertex>),
+}
+
+impl Feature<'_> {
+    /**
+     * Features sharing a vertex: segments with a common end, or a segment
+     * and its ends.
+     */
+    fn is_incident(&self, other: &Feature) -> bool {
+        match (self, other) {
+            (Feature::Segment(s1), Feature::Segment(s2)) => {
+                return s1.v1 == s2.v1 || s1.v1 == s2.v2 || s1.v2 == s2.v1 || s1.v2 == s2.v2;
+            }
+            (Feature::Segment(s), Feature::Vertex(v))
+            | (Feature::Vertex(v), Feature::Segment(s)) => {
+                return s.v1 == **v || s.v2 == **v;
+            }
+            (Feature::Vertex(v1), Feature::Vertex(v2)) => return v1 == v2,
+        }
     }
 }
 
@@ -116,6 +1193,45 @@ mod vertices {
         assert!(vertices.contains(&v3));
         assert!(vertices.contains(&v4));
     }
+
+    #[test]
+    fn incremental_index() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0, 1.0));
+        let v4 = Rc::new(Vertex::new(0.0, 1.0));
+        let ghost = Rc::new(Vertex::new_ghost());
+
+        let t1 = Rc::new(Triangle::new(&v1, &v2, &v3));
+        let t2 = Rc::new(Triangle::new(&v1, &v3, &v4));
+        let t3 = Rc::new(Triangle::new(&v2, &v1, &ghost));
+
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&t1);
+        triangulation.include_triangle(&t2);
+        triangulation.include_triangle(&t3);
+        assert!(!triangulation.include_triangle(&t1));
+        assert_eq!(triangulation.vertices().len(), 4);
+        assert!(!triangulation.vertices().contains(&ghost));
+        assert_eq!(triangulation.edges().len(), 9);
+
+        /* vertices leave the index along with their last triangle */
+        triangulation.remove_triangle(&t2);
+        assert_eq!(triangulation.vertices().len(), 3);
+        assert!(!triangulation.vertices().contains(&v4));
+        assert!(triangulation.vertices().contains(&v3));
+        assert!(!triangulation.remove_triangle(&t2));
+
+        let edges = triangulation.edges();
+        assert_eq!(edges.len(), 6);
+        assert!(edges.contains(&Edge::new(&v1, &v2)));
+        assert!(!edges.contains(&Edge::new(&v3, &v4)));
+
+        triangulation.remove_triangle(&t1);
+        triangulation.remove_triangle(&t3);
+        assert!(triangulation.vertices().is_empty());
+        assert!(triangulation.edges().is_empty());
+    }
 }
 
 #[cfg(test)]
@@ -151,4 +1267,729 @@ mod edges {
         assert!(edges.contains(&e23));
         assert!(edges.contains(&e34));
     }
+
+    #[test]
+    fn iterators() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0, 1.0));
+        let v4 = Rc::new(Vertex::new(0.0, 1.0));
+
+        /* square of two triangles, surrounded by ghost triangles */
+        let ghost = Rc::new(Vertex::new_ghost());
+        let mut triangulation = Triangulation::new();
+        for (a, b, c) in vec![
+            (&v1, &v2, &v3),
+            (&v1, &v3, &v4),
+            (&v2, &v1, &ghost),
+            (&v3, &v2, &ghost),
+            (&v4, &v3, &ghost),
+            (&v1, &v4, &ghost),
+        ] {
+            triangulation.include_triangle(&Rc::new(Triangle::new(a, b, c)));
+        }
+
+        assert_eq!(triangulation.solid_triangles().count(), 2);
+        assert!(triangulation.solid_triangles().all(|t| !t.is_ghost()));
+        assert_eq!(triangulation.ghost_triangles().count(), 4);
+
+        let interior: Vec<Rc<Edge>> = triangulation.interior_edges().collect();
+        assert_eq!(interior.len(), 1);
+        assert!(interior[0].contains(&v1) && interior[0].contains(&v3));
+
+        let boundary: HashSet<Rc<Edge>> = triangulation.boundary_edges().collect();
+        assert_eq!(boundary.len(), 4);
+        assert!(boundary.contains(&Edge::new(&v1, &v2)));
+        assert!(boundary.contains(&Edge::new(&v4, &v1)));
+
+        triangulation.constrain(&Rc::new(Edge::new(&v3, &v1)));
+        triangulation.constrain(&Rc::new(Edge::new(&v2, &v1)));
+        let constrained: HashSet<Rc<Edge>> = triangulation.constrained_edges().collect();
+        assert_eq!(constrained.len(), 2);
+        assert!(constrained.contains(&Edge::new(&v1, &v2)));
+    }
+
+    #[test]
+    fn constraint_marks() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0, 0.0));
+        let edge = Rc::new(Edge::new(&v1, &v2));
+
+        let mut triangulation = Triangulation::new();
+        assert!(triangulation.constrain(&edge));
+        assert!(!triangulation.constrain(&Rc::new(edge.opposite())));
+        assert!(triangulation.is_constrained(&edge));
+        assert!(triangulation.is_constrained(&edge.opposite()));
+
+        let half_1 = Rc::new(Edge::new(&v1, &v3));
+        let half_2 = Rc::new(Edge::new(&v3, &v2));
+        assert!(triangulation.split_constraint(&edge.opposite(), &half_1, &half_2));
+        assert!(!triangulation.is_constrained(&edge));
+        assert!(triangulation.is_constrained(&half_1));
+        assert!(triangulation.is_constrained(&half_2.opposite()));
+        assert!(!triangulation.split_constraint(&edge, &half_1, &half_2));
+
+        assert!(triangulation.unconstrain(&half_1.opposite()));
+        assert!(!triangulation.unconstrain(&half_1));
+        assert_eq!(triangulation.constraints().len(), 1);
+    }
 }
+
+#[cfg(test)]
+mod locate {
+    use super::*;
+    use crate::elements::polyline::*;
+    use crate::planar::triangulation_procedures;
+
+    fn square_with_hole() -> Triangulation {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(3.0, 0.0));
+        let v3 = Rc::new(Vertex::new(3.0, 3.0));
+        let v4 = Rc::new(Vertex::new(0.0, 3.0));
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![Rc::clone(&v1), Rc::clone(&v2), v3, v4]).unwrap(),
+        );
+
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(1.0, 1.0)),
+                Rc::new(Vertex::new(2.0, 1.0)),
+                Rc::new(Vertex::new(2.0, 2.0)),
+                Rc::new(Vertex::new(1.0, 2.0)),
+            ])
+            .unwrap(),
+        );
+
+        let mut triangulation = Triangulation::from_initial_segment((&v1, &v2));
+        triangulation_procedures::boundary::include(&mut triangulation, &boundary, &HashSet::new());
+        triangulation_procedures::hole::include(&mut triangulation, &hole, &HashSet::new()).unwrap();
+        return triangulation;
+    }
+
+    #[test]
+    fn walk() {
+        let triangulation = square_with_hole();
+
+        for (x, y) in vec![(0.1, 0.2), (2.9, 2.8), (1.5, 0.5), (0.5, 1.5), (2.5, 2.5)] {
+            let vertex = Vertex::new(x, y);
+            let triangle = triangulation.locate(&vertex).unwrap();
+            assert!(!triangle.is_ghost());
+            assert_ne!(triangle.contains(&vertex), Continence::Outside);
+        }
+
+        /* inside the hole and out of the boundary */
+        assert!(triangulation.locate(&Vertex::new(1.5, 1.5)).is_none());
+        assert!(triangulation.locate(&Vertex::new(4.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn spatial_index() {
+        let mut triangulation = square_with_hole();
+        triangulation.build_spatial_index();
+        assert!(triangulation.spatial_index().is_some());
+
+        let vertex = Vertex::new(2.6, 0.3);
+        let triangle = triangulation.locate(&vertex).unwrap();
+        assert_ne!(triangle.contains(&vertex), Continence::Outside);
+        assert!(triangulation.locate(&Vertex::new(1.5, 1.5)).is_none());
+
+        /* changes drop the index */
+        triangulation.remove_triangle(&triangle);
+        assert!(triangulation.spatial_index().is_none());
+        assert!(triangulation.locate(&vertex).is_none());
+    }
+} /* end - locate tests */
+
+#[cfg(test)]
+mod nearest {
+    use super::*;
+    use crate::elements::bounding_box::*;
+    use crate::planar::structured;
+
+    fn grid() -> Triangulation {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(4.0, 4.0)),
+        ])
+        .unwrap();
+        return structured::rectangle(&bounding_box, 4, 4).unwrap();
+    }
+
+    #[test]
+    fn nearest_vertex() {
+        let triangulation = grid();
+        assert_eq!(
+            triangulation.nearest_vertex(&Vertex::new(2.2, 2.9)),
+            Some(Rc::new(Vertex::new(2.0, 3.0)))
+        );
+        assert_eq!(
+            triangulation.nearest_vertex(&Vertex::new(-3.0, 5.0)),
+            Some(Rc::new(Vertex::new(0.0, 4.0)))
+        );
+        assert_eq!(Triangulation::new().nearest_vertex(&Vertex::new(0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn nearest_vertex_indexed() {
+        let mut triangulation = grid();
+        triangulation.build_spatial_index();
+        assert_eq!(
+            triangulation.nearest_vertex(&Vertex::new(2.2, 2.9)),
+            Some(Rc::new(Vertex::new(2.0, 3.0)))
+        );
+        assert_eq!(
+            triangulation.nearest_vertex(&Vertex::new(-3.0, 5.0)),
+            Some(Rc::new(Vertex::new(0.0, 4.0)))
+        );
+    }
+
+    #[test]
+    fn k_nearest() {
+        let triangulation = grid();
+        let nearest = triangulation.k_nearest(&Vertex::new(1.1, 1.2), 4);
+        assert_eq!(
+            nearest,
+            vec![
+                Rc::new(Vertex::new(1.0, 1.0)),
+                Rc::new(Vertex::new(1.0, 2.0)),
+                Rc::new(Vertex::new(2.0, 1.0)),
+                Rc::new(Vertex::new(0.0, 1.0)),
+            ]
+        );
+
+        assert_eq!(triangulation.k_nearest(&Vertex::new(1.1, 1.2), 100).len(), 25);
+        assert!(triangulation.k_nearest(&Vertex::new(1.1, 1.2), 0).is_empty());
+    }
+
+    #[test]
+    fn without_ghosts() {
+        /* rotation resumes in the opposite direction at the border */
+        let mut triangulation = grid();
+        let ghosts: Vec<Rc<Triangle>> =
+            triangulation.triangles.iter().filter(|t| t.is_ghost()).cloned().collect();
+        for ghost in ghosts.iter() {
+            triangulation.remove_triangle(ghost);
+        }
+
+        let nearest = triangulation.k_nearest(&Vertex::new(0.1, 0.0), 3);
+        assert_eq!(
+            nearest,
+            vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(1.0, 0.0)),
+                Rc::new(Vertex::new(0.0, 1.0)),
+            ]
+        );
+    }
+} /* end - nearest tests */
+
+#[cfg(test)]
+mod dynamic_updates {
+    use super::*;
+    use crate::elements::bounding_box::*;
+    use crate::planar::structured;
+
+    fn grid() -> Triangulation {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(4.0, 4.0)),
+        ])
+        .unwrap();
+        return structured::rectangle(&bounding_box, 4, 4).unwrap();
+    }
+
+    fn is_delaunay(triangulation: &Triangulation) -> bool {
+        let vertices = triangulation.vertices();
+        return triangulation.triangles.iter().filter(|t| !t.is_ghost()).all(|t| {
+            vertices
+                .iter()
+                .all(|v| t.encircles(v) != Continence::Inside)
+        });
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut triangulation = grid();
+        let triangles = triangulation.triangles.clone();
+
+        let vertex = Rc::new(Vertex::new(1.3, 1.6));
+        assert!(triangulation.insert_vertex(&vertex));
+        assert!(triangulation.vertices().contains(&vertex));
+        assert!(is_delaunay(&triangulation));
+        assert!(!triangulation.insert_vertex(&vertex));
+        assert!(!triangulation.insert_vertex(&Rc::new(Vertex::new(5.0, 1.0))));
+
+        assert!(triangulation.remove_vertex(&vertex));
+        assert!(!triangulation.vertices().contains(&vertex));
+        assert_eq!(triangulation.triangles.len(), triangles.len());
+        assert!(is_delaunay(&triangulation));
+    }
+
+    #[test]
+    fn remove_grid_vertex() {
+        let mut triangulation = grid();
+        let solid_count = |t: &Triangulation| t.triangles.iter().filter(|t| !t.is_ghost()).count();
+
+        let vertex = Rc::new(Vertex::new(2.0, 2.0));
+        assert!(triangulation.remove_vertex(&vertex));
+        assert_eq!(solid_count(&triangulation), 30);
+        assert!(is_delaunay(&triangulation));
+
+        /* every edge has its opposite */
+        for edge in triangulation.adjacency.keys() {
+            assert!(triangulation.adjacency.contains_key(&edge.opposite()));
+        }
+
+        /* border vertices are kept */
+        let border = Rc::new(Vertex::new(2.0, 0.0));
+        assert!(!triangulation.remove_vertex(&border));
+    }
+
+    #[test]
+    fn constraints() {
+        let mut triangulation = grid();
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(2.0, 1.0));
+        triangulation.constrain(&Rc::new(Edge::new(&v1, &v2)));
+
+        assert!(!triangulation.remove_vertex(&v1));
+        assert!(!triangulation.insert_vertex(&Rc::new(Vertex::new(1.5, 1.0))));
+        assert!(triangulation.move_vertex(&v2, Vertex::new(2.1, 1.1)).is_err());
+
+        /* the cavity stops at the constraint */
+        let vertex = Rc::new(Vertex::new(1.5, 1.1));
+        assert!(triangulation.insert_vertex(&vertex));
+        assert!(triangulation.adjacency.contains_key(&Edge::new(&v1, &v2)));
+    }
+
+    #[test]
+    fn flip_edges() {
+        let mut triangulation = grid();
+        let count = triangulation.triangles.len();
+
+        let edge = triangulation
+            .adjacency
+            .iter()
+            .find(|(e, t)| {
+                !t.is_ghost()
+                    && triangulation
+                        .adjacency
+                        .get(&e.opposite())
+                        .map_or(false, |t| !t.is_ghost())
+            })
+            .map(|(e, _)| e)
+            .unwrap();
+        let v3 = triangulation.adjacency.get(&edge).unwrap().opposite_vertex(&edge).unwrap();
+
+        assert!(triangulation.flip_edge(&edge));
+        assert_eq!(triangulation.triangles.len(), count);
+        assert!(!triangulation.adjacency.contains_key(&edge));
+        /* the other diagonal crosses the edge at its midpoint */
+        let midpoint = Vertex::new((edge.v1.x + edge.v2.x) / 2.0, (edge.v1.y + edge.v2.y) / 2.0);
+        let flipped = triangulation
+            .adjacency
+            .keys()
+            .find(|e| e.v2 == v3 && e.contains(&midpoint))
+            .unwrap();
+        assert!(triangulation.flip_edge(&flipped));
+        assert!(triangulation.adjacency.contains_key(&edge));
+
+        /* border edges have no solid triangle on their outer side */
+        let border = Edge::new(&Rc::new(Vertex::new(0.0, 0.0)), &Rc::new(Vertex::new(1.0, 0.0)));
+        assert!(!triangulation.flip_edge(&border));
+        assert!(!triangulation.flip_edge(&border.opposite()));
+    }
+
+    #[test]
+    fn restore_after_moves() {
+        let mut triangulation = grid();
+        let vertex = Rc::new(Vertex::new(1.3, 1.6));
+        assert!(triangulation.insert_vertex(&vertex));
+
+        /* flips an edge around the vertex, as moving it would break the property */
+        let star: Vec<Rc<Triangle>> = triangulation
+            .triangles
+            .iter()
+            .filter(|t| !t.is_ghost() && t.opposite_edge(&vertex).is_some())
+            .cloned()
+            .collect();
+        let is_flipped = star.iter().any(|t| {
+            let spoke = Edge::new(&vertex, &t.opposite_edge(&vertex).unwrap().v1);
+            return triangulation.flip_edge(&spoke);
+        });
+        assert!(is_flipped);
+        assert!(!is_delaunay(&triangulation));
+
+        /* constrained edges are kept */
+        let region: Vec<Rc<Triangle>> = triangulation.triangles.iter().cloned().collect();
+        let constrained: Vec<Rc<Edge>> = triangulation
+            .edges()
+            .iter()
+            .filter(|e| !e.v1.is_ghost && !e.v2.is_ghost)
+            .collect();
+        for edge in constrained.iter() {
+            triangulation.constrain(edge);
+        }
+        assert_eq!(triangulation.restore_delaunay(&region), 0);
+        assert!(!is_delaunay(&triangulation));
+
+        for edge in constrained.iter() {
+            triangulation.unconstrain(edge);
+        }
+        assert!(triangulation.restore_delaunay(&region) > 0);
+        assert!(is_delaunay(&triangulation));
+        assert!(triangulation.vertices().contains(&vertex));
+    }
+
+    #[test]
+    fn move_vertices() {
+        let mut triangulation = grid();
+        let count = triangulation.triangles.len();
+
+        let vertex = Rc::new(Vertex::new(2.0, 2.0));
+        let moved = triangulation
+            .move_vertex(&vertex, Vertex::new(2.3, 2.2))
+            .unwrap();
+        assert_eq!(*moved, Vertex::new(2.3, 2.2));
+        assert!(triangulation.vertices().contains(&moved));
+        assert!(!triangulation.vertices().contains(&vertex));
+        assert_eq!(triangulation.triangles.len(), count);
+        assert!(is_delaunay(&triangulation));
+
+        /* out of the kernel of its star */
+        let triangles = triangulation.triangles.clone();
+        let conflicting = triangulation
+            .move_vertex(&moved, Vertex::new(3.5, 3.5))
+            .unwrap_err();
+        assert!(!conflicting.is_empty());
+        assert!(conflicting.iter().all(|t| triangles.contains(t)));
+        assert_eq!(triangulation.triangles, triangles);
+
+        /* hull vertices are kept */
+        let corner = Rc::new(Vertex::new(0.0, 0.0));
+        assert!(triangulation
+            .move_vertex(&corner, Vertex::new(0.1, 0.1))
+            .is_err());
+        assert!(triangulation
+            .move_vertex(&Rc::new(Vertex::new(9.0, 9.0)), Vertex::new(1.0, 1.0))
+            .unwrap_err()
+            .is_empty());
+        assert_eq!(triangulation.triangles, triangles);
+    }
+} /* end - dynamic_updates tests */
+
+#[cfg(test)]
+mod hull {
+    use super::*;
+    use crate::elements::bounding_box::*;
+    use crate::planar::structured;
+
+    #[test]
+    fn grid_hull() {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(4.0, 4.0)),
+        ])
+        .unwrap();
+        let mut triangulation = structured::rectangle(&bounding_box, 4, 4).unwrap();
+        assert_eq!(triangulation.convex_hull().unwrap().vertices.len(), 4);
+
+        /* removing a corner cuts the hull */
+        let corner = Rc::new(Vertex::new(4.0, 4.0));
+        let corner_triangles: Vec<Rc<Triangle>> = triangulation
+            .triangles
+            .iter()
+            .filter(|t| t.v1 == corner || t.v2 == corner || t.v3 == corner)
+            .cloned()
+            .collect();
+        for triangle in corner_triangles.iter() {
+            triangulation.remove_triangle(triangle);
+        }
+        let hull = triangulation.convex_hull().unwrap();
+        assert_eq!(hull.vertices.len(), 5);
+        assert!(!hull.vertices.contains(&corner));
+    }
+
+    #[test]
+    fn extract_region() {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(4.0, 4.0)),
+        ])
+        .unwrap();
+        let triangulation = structured::rectangle(&bounding_box, 4, 4).unwrap();
+
+        let (boundaries, triangles) = triangulation.extract_region(|t| t.center().x < 2.0);
+        assert_eq!(boundaries.len(), 1);
+        assert_eq!(boundaries[0].area(), Some(8.0));
+        assert_eq!(triangles.len(), 16);
+
+        /* triangles out of the central square make an annulus */
+        let (boundaries, triangles) = triangulation.extract_region(|t| {
+            let center = t.center();
+            return center.x < 1.0 || center.x > 3.0 || center.y < 1.0 || center.y > 3.0;
+        });
+        assert_eq!(boundaries.len(), 2);
+        assert_eq!(boundaries[0].area(), Some(16.0));
+        assert_eq!(boundaries[1].area(), Some(-4.0));
+        assert_eq!(triangles.len(), 24);
+
+        let (boundaries, triangles) = triangulation.extract_region(|_| false);
+        assert!(boundaries.is_empty());
+        assert!(triangles.is_empty());
+    }
+
+    #[test]
+    fn flood_fill() {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(4.0, 4.0)),
+        ])
+        .unwrap();
+        let mut triangulation = structured::rectangle(&bounding_box, 4, 4).unwrap();
+
+        /* a wall along x = 2 splits the grid in halves */
+        for y in 0..4 {
+            let wall = Rc::new(Edge::new(
+                &Rc::new(Vertex::new(2.0, y as f64)),
+                &Rc::new(Vertex::new(2.0, y as f64 + 1.0)),
+            ));
+            triangulation.constrain(&wall);
+        }
+        let start = triangulation.locate(&Vertex::new(0.2, 0.1)).unwrap();
+        let region = triangulation.flood_fill(&start, |e| !triangulation.is_constrained(e));
+        assert_eq!(region.len(), 16);
+        assert!(region.iter().all(|t| t.center().x < 2.0));
+
+        /* crossing every edge reaches the whole grid, crossing none stays */
+        assert_eq!(triangulation.flood_fill(&start, |_| true).len(), 32);
+        assert_eq!(triangulation.flood_fill(&start, |_| false).len(), 1);
+
+        let ghost = triangulation.ghost_triangles().next().cloned().unwrap();
+        assert!(triangulation.flood_fill(&ghost, |_| true).is_empty());
+    }
+
+    #[test]
+    fn submesh() {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(4.0, 4.0)),
+        ])
+        .unwrap();
+        let mut triangulation = structured::rectangle(&bounding_box, 4, 4).unwrap();
+        let crossing = Rc::new(Edge::new(
+            &Rc::new(Vertex::new(1.0, 0.0)),
+            &Rc::new(Vertex::new(1.0, 1.0)),
+        ));
+        let outside = Rc::new(Edge::new(
+            &Rc::new(Vertex::new(3.0, 0.0)),
+            &Rc::new(Vertex::new(3.0, 1.0)),
+        ));
+        assert!(triangulation.constrain(&crossing));
+        assert!(triangulation.constrain(&outside));
+
+        let (_, left) = triangulation.extract_region(|t| t.center().x < 2.0);
+        let submesh = triangulation.submesh(&left.iter().cloned().collect());
+        assert!(submesh.validate().is_ok());
+        assert_eq!(submesh.solid_triangles().count(), 16);
+        assert_eq!(submesh.ghost_loops().len(), 1);
+        assert_eq!(submesh.ghost_triangles().count(), 12);
+        assert_eq!(submesh.constraints().len(), 1);
+        assert!(submesh.is_constrained(&crossing));
+
+        /* vertices are copies, not shared with the source */
+        for vertex in submesh.vertices().iter().filter(|v| !v.is_ghost) {
+            let source = triangulation.vertices().iter().find(|&v| v == vertex).unwrap();
+            assert!(!Rc::ptr_eq(vertex, source));
+        }
+
+        /* an annulus has loops of its own */
+        let (_, annulus) = triangulation.extract_region(|t| {
+            let center = t.center();
+            return center.x < 1.0 || center.x > 3.0 || center.y < 1.0 || center.y > 3.0;
+        });
+        let submesh = triangulation.submesh(&annulus.iter().cloned().collect());
+        assert!(submesh.validate().is_ok());
+        assert_eq!(submesh.ghost_loops().len(), 2);
+    }
+
+    #[test]
+    fn merge() {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(4.0, 4.0)),
+        ])
+        .unwrap();
+        let triangulation = structured::rectangle(&bounding_box, 4, 4).unwrap();
+        let (_, left) = triangulation.extract_region(|t| t.center().x < 2.0);
+        let (_, right) = triangulation.extract_region(|t| t.center().x > 2.0);
+        let left = triangulation.submesh(&left.into_iter().collect());
+        let right = triangulation.submesh(&right.into_iter().collect());
+
+        let chain = |x: f64, length: usize| {
+            let vertices = (0..=length).map(|y| Rc::new(Vertex::new(x, y as f64))).collect();
+            return Polyline::new_opened(vertices).unwrap();
+        };
+
+        let merged = Triangulation::merge(&left, &right, &chain(2.0, 4)).unwrap();
+        assert!(merged.validate().is_ok());
+        assert_eq!(merged.solid_triangles().count(), 32);
+        assert_eq!(merged.ghost_loops().len(), 1);
+        assert_eq!(merged.boundary_edges().count(), 16);
+
+        /* chains off the shared border, or covering part of it */
+        assert!(Triangulation::merge(&left, &right, &chain(1.0, 4)).is_err());
+        assert!(Triangulation::merge(&left, &right, &chain(2.0, 2)).is_err());
+    }
+} /* end - hull tests */
+
+#[cfg(test)]
+mod local_feature_size {
+    use super::*;
+
+    /* square with an inner constraint segment, nearer its bottom side */
+    fn square() -> (Triangulation, HashSet<Rc<Edge>>) {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(4.0, 0.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(0.0, 4.0));
+        let v5 = Rc::new(Vertex::new(1.0, 1.0));
+        let v6 = Rc::new(Vertex::new(3.0, 1.0));
+
+        let mut triangulation = Triangulation::new();
+        for (a, b, c) in vec![
+            (&v1, &v2, &v6),
+            (&v1, &v6, &v5),
+            (&v1, &v5, &v4),
+            (&v2, &v3, &v6),
+            (&v6, &v3, &v5),
+            (&v5, &v3, &v4),
+        ] {
+            triangulation.include_triangle(&Rc::new(Triangle::new(a, b, c)));
+        }
+
+        let segments: HashSet<Rc<Edge>> = vec![
+            Edge::new(&v1, &v2),
+            Edge::new(&v2, &v3),
+            Edge::new(&v3, &v4),
+            Edge::new(&v4, &v1),
+            Edge::new(&v5, &v6),
+        ]
+        .into_iter()
+        .map(Rc::new)
+        .collect();
+        return (triangulation, segments);
+    }
+
+    #[test]
+    fn between_features() {
+        let (triangulation, segments) = square();
+
+        /* the segment and the bottom side, which do not touch */
+        let size = triangulation.local_feature_size(&Vertex::new(2.0, 0.5), &segments);
+        assert!((size.unwrap() - 0.5).abs() < 1.0E-12);
+
+        /* at a corner, both sides are incident, the inner segment is not */
+        let size = triangulation.local_feature_size(&Vertex::new(0.0, 0.0), &segments);
+        assert!((size.unwrap() - 2.0_f64.sqrt()).abs() < 1.0E-12);
+
+        /* at the segment end, the left side is one unit away */
+        let size = triangulation.local_feature_size(&Vertex::new(1.0, 1.0), &segments);
+        assert!((size.unwrap() - 1.0).abs() < 1.0E-12);
+    }
+
+    #[test]
+    fn with_spatial_index() {
+        let (mut triangulation, segments) = square();
+        let probes = vec![
+            Vertex::new(2.0, 0.5),
+            Vertex::new(0.0, 0.0),
+            Vertex::new(1.0, 1.0),
+            Vertex::new(3.5, 3.0),
+            Vertex::new(2.0, 3.9),
+        ];
+        let sizes: Vec<Option<f64>> = probes
+            .iter()
+            .map(|p| triangulation.local_feature_size(p, &segments))
+            .collect();
+
+        triangulation.build_spatial_index();
+        for (probe, size) in probes.iter().zip(sizes.iter()) {
+            let indexed = triangulation.local_feature_size(probe, &segments);
+            assert!((indexed.unwrap() - size.unwrap()).abs() < 1.0E-12);
+        }
+    }
+
+    #[test]
+    fn single_segment() {
+        let (triangulation, _) = square();
+        let segment = Rc::new(Edge::new(
+            &Rc::new(Vertex::new(1.0, 1.0)),
+            &Rc::new(Vertex::new(3.0, 1.0)),
+        ));
+        let segments: HashSet<Rc<Edge>> = vec![segment].into_iter().collect();
+
+        /* the segment ends are the only non incident features */
+        let size = triangulation.local_feature_size(&Vertex::new(2.0, 1.0), &segments);
+        assert!((size.unwrap() - 1.0).abs() < 1.0E-12);
+        assert_eq!(
+            triangulation.local_feature_size(&Vertex::new(2.0, 1.0), &HashSet::new()),
+            None
+        );
+    }
+} /* end - local_feature_size tests */
+
+#[cfg(test)]
+mod ghost_loops {
+    use super::*;
+
+    fn square(x: f64, y: f64) -> Triangulation {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(x, y)),
+                Rc::new(Vertex::new(x + 1.0, y)),
+                Rc::new(Vertex::new(x + 1.0, y + 1.0)),
+                Rc::new(Vertex::new(x, y + 1.0)),
+            ])
+            .unwrap(),
+        );
+        let mut triangulation = Triangulation::from_initial_segment((
+            &boundary.vertices[0],
+            &boundary.vertices[1],
+        ));
+        triangulation_procedures::boundary::include(&mut triangulation, &boundary, &HashSet::new());
+        for edge in boundary.into_edges().iter() {
+            triangulation.constrain(edge);
+        }
+        return triangulation;
+    }
+
+    #[test]
+    fn disconnected_components() {
+        let mut triangulation = square(0.0, 0.0);
+        assert_eq!(triangulation.ghost_loops(), vec![Rc::new(Vertex::new_ghost())]);
+
+        assert!(triangulation.include_component(&square(2.0, 0.0)));
+        assert!(!triangulation.include_component(&square(2.0, 0.0)));
+        assert!(triangulation.validate().is_ok());
+        assert_eq!(triangulation.solid_triangles().count(), 4);
+        assert_eq!(triangulation.constraints().len(), 8);
+
+        let ghosts = triangulation.ghost_loops();
+        assert_eq!(ghosts.len(), 2);
+        for ghost in ghosts.iter() {
+            let edges = triangulation.loop_edges(ghost);
+            assert_eq!(edges.len(), 4);
+            let left = edges.iter().all(|e| e.v1.x < 1.5);
+            assert_eq!(left, ghost.ghost_loop() == Some(0));
+        }
+        assert_eq!(triangulation.new_ghost_loop().ghost_loop(), Some(2));
+
+        /* insertions stay in their component */
+        let vertex = Rc::new(Vertex::new(2.5, 0.25));
+        assert!(triangulation.insert_vertex(&vertex));
+        assert!(triangulation.validate().is_ok());
+        assert_eq!(triangulation.solid_triangles().count(), 6);
+    }
+} /* end - ghost_loops tests */
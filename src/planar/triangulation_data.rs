@@ -1,4 +1,9 @@
+use crate::elements::{triangle::*, vertex::*};
+use crate::planar::{structured, triangulation::*};
+
+use std::collections::HashMap;
 use std::fmt;
+use std::rc::Rc;
 
 /**
  * Triangulation is defined by point coordinates and triangle indices.
@@ -10,6 +15,7 @@ use std::fmt;
  *  - Coordinates Vec must be 2*n, where n is the quantity of points.
  *  - Triangles Vec must be 3*t, where t is the quantity of triangles.
  */
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TriangulationData {
     pub coordinates: Vec<f64>,
     pub triangles: Vec<usize>,
@@ -22,6 +28,87 @@ impl TriangulationData {
             triangles: triangles,
         }
     }
+
+    /**
+     * Index based representation of the solid triangles. Points are sorted
+     * by coordinates, and triangles by indices, starting at their lowest
+     * index, so that equal triangulations have equal data.
+     */
+    pub fn from_triangulation(triangulation: &Triangulation) -> Self {
+        let mut vertices: Vec<Rc<Vertex>> = triangulation.vertices().iter().cloned().collect();
+        vertices.sort_by(|v1, v2| v1.partial_cmp(v2).unwrap());
+        let indices: HashMap<Rc<Vertex>, usize> = vertices
+            .iter()
+            .enumerate()
+            .map(|(index, v)| (Rc::clone(v), index))
+            .collect();
+
+        let mut triangles: Vec<[usize; 3]> = triangulation
+            .solid_triangles()
+            .map(|t| {
+                let t = t.ensure_ccw();
+                let corners = [indices[&t.v1], indices[&t.v2], indices[&t.v3]];
+                let lowest = (0..3).min_by_key(|&i| corners[i]).unwrap();
+                return [
+                    corners[lowest],
+                    corners[(lowest + 1) % 3],
+                    corners[(lowest + 2) % 3],
+                ];
+            })
+            .collect();
+        triangles.sort();
+
+        return Self {
+            coordinates: vertices.iter().map(|v| vec![v.x, v.y]).flatten().collect(),
+            triangles: triangles.iter().map(|t| t.to_vec()).flatten().collect(),
+        };
+    }
+
+    /**
+     * Triangulation of the data, sharing a vertex for each point, with ghost
+     * triangles at every border edge. Returns None if the lengths are not
+     * multiples of 2 and 3, or if any index is out of the points.
+     */
+    pub fn to_triangulation(&self) -> Option<Triangulation> {
+        if self.coordinates.len() % 2 != 0 || self.triangles.len() % 3 != 0 {
+            return None;
+        }
+        let vertices: Vec<Rc<Vertex>> = self
+            .coordinates
+            .chunks(2)
+            .map(|xy| Rc::new(Vertex::new(xy[0], xy[1])))
+            .collect();
+
+        let mut triangulation = Triangulation::new();
+        for corners in self.triangles.chunks(3) {
+            triangulation.include_triangle(&Rc::new(Triangle::new(
+                vertices.get(corners[0])?,
+                vertices.get(corners[1])?,
+                vertices.get(corners[2])?,
+            )));
+        }
+        structured::include_ghosts(&mut triangulation);
+
+        return Some(triangulation);
+    }
+}
+
+/* triangulations are serialized as their index based data */
+#[cfg(feature = "serialize")]
+impl serde::Serialize for Triangulation {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return TriangulationData::from_triangulation(self).serialize(serializer);
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de> serde::Deserialize<'de> for Triangulation {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = TriangulationData::deserialize(deserializer)?;
+        return data
+            .to_triangulation()
+            .ok_or_else(|| serde::de::Error::custom("invalid triangulation data"));
+    }
 }
 
 impl fmt::Display for TriangulationData {
@@ -45,3 +132,75 @@ impl fmt::Display for TriangulationData {
         return write!(formatter, "");
     }
 }
+
+#[cfg(test)]
+mod triangulation_data {
+    use super::*;
+    use crate::elements::bounding_box::*;
+
+    fn grid() -> Triangulation {
+        let bounding_box = BoundingBox::from_vertices(vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(2.0, 1.0)),
+        ])
+        .unwrap();
+        return structured::rectangle(&bounding_box, 2, 1).unwrap();
+    }
+
+    #[test]
+    fn round_trip() {
+        let triangulation = grid();
+        let data = TriangulationData::from_triangulation(&triangulation);
+        assert_eq!(
+            data.coordinates,
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 2.0, 0.0, 2.0, 1.0]
+        );
+        assert_eq!(data.triangles, vec![0, 2, 3, 0, 3, 1, 2, 4, 5, 2, 5, 3]);
+
+        let restored = data.to_triangulation().unwrap();
+        assert_eq!(restored.triangles, triangulation.triangles);
+        assert_eq!(restored.adjacency.len(), triangulation.adjacency.len());
+
+        let invalid = TriangulationData::from(vec![0.0, 0.0], vec![0, 1, 2]);
+        assert!(invalid.to_triangulation().is_none());
+    }
+
+    #[test]
+    fn counterclockwise() {
+        /* one triangle included clockwise */
+        let mut triangulation = grid();
+        let triangle = Rc::clone(triangulation.solid_triangles().next().unwrap());
+        triangulation.remove_triangle(&triangle);
+        triangulation.include_triangle(&Rc::new(Triangle::new(
+            &triangle.v1,
+            &triangle.v3,
+            &triangle.v2,
+        )));
+
+        let data = TriangulationData::from_triangulation(&triangulation);
+        assert_eq!(data.triangles.len(), 12);
+        for corners in data.triangles.chunks(3) {
+            let point =
+                |index: usize| (data.coordinates[2 * index], data.coordinates[2 * index + 1]);
+            let ((x1, y1), (x2, y2), (x3, y3)) =
+                (point(corners[0]), point(corners[1]), point(corners[2]));
+            assert!((x2 - x1) * (y3 - y1) - (x3 - x1) * (y2 - y1) > 0.0);
+        }
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn serde() {
+        let triangulation = grid();
+        let json = serde_json::to_string(&triangulation).unwrap();
+        let restored: Triangulation = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.triangles, triangulation.triangles);
+
+        let vertex: Vertex =
+            serde_json::from_str("{\"x\":1.0,\"y\":2.0,\"is_ghost\":false}").unwrap();
+        assert_eq!(vertex, Vertex::new(1.0, 2.0));
+        let triangle = triangulation.triangles.iter().next().unwrap();
+        let json = serde_json::to_string(triangle).unwrap();
+        assert_eq!(serde_json::from_str::<Triangle>(&json).unwrap(), **triangle);
+    }
+} /* end - triangulation_data tests */
@@ -0,0 +1,259 @@
+use crate::elements::{edge::*, triangle::*, vertex::*};
+use crate::planar::{refine_params::ParamError, validation::InvariantViolation};
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::rc::Rc;
+
+/**
+ * Failures of parsing, constraint insertion and triangulation.
+ *  - OutsideBoundary: constraints that are not inside the boundary, or that
+ * are not outside the holes. Carries the conflicting vertices and segments.
+ *  - SelfIntersectingPolyline: polyline whose edges intersect each other.
+ * Carries the polyline vertices.
+ *  - DegenerateInput: input that does not describe a valid geometry, such as
+ * polylines with too few vertices.
+ *  - UnrecoverableConstraint: constraint segment that could not be recovered
+ * in the triangulation. Carries every recovery step attempted.
+ *  - NonTerminatingRefinement: refinement creating edges shorter than the
+ * edge length floor, as for too strict quality parameters. Carries the
+ * triangles around the short edges.
+ *  - InvalidTriangulation: triangulation breaking its invariants, as found
+ * by validation. Carries every violation.
+ *  - InvalidParams: refinement params that cannot be met, as found by
+ * RefineParams::validated.
+ *  - IoError: failure reading or writing files.
+ *  - Cancelled: triangulation aborted by its cancellation token.
+ */
+#[derive(Debug)]
+pub enum TriangulationError {
+    OutsideBoundary {
+        vertices: HashSet<Rc<Vertex>>,
+        segments: HashSet<Rc<Edge>>,
+    },
+    SelfIntersectingPolyline(Vec<Rc<Vertex>>),
+    DegenerateInput(String),
+    UnrecoverableConstraint {
+        segment: Rc<Edge>,
+        steps: Vec<RecoveryStep>,
+    },
+    NonTerminatingRefinement {
+        triangles: Vec<Rc<Triangle>>,
+        edge_length_floor: f64,
+    },
+    InvalidTriangulation(Vec<InvariantViolation>),
+    InvalidParams(ParamError),
+    IoError(io::Error),
+    Cancelled,
+}
+
+/**
+ * Single attempt to recover a constraint segment: the cavity of triangles
+ * retriangulated around the segment, and the triangulation edges still
+ * crossing the segment afterwards, which block its recovery.
+ */
+#[derive(Clone, Debug)]
+pub struct RecoveryStep {
+    pub cavity: Vec<Rc<Triangle>>,
+    pub blocking_edges: Vec<Rc<Edge>>,
+}
+
+impl TriangulationError {
+    pub fn outside_vertices(vertices: HashSet<Rc<Vertex>>) -> Self {
+        return TriangulationError::OutsideBoundary {
+            vertices,
+            segments: HashSet::new(),
+        };
+    }
+
+    pub fn outside_segments(segments: HashSet<Rc<Edge>>) -> Self {
+        return TriangulationError::OutsideBoundary {
+            vertices: HashSet::new(),
+            segments,
+        };
+    }
+}
+
+impl fmt::Display for TriangulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TriangulationError::OutsideBoundary { vertices, segments } => {
+                write!(f, "Constraints out of the boundary or inside holes:")?;
+                for vertex in vertices.iter() {
+                    write!(f, " {}", vertex)?;
+                }
+                for segment in segments.iter() {
+                    write!(f, " {}", segment)?;
+                }
+                return Ok(());
+            }
+            TriangulationError::SelfIntersectingPolyline(vertices) => {
+                write!(f, "Self intersecting polyline:")?;
+                for vertex in vertices.iter() {
+                    write!(f, " {}", vertex)?;
+                }
+                return Ok(());
+            }
+            TriangulationError::DegenerateInput(message) => {
+                return write!(f, "Degenerate input: {}", message);
+            }
+            TriangulationError::UnrecoverableConstraint { segment, steps } => {
+                write!(f, "Failed to recover constraint segment {}", segment)?;
+                for (index, step) in steps.iter().enumerate() {
+                    write!(f, "\n  step {}: cavity", index + 1)?;
+                    for triangle in step.cavity.iter() {
+                        write!(f, " {}", triangle)?;
+                    }
+                    write!(f, "\n  step {}: blocking edges", index + 1)?;
+                    for edge in step.blocking_edges.iter() {
+                        write!(f, " {}", edge)?;
+                    }
+                }
+                return Ok(());
+            }
+            TriangulationError::NonTerminatingRefinement {
+                triangles,
+                edge_length_floor,
+            } => {
+                write!(
+                    f,
+                    "Refinement does not terminate, edges shorter than {}:",
+                    edge_length_floor
+                )?;
+                for triangle in triangles.iter() {
+                    write!(f, " {}", triangle)?;
+                }
+                return Ok(());
+            }
+            TriangulationError::InvalidTriangulation(violations) => {
+                write!(f, "Triangulation breaks {} invariants:", violations.len())?;
+                for violation in violations.iter() {
+                    write!(f, "\n  {}", violation)?;
+                }
+                return Ok(());
+            }
+            TriangulationError::InvalidParams(error) => {
+                return write!(f, "Invalid refine params: {}", error);
+            }
+            TriangulationError::IoError(error) => {
+                return write!(f, "IO error: {}", error);
+            }
+            TriangulationError::Cancelled => {
+                return write!(f, "Triangulation cancelled");
+            }
+        }
+    }
+}
+
+impl Error for TriangulationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TriangulationError::IoError(error) => return Some(error),
+            TriangulationError::InvalidParams(error) => return Some(error),
+            _ => return None,
+        }
+    }
+}
+
+impl From<io::Error> for TriangulationError {
+    fn from(error: io::Error) -> Self {
+        return TriangulationError::IoError(error);
+    }
+}
+
+impl From<ParamError> for TriangulationError {
+    fn from(error: ParamError) -> Self {
+        return TriangulationError::InvalidParams(error);
+    }
+}
+
+#[cfg(test)]
+mod triangulation_error {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let v1 = Rc::new(Vertex::new(1.0, 2.0));
+        let error = TriangulationError::outside_vertices(vec![v1].into_iter().collect());
+        assert_eq!(
+            format!("{}", error),
+            "Constraints out of the boundary or inside holes: (1, 2)"
+        );
+
+        let error = TriangulationError::DegenerateInput(String::from("empty polyline"));
+        assert_eq!(format!("{}", error), "Degenerate input: empty polyline");
+    }
+
+    #[test]
+    fn display_recovery_steps() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0, -1.0));
+        let v4 = Rc::new(Vertex::new(1.0, 1.0));
+
+        let error = TriangulationError::UnrecoverableConstraint {
+            segment: Rc::new(Edge::new(&v1, &v2)),
+            steps: vec![RecoveryStep {
+                cavity: vec![Rc::new(Triangle::new(&v1, &v3, &v4))],
+                blocking_edges: vec![Rc::new(Edge::new(&v3, &v4))],
+            }],
+        };
+        assert_eq!(
+            format!("{}", error),
+            "Failed to recover constraint segment ((0, 0) - (2, 0))\n  \
+             step 1: cavity ((0, 0) - (1, -1) - (1, 1))\n  \
+             step 1: blocking edges ((1, -1) - (1, 1))"
+        );
+    }
+
+    #[test]
+    fn display_non_terminating() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+
+        let error = TriangulationError::NonTerminatingRefinement {
+            triangles: vec![Rc::new(Triangle::new(&v1, &v2, &v3))],
+            edge_length_floor: 0.5,
+        };
+        assert_eq!(
+            format!("{}", error),
+            "Refinement does not terminate, edges shorter than 0.5: ((0, 0) - (1, 0) - (0, 1))"
+        );
+    }
+
+    #[test]
+    fn display_invalid_triangulation() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+
+        let error = TriangulationError::InvalidTriangulation(vec![
+            InvariantViolation::UnmatchedEdge(Rc::new(Edge::new(&v1, &v2))),
+            InvariantViolation::OpenGhostRing(Rc::clone(&v2)),
+        ]);
+        assert_eq!(
+            format!("{}", error),
+            "Triangulation breaks 2 invariants:\n  \
+             Edge ((0, 0) - (1, 0)) without opposite triangle\n  \
+             Ghost triangles not closed around (1, 0)"
+        );
+    }
+
+    #[test]
+    fn io_source() {
+        let error: TriangulationError = io::Error::new(io::ErrorKind::NotFound, "missing").into();
+        assert!(error.source().is_some());
+        assert!(TriangulationError::DegenerateInput(String::new())
+            .source()
+            .is_none());
+
+        let error: TriangulationError = ParamError::NonPositiveArea(0.0).into();
+        assert!(error.source().is_some());
+        assert_eq!(
+            format!("{}", error),
+            "Invalid refine params: Max area 0 is not positive"
+        );
+    }
+} /* end - triangulation_error tests */
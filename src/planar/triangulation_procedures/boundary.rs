@@ -132,18 +132,14 @@ mod include_boundary {
         include(&mut triangulation, &boundary, &HashSet::new());
 
         let solid_triangles: Vec<Rc<Triangle>> = triangulation
-            .triangles
-            .iter()
-            .filter(|triangle| !triangle.is_ghost())
+            .solid_triangles()
             .cloned()
             .collect();
 
         assert_eq!(solid_triangles.len(), 4);
 
         let ghost_triangles: Vec<Rc<Triangle>> = triangulation
-            .triangles
-            .iter()
-            .filter(|triangle| triangle.is_ghost())
+            .ghost_triangles()
             .cloned()
             .collect();
 
@@ -178,18 +174,14 @@ mod include_boundary {
         include(&mut triangulation, &boundary, &HashSet::new());
 
         let solid_triangles: Vec<Rc<Triangle>> = triangulation
-            .triangles
-            .iter()
-            .filter(|triangle| !triangle.is_ghost())
+            .solid_triangles()
             .cloned()
             .collect();
 
         assert_eq!(solid_triangles.len(), 6);
 
         let ghost_triangles: Vec<Rc<Triangle>> = triangulation
-            .triangles
-            .iter()
-            .filter(|triangle| triangle.is_ghost())
+            .ghost_triangles()
             .cloned()
             .collect();
 
@@ -232,18 +224,14 @@ mod include_boundary {
         include(&mut triangulation, &boundary, &HashSet::new());
 
         let solid_triangles: Vec<Rc<Triangle>> = triangulation
-            .triangles
-            .iter()
-            .filter(|triangle| !triangle.is_ghost())
+            .solid_triangles()
             .cloned()
             .collect();
 
         assert_eq!(solid_triangles.len(), 10);
 
         let ghost_triangles: Vec<Rc<Triangle>> = triangulation
-            .triangles
-            .iter()
-            .filter(|triangle| triangle.is_ghost())
+            .ghost_triangles()
             .cloned()
             .collect();
 
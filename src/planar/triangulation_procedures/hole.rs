@@ -1,18 +1,22 @@
 use crate::elements::{edge::*, polyline::*, triangle::*, vertex::*};
-use crate::planar::{triangulation::*, triangulation_procedures};
+use crate::planar::{triangulation::*, triangulation_error::*, triangulation_procedures};
 use crate::properties::continence::Continence;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
+/* Upper bound of recovery attempts of a single hole segment */
+const MAX_RECOVERY_ATTEMPTS: usize = 8;
+
 /**
- * Include hole and returns included segments
+ * Include hole and returns included segments. Returns an error if any hole
+ * segment could not be recovered.
  */
 pub fn include(
     triangulation: &mut Triangulation,
     hole: &Rc<Polyline>,
     segment_constraints: &HashSet<Rc<Edge>>,
-) {
+) -> Result<(), TriangulationError> {
     let hole_segments: HashSet<Rc<Edge>> = hole
         .into_edges()
         .iter()
@@ -34,22 +38,18 @@ pub fn include(
         .cloned()
         .collect();
 
-    // Uncomment to debug
-    // let existing_segments: HashSet<Rc<Edge>> = triangulation.edges();
-    // let missing_segments: Vec<Rc<Edge>> = hole_segments
-    //     .iter()
-    //     .filter(|&e| !existing_segments.contains(e))
-    //     .cloned()
-    //     .collect();
-
-    // println!("\n\nMissing segments");
-    // for e in missing_segments.iter() {
-    //     println!("{}", e);
-    // }
+    if log::log_enabled!(log::Level::Trace) {
+        let existing_segments = triangulation.edges();
+        for e in hole_segments.iter().filter(|&e| !existing_segments.contains(e)) {
+            log::trace!("missing hole segment {}", e);
+        }
+    }
 
     /* Inserts missing segments */
+    let mut steps: Vec<RecoveryStep> = Vec::new();
+    let mut last_segment: Option<Rc<Edge>> = None;
     loop {
-        let existing_segments: HashSet<Rc<Edge>> = triangulation.edges();
+        let existing_segments = triangulation.edges();
         let missing_segment = hole_segments
             .iter()
             .find(|&e| !existing_segments.contains(e));
@@ -57,17 +57,31 @@ pub fn include(
         if missing_segment.is_none() {
             break;
         }
+        let missing_segment = missing_segment.unwrap();
+
+        /* steps are kept while the same segment is retried */
+        if last_segment.as_ref() != Some(missing_segment) {
+            steps = Vec::new();
+            last_segment = Some(Rc::clone(missing_segment));
+        }
+        if steps.len() >= MAX_RECOVERY_ATTEMPTS {
+            return Err(TriangulationError::UnrecoverableConstraint {
+                segment: Rc::clone(missing_segment),
+                steps,
+            });
+        }
 
-        triangulation_procedures::segment::include(
+        triangulation_procedures::segment::include_recording(
             triangulation,
-            missing_segment.unwrap(),
+            missing_segment,
             &segment_constraints,
-        );
+            &mut steps,
+        )?;
     }
 
-    /* Inserting ghost triangles into holes */
+    /* Inserting ghost triangles into holes, around a loop of their own */
     let mut pending_edges: Vec<Rc<Edge>> = Vec::new();
-    let ghost_vertex = Rc::new(Vertex::new_ghost());
+    let ghost_vertex = triangulation.new_ghost_loop();
 
     for hole_edge in hole_segments.iter() {
         let edge_to_hole = Rc::clone(&hole_edge);
@@ -108,11 +122,116 @@ pub fn include(
             pending_edges.push(Rc::new(e3.opposite()));
         }
     }
+
+    return Ok(());
 } /* end - include holes */
 
+/**
+ * Carves the hole marked by the seed: the triangles reached from the one
+ * containing the seed without crossing constrained edges are replaced by
+ * ghost triangles, and constraints inside them are unmarked. Returns the
+ * counterclockwise loop of constrained edges around the hole. Returns an
+ * error, carving nothing, if the seed is out of the triangulation, or if
+ * its region reaches the boundary or is not bounded by a single simple
+ * loop.
+ */
+pub fn carve(
+    triangulation: &mut Triangulation,
+    seed: &Vertex,
+) -> Result<Polyline, TriangulationError> {
+    let start = match triangulation.locate(seed) {
+        Some(start) => start,
+        None => return Err(carve_error(seed, "is out of the triangulation")),
+    };
+    let region = triangulation.flood_fill(&start, |edge| !triangulation.is_constrained(edge));
+    let (hole, border, inner_constraints) = match enclosure(triangulation, &region) {
+        Ok(enclosure) => enclosure,
+        Err(reason) => return Err(carve_error(seed, reason)),
+    };
+
+    for triangle in region.iter() {
+        triangulation.remove_triangle(triangle);
+    }
+    for edge in inner_constraints.iter() {
+        triangulation.unconstrain(edge);
+    }
+    let ghost_vertex = triangulation.new_ghost_loop();
+    for edge in border.iter() {
+        triangulation.include_triangle(&Rc::new(Triangle::new(&edge.v1, &edge.v2, &ghost_vertex)));
+    }
+
+    return Ok(hole);
+} /* end - carve */
+
+/**
+ * Counterclockwise loop of the constrained edges around the region, along
+ * with its edges, as oriented in the region triangles, and the constrained
+ * edges inside the region. Returns the reason if the region reaches a ghost
+ * triangle, as at the boundary or at holes, or if it is not bounded by a
+ * single simple loop.
+ */
+pub(crate) fn enclosure(
+    triangulation: &Triangulation,
+    region: &HashSet<Rc<Triangle>>,
+) -> Result<(Polyline, Vec<Rc<Edge>>, Vec<Rc<Edge>>), &'static str> {
+    /* border edges, keyed by their first vertex, and inner constraints */
+    let mut border: HashMap<Rc<Vertex>, Rc<Edge>> = HashMap::new();
+    let mut inner_constraints: Vec<Rc<Edge>> = Vec::new();
+    for triangle in region.iter() {
+        let (e1, e2, e3) = triangle.inner_edges();
+        for edge in vec![e1, e2, e3].into_iter() {
+            match triangulation.adjacency.get(&edge.opposite()) {
+                Some(neighbor) if region.contains(neighbor) => {
+                    if triangulation.is_constrained(&edge) {
+                        inner_constraints.push(edge);
+                    }
+                }
+                Some(neighbor) if !neighbor.is_ghost() => {
+                    if border.insert(Rc::clone(&edge.v1), edge).is_some() {
+                        return Err("bounds a pinched region");
+                    }
+                }
+                _ => return Err("reaches the boundary"),
+            }
+        }
+    }
+
+    /* chains border edges into a single loop */
+    let mut vertices: Vec<Rc<Vertex>> = Vec::new();
+    let first = match border.keys().min() {
+        Some(first) => Rc::clone(first),
+        None => return Err("reaches the boundary"),
+    };
+    let mut current = Rc::clone(&first);
+    loop {
+        vertices.push(Rc::clone(&current));
+        current = match border.get(&current) {
+            Some(edge) => Rc::clone(&edge.v2),
+            None => return Err("is not bounded by a loop"),
+        };
+        if current == first || vertices.len() > border.len() {
+            break;
+        }
+    }
+    if vertices.len() != border.len() {
+        return Err("is bounded by several loops");
+    }
+    let polyline = match Polyline::new_closed(vertices) {
+        Some(polyline) => polyline,
+        None => return Err("is not bounded by a loop"),
+    };
+
+    return Ok((polyline, border.into_iter().map(|(_, edge)| edge).collect(), inner_constraints));
+} /* end - enclosure */
+
+fn carve_error(seed: &Vertex, reason: &str) -> TriangulationError {
+    return TriangulationError::DegenerateInput(format!("hole seed {} {}", seed, reason));
+}
+
 #[cfg(test)]
 mod include_hole {
     use super::*;
+    use crate::elements::vertex::*;
 
     #[test]
     fn sample_1() {
@@ -150,16 +269,15 @@ mod include_hole {
             &mut triangulation,
             &hole,
             &boundary.into_edges().iter().cloned().collect(),
-        );
+        )
+        .unwrap();
 
         let ghost_triangles: Vec<Rc<Triangle>> = triangulation
-            .triangles
-            .iter()
-            .filter(|triangle| triangle.is_ghost())
+            .ghost_triangles()
             .cloned()
             .collect();
 
-        let ghost_vertex = Rc::new(Vertex::new_ghost());
+        let ghost_vertex = Rc::new(Vertex::new_ghost_of(1));
         let ghost_56 = Rc::new(Triangle::new(&v5, &v6, &ghost_vertex));
         let ghost_67 = Rc::new(Triangle::new(&v6, &v7, &ghost_vertex));
         let ghost_78 = Rc::new(Triangle::new(&v7, &v8, &ghost_vertex));
@@ -171,9 +289,7 @@ mod include_hole {
         assert!(ghost_triangles.contains(&ghost_85));
 
         let solid_triangles: Vec<Rc<Triangle>> = triangulation
-            .triangles
-            .iter()
-            .filter(|t| !t.is_ghost())
+            .solid_triangles()
             .cloned()
             .collect();
 
@@ -223,16 +339,15 @@ mod include_hole {
             &mut triangulation,
             &hole,
             &boundary.into_edges().iter().cloned().collect(),
-        );
+        )
+        .unwrap();
 
         let ghost_triangles: Vec<Rc<Triangle>> = triangulation
-            .triangles
-            .iter()
-            .filter(|triangle| triangle.is_ghost())
+            .ghost_triangles()
             .cloned()
             .collect();
 
-        let ghost_vertex = Rc::new(Vertex::new_ghost());
+        let ghost_vertex = Rc::new(Vertex::new_ghost_of(1));
         let ghost_5 = Rc::new(Triangle::new(&v5, &v6, &ghost_vertex));
         let ghost_6 = Rc::new(Triangle::new(&v6, &v7, &ghost_vertex));
         let ghost_7 = Rc::new(Triangle::new(&v7, &v8, &ghost_vertex));
@@ -248,9 +363,7 @@ mod include_hole {
         assert!(ghost_triangles.contains(&ghost_10));
 
         let solid_triangles: Vec<Rc<Triangle>> = triangulation
-            .triangles
-            .iter()
-            .filter(|t| !t.is_ghost())
+            .solid_triangles()
             .cloned()
             .collect();
 
@@ -313,12 +426,11 @@ mod include_hole {
             &mut triangulation,
             &hole,
             &boundary.into_edges().iter().cloned().collect(),
-        );
+        )
+        .unwrap();
 
         let solid_triangles: Vec<Rc<Triangle>> = triangulation
-            .triangles
-            .iter()
-            .filter(|t| !t.is_ghost())
+            .solid_triangles()
             .cloned()
             .collect();
 
@@ -329,3 +441,84 @@ mod include_hole {
         }
     } /* end - sample_3 */
 } /* end - include_holes tests */
+
+#[cfg(test)]
+mod carve_hole {
+    use super::*;
+
+    /* square boundary with a constrained inner square and a dangling segment inside it */
+    fn walled_square() -> Triangulation {
+        let corners = vec![(0.0, 0.0), (3.0, 0.0), (3.0, 3.0), (0.0, 3.0)];
+        let boundary = Rc::new(
+            Polyline::new_closed(
+                corners
+                    .iter()
+                    .map(|&(x, y)| Rc::new(Vertex::new(x, y)))
+                    .collect(),
+            )
+            .unwrap(),
+        );
+        let wall = Polyline::new_closed(vec![
+            Rc::new(Vertex::new(1.0, 1.0)),
+            Rc::new(Vertex::new(2.0, 1.0)),
+            Rc::new(Vertex::new(2.0, 2.0)),
+            Rc::new(Vertex::new(1.0, 2.0)),
+        ])
+        .unwrap();
+        let dangling = Rc::new(Edge::new(
+            &Rc::new(Vertex::new(1.0, 1.0)),
+            &Rc::new(Vertex::new(1.5, 1.5)),
+        ));
+
+        let mut triangulation =
+            Triangulation::from_initial_segment((&boundary.vertices[0], &boundary.vertices[1]));
+        triangulation_procedures::boundary::include(&mut triangulation, &boundary, &HashSet::new());
+        let mut constraints: HashSet<Rc<Edge>> = boundary.into_edges().into_iter().collect();
+        for segment in wall.into_edges().iter().chain(vec![dangling].iter()) {
+            triangulation_procedures::vertices::include(
+                &mut triangulation,
+                vec![Rc::clone(&segment.v1), Rc::clone(&segment.v2)],
+                &constraints,
+                &None,
+                &HashSet::new(),
+            );
+            triangulation_procedures::segment::include(&mut triangulation, segment, &constraints)
+                .unwrap();
+            constraints.insert(Rc::clone(segment));
+        }
+        for constraint in constraints.iter() {
+            triangulation.constrain(constraint);
+        }
+        return triangulation;
+    }
+
+    #[test]
+    fn inner_wall() {
+        let mut triangulation = walled_square();
+        let hole = carve(&mut triangulation, &Vertex::new(1.8, 1.2)).unwrap();
+
+        assert_eq!(hole.vertices.len(), 4);
+        assert!(hole.contains(&Vertex::new(1.5, 1.2)) == Some(Continence::Inside));
+        assert!(triangulation.validate().is_ok());
+        assert_eq!(triangulation.ghost_loops().len(), 2);
+
+        /* dangling segment is gone along with the hole triangles */
+        assert!(!triangulation.vertices().contains(&Vertex::new(1.5, 1.5)));
+        assert_eq!(triangulation.constraints().len(), 8);
+        for triangle in triangulation.solid_triangles() {
+            assert!(hole.contains(&triangle.center()) == Some(Continence::Outside));
+        }
+        assert!(triangulation.locate(&Vertex::new(1.2, 1.8)).is_none());
+    }
+
+    #[test]
+    fn reaching_boundary() {
+        let mut triangulation = walled_square();
+        let triangles = triangulation.triangles.clone();
+
+        /* region between the boundary and the wall */
+        assert!(carve(&mut triangulation, &Vertex::new(0.5, 0.5)).is_err());
+        assert!(carve(&mut triangulation, &Vertex::new(4.0, 0.5)).is_err());
+        assert_eq!(triangulation.triangles, triangles);
+    }
+} /* end - carve tests */
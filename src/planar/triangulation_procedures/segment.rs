@@ -1,28 +1,56 @@
 use crate::elements::{edge::*, polyline::*, triangle::*, vertex::*};
-use crate::planar::triangulation::*;
+use crate::planar::{triangulation::*, triangulation_error::*};
 use crate::properties::{continence::*, orientation::*};
 
 use crate::planar::triangulation_procedures;
 
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 use std::rc::Rc;
 
 /**
  * Includes Segment.
- * Takes all triangles that intercept the segment or whose circumcircle encircles
+ * Recovers the segment by flips if possible, which adds no vertices. Else,
+ * takes all triangles that intercept the segment or whose circumcircle encircles
  * one of its end vertices, retriangulates the vertices of the triangle around the
  * segment having the segment as constraint. Reinserts the taken triangulation into
- * the main triangulation.
+ * the main triangulation. Returns an error, leaving the triangulation
+ * untouched, if the taken triangles do not make a single polygon.
  */
 pub fn include(
     triangulation: &mut Triangulation,
     segment: &Rc<Edge>,
     segment_constraints: &HashSet<Rc<Edge>>,
-) {
+) -> Result<(), TriangulationError> {
+    return include_recording(triangulation, segment, segment_constraints, &mut Vec::new());
+} /* end - include segment */
+
+/**
+ * Includes segment as include does, recording the attempt into the steps:
+ * the taken triangles and the edges still crossing the segment afterwards.
+ * The segment is recovered, and marked as constrained, only if the last
+ * step has no blocking edges.
+ */
+pub fn include_recording(
+    triangulation: &mut Triangulation,
+    segment: &Rc<Edge>,
+    segment_constraints: &HashSet<Rc<Edge>>,
+    steps: &mut Vec<RecoveryStep>,
+) -> Result<(), TriangulationError> {
+    let former_triangles = triangulation.triangles.clone();
+    if recover_by_flips(triangulation, segment, segment_constraints) {
+        log::debug!("recovered segment {} by flips", segment);
+        steps.push(RecoveryStep {
+            cavity: former_triangles
+                .difference(&triangulation.triangles)
+                .cloned()
+                .collect(),
+            blocking_edges: Vec::new(),
+        });
+        return Ok(());
+    }
+
     let conflicting_triangles: HashSet<Rc<Triangle>> = triangulation
-        .triangles
-        .iter()
-        .filter(|t| !t.is_ghost())
+        .solid_triangles()
         .filter(|triangle| {
             let polyline = triangle.as_polyline().unwrap();
             let segment_polyline = segment.as_polyline().unwrap();
@@ -59,23 +87,33 @@ pub fn include(
         .cloned()
         .collect();
 
-    // Uncomment to debug
-    // println!("\n\nSolid Triangles");
-    // for t in triangulation.triangles.iter().filter(|t| !t.is_ghost()) {
-    //     println!("{}", t);
-    // }
-    // println!("\nConflicting Triangles against {}", segment);
-    // for t in conflicting_triangles.iter() {
-    //     println!("{}", t);
-    // }
+    log::debug!(
+        "recovering segment {}, conflicting with {} triangles",
+        segment,
+        conflicting_triangles.len()
+    );
+    for t in conflicting_triangles.iter() {
+        log::trace!("conflicting triangle {}", t);
+    }
+
+    let triangles_boundary: Rc<Polyline> = match Polyline::triangles_hull(&conflicting_triangles).single() {
+        Some(hull) => Rc::new(hull),
+        None => {
+            steps.push(RecoveryStep {
+                cavity: conflicting_triangles.iter().cloned().collect(),
+                blocking_edges: crossing_edges(triangulation, segment),
+            });
+            return Err(TriangulationError::UnrecoverableConstraint {
+                segment: Rc::clone(segment),
+                steps: steps.clone(),
+            });
+        }
+    };
 
     for conflicting_triangle in conflicting_triangles.iter() {
         triangulation.remove_triangle(conflicting_triangle);
     }
 
-    let triangles_boundary: Rc<Polyline> =
-        Rc::new(Polyline::triangles_hull(&conflicting_triangles).unwrap());
-
     let conflicting_vertices: HashSet<Rc<Vertex>> = conflicting_triangles
         .iter()
         .map(|triangle| {
@@ -108,22 +146,145 @@ pub fn include(
     );
 
     let new_solid_triangles: HashSet<Rc<Triangle>> = segment_triangulation
-        .triangles
-        .iter()
-        .filter(|t| !t.is_ghost())
+        .solid_triangles()
         .cloned()
         .collect();
 
-    // Uncomment to debug
-    // println!("\nNew Solid Triangles");
-    // for t in new_solid_triangles.iter() {
-    //     println!("{}", t);
-    // }
+    for t in new_solid_triangles.iter() {
+        log::trace!("retriangulated cavity of {}: {}", segment, t);
+    }
 
     for new_triangle in new_solid_triangles.iter() {
         triangulation.include_triangle(new_triangle);
     }
-} /* end - include segment */
+
+    let blocking_edges = crossing_edges(triangulation, segment);
+    if blocking_edges.is_empty() {
+        triangulation.constrain(segment);
+    }
+    steps.push(RecoveryStep {
+        cavity: conflicting_triangles.iter().cloned().collect(),
+        blocking_edges,
+    });
+
+    return Ok(());
+} /* end - include_recording */
+
+/**
+ * Recovers the segment by flipping the edges crossing it, as by Sloan.
+ * Crossing edges are queued, and each one whose quadrilateral is convex is
+ * flipped, being queued again while the flipped edge still crosses the
+ * segment. Edges created by the flips are then flipped back to Delaunay,
+ * except the segment, which is marked as constrained, so that no vertex is
+ * added. Returns false, leaving a
+ * valid triangulation to recover the segment otherwise, if a vertex lies on
+ * the segment interior, if the segment crosses a constraint, or if the
+ * flips stall.
+ */
+pub fn recover_by_flips(
+    triangulation: &mut Triangulation,
+    segment: &Rc<Edge>,
+    segment_constraints: &HashSet<Rc<Edge>>,
+) -> bool {
+    let vertices = triangulation.vertices();
+    if !vertices.contains(&segment.v1) || !vertices.contains(&segment.v2) {
+        return false;
+    }
+    let passes_vertex = vertices
+        .iter()
+        .any(|v| *v != segment.v1 && *v != segment.v2 && segment.contains(v));
+    if passes_vertex {
+        return false;
+    }
+
+    let mut pending: VecDeque<Rc<Edge>> = crossing_edges(triangulation, segment).into();
+    let crosses_constraint = pending.iter().any(|edge| {
+        segment_constraints.contains(edge)
+            || segment_constraints.contains(&edge.opposite())
+            || segment_constraints
+                .iter()
+                .any(|s| s.contains(&edge.v1) && s.contains(&edge.v2))
+    });
+    if crosses_constraint {
+        return false;
+    }
+
+    let mut new_edges: Vec<Rc<Edge>> = Vec::new();
+    let mut stalled_count: usize = 0;
+    while let Some(edge) = pending.pop_front() {
+        let v3 = triangulation
+            .adjacency
+            .get(&*edge)
+            .and_then(|t| t.opposite_vertex(&edge));
+        let v4 = triangulation
+            .adjacency
+            .get(&edge.opposite())
+            .and_then(|t| t.opposite_vertex(&Rc::new(edge.opposite())));
+        let (v3, v4) = match (v3, v4) {
+            (Some(v3), Some(v4)) => (v3, v4),
+            _ => return false,
+        };
+
+        /* non convex quadrilaterals are retried once others are flipped */
+        if !triangulation.flip_edge(&edge) {
+            pending.push_back(edge);
+            stalled_count += 1;
+            if stalled_count > pending.len() {
+                return false;
+            }
+            continue;
+        }
+        stalled_count = 0;
+
+        let flipped_edge = Rc::new(Edge::new(&v4, &v3));
+        if crosses(segment, &flipped_edge) {
+            pending.push_back(flipped_edge);
+        } else {
+            new_edges.push(flipped_edge);
+        }
+    }
+
+    /* the recovered segment is marked, so that flips keep it */
+    triangulation.constrain(segment);
+    let new_edges: Vec<Rc<Edge>> = new_edges
+        .into_iter()
+        .filter(|edge| **edge != **segment && **edge != segment.opposite())
+        .collect();
+    triangulation.legalize(new_edges);
+
+    return triangulation.adjacency.contains_key(&**segment)
+        || triangulation.adjacency.contains_key(&segment.opposite());
+} /* end - recover_by_flips */
+
+/**
+ * Solid triangulation edges crossing the segment interior, once per pair of
+ * opposite edges.
+ */
+pub fn crossing_edges(triangulation: &Triangulation, segment: &Edge) -> Vec<Rc<Edge>> {
+    let mut edges: HashSet<Rc<Edge>> = HashSet::new();
+    for edge in triangulation.edges() {
+        if edge.v1.is_ghost || edge.v2.is_ghost {
+            continue;
+        }
+        if edges.contains(&Rc::new(edge.opposite())) || !crosses(segment, &edge) {
+            continue;
+        }
+        edges.insert(edge);
+    }
+
+    return edges.into_iter().collect();
+}
+
+/* whether the edge and the segment cross at a single interior point */
+fn crosses(segment: &Edge, edge: &Edge) -> bool {
+    let o1 = orientation(&segment.v1, &segment.v2, &edge.v1);
+    let o2 = orientation(&segment.v1, &segment.v2, &edge.v2);
+    let o3 = orientation(&edge.v1, &edge.v2, &segment.v1);
+    let o4 = orientation(&edge.v1, &edge.v2, &segment.v2);
+    let splits_edge = o1 != Orientation::Colinear && o2 != Orientation::Colinear && o1 != o2;
+    let splits_segment = o3 != Orientation::Colinear && o4 != Orientation::Colinear && o3 != o4;
+    return splits_edge && splits_segment;
+}
 
 #[cfg(test)]
 mod include_segment {
@@ -160,7 +321,8 @@ mod include_segment {
             &HashSet::new(),
         );
 
-        triangulation_procedures::segment::include(&mut triangulation, &s1, &HashSet::new());
+        triangulation_procedures::segment::include(&mut triangulation, &s1, &HashSet::new())
+            .unwrap();
 
         for v in vertices.iter() {
             assert!(triangulation.vertices().contains(v));
@@ -179,9 +341,7 @@ mod include_segment {
         ];
 
         let triangles = triangulation
-            .triangles
-            .iter()
-            .filter(|t| !t.is_ghost())
+            .solid_triangles()
             .cloned()
             .collect::<Vec<Rc<Triangle>>>();
 
@@ -230,7 +390,8 @@ mod include_segment {
             &HashSet::new(),
         );
         assert_eq!(triangulation.vertices().len(), 8);
-        triangulation_procedures::segment::include(&mut triangulation, &s1, &HashSet::new());
+        triangulation_procedures::segment::include(&mut triangulation, &s1, &HashSet::new())
+            .unwrap();
 
         for v in vertices.iter() {
             assert!(triangulation.vertices().contains(v));
@@ -281,7 +442,8 @@ mod include_segment {
             &HashSet::new(),
         );
         assert_eq!(triangulation.vertices().len(), 7);
-        triangulation_procedures::segment::include(&mut triangulation, &s1, &HashSet::new());
+        triangulation_procedures::segment::include(&mut triangulation, &s1, &HashSet::new())
+            .unwrap();
         assert_eq!(triangulation.vertices().len(), 9);
         assert!(triangulation.edges().contains(&s1));
         assert!(triangulation.vertices().contains(&v8));
@@ -294,9 +456,124 @@ mod include_segment {
             &mut triangulation,
             &s2,
             &vec![Rc::clone(&s1)].iter().cloned().collect(),
-        );
+        )
+        .unwrap();
         assert_eq!(triangulation.vertices().len(), 11);
         assert!(triangulation.edges().contains(&s1));
         assert!(triangulation.edges().contains(&s2));
     }
+
+    #[test]
+    fn records_recovery_steps() {
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(3.0, 1.0));
+        let v3 = Rc::new(Vertex::new(3.0, 3.0));
+        let v4 = Rc::new(Vertex::new(1.0, 3.0));
+        let v5 = Rc::new(Vertex::new(2.0, 2.5));
+
+        let mut triangulation = Triangulation::from_initial_segment((&v1, &v2));
+        triangulation_procedures::vertices::include(
+            &mut triangulation,
+            vec![Rc::clone(&v3), Rc::clone(&v4), Rc::clone(&v5)],
+            &HashSet::new(),
+            &None,
+            &HashSet::new(),
+        );
+
+        /* the segment is crossed by the edges reaching the upper vertex */
+        let segment = Rc::new(Edge::new(&v1, &v2));
+        let upper_segment = Rc::new(Edge::new(&Rc::new(Vertex::new(1.5, 2.0)), &v3));
+        assert!(crossing_edges(&triangulation, &segment).is_empty());
+        assert!(!crossing_edges(&triangulation, &upper_segment).is_empty());
+
+        /* an existing edge is recovered with no flips */
+        let mut steps: Vec<RecoveryStep> = Vec::new();
+        include_recording(&mut triangulation, &segment, &HashSet::new(), &mut steps).unwrap();
+        assert_eq!(steps.len(), 1);
+        assert!(steps[0].cavity.is_empty());
+        assert!(steps[0].blocking_edges.is_empty());
+
+        let diagonal = Rc::new(Edge::new(&v2, &v4));
+        assert!(!crossing_edges(&triangulation, &diagonal).is_empty());
+        let mut steps: Vec<RecoveryStep> = Vec::new();
+        include_recording(&mut triangulation, &diagonal, &HashSet::new(), &mut steps).unwrap();
+        assert_eq!(steps.len(), 1);
+        assert!(!steps[0].cavity.is_empty());
+        assert!(steps[0].blocking_edges.is_empty());
+    }
+
+    #[test]
+    fn recover_by_flips() {
+        /* a fan of thin triangles, crossed by the segment from v1 to v2 */
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(10.0, 0.0));
+        let upper: Vec<Rc<Vertex>> = (1..5)
+            .map(|i| Rc::new(Vertex::new(2.0 * i as f64, 0.5 + 0.1 * i as f64)))
+            .collect();
+        let lower: Vec<Rc<Vertex>> = (1..5)
+            .map(|i| Rc::new(Vertex::new(2.0 * i as f64 + 1.0, -0.5 - 0.1 * i as f64)))
+            .collect();
+
+        let mut triangulation = Triangulation::from_initial_segment((&v1, &upper[0]));
+        triangulation_procedures::vertices::include(
+            &mut triangulation,
+            upper
+                .iter()
+                .skip(1)
+                .chain(lower.iter())
+                .chain(vec![&v2].into_iter())
+                .cloned()
+                .collect(),
+            &HashSet::new(),
+            &None,
+            &HashSet::new(),
+        );
+        let vertices_count = triangulation.vertices().len();
+
+        let segment = Rc::new(Edge::new(&v1, &v2));
+        assert!(!crossing_edges(&triangulation, &segment).is_empty());
+        assert!(triangulation_procedures::segment::recover_by_flips(
+            &mut triangulation,
+            &segment,
+            &HashSet::new()
+        ));
+
+        /* no Steiner points */
+        assert_eq!(triangulation.vertices().len(), vertices_count);
+        assert!(
+            triangulation.edges().contains(&segment)
+                || triangulation.edges().contains(&segment.opposite())
+        );
+        assert!(crossing_edges(&triangulation, &segment).is_empty());
+
+        /* constrained Delaunay once the segment is marked */
+        triangulation.constrain(&segment);
+        assert!(triangulation.validate().is_ok());
+    }
+
+    #[test]
+    fn recover_by_flips_through_vertex() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(4.0, 0.0));
+        let v3 = Rc::new(Vertex::new(2.0, 0.0));
+        let v4 = Rc::new(Vertex::new(2.0, 2.0));
+        let v5 = Rc::new(Vertex::new(2.0, -2.0));
+
+        let mut triangulation = Triangulation::from_initial_segment((&v1, &v4));
+        triangulation_procedures::vertices::include(
+            &mut triangulation,
+            vec![Rc::clone(&v2), Rc::clone(&v3), Rc::clone(&v5)],
+            &HashSet::new(),
+            &None,
+            &HashSet::new(),
+        );
+
+        /* the segment passes through v3, thus it is split instead */
+        let segment = Rc::new(Edge::new(&v1, &v2));
+        assert!(!triangulation_procedures::segment::recover_by_flips(
+            &mut triangulation,
+            &segment,
+            &HashSet::new()
+        ));
+    }
 } /* end - include_segment tests */
@@ -1,10 +1,13 @@
 use crate::elements::{edge::*, polyline::*, triangle::*, vertex::*};
-use crate::planar::{triangulation::*, triangulation_procedures};
-use crate::properties::continence::*;
+use crate::planar::{insertion_order, triangulation::*, triangulation_procedures};
+use crate::properties::{continence::*, distance::*, orientation::*};
 
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
+/* Distance to edges, relative to their length, of vertices taken as on them */
+pub(crate) const ON_EDGE_TOLERANCE: f64 = 1.0E-9;
+
 /**
  * Inserts vertices in the triangulation.
  */
@@ -15,12 +18,44 @@ pub fn include(
     boundary: &Option<Rc<Polyline>>,
     holes: &HashSet<Rc<Polyline>>,
 ) -> (HashSet<Rc<Triangle>>, HashSet<Rc<Triangle>>) {
-    let existing_vertices: HashSet<Rc<Vertex>> = triangulation.vertices();
+    return include_observed(
+        triangulation,
+        vertices,
+        segment_constraints,
+        boundary,
+        holes,
+        false,
+        &mut |_, _| true,
+    );
+}
+
+/**
+ * Inserts vertices as include, calling the observer before each insertion
+ * with the count of insertions so far and the count of vertices to insert.
+ * Insertion stops early once the observer returns false, leaving a valid
+ * triangulation without the remaining vertices. With spatial sorting, the
+ * vertices are sorted along a Hilbert curve before conflicts are
+ * distributed, so that consecutive insertions are local.
+ */
+pub fn include_observed(
+    triangulation: &mut Triangulation,
+    vertices: Vec<Rc<Vertex>>,
+    segment_constraints: &HashSet<Rc<Edge>>,
+    boundary: &Option<Rc<Polyline>>,
+    holes: &HashSet<Rc<Polyline>>,
+    spatial_sorting: bool,
+    observer: &mut dyn FnMut(usize, usize) -> bool,
+) -> (HashSet<Rc<Triangle>>, HashSet<Rc<Triangle>>) {
+    let existing_vertices = triangulation.vertices();
     let mut vertices: Vec<Rc<Vertex>> = vertices
         .iter()
         .filter(|&v| !existing_vertices.contains(v)) /* filters existing vertices */
         .cloned()
         .collect();
+    if spatial_sorting {
+        vertices = insertion_order::hilbert_sort(vertices);
+    }
+    let total = vertices.len();
 
     let mut conflict_map: HashMap<Rc<Triangle>, Vec<Rc<Vertex>>> = HashMap::new();
     for possible_triangle in triangulation.triangles.iter() {
@@ -36,13 +71,14 @@ pub fn include(
         );
     }
 
-    return solve_conflicts(
+    return solve_conflicts_observed(
         triangulation,
         &mut conflict_map,
         &mut vertices,
         segment_constraints,
         boundary,
         holes,
+        &mut |processed| observer(processed, total),
     );
 } /* end - include vertices method */
 
@@ -58,25 +94,66 @@ pub fn solve_conflicts(
     segment_constraints: &HashSet<Rc<Edge>>,
     boundary: &Option<Rc<Polyline>>,
     holes: &HashSet<Rc<Polyline>>,
+) -> (HashSet<Rc<Triangle>>, HashSet<Rc<Triangle>>) {
+    return solve_conflicts_observed(
+        triangulation,
+        conflict_map,
+        remaining_vertices,
+        segment_constraints,
+        boundary,
+        holes,
+        &mut |_| true,
+    );
+}
+
+/**
+ * Solves conflicts as solve_conflicts, calling the observer before each
+ * insertion with the count of insertions so far. Stops early once the
+ * observer returns false, leaving the unsolved conflicts in the map.
+ */
+pub fn solve_conflicts_observed(
+    triangulation: &mut Triangulation,
+    conflict_map: &mut HashMap<Rc<Triangle>, Vec<Rc<Vertex>>>,
+    remaining_vertices: &mut Vec<Rc<Vertex>>,
+    segment_constraints: &HashSet<Rc<Edge>>,
+    boundary: &Option<Rc<Polyline>>,
+    holes: &HashSet<Rc<Polyline>>,
+    observer: &mut dyn FnMut(usize) -> bool,
 ) -> (HashSet<Rc<Triangle>>, HashSet<Rc<Triangle>>) {
     let mut included_triangles: HashSet<Rc<Triangle>> = HashSet::new();
     let mut removed_triangles: HashSet<Rc<Triangle>> = HashSet::new();
+    let mut processed: usize = 0;
+
+    loop {
+        if conflict_map.is_empty() && !remaining_vertices.is_empty() {
+            /*
+                Vertices of removed triangles are only distributed to the
+                triangles created afterwards, missing the former ones of
+                the same cavity, so that they are located once more.
+            */
+            distribute_conflicts_over_triangulation(
+                triangulation,
+                None,
+                conflict_map,
+                remaining_vertices,
+                boundary,
+                holes,
+            );
+        }
+        if conflict_map.is_empty() {
+            break;
+        }
 
-    while !conflict_map.is_empty() {
-        // Uncommet to debug
-        // println!("\n\nExisting Triangles");
-        // for t in triangulation.triangles.iter() {
-        //     println!("{}", t);
-        // }
-
-        // println!("\nConflict Map");
-        // for (t, vs) in conflict_map.iter() {
-        //     println!("{}", t);
-        //     for v in vs.iter() {
-        //         print!("{}", v);
-        //     }
-        //     println!();
-        // }
+        if !observer(processed) {
+            break;
+        }
+        processed += 1;
+
+        if log::log_enabled!(log::Level::Trace) {
+            for (t, vs) in conflict_map.iter() {
+                log::trace!("triangle {} conflicting with {} vertices", t, vs.len());
+            }
+        }
 
         let next_conflicting_triangle: Rc<Triangle> =
             Rc::clone(conflict_map.keys().next().unwrap());
@@ -86,18 +163,32 @@ pub fn solve_conflicts(
 
         let conflict_vertex: Rc<Vertex> = conflicting_vertices.pop().unwrap();
 
-        triangulation.remove_triangle(&next_conflicting_triangle);
-        removed_triangles.insert(Rc::clone(&next_conflicting_triangle));
-
-        let (e1, e2, e3) = next_conflicting_triangle.inner_edges();
-
-        let mut pending_cavities: Vec<Rc<Edge>> = vec![e1, e2, e3];
-
         if !conflicting_vertices.is_empty() {
             /* reinclude conflicts */
             remaining_vertices.append(&mut conflicting_vertices);
         }
 
+        /*
+            The cavity is dug from the triangle holding the vertex, which
+            conflicts as well, so that no constraint lies between the vertex
+            and the cavity.
+        */
+        let cavity_triangle =
+            match holding_triangle(triangulation, &next_conflicting_triangle, &conflict_vertex) {
+                Some(holding) => holding,
+                None => next_conflicting_triangle,
+            };
+        if let Some(mut conflicting_vertices) = conflict_map.remove(&cavity_triangle) {
+            remaining_vertices.append(&mut conflicting_vertices);
+        }
+
+        triangulation.remove_triangle(&cavity_triangle);
+        removed_triangles.insert(Rc::clone(&cavity_triangle));
+
+        let (e1, e2, e3) = cavity_triangle.inner_edges();
+
+        let mut pending_cavities: Vec<Rc<Edge>> = vec![e1, e2, e3];
+
         while !pending_cavities.is_empty() {
             let edge: Rc<Edge> = pending_cavities.pop().unwrap();
             let edge_to_outer_triangle: Rc<Edge> = Rc::new(edge.opposite());
@@ -109,35 +200,35 @@ pub fn solve_conflicts(
                     .unwrap(),
             );
 
-            let mut is_conflicting =
-                outer_triangle.encircles(&conflict_vertex) == Continence::Inside;
+            let mut is_conflicting = is_in_conflict(&outer_triangle, &conflict_vertex);
+            let mut is_on_edge = false;
 
             if outer_triangle.is_ghost() && !is_conflicting {
                 let outer_edge = Edge::new(&outer_triangle.v1, &outer_triangle.v2);
-                is_conflicting = outer_edge.contains(&conflict_vertex);
-                // Uncomment to debug
-                // println!(
-                //     "{} {} conflicting: {}",
-                //     outer_triangle, conflict_vertex, is_conflicting
-                // );
+                /* steiner points of boundary and hole edges round off to either side */
+                is_on_edge = outer_edge.contains(&conflict_vertex)
+                    || edge_distance(&outer_edge, &conflict_vertex)
+                        <= ON_EDGE_TOLERANCE * outer_edge.length();
+                is_conflicting = is_on_edge;
             }
 
             let is_constrained = segment_constraints.contains(&edge_to_outer_triangle)
                 || segment_constraints.contains(&edge);
 
-            let may_insert =
-                may_insert_triangle(&outer_triangle, &conflict_vertex, boundary, holes);
-
-            // Uncomment to debug
-            // println!(
-            //     "{}, {} x {}: {} {} {}",
-            //     edge,
-            //     outer_triangle,
-            //     conflict_vertex,
-            //     is_conflicting,
-            //     is_constrained,
-            //     may_insert,
-            // );
+            /* ghost triangles split at their edge are kept outside the domain */
+            let may_insert = is_on_edge
+                || may_insert_triangle(&outer_triangle, &conflict_vertex, boundary, holes);
+
+            log::trace!(
+                "digging cavity of {} across {} into {}: conflicting {}, constrained {}, \
+                 insertable {}",
+                conflict_vertex,
+                edge,
+                outer_triangle,
+                is_conflicting,
+                is_constrained,
+                may_insert,
+            );
 
             if is_conflicting && !is_constrained && may_insert {
                 triangulation.remove_triangle(&outer_triangle);
@@ -203,7 +294,7 @@ fn distribute_conflicts(
 
     for _ in 0..vertices.len() {
         let pending_vertex: Rc<Vertex> = vertices.remove(0);
-        let has_conflict = triangle.encircles(&pending_vertex) == Continence::Inside;
+        let has_conflict = is_in_conflict(triangle, &pending_vertex);
 
         let may_insert = may_insert_triangle(&triangle, &pending_vertex, boundary, holes);
 
@@ -259,10 +350,62 @@ pub fn distribute_conflicts_over_triangulation(
 
         distribute_conflicts(&next_triangle, conflict_map, vertices, boundary, holes);
     }
+    log::debug!(
+        "distributed conflicts over {} triangles, {} vertices left",
+        visited_triangles.len(),
+        vertices.len()
+    );
 }
 
 /**
- * Evaluates if triangle is inside boudanry and outside holes
+ * Solid triangle holding the vertex, inside or on its edges, reached by
+ * walking from the triangle towards the vertex. Returns None if the walk
+ * leaves the solid triangles, as for vertices out of them, or if it does
+ * not settle within as many steps as triangles.
+ */
+fn holding_triangle(
+    triangulation: &Triangulation,
+    triangle: &Rc<Triangle>,
+    vertex: &Vertex,
+) -> Option<Rc<Triangle>> {
+    let mut current = Rc::clone(triangle);
+    for _ in 0..triangulation.triangles.len() {
+        if current.is_ghost() {
+            return None;
+        }
+        let (e1, e2, e3) = current.inner_edges();
+        let exit = vec![e1, e2, e3]
+            .into_iter()
+            .find(|edge| orientation(&edge.v1, &edge.v2, vertex) == Orientation::Clockwise);
+        match exit {
+            Some(edge) => current = Rc::clone(triangulation.adjacency.get(&edge.opposite())?),
+            None => return Some(current),
+        }
+    }
+    return None;
+}
+
+/**
+ * Evaluates if the vertex lies inside the circumcircle of the triangle, or
+ * at the outer side of a ghost triangle of the hull. Ghost triangles of
+ * hole loops are not taken as conflicting by their side, which reaches into
+ * the domain across the hole, but only by vertices lying on their solid
+ * edge, as the cavity is dug.
+ */
+fn is_in_conflict(triangle: &Triangle, vertex: &Vertex) -> bool {
+    if let Some(ghost) = triangle.ghost_vertex() {
+        if ghost.ghost_loop() != Some(0) {
+            return false;
+        }
+    }
+    return triangle.encircles(vertex) == Continence::Inside;
+}
+
+/**
+ * Evaluates if triangle is inside boudanry and outside holes. For vertices
+ * lying exactly on boundary or hole corners, whose path from the triangle
+ * center would end at the corner, and be classified depending on round off,
+ * only the triangle center is evaluated.
  */
 fn may_insert_triangle(
     triangle: &Rc<Triangle>,
@@ -270,6 +413,22 @@ fn may_insert_triangle(
     boundary: &Option<Rc<Polyline>>,
     holes: &HashSet<Rc<Polyline>>,
 ) -> bool {
+    let is_corner = boundary
+        .iter()
+        .chain(holes.iter())
+        .any(|polyline| polyline.vertices.iter().any(|v| v == target_vertex));
+    if is_corner && !triangle.is_ghost() {
+        let center = triangle.center();
+        let is_inside_boundary = match boundary {
+            Some(boundary) => boundary.contains(&center) == Some(Continence::Inside),
+            None => true,
+        };
+        let is_outside_holes = holes
+            .iter()
+            .all(|hole| hole.contains(&center) == Some(Continence::Outside));
+        return is_inside_boundary && is_outside_holes;
+    }
+
     let p2: Polyline;
     if triangle.is_ghost() {
         p2 = Polyline::new_closed(vec![
@@ -305,6 +464,52 @@ fn may_insert_triangle(
     return is_inside_boundary && is_outside_holes;
 } /* end - may_insert_triangle */
 
+#[cfg(test)]
+mod corner_vertices {
+    use super::*;
+
+    #[test]
+    fn evaluates_corners() {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
+            ])
+            .unwrap(),
+        );
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(2.0, 2.0)),
+                Rc::new(Vertex::new(3.0, 2.0)),
+                Rc::new(Vertex::new(3.0, 3.0)),
+            ])
+            .unwrap(),
+        );
+        let holes: HashSet<Rc<Polyline>> = vec![hole].into_iter().collect();
+        let triangle = Rc::new(Triangle::new(
+            &Rc::new(Vertex::new(0.0, 0.0)),
+            &Rc::new(Vertex::new(2.0, 0.0)),
+            &Rc::new(Vertex::new(0.0, 2.0)),
+        ));
+        let boundary = Some(boundary);
+
+        /* corners are evaluated from the triangle center only */
+        let corner = Rc::new(Vertex::new(3.0, 2.0));
+        assert!(may_insert_triangle(&triangle, &corner, &boundary, &holes));
+        let corner = Rc::new(Vertex::new(4.0, 4.0));
+        assert!(may_insert_triangle(&triangle, &corner, &boundary, &holes));
+
+        let hole_triangle = Rc::new(Triangle::new(
+            &Rc::new(Vertex::new(2.0, 2.0)),
+            &Rc::new(Vertex::new(3.0, 2.0)),
+            &Rc::new(Vertex::new(3.0, 3.0)),
+        ));
+        assert!(!may_insert_triangle(&hole_triangle, &corner, &boundary, &holes));
+    }
+} /* end - corner_vertices tests */
+
 #[cfg(test)]
 mod include_vertices {
     use super::*;
@@ -354,7 +559,7 @@ mod include_vertices {
             &HashSet::new(),
         );
 
-        let vertices: HashSet<Rc<Vertex>> = triangulation.vertices();
+        let vertices = triangulation.vertices();
         assert_eq!(vertices.len(), 8);
         assert!(vertices.contains(&v1));
         assert!(vertices.contains(&v2));
@@ -416,7 +621,7 @@ mod include_vertices {
             &HashSet::new(),
         );
 
-        let vertices: HashSet<Rc<Vertex>> = triangulation.vertices();
+        let vertices = triangulation.vertices();
         assert_eq!(vertices.len(), 6);
         assert!(vertices.contains(&v1));
         assert!(vertices.contains(&v2));
@@ -475,7 +680,7 @@ mod include_vertices {
             &HashSet::new(),
         );
 
-        let vertices: HashSet<Rc<Vertex>> = triangulation.vertices();
+        let vertices = triangulation.vertices();
         assert_eq!(vertices.len(), 6);
         assert!(vertices.contains(&v1));
         assert!(vertices.contains(&v2));
@@ -494,6 +699,53 @@ mod include_vertices {
             assert!(triangles.contains(t));
         }
     } /* end - sample_3 */
+
+    #[test]
+    fn spatial_sorting() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(10.0, 0.0));
+        let vertices: Vec<Rc<Vertex>> = vec![Rc::clone(&v1), Rc::clone(&v2)]
+            .into_iter()
+            .chain((0..200).map(|index| {
+                /* jittered grid, avoiding cocircular vertices */
+                let jitter = ((index * 7919) % 97) as f64 * 1.0E-3;
+                return Rc::new(Vertex::new(
+                    0.5 + (index % 20) as f64 * 0.45 + jitter,
+                    0.5 + (index / 20) as f64 * 0.9 + 0.5 * jitter,
+                ));
+            }))
+            .collect();
+
+        let mut triangulations: Vec<Triangulation> = Vec::new();
+        for spatial_sorting in vec![false, true] {
+            let mut triangulation = Triangulation::from_initial_segment((&v1, &v2));
+            let mut processed_count: usize = 0;
+            triangulation_procedures::vertices::include_observed(
+                &mut triangulation,
+                vertices.clone(),
+                &HashSet::new(),
+                &None,
+                &HashSet::new(),
+                spatial_sorting,
+                &mut |processed, total| {
+                    assert_eq!(total, 200);
+                    processed_count = processed + 1;
+                    return true;
+                },
+            );
+            assert!(processed_count >= 200);
+            triangulations.push(triangulation);
+        }
+
+        /* the delaunay triangulation does not depend on the insertion order */
+        assert_eq!(triangulations[0].vertices().len(), 202);
+        let (triangles, sorted_triangles) =
+            (&triangulations[0].triangles, &triangulations[1].triangles);
+        assert_eq!(triangles.len(), sorted_triangles.len());
+        for triangle in triangles.iter() {
+            assert!(sorted_triangles.iter().any(|t| t == triangle));
+        }
+    } /* end - spatial_sorting */
 } /* end - vertices inclusion */
 
 #[cfg(test)]
@@ -535,6 +787,7 @@ mod may_insert_triangle {
                 .cloned()
                 .collect(),
             )
+            .single()
             .unwrap(),
         );
 
@@ -1,30 +1,131 @@
-use crate::elements::{edge::*, polyline::*, vertex::*};
-use crate::planar::{refine_params::*, triangulation::*};
-use crate::properties::continence::*;
+use crate::elements::{edge::*, polyline::*, segment_index::*, triangle::*, vertex::*};
+use crate::planar::{
+    checkpoint, conforming_mode::*, constraint_chain::*, debug_dump::*, dual_mesh,
+    dual_mesh::VoronoiCell, generators, generators::SeedPattern,
+    insertion_order, progress::*, provenance::*, refine_params::*, tiling::Tiling, timings::*,
+    triangulation::*, triangulation_error::*, validation::InvariantViolation, vertex_attributes::*,
+};
+use crate::properties::{continence::*, distance::*, orientation::*};
 
-use crate::planar::{refine_procedures, triangulation_procedures};
+use crate::planar::{refine_procedures, smoothing, triangulation_procedures};
 
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
 use std::rc::Rc;
 
 pub struct Triangulator {
     pub triangulation: RefCell<Triangulation>,
     pub boundary: Rc<Polyline>,
     pub holes: HashSet<Rc<Polyline>>,
+    pub hole_seeds: Vec<Rc<Vertex>>,
     pub vertices: HashSet<Rc<Vertex>>,
     pub segments: HashSet<Rc<Edge>>,
+    pub mode: ConformingMode,
+    pub provenance: ProvenanceMap,
+    pub record_provenance: bool,
+    pub compact_adjacency: bool,
+    pub attributes: AttributeTable,
+    pub domains: Vec<Rc<Polyline>>,
+    pub disjoint_boundaries: Vec<Rc<Polyline>>,
+    pub max_constraint_length: Option<f64>,
+    pub welding_tolerance: f64,
+    pub snap_tolerance: Option<f64>,
+    pub intersection_grid: Option<f64>,
+    pub spatial_sorting: bool,
+    pub progress_observer: Option<Box<dyn ProgressObserver>>,
+    pub cancellation: Option<CancellationToken>,
+    pub debug_observer: Option<Box<dyn DebugObserver>>,
+    timings: Timings,
+    refine_params: Option<RefineParams>,
 }
 
+/* Upper bound of unencroachment passes while conforming segments */
+const MAX_CONFORMING_PASSES: usize = 64;
+
+/* Vertices held at once while streaming vertex insertion */
+const VERTEX_CHUNK_SIZE: usize = 1 << 16;
+
+/* Distance below which constraint vertices are merged */
+pub const DEFAULT_WELDING_TOLERANCE: f64 = 1.0E-12;
+
 impl Triangulator {
+    /**
+     * Creates a triangulator for the boundary. Boundary vertices closer than
+     * the default welding tolerance are merged, unless the boundary would
+     * degenerate.
+     */
     pub fn new(boundary: &Rc<Polyline>) -> Self {
+        let boundary = match normalize_polyline(boundary, DEFAULT_WELDING_TOLERANCE) {
+            Some(welded) => welded,
+            None => Rc::clone(boundary),
+        };
+
         Self {
             triangulation: RefCell::new(Triangulation::new()),
-            boundary: Rc::clone(boundary),
+            boundary,
             holes: HashSet::new(),
+            hole_seeds: Vec::new(),
             vertices: HashSet::new(),
             segments: HashSet::new(),
+            mode: ConformingMode::Constrained,
+            provenance: ProvenanceMap::new(),
+            record_provenance: false,
+            compact_adjacency: false,
+            attributes: AttributeTable::new(),
+            domains: Vec::new(),
+            disjoint_boundaries: Vec::new(),
+            max_constraint_length: None,
+            welding_tolerance: DEFAULT_WELDING_TOLERANCE,
+            snap_tolerance: None,
+            intersection_grid: None,
+            spatial_sorting: true,
+            progress_observer: None,
+            cancellation: None,
+            debug_observer: None,
+            timings: Timings::new(),
+            refine_params: None,
+        }
+    }
+
+    /**
+     * Creates a triangulator whose constraint vertices closer than the
+     * tolerance are merged, as well as zero length constraint edges dropped.
+     * Returns None if the boundary degenerates after welding.
+     */
+    pub fn with_welding_tolerance(boundary: &Rc<Polyline>, tolerance: f64) -> Option<Self> {
+        let mut triangulator = Self::new(&normalize_polyline(boundary, tolerance)?);
+        triangulator.welding_tolerance = tolerance;
+        return Some(triangulator);
+    }
+
+    /**
+     * Creates a triangulator whose boundary is the union of the domains,
+     * which may share edges. Shared edges are kept as segment constraints,
+     * so that no triangle crosses the interfaces. Returns None if the union
+     * is not a single domain.
+     */
+    pub fn from_domains(domains: &Vec<Rc<Polyline>>) -> Option<Self> {
+        let mut pending: Vec<Rc<Polyline>> = domains.iter().cloned().collect();
+        let mut boundary: Rc<Polyline> = Rc::clone(pending.get(0)?);
+        pending.remove(0);
+
+        while !pending.is_empty() {
+            let united = pending.iter().enumerate().find_map(|(index, domain)| {
+                let (union, _) = Polyline::union(&boundary, domain)?;
+                return Some((index, union));
+            });
+
+            let (index, union) = united?;
+            boundary = Rc::new(union);
+            pending.remove(index);
+        }
+
+        let mut triangulator = Self::new(&boundary);
+        if triangulator.insert_domains(domains).is_err() {
+            return None;
         }
+        return Some(triangulator);
     }
 
     /**
@@ -32,16 +133,45 @@ impl Triangulator {
      * the boundary or it is inside any hole, no vertices are inserted
      * and the conflicting set is returned. If out of bounds condition
      * is not met, vertices are inserted into the hashSet. No duplicate
-     * vertex is kept.
+     * vertex is kept. If the snap tolerance is set, vertices within it from
+     * the boundary or holes are snapped onto them, splitting their edges,
+     * instead of being conflicting.
      */
     pub fn insert_vertices(
         &mut self,
         vertices: &HashSet<Rc<Vertex>>,
-    ) -> Result<&Self, HashSet<Rc<Vertex>>> {
+    ) -> Result<&Self, TriangulationError> {
         if vertices.is_empty() {
             return Ok(self);
         }
 
+        /* Vertices welding to existing constraint vertices are dropped */
+        let mut welded_vertices: HashSet<Rc<Vertex>> = HashSet::new();
+        for vertex in vertices.iter() {
+            let welded = self.weld_vertex(vertex, &welded_vertices);
+            if Rc::ptr_eq(&welded, vertex) {
+                welded_vertices.insert(welded);
+            }
+        }
+        let vertices = &welded_vertices;
+
+        /* Vertices on the boundary or holes, within the snap tolerance, split them */
+        let mut snapped_vertices: Vec<Rc<Vertex>> = Vec::new();
+        let mut unsnapped_vertices: HashSet<Rc<Vertex>> = HashSet::new();
+        for vertex in vertices.iter() {
+            let snapped = match self.snap_tolerance {
+                Some(tolerance) => self.snap_vertex(vertex, tolerance),
+                None => None,
+            };
+            match snapped {
+                Some(snapped) => snapped_vertices.push(snapped),
+                None => {
+                    unsnapped_vertices.insert(Rc::clone(vertex));
+                }
+            }
+        }
+        let vertices = &unsnapped_vertices;
+
         let mut panic_vertices: HashSet<Rc<Vertex>> = HashSet::new();
 
         /* Boundary continence */
@@ -61,39 +191,45 @@ impl Triangulator {
 
         /* Then retuns error if wrong boundary */
         if !panic_vertices.is_empty() {
-            return Err(panic_vertices);
+            return Err(TriangulationError::outside_vertices(panic_vertices));
+        }
+
+        if !snapped_vertices.is_empty() {
+            self.boundary = Rc::new(self.boundary.split_edges(&snapped_vertices));
+            self.holes = self
+                .holes
+                .iter()
+                .map(|hole| Rc::new(hole.split_edges(&snapped_vertices)))
+                .collect();
         }
 
         /* Inserts vertices if they don't exist already */
+        let segment_index = SegmentIndex::new(self.segments.iter());
+        let mut split_vertices: HashMap<Rc<Edge>, Vec<Rc<Vertex>>> = HashMap::new();
         for vertex in vertices.iter() {
-            let mut should_insert = true;
-            for segment in self
-                .segments
-                .iter()
-                .cloned()
-                .collect::<HashSet<Rc<Edge>>>()
+            let containing = segment_index.containing(vertex);
+            if containing
                 .iter()
+                .any(|segment| &segment.v1 == vertex || &segment.v2 == vertex)
             {
-                if &segment.v1 == vertex || &segment.v2 == vertex {
-                    /* Ignore vertices if they are and end vertex */
-                    should_insert = false;
-                    break;
-                }
-                if segment.contains(vertex) {
-                    /* splits segment if contained */
-                    self.segments.remove(segment);
-                    self.segments
-                        .insert(Rc::new(Edge::new(&segment.v1, vertex)));
-                    self.segments
-                        .insert(Rc::new(Edge::new(vertex, &segment.v2)));
-                    should_insert = false;
-                    break;
-                }
+                /* Ignore vertices if they are and end vertex */
+                continue;
             }
-            if should_insert {
-                self.vertices.insert(Rc::clone(&vertex));
+            match containing.first() {
+                /* splits segment if contained */
+                Some(segment) => split_vertices
+                    .entry(Rc::clone(segment))
+                    .or_insert(Vec::new())
+                    .push(Rc::clone(vertex)),
+                None => {
+                    self.vertices.insert(Rc::clone(&vertex));
+                }
             }
         }
+        for (segment, vertices) in split_vertices.iter() {
+            self.segments.remove(segment);
+            self.segments.extend(split_segment(segment, vertices));
+        }
 
         return Ok(self);
     }
@@ -103,11 +239,32 @@ impl Triangulator {
      * all holes, or if it is not inside the boundary, returns the set of
      * conflicting segments. If out of bounds condition is not met, all
      * segments are inserted. Segments that meet intersection are splited.
+     * Segment ends are welded to constraint vertices within the welding
+     * tolerance, and segments shorter than it are dropped.
      */
     pub fn insert_segments(
         &mut self,
         segments: &HashSet<Rc<Edge>>,
-    ) -> Result<&Self, HashSet<Rc<Edge>>> {
+    ) -> Result<&Self, TriangulationError> {
+        let mut welded_vertices: HashSet<Rc<Vertex>> = HashSet::new();
+        let mut welded_segments: HashSet<Rc<Edge>> = HashSet::new();
+        for segment in segments.iter() {
+            let v1 = self.weld_vertex(&segment.v1, &welded_vertices);
+            welded_vertices.insert(Rc::clone(&v1));
+            let v2 = self.weld_vertex(&segment.v2, &welded_vertices);
+            welded_vertices.insert(Rc::clone(&v2));
+
+            if v1 == v2 || distance(&v1, &v2) <= self.welding_tolerance {
+                continue;
+            }
+            if Rc::ptr_eq(&v1, &segment.v1) && Rc::ptr_eq(&v2, &segment.v2) {
+                welded_segments.insert(Rc::clone(segment));
+            } else {
+                welded_segments.insert(Rc::new(Edge::new(&v1, &v2)));
+            }
+        }
+        let segments = &welded_segments;
+
         if segments.is_empty() {
             return Ok(self);
         }
@@ -136,39 +293,118 @@ impl Triangulator {
         }
 
         if !conflicting_segments.is_empty() {
-            return Err(conflicting_segments);
+            return Err(TriangulationError::outside_segments(conflicting_segments));
         }
 
-        /* Removes vertices */
-        let mut aux_list: Vec<Rc<Edge>> = segments.iter().cloned().collect();
-        let mut segments_to_insert: HashSet<Rc<Edge>> = HashSet::new();
-        while !aux_list.is_empty() {
-            let segment = aux_list.pop().unwrap();
-            let mut should_insert = true;
-            for vertex in self
-                .vertices
-                .iter()
-                .cloned()
-                .collect::<HashSet<Rc<Vertex>>>()
-                .iter()
-            {
-                if &segment.v1 == vertex || &segment.v2 == vertex {
-                    /* Ignore vertices if they are an end vertex */
-                    self.vertices.remove(vertex);
-                }
-                if segment.contains(vertex) {
-                    /* splits segment if contained */
-                    aux_list.push(Rc::new(Edge::new(&segment.v1, vertex)));
-                    aux_list.push(Rc::new(Edge::new(vertex, &segment.v2)));
-                    self.vertices.remove(vertex);
-                    should_insert = false;
-                    break;
+        self.merge_segments(segments);
+        return Ok(self);
+    }
+
+    /**
+     * Inserts an open polyline, such as a crack or a fault line, as a chain
+     * of segment constraints. Unlike insert_segments, its vertices may lie on
+     * the boundary or on holes, which are split there. Edges lying on the
+     * boundary or on holes are already constrained and are dropped. If any
+     * edge is not inside the boundary and outside all holes, returns the set
+     * of conflicting edges and nothing is inserted.
+     */
+    pub fn insert_polyline_constraint(
+        &mut self,
+        polyline: &Rc<Polyline>,
+    ) -> Result<&Self, TriangulationError> {
+        let mut welded_vertices: HashSet<Rc<Vertex>> = HashSet::new();
+        let mut vertices: Vec<Rc<Vertex>> = Vec::new();
+        for vertex in polyline.vertices.iter() {
+            let welded = self.weld_vertex(vertex, &welded_vertices);
+            welded_vertices.insert(Rc::clone(&welded));
+            if vertices.last() != Some(&welded) {
+                vertices.push(welded);
+            }
+        }
+        let polyline = match Polyline::new_opened(vertices) {
+            Some(polyline) => polyline,
+            None => return Ok(self),
+        };
+
+        /* Accumulate conflicting edges, dropping the ones on constraints */
+        let mut segments: HashSet<Rc<Edge>> = HashSet::new();
+        let mut conflicting_segments: HashSet<Rc<Edge>> = HashSet::new();
+        for edge in polyline.into_edges() {
+            let edge_polyline: Polyline =
+                Polyline::new_opened(vec![Rc::clone(&edge.v1), Rc::clone(&edge.v2)]).unwrap();
+
+            let mut is_conflicting = false;
+            let mut is_on_constraint = false;
+            match Polyline::continence(&self.boundary, &edge_polyline) {
+                Some((Continence::Inside, _)) => {}
+                Some((Continence::Boundary, _)) => is_on_constraint = true,
+                _ => is_conflicting = true,
+            }
+            for hole in self.holes.iter() {
+                match Polyline::continence(hole, &edge_polyline) {
+                    Some((Continence::Outside, _)) => {}
+                    Some((Continence::Boundary, _)) => is_on_constraint = true,
+                    _ => is_conflicting = true,
                 }
             }
-            if should_insert {
-                segments_to_insert.insert(Rc::clone(&segment));
+
+            if is_conflicting {
+                conflicting_segments.insert(edge);
+            } else if !is_on_constraint {
+                segments.insert(edge);
+            }
+        }
+
+        if !conflicting_segments.is_empty() {
+            return Err(TriangulationError::outside_segments(conflicting_segments));
+        }
+
+        /* Splits the boundary and holes at contact vertices */
+        let contacts: Vec<Rc<Vertex>> = polyline.vertices.clone();
+        self.boundary = Rc::new(self.boundary.split_edges(&contacts));
+        self.holes = self
+            .holes
+            .iter()
+            .map(|hole| Rc::new(hole.split_edges(&contacts)))
+            .collect();
+
+        self.merge_segments(&segments);
+        return Ok(self);
+    }
+
+    /**
+     * Merges segments into existing ones, splitting them at intersections and
+     * at contained vertices, which are not kept as vertices anymore.
+     */
+    fn merge_segments(&mut self, segments: &HashSet<Rc<Edge>>) {
+        /* Removes vertices on segments, which split them unless they are ends */
+        let segment_index = SegmentIndex::new(segments.iter());
+        let mut split_vertices: HashMap<Rc<Edge>, Vec<Rc<Vertex>>> = HashMap::new();
+        let mut covered_vertices: Vec<Rc<Vertex>> = Vec::new();
+        for vertex in self.vertices.iter() {
+            let containing = segment_index.containing(vertex);
+            if containing.is_empty() {
+                continue;
+            }
+            for segment in containing.iter() {
+                split_vertices
+                    .entry(Rc::clone(segment))
+                    .or_insert(Vec::new())
+                    .push(Rc::clone(vertex));
             }
+            covered_vertices.push(Rc::clone(vertex));
         }
+        for vertex in covered_vertices.iter() {
+            self.vertices.remove(vertex);
+        }
+        let segments_to_insert: HashSet<Rc<Edge>> = segments
+            .iter()
+            .map(|segment| match split_vertices.get(segment) {
+                Some(vertices) => split_segment(segment, vertices),
+                None => vec![Rc::clone(segment)],
+            })
+            .flatten()
+            .collect();
 
         /* Split segments */
         let new_vertex_pairs =
@@ -177,29 +413,274 @@ impl Triangulator {
         let existing_vertex_pairs =
             Edge::into_vertex_pairs(self.segments.iter().cloned().collect());
 
-        let splited_segments = split_intersections(
+        let splited_segments = split_intersections_snapped(
             &new_vertex_pairs
                 .iter()
                 .chain(existing_vertex_pairs.iter())
                 .cloned()
                 .collect(),
+            self.intersection_grid.unwrap_or(0.0),
         );
 
         self.segments = Edge::from_vertex_pairs(splited_segments)
             .iter()
             .cloned()
             .collect();
+    }
+
+    /**
+     * Removes the segment constraint, along with the Steiner points inserted
+     * on it, either by intersections with other segments or by refinement,
+     * unless they are ends of other constraints or inserted vertices. The
+     * triangles around the segment are flipped back to Delaunay. Returns
+     * false if no segment constraint lies on the segment.
+     */
+    pub fn remove_segment(&mut self, segment: &Edge) -> bool {
+        let pieces: HashSet<Rc<Edge>> = self
+            .segments
+            .iter()
+            .filter(|s| segment.contains(&s.v1) && segment.contains(&s.v2))
+            .cloned()
+            .collect();
+        if pieces.is_empty() {
+            return false;
+        }
+        self.segments = self.segments.difference(&pieces).cloned().collect();
+
+        let remaining_constraints = self.constraint_edges();
+        let is_kept = |vertex: &Rc<Vertex>| {
+            *vertex == segment.v1
+                || *vertex == segment.v2
+                || self.vertices.contains(vertex)
+                || remaining_constraints.iter().any(|c| c.contains(vertex))
+        };
+
+        self.marked_constraints();
+        let mut triangulation = self.triangulation.borrow_mut();
+
+        /* unmarks the subsegments of the removed segment */
+        let removed_subsegments: Vec<Rc<Edge>> = triangulation
+            .constraints()
+            .iter()
+            .filter(|e| {
+                !remaining_constraints
+                    .iter()
+                    .any(|c| c.contains(&e.v1) && c.contains(&e.v2))
+            })
+            .cloned()
+            .collect();
+        for subsegment in removed_subsegments.iter() {
+            triangulation.unconstrain(subsegment);
+        }
+
+        let steiner_vertices: Vec<Rc<Vertex>> = triangulation
+            .vertices()
+            .iter()
+            .filter(|v| segment.contains(v) && !is_kept(v))
+            .cloned()
+            .collect();
+
+        let former_triangles = triangulation.triangles.clone();
+        for vertex in steiner_vertices.iter() {
+            triangulation.remove_vertex(vertex);
+        }
+
+        /* flips from the new triangles and from the former subsegments */
+        let pending: Vec<Rc<Edge>> = triangulation
+            .triangles
+            .difference(&former_triangles)
+            .map(|t| {
+                let (e1, e2, e3) = t.inner_edges();
+                return vec![e1, e2, e3];
+            })
+            .flatten()
+            .chain(
+                triangulation
+                    .edges()
+                    .iter()
+                    .filter(|e| segment.contains(&e.v1) && segment.contains(&e.v2)),
+            )
+            .collect();
+        triangulation.legalize(pending);
+
+        self.provenance.retain(&triangulation);
+        return true;
+    }
+
+    /**
+     * Inserts domains, that lie inside the boundary and may share edges.
+     * Domain edges inside the boundary are inserted as segment constraints,
+     * split at every other domain vertex, and triangles are assigned to the
+     * domain they fall in. Returns the interfaces conflicting with holes, if
+     * any.
+     */
+    pub fn insert_domains(
+        &mut self,
+        domains: &Vec<Rc<Polyline>>,
+    ) -> Result<&Self, TriangulationError> {
+        let domains_vertices: Vec<Rc<Vertex>> = domains
+            .iter()
+            .map(|domain| domain.vertices.iter().cloned())
+            .flatten()
+            .chain(self.boundary.vertices.iter().cloned())
+            .collect::<HashSet<Rc<Vertex>>>()
+            .into_iter()
+            .collect();
+
+        let interfaces: HashSet<Rc<Edge>> = domains
+            .iter()
+            .map(|domain| domain.split_edges(&domains_vertices).into_edges())
+            .flatten()
+            .filter(|edge| self.boundary.contains(&edge.midpoint()) == Some(Continence::Inside))
+            .fold(HashSet::new(), |mut acc, edge| {
+                /* shared edges come in both orientations */
+                if !acc.contains(&Rc::new(edge.opposite())) {
+                    acc.insert(edge);
+                }
+                return acc;
+            });
+
+        /* interfaces end at the boundary, thus only holes may conflict */
+        let conflicting_interfaces: HashSet<Rc<Edge>> = interfaces
+            .iter()
+            .filter(|interface| {
+                let interface_polyline = interface.as_polyline().unwrap();
+                return self.holes.iter().any(|hole| {
+                    Polyline::continence(hole, &interface_polyline)
+                        != Some((Continence::Outside, BoundaryInclusion::Open))
+                });
+            })
+            .cloned()
+            .collect();
+
+        if !conflicting_interfaces.is_empty() {
+            return Err(TriangulationError::outside_segments(conflicting_interfaces));
+        }
+
+        self.merge_segments(&interfaces);
+        self.domains = domains.iter().cloned().collect();
+        return Ok(self);
+    }
+
+    /**
+     * Index of the domain the triangle falls in. Returns None if ghost or
+     * if no domain contains it.
+     */
+    pub fn domain_of(&self, triangle: &Triangle) -> Option<usize> {
+        if triangle.is_ghost() {
+            return None;
+        }
+        let center = triangle.center();
+        return self
+            .domains
+            .iter()
+            .position(|domain| domain.contains(&center) == Some(Continence::Inside));
+    }
+
+    /**
+     * Domain index of every solid triangle contained by a domain.
+     */
+    pub fn domain_membership(&self) -> HashMap<Rc<Triangle>, usize> {
+        return self
+            .triangulation
+            .borrow()
+            .triangles
+            .iter()
+            .filter_map(|t| Some((Rc::clone(t), self.domain_of(t)?)))
+            .collect();
+    }
+
+    /**
+     * Voronoi cells of the vertices clipped by the regions, which are the
+     * domains if any, else the boundary and the disjoint ones, and out of
+     * the holes. Regions are indexed as domain_of and component_of do.
+     */
+    pub fn voronoi_cells(&self) -> Vec<VoronoiCell> {
+        let regions: Vec<Rc<Polyline>> = match self.domains.is_empty() {
+            true => vec![Rc::clone(&self.boundary)]
+                .into_iter()
+                .chain(self.disjoint_boundaries.iter().cloned())
+                .collect(),
+            false => self.domains.clone(),
+        };
+        let holes: Vec<Rc<Polyline>> = self.holes.iter().cloned().collect();
+        return dual_mesh::clipped_cells(&self.triangulation.borrow(), &regions, &holes);
+    }
+
+    /**
+     * Includes a triangulated disconnected component, whose boundary lies
+     * outside the boundary and the disjoint boundaries of this one. Its
+     * triangles are kept as they are, around ghost loops of their own, and
+     * its boundary joins the disjoint boundaries, as well as its holes,
+     * segments and vertices join these ones. Triangulation and refinement
+     * only apply to the boundary, thus components are included once refined,
+     * and triangulating or refining again fails afterwards. Returns the
+     * component boundary vertices, if it overlaps any boundary.
+     */
+    pub fn include_component(&mut self, component: Triangulator) -> Result<&Self, TriangulationError> {
+        let is_disjoint = vec![&self.boundary]
+            .into_iter()
+            .chain(self.disjoint_boundaries.iter())
+            .all(|boundary| {
+                return Polyline::continence(boundary, &component.boundary)
+                    == Some((Continence::Outside, BoundaryInclusion::Open))
+                    && Polyline::continence(&component.boundary, boundary)
+                        == Some((Continence::Outside, BoundaryInclusion::Open));
+            });
+        if !is_disjoint
+            || !self
+                .triangulation
+                .borrow_mut()
+                .include_component(&component.triangulation.borrow())
+        {
+            return Err(TriangulationError::outside_vertices(
+                component.boundary.vertices.iter().cloned().collect(),
+            ));
+        }
 
+        self.disjoint_boundaries.push(Rc::clone(&component.boundary));
+        self.disjoint_boundaries.extend(component.disjoint_boundaries.into_iter());
+        self.holes.extend(component.holes.into_iter());
+        self.segments.extend(component.segments.into_iter());
+        self.vertices.extend(component.vertices.into_iter());
+        self.attributes.extend(component.attributes);
+        self.timings.merge(&component.timings);
         return Ok(self);
     }
 
+    /**
+     * Index of the disconnected component the triangle falls in, 0 for the
+     * boundary and the following ones for the disjoint boundaries. Returns
+     * None if ghost or out of every boundary.
+     */
+    pub fn component_of(&self, triangle: &Triangle) -> Option<usize> {
+        if triangle.is_ghost() {
+            return None;
+        }
+        let center = triangle.center();
+        return vec![&self.boundary]
+            .into_iter()
+            .chain(self.disjoint_boundaries.iter())
+            .position(|boundary| boundary.contains(&center) == Some(Continence::Inside));
+    }
+
     /**
      * Inserts hole. If hole intercepts the boundary, any existing hole, or
      * existing segments returns the set of conflicting vertices. If not,
      * hole is inserted. If any existing vertex or segment belongs to the
-     * hole, it is removed.
+     * hole, it is removed. Hole vertices are welded within the welding
+     * tolerance, and the hole vertices are returned if it degenerates.
      */
-    pub fn insert_hole(&mut self, hole: &Rc<Polyline>) -> Result<&Self, HashSet<Rc<Vertex>>> {
+    pub fn insert_hole(&mut self, hole: &Rc<Polyline>) -> Result<&Self, TriangulationError> {
+        let hole = &match normalize_polyline(hole, self.welding_tolerance) {
+            Some(welded) => welded,
+            None => {
+                return Err(TriangulationError::DegenerateInput(String::from(
+                    "hole with less than 3 vertices after welding",
+                )))
+            }
+        };
+
         let mut conflicting_vertices: HashSet<Rc<Vertex>> = HashSet::new();
 
         let is_hole_inside_boundary = Polyline::continence(&self.boundary, hole)
@@ -227,7 +708,7 @@ impl Triangulator {
         }
 
         if !conflicting_vertices.is_empty() {
-            return Err(conflicting_vertices);
+            return Err(TriangulationError::outside_vertices(conflicting_vertices));
         }
 
         for segment in self.segments.iter() {
@@ -242,7 +723,7 @@ impl Triangulator {
         }
 
         if !conflicting_vertices.is_empty() {
-            return Err(conflicting_vertices);
+            return Err(TriangulationError::outside_vertices(conflicting_vertices));
         }
 
         self.holes.insert(Rc::clone(hole));
@@ -250,1039 +731,4414 @@ impl Triangulator {
     }
 
     /**
-     * Refine the triangulation. Raises triangulation error if any.
-     * Else refines ans returns the triangulation.
+     * Removes the hole, whose edges are kept as segment constraints. If
+     * already triangulated, the ghost triangles inside the hole are replaced
+     * by the constrained Delaunay triangulation of the hole polygon, split at
+     * any Steiner points on its edges. Returns false if there is no such
+     * hole, or if its polygon could not be triangulated, in which case the
+     * triangulator is left unchanged.
      */
-    pub fn refine(&mut self, params: RefineParams) -> &Self {
-        let mut segment_constraints: HashSet<Rc<Edge>> = self
-            .holes
-            .iter()
-            .map(|hole| hole.into_edges())
-            .flatten()
-            .chain(self.boundary.into_edges())
-            .chain(self.segments.iter().cloned())
-            .collect();
-
-        let (segments_splitting, included_triangles, removed_triangles) =
-            refine_procedures::encroachment::unencroach(
-                &mut self.triangulation.borrow_mut(),
-                &segment_constraints,
-                &Some(Rc::clone(&self.boundary)),
-                &self.holes,
-            );
+    pub fn remove_hole(&mut self, hole: &Rc<Polyline>) -> bool {
+        let hole = match normalize_polyline(hole, self.welding_tolerance) {
+            Some(welded) => welded,
+            None => return false,
+        };
+        if !self.holes.contains(&hole) {
+            return false;
+        }
+        let hole_edges = hole.into_edges();
+
+        /* ghost triangles of the loop inside the hole, counterclockwise around it */
+        let filling = {
+            let triangulation = self.triangulation.borrow();
+            let ghost_vertex = triangulation.ghost_loops().into_iter().find(|ghost| {
+                return triangulation
+                    .loop_edges(ghost)
+                    .iter()
+                    .any(|g| hole_edges.iter().any(|e| e.contains(&g.v1) && e.contains(&g.v2)));
+            });
+            match ghost_vertex {
+                Some(ghost_vertex) => {
+                    let ghosts: Vec<(Rc<Triangle>, Rc<Edge>)> = triangulation
+                        .ghost_triangles()
+                        .filter_map(|t| Some((Rc::clone(t), t.opposite_edge(&ghost_vertex)?)))
+                        .collect();
+                    let next: HashMap<Rc<Vertex>, Rc<Vertex>> = ghosts
+                        .iter()
+                        .map(|(_, edge)| (Rc::clone(&edge.v1), Rc::clone(&edge.v2)))
+                        .collect();
+
+                    let mut polygon: Vec<Rc<Vertex>> = vec![Rc::clone(&ghosts[0].1.v1)];
+                    while let Some(vertex) = next.get(polygon.last().unwrap()) {
+                        if *vertex == polygon[0] || polygon.len() > next.len() {
+                            break;
+                        }
+                        polygon.push(Rc::clone(vertex));
+                    }
+                    match clip_ears(polygon) {
+                        Some(new_triangles) => Some((ghosts, new_triangles)),
+                        None => return false,
+                    }
+                }
+                None => None,
+            }
+        };
+
+        self.holes.remove(&hole);
+        self.merge_segments(&hole_edges.iter().cloned().collect());
+        let (ghosts, new_triangles) = match filling {
+            Some(filling) => filling,
+            None => return true,
+        };
+
+        /* flips to constrained Delaunay, the hole edges being segments now */
+        self.marked_constraints();
+        let mut triangulation = self.triangulation.borrow_mut();
+        for (ghost, edge) in ghosts.iter() {
+            triangulation.remove_triangle(ghost);
+            triangulation.constrain(edge);
+        }
+        for triangle in new_triangles.iter() {
+            triangulation.include_triangle(triangle);
+        }
 
-        segment_constraints = segment_constraints
+        let pending: Vec<Rc<Edge>> = new_triangles
             .iter()
-            .filter(|&s| {
-                let removed_segments = segments_splitting
-                    .values()
-                    .cloned()
-                    .collect::<HashSet<Rc<Edge>>>();
-                return !removed_segments.contains(s) && !removed_segments.contains(&s.opposite());
+            .map(|t| {
+                let (e1, e2, e3) = t.inner_edges();
+                return vec![e1, e2, e3];
             })
-            .chain(segments_splitting.keys())
-            .cloned()
+            .flatten()
             .collect();
+        triangulation.legalize(pending);
 
-        let segments_splitting = refine_procedures::triangle_split::split_irregular(
-            &mut self.triangulation.borrow_mut(),
-            &params,
-            &segment_constraints,
-            &Some(Rc::clone(&self.boundary)),
-            &self.holes,
-        );
+        return true;
+    }
 
-        segment_constraints = segment_constraints
+    /**
+     * Inserts seed points of holes, which mark holes by a point instead of a
+     * polyline. On triangulation, the triangles reached from each seed
+     * without crossing constraints are carved, and the loop of constraints
+     * around them becomes a hole. Returns the seeds that are not inside the
+     * boundary and outside the holes, inserting none of them.
+     */
+    pub fn insert_hole_seeds(
+        &mut self,
+        seeds: &Vec<Rc<Vertex>>,
+    ) -> Result<&Self, TriangulationError> {
+        let outside_vertices: HashSet<Rc<Vertex>> = seeds
             .iter()
-            .filter(|&s| {
-                let removed_segments = segments_splitting
-                    .values()
-                    .cloned()
-                    .collect::<HashSet<Rc<Edge>>>();
-                return !removed_segments.contains(s) && !removed_segments.contains(&s.opposite());
+            .filter(|seed| {
+                self.boundary.contains(seed) != Some(Continence::Inside)
+                    || self
+                        .holes
+                        .iter()
+                        .any(|hole| hole.contains(seed) != Some(Continence::Outside))
             })
-            .chain(segments_splitting.keys())
             .cloned()
             .collect();
+        if !outside_vertices.is_empty() {
+            return Err(TriangulationError::outside_vertices(outside_vertices));
+        }
 
-        return self;
+        self.hole_seeds.extend(seeds.iter().cloned());
+        return Ok(self);
     }
 
     /**
-     * Triangulates
+     * Index of the first seed whose region, reached without crossing
+     * constraints, contains each solid triangle. Triangles of no seed region
+     * are left out, as well as seeds out of the triangulation.
      */
-    pub fn triangulate(&mut self) -> &Self {
-        /* Initialize triangulation */
-        let v1 = self.boundary.vertices.get(0).unwrap();
-        let v2 = self.boundary.vertices.get(1).unwrap();
-        let mut triangulation = Triangulation::from_initial_segment((&v1, &v2));
-
-        /* 1 Boundary inclusion */
-        triangulation_procedures::boundary::include(
-            &mut triangulation,
-            &self.boundary,
-            &HashSet::new(),
-        );
-
-        /* boundary segments as segment constraints */
-        let mut segment_constraints: HashSet<Rc<Edge>> =
-            self.boundary.into_edges().iter().cloned().collect();
+    pub fn seeded_regions(&self, seeds: &[Rc<Vertex>]) -> HashMap<Rc<Triangle>, usize> {
+        let triangulation = self.triangulation.borrow();
+        let mut regions: HashMap<Rc<Triangle>, usize> = HashMap::new();
+        for (index, seed) in seeds.iter().enumerate() {
+            let start = match triangulation.locate(seed) {
+                Some(start) => start,
+                None => continue,
+            };
+            if regions.contains_key(&start) {
+                continue;
+            }
+            for triangle in triangulation
+                .flood_fill(&start, |edge| !triangulation.is_constrained(edge))
+                .into_iter()
+            {
+                regions.entry(triangle).or_insert(index);
+            }
+        }
+        return regions;
+    }
 
-        /* 2 Holes inclusion */
-        for hole in self.holes.iter() {
-            triangulation_procedures::hole::include(&mut triangulation, hole, &segment_constraints);
+    /**
+     * Closed loops of constrained segments inside the boundary, detected on
+     * the triangulation as the regions bounded by a single loop of
+     * constraints, away from the boundary and holes. Regions holding loops
+     * nested in them are bounded by several loops, thus only the innermost
+     * loops are found. Loops are sorted by their seeds.
+     */
+    pub fn detect_loops(&self) -> Vec<ConstraintLoop> {
+        let triangulation = self.triangulation.borrow();
+        let mut visited: HashSet<Rc<Triangle>> = HashSet::new();
+        let mut loops: Vec<ConstraintLoop> = Vec::new();
+        for triangle in triangulation.solid_triangles() {
+            if visited.contains(triangle) {
+                continue;
+            }
+            let region =
+                triangulation.flood_fill(triangle, |edge| !triangulation.is_constrained(edge));
+            visited.extend(region.iter().cloned());
 
-            segment_constraints = segment_constraints
+            let polyline = match triangulation_procedures::hole::enclosure(&triangulation, &region)
+            {
+                Ok((polyline, _, _)) => polyline,
+                Err(_) => continue,
+            };
+            /* center of the widest triangle, strictly inside the loop */
+            let seed = region
                 .iter()
-                .chain(hole.into_edges().iter())
-                .cloned()
-                .collect();
-        }
-
-        /* 3 Include Segment Constraints */
-        for segment in self.segments.iter() {
-            triangulation_procedures::segment::include(
-                &mut triangulation,
-                segment,
-                &segment_constraints,
-            );
-            segment_constraints.insert(Rc::clone(segment));
+                .max_by(|t1, t2| t1.area().partial_cmp(&t2.area()).unwrap())
+                .unwrap()
+                .center();
+            loops.push(ConstraintLoop {
+                polyline,
+                seed: Rc::new(seed),
+                triangles: region,
+            });
         }
 
-        /* 4 Include remaining Vertices */
-        triangulation_procedures::vertices::include(
-            &mut triangulation,
-            self.vertices.iter().cloned().collect(),
-            &segment_constraints,
-            &Some(Rc::clone(&self.boundary)),
-            &self.holes,
-        );
-
-        self.triangulation = RefCell::new(triangulation);
-
-        return self;
+        loops.sort_by(|l1, l2| l1.seed.cmp(&l2.seed));
+        return loops;
     }
-} /* end - module */
+
+    /**
+     * Carves the detected loop as a hole, as if its seed was inserted as a
+     * hole seed. Constraints inside the loop are carved along with it.
+     * Returns an error, carving nothing, if the loop is no longer enclosed.
+     */
+    pub fn carve_loop(
+        &mut self,
+        constraint_loop: &ConstraintLoop,
+    ) -> Result<&Self, TriangulationError> {
+        let hole = triangulation_procedures::hole::carve(
+            &mut self.triangulation.borrow_mut(),
+            &constraint_loop.seed,
+        )?;
+        self.hole_seeds.push(Rc::clone(&constraint_loop.seed));
+        self.include_carved_holes(vec![Rc::new(hole)]);
+        self.provenance.retain(&self.triangulation.borrow());
+        return Ok(self);
+    }
+
+    /**
+     * Registers the carved holes, dropping the constraints they hold.
+     */
+    fn include_carved_holes(&mut self, holes: Vec<Rc<Polyline>>) {
+        for hole in holes.into_iter() {
+            /* constraints inside the hole, or along it, are carved along with it */
+            let is_carved = |vertex: &Vertex| hole.contains(vertex) != Some(Continence::Outside);
+            self.segments.retain(|segment| {
+                !(is_carved(&segment.v1)
+                    && is_carved(&segment.v2)
+                    && is_carved(&segment.midpoint()))
+            });
+            self.vertices.retain(|vertex| !is_carved(vertex));
+            self.holes.insert(hole);
+        }
+    }
+
+    /**
+     * Refine the triangulation. Panics on refinement errors, other than
+     * cancellation. Else refines ans returns the triangulation.
+     */
+    #[deprecated(
+        since = "0.1.1",
+        note = "panics on refinement errors, use try_refine instead"
+    )]
+    pub fn refine(&mut self, params: RefineParams) -> &Self {
+        #[allow(deprecated)]
+        return self.refine_observed(params, &mut |_, _| {});
+    }
+
+    /**
+     * Refines as refine, raising the cancelled error if the cancellation
+     * token aborted refinement, and the non terminating refinement error if
+     * splits went below the edge length floor.
+     */
+    pub fn try_refine(&mut self, params: RefineParams) -> Result<&Self, TriangulationError> {
+        return self.try_refine_observed(params, &mut |_, _| {});
+    }
+
+    /**
+     * Refines as refine, calling the observer after each triangle split with
+     * the triangulation and the count of splits so far. Refinement stops
+     * early, keeping a valid triangulation, once the cancellation token is
+     * cancelled.
+     */
+    #[deprecated(
+        since = "0.1.1",
+        note = "panics on refinement errors, use try_refine_observed instead"
+    )]
+    pub fn refine_observed(
+        &mut self,
+        params: RefineParams,
+        observer: &mut dyn FnMut(&Triangulation, usize),
+    ) -> &Self {
+        if let Err(error) = self.try_refine_observed(params, observer) {
+            match error {
+                TriangulationError::Cancelled => {}
+                error => panic!("{}", error),
+            }
+        }
+        return self;
+    }
+
+    /**
+     * Refines as refine_observed, returning an error instead of refining
+     * forever once splits create edges shorter than the edge length floor.
+     * The triangulation is kept valid, refined up to that split, and the
+     * error lists the triangles around the short edges.
+     */
+    pub fn try_refine_observed(
+        &mut self,
+        params: RefineParams,
+        observer: &mut dyn FnMut(&Triangulation, usize),
+    ) -> Result<&Self, TriangulationError> {
+        if !self.disjoint_boundaries.is_empty() {
+            return Err(disjoint_components_error());
+        }
+        self.refine_params = Some(params.clone());
+
+        let mut segment_constraints: HashSet<Rc<Edge>> = self.marked_constraints();
+
+        let candidates = self.provenance_candidates();
+        let former_triangles = self.provenance_snapshot(&self.triangulation.borrow());
+
+        let start = Instant::now();
+        refine_procedures::encroachment::unencroach(
+                &mut self.triangulation.borrow_mut(),
+                &segment_constraints,
+                &Some(Rc::clone(&self.boundary)),
+                &self.holes,
+            );
+        self.timings.record_since(Phase::Unencroachment, start);
+
+        segment_constraints = self.triangulation.borrow().constraints().clone();
+        dump(
+            &mut self.debug_observer,
+            Phase::Unencroachment,
+            &self.triangulation.borrow(),
+            &segment_constraints,
+        );
+
+        self.record_steiner_provenance(&former_triangles, &candidates);
+        let former_triangles = self.provenance_snapshot(&self.triangulation.borrow());
+
+        let progress_observer = &mut self.progress_observer;
+        let cancellation = &self.cancellation;
+        let mut observe_split = |triangulation: &Triangulation, splits: usize, pending: usize| {
+            observer(triangulation, splits);
+            let total = splits + pending;
+            return report(
+                progress_observer,
+                cancellation,
+                Phase::TriangleSplit,
+                splits,
+                total,
+            );
+        };
+
+        let edge_length_floor = refine_procedures::triangle_split::edge_length_floor(
+            &self.triangulation.borrow(),
+            &segment_constraints,
+        );
+        let mut stalled: Vec<Rc<Triangle>> = Vec::new();
+
+        let start = Instant::now();
+        refine_procedures::triangle_split::split_irregular_recording(
+            &mut self.triangulation.borrow_mut(),
+            &params,
+            &segment_constraints,
+            &Some(Rc::clone(&self.boundary)),
+            &self.holes,
+            &mut observe_split,
+            &mut stalled,
+        );
+        self.timings.record_since(Phase::TriangleSplit, start);
+
+        segment_constraints = self.triangulation.borrow().constraints().clone();
+        dump(
+            &mut self.debug_observer,
+            Phase::TriangleSplit,
+            &self.triangulation.borrow(),
+            &segment_constraints,
+        );
+
+        self.record_steiner_provenance(&former_triangles, &candidates);
+
+        if !stalled.is_empty() {
+            return Err(TriangulationError::NonTerminatingRefinement {
+                triangles: stalled,
+                edge_length_floor,
+            });
+        }
+        if is_cancelled(&self.cancellation) {
+            return Err(TriangulationError::Cancelled);
+        }
+        return Ok(self);
+    }
+
+    /**
+     * Recovers the segment constraints by inserting Steiner points on the
+     * segments themselves, until no vertex encroaches any subsegment. No
+     * quality refinement is made, so that inner vertices are kept as given.
+     * Boundary, holes and segments are updated with the Steiner points.
+     * Panics if segments are still encroached after the conforming passes.
+     */
+    #[deprecated(
+        since = "0.1.1",
+        note = "panics on conforming errors, use try_conform instead"
+    )]
+    pub fn conform(&mut self) -> &Self {
+        if let Err(error) = self.try_conform() {
+            panic!("{}", error);
+        }
+        return self;
+    }
+
+    /**
+     * Conforms as conform, raising the non terminating refinement error, with
+     * the triangles along the last split subsegments, if segments are still
+     * encroached after the conforming passes. Boundary, holes and segments
+     * are updated with the Steiner points inserted so far anyway.
+     */
+    pub fn try_conform(&mut self) -> Result<&Self, TriangulationError> {
+        return self.conform_passes(MAX_CONFORMING_PASSES);
+    }
+
+    fn conform_passes(&mut self, passes: usize) -> Result<&Self, TriangulationError> {
+        self.mode = ConformingMode::Conforming;
+
+        let candidates = self.provenance_candidates();
+        let former_triangles = self.provenance_snapshot(&self.triangulation.borrow());
+
+        let mut segment_constraints: HashSet<Rc<Edge>> = self.marked_constraints();
+        let mut segments_splitting: HashMap<Rc<Edge>, Rc<Edge>> = HashMap::new();
+
+        for _ in 0..passes {
+            let start = Instant::now();
+            segments_splitting = refine_procedures::encroachment::unencroach(
+                &mut self.triangulation.borrow_mut(),
+                &segment_constraints,
+                &Some(Rc::clone(&self.boundary)),
+                &self.holes,
+            )
+            .0;
+            self.timings.record_since(Phase::Unencroachment, start);
+
+            if segments_splitting.is_empty() {
+                break;
+            }
+            segment_constraints = self.triangulation.borrow().constraints().clone();
+            dump(
+                &mut self.debug_observer,
+                Phase::Unencroachment,
+                &self.triangulation.borrow(),
+                &segment_constraints,
+            );
+        }
+
+        self.record_steiner_provenance(&former_triangles, &candidates);
+
+        let steiner_vertices: Vec<Rc<Vertex>> = segment_constraints
+            .iter()
+            .map(|s| vec![Rc::clone(&s.v1), Rc::clone(&s.v2)])
+            .flatten()
+            .collect::<HashSet<Rc<Vertex>>>()
+            .into_iter()
+            .collect();
+
+        self.boundary = Rc::new(self.boundary.split_edges(&steiner_vertices));
+        self.holes = self
+            .holes
+            .iter()
+            .map(|hole| Rc::new(hole.split_edges(&steiner_vertices)))
+            .collect();
+        self.segments = self
+            .segments
+            .iter()
+            .map(|segment| {
+                Edge::from_vertex_pairs(vertex_pairs(
+                    &segment.as_polyline().unwrap().split_edges(&steiner_vertices).vertices,
+                    true,
+                ))
+            })
+            .flatten()
+            .collect();
+
+        if !segments_splitting.is_empty() {
+            let triangulation = self.triangulation.borrow();
+            let triangles: Vec<Rc<Triangle>> = segments_splitting
+                .keys()
+                .map(|edge| vec![Edge::new(&edge.v1, &edge.v2), edge.opposite()])
+                .flatten()
+                .filter_map(|edge| triangulation.adjacency.get(&edge))
+                .filter(|triangle| !triangle.is_ghost())
+                .cloned()
+                .collect::<HashSet<Rc<Triangle>>>()
+                .into_iter()
+                .collect();
+            return Err(TriangulationError::NonTerminatingRefinement {
+                triangles,
+                edge_length_floor: refine_procedures::triangle_split::edge_length_floor(
+                    &triangulation,
+                    &segment_constraints,
+                ),
+            });
+        }
+        return Ok(self);
+    }
+
+    /**
+     * Smooths the triangulation by Lloyd relaxation. Boundary, holes and
+     * segment vertices, as well as inserted vertices, are kept.
+     */
+    pub fn smooth(&mut self, iterations: usize) -> &Self {
+        self.marked_constraints();
+        smoothing::lloyd(&mut self.triangulation.borrow_mut(), &self.vertices, iterations);
+
+        /* moved vertices are given new triangles, with no provenance */
+        self.provenance.retain(&self.triangulation.borrow());
+
+        return self;
+    }
+
+    /**
+     * Optimizes the triangulation by Optimal Delaunay Triangulation smoothing,
+     * reducing interpolation error. Boundary, holes and segment vertices, as
+     * well as inserted vertices, are kept.
+     */
+    pub fn optimize(&mut self, iterations: usize) -> &Self {
+        self.marked_constraints();
+        smoothing::odt(&mut self.triangulation.borrow_mut(), &self.vertices, iterations);
+
+        self.provenance.retain(&self.triangulation.borrow());
+
+        return self;
+    }
+
+    /**
+     * Boundary, each hole and each chain of segments, as polylines split at
+     * every triangulation vertex lying on them, thus following the Steiner
+     * points inserted by refinement.
+     */
+    pub fn constraint_chains(&self) -> Vec<ConstraintChain> {
+        let vertices: Vec<Rc<Vertex>> =
+            self.triangulation.borrow().vertices().iter().cloned().collect();
+        let chain = |kind: ChainKind, polyline: &Polyline| ConstraintChain {
+            kind,
+            polyline: polyline.split_edges(&vertices),
+        };
+
+        return vec![&self.boundary]
+            .into_iter()
+            .chain(self.disjoint_boundaries.iter())
+            .map(|boundary| chain(ChainKind::Boundary, boundary))
+            .chain(self.holes.iter().map(|hole| chain(ChainKind::Hole, hole)))
+            .chain(
+                chain_segments(&self.segments)
+                    .iter()
+                    .map(|segments| chain(ChainKind::Segment, segments)),
+            )
+            .collect();
+    }
+
+    /**
+     * Boundary, holes and segments edges.
+     */
+    fn constraint_edges(&self) -> HashSet<Rc<Edge>> {
+        return self
+            .holes
+            .iter()
+            .map(|hole| hole.into_edges())
+            .flatten()
+            .chain(self.boundary.into_edges())
+            .chain(self.disjoint_boundaries.iter().map(|b| b.into_edges()).flatten())
+            .chain(self.segments.iter().cloned())
+            .collect();
+    }
+
+    /**
+     * Constraints marked on the triangulation edges, split along with them.
+     * Triangulations without marks, such as resumed or stitched ones, are
+     * marked with the boundary, holes and segments edges first, as chains
+     * of the triangulation edges along them.
+     */
+    fn marked_constraints(&self) -> HashSet<Rc<Edge>> {
+        let mut triangulation = self.triangulation.borrow_mut();
+        if triangulation.constraints().is_empty() {
+            let mut neighbors: HashMap<Rc<Vertex>, Vec<Rc<Vertex>>> = HashMap::new();
+            for edge in triangulation.adjacency.keys() {
+                if !edge.v1.is_ghost && !edge.v2.is_ghost {
+                    neighbors
+                        .entry(Rc::clone(&edge.v1))
+                        .or_insert_with(Vec::new)
+                        .push(Rc::clone(&edge.v2));
+                }
+            }
+            for constraint in self.constraint_edges().iter() {
+                if let Some(chain) = chain_along(&neighbors, constraint) {
+                    for edge in Edge::from_vertex_pairs(vertex_pairs(&chain, true)) {
+                        triangulation.constrain(&edge);
+                    }
+                }
+            }
+        }
+        return triangulation.constraints().clone();
+    }
+
+    /**
+     * Inserts seed points of the pattern as vertex constraints, filling the
+     * domain with a background of the spacing. Seed points are inside the
+     * boundary, outside the holes and no closer than half the spacing to
+     * any constraint, thus holes and segments are to be inserted first.
+     */
+    pub fn insert_seed_points(
+        &mut self,
+        pattern: SeedPattern,
+        spacing: f64,
+    ) -> Result<&Self, TriangulationError> {
+        let seed_points: HashSet<Rc<Vertex>> = generators::seed_points(
+            &self.boundary,
+            &self.holes,
+            &self.segments,
+            pattern,
+            spacing,
+            0,
+        )
+        .into_iter()
+        .collect();
+        return self.insert_vertices(&seed_points);
+    }
+
+    /**
+     * Inserts a stream of vertices straight into the triangulation, which is
+     * triangulated first if empty. Vertices are read in chunks, validated
+     * against the boundary and holes, and inserted in biased randomized
+     * order, so that no more than a chunk is held besides the triangulation.
+     * Vertices on existing vertices or on constrained edges are skipped.
+     * Streamed vertices are not kept as constraint vertices, thus are lost
+     * by triangulating again. Returns the count of inserted vertices, or
+     * raises the outside vertices of the first invalid chunk, keeping the
+     * former chunks inserted.
+     */
+    pub fn insert_vertices_iter(
+        &mut self,
+        vertices: impl Iterator<Item = (f64, f64)>,
+    ) -> Result<usize, TriangulationError> {
+        return self.insert_vertices_chunked(vertices, VERTEX_CHUNK_SIZE);
+    }
+
+    fn insert_vertices_chunked(
+        &mut self,
+        mut vertices: impl Iterator<Item = (f64, f64)>,
+        chunk_size: usize,
+    ) -> Result<usize, TriangulationError> {
+        if self.triangulation.borrow().triangles.is_empty() {
+            self.try_triangulate()?;
+        }
+
+        self.marked_constraints();
+
+        let mut inserted: usize = 0;
+        for seed in 0.. {
+            let chunk: Vec<Rc<Vertex>> = vertices
+                .by_ref()
+                .take(chunk_size)
+                .map(|(x, y)| Rc::new(Vertex::new(x, y)))
+                .collect();
+            if chunk.is_empty() {
+                break;
+            }
+
+            let outside_vertices: HashSet<Rc<Vertex>> = chunk
+                .iter()
+                .filter(|v| {
+                    self.boundary.contains(v) != Some(Continence::Inside)
+                        || self
+                            .holes
+                            .iter()
+                            .any(|hole| hole.contains(v) != Some(Continence::Outside))
+                })
+                .cloned()
+                .collect();
+            if !outside_vertices.is_empty() {
+                return Err(TriangulationError::outside_vertices(outside_vertices));
+            }
+
+            let mut triangulation = self.triangulation.borrow_mut();
+            for vertex in insertion_order::brio(chunk, seed).iter() {
+                if triangulation.insert_vertex(vertex) {
+                    inserted += 1;
+                }
+            }
+        }
+
+        self.provenance.retain(&self.triangulation.borrow());
+        return Ok(inserted);
+    }
+
+    /**
+     * Splits boundary, holes and segments edges longer than max_length in
+     * equally sized pieces, so that constrained insertion never retriangulates
+     * cavities spanning the whole domain.
+     */
+    pub fn presplit_constraints(&mut self, max_length: f64) -> &Self {
+        self.boundary = Rc::new(self.boundary.subdivide(max_length));
+        self.holes = self
+            .holes
+            .iter()
+            .map(|hole| Rc::new(hole.subdivide(max_length)))
+            .collect();
+        self.segments = self
+            .segments
+            .iter()
+            .filter_map(|segment| segment.as_polyline())
+            .map(|segment| segment.subdivide(max_length).into_edges())
+            .flatten()
+            .collect();
+
+        return self;
+    }
+
+    /**
+     * Triangulates. Panics if any constraint could not be recovered.
+     */
+    #[deprecated(
+        since = "0.1.1",
+        note = "panics on unrecoverable constraints, use try_triangulate instead"
+    )]
+    pub fn triangulate(&mut self) -> &Self {
+        if let Err(error) = self.try_triangulate() {
+            panic!("{}", error);
+        }
+        return self;
+    }
+
+    /**
+     * Triangulates, returning an error if any constraint could not be
+     * recovered. The triangulation is kept unchanged on error.
+     */
+    pub fn try_triangulate(&mut self) -> Result<&Self, TriangulationError> {
+        if !self.disjoint_boundaries.is_empty() {
+            return Err(disjoint_components_error());
+        }
+        if let Some(max_length) = self.max_constraint_length {
+            self.presplit_constraints(max_length);
+        }
+
+        /* Initialize triangulation */
+        let v1 = self.boundary.vertices.get(0).unwrap();
+        let v2 = self.boundary.vertices.get(1).unwrap();
+        let mut triangulation = Triangulation::from_initial_segment((&v1, &v2));
+        triangulation.set_compact_adjacency(self.compact_adjacency);
+        self.provenance = ProvenanceMap::new();
+        self.timings = Timings::new();
+
+        /* 1 Boundary inclusion */
+        let start = Instant::now();
+        triangulation_procedures::boundary::include(
+            &mut triangulation,
+            &self.boundary,
+            &HashSet::new(),
+        );
+        self.timings.record_since(Phase::BoundaryInclusion, start);
+        if self.record_provenance {
+            self.provenance.record(
+                &HashSet::new(),
+                &triangulation,
+                &self
+                    .boundary
+                    .into_edges()
+                    .into_iter()
+                    .map(Provenance::Boundary)
+                    .collect(),
+            );
+        }
+
+        /* boundary segments as segment constraints */
+        let mut segment_constraints: HashSet<Rc<Edge>> =
+            self.boundary.into_edges().iter().cloned().collect();
+        dump(
+            &mut self.debug_observer,
+            Phase::BoundaryInclusion,
+            &triangulation,
+            &segment_constraints,
+        );
+
+        /* 2 Holes inclusion */
+        for (index, hole) in self.holes.iter().enumerate() {
+            let total = self.holes.len();
+            if !report(
+                &mut self.progress_observer,
+                &self.cancellation,
+                Phase::HoleInclusion,
+                index,
+                total,
+            ) {
+                return Err(TriangulationError::Cancelled);
+            }
+            let former_triangles = self.provenance_snapshot(&triangulation);
+            let start = Instant::now();
+            triangulation_procedures::hole::include(&mut triangulation, hole, &segment_constraints)?;
+            self.timings.record_since(Phase::HoleInclusion, start);
+            if self.record_provenance {
+                self.provenance.record(
+                    &former_triangles,
+                    &triangulation,
+                    &hole.into_edges().into_iter().map(Provenance::Hole).collect(),
+                );
+            }
+
+            segment_constraints = segment_constraints
+                .iter()
+                .chain(hole.into_edges().iter())
+                .cloned()
+                .collect();
+            dump(
+                &mut self.debug_observer,
+                Phase::HoleInclusion,
+                &triangulation,
+                &segment_constraints,
+            );
+        }
+
+        /* 3 Include Segment Constraints */
+        for (index, segment) in self.segments.iter().enumerate() {
+            let total = self.segments.len();
+            if !report(
+                &mut self.progress_observer,
+                &self.cancellation,
+                Phase::SegmentRecovery,
+                index,
+                total,
+            ) {
+                return Err(TriangulationError::Cancelled);
+            }
+            let former_triangles = self.provenance_snapshot(&triangulation);
+            let start = Instant::now();
+
+            /* segment ends come first, so that chains of segments close on them */
+            triangulation_procedures::vertices::include(
+                &mut triangulation,
+                vec![Rc::clone(&segment.v1), Rc::clone(&segment.v2)],
+                &segment_constraints,
+                &Some(Rc::clone(&self.boundary)),
+                &self.holes,
+            );
+
+            /* segments passing through existing vertices are split at them */
+            let passed_vertices: Vec<Rc<Vertex>> = triangulation
+                .vertices()
+                .iter()
+                .filter(|v| **v != segment.v1 && **v != segment.v2 && segment.contains(v))
+                .cloned()
+                .collect();
+            for subsegment in split_segment(segment, &passed_vertices).iter() {
+                let mut steps: Vec<RecoveryStep> = Vec::new();
+                triangulation_procedures::segment::include_recording(
+                    &mut triangulation,
+                    subsegment,
+                    &segment_constraints,
+                    &mut steps,
+                )?;
+                let is_blocked = match steps.last() {
+                    Some(step) => !step.blocking_edges.is_empty(),
+                    None => false,
+                };
+                if is_blocked {
+                    return Err(TriangulationError::UnrecoverableConstraint {
+                        segment: Rc::clone(subsegment),
+                        steps,
+                    });
+                }
+                segment_constraints.insert(Rc::clone(subsegment));
+            }
+            self.timings.record_since(Phase::SegmentRecovery, start);
+            if self.record_provenance {
+                self.provenance.record(
+                    &former_triangles,
+                    &triangulation,
+                    &vec![Provenance::Segment(Rc::clone(segment))],
+                );
+            }
+            dump(
+                &mut self.debug_observer,
+                Phase::SegmentRecovery,
+                &triangulation,
+                &segment_constraints,
+            );
+        }
+
+        /* 4 Include remaining Vertices */
+        let progress_observer = &mut self.progress_observer;
+        let cancellation = &self.cancellation;
+        let start = Instant::now();
+        triangulation_procedures::vertices::include_observed(
+            &mut triangulation,
+            self.vertices.iter().cloned().collect(),
+            &segment_constraints,
+            &Some(Rc::clone(&self.boundary)),
+            &self.holes,
+            self.spatial_sorting,
+            &mut |processed, total| {
+                report(
+                    progress_observer,
+                    cancellation,
+                    Phase::VertexInsertion,
+                    processed,
+                    total,
+                )
+            },
+        );
+        self.timings.record_since(Phase::VertexInsertion, start);
+        if is_cancelled(&self.cancellation) {
+            return Err(TriangulationError::Cancelled);
+        }
+        dump(
+            &mut self.debug_observer,
+            Phase::VertexInsertion,
+            &triangulation,
+            &segment_constraints,
+        );
+        self.provenance.retain(&triangulation);
+
+        for segment in segment_constraints.iter() {
+            triangulation.constrain(segment);
+        }
+
+        /* 5 Carve holes marked by seeds, skipping those already carved */
+        let hole_seeds: Vec<Rc<Vertex>> = self
+            .hole_seeds
+            .iter()
+            .filter(|seed| {
+                self.holes
+                    .iter()
+                    .all(|hole| hole.contains(seed) == Some(Continence::Outside))
+            })
+            .cloned()
+            .collect();
+        let mut carved_holes: Vec<Rc<Polyline>> = Vec::new();
+        for seed in hole_seeds.iter() {
+            let hole = triangulation_procedures::hole::carve(&mut triangulation, seed)?;
+            carved_holes.push(Rc::new(hole));
+        }
+        self.include_carved_holes(carved_holes);
+        if !hole_seeds.is_empty() {
+            self.provenance.retain(&triangulation);
+        }
+        self.triangulation = RefCell::new(triangulation);
+
+        if self.mode == ConformingMode::Conforming {
+            self.try_conform()?;
+        }
+
+        return Ok(self);
+    }
+
+    /**
+     * Triangulates and refines the domain in square tiles with side
+     * tile_size, on the given count of threads, as Tiling does, replacing
+     * the triangulation by the stitched one. Boundary, holes and segments
+     * are kept, and marked again along the stitched edges. Tiles are only
+     * refined by size, and cut lines are no constraints, thus the fans
+     * stitching them are flipped back to constrained Delaunay and the
+     * stitched mesh is refined with the whole params. Raises the
+     * degenerate input error if tile_size is not positive or if there are
+     * hole seeds, which are carved from a whole triangulation only, the
+     * error of cutting the tiles, and the error of the first tile that
+     * fails.
+     */
+    pub fn try_triangulate_tiled(
+        &mut self,
+        tile_size: f64,
+        params: RefineParams,
+        jobs: usize,
+    ) -> Result<&Self, TriangulationError> {
+        if !self.disjoint_boundaries.is_empty() {
+            return Err(disjoint_components_error());
+        }
+        if !self.hole_seeds.is_empty() {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "hole seeds are carved from a whole triangulation, not from tiles",
+            )));
+        }
+        let tiling = Tiling::new(self, tile_size)?;
+        let mut triangulation = tiling.triangulate_parallel(&params, jobs)?;
+        triangulation.set_compact_adjacency(self.compact_adjacency);
+
+        self.triangulation = RefCell::new(triangulation);
+        self.timings = Timings::new();
+        self.marked_constraints();
+
+        let region: Vec<Rc<Triangle>> =
+            self.triangulation.borrow().triangles.iter().cloned().collect();
+        self.triangulation.borrow_mut().restore_delaunay(&region);
+
+        self.provenance = ProvenanceMap::new();
+        let candidates = self.provenance_candidates();
+        if self.record_provenance {
+            self.provenance.record(&HashSet::new(), &self.triangulation.borrow(), &candidates);
+        }
+
+        return self.try_refine(params);
+    }
+
+    /**
+     * Checks the triangulation invariants, as Triangulation::validate, and
+     * that the boundary, holes and segments are triangulation edges, or
+     * chains of them as split by refinement. The boundary, holes and
+     * segments are expected among the constraint marks, or as chains of
+     * marks, which in turn are expected among the triangulation edges.
+     * Neither the triangulator nor the triangulation are changed.
+     */
+    pub fn validate(&self) -> Result<(), Vec<InvariantViolation>> {
+        let triangulation = self.triangulation.borrow();
+        let mut violations = match triangulation.validate() {
+            Ok(()) => Vec::new(),
+            Err(violations) => violations,
+        };
+
+        /* marks in both orientations, as constraints are marked in either */
+        let marks: HashSet<Rc<Edge>> = triangulation
+            .constraints()
+            .iter()
+            .map(|edge| vec![Rc::clone(edge), Rc::new(edge.opposite())])
+            .flatten()
+            .collect();
+        for constraint in self.constraint_edges().iter() {
+            if !is_marked_chain(&marks, constraint) {
+                violations.push(InvariantViolation::MissingConstraint(Rc::clone(constraint)));
+            }
+        }
+
+        if violations.is_empty() {
+            return Ok(());
+        }
+        return Err(violations);
+    }
+
+    /**
+     * Time spent on each phase since the last triangulation began, including
+     * refinement and conforming phases.
+     */
+    pub fn timings(&self) -> &Timings {
+        return &self.timings;
+    }
+
+    /**
+     * Attributes of every vertex of the triangulation, as resolved by the
+     * attribute table: input vertices keep their own, and Steiner points are
+     * interpolated from their neighbors.
+     */
+    pub fn vertex_attributes(&self) -> HashMap<Rc<Vertex>, VertexAttributes> {
+        return self.attributes.resolve(&self.triangulation.borrow());
+    }
+
+    /**
+     * Writes the triangulation, the last refine params and the triangles
+     * still pending refinement as a checkpoint.
+     */
+    pub fn save_checkpoint(&self, writer: &mut dyn Write) -> std::io::Result<()> {
+        return checkpoint::write(
+            writer,
+            &self.triangulation.borrow(),
+            self.refine_params.as_ref(),
+        );
+    }
+
+    /**
+     * Replaces the triangulation by the checkpoint one, returning the refine
+     * params it was saved with. The triangulator is expected to hold the same
+     * constraints as when the checkpoint was saved, so that refining again
+     * resumes the pending triangles.
+     */
+    pub fn resume(
+        &mut self,
+        reader: &mut dyn Read,
+    ) -> Result<Option<RefineParams>, TriangulationError> {
+        let mut checkpoint = checkpoint::read(reader)?;
+        checkpoint.triangulation.set_compact_adjacency(self.compact_adjacency);
+
+        self.provenance.retain(&checkpoint.triangulation);
+        self.triangulation = RefCell::new(checkpoint.triangulation);
+        self.refine_params = checkpoint.params.clone();
+
+        /* checkpoints carry no constraint marks */
+        self.marked_constraints();
+
+        return Ok(checkpoint.params);
+    }
+
+    /**
+     * Returns the constraint vertex, or the extra vertex, within the welding
+     * tolerance of the vertex. Returns the vertex itself if there is none.
+     */
+    fn weld_vertex(&self, vertex: &Rc<Vertex>, extra: &HashSet<Rc<Vertex>>) -> Rc<Vertex> {
+        let is_close = |other: &&Rc<Vertex>| {
+            !Rc::ptr_eq(other, vertex)
+                && (*other == vertex || distance(other, vertex) <= self.welding_tolerance)
+        };
+
+        let existing = self
+            .boundary
+            .vertices
+            .iter()
+            .chain(self.holes.iter().map(|h| h.vertices.iter()).flatten())
+            .chain(self.segments.iter().map(|s| vec![&s.v1, &s.v2]).flatten())
+            .chain(self.vertices.iter())
+            .chain(extra.iter())
+            .find(is_close);
+
+        match existing {
+            Some(other) => return Rc::clone(other),
+            None => return Rc::clone(vertex),
+        }
+    }
+
+    /**
+     * Returns the nearest point of the boundary or hole edges to the vertex,
+     * if it is within the tolerance. Edge ends within the welding tolerance
+     * of that point are returned instead. Returns None if no edge is close.
+     */
+    fn snap_vertex(&self, vertex: &Rc<Vertex>, tolerance: f64) -> Option<Rc<Vertex>> {
+        let (edge, projection) = self
+            .boundary
+            .into_edges()
+            .into_iter()
+            .chain(self.holes.iter().map(|hole| hole.into_edges()).flatten())
+            .map(|edge| {
+                let projection = project_onto_edge(&edge, vertex);
+                return (edge, projection);
+            })
+            .filter(|(_, projection)| distance(projection, vertex) <= tolerance)
+            .min_by(|(_, p1), (_, p2)| {
+                distance(p1, vertex)
+                    .partial_cmp(&distance(p2, vertex))
+                    .unwrap()
+            })?;
+
+        for end in vec![&edge.v1, &edge.v2] {
+            if **end == projection || distance(end, &projection) <= self.welding_tolerance {
+                return Some(Rc::clone(end));
+            }
+        }
+        return Some(Rc::new(projection));
+    }
+
+    /**
+     * Every boundary edge, hole edge and segment, as provenance candidates.
+     * Empty unless provenance is recorded.
+     */
+    fn provenance_candidates(&self) -> Vec<Provenance> {
+        if !self.record_provenance {
+            return Vec::new();
+        }
+        return self
+            .boundary
+            .into_edges()
+            .into_iter()
+            .map(Provenance::Boundary)
+            .chain(
+                self.holes
+                    .iter()
+                    .map(|hole| hole.into_edges())
+                    .flatten()
+                    .map(Provenance::Hole),
+            )
+            .chain(self.segments.iter().cloned().map(Provenance::Segment))
+            .collect();
+    }
+
+    /**
+     * Triangles before a step, whose new triangles are then recorded as
+     * provenance. Empty unless provenance is recorded, so that triangles
+     * are not copied on every step.
+     */
+    fn provenance_snapshot(&self, triangulation: &Triangulation) -> HashSet<Rc<Triangle>> {
+        if !self.record_provenance {
+            return HashSet::new();
+        }
+        return triangulation.triangles.clone();
+    }
+
+    fn record_steiner_provenance(
+        &mut self,
+        former_triangles: &HashSet<Rc<Triangle>>,
+        candidates: &Vec<Provenance>,
+    ) {
+        if self.record_provenance {
+            self.provenance.record_steiner(
+                former_triangles,
+                &self.triangulation.borrow(),
+                candidates,
+            );
+        }
+    }
+} /* end - module */
+
+/**
+ * Welds the polyline and orients it counterclockwise, as boundary and holes
+ * are expected, keeping it if neither changed it. Returns None if it
+ * degenerates.
+ */
+fn normalize_polyline(polyline: &Rc<Polyline>, tolerance: f64) -> Option<Rc<Polyline>> {
+    let welded = polyline.weld(tolerance)?;
+    let is_clockwise = welded.orientation() == Some(Orientation::Clockwise);
+    if welded.vertices.len() == polyline.vertices.len() && !is_clockwise {
+        return Some(Rc::clone(polyline));
+    }
+    return Some(Rc::new(welded.ensure_ccw()));
+}
+
+/**
+ * Nearest point of the edge to the vertex.
+ */
+fn project_onto_edge(edge: &Edge, vertex: &Vertex) -> Vertex {
+    let (dx, dy) = (edge.v2.x - edge.v1.x, edge.v2.y - edge.v1.y);
+    let squared_length = dx * dx + dy * dy;
+    if squared_length == 0.0 {
+        return Vertex::new(edge.v1.x, edge.v1.y);
+    }
+
+    let ratio = ((vertex.x - edge.v1.x) * dx + (vertex.y - edge.v1.y) * dy) / squared_length;
+    let ratio = ratio.max(0.0).min(1.0);
+    return Vertex::new(edge.v1.x + ratio * dx, edge.v1.y + ratio * dy);
+}
+
+/**
+ * Evaluates if the marks chain the constraint from its first to its last
+ * vertex, through marks advancing along it. Steiner points splitting the
+ * constraint round off to either side of it, so that they are taken on it
+ * within a distance relative to its length.
+ */
+fn is_marked_chain(marks: &HashSet<Rc<Edge>>, constraint: &Edge) -> bool {
+    let mut neighbors: HashMap<Rc<Vertex>, Vec<Rc<Vertex>>> = HashMap::new();
+    for mark in marks.iter() {
+        neighbors
+            .entry(Rc::clone(&mark.v1))
+            .or_insert_with(Vec::new)
+            .push(Rc::clone(&mark.v2));
+    }
+    return chain_along(&neighbors, constraint).is_some();
+}
+
+/**
+ * Vertices chaining the constraint from its first to its last vertex,
+ * through neighbors advancing along it, as is_marked_chain takes them.
+ * Returns None if the neighbors do not reach its last vertex.
+ */
+fn chain_along(
+    neighbors: &HashMap<Rc<Vertex>, Vec<Rc<Vertex>>>,
+    constraint: &Edge,
+) -> Option<Vec<Rc<Vertex>>> {
+    let (dx, dy) = (constraint.v2.x - constraint.v1.x, constraint.v2.y - constraint.v1.y);
+    let along = |v: &Vertex| (v.x - constraint.v1.x) * dx + (v.y - constraint.v1.y) * dy;
+    let tolerance = triangulation_procedures::vertices::ON_EDGE_TOLERANCE * constraint.length();
+
+    let mut chain: Vec<Rc<Vertex>> = vec![Rc::clone(&constraint.v1)];
+    let mut current = Rc::clone(&constraint.v1);
+    for _ in 0..neighbors.len() {
+        if current == constraint.v2 {
+            return Some(chain);
+        }
+        let next = neighbors.get(&current).and_then(|candidates| {
+            candidates
+                .iter()
+                .filter(|v| along(v) > along(&current))
+                .filter(|v| edge_distance(constraint, v) <= tolerance)
+                .min_by(|a, b| along(a).partial_cmp(&along(b)).unwrap())
+                .cloned()
+        });
+        match next {
+            Some(next) => {
+                chain.push(Rc::clone(&next));
+                current = next;
+            }
+            None => return None,
+        }
+    }
+    if current == constraint.v2 {
+        return Some(chain);
+    }
+    return None;
+}
+
+/**
+ * Triangles of the counterclockwise simple polygon, by ear clipping. Ears of
+ * zero area, around collinear vertices such as Steiner points splitting an
+ * edge, are never clipped, nor ears touching any other polygon vertex, so
+ * that collinear vertices are left to the ears beside them. Returns None if
+ * no ear is left before the polygon is reduced to a proper triangle.
+ */
+fn clip_ears(mut polygon: Vec<Rc<Vertex>>) -> Option<Vec<Rc<Triangle>>> {
+    let mut triangles: Vec<Rc<Triangle>> = Vec::new();
+    while polygon.len() >= 3 {
+        let count = polygon.len();
+        let index = (0..count).find(|&index| {
+            let v1 = &polygon[index];
+            let v2 = &polygon[(index + 1) % count];
+            let v3 = &polygon[(index + 2) % count];
+            if orientation(v1, v2, v3) != Orientation::Counterclockwise {
+                return false;
+            }
+            let ear = Triangle::new(v1, v2, v3);
+            return polygon
+                .iter()
+                .filter(|v| *v != v1 && *v != v2 && *v != v3)
+                .all(|v| ear.contains(v) == Continence::Outside);
+        })?;
+
+        triangles.push(Rc::new(Triangle::new(
+            &polygon[index],
+            &polygon[(index + 1) % count],
+            &polygon[(index + 2) % count],
+        )));
+        polygon.remove((index + 1) % count);
+        if polygon.len() == 2 {
+            return Some(triangles);
+        }
+    }
+    return None;
+}
+
+fn disjoint_components_error() -> TriangulationError {
+    return TriangulationError::DegenerateInput(String::from(
+        "disconnected components are triangulated and refined before their inclusion",
+    ));
+}
+
+#[cfg(test)]
+mod insert_holes {
+    use super::*;
+
+    #[test]
+    fn sample_1() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
+        boundary.push(Rc::clone(&v1));
+        boundary.push(Rc::clone(&v2));
+        boundary.push(Rc::clone(&v3));
+        boundary.push(Rc::clone(&v4));
+        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+
+        /* Inner Vertices */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(3.0, 2.0));
+        let v13 = Rc::new(Vertex::new(3.0, 3.0));
+        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+
+        let mut hole_vertices: Vec<Rc<Vertex>> = Vec::new();
+        hole_vertices.push(Rc::clone(&v11));
+        hole_vertices.push(Rc::clone(&v12));
+        hole_vertices.push(Rc::clone(&v13));
+        hole_vertices.push(Rc::clone(&v14));
+        let hole = Rc::new(Polyline::new_closed(hole_vertices).unwrap());
+
+        let mut triangulator = Triangulator::new(&boundary);
+        let result = triangulator.insert_hole(&hole);
+        assert!(result.is_ok());
+
+        assert!(triangulator.holes.contains(&hole));
+    }
+
+    #[test]
+    fn error_insert_if_intersection_with_boundary() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
+        boundary.push(Rc::clone(&v1));
+        boundary.push(Rc::clone(&v2));
+        boundary.push(Rc::clone(&v3));
+        boundary.push(Rc::clone(&v4));
+        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+
+        /* Inner Vertices */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(3.0, 2.0));
+        let v13 = Rc::new(Vertex::new(6.0, 6.0));
+        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+
+        let mut hole_vertices: Vec<Rc<Vertex>> = Vec::new();
+        hole_vertices.push(Rc::clone(&v11));
+        hole_vertices.push(Rc::clone(&v12));
+        hole_vertices.push(Rc::clone(&v13));
+        hole_vertices.push(Rc::clone(&v14));
+        let hole = Rc::new(Polyline::new_closed(hole_vertices).unwrap());
+
+        let mut triangulator = Triangulator::new(&boundary);
+        let result = triangulator.insert_hole(&hole);
+
+        assert!(result.is_err());
+        if let Err(TriangulationError::OutsideBoundary { vertices, .. }) = result {
+            assert_eq!(vertices.len(), 4);
+            assert!(vertices.contains(&v1));
+            assert!(vertices.contains(&v2));
+            assert!(vertices.contains(&v3));
+            assert!(vertices.contains(&v4));
+        }
+        assert!(triangulator.holes.is_empty());
+    }
+
+    #[test]
+    fn error_insert_if_intersection_at_boundary() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
+        boundary.push(Rc::clone(&v1));
+        boundary.push(Rc::clone(&v2));
+        boundary.push(Rc::clone(&v3));
+        boundary.push(Rc::clone(&v4));
+        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+
+        /* Hole Vertices */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(3.0, 2.0));
+        let v13 = Rc::new(Vertex::new(3.0, 3.0));
+        let v14 = Rc::new(Vertex::new(2.0, 4.0));
+
+        let mut hole_vertices: Vec<Rc<Vertex>> = Vec::new();
+        hole_vertices.push(Rc::clone(&v11));
+        hole_vertices.push(Rc::clone(&v12));
+        hole_vertices.push(Rc::clone(&v13));
+        hole_vertices.push(Rc::clone(&v14));
+        let hole = Rc::new(Polyline::new_closed(hole_vertices).unwrap());
+
+        let mut triangulator = Triangulator::new(&boundary);
+        let result = triangulator.insert_hole(&hole);
+
+        assert!(result.is_err());
+        if let Err(TriangulationError::OutsideBoundary { vertices, .. }) = result {
+            assert_eq!(vertices.len(), 4);
+            assert!(vertices.contains(&v1));
+            assert!(vertices.contains(&v2));
+            assert!(vertices.contains(&v3));
+            assert!(vertices.contains(&v4));
+        }
+        assert!(triangulator.holes.is_empty());
+    }
+
+    #[test]
+    fn two_holes() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
+        boundary.push(Rc::clone(&v1));
+        boundary.push(Rc::clone(&v2));
+        boundary.push(Rc::clone(&v3));
+        boundary.push(Rc::clone(&v4));
+        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+
+        /* Hole 1 */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(2.9, 2.0));
+        let v13 = Rc::new(Vertex::new(2.0, 2.9));
+
+        let mut hole1_vertices: Vec<Rc<Vertex>> = Vec::new();
+        hole1_vertices.push(Rc::clone(&v11));
+        hole1_vertices.push(Rc::clone(&v12));
+        hole1_vertices.push(Rc::clone(&v13));
+        let hole_1 = Rc::new(Polyline::new_closed(hole1_vertices).unwrap());
+
+        /* Hole 2 */
+        let v21 = Rc::new(Vertex::new(3.0, 3.0));
+        let v22 = Rc::new(Vertex::new(2.1, 3.0));
+        let v23 = Rc::new(Vertex::new(3.0, 2.1));
+
+        let mut hole2_vertices: Vec<Rc<Vertex>> = Vec::new();
+        hole2_vertices.push(Rc::clone(&v21));
+        hole2_vertices.push(Rc::clone(&v22));
+        hole2_vertices.push(Rc::clone(&v23));
+        let hole_2 = Rc::new(Polyline::new_closed(hole2_vertices).unwrap());
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_hole(&hole_1);
+        let result = triangulator.insert_hole(&hole_2);
+
+        assert!(result.is_ok());
+        assert!(triangulator.holes.contains(&hole_1));
+        assert!(triangulator.holes.contains(&hole_2));
+    }
+
+    #[test]
+    fn error_on_hole_intersection() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
+        boundary.push(Rc::clone(&v1));
+        boundary.push(Rc::clone(&v2));
+        boundary.push(Rc::clone(&v3));
+        boundary.push(Rc::clone(&v4));
+        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+
+        /* Hole 1 */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(2.9, 2.0));
+        let v13 = Rc::new(Vertex::new(2.0, 2.9));
+
+        let mut hole1_vertices: Vec<Rc<Vertex>> = Vec::new();
+        hole1_vertices.push(Rc::clone(&v11));
+        hole1_vertices.push(Rc::clone(&v12));
+        hole1_vertices.push(Rc::clone(&v13));
+        let hole_1 = Rc::new(Polyline::new_closed(hole1_vertices).unwrap());
+
+        /* Hole 2 */
+        let v21 = Rc::new(Vertex::new(3.0, 3.0));
+        let v22 = Rc::new(Vertex::new(2.1, 3.0));
+        let v23 = Rc::new(Vertex::new(2.9, 2.0));
+        let v24 = Rc::new(Vertex::new(3.0, 2.1));
+
+        let mut hole2_vertices: Vec<Rc<Vertex>> = Vec::new();
+        hole2_vertices.push(Rc::clone(&v21));
+        hole2_vertices.push(Rc::clone(&v22));
+        hole2_vertices.push(Rc::clone(&v23));
+        hole2_vertices.push(Rc::clone(&v24));
+        let hole_2 = Rc::new(Polyline::new_closed(hole2_vertices).unwrap());
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_hole(&hole_1);
+        let result = triangulator.insert_hole(&hole_2);
+
+        assert!(result.is_err());
+        if let Err(TriangulationError::OutsideBoundary { vertices, .. }) = result {
+            assert_eq!(vertices.len(), 3);
+            assert!(vertices.contains(&v11));
+            assert!(vertices.contains(&v12));
+            assert!(vertices.contains(&v13));
+        }
+        assert!(triangulator.holes.contains(&hole_1));
+    }
+
+    #[test]
+    fn error_on_segments_wrong_continence() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
+        boundary.push(Rc::clone(&v1));
+        boundary.push(Rc::clone(&v2));
+        boundary.push(Rc::clone(&v3));
+        boundary.push(Rc::clone(&v4));
+        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+
+        /* Segments */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(3.0, 2.0));
+        let v13 = Rc::new(Vertex::new(3.0, 3.0));
+        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+
+        let segments_constrains: HashSet<Rc<Edge>> = vec![
+            Rc::new(Edge::new(&v11, &v12)),
+            Rc::new(Edge::new(&v13, &v14)),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        /* Hole */
+        let v21 = Rc::new(Vertex::new(1.5, 1.5));
+        let v22 = Rc::new(Vertex::new(3.5, 1.5));
+        let v23 = Rc::new(Vertex::new(3.5, 3.5));
+        let v24 = Rc::new(Vertex::new(1.5, 3.5));
+
+        let mut hole_vertices: Vec<Rc<Vertex>> = Vec::new();
+        hole_vertices.push(Rc::clone(&v21));
+        hole_vertices.push(Rc::clone(&v22));
+        hole_vertices.push(Rc::clone(&v23));
+        hole_vertices.push(Rc::clone(&v24));
+        let hole = Rc::new(Polyline::new_closed(hole_vertices).unwrap());
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_segments(&segments_constrains);
+        let result = triangulator.insert_hole(&hole);
+
+        assert!(result.is_err());
+        if let Err(TriangulationError::OutsideBoundary { vertices, .. }) = result {
+            assert_eq!(vertices.len(), 4);
+            assert!(vertices.contains(&v11));
+            assert!(vertices.contains(&v12));
+            assert!(vertices.contains(&v13));
+            assert!(vertices.contains(&v14));
+        }
+        assert!(triangulator.holes.is_empty());
+    }
+
+    #[test]
+    fn normalizes_orientation() {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+            ])
+            .unwrap(),
+        );
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(1.0, 1.0)),
+                Rc::new(Vertex::new(1.0, 2.0)),
+                Rc::new(Vertex::new(2.0, 2.0)),
+                Rc::new(Vertex::new(2.0, 1.0)),
+            ])
+            .unwrap(),
+        );
+
+        let mut triangulator = Triangulator::new(&boundary);
+        assert_eq!(
+            triangulator.boundary.orientation(),
+            Some(Orientation::Counterclockwise)
+        );
+        assert!(triangulator.insert_hole(&hole).is_ok());
+        assert!(triangulator
+            .holes
+            .iter()
+            .all(|h| h.orientation() == Some(Orientation::Counterclockwise)));
+
+        triangulator.try_triangulate().unwrap();
+        let area: f64 = triangulator
+            .triangulation
+            .borrow()
+            .triangles
+            .iter()
+            .filter_map(|t| t.area())
+            .sum();
+        assert!((area - 15.0).abs() < 1.0E-9);
+
+        assert!(triangulator.remove_hole(&hole));
+        assert!(triangulator.holes.is_empty());
+    }
+} /* end - insert_hole tests */
+
+#[cfg(test)]
+mod insert_vertices {
+    use super::*;
+
+    #[test]
+    fn sample_1() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(5.0, 1.0));
+        let v3 = Rc::new(Vertex::new(5.0, 5.0));
+        let v4 = Rc::new(Vertex::new(1.0, 5.0));
+
+        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
+        boundary.push(Rc::clone(&v1));
+        boundary.push(Rc::clone(&v2));
+        boundary.push(Rc::clone(&v3));
+        boundary.push(Rc::clone(&v4));
+        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+
+        /* Inner Vertices */
+        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+        let v15 = Rc::new(Vertex::new(1.0000001, 1.0000001));
+        let v16 = Rc::new(Vertex::new(4.9999999, 4.9999999));
+
+        let mut inner_vertices: HashSet<Rc<Vertex>> = HashSet::new();
+        inner_vertices.insert(Rc::clone(&v14));
+        inner_vertices.insert(Rc::clone(&v15));
+        inner_vertices.insert(Rc::clone(&v16));
+
+        let mut triangulator = Triangulator::new(&boundary);
+        let result = triangulator.insert_vertices(&inner_vertices);
+        assert!(result.is_ok());
+
+        assert!(triangulator.vertices.contains(&v14));
+        assert!(triangulator.vertices.contains(&v15));
+        assert!(triangulator.vertices.contains(&v16));
+    }
+
+    #[test]
+    fn error_if_any_out_of_boundary() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(5.0, 1.0));
+        let v3 = Rc::new(Vertex::new(5.0, 5.0));
+        let v4 = Rc::new(Vertex::new(1.0, 5.0));
+
+        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
+        boundary.push(Rc::clone(&v1));
+        boundary.push(Rc::clone(&v2));
+        boundary.push(Rc::clone(&v3));
+        boundary.push(Rc::clone(&v4));
+        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+
+        /* Outer Vertices */
+        let v10 = Rc::new(Vertex::new(0.0, 0.0));
+        let v11 = Rc::new(Vertex::new(0.9999999, 0.9999999));
+        let v12 = Rc::new(Vertex::new(5.0000001, 5.0000001));
+        let v13 = Rc::new(Vertex::new(3.0, 1.0)); /* at the boundary */
+
+        /* Inner Vertices */
+        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+        let v15 = Rc::new(Vertex::new(1.0000001, 1.0000001));
+        let v16 = Rc::new(Vertex::new(4.9999999, 4.9999999));
+
+        let mut inner_vertices: HashSet<Rc<Vertex>> = HashSet::new();
+        inner_vertices.insert(Rc::clone(&v10));
+        inner_vertices.insert(Rc::clone(&v11));
+        inner_vertices.insert(Rc::clone(&v12));
+        inner_vertices.insert(Rc::clone(&v13));
+        inner_vertices.insert(Rc::clone(&v14));
+        inner_vertices.insert(Rc::clone(&v15));
+        inner_vertices.insert(Rc::clone(&v16));
+
+        let mut triangulator = Triangulator::new(&boundary);
+        let result = triangulator.insert_vertices(&inner_vertices);
+        assert!(result.is_err());
+
+        if let Err(TriangulationError::OutsideBoundary { vertices: panic_vertices, .. }) = result {
+            assert!(panic_vertices.contains(&v10));
+            assert!(panic_vertices.contains(&v11));
+            assert!(panic_vertices.contains(&v12));
+            assert!(panic_vertices.contains(&v13));
+        }
+    }
+
+    #[test]
+    fn error_if_any_inside_hole() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
+        boundary.push(Rc::clone(&v1));
+        boundary.push(Rc::clone(&v2));
+        boundary.push(Rc::clone(&v3));
+        boundary.push(Rc::clone(&v4));
+        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+
+        /* Hole Vertices */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(3.0, 2.0));
+        let v13 = Rc::new(Vertex::new(3.0, 3.0));
+        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+
+        let mut hole_vertices: Vec<Rc<Vertex>> = Vec::new();
+        hole_vertices.push(Rc::clone(&v11));
+        hole_vertices.push(Rc::clone(&v12));
+        hole_vertices.push(Rc::clone(&v13));
+        hole_vertices.push(Rc::clone(&v14));
+        let hole = Rc::new(Polyline::new_closed(hole_vertices).unwrap());
+
+        /* Ok Vertices */
+        let v21 = Rc::new(Vertex::new(1.4, 1.4));
+        let v22 = Rc::new(Vertex::new(3.5, 3.5));
+
+        /* Err Vertices */
+        let v23 = Rc::new(Vertex::new(2.2, 2.2));
+        let v24 = Rc::new(Vertex::new(2.0, 2.5));
+
+        let mut inner_vertices: HashSet<Rc<Vertex>> = HashSet::new();
+        inner_vertices.insert(Rc::clone(&v21));
+        inner_vertices.insert(Rc::clone(&v22));
+        inner_vertices.insert(Rc::clone(&v23));
+        inner_vertices.insert(Rc::clone(&v24));
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_hole(&hole);
+        let result = triangulator.insert_vertices(&inner_vertices);
+        assert!(result.is_err());
+
+        if let Err(TriangulationError::OutsideBoundary { vertices: panic_vertices, .. }) = result {
+            assert!(panic_vertices.contains(&v23));
+            assert!(panic_vertices.contains(&v24));
+        }
+    }
+
+    #[test]
+    fn snaps_onto_boundary_and_holes() {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
+            ])
+            .unwrap(),
+        );
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(1.0, 1.0)),
+                Rc::new(Vertex::new(2.0, 1.0)),
+                Rc::new(Vertex::new(2.0, 2.0)),
+                Rc::new(Vertex::new(1.0, 2.0)),
+            ])
+            .unwrap(),
+        );
+
+        let on_boundary = Rc::new(Vertex::new(3.0, 0.0));
+        let near_hole = Rc::new(Vertex::new(1.5, 2.0 + 1e-4));
+        let near_corner = Rc::new(Vertex::new(4.0 - 1e-14, 4.0));
+        let inner = Rc::new(Vertex::new(3.0, 3.0));
+        let vertices: HashSet<Rc<Vertex>> = vec![
+            Rc::clone(&on_boundary),
+            Rc::clone(&near_hole),
+            Rc::clone(&near_corner),
+            Rc::clone(&inner),
+        ]
+        .into_iter()
+        .collect();
+
+        /* rejected by default */
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_hole(&hole).unwrap();
+        assert!(triangulator.insert_vertices(&vertices).is_err());
+
+        triangulator.snap_tolerance = Some(1e-3);
+        triangulator.insert_vertices(&vertices).unwrap();
+        assert_eq!(triangulator.vertices.len(), 1);
+        assert!(triangulator.vertices.contains(&inner));
+        assert_eq!(triangulator.boundary.vertices.len(), 5);
+        assert!(triangulator.boundary.vertices.contains(&on_boundary));
+
+        let hole = triangulator.holes.iter().next().unwrap();
+        assert_eq!(hole.vertices.len(), 5);
+        assert!(hole.vertices.contains(&Rc::new(Vertex::new(1.5, 2.0))));
+
+        triangulator.try_triangulate().unwrap();
+        assert!(triangulator
+            .triangulation
+            .borrow()
+            .vertices()
+            .contains(&on_boundary));
+    }
+
+    #[test]
+    fn splits_segments() {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
+            ])
+            .unwrap(),
+        );
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(3.0, 3.0));
+        let segments: HashSet<Rc<Edge>> = vec![Rc::new(Edge::new(&v1, &v2))].into_iter().collect();
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_segments(&segments).unwrap();
+
+        /* vertices on the segment split it, ends and others are kept apart */
+        let on_segment_1 = Rc::new(Vertex::new(2.5, 2.5));
+        let on_segment_2 = Rc::new(Vertex::new(1.5, 1.5));
+        let apart = Rc::new(Vertex::new(3.0, 1.0));
+        let vertices: HashSet<Rc<Vertex>> = vec![
+            Rc::clone(&on_segment_1),
+            Rc::clone(&on_segment_2),
+            Rc::new(Vertex::new(1.0, 1.0)),
+            Rc::clone(&apart),
+        ]
+        .into_iter()
+        .collect();
+        triangulator.insert_vertices(&vertices).unwrap();
+
+        let expected_segments: HashSet<Rc<Edge>> = vec![
+            Rc::new(Edge::new(&v1, &on_segment_2)),
+            Rc::new(Edge::new(&on_segment_2, &on_segment_1)),
+            Rc::new(Edge::new(&on_segment_1, &v2)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(triangulator.segments, expected_segments);
+        assert_eq!(triangulator.vertices.len(), 1);
+        assert!(triangulator.vertices.contains(&apart));
+    }
+}
+
+#[cfg(test)]
+mod insert_segments {
+    use super::*;
+
+    #[test]
+    fn sample_1() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
+        boundary.push(Rc::clone(&v1));
+        boundary.push(Rc::clone(&v2));
+        boundary.push(Rc::clone(&v3));
+        boundary.push(Rc::clone(&v4));
+        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+
+        /* Segments Vertices */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(3.0, 2.0));
+        let v13 = Rc::new(Vertex::new(3.0, 3.0));
+        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+        let e1 = Rc::new(Edge::new(&v11, &v12));
+        let e2 = Rc::new(Edge::new(&v13, &v14));
+
+        let mut segments: HashSet<Rc<Edge>> = HashSet::new();
+        segments.insert(Rc::clone(&e1));
+        segments.insert(Rc::clone(&e2));
+
+        let mut triangulator = Triangulator::new(&boundary);
+        let result = triangulator.insert_segments(&segments);
+        assert!(result.is_ok());
+
+        assert!(triangulator.segments.contains(&e1));
+        assert!(triangulator.segments.contains(&e2));
+    }
+
+    #[test]
+    fn error_if_any_out_of_boundary() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
+        boundary.push(Rc::clone(&v1));
+        boundary.push(Rc::clone(&v2));
+        boundary.push(Rc::clone(&v3));
+        boundary.push(Rc::clone(&v4));
+        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+
+        /* Segments Vertices */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(3.0, 2.0));
+        let v13 = Rc::new(Vertex::new(3.0, 3.0));
+        let v14 = Rc::new(Vertex::new(2.0, 5.0));
+        let e1 = Rc::new(Edge::new(&v11, &v12));
+        let e2 = Rc::new(Edge::new(&v13, &v14));
+
+        let mut segments: HashSet<Rc<Edge>> = HashSet::new();
+        segments.insert(Rc::clone(&e1));
+        segments.insert(Rc::clone(&e2));
+
+        let mut triangulator = Triangulator::new(&boundary);
+        let result = triangulator.insert_segments(&segments);
+        assert!(result.is_err());
+
+        if let Err(TriangulationError::OutsideBoundary {
+            segments: panic_segments, ..
+        }) = result
+        {
+            assert_eq!(panic_segments.len(), 1);
+            assert!(panic_segments.contains(&e2));
+        }
+    }
+
+    #[test]
+    fn donot_remove_vertices_on_continence() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
+        boundary.push(Rc::clone(&v1));
+        boundary.push(Rc::clone(&v2));
+        boundary.push(Rc::clone(&v3));
+        boundary.push(Rc::clone(&v4));
+        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+
+        /* Segments */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(3.0, 2.0));
+        let v13 = Rc::new(Vertex::new(3.0, 3.0));
+        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+        let e1 = Rc::new(Edge::new(&v11, &v12));
+        let e2 = Rc::new(Edge::new(&v13, &v14));
+
+        let mut segments: HashSet<Rc<Edge>> = HashSet::new();
+        segments.insert(Rc::clone(&e1));
+        segments.insert(Rc::clone(&e2));
+
+        /* Vertices */
+        let v21 = Rc::new(Vertex::new(2.5, 3.0));
+        let v22 = Rc::new(Vertex::new(2.0, 2.2));
+
+        let mut vertices: HashSet<Rc<Vertex>> = HashSet::new();
+        vertices.insert(Rc::clone(&v21));
+        vertices.insert(Rc::clone(&v22));
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_vertices(&vertices);
+        assert!(!triangulator.vertices.is_empty());
+
+        let result = triangulator.insert_segments(&segments);
+        assert!(result.is_ok());
+
+        assert!(!triangulator.vertices.contains(&v21));
+        assert!(triangulator.vertices.contains(&v22));
+    }
+
+    #[test]
+    fn error_on_inside_hole() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v1),
+                Rc::clone(&v2),
+                Rc::clone(&v3),
+                Rc::clone(&v4),
+            ])
+            .unwrap(),
+        );
+
+        /* Segments */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(3.0, 2.0));
+        let v13 = Rc::new(Vertex::new(3.0, 3.0));
+        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v11),
+                Rc::clone(&v12),
+                Rc::clone(&v13),
+                Rc::clone(&v14),
+            ])
+            .unwrap(),
+        );
+
+        /* Vertices */
+        let v21 = Rc::new(Vertex::new(2.3, 2.3));
+        let v22 = Rc::new(Vertex::new(2.8, 2.8));
+        let v23 = Rc::new(Vertex::new(1.5, 1.5));
+        let v24 = Rc::new(Vertex::new(1.5, 3.5));
+        let e1 = Rc::new(Edge::new(&v21, &v22));
+        let e2 = Rc::new(Edge::new(&v23, &v24));
+        let segments_set: HashSet<Rc<Edge>> = vec![Rc::clone(&e1), Rc::clone(&e2)]
+            .iter()
+            .cloned()
+            .collect();
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_hole(&hole);
+        let result = triangulator.insert_segments(&segments_set);
+
+        assert!(result.is_err());
+        if let Err(TriangulationError::OutsideBoundary {
+            segments: conflicting_edges, ..
+        }) = result
+        {
+            assert!(conflicting_edges.contains(&e1));
+            assert!(!conflicting_edges.contains(&e2));
+        }
+
+        assert!(!triangulator.segments.contains(&e1));
+        assert!(!triangulator.segments.contains(&e2));
+    }
+
+    #[test]
+    fn error_on_hole_intersection() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v1),
+                Rc::clone(&v2),
+                Rc::clone(&v3),
+                Rc::clone(&v4),
+            ])
+            .unwrap(),
+        );
+
+        /* Segments */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(3.0, 2.0));
+        let v13 = Rc::new(Vertex::new(3.0, 3.0));
+        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v11),
+                Rc::clone(&v12),
+                Rc::clone(&v13),
+                Rc::clone(&v14),
+            ])
+            .unwrap(),
+        );
+
+        /* Vertices */
+        let v21 = Rc::new(Vertex::new(2.3, 2.3));
+        let v22 = Rc::new(Vertex::new(3.5, 2.3));
+        let v23 = Rc::new(Vertex::new(1.5, 1.5));
+        let v24 = Rc::new(Vertex::new(1.5, 3.5));
+        let e1 = Rc::new(Edge::new(&v21, &v22));
+        let e2 = Rc::new(Edge::new(&v23, &v24));
+        let segments_set: HashSet<Rc<Edge>> = vec![Rc::clone(&e1), Rc::clone(&e2)]
+            .iter()
+            .cloned()
+            .collect();
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_hole(&hole);
+        assert!(triangulator.holes.contains(&hole));
+
+        let result = triangulator.insert_segments(&segments_set);
+        assert!(result.is_err());
+
+        if let Err(TriangulationError::OutsideBoundary {
+            segments: conflicting_edges, ..
+        }) = result
+        {
+            assert!(conflicting_edges.contains(&e1));
+            assert!(!conflicting_edges.contains(&e2));
+        }
+
+        assert!(!triangulator.segments.contains(&e1));
+        assert!(!triangulator.segments.contains(&e2));
+    }
+
+    #[test]
+    fn error_on_hole_intersection_with_vertices_end_outside() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v1),
+                Rc::clone(&v2),
+                Rc::clone(&v3),
+                Rc::clone(&v4),
+            ])
+            .unwrap(),
+        );
+
+        /* Hole */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(3.0, 2.0));
+        let v13 = Rc::new(Vertex::new(3.0, 3.0));
+        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v11),
+                Rc::clone(&v12),
+                Rc::clone(&v13),
+                Rc::clone(&v14),
+            ])
+            .unwrap(),
+        );
+
+        /* Segments */
+        let v21 = Rc::new(Vertex::new(1.5, 2.5));
+        let v22 = Rc::new(Vertex::new(3.5, 2.5));
+        let v23 = Rc::new(Vertex::new(3.5, 3.5));
+        let v24 = Rc::new(Vertex::new(1.5, 3.5));
+        let e1 = Rc::new(Edge::new(&v21, &v22));
+        let e2 = Rc::new(Edge::new(&v23, &v24));
+        let segments_set: HashSet<Rc<Edge>> = vec![Rc::clone(&e1), Rc::clone(&e2)]
+            .iter()
+            .cloned()
+            .collect();
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_hole(&hole);
+        let result = triangulator.insert_segments(&segments_set);
+
+        assert!(result.is_err());
+        if let Err(TriangulationError::OutsideBoundary {
+            segments: conflicting_edges, ..
+        }) = result
+        {
+            assert!(conflicting_edges.contains(&e1));
+            assert!(!conflicting_edges.contains(&e2));
+        }
+
+        assert!(!triangulator.segments.contains(&e1));
+        assert!(!triangulator.segments.contains(&e2));
+    }
+
+    #[test]
+    fn snap_rounded_crossings() {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
+            ])
+            .unwrap(),
+        );
+
+        /* three segments crossing nearly at (2, 2) */
+        let segments: HashSet<Rc<Edge>> = vec![
+            ((1.0, 1.0), (3.0, 3.0)),
+            ((1.0, 3.0), (3.0, 1.0)),
+            ((1.0, 2.0 + 1.0E-9), (3.0, 2.0 + 1.0E-9)),
+        ]
+        .into_iter()
+        .map(|((x1, y1), (x2, y2))| {
+            Rc::new(Edge::new(
+                &Rc::new(Vertex::new(x1, y1)),
+                &Rc::new(Vertex::new(x2, y2)),
+            ))
+        })
+        .collect();
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_segments(&segments).unwrap();
+        assert!(triangulator.segments.iter().any(|edge| edge.length() < 1.0E-6));
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.intersection_grid = Some(1.0E-6);
+        triangulator.insert_segments(&segments).unwrap();
+        assert_eq!(triangulator.segments.len(), 6);
+        assert!(triangulator.segments.iter().all(|edge| edge.length() > 1.0E-6));
+    }
+}
+
+#[cfg(test)]
+mod triangulate {
+    use super::*;
+
+    #[test]
+    fn triangulates_boundary_sample() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v1),
+                Rc::clone(&v2),
+                Rc::clone(&v3),
+                Rc::clone(&v4),
+            ])
+            .unwrap(),
+        );
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.try_triangulate().unwrap();
+
+        for edge in boundary.into_edges().iter() {
+            assert!(triangulator.triangulation.borrow().edges().contains(edge));
+        }
+
+        for triangle in triangulator
+            .triangulation
+            .borrow()
+            .solid_triangles()
+        {
+            assert_eq!(
+                boundary.contains(&triangle.center()),
+                Some(Continence::Inside)
+            );
+        }
+    }
+
+    #[test]
+    fn triangulates_hole() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v1),
+                Rc::clone(&v2),
+                Rc::clone(&v3),
+                Rc::clone(&v4),
+            ])
+            .unwrap(),
+        );
+
+        /* hole */
+        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        let v12 = Rc::new(Vertex::new(3.0, 2.0));
+        let v13 = Rc::new(Vertex::new(3.0, 3.0));
+        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v11),
+                Rc::clone(&v12),
+                Rc::clone(&v13),
+                Rc::clone(&v14),
+            ])
+            .unwrap(),
+        );
+        let mut triangulator = Triangulator::new(&boundary);
+        if triangulator.insert_hole(&hole).is_err() {
+            panic!("Expected not err");
+        }
+        triangulator.try_triangulate().unwrap();
+
+        for edge in hole.into_edges().iter().chain(boundary.into_edges().iter()) {
+            assert!(triangulator.triangulation.borrow().edges().contains(edge));
+        }
+
+        for triangle in triangulator
+            .triangulation
+            .borrow()
+            .solid_triangles()
+        {
+            assert_eq!(hole.contains(&triangle.center()), Some(Continence::Outside));
+            assert_eq!(
+                boundary.contains(&triangle.center()),
+                Some(Continence::Inside)
+            );
+        }
+    }
+
+    #[test]
+    fn includes_segment_constraints() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v1),
+                Rc::clone(&v2),
+                Rc::clone(&v3),
+                Rc::clone(&v4),
+            ])
+            .unwrap(),
+        );
+
+        /* Segments */
+        let v21 = Rc::new(Vertex::new(1.5, 2.5));
+        let v22 = Rc::new(Vertex::new(3.5, 2.5));
+        let v23 = Rc::new(Vertex::new(3.5, 3.5));
+        let v24 = Rc::new(Vertex::new(1.5, 3.5));
+
+        let e1 = Rc::new(Edge::new(&v21, &v22));
+        let e2 = Rc::new(Edge::new(&v23, &v24));
+        let segments_set: HashSet<Rc<Edge>> = vec![Rc::clone(&e1), Rc::clone(&e2)]
+            .iter()
+            .cloned()
+            .collect();
+
+        let mut triangulator = Triangulator::new(&boundary);
+        if triangulator.insert_segments(&segments_set).is_err() {
+            panic!("Expected not err");
+        }
+
+        triangulator.try_triangulate().unwrap();
+
+        for constrained_edge in segments_set.iter().chain(boundary.into_edges().iter()) {
+            assert!(Edge::decompose(
+                &triangulator.triangulation.borrow().edges().to_set(),
+                constrained_edge
+            )
+            .is_some());
+        }
+
+        /* segments are recovered with no Steiner points */
+        assert_eq!(triangulator.triangulation.borrow().vertices().len(), 8);
+    }
+
+    #[test]
+    fn includes_vertex_constraints() {
+        /* Squared boundary */
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v3 = Rc::new(Vertex::new(4.0, 4.0));
+        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v1),
+                Rc::clone(&v2),
+                Rc::clone(&v3),
+                Rc::clone(&v4),
+            ])
+            .unwrap(),
+        );
+
+        /* Vertices */
+        let v21 = Rc::new(Vertex::new(1.5, 2.5));
+        let v22 = Rc::new(Vertex::new(3.5, 2.5));
+        let v23 = Rc::new(Vertex::new(3.5, 3.5));
+        let v24 = Rc::new(Vertex::new(1.5, 3.5));
+
+        let vertices_set: HashSet<Rc<Vertex>> = vec![
+            Rc::clone(&v21),
+            Rc::clone(&v22),
+            Rc::clone(&v23),
+            Rc::clone(&v24),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        let mut triangulator = Triangulator::new(&boundary);
+        if triangulator.insert_vertices(&vertices_set).is_err() {
+            panic!("Expected not err");
+        }
+
+        triangulator.try_triangulate().unwrap();
+
+        for constrained_vertex in vertices_set.iter() {
+            assert!(triangulator
+                .triangulation
+                .borrow()
+                .vertices()
+                .contains(constrained_vertex));
+        }
+    }
+
+    #[test]
+    fn validates_refined_triangulation() {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(6.0, 0.0)),
+                Rc::new(Vertex::new(6.0, 4.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
+            ])
+            .unwrap(),
+        );
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(1.0, 1.0)),
+                Rc::new(Vertex::new(2.0, 1.0)),
+                Rc::new(Vertex::new(2.0, 2.0)),
+                Rc::new(Vertex::new(1.0, 2.0)),
+            ])
+            .unwrap(),
+        );
+        let segment = Rc::new(Edge::new(
+            &Rc::new(Vertex::new(3.0, 1.0)),
+            &Rc::new(Vertex::new(5.0, 3.0)),
+        ));
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_hole(&hole).unwrap();
+        triangulator
+            .insert_segments(&vec![segment].into_iter().collect())
+            .unwrap();
+        triangulator.try_triangulate().unwrap();
+        assert!(triangulator.validate().is_ok());
+
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(0.1),
+                quality_ratio: 1.0,
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .unwrap();
+        assert!(triangulator.validate().is_ok());
+
+        /* a constraint no longer in the triangulation */
+        triangulator.segments.insert(Rc::new(Edge::new(
+            &Rc::new(Vertex::new(3.0, 3.0)),
+            &Rc::new(Vertex::new(5.0, 1.0)),
+        )));
+        let violations = triangulator.validate().unwrap_err();
+        assert!(violations
+            .iter()
+            .all(|violation| matches!(violation, InvariantViolation::MissingConstraint(_))));
+
+        /* validation marks nothing, even while the triangulation is borrowed */
+        let triangulation = triangulator.triangulation.borrow();
+        let marks = triangulation.constraints().clone();
+        assert_eq!(triangulator.validate().unwrap_err().len(), violations.len());
+        assert_eq!(triangulation.constraints(), &marks);
+    }
+
+    #[test]
+    fn triangulates_tiled() {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(6.0, 0.0)),
+                Rc::new(Vertex::new(6.0, 4.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
+            ])
+            .unwrap(),
+        );
+        /* cut lines cross the hole and the segment */
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(1.0, 1.0)),
+                Rc::new(Vertex::new(2.0, 1.0)),
+                Rc::new(Vertex::new(2.0, 2.0)),
+                Rc::new(Vertex::new(1.0, 2.0)),
+            ])
+            .unwrap(),
+        );
+        let segment = Rc::new(Edge::new(
+            &Rc::new(Vertex::new(3.0, 1.0)),
+            &Rc::new(Vertex::new(5.0, 3.0)),
+        ));
+        let params = RefineParams {
+            max_area: Some(0.1),
+            quality_ratio: 1.0,
+            max_edge_length: None,
+            regions: Vec::new(),
+        };
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_hole(&hole).unwrap();
+        triangulator
+            .insert_segments(&vec![segment].into_iter().collect())
+            .unwrap();
+        triangulator
+            .try_triangulate_tiled(1.5, params.clone(), 2)
+            .unwrap();
+        assert!(triangulator.validate().is_ok());
+
+        /* seams are refined as the tiles, and constraints are marked again */
+        let triangulation = triangulator.triangulation.borrow();
+        let area: f64 = triangulation.solid_triangles().map(|t| t.area().unwrap()).sum();
+        assert!(float_cmp::approx_eq!(f64, area, 23.0, epsilon = 1.0E-10f64));
+        for triangle in triangulation.solid_triangles() {
+            assert!(!refine_procedures::triangle_split::is_irregular_triangle(
+                triangle, &params
+            ));
+            assert!(!refine_procedures::triangle_split::is_large_triangle(
+                triangle, &params
+            ));
+        }
+        assert!(!triangulation.constraints().is_empty());
+    }
+
+    #[test]
+    fn max_edge_length() {
+        let square = |x: f64, size: f64| {
+            return Rc::new(
+                Polyline::new_closed(vec![
+                    Rc::new(Vertex::new(x, 0.0)),
+                    Rc::new(Vertex::new(x + size, 0.0)),
+                    Rc::new(Vertex::new(x + size, size)),
+                    Rc::new(Vertex::new(x, size)),
+                ])
+                .unwrap(),
+            );
+        };
+
+        let mut triangulator = Triangulator::new(&square(0.0, 4.0));
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams {
+                max_area: None,
+                quality_ratio: 2.0,
+                max_edge_length: Some(1.0),
+                regions: vec![RegionParams {
+                    max_edge_length: Some(0.5),
+                    ..RegionParams::new(square(0.0, 2.0), false)
+                }],
+            })
+            .unwrap();
+        assert!(triangulator.validate().is_ok());
+
+        let triangulation = triangulator.triangulation.borrow();
+        for triangle in triangulation.solid_triangles() {
+            let (e1, e2, e3) = triangle.inner_edges();
+            let longest = e1.length().max(e2.length()).max(e3.length());
+            let center = triangle.center();
+            if center.x < 2.0 && center.y < 2.0 {
+                assert!(longest <= 0.5);
+            } else {
+                assert!(longest <= 1.0);
+            }
+        }
+    }
+    #[test]
+    fn region_params() {
+        let square = |x: f64, y: f64, size: f64| {
+            return Rc::new(
+                Polyline::new_closed(vec![
+                    Rc::new(Vertex::new(x, y)),
+                    Rc::new(Vertex::new(x + size, y)),
+                    Rc::new(Vertex::new(x + size, y + size)),
+                    Rc::new(Vertex::new(x, y + size)),
+                ])
+                .unwrap(),
+            );
+        };
+        let hole = square(1.5, 1.5, 1.0);
+
+        let mut triangulator = Triangulator::new(&square(0.0, 0.0, 4.0));
+        triangulator.insert_hole(&hole).unwrap();
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(1.0),
+                quality_ratio: 2.0,
+                max_edge_length: None,
+                regions: vec![RegionParams {
+                    max_area: Some(0.05),
+                    ..RegionParams::new(Rc::clone(&hole), true)
+                }],
+            })
+            .unwrap();
+        assert!(triangulator.validate().is_ok());
+
+        /* the triangles around the hole are finer than the others */
+        let triangulation = triangulator.triangulation.borrow();
+        let (around, apart): (Vec<Rc<Triangle>>, Vec<Rc<Triangle>>) = triangulation
+            .solid_triangles()
+            .into_iter()
+            .cloned()
+            .partition(|triangle| {
+                vec![&triangle.v1, &triangle.v2, &triangle.v3]
+                    .into_iter()
+                    .any(|v| hole.contains(v) == Some(Continence::Boundary))
+            });
+        assert!(!around.is_empty());
+        assert!(around.iter().all(|triangle| triangle.area().unwrap() < 0.05));
+        assert!(apart.iter().all(|triangle| triangle.area().unwrap() < 1.0));
+        assert!(apart.iter().any(|triangle| triangle.area().unwrap() > 0.05));
+    }
+
+    fn lattice_polyline(points: &[(f64, f64)]) -> Rc<Polyline> {
+        let vertices = points
+            .iter()
+            .map(|&(x, y)| Rc::new(Vertex::new(x, y)))
+            .collect();
+        return Rc::new(Polyline::new_closed(vertices).unwrap());
+    }
+
+    fn solid_area(triangulator: &Triangulator) -> f64 {
+        return triangulator
+            .triangulation
+            .borrow()
+            .solid_triangles()
+            .filter_map(|t| t.area())
+            .sum();
+    }
+
+    #[test]
+    fn recovers_segments_across_constraints() {
+        /* minimized property case, once digging cavities across recovered segments */
+        let boundary = lattice_polyline(&[
+            (-9.5, 2.5),
+            (-6.25, -3.75),
+            (-3.0, -9.0),
+            (4.25, -6.75),
+            (6.75, -5.5),
+            (6.75, 1.5),
+            (6.25, 6.0),
+            (3.75, 9.25),
+            (-1.5, 9.5),
+            (-5.0, 7.5),
+        ]);
+        let ends = vec![
+            (-2.8594202840160983, -2.15331118688643),
+            (1.752181530147437, -1.9451528450044362),
+            (2.7467577897212507, -0.0900483164837095),
+            (0.5510267832041452, 1.890390768823837),
+            (-1.600379249278071, -2.4263838402533633),
+            (-2.445498262660982, 1.4154354206564872),
+            (2.4073625032673656, -3.3376687902141295),
+            (-3.1874923198500387, -0.3454958354201709),
+            (2.0007906955524724, 0.24301338477976814),
+            (1.2979166803154731, 1.9264125927281077),
+            (-2.266672768284044, 1.8555034736346292),
+            (-1.0501529084096255, 3.1063825620266208),
+            (-0.49645254719127274, 0.2328830237281753),
+            (2.27602104180475, -1.275501167864413),
+        ];
+
+        /* hash ordering varies between runs, so that a few runs are taken */
+        for _ in 0..8 {
+            let segments: HashSet<Rc<Edge>> = ends
+                .chunks(2)
+                .map(|pair| {
+                    Rc::new(Edge::new(
+                        &Rc::new(Vertex::new(pair[0].0, pair[0].1)),
+                        &Rc::new(Vertex::new(pair[1].0, pair[1].1)),
+                    ))
+                })
+                .collect();
+            let mut triangulator = Triangulator::new(&boundary);
+            triangulator.insert_segments(&segments).unwrap();
+            assert!(triangulator.try_triangulate().is_ok());
+            assert!(triangulator.validate().is_ok());
+            let domain = boundary.area().unwrap().abs();
+            assert!((solid_area(&triangulator) - domain).abs() <= 1.0E-9 * domain);
+        }
+    }
+
+    #[test]
+    fn refines_around_hole() {
+        let boundary = lattice_polyline(&[(0.0, 0.0), (6.0, 0.0), (6.0, 6.0), (0.0, 6.0)]);
+        let hole = lattice_polyline(&[(2.1, 1.9), (4.2, 2.3), (2.9, 4.1)]);
+
+        /* ghost triangles of the hole never conflict with vertices across it */
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_hole(&hole).unwrap();
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(0.2),
+                quality_ratio: 2.0_f64.sqrt(),
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .unwrap();
+        assert!(triangulator.validate().is_ok());
+        let domain = boundary.area().unwrap().abs() - hole.area().unwrap().abs();
+        assert!((solid_area(&triangulator) - domain).abs() <= 1.0E-9 * domain);
+    }
+
+    #[test]
+    fn keeps_cocircular_lattice_points_delaunay() {
+        /* minimized property case, (2.75, -1.75) cocircular to (2.25, -2.25) (8, -3.5) (4, -7.5) */
+        let boundary = lattice_polyline(&[
+            (-6.75, 4.5),
+            (-5.75, 0.75),
+            (-3.5, -5.0),
+            (4.0, -7.5),
+            (8.0, -3.5),
+            (6.5, 2.25),
+            (3.5, 7.25),
+            (-6.5, 6.25),
+        ]);
+        let hole = lattice_polyline(&[
+            (-1.5, -1.0),
+            (-0.25, -1.25),
+            (0.5, -0.5),
+            (1.5, 1.0),
+            (-1.5, 1.25),
+        ]);
+        let points = vec![
+            (1.0, -1.0),
+            (-1.0, -2.0),
+            (1.0, 3.25),
+            (1.25, 1.75),
+            (0.75, -0.5),
+            (0.75, -2.5),
+            (-0.5, 1.75),
+            (2.0, 0.5),
+            (2.5, -1.5),
+            (-2.75, -0.5),
+            (2.75, -1.75),
+            (3.25, 0.25),
+            (2.25, -2.25),
+            (-1.5, 1.5),
+        ];
+
+        for _ in 0..8 {
+            let vertices: HashSet<Rc<Vertex>> = points
+                .iter()
+                .map(|&(x, y)| Rc::new(Vertex::new(x, y)))
+                .collect();
+            let mut triangulator = Triangulator::new(&boundary);
+            triangulator.insert_hole(&hole).unwrap();
+            triangulator.insert_vertices(&vertices).unwrap();
+            assert!(triangulator.try_triangulate().is_ok());
+            assert!(triangulator.validate().is_ok());
+            let domain = boundary.area().unwrap().abs() - hole.area().unwrap().abs();
+            assert!((solid_area(&triangulator) - domain).abs() <= 1.0E-9 * domain);
+            for triangle in triangulator.triangulation.borrow().solid_triangles() {
+                assert_ne!(hole.contains(&triangle.center()), Some(Continence::Inside));
+            }
+        }
+    }
+}
 
 #[cfg(test)]
-mod insert_holes {
+mod conform {
     use super::*;
 
     #[test]
-    fn sample_1() {
+    fn splits_encroached_segment() {
         /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(4.0, 0.0));
         let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+        let v4 = Rc::new(Vertex::new(0.0, 4.0));
 
-        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
-        boundary.push(Rc::clone(&v1));
-        boundary.push(Rc::clone(&v2));
-        boundary.push(Rc::clone(&v3));
-        boundary.push(Rc::clone(&v4));
-        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v1),
+                Rc::clone(&v2),
+                Rc::clone(&v3),
+                Rc::clone(&v4),
+            ])
+            .unwrap(),
+        );
 
-        /* Inner Vertices */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
+        /* Segment */
+        let v11 = Rc::new(Vertex::new(1.0, 2.0));
         let v12 = Rc::new(Vertex::new(3.0, 2.0));
-        let v13 = Rc::new(Vertex::new(3.0, 3.0));
-        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+        let segments_set: HashSet<Rc<Edge>> = vec![Rc::new(Edge::new(&v11, &v12))]
+            .iter()
+            .cloned()
+            .collect();
 
-        let mut hole_vertices: Vec<Rc<Vertex>> = Vec::new();
-        hole_vertices.push(Rc::clone(&v11));
-        hole_vertices.push(Rc::clone(&v12));
-        hole_vertices.push(Rc::clone(&v13));
-        hole_vertices.push(Rc::clone(&v14));
-        let hole = Rc::new(Polyline::new_closed(hole_vertices).unwrap());
+        /* Encroaching vertex */
+        let v21 = Rc::new(Vertex::new(2.0, 2.3));
+        let vertices_set: HashSet<Rc<Vertex>> = vec![Rc::clone(&v21)].iter().cloned().collect();
 
         let mut triangulator = Triangulator::new(&boundary);
-        let result = triangulator.insert_hole(&hole);
-        assert!(result.is_ok());
+        if triangulator.insert_segments(&segments_set).is_err() {
+            panic!("Expected not err");
+        }
+        if triangulator.insert_vertices(&vertices_set).is_err() {
+            panic!("Expected not err");
+        }
 
-        assert!(triangulator.holes.contains(&hole));
+        triangulator.try_triangulate().unwrap();
+        let inner_vertices_before = triangulator.triangulation.borrow().vertices().len();
+        triangulator.try_conform().unwrap();
+
+        assert_eq!(triangulator.mode, ConformingMode::Conforming);
+        assert!(triangulator.segments.len() > 1);
+
+        let triangulation = triangulator.triangulation.borrow();
+        let vertices = triangulation.vertices();
+        let edges = triangulation.edges();
+        assert!(vertices.contains(&v21));
+        assert!(vertices.len() > inner_vertices_before);
+
+        for segment in triangulator
+            .segments
+            .iter()
+            .chain(triangulator.boundary.into_edges().iter())
+        {
+            assert!(edges.contains(segment) || edges.contains(&segment.opposite()));
+            for vertex in vertices.iter() {
+                assert_ne!(segment.encroach(vertex), Continence::Inside);
+            }
+        }
+
+        /* Steiner points lie on the segment */
+        for vertex in vertices.iter() {
+            let is_given = vertex == &v21 || boundary.vertices.contains(vertex);
+            let is_on_constraint = vertex.y == 2.0 || boundary.contains(vertex) == Some(Continence::Boundary);
+            assert!(is_given || is_on_constraint);
+        }
     }
 
     #[test]
-    fn error_insert_if_intersection_with_boundary() {
+    fn triangulate_conforms_on_conforming_mode() {
         /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(4.0, 0.0));
         let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+        let v4 = Rc::new(Vertex::new(0.0, 4.0));
 
-        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
-        boundary.push(Rc::clone(&v1));
-        boundary.push(Rc::clone(&v2));
-        boundary.push(Rc::clone(&v3));
-        boundary.push(Rc::clone(&v4));
-        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v1),
+                Rc::clone(&v2),
+                Rc::clone(&v3),
+                Rc::clone(&v4),
+            ])
+            .unwrap(),
+        );
 
-        /* Inner Vertices */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(3.0, 2.0));
-        let v13 = Rc::new(Vertex::new(6.0, 6.0));
-        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+        /* Vertex encroaching boundary */
+        let v21 = Rc::new(Vertex::new(2.0, 0.5));
+        let vertices_set: HashSet<Rc<Vertex>> = vec![Rc::clone(&v21)].iter().cloned().collect();
 
-        let mut hole_vertices: Vec<Rc<Vertex>> = Vec::new();
-        hole_vertices.push(Rc::clone(&v11));
-        hole_vertices.push(Rc::clone(&v12));
-        hole_vertices.push(Rc::clone(&v13));
-        hole_vertices.push(Rc::clone(&v14));
-        let hole = Rc::new(Polyline::new_closed(hole_vertices).unwrap());
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.mode = ConformingMode::Conforming;
+        if triangulator.insert_vertices(&vertices_set).is_err() {
+            panic!("Expected not err");
+        }
+        triangulator.try_triangulate().unwrap();
+
+        assert!(triangulator.boundary.vertices.len() > 4);
+        for edge in triangulator.boundary.into_edges().iter() {
+            assert_ne!(edge.encroach(&v21), Continence::Inside);
+        }
+    }
+
+    #[test]
+    fn error_when_passes_run_out() {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
+            ])
+            .unwrap(),
+        );
+        let encroaching: HashSet<Rc<Vertex>> =
+            vec![Rc::new(Vertex::new(2.0, 0.5))].into_iter().collect();
 
         let mut triangulator = Triangulator::new(&boundary);
-        let result = triangulator.insert_hole(&hole);
+        triangulator.insert_vertices(&encroaching).unwrap();
+        triangulator.try_triangulate().unwrap();
 
-        assert!(result.is_err());
-        if let Err(vertices) = result {
-            assert_eq!(vertices.len(), 4);
-            assert!(vertices.contains(&v1));
-            assert!(vertices.contains(&v2));
-            assert!(vertices.contains(&v3));
-            assert!(vertices.contains(&v4));
+        /* a single pass splits the boundary, with no pass left to check it */
+        match triangulator.conform_passes(1) {
+            Err(TriangulationError::NonTerminatingRefinement { triangles, .. }) => {
+                assert!(!triangles.is_empty());
+            }
+            _ => panic!("Expected non terminating refinement"),
         }
-        assert!(triangulator.holes.is_empty());
+        assert!(triangulator.boundary.vertices.len() > 4);
+        assert!(triangulator.validate().is_ok());
+
+        /* further passes find nothing else to split */
+        assert!(triangulator.try_conform().is_ok());
     }
 
     #[test]
-    fn error_insert_if_intersection_at_boundary() {
+    fn marks_split_constraints() {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
+            ])
+            .unwrap(),
+        );
+        let segment = Rc::new(Edge::new(
+            &Rc::new(Vertex::new(1.0, 2.0)),
+            &Rc::new(Vertex::new(3.0, 2.0)),
+        ));
+
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator
+            .insert_segments(&vec![Rc::clone(&segment)].into_iter().collect())
+            .unwrap();
+        triangulator.try_triangulate().unwrap();
+        assert!(triangulator.triangulation.borrow().is_constrained(&segment));
+        assert_eq!(triangulator.triangulation.borrow().constraints().len(), 5);
+
+        for max_area in vec![0.5, 0.1] {
+            triangulator
+                .try_refine(RefineParams {
+                    max_area: Some(max_area),
+                    quality_ratio: 1.0,
+                    max_edge_length: None,
+                    regions: Vec::new(),
+                })
+                .unwrap();
+
+            /* marks follow the subsegments, which cover the constraints */
+            let triangulation = triangulator.triangulation.borrow();
+            assert!(!triangulation.is_constrained(&segment));
+            let constrained: HashSet<Rc<Edge>> = triangulation.constrained_edges().collect();
+            assert_eq!(constrained.len(), triangulation.constraints().len());
+            for constraint in boundary.into_edges().iter().chain(vec![Rc::clone(&segment)].iter()) {
+                assert!(Edge::decompose(&constrained, constraint).is_some());
+            }
+            for edge in triangulation.interior_edges() {
+                let is_on_segment = segment.contains(&edge.v1) && segment.contains(&edge.v2);
+                assert_eq!(triangulation.is_constrained(&edge), is_on_segment);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod smooth {
+    use super::*;
+
+    #[test]
+    fn keeps_domain() {
         /* Squared boundary */
         let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
-
-        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
-        boundary.push(Rc::clone(&v1));
-        boundary.push(Rc::clone(&v2));
-        boundary.push(Rc::clone(&v3));
-        boundary.push(Rc::clone(&v4));
-        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+        let v2 = Rc::new(Vertex::new(5.0, 1.0));
+        let v3 = Rc::new(Vertex::new(5.0, 5.0));
+        let v4 = Rc::new(Vertex::new(1.0, 5.0));
 
-        /* Hole Vertices */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(3.0, 2.0));
-        let v13 = Rc::new(Vertex::new(3.0, 3.0));
-        let v14 = Rc::new(Vertex::new(2.0, 4.0));
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::clone(&v1),
+                Rc::clone(&v2),
+                Rc::clone(&v3),
+                Rc::clone(&v4),
+            ])
+            .unwrap(),
+        );
 
-        let mut hole_vertices: Vec<Rc<Vertex>> = Vec::new();
-        hole_vertices.push(Rc::clone(&v11));
-        hole_vertices.push(Rc::clone(&v12));
-        hole_vertices.push(Rc::clone(&v13));
-        hole_vertices.push(Rc::clone(&v14));
-        let hole = Rc::new(Polyline::new_closed(hole_vertices).unwrap());
+        /* Segment */
+        let v11 = Rc::new(Vertex::new(2.0, 3.0));
+        let v12 = Rc::new(Vertex::new(4.0, 3.0));
+        let segments_set: HashSet<Rc<Edge>> = vec![Rc::new(Edge::new(&v11, &v12))]
+            .iter()
+            .cloned()
+            .collect();
 
         let mut triangulator = Triangulator::new(&boundary);
-        let result = triangulator.insert_hole(&hole);
+        if triangulator.insert_segments(&segments_set).is_err() {
+            panic!("Expected not err");
+        }
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(0.2),
+                quality_ratio: 1.0,
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .unwrap();
 
-        assert!(result.is_err());
-        if let Err(vertices) = result {
-            assert_eq!(vertices.len(), 4);
-            assert!(vertices.contains(&v1));
-            assert!(vertices.contains(&v2));
-            assert!(vertices.contains(&v3));
-            assert!(vertices.contains(&v4));
+        let vertices_count = triangulator.triangulation.borrow().vertices().len();
+        triangulator.smooth(3);
+
+        let triangulation = triangulator.triangulation.borrow();
+        assert_eq!(triangulation.vertices().len(), vertices_count);
+
+        let area: f64 = triangulation
+            .solid_triangles()
+            .map(|t| t.area().unwrap())
+            .sum();
+        assert!((area - 16.0).abs() < 1.0e-10);
+
+        for constrained_edge in segments_set.iter().chain(boundary.into_edges().iter()) {
+            assert!(Edge::decompose(&triangulation.edges().to_set(), constrained_edge).is_some());
         }
-        assert!(triangulator.holes.is_empty());
     }
+}
+
+#[cfg(test)]
+mod provenance {
+    use super::*;
 
     #[test]
-    fn two_holes() {
-        /* Squared boundary */
+    fn maps_triangles_to_constraints() {
         let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+        let v2 = Rc::new(Vertex::new(5.0, 1.0));
+        let v3 = Rc::new(Vertex::new(5.0, 5.0));
+        let v4 = Rc::new(Vertex::new(1.0, 5.0));
+        let boundary = Rc::new(Polyline::new_closed(vec![v1, v2, v3, v4]).unwrap());
 
-        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
-        boundary.push(Rc::clone(&v1));
-        boundary.push(Rc::clone(&v2));
-        boundary.push(Rc::clone(&v3));
-        boundary.push(Rc::clone(&v4));
-        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+        let v11 = Rc::new(Vertex::new(2.0, 3.0));
+        let v12 = Rc::new(Vertex::new(4.0, 3.0));
+        let segment = Rc::new(Edge::new(&v11, &v12));
+        let segments_set: HashSet<Rc<Edge>> = vec![Rc::clone(&segment)].into_iter().collect();
 
-        /* Hole 1 */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(2.9, 2.0));
-        let v13 = Rc::new(Vertex::new(2.0, 2.9));
+        /* encroaches the segment */
+        let v21 = Rc::new(Vertex::new(3.0, 3.3));
+        let vertices_set: HashSet<Rc<Vertex>> = vec![v21].into_iter().collect();
 
-        let mut hole1_vertices: Vec<Rc<Vertex>> = Vec::new();
-        hole1_vertices.push(Rc::clone(&v11));
-        hole1_vertices.push(Rc::clone(&v12));
-        hole1_vertices.push(Rc::clone(&v13));
-        let hole_1 = Rc::new(Polyline::new_closed(hole1_vertices).unwrap());
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.record_provenance = true;
+        assert!(triangulator.insert_segments(&segments_set).is_ok());
+        assert!(triangulator.insert_vertices(&vertices_set).is_ok());
+        triangulator.try_triangulate().unwrap();
 
-        /* Hole 2 */
-        let v21 = Rc::new(Vertex::new(3.0, 3.0));
-        let v22 = Rc::new(Vertex::new(2.1, 3.0));
-        let v23 = Rc::new(Vertex::new(3.0, 2.1));
+        for triangle in triangulator.provenance.triangles.keys() {
+            assert!(triangulator.triangulation.borrow().triangles.contains(triangle));
+        }
 
-        let mut hole2_vertices: Vec<Rc<Vertex>> = Vec::new();
-        hole2_vertices.push(Rc::clone(&v21));
-        hole2_vertices.push(Rc::clone(&v22));
-        hole2_vertices.push(Rc::clone(&v23));
-        let hole_2 = Rc::new(Polyline::new_closed(hole2_vertices).unwrap());
+        triangulator.try_conform().unwrap();
+
+        /* triangles around the segment Steiner points */
+        let segment_triangles = triangulator
+            .provenance
+            .triangles_of(&Provenance::Segment(Rc::clone(&segment)));
+        assert!(segment_triangles
+            .iter()
+            .any(|t| t.v1 == Rc::new(Vertex::new(3.0, 3.0))
+                || t.v2 == Rc::new(Vertex::new(3.0, 3.0))
+                || t.v3 == Rc::new(Vertex::new(3.0, 3.0))));
+
+        for triangle in triangulator.provenance.triangles.keys() {
+            assert!(triangulator.triangulation.borrow().triangles.contains(triangle));
+        }
+    }
+
+    #[test]
+    fn records_nothing_by_default() {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(1.0, 1.0)),
+                Rc::new(Vertex::new(5.0, 1.0)),
+                Rc::new(Vertex::new(5.0, 5.0)),
+                Rc::new(Vertex::new(1.0, 5.0)),
+            ])
+            .unwrap(),
+        );
+        let segment = Rc::new(Edge::new(
+            &Rc::new(Vertex::new(2.0, 3.0)),
+            &Rc::new(Vertex::new(4.0, 3.0)),
+        ));
 
         let mut triangulator = Triangulator::new(&boundary);
-        triangulator.insert_hole(&hole_1);
-        let result = triangulator.insert_hole(&hole_2);
+        assert!(!triangulator.record_provenance);
+        assert!(triangulator.insert_segments(&vec![segment].into_iter().collect()).is_ok());
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(0.5),
+                quality_ratio: 1.0,
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .unwrap();
+        assert!(triangulator.provenance.triangles.is_empty());
+    }
+} /* end - provenance tests */
 
-        assert!(result.is_ok());
-        assert!(triangulator.holes.contains(&hole_1));
-        assert!(triangulator.holes.contains(&hole_2));
+#[cfg(test)]
+mod domains {
+    use super::*;
+
+    fn rectangle(x0: f64, y0: f64, x1: f64, y1: f64) -> Rc<Polyline> {
+        return Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(x0, y0)),
+                Rc::new(Vertex::new(x1, y0)),
+                Rc::new(Vertex::new(x1, y1)),
+                Rc::new(Vertex::new(x0, y1)),
+            ])
+            .unwrap(),
+        );
     }
 
     #[test]
-    fn error_on_hole_intersection() {
-        /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+    fn shared_interface() {
+        let left = rectangle(1.0, 1.0, 3.0, 3.0);
+        let right = rectangle(3.0, 1.0, 5.0, 3.0);
+
+        let mut triangulator = Triangulator::from_domains(&vec![left, right]).unwrap();
+        assert_eq!(triangulator.boundary.vertices.len(), 6);
+
+        let interface = Rc::new(Edge::new(
+            &Rc::new(Vertex::new(3.0, 1.0)),
+            &Rc::new(Vertex::new(3.0, 3.0)),
+        ));
+        assert_eq!(triangulator.segments.len(), 1);
+        assert!(
+            triangulator.segments.contains(&interface)
+                || triangulator.segments.contains(&Rc::new(interface.opposite()))
+        );
 
-        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
-        boundary.push(Rc::clone(&v1));
-        boundary.push(Rc::clone(&v2));
-        boundary.push(Rc::clone(&v3));
-        boundary.push(Rc::clone(&v4));
-        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(0.5),
+                quality_ratio: 1.0,
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .unwrap();
+
+        let membership = triangulator.domain_membership();
+        let solid_triangles = triangulator
+            .triangulation
+            .borrow()
+            .solid_triangles()
+            .count();
+        assert_eq!(membership.len(), solid_triangles);
+
+        for (triangle, domain) in membership.iter() {
+            let center = triangle.center();
+            assert_eq!(*domain, if center.x < 3.0 { 0 } else { 1 });
+            for vertex in vec![&triangle.v1, &triangle.v2, &triangle.v3] {
+                if *domain == 0 {
+                    assert!(vertex.x <= 3.0);
+                } else {
+                    assert!(vertex.x >= 3.0);
+                }
+            }
+        }
+    }
 
-        /* Hole 1 */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(2.9, 2.0));
-        let v13 = Rc::new(Vertex::new(2.0, 2.9));
+    #[test]
+    fn t_junction() {
+        /* right domain shares part of the left domain edge */
+        let left = rectangle(1.0, 1.0, 3.0, 5.0);
+        let right = rectangle(3.0, 2.0, 5.0, 4.0);
 
-        let mut hole1_vertices: Vec<Rc<Vertex>> = Vec::new();
-        hole1_vertices.push(Rc::clone(&v11));
-        hole1_vertices.push(Rc::clone(&v12));
-        hole1_vertices.push(Rc::clone(&v13));
-        let hole_1 = Rc::new(Polyline::new_closed(hole1_vertices).unwrap());
+        let mut triangulator = Triangulator::from_domains(&vec![left, right]).unwrap();
+        assert_eq!(triangulator.segments.len(), 1);
 
-        /* Hole 2 */
-        let v21 = Rc::new(Vertex::new(3.0, 3.0));
-        let v22 = Rc::new(Vertex::new(2.1, 3.0));
-        let v23 = Rc::new(Vertex::new(2.9, 2.0));
-        let v24 = Rc::new(Vertex::new(3.0, 2.1));
+        triangulator.try_triangulate().unwrap();
+        assert!(triangulator.domain_membership().values().any(|&d| d == 1));
+    }
 
-        let mut hole2_vertices: Vec<Rc<Vertex>> = Vec::new();
-        hole2_vertices.push(Rc::clone(&v21));
-        hole2_vertices.push(Rc::clone(&v22));
-        hole2_vertices.push(Rc::clone(&v23));
-        hole2_vertices.push(Rc::clone(&v24));
-        let hole_2 = Rc::new(Polyline::new_closed(hole2_vertices).unwrap());
+    #[test]
+    fn disjoint_domains() {
+        let left = rectangle(1.0, 1.0, 2.0, 2.0);
+        let right = rectangle(3.0, 1.0, 4.0, 2.0);
+        assert!(Triangulator::from_domains(&vec![left, right]).is_none());
+    }
+} /* end - domains tests */
 
-        let mut triangulator = Triangulator::new(&boundary);
-        triangulator.insert_hole(&hole_1);
-        let result = triangulator.insert_hole(&hole_2);
+#[cfg(test)]
+mod components {
+    use super::*;
 
-        assert!(result.is_err());
-        if let Err(vertices) = result {
-            assert_eq!(vertices.len(), 3);
-            assert!(vertices.contains(&v11));
-            assert!(vertices.contains(&v12));
-            assert!(vertices.contains(&v13));
+    fn refined_square(x: f64, hole: bool) -> Triangulator {
+        let square = |x0: f64, y0: f64, size: f64| {
+            Rc::new(
+                Polyline::new_closed(vec![
+                    Rc::new(Vertex::new(x0, y0)),
+                    Rc::new(Vertex::new(x0 + size, y0)),
+                    Rc::new(Vertex::new(x0 + size, y0 + size)),
+                    Rc::new(Vertex::new(x0, y0 + size)),
+                ])
+                .unwrap(),
+            )
+        };
+        let mut triangulator = Triangulator::new(&square(x, 0.0, 4.0));
+        if hole {
+            triangulator.insert_hole(&square(x + 1.0, 1.0, 2.0)).unwrap();
         }
-        assert!(triangulator.holes.contains(&hole_1));
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(0.5),
+                quality_ratio: 1.5,
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .unwrap();
+        return triangulator;
     }
 
     #[test]
-    fn error_on_segments_wrong_continence() {
-        /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
-
-        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
-        boundary.push(Rc::clone(&v1));
-        boundary.push(Rc::clone(&v2));
-        boundary.push(Rc::clone(&v3));
-        boundary.push(Rc::clone(&v4));
-        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+    fn disconnected_components() {
+        let mut triangulator = refined_square(0.0, false);
+        let right = refined_square(6.0, true);
+        let right_triangles = right.triangulation.borrow().solid_triangles().count();
+        let left_triangles = triangulator.triangulation.borrow().solid_triangles().count();
+
+        triangulator.include_component(right).unwrap();
+        assert_eq!(triangulator.disjoint_boundaries.len(), 1);
+        assert_eq!(triangulator.holes.len(), 1);
+        assert!(triangulator.validate().is_ok());
+
+        let triangulation = triangulator.triangulation.borrow();
+        assert_eq!(
+            triangulation.solid_triangles().count(),
+            left_triangles + right_triangles
+        );
+        assert_eq!(triangulation.ghost_loops().len(), 3);
+        for triangle in triangulation.solid_triangles() {
+            let component = if triangle.center().x < 5.0 { 0 } else { 1 };
+            assert_eq!(triangulator.component_of(triangle), Some(component));
+        }
+        drop(triangulation);
+
+        assert!(triangulator.try_triangulate().is_err());
+        assert!(triangulator
+            .try_refine(RefineParams {
+                max_area: Some(0.1),
+                quality_ratio: 1.5,
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .is_err());
+    }
 
-        /* Segments */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(3.0, 2.0));
-        let v13 = Rc::new(Vertex::new(3.0, 3.0));
-        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+    #[test]
+    fn overlapping_components() {
+        let mut triangulator = refined_square(0.0, false);
+        assert!(triangulator.include_component(refined_square(2.0, false)).is_err());
+        assert!(triangulator.disjoint_boundaries.is_empty());
+    }
+} /* end - components tests */
 
-        let segments_constrains: HashSet<Rc<Edge>> = vec![
-            Rc::new(Edge::new(&v11, &v12)),
-            Rc::new(Edge::new(&v13, &v14)),
-        ]
-        .iter()
-        .cloned()
-        .collect();
+#[cfg(test)]
+mod presplit_constraints {
+    use super::*;
 
-        /* Hole */
-        let v21 = Rc::new(Vertex::new(1.5, 1.5));
-        let v22 = Rc::new(Vertex::new(3.5, 1.5));
-        let v23 = Rc::new(Vertex::new(3.5, 3.5));
-        let v24 = Rc::new(Vertex::new(1.5, 3.5));
+    #[test]
+    fn bounded_constraints() {
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(9.0, 1.0));
+        let v3 = Rc::new(Vertex::new(9.0, 9.0));
+        let v4 = Rc::new(Vertex::new(1.0, 9.0));
+        let boundary = Rc::new(Polyline::new_closed(vec![v1, v2, v3, v4]).unwrap());
 
-        let mut hole_vertices: Vec<Rc<Vertex>> = Vec::new();
-        hole_vertices.push(Rc::clone(&v21));
-        hole_vertices.push(Rc::clone(&v22));
-        hole_vertices.push(Rc::clone(&v23));
-        hole_vertices.push(Rc::clone(&v24));
-        let hole = Rc::new(Polyline::new_closed(hole_vertices).unwrap());
+        let v11 = Rc::new(Vertex::new(2.0, 5.0));
+        let v12 = Rc::new(Vertex::new(8.0, 5.0));
+        let segments_set: HashSet<Rc<Edge>> =
+            vec![Rc::new(Edge::new(&v11, &v12))].into_iter().collect();
 
         let mut triangulator = Triangulator::new(&boundary);
-        triangulator.insert_segments(&segments_constrains);
-        let result = triangulator.insert_hole(&hole);
+        assert!(triangulator.insert_segments(&segments_set).is_ok());
+        triangulator.max_constraint_length = Some(2.0);
+        triangulator.try_triangulate().unwrap();
 
-        assert!(result.is_err());
-        if let Err(vertices) = result {
-            assert_eq!(vertices.len(), 4);
-            assert!(vertices.contains(&v11));
-            assert!(vertices.contains(&v12));
-            assert!(vertices.contains(&v13));
-            assert!(vertices.contains(&v14));
+        assert_eq!(triangulator.boundary.vertices.len(), 16);
+        assert_eq!(triangulator.segments.len(), 3);
+
+        let edges = triangulator.triangulation.borrow().edges().to_set();
+        for segment in triangulator
+            .segments
+            .iter()
+            .cloned()
+            .chain(triangulator.boundary.into_edges())
+        {
+            assert!(segment.length() <= 2.0 + 1.0E-10);
+            assert!(edges.contains(&segment) || edges.contains(&Rc::new(segment.opposite())));
         }
-        assert!(triangulator.holes.is_empty());
     }
-} /* end - insert_hole tests */
+} /* end - presplit_constraints tests */
 
 #[cfg(test)]
-mod insert_vertices {
+mod optimize {
     use super::*;
 
     #[test]
-    fn sample_1() {
-        /* Squared boundary */
+    fn keeps_constraints_and_area() {
         let v1 = Rc::new(Vertex::new(1.0, 1.0));
         let v2 = Rc::new(Vertex::new(5.0, 1.0));
         let v3 = Rc::new(Vertex::new(5.0, 5.0));
         let v4 = Rc::new(Vertex::new(1.0, 5.0));
+        let boundary = Rc::new(Polyline::new_closed(vec![v1, v2, v3, v4]).unwrap());
 
-        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
-        boundary.push(Rc::clone(&v1));
-        boundary.push(Rc::clone(&v2));
-        boundary.push(Rc::clone(&v3));
-        boundary.push(Rc::clone(&v4));
-        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
-
-        /* Inner Vertices */
-        let v14 = Rc::new(Vertex::new(2.0, 3.0));
-        let v15 = Rc::new(Vertex::new(1.0000001, 1.0000001));
-        let v16 = Rc::new(Vertex::new(4.9999999, 4.9999999));
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(0.5),
+                quality_ratio: 1.0,
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .unwrap();
+        let vertices_before = triangulator.triangulation.borrow().vertices().len();
 
-        let mut inner_vertices: HashSet<Rc<Vertex>> = HashSet::new();
-        inner_vertices.insert(Rc::clone(&v14));
-        inner_vertices.insert(Rc::clone(&v15));
-        inner_vertices.insert(Rc::clone(&v16));
+        triangulator.optimize(5);
 
-        let mut triangulator = Triangulator::new(&boundary);
-        let result = triangulator.insert_vertices(&inner_vertices);
-        assert!(result.is_ok());
+        let triangulation = triangulator.triangulation.borrow();
+        assert_eq!(triangulation.vertices().len(), vertices_before);
+        for vertex in boundary.vertices.iter() {
+            assert!(triangulation.vertices().contains(vertex));
+        }
 
-        assert!(triangulator.vertices.contains(&v14));
-        assert!(triangulator.vertices.contains(&v15));
-        assert!(triangulator.vertices.contains(&v16));
+        let area: f64 = triangulation
+            .solid_triangles()
+            .map(|t| t.area().unwrap())
+            .sum();
+        assert!((area - 16.0).abs() < 1.0e-9);
     }
+} /* end - optimize tests */
 
-    #[test]
-    fn error_if_any_out_of_boundary() {
-        /* Squared boundary */
+#[cfg(test)]
+mod welding {
+    use super::*;
+
+    fn square() -> Rc<Polyline> {
         let v1 = Rc::new(Vertex::new(1.0, 1.0));
         let v2 = Rc::new(Vertex::new(5.0, 1.0));
-        let v3 = Rc::new(Vertex::new(5.0, 5.0));
-        let v4 = Rc::new(Vertex::new(1.0, 5.0));
-
-        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
-        boundary.push(Rc::clone(&v1));
-        boundary.push(Rc::clone(&v2));
-        boundary.push(Rc::clone(&v3));
-        boundary.push(Rc::clone(&v4));
-        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
-
-        /* Outer Vertices */
-        let v10 = Rc::new(Vertex::new(0.0, 0.0));
-        let v11 = Rc::new(Vertex::new(0.9999999, 0.9999999));
-        let v12 = Rc::new(Vertex::new(5.0000001, 5.0000001));
-        let v13 = Rc::new(Vertex::new(3.0, 1.0)); /* at the boundary */
+        let v3 = Rc::new(Vertex::new(5.0, 1.0 + 1.0E-14));
+        let v4 = Rc::new(Vertex::new(5.0, 5.0));
+        let v5 = Rc::new(Vertex::new(1.0, 5.0));
+        return Rc::new(Polyline::new_closed(vec![v1, v2, v3, v4, v5]).unwrap());
+    }
 
-        /* Inner Vertices */
-        let v14 = Rc::new(Vertex::new(2.0, 3.0));
-        let v15 = Rc::new(Vertex::new(1.0000001, 1.0000001));
-        let v16 = Rc::new(Vertex::new(4.9999999, 4.9999999));
+    #[test]
+    fn welds_boundary() {
+        let mut triangulator = Triangulator::new(&square());
+        assert_eq!(triangulator.boundary.vertices.len(), 4);
+
+        triangulator.try_triangulate().unwrap();
+        let triangulation = triangulator.triangulation.borrow();
+        assert_eq!(triangulation.vertices().len(), 4);
+        assert_eq!(
+            triangulation.solid_triangles().count(),
+            2
+        );
+    }
 
-        let mut inner_vertices: HashSet<Rc<Vertex>> = HashSet::new();
-        inner_vertices.insert(Rc::clone(&v10));
-        inner_vertices.insert(Rc::clone(&v11));
-        inner_vertices.insert(Rc::clone(&v12));
-        inner_vertices.insert(Rc::clone(&v13));
-        inner_vertices.insert(Rc::clone(&v14));
-        inner_vertices.insert(Rc::clone(&v15));
-        inner_vertices.insert(Rc::clone(&v16));
+    #[test]
+    fn welds_constraints() {
+        let mut triangulator = Triangulator::with_welding_tolerance(&square(), 1.0E-3).unwrap();
+
+        let v1 = Rc::new(Vertex::new(2.0, 2.0));
+        let v2 = Rc::new(Vertex::new(4.0, 2.0));
+        let v3 = Rc::new(Vertex::new(4.0, 2.0 + 1.0E-4));
+        let v4 = Rc::new(Vertex::new(2.0, 4.0));
+        let v5 = Rc::new(Vertex::new(2.0 + 1.0E-4, 4.0));
+
+        /* the second segment is shorter than the tolerance */
+        let segments: HashSet<Rc<Edge>> = vec![
+            Rc::new(Edge::new(&v1, &v2)),
+            Rc::new(Edge::new(&v2, &v3)),
+            Rc::new(Edge::new(&v4, &v5)),
+        ]
+        .into_iter()
+        .collect();
+        assert!(triangulator.insert_segments(&segments).is_ok());
+        assert_eq!(triangulator.segments.len(), 1);
 
-        let mut triangulator = Triangulator::new(&boundary);
-        let result = triangulator.insert_vertices(&inner_vertices);
-        assert!(result.is_err());
+        /* the first vertex welds to a segment end */
+        let vertices: HashSet<Rc<Vertex>> = vec![
+            Rc::new(Vertex::new(2.0, 2.0 + 1.0E-4)),
+            Rc::new(Vertex::new(3.0, 4.0)),
+        ]
+        .into_iter()
+        .collect();
+        assert!(triangulator.insert_vertices(&vertices).is_ok());
+        assert_eq!(triangulator.vertices.len(), 1);
 
-        if let Err(panic_vertices) = result {
-            assert!(panic_vertices.contains(&v10));
-            assert!(panic_vertices.contains(&v11));
-            assert!(panic_vertices.contains(&v12));
-            assert!(panic_vertices.contains(&v13));
-        }
+        triangulator.try_triangulate().unwrap();
+        assert_eq!(triangulator.triangulation.borrow().vertices().len(), 7);
     }
 
     #[test]
-    fn error_if_any_inside_hole() {
-        /* Squared boundary */
+    fn degenerate_boundary() {
         let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
-
-        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
-        boundary.push(Rc::clone(&v1));
-        boundary.push(Rc::clone(&v2));
-        boundary.push(Rc::clone(&v3));
-        boundary.push(Rc::clone(&v4));
-        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
-
-        /* Hole Vertices */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(3.0, 2.0));
-        let v13 = Rc::new(Vertex::new(3.0, 3.0));
-        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+        let v2 = Rc::new(Vertex::new(1.0, 1.1));
+        let v3 = Rc::new(Vertex::new(1.1, 1.0));
+        let boundary = Rc::new(Polyline::new_closed(vec![v1, v2, v3]).unwrap());
 
-        let mut hole_vertices: Vec<Rc<Vertex>> = Vec::new();
-        hole_vertices.push(Rc::clone(&v11));
-        hole_vertices.push(Rc::clone(&v12));
-        hole_vertices.push(Rc::clone(&v13));
-        hole_vertices.push(Rc::clone(&v14));
-        let hole = Rc::new(Polyline::new_closed(hole_vertices).unwrap());
+        assert!(Triangulator::with_welding_tolerance(&boundary, 0.5).is_none());
+    }
+} /* end - welding tests */
 
-        /* Ok Vertices */
-        let v21 = Rc::new(Vertex::new(1.4, 1.4));
-        let v22 = Rc::new(Vertex::new(3.5, 3.5));
+#[cfg(test)]
+mod timings {
+    use super::*;
 
-        /* Err Vertices */
-        let v23 = Rc::new(Vertex::new(2.2, 2.2));
-        let v24 = Rc::new(Vertex::new(2.0, 2.5));
+    #[test]
+    fn records_phases() {
+        let v1 = Rc::new(Vertex::new(1.0, 1.0));
+        let v2 = Rc::new(Vertex::new(5.0, 1.0));
+        let v3 = Rc::new(Vertex::new(5.0, 5.0));
+        let v4 = Rc::new(Vertex::new(1.0, 5.0));
+        let boundary = Rc::new(Polyline::new_closed(vec![v1, v2, v3, v4]).unwrap());
 
-        let mut inner_vertices: HashSet<Rc<Vertex>> = HashSet::new();
-        inner_vertices.insert(Rc::clone(&v21));
-        inner_vertices.insert(Rc::clone(&v22));
-        inner_vertices.insert(Rc::clone(&v23));
-        inner_vertices.insert(Rc::clone(&v24));
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(2.0, 2.0)),
+                Rc::new(Vertex::new(3.0, 2.0)),
+                Rc::new(Vertex::new(3.0, 3.0)),
+                Rc::new(Vertex::new(2.0, 3.0)),
+            ])
+            .unwrap(),
+        );
 
         let mut triangulator = Triangulator::new(&boundary);
-        triangulator.insert_hole(&hole);
-        let result = triangulator.insert_vertices(&inner_vertices);
-        assert!(result.is_err());
+        triangulator.insert_hole(&hole).unwrap();
+        triangulator.try_triangulate().unwrap();
+        assert_eq!(triangulator.timings().count(Phase::BoundaryInclusion), 1);
+        assert_eq!(triangulator.timings().count(Phase::HoleInclusion), 1);
+        assert_eq!(triangulator.timings().count(Phase::SegmentRecovery), 0);
+        assert_eq!(triangulator.timings().count(Phase::VertexInsertion), 1);
+        assert_eq!(triangulator.timings().count(Phase::TriangleSplit), 0);
+
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(1.0),
+                quality_ratio: 1.0,
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .unwrap();
+        assert_eq!(triangulator.timings().count(Phase::Unencroachment), 1);
+        assert_eq!(triangulator.timings().count(Phase::TriangleSplit), 1);
 
-        if let Err(panic_vertices) = result {
-            assert!(panic_vertices.contains(&v23));
-            assert!(panic_vertices.contains(&v24));
-        }
+        /* triangulating again starts over */
+        triangulator.try_triangulate().unwrap();
+        assert_eq!(triangulator.timings().count(Phase::TriangleSplit), 0);
     }
-}
+} /* end - timings tests */
 
 #[cfg(test)]
-mod insert_segments {
+mod debug_observer {
     use super::*;
 
-    #[test]
-    fn sample_1() {
-        /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
-
-        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
-        boundary.push(Rc::clone(&v1));
-        boundary.push(Rc::clone(&v2));
-        boundary.push(Rc::clone(&v3));
-        boundary.push(Rc::clone(&v4));
-        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+    struct Recorder {
+        phases: Rc<RefCell<Vec<(Phase, usize)>>>,
+    }
 
-        /* Segments Vertices */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(3.0, 2.0));
-        let v13 = Rc::new(Vertex::new(3.0, 3.0));
-        let v14 = Rc::new(Vertex::new(2.0, 3.0));
-        let e1 = Rc::new(Edge::new(&v11, &v12));
-        let e2 = Rc::new(Edge::new(&v13, &v14));
+    impl DebugObserver for Recorder {
+        fn snapshot(
+            &mut self,
+            phase: Phase,
+            _: &Triangulation,
+            constraints: &HashSet<Rc<Edge>>,
+        ) {
+            self.phases.borrow_mut().push((phase, constraints.len()));
+        }
+    }
 
-        let mut segments: HashSet<Rc<Edge>> = HashSet::new();
-        segments.insert(Rc::clone(&e1));
-        segments.insert(Rc::clone(&e2));
+    fn square() -> Rc<Polyline> {
+        return Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(6.0, 0.0)),
+                Rc::new(Vertex::new(6.0, 6.0)),
+                Rc::new(Vertex::new(0.0, 6.0)),
+            ])
+            .unwrap(),
+        );
+    }
 
-        let mut triangulator = Triangulator::new(&boundary);
-        let result = triangulator.insert_segments(&segments);
-        assert!(result.is_ok());
+    fn record(triangulator: &mut Triangulator) -> Rc<RefCell<Vec<(Phase, usize)>>> {
+        let phases = Rc::new(RefCell::new(Vec::new()));
+        triangulator.debug_observer = Some(Box::new(Recorder {
+            phases: Rc::clone(&phases),
+        }));
+        return phases;
+    }
 
-        assert!(triangulator.segments.contains(&e1));
-        assert!(triangulator.segments.contains(&e2));
+    #[test]
+    fn snapshots_each_phase() {
+        let hole = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(2.0, 2.0)),
+                Rc::new(Vertex::new(3.0, 2.0)),
+                Rc::new(Vertex::new(3.0, 3.0)),
+                Rc::new(Vertex::new(2.0, 3.0)),
+            ])
+            .unwrap(),
+        );
+        let mut triangulator = Triangulator::new(&square());
+        triangulator.insert_hole(&hole).unwrap();
+        let phases = record(&mut triangulator);
+
+        triangulator.try_triangulate().unwrap();
+        assert_eq!(
+            *phases.borrow(),
+            vec![
+                (Phase::BoundaryInclusion, 4),
+                (Phase::HoleInclusion, 8),
+                (Phase::VertexInsertion, 8),
+            ]
+        );
+
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(2.0),
+                quality_ratio: 2.0,
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .unwrap();
+        let phases: Vec<Phase> = phases.borrow().iter().map(|(phase, _)| *phase).collect();
+        assert_eq!(&phases[3..], &[Phase::Unencroachment, Phase::TriangleSplit]);
     }
 
     #[test]
-    fn error_if_any_out_of_boundary() {
-        /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+    fn snapshots_each_segment() {
+        let segments: HashSet<Rc<Edge>> = vec![
+            Rc::new(Edge::new(
+                &Rc::new(Vertex::new(1.0, 1.0)),
+                &Rc::new(Vertex::new(2.0, 5.0)),
+            )),
+            Rc::new(Edge::new(
+                &Rc::new(Vertex::new(4.0, 1.0)),
+                &Rc::new(Vertex::new(5.0, 5.0)),
+            )),
+        ]
+        .into_iter()
+        .collect();
+        let mut triangulator = Triangulator::new(&square());
+        triangulator.insert_segments(&segments).unwrap();
+        let phases = record(&mut triangulator);
+
+        triangulator.try_triangulate().unwrap();
+        assert_eq!(
+            *phases.borrow(),
+            vec![
+                (Phase::BoundaryInclusion, 4),
+                (Phase::SegmentRecovery, 5),
+                (Phase::SegmentRecovery, 6),
+                (Phase::VertexInsertion, 6),
+            ]
+        );
+    }
+} /* end - debug_observer tests */
 
-        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
-        boundary.push(Rc::clone(&v1));
-        boundary.push(Rc::clone(&v2));
-        boundary.push(Rc::clone(&v3));
-        boundary.push(Rc::clone(&v4));
-        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+#[cfg(test)]
+mod remove_segment {
+    use super::*;
 
-        /* Segments Vertices */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(3.0, 2.0));
-        let v13 = Rc::new(Vertex::new(3.0, 3.0));
-        let v14 = Rc::new(Vertex::new(2.0, 5.0));
-        let e1 = Rc::new(Edge::new(&v11, &v12));
-        let e2 = Rc::new(Edge::new(&v13, &v14));
+    #[test]
+    fn removes_steiner_points() {
+        let boundary = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
+            ])
+            .unwrap(),
+        );
 
-        let mut segments: HashSet<Rc<Edge>> = HashSet::new();
-        segments.insert(Rc::clone(&e1));
-        segments.insert(Rc::clone(&e2));
+        let v1 = Rc::new(Vertex::new(1.0, 2.0));
+        let v2 = Rc::new(Vertex::new(3.0, 2.0));
+        let segment = Rc::new(Edge::new(&v1, &v2));
+        let segments: HashSet<Rc<Edge>> = vec![Rc::clone(&segment)].into_iter().collect();
 
-        let mut triangulator = Triangulator::new(&boundary);
-        let result = triangulator.insert_segments(&segments);
-        assert!(result.is_err());
+        /* encroaching vertex, splitting the segment at its midpoint */
+        let vertex = Rc::new(Vertex::new(2.0, 2.3));
+        let vertices: HashSet<Rc<Vertex>> = vec![Rc::clone(&vertex)].into_iter().collect();
 
-        if let Err(panic_segments) = result {
-            assert_eq!(panic_segments.len(), 1);
-            assert!(panic_segments.contains(&e2));
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_segments(&segments).unwrap();
+        triangulator.insert_vertices(&vertices).unwrap();
+        triangulator.try_triangulate().unwrap();
+        triangulator.try_conform().unwrap();
+
+        let midpoint = Rc::new(Vertex::new(2.0, 2.0));
+        assert!(triangulator.triangulation.borrow().vertices().contains(&midpoint));
+
+        assert!(!triangulator.remove_segment(&Edge::new(&v1, &vertex)));
+        assert!(triangulator.remove_segment(&segment));
+        assert!(triangulator.segments.is_empty());
+
+        let triangulation = triangulator.triangulation.borrow();
+        let vertices = triangulation.vertices();
+        assert!(!vertices.contains(&midpoint));
+        assert!(vertices.contains(&vertex));
+        assert!(vertices.contains(&v1));
+
+        /* Delaunay again */
+        for triangle in triangulation.solid_triangles() {
+            for other in vertices.iter() {
+                assert_ne!(triangle.encircles(other), Continence::Inside);
+            }
+        }
+        for edge in triangulation.adjacency.keys() {
+            assert!(triangulation.adjacency.contains_key(&edge.opposite()));
         }
     }
+} /* end - remove_segment tests */
 
-    #[test]
-    fn donot_remove_vertices_on_continence() {
-        /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
-
-        let mut boundary: Vec<Rc<Vertex>> = Vec::new();
-        boundary.push(Rc::clone(&v1));
-        boundary.push(Rc::clone(&v2));
-        boundary.push(Rc::clone(&v3));
-        boundary.push(Rc::clone(&v4));
-        let boundary = Rc::new(Polyline::new_closed(boundary).unwrap());
+#[cfg(test)]
+mod insert_polyline_constraint {
+    use super::*;
 
-        /* Segments */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(3.0, 2.0));
-        let v13 = Rc::new(Vertex::new(3.0, 3.0));
-        let v14 = Rc::new(Vertex::new(2.0, 3.0));
-        let e1 = Rc::new(Edge::new(&v11, &v12));
-        let e2 = Rc::new(Edge::new(&v13, &v14));
+    fn square() -> Rc<Polyline> {
+        return Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
+            ])
+            .unwrap(),
+        );
+    }
 
-        let mut segments: HashSet<Rc<Edge>> = HashSet::new();
-        segments.insert(Rc::clone(&e1));
-        segments.insert(Rc::clone(&e2));
+    #[test]
+    fn fault_across_the_domain() {
+        /* from the bottom edge to the right edge, through the interior */
+        let fault = Rc::new(
+            Polyline::new_opened(vec![
+                Rc::new(Vertex::new(1.0, 0.0)),
+                Rc::new(Vertex::new(2.0, 1.5)),
+                Rc::new(Vertex::new(4.0, 3.0)),
+            ])
+            .unwrap(),
+        );
 
-        /* Vertices */
-        let v21 = Rc::new(Vertex::new(2.5, 3.0));
-        let v22 = Rc::new(Vertex::new(2.0, 2.2));
+        let mut triangulator = Triangulator::new(&square());
+        triangulator.insert_polyline_constraint(&fault).unwrap();
+        assert_eq!(triangulator.boundary.vertices.len(), 6);
+        assert_eq!(triangulator.segments.len(), 2);
+
+        triangulator.try_triangulate().unwrap();
+        let triangulation = triangulator.triangulation.borrow();
+        for edge in fault.into_edges() {
+            assert!(
+                triangulation.adjacency.contains_key(&edge)
+                    && triangulation.adjacency.contains_key(&edge.opposite())
+            );
+        }
+    }
 
-        let mut vertices: HashSet<Rc<Vertex>> = HashSet::new();
-        vertices.insert(Rc::clone(&v21));
-        vertices.insert(Rc::clone(&v22));
+    #[test]
+    fn drops_edges_on_boundary() {
+        let crack = Rc::new(
+            Polyline::new_opened(vec![
+                Rc::new(Vertex::new(4.0, 1.0)),
+                Rc::new(Vertex::new(4.0, 2.0)),
+                Rc::new(Vertex::new(2.0, 2.0)),
+            ])
+            .unwrap(),
+        );
 
-        let mut triangulator = Triangulator::new(&boundary);
-        triangulator.insert_vertices(&vertices);
-        assert!(!triangulator.vertices.is_empty());
+        let mut triangulator = Triangulator::new(&square());
+        triangulator.insert_polyline_constraint(&crack).unwrap();
+        assert_eq!(triangulator.boundary.vertices.len(), 6);
+        assert_eq!(triangulator.segments.len(), 1);
+    }
 
-        let result = triangulator.insert_segments(&segments);
-        assert!(result.is_ok());
+    #[test]
+    fn error_on_crossing_the_boundary() {
+        let fault = Rc::new(
+            Polyline::new_opened(vec![
+                Rc::new(Vertex::new(2.0, 2.0)),
+                Rc::new(Vertex::new(5.0, 2.0)),
+            ])
+            .unwrap(),
+        );
 
-        assert!(!triangulator.vertices.contains(&v21));
-        assert!(triangulator.vertices.contains(&v22));
+        let mut triangulator = Triangulator::new(&square());
+        let result = triangulator.insert_polyline_constraint(&fault);
+        assert!(result.is_err());
+        assert!(triangulator.segments.is_empty());
+        assert_eq!(triangulator.boundary.vertices.len(), 4);
     }
+} /* end - insert_polyline_constraint tests */
 
-    #[test]
-    fn error_on_inside_hole() {
-        /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+#[cfg(test)]
+mod remove_hole {
+    use super::*;
 
+    #[test]
+    fn fills_hole() {
         let boundary = Rc::new(
             Polyline::new_closed(vec![
-                Rc::clone(&v1),
-                Rc::clone(&v2),
-                Rc::clone(&v3),
-                Rc::clone(&v4),
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(6.0, 0.0)),
+                Rc::new(Vertex::new(6.0, 6.0)),
+                Rc::new(Vertex::new(0.0, 6.0)),
             ])
             .unwrap(),
         );
 
-        /* Segments */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(3.0, 2.0));
-        let v13 = Rc::new(Vertex::new(3.0, 3.0));
-        let v14 = Rc::new(Vertex::new(2.0, 3.0));
-
+        /* non convex hole */
         let hole = Rc::new(
             Polyline::new_closed(vec![
-                Rc::clone(&v11),
-                Rc::clone(&v12),
-                Rc::clone(&v13),
-                Rc::clone(&v14),
+                Rc::new(Vertex::new(2.0, 2.0)),
+                Rc::new(Vertex::new(4.0, 2.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(3.0, 3.0)),
+                Rc::new(Vertex::new(2.0, 4.0)),
             ])
             .unwrap(),
         );
 
-        /* Vertices */
-        let v21 = Rc::new(Vertex::new(2.3, 2.3));
-        let v22 = Rc::new(Vertex::new(2.8, 2.8));
-        let v23 = Rc::new(Vertex::new(1.5, 1.5));
-        let v24 = Rc::new(Vertex::new(1.5, 3.5));
-        let e1 = Rc::new(Edge::new(&v21, &v22));
-        let e2 = Rc::new(Edge::new(&v23, &v24));
-        let segments_set: HashSet<Rc<Edge>> = vec![Rc::clone(&e1), Rc::clone(&e2)]
-            .iter()
-            .cloned()
-            .collect();
-
         let mut triangulator = Triangulator::new(&boundary);
-        triangulator.insert_hole(&hole);
-        let result = triangulator.insert_segments(&segments_set);
+        triangulator.insert_hole(&hole).unwrap();
+        triangulator.try_triangulate().unwrap();
+        assert!(triangulator.triangulation.borrow().locate(&Vertex::new(3.0, 2.5)).is_none());
+        assert_eq!(triangulator.triangulation.borrow().ghost_loops().len(), 2);
 
-        assert!(result.is_err());
-        if let Err(conflicting_edges) = result {
-            assert!(conflicting_edges.contains(&e1));
-            assert!(!conflicting_edges.contains(&e2));
+        assert!(triangulator.remove_hole(&hole));
+        assert!(!triangulator.remove_hole(&hole));
+        assert!(triangulator.holes.is_empty());
+        assert_eq!(triangulator.segments.len(), 5);
+
+        let triangulation = triangulator.triangulation.borrow();
+        assert!(triangulation.locate(&Vertex::new(3.0, 2.5)).is_some());
+
+        /* hole edges are kept, ghosts only surround the boundary */
+        let edges = triangulation.edges();
+        for edge in hole.into_edges().iter() {
+            assert!(edges.contains(edge) && edges.contains(&edge.opposite()));
         }
+        let ghosts = triangulation.ghost_triangles().count();
+        assert_eq!(ghosts, 4);
+        assert_eq!(triangulation.ghost_loops(), vec![Rc::new(Vertex::new_ghost())]);
 
-        assert!(!triangulator.segments.contains(&e1));
-        assert!(!triangulator.segments.contains(&e2));
+        let area: f64 = triangulation.triangles.iter().filter_map(|t| t.area()).sum();
+        assert!((area - 36.0).abs() < 1.0E-9);
     }
 
     #[test]
-    fn error_on_hole_intersection() {
-        /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
-
+    fn fills_split_hole() {
         let boundary = Rc::new(
             Polyline::new_closed(vec![
-                Rc::clone(&v1),
-                Rc::clone(&v2),
-                Rc::clone(&v3),
-                Rc::clone(&v4),
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(6.0, 0.0)),
+                Rc::new(Vertex::new(6.0, 6.0)),
+                Rc::new(Vertex::new(0.0, 6.0)),
             ])
             .unwrap(),
         );
-
-        /* Segments */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(3.0, 2.0));
-        let v13 = Rc::new(Vertex::new(3.0, 3.0));
-        let v14 = Rc::new(Vertex::new(2.0, 3.0));
-
         let hole = Rc::new(
             Polyline::new_closed(vec![
-                Rc::clone(&v11),
-                Rc::clone(&v12),
-                Rc::clone(&v13),
-                Rc::clone(&v14),
+                Rc::new(Vertex::new(2.1, 1.9)),
+                Rc::new(Vertex::new(4.2, 2.3)),
+                Rc::new(Vertex::new(2.9, 4.1)),
             ])
             .unwrap(),
         );
 
-        /* Vertices */
-        let v21 = Rc::new(Vertex::new(2.3, 2.3));
-        let v22 = Rc::new(Vertex::new(3.5, 2.3));
-        let v23 = Rc::new(Vertex::new(1.5, 1.5));
-        let v24 = Rc::new(Vertex::new(1.5, 3.5));
-        let e1 = Rc::new(Edge::new(&v21, &v22));
-        let e2 = Rc::new(Edge::new(&v23, &v24));
-        let segments_set: HashSet<Rc<Edge>> = vec![Rc::clone(&e1), Rc::clone(&e2)]
+        /* refinement splits the hole edges at Steiner points */
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_hole(&hole).unwrap();
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(0.2),
+                quality_ratio: 2.0_f64.sqrt(),
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .unwrap();
+        let triangulation = triangulator.triangulation.borrow();
+        let steiner_points = triangulation
+            .vertices()
             .iter()
-            .cloned()
-            .collect();
+            .filter(|v| hole.contains(v) == Some(Continence::Boundary))
+            .count();
+        assert!(steiner_points > 3);
+        drop(triangulation);
+
+        assert!(triangulator.remove_hole(&hole));
+        assert!(triangulator.validate().is_ok());
+        let triangulation = triangulator.triangulation.borrow();
+        assert_eq!(triangulation.ghost_loops().len(), 1);
+        let area: f64 = triangulation.triangles.iter().filter_map(|t| t.area()).sum();
+        assert!((area - 36.0).abs() < 1.0E-9);
+    }
+
+    #[test]
+    fn clips_collinear_vertices() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(2.0, 0.0));
+        let v4 = Rc::new(Vertex::new(1.0, 1.0));
+        let v5 = Rc::new(Vertex::new(0.5, 0.5));
+
+        /* triangle with two split edges */
+        let polygon = vec![&v1, &v2, &v3, &v4, &v5].into_iter().cloned().collect();
+        let triangles = clip_ears(polygon).unwrap();
+        assert_eq!(triangles.len(), 3);
+        for triangle in triangles.iter() {
+            assert_eq!(
+                orientation(&triangle.v1, &triangle.v2, &triangle.v3),
+                Orientation::Counterclockwise
+            );
+        }
+        let area: f64 = triangles.iter().filter_map(|t| t.area()).sum();
+        assert!((area - 1.0).abs() < 1.0E-12);
+
+        /* no proper ear */
+        assert!(clip_ears(vec![&v1, &v2, &v3].into_iter().cloned().collect()).is_none());
+        assert!(clip_ears(vec![&v1, &v3, &v2, &v4].into_iter().cloned().collect()).is_none());
+    }
+} /* end - remove_hole tests */
+
+#[cfg(test)]
+mod hole_seeds {
+    use super::*;
+
+    fn polygon(points: Vec<(f64, f64)>) -> Rc<Polyline> {
+        return Rc::new(
+            Polyline::new_closed(
+                points
+                    .into_iter()
+                    .map(|(x, y)| Rc::new(Vertex::new(x, y)))
+                    .collect(),
+            )
+            .unwrap(),
+        );
+    }
 
+    /* triangular wall, of area 2.11, inside a square */
+    fn wall() -> Rc<Polyline> {
+        return polygon(vec![(2.1, 1.9), (4.2, 2.3), (3.1, 4.1)]);
+    }
+
+    fn walled() -> Triangulator {
+        let boundary = polygon(vec![(0.0, 0.0), (6.0, 0.0), (6.0, 6.0), (0.0, 6.0)]);
         let mut triangulator = Triangulator::new(&boundary);
-        triangulator.insert_hole(&hole);
-        assert!(triangulator.holes.contains(&hole));
+        let wall = wall();
+        triangulator.insert_segments(&wall.into_edges().into_iter().collect()).unwrap();
+        let vertices = vec![Rc::new(Vertex::new(3.0, 2.8)), Rc::new(Vertex::new(1.0, 5.0))];
+        triangulator.insert_vertices(&vertices.into_iter().collect()).unwrap();
+        return triangulator;
+    }
 
-        let result = triangulator.insert_segments(&segments_set);
-        assert!(result.is_err());
+    #[test]
+    fn carves_walled_region() {
+        let mut triangulator = walled();
+        triangulator.insert_hole_seeds(&vec![Rc::new(Vertex::new(3.0, 2.5))]).unwrap();
+        triangulator.try_triangulate().unwrap();
+
+        /* the wall became a hole, and what it held is gone */
+        assert_eq!(triangulator.holes.len(), 1);
+        assert!(triangulator.segments.is_empty());
+        assert_eq!(triangulator.vertices.len(), 1);
+        assert!(triangulator.validate().is_ok());
+        {
+            let triangulation = triangulator.triangulation.borrow();
+            assert_eq!(triangulation.ghost_loops().len(), 2);
+            assert!(triangulation.locate(&Vertex::new(3.0, 3.0)).is_none());
+            let area: f64 = triangulation.triangles.iter().filter_map(|t| t.area()).sum();
+            assert!((area - 33.89).abs() < 1.0E-9);
+        }
 
-        if let Err(conflicting_edges) = result {
-            assert!(conflicting_edges.contains(&e1));
-            assert!(!conflicting_edges.contains(&e2));
+        /* triangulating again keeps the carved hole */
+        triangulator.try_triangulate().unwrap();
+        assert_eq!(triangulator.holes.len(), 1);
+        assert_eq!(triangulator.triangulation.borrow().ghost_loops().len(), 2);
+    }
+
+    #[test]
+    fn invalid_seeds() {
+        let mut triangulator = walled();
+        assert!(triangulator.insert_hole_seeds(&vec![Rc::new(Vertex::new(7.0, 1.0))]).is_err());
+        assert!(triangulator.hole_seeds.is_empty());
+
+        /* region of the seed reaches the boundary */
+        triangulator.insert_hole_seeds(&vec![Rc::new(Vertex::new(1.0, 1.0))]).unwrap();
+        assert!(triangulator.try_triangulate().is_err());
+    }
+
+    #[test]
+    fn seeded_regions() {
+        let mut triangulator = walled();
+        triangulator.try_triangulate().unwrap();
+        let seeds = vec![
+            Rc::new(Vertex::new(1.0, 1.0)),
+            Rc::new(Vertex::new(3.0, 2.5)),
+            Rc::new(Vertex::new(5.0, 5.0)),
+        ];
+        let regions = triangulator.seeded_regions(&seeds);
+
+        /* the third seed shares the region of the first one */
+        let triangulation = triangulator.triangulation.borrow();
+        assert_eq!(regions.len(), triangulation.solid_triangles().count());
+        for (triangle, index) in regions.iter() {
+            let is_walled = wall().contains(&triangle.center()) == Some(Continence::Inside);
+            assert_eq!(*index, if is_walled { 1 } else { 0 });
+        }
+    }
+} /* end - hole_seeds tests */
+
+#[cfg(test)]
+mod detect_loops {
+    use super::*;
+
+    fn polygon(points: Vec<(f64, f64)>) -> Polyline {
+        return Polyline::new_closed(
+            points
+                .into_iter()
+                .map(|(x, y)| Rc::new(Vertex::new(x, y)))
+                .collect(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn walled_regions() {
+        let boundary = Rc::new(polygon(vec![(0.0, 0.0), (6.0, 0.0), (6.0, 6.0), (0.0, 6.0)]));
+        let mut triangulator = Triangulator::new(&boundary);
+
+        /* two closed walls and an open crack */
+        let pond = polygon(vec![(1.1, 0.9), (2.2, 1.3), (1.6, 2.1)]);
+        let field = polygon(vec![(3.1, 3.9), (4.7, 3.3), (4.2, 5.1)]);
+        let crack = Rc::new(Edge::new(
+            &Rc::new(Vertex::new(4.1, 1.2)),
+            &Rc::new(Vertex::new(5.2, 2.3)),
+        ));
+        let segments: HashSet<Rc<Edge>> = pond
+            .into_edges()
+            .into_iter()
+            .chain(field.into_edges().into_iter())
+            .chain(vec![crack].into_iter())
+            .collect();
+        triangulator.insert_segments(&segments).unwrap();
+        triangulator.try_triangulate().unwrap();
+
+        let loops = triangulator.detect_loops();
+        assert_eq!(loops.len(), 2);
+        assert_eq!(loops[0].polyline.area(), pond.area());
+        assert_eq!(loops[1].polyline.vertices.len(), 3);
+        for constraint_loop in loops.iter() {
+            let seed_continence = constraint_loop.polyline.contains(&constraint_loop.seed);
+            assert_eq!(seed_continence, Some(Continence::Inside));
         }
 
-        assert!(!triangulator.segments.contains(&e1));
-        assert!(!triangulator.segments.contains(&e2));
+        /* the field is tagged by its seed, the pond carved */
+        let regions = triangulator.seeded_regions(&vec![Rc::clone(&loops[1].seed)]);
+        assert_eq!(regions.keys().cloned().collect::<HashSet<_>>(), loops[1].triangles);
+
+        triangulator.carve_loop(&loops[0]).unwrap();
+        assert_eq!(triangulator.holes.len(), 1);
+        assert_eq!(triangulator.segments.len(), 4);
+        assert!(triangulator.validate().is_ok());
+        assert!(triangulator.carve_loop(&loops[0]).is_err());
+
+        let loops = triangulator.detect_loops();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].polyline.area(), field.area());
     }
+} /* end - detect_loops tests */
 
-    #[test]
-    fn error_on_hole_intersection_with_vertices_end_outside() {
-        /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+#[cfg(test)]
+mod constraint_chains {
+    use super::*;
 
+    #[test]
+    fn refined_chains() {
         let boundary = Rc::new(
             Polyline::new_closed(vec![
-                Rc::clone(&v1),
-                Rc::clone(&v2),
-                Rc::clone(&v3),
-                Rc::clone(&v4),
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(0.0, 4.0)),
             ])
             .unwrap(),
         );
+        let v1 = Rc::new(Vertex::new(1.0, 2.0));
+        let v2 = Rc::new(Vertex::new(3.0, 2.0));
+        let segments: HashSet<Rc<Edge>> = vec![Rc::new(Edge::new(&v1, &v2))].into_iter().collect();
 
-        /* Hole */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(3.0, 2.0));
-        let v13 = Rc::new(Vertex::new(3.0, 3.0));
-        let v14 = Rc::new(Vertex::new(2.0, 3.0));
+        let mut triangulator = Triangulator::new(&boundary);
+        triangulator.insert_segments(&segments).unwrap();
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams {
+                max_area: Some(0.25),
+                quality_ratio: 2.0_f64.sqrt(),
+                max_edge_length: None,
+                regions: Vec::new(),
+            })
+            .unwrap();
+
+        let chains = triangulator.constraint_chains();
+        assert_eq!(chains.len(), 2);
+        assert_eq!(chains[0].kind, ChainKind::Boundary);
+        assert_eq!(chains[1].kind, ChainKind::Segment);
+        assert!(chains[0].polyline.vertices.len() > 4);
+
+        /* sub-edges are triangulation edges */
+        let edges = triangulator.triangulation.borrow().edges().to_set();
+        for chain in chains.iter() {
+            for edge in chain.edges().iter() {
+                assert!(edges.contains(edge) || edges.contains(&edge.opposite()));
+            }
+        }
+    }
+} /* end - constraint_chains tests */
 
-        let hole = Rc::new(
+#[cfg(test)]
+mod checkpoint_resume {
+    use super::*;
+
+    #[test]
+    fn resumes_refinement() {
+        let boundary = Rc::new(
             Polyline::new_closed(vec![
-                Rc::clone(&v11),
-                Rc::clone(&v12),
-                Rc::clone(&v13),
-                Rc::clone(&v14),
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 3.0)),
+                Rc::new(Vertex::new(0.0, 3.0)),
             ])
             .unwrap(),
         );
-
-        /* Segments */
-        let v21 = Rc::new(Vertex::new(1.5, 2.5));
-        let v22 = Rc::new(Vertex::new(3.5, 2.5));
-        let v23 = Rc::new(Vertex::new(3.5, 3.5));
-        let v24 = Rc::new(Vertex::new(1.5, 3.5));
-        let e1 = Rc::new(Edge::new(&v21, &v22));
-        let e2 = Rc::new(Edge::new(&v23, &v24));
-        let segments_set: HashSet<Rc<Edge>> = vec![Rc::clone(&e1), Rc::clone(&e2)]
-            .iter()
-            .cloned()
-            .collect();
+        let params = RefineParams {
+            max_area: Some(0.05),
+            quality_ratio: 2.0_f64.sqrt(),
+            max_edge_length: None,
+            regions: Vec::new(),
+        };
 
         let mut triangulator = Triangulator::new(&boundary);
-        triangulator.insert_hole(&hole);
-        let result = triangulator.insert_segments(&segments_set);
-
-        assert!(result.is_err());
-        if let Err(conflicting_edges) = result {
-            assert!(conflicting_edges.contains(&e1));
-            assert!(!conflicting_edges.contains(&e2));
-        }
+        triangulator.try_triangulate().unwrap();
+        let mut buffer: Vec<u8> = Vec::new();
+        triangulator
+            .try_refine_observed(params.clone(), &mut |triangulation, splits| {
+                if splits == 5 {
+                    checkpoint::write(&mut buffer, triangulation, Some(&params)).unwrap();
+                }
+            })
+            .unwrap();
+        let mut saved: Vec<u8> = Vec::new();
+        triangulator.save_checkpoint(&mut saved).unwrap();
+        let saved = checkpoint::read(&mut saved.as_slice()).unwrap();
+        let refined = triangulator.triangulation.borrow();
+        assert_eq!(
+            saved.pending.len(),
+            checkpoint::pending_triangles(&refined, &params).len()
+        );
 
-        assert!(!triangulator.segments.contains(&e1));
-        assert!(!triangulator.segments.contains(&e2));
+        let mut resumed = Triangulator::new(&boundary);
+        let resumed_params = resumed.resume(&mut buffer.as_slice()).unwrap().unwrap();
+        assert_eq!(resumed_params.max_area, params.max_area);
+        let pending = checkpoint::pending_triangles(&resumed.triangulation.borrow(), &params);
+        assert!(!pending.is_empty());
+        assert!(resumed.validate().is_ok());
+
+        resumed.try_refine(resumed_params).unwrap();
+        let triangulation = resumed.triangulation.borrow();
+        assert!(checkpoint::pending_triangles(&triangulation, &params).len() < pending.len());
+        let area: f64 = triangulation
+            .triangles
+            .iter()
+            .filter_map(|t| t.area())
+            .sum();
+        assert!((area - 12.0).abs() < 1.0E-9);
     }
-}
+} /* end - checkpoint_resume tests */
 
 #[cfg(test)]
-mod triangulate {
+mod progress {
     use super::*;
 
-    #[test]
-    fn triangulates_boundary_sample() {
-        /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+    /* records every report, cancelling the token after a count of reports */
+    struct Recorder {
+        reports: Rc<RefCell<Vec<(Phase, usize, usize)>>>,
+        cancel_after: Option<(usize, CancellationToken)>,
+    }
+
+    impl ProgressObserver for Recorder {
+        fn progress(&mut self, phase: Phase, processed: usize, total: usize) {
+            self.reports.borrow_mut().push((phase, processed, total));
+            if let Some((count, token)) = &self.cancel_after {
+                if self.reports.borrow().len() >= *count {
+                    token.cancel();
+                }
+            }
+        }
+    }
 
+    fn sample_triangulator() -> Triangulator {
         let boundary = Rc::new(
             Polyline::new_closed(vec![
-                Rc::clone(&v1),
-                Rc::clone(&v2),
-                Rc::clone(&v3),
-                Rc::clone(&v4),
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 0.0)),
+                Rc::new(Vertex::new(4.0, 3.0)),
+                Rc::new(Vertex::new(0.0, 3.0)),
             ])
             .unwrap(),
         );
-
         let mut triangulator = Triangulator::new(&boundary);
-        triangulator.triangulate();
+        triangulator
+            .insert_vertices(
+                &vec![
+                    Rc::new(Vertex::new(1.0, 1.0)),
+                    Rc::new(Vertex::new(2.0, 2.0)),
+                    Rc::new(Vertex::new(3.0, 1.0)),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap();
+        return triangulator;
+    }
 
-        for edge in boundary.into_edges().iter() {
-            assert!(triangulator.triangulation.borrow().edges().contains(edge));
+    fn params() -> RefineParams {
+        return RefineParams {
+            max_area: Some(0.05),
+            quality_ratio: 2.0_f64.sqrt(),
+            max_edge_length: None,
+            regions: Vec::new(),
+        };
+    }
+
+    #[test]
+    fn reports_phases() {
+        let reports = Rc::new(RefCell::new(Vec::new()));
+        let mut triangulator = sample_triangulator();
+        triangulator.progress_observer = Some(Box::new(Recorder {
+            reports: Rc::clone(&reports),
+            cancel_after: None,
+        }));
+        triangulator.try_triangulate().unwrap();
+        triangulator.try_refine(params()).unwrap();
+
+        let reports = reports.borrow();
+        let insertions: Vec<&(Phase, usize, usize)> = reports
+            .iter()
+            .filter(|(phase, _, _)| *phase == Phase::VertexInsertion)
+            .collect();
+        assert_eq!(insertions.first(), Some(&&(Phase::VertexInsertion, 0, 3)));
+        assert!(reports
+            .iter()
+            .any(|(phase, _, _)| *phase == Phase::TriangleSplit));
+        assert!(reports
+            .iter()
+            .all(|(_, processed, total)| processed <= total));
+    }
+
+    #[test]
+    fn cancels_refinement() {
+        let token = CancellationToken::new();
+        let reports = Rc::new(RefCell::new(Vec::new()));
+        let mut triangulator = sample_triangulator();
+        triangulator.try_triangulate().unwrap();
+        triangulator.cancellation = Some(token.clone());
+        triangulator.progress_observer = Some(Box::new(Recorder {
+            reports: Rc::clone(&reports),
+            cancel_after: Some((10, token)),
+        }));
+
+        match triangulator.try_refine(params()) {
+            Err(TriangulationError::Cancelled) => (),
+            _ => panic!("refinement was not cancelled"),
         }
+        assert_eq!(reports.borrow().len(), 10);
 
-        for triangle in triangulator
-            .triangulation
-            .borrow()
+        /* the triangulation is left valid */
+        let triangulation = triangulator.triangulation.borrow();
+        let area: f64 = triangulation
             .triangles
             .iter()
-            .filter(|t| !t.is_ghost())
-        {
-            assert_eq!(
-                boundary.contains(&triangle.center()),
-                Some(Continence::Inside)
-            );
+            .filter_map(|t| t.area())
+            .sum();
+        assert!((area - 12.0).abs() < 1.0E-9);
+        assert!(!checkpoint::pending_triangles(&triangulation, &params()).is_empty());
+    }
+
+    #[test]
+    fn reports_non_terminating_refinement() {
+        let mut triangulator = sample_triangulator();
+        triangulator.try_triangulate().unwrap();
+
+        /* no triangulation has every angle above 60 degrees */
+        let impossible = RefineParams {
+            max_area: None,
+            quality_ratio: 0.55,
+            max_edge_length: None,
+            regions: Vec::new(),
+        };
+        match triangulator.try_refine(impossible) {
+            Err(TriangulationError::NonTerminatingRefinement {
+                triangles,
+                edge_length_floor,
+            }) => {
+                assert!(!triangles.is_empty());
+                assert!(edge_length_floor > 0.0);
+                for triangle in triangles.iter() {
+                    let (e1, e2, e3) = triangle.inner_edges();
+                    assert!(vec![e1, e2, e3]
+                        .iter()
+                        .any(|e| e.length() < edge_length_floor));
+                }
+            }
+            _ => panic!("non terminating refinement was not reported"),
         }
+
+        /* the triangulation is left valid */
+        let triangulation = triangulator.triangulation.borrow();
+        let area: f64 = triangulation
+            .triangles
+            .iter()
+            .filter_map(|t| t.area())
+            .sum();
+        assert!((area - 12.0).abs() < 1.0E-9);
     }
 
     #[test]
-    fn triangulates_hole() {
-        /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+    fn cancels_triangulation() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let mut triangulator = sample_triangulator();
+        triangulator.cancellation = Some(token);
+
+        match triangulator.try_triangulate() {
+            Err(TriangulationError::Cancelled) => (),
+            _ => panic!("triangulation was not cancelled"),
+        }
+    }
+} /* end - progress tests */
+
+#[cfg(test)]
+mod insert_vertices_iter {
+    use super::*;
 
+    fn square_with_hole() -> Triangulator {
         let boundary = Rc::new(
             Polyline::new_closed(vec![
-                Rc::clone(&v1),
-                Rc::clone(&v2),
-                Rc::clone(&v3),
-                Rc::clone(&v4),
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(10.0, 0.0)),
+                Rc::new(Vertex::new(10.0, 10.0)),
+                Rc::new(Vertex::new(0.0, 10.0)),
             ])
             .unwrap(),
         );
-
-        /* hole */
-        let v11 = Rc::new(Vertex::new(2.0, 2.0));
-        let v12 = Rc::new(Vertex::new(3.0, 2.0));
-        let v13 = Rc::new(Vertex::new(3.0, 3.0));
-        let v14 = Rc::new(Vertex::new(2.0, 3.0));
-
         let hole = Rc::new(
             Polyline::new_closed(vec![
-                Rc::clone(&v11),
-                Rc::clone(&v12),
-                Rc::clone(&v13),
-                Rc::clone(&v14),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(6.0, 4.0)),
+                Rc::new(Vertex::new(6.0, 6.0)),
+                Rc::new(Vertex::new(4.0, 6.0)),
             ])
             .unwrap(),
         );
         let mut triangulator = Triangulator::new(&boundary);
-        if triangulator.insert_hole(&hole).is_err() {
-            panic!("Expected not err");
-        }
-        triangulator.triangulate();
+        triangulator.insert_hole(&hole).unwrap();
+        return triangulator;
+    }
 
-        for edge in hole.into_edges().iter().chain(boundary.into_edges().iter()) {
-            assert!(triangulator.triangulation.borrow().edges().contains(edge));
-        }
+    #[test]
+    fn inserts_in_chunks() {
+        let points: Vec<(f64, f64)> = (0..900)
+            .map(|index| {
+                /* jittered grid, avoiding cocircular vertices */
+                let jitter = ((index * 7919) % 97) as f64 * 1.0E-3;
+                return (
+                    0.15 + (index % 30) as f64 * 0.33 + jitter,
+                    0.1 + (index / 30) as f64 * 0.33 + 0.5 * jitter,
+                );
+            })
+            .filter(|(x, y)| !(*x > 3.9 && *x < 6.1 && *y > 3.9 && *y < 6.1))
+            .collect();
 
-        for triangle in triangulator
-            .triangulation
-            .borrow()
+        let mut triangulator = square_with_hole();
+        let inserted = triangulator
+            .insert_vertices_chunked(points.iter().cloned(), 100)
+            .unwrap();
+        assert_eq!(inserted, points.len());
+
+        let triangulation = triangulator.triangulation.borrow();
+        let vertices = triangulation.vertices();
+        assert_eq!(vertices.len(), points.len() + 8);
+
+        let area: f64 = triangulation
             .triangles
             .iter()
-            .filter(|t| !t.is_ghost())
-        {
-            assert_eq!(hole.contains(&triangle.center()), Some(Continence::Outside));
-            assert_eq!(
-                boundary.contains(&triangle.center()),
-                Some(Continence::Inside)
-            );
+            .filter_map(|t| t.area())
+            .sum();
+        assert!((area - 96.0).abs() < 1.0E-9);
+
+        /* delaunay, away from the constraints */
+        for triangle in triangulation.solid_triangles() {
+            let center = triangle.circumcenter().unwrap();
+            let radius = distance(&center, &triangle.v1);
+            let (x, y) = (center.x, center.y);
+            let is_clear = x - radius > 0.0
+                && x + radius < 10.0
+                && y - radius > 0.0
+                && y + radius < 10.0
+                && (x + radius < 4.0 || x - radius > 6.0 || y + radius < 4.0 || y - radius > 6.0);
+            if !is_clear {
+                continue;
+            }
+            for vertex in vertices.iter().filter(|v| distance(v, &center) < radius) {
+                assert_ne!(triangle.encircles(vertex), Continence::Inside);
+            }
         }
     }
 
     #[test]
-    fn includes_segment_constraints() {
-        /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
+    fn outside_vertices() {
+        let mut triangulator = square_with_hole();
+        let points = vec![(1.0, 1.0), (5.0, 5.0), (11.0, 1.0)];
 
-        let boundary = Rc::new(
-            Polyline::new_closed(vec![
-                Rc::clone(&v1),
-                Rc::clone(&v2),
-                Rc::clone(&v3),
-                Rc::clone(&v4),
-            ])
-            .unwrap(),
-        );
+        match triangulator.insert_vertices_iter(points.into_iter()) {
+            Err(TriangulationError::OutsideBoundary { vertices, .. }) => {
+                assert_eq!(vertices.len(), 2);
+            }
+            _ => panic!("outside vertices were inserted"),
+        }
+    }
+} /* end - insert_vertices_iter tests */
 
-        /* Segments */
-        let v21 = Rc::new(Vertex::new(1.5, 2.5));
-        let v22 = Rc::new(Vertex::new(3.5, 2.5));
-        let v23 = Rc::new(Vertex::new(3.5, 3.5));
-        let v24 = Rc::new(Vertex::new(1.5, 3.5));
+#[cfg(test)]
+mod vertex_attributes {
+    use super::*;
+    use std::collections::BTreeMap;
 
-        let e1 = Rc::new(Edge::new(&v21, &v22));
-        let e2 = Rc::new(Edge::new(&v23, &v24));
-        let segments_set: HashSet<Rc<Edge>> = vec![Rc::clone(&e1), Rc::clone(&e2)]
+    #[test]
+    fn survive_refinement() {
+        let corners = vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)];
+        let vertices: Vec<Rc<Vertex>> = corners
             .iter()
-            .cloned()
+            .map(|(x, y)| Rc::new(Vertex::new(*x, *y)))
             .collect();
+        let boundary = Rc::new(Polyline::new_closed(vertices.clone()).unwrap());
 
         let mut triangulator = Triangulator::new(&boundary);
-        if triangulator.insert_segments(&segments_set).is_err() {
-            panic!("Expected not err");
+        for (index, vertex) in vertices.iter().enumerate() {
+            let mut metadata = BTreeMap::new();
+            metadata.insert(String::from("elevation"), vertex.x + vertex.y);
+            triangulator.attributes.insert(
+                vertex,
+                VertexAttributes::new(Some(format!("corner {}", index)), metadata),
+            );
         }
-
-        triangulator.triangulate();
-
-        for constrained_edge in segments_set.iter().chain(boundary.into_edges().iter()) {
-            assert!(Edge::decompose(
-                &triangulator.triangulation.borrow().edges(),
-                constrained_edge
-            )
-            .is_some());
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams::max_area(0.5))
+            .unwrap();
+
+        let attributes = triangulator.vertex_attributes();
+        assert_eq!(attributes.len(), triangulator.triangulation.borrow().vertices().len());
+        assert!(attributes.len() > 4);
+
+        for (vertex, attributes) in attributes.iter() {
+            match vertices.iter().position(|v| v == vertex) {
+                Some(index) => assert_eq!(attributes.id, Some(format!("corner {}", index))),
+                None => assert_eq!(attributes.id, None),
+            }
+            let elevation = attributes.metadata["elevation"];
+            assert!(elevation >= 0.0 && elevation <= 8.0);
         }
     }
+} /* end - vertex_attributes tests */
 
-    #[test]
-    fn includes_vertex_constraints() {
-        /* Squared boundary */
-        let v1 = Rc::new(Vertex::new(1.0, 1.0));
-        let v2 = Rc::new(Vertex::new(4.0, 1.0));
-        let v3 = Rc::new(Vertex::new(4.0, 4.0));
-        let v4 = Rc::new(Vertex::new(1.0, 4.0));
-
-        let boundary = Rc::new(
-            Polyline::new_closed(vec![
-                Rc::clone(&v1),
-                Rc::clone(&v2),
-                Rc::clone(&v3),
-                Rc::clone(&v4),
-            ])
-            .unwrap(),
-        );
-
-        /* Vertices */
-        let v21 = Rc::new(Vertex::new(1.5, 2.5));
-        let v22 = Rc::new(Vertex::new(3.5, 2.5));
-        let v23 = Rc::new(Vertex::new(3.5, 3.5));
-        let v24 = Rc::new(Vertex::new(1.5, 3.5));
-
-        let vertices_set: HashSet<Rc<Vertex>> = vec![
-            Rc::clone(&v21),
-            Rc::clone(&v22),
-            Rc::clone(&v23),
-            Rc::clone(&v24),
-        ]
-        .iter()
-        .cloned()
-        .collect();
-
-        let mut triangulator = Triangulator::new(&boundary);
-        if triangulator.insert_vertices(&vertices_set).is_err() {
-            panic!("Expected not err");
-        }
-
-        triangulator.triangulate();
+#[cfg(test)]
+mod voronoi_cells {
+    use super::*;
 
-        for constrained_vertex in vertices_set.iter() {
-            assert!(triangulator
-                .triangulation
-                .borrow()
-                .vertices()
-                .contains(constrained_vertex));
-        }
+    #[test]
+    fn tile_the_domain() {
+        let square = |x1: f64, y1: f64, x2: f64, y2: f64| {
+            return Rc::new(
+                Polyline::new_closed(vec![
+                    Rc::new(Vertex::new(x1, y1)),
+                    Rc::new(Vertex::new(x2, y1)),
+                    Rc::new(Vertex::new(x2, y2)),
+                    Rc::new(Vertex::new(x1, y2)),
+                ])
+                .unwrap(),
+            );
+        };
+
+        let mut triangulator = Triangulator::new(&square(0.0, 0.0, 4.0, 4.0));
+        triangulator.insert_hole(&square(1.0, 1.0, 2.0, 2.0)).unwrap();
+        triangulator.try_triangulate().unwrap();
+        triangulator
+            .try_refine(RefineParams::max_area(0.25))
+            .unwrap();
+
+        let cells = triangulator.voronoi_cells();
+        assert_eq!(cells.len(), triangulator.triangulation.borrow().vertices().len());
+
+        /* cells cover the domain out of the hole, even at obtuse triangles */
+        let total: f64 = cells.iter().map(|cell| cell.area).sum();
+        assert!((total - 15.0).abs() < 1.0E-9);
+        assert!(cells.iter().all(|cell| cell.region == 0 && !cell.neighbors.is_empty()));
     }
-}
+} /* end - voronoi_cells tests */
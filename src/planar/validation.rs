@@ -0,0 +1,247 @@
+use crate::elements::{edge::*, triangle::*, vertex::*};
+use crate::planar::triangulation::*;
+use crate::properties::{continence::*, orientation::*};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+/**
+ * Broken invariants of a triangulation, as found by Triangulation::validate.
+ *  - MissingAdjacency: triangle edge not mapped to the triangle.
+ *  - StaleAdjacency: edge mapped to a triangle that is not in the
+ * triangulation, or that does not hold the edge.
+ *  - UnmatchedEdge: edge without a triangle at its other side.
+ *  - InvertedTriangle: solid triangle not in counterclockwise order.
+ *  - NonDelaunayEdge: unconstrained edge whose other side vertex lies inside
+ * the circumcircle of its triangle.
+ *  - OpenGhostRing: vertex where the solid edges of the ghost triangles of
+ * a loop do not close in rings, entering as many times as leaving.
+ *  - MissingConstraint: constraint that is not a triangulation edge, nor a
+ * chain of them.
+ */
+#[derive(Debug)]
+pub enum InvariantViolation {
+    MissingAdjacency {
+        edge: Rc<Edge>,
+        triangle: Rc<Triangle>,
+    },
+    StaleAdjacency {
+        edge: Rc<Edge>,
+        triangle: Rc<Triangle>,
+    },
+    UnmatchedEdge(Rc<Edge>),
+    InvertedTriangle(Rc<Triangle>),
+    NonDelaunayEdge {
+        edge: Rc<Edge>,
+        vertex: Rc<Vertex>,
+    },
+    OpenGhostRing(Rc<Vertex>),
+    MissingConstraint(Rc<Edge>),
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::MissingAdjacency { edge, triangle } => {
+                return write!(f, "Edge {} of {} missing from adjacency", edge, triangle);
+            }
+            InvariantViolation::StaleAdjacency { edge, triangle } => {
+                return write!(f, "Edge {} mapped to stale triangle {}", edge, triangle);
+            }
+            InvariantViolation::UnmatchedEdge(edge) => {
+                return write!(f, "Edge {} without opposite triangle", edge);
+            }
+            InvariantViolation::InvertedTriangle(triangle) => {
+                return write!(f, "Triangle {} not counterclockwise", triangle);
+            }
+            InvariantViolation::NonDelaunayEdge { edge, vertex } => {
+                return write!(f, "Edge {} not Delaunay, encircling {}", edge, vertex);
+            }
+            InvariantViolation::OpenGhostRing(vertex) => {
+                return write!(f, "Ghost triangles not closed around {}", vertex);
+            }
+            InvariantViolation::MissingConstraint(edge) => {
+                return write!(f, "Constraint {} not in the triangulation", edge);
+            }
+        }
+    }
+}
+
+/**
+ * Checks the adjacency, the orientation of the solid triangles, the
+ * Delaunay property of unconstrained edges, the rings of ghost triangles
+ * and the presence of constrained edges. Returns every violation found.
+ */
+pub fn validate(triangulation: &Triangulation) -> Vec<InvariantViolation> {
+    let mut violations: Vec<InvariantViolation> = Vec::new();
+
+    /* adjacency */
+    for triangle in triangulation.triangles.iter() {
+        let (e1, e2, e3) = triangle.inner_edges();
+        for edge in vec![e1, e2, e3] {
+            match triangulation.adjacency.get(&edge) {
+                Some(adjacent) if adjacent == triangle => {}
+                _ => violations.push(InvariantViolation::MissingAdjacency {
+                    edge,
+                    triangle: Rc::clone(triangle),
+                }),
+            }
+        }
+    }
+    for (edge, triangle) in triangulation.adjacency.iter() {
+        if !triangulation.triangles.contains(triangle) || triangle.opposite_vertex(&edge).is_none()
+        {
+            violations.push(InvariantViolation::StaleAdjacency {
+                edge: Rc::clone(&edge),
+                triangle: Rc::clone(triangle),
+            });
+        }
+        if !triangulation.adjacency.contains_key(&edge.opposite()) {
+            violations.push(InvariantViolation::UnmatchedEdge(Rc::clone(&edge)));
+        }
+    }
+
+    /* orientation and Delaunay property */
+    for triangle in triangulation.solid_triangles() {
+        if orientation(&triangle.v1, &triangle.v2, &triangle.v3) != Orientation::Counterclockwise {
+            violations.push(InvariantViolation::InvertedTriangle(Rc::clone(triangle)));
+            continue;
+        }
+
+        let (e1, e2, e3) = triangle.inner_edges();
+        for edge in vec![e1, e2, e3] {
+            if edge.v1 > edge.v2 || triangulation.is_constrained(&edge) {
+                continue;
+            }
+            let opposite = Rc::new(edge.opposite());
+            let vertex = match triangulation.adjacency.get(&opposite) {
+                Some(adjacent) if !adjacent.is_ghost() => adjacent.opposite_vertex(&opposite),
+                _ => None,
+            };
+            if let Some(vertex) = vertex {
+                if triangle.encircles(&vertex) == Continence::Inside {
+                    violations.push(InvariantViolation::NonDelaunayEdge { edge, vertex });
+                }
+            }
+        }
+    }
+
+    /* ghost rings: solid edges of the ghost triangles of each loop enter and leave each vertex */
+    let mut balance: HashMap<(Rc<Vertex>, Rc<Vertex>), i64> = HashMap::new();
+    for triangle in triangulation.ghost_triangles() {
+        let ghost_vertex = triangle.ghost_vertex().unwrap();
+        if let Some(edge) = triangle.opposite_edge(&ghost_vertex) {
+            *balance.entry((Rc::clone(&ghost_vertex), Rc::clone(&edge.v1))).or_insert(0) += 1;
+            *balance.entry((ghost_vertex, Rc::clone(&edge.v2))).or_insert(0) -= 1;
+        }
+    }
+    for ((_, vertex), count) in balance.into_iter() {
+        if count != 0 {
+            violations.push(InvariantViolation::OpenGhostRing(vertex));
+        }
+    }
+
+    /* constraints */
+    for constraint in triangulation.constraints().iter() {
+        if !triangulation.adjacency.contains_key(constraint)
+            && !triangulation.adjacency.contains_key(&constraint.opposite())
+        {
+            violations.push(InvariantViolation::MissingConstraint(Rc::clone(constraint)));
+        }
+    }
+
+    return violations;
+} /* end - validate */
+
+#[cfg(test)]
+mod validate {
+    use super::*;
+
+    /* square of two triangles, surrounded by ghost triangles */
+    fn square() -> (Triangulation, Vec<Rc<Vertex>>) {
+        let vertices = vec![
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(1.0, 0.0)),
+            Rc::new(Vertex::new(1.0, 1.0)),
+            Rc::new(Vertex::new(0.0, 1.0)),
+        ];
+        let (v1, v2, v3, v4) = (&vertices[0], &vertices[1], &vertices[2], &vertices[3]);
+        let ghost = Rc::new(Vertex::new_ghost());
+
+        let mut triangulation = Triangulation::new();
+        for (a, b, c) in vec![
+            (v1, v2, v3),
+            (v1, v3, v4),
+            (v2, v1, &ghost),
+            (v3, v2, &ghost),
+            (v4, v3, &ghost),
+            (v1, v4, &ghost),
+        ] {
+            triangulation.include_triangle(&Rc::new(Triangle::new(a, b, c)));
+        }
+        return (triangulation, vertices);
+    }
+
+    #[test]
+    fn valid_triangulation() {
+        let (triangulation, _) = square();
+        assert!(validate(&triangulation).is_empty());
+    }
+
+    #[test]
+    fn broken_adjacency() {
+        let (mut triangulation, vertices) = square();
+        let edge = Rc::new(Edge::new(&vertices[0], &vertices[1]));
+        let triangle = Rc::clone(triangulation.adjacency.get(&edge).unwrap());
+        triangulation.triangles.remove(&triangle);
+
+        let violations = validate(&triangulation);
+        assert!(violations.iter().any(|violation| match violation {
+            InvariantViolation::StaleAdjacency { triangle: stale, .. } => *stale == triangle,
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn non_delaunay_and_open_ring() {
+        let (mut triangulation, vertices) = square();
+        let (v1, v2, v3) = (&vertices[0], &vertices[1], &vertices[2]);
+
+        /* the diagonal flipped to a thin pair, then one ghost removed */
+        let v5 = Rc::new(Vertex::new(0.9, 0.1));
+        let t1 = Rc::clone(triangulation.adjacency.get(&Edge::new(v1, v2)).unwrap());
+        triangulation.remove_triangle(&t1);
+        for (a, b, c) in vec![(v1, v2, &v5), (v2, v3, &v5), (v3, v1, &v5)] {
+            triangulation.include_triangle(&Rc::new(Triangle::new(a, b, c)));
+        }
+        let ghost = Rc::clone(triangulation.ghost_triangles().next().unwrap());
+        triangulation.remove_triangle(&ghost);
+
+        let violations = validate(&triangulation);
+        assert!(violations
+            .iter()
+            .any(|violation| matches!(violation, InvariantViolation::NonDelaunayEdge { .. })));
+        assert!(violations
+            .iter()
+            .any(|violation| matches!(violation, InvariantViolation::OpenGhostRing(_))));
+        assert!(violations
+            .iter()
+            .any(|violation| matches!(violation, InvariantViolation::UnmatchedEdge(_))));
+        assert!(format!("{}", violations[0]).len() > 0);
+    }
+
+    #[test]
+    fn constraints() {
+        let (mut triangulation, vertices) = square();
+        let diagonal = Rc::new(Edge::new(&vertices[1], &vertices[3]));
+        triangulation.constrain(&diagonal);
+
+        let violations = validate(&triangulation);
+        assert_eq!(violations.len(), 1);
+        match &violations[0] {
+            InvariantViolation::MissingConstraint(edge) => assert_eq!(*edge, diagonal),
+            violation => panic!("unexpected violation {}", violation),
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use crate::elements::vertex::*;
+use crate::planar::triangulation::*;
+use crate::properties::distance::*;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+
+/**
+ * External data of an input vertex: an id to map results back to the
+ * caller's data, and named scalar values, such as elevations or weights.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VertexAttributes {
+    pub id: Option<String>,
+    pub metadata: BTreeMap<String, f64>,
+}
+
+impl VertexAttributes {
+    pub fn new(id: Option<String>, metadata: BTreeMap<String, f64>) -> Self {
+        Self { id, metadata }
+    }
+}
+
+/**
+ * Attributes of input vertices, kept apart from the triangulation so that
+ * procedures are not aware of them. Vertices created by the procedures,
+ * such as Steiner points, or moved by them, are resolved from their
+ * attributed neighbors once the mesh is complete.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct AttributeTable {
+    pub attributes: HashMap<Rc<Vertex>, VertexAttributes>,
+}
+
+impl AttributeTable {
+    pub fn new() -> Self {
+        Self {
+            attributes: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, vertex: &Rc<Vertex>, attributes: VertexAttributes) {
+        self.attributes.insert(Rc::clone(vertex), attributes);
+    }
+
+    pub fn get(&self, vertex: &Vertex) -> Option<&VertexAttributes> {
+        return self.attributes.get(vertex);
+    }
+
+    pub fn len(&self) -> usize {
+        return self.attributes.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.attributes.is_empty();
+    }
+
+    pub fn extend(&mut self, other: AttributeTable) {
+        self.attributes.extend(other.attributes.into_iter());
+    }
+
+    /**
+     * Attributes of every vertex of the triangulation. Attributed vertices
+     * keep their own. The other ones are resolved ring by ring away from the
+     * attributed vertices, with no id, and each value weighted by the
+     * inverse distance to the resolved neighbors that have it. Vertices no
+     * attributed vertex reaches, if any, get default attributes.
+     */
+    pub fn resolve(&self, triangulation: &Triangulation) -> HashMap<Rc<Vertex>, VertexAttributes> {
+        let mut neighbors: HashMap<Rc<Vertex>, HashSet<Rc<Vertex>>> = HashMap::new();
+        for triangle in triangulation.solid_triangles() {
+            let vertices = [&triangle.v1, &triangle.v2, &triangle.v3];
+            for index in 0..3 {
+                let v1 = vertices[index];
+                let v2 = vertices[(index + 1) % 3];
+                neighbors
+                    .entry(Rc::clone(v1))
+                    .or_insert(HashSet::new())
+                    .insert(Rc::clone(v2));
+                neighbors
+                    .entry(Rc::clone(v2))
+                    .or_insert(HashSet::new())
+                    .insert(Rc::clone(v1));
+            }
+        }
+
+        let mut resolved: HashMap<Rc<Vertex>, VertexAttributes> = HashMap::new();
+        let mut front: Vec<Rc<Vertex>> = Vec::new();
+        for vertex in triangulation.vertices().iter() {
+            if let Some(attributes) = self.attributes.get(vertex) {
+                resolved.insert(Rc::clone(vertex), attributes.clone());
+                front.push(Rc::clone(vertex));
+            }
+        }
+
+        while !front.is_empty() {
+            let next_front: HashSet<Rc<Vertex>> = front
+                .iter()
+                .filter_map(|vertex| neighbors.get(vertex))
+                .flatten()
+                .filter(|vertex| !resolved.contains_key(*vertex))
+                .cloned()
+                .collect();
+
+            /* every vertex of the ring is resolved from the former rings only */
+            let ring: Vec<(Rc<Vertex>, VertexAttributes)> = next_front
+                .iter()
+                .map(|vertex| {
+                    let sources = neighbors[vertex]
+                        .iter()
+                        .filter_map(|neighbor| Some((neighbor, resolved.get(neighbor)?)));
+                    return (Rc::clone(vertex), interpolated(vertex, sources));
+                })
+                .collect();
+            resolved.extend(ring.into_iter());
+
+            front = next_front.into_iter().collect();
+        }
+
+        for vertex in triangulation.vertices().iter() {
+            resolved
+                .entry(Rc::clone(vertex))
+                .or_insert(VertexAttributes::default());
+        }
+        return resolved;
+    } /* end - resolve */
+} /* end - AttributeTable */
+
+/**
+ * Inverse distance weighted values of the sources at the vertex, with no id.
+ */
+fn interpolated<'a, I>(vertex: &Vertex, sources: I) -> VertexAttributes
+where
+    I: Iterator<Item = (&'a Rc<Vertex>, &'a VertexAttributes)>,
+{
+    let mut sums: BTreeMap<String, (f64, f64)> = BTreeMap::new();
+    for (source, attributes) in sources {
+        let weight = 1.0 / distance(vertex, source);
+        for (name, value) in attributes.metadata.iter() {
+            let sum = sums.entry(name.clone()).or_insert((0.0, 0.0));
+            sum.0 += weight * value;
+            sum.1 += weight;
+        }
+    }
+
+    let metadata = sums
+        .into_iter()
+        .map(|(name, (weighted_sum, total_weight))| (name, weighted_sum / total_weight))
+        .collect();
+    return VertexAttributes::new(None, metadata);
+}
+
+#[cfg(test)]
+mod vertex_attributes {
+    use super::*;
+    use crate::elements::triangle::*;
+
+    fn elevation(id: &str, value: f64) -> VertexAttributes {
+        let mut metadata = BTreeMap::new();
+        metadata.insert(String::from("elevation"), value);
+        return VertexAttributes::new(Some(String::from(id)), metadata);
+    }
+
+    #[test]
+    fn resolve() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(1.0, 2.0));
+        let steiner = Rc::new(Vertex::new(1.0, 0.0));
+
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v1, &steiner, &v3)));
+        triangulation.include_triangle(&Rc::new(Triangle::new(&steiner, &v2, &v3)));
+
+        let mut table = AttributeTable::new();
+        table.insert(&v1, elevation("a", 1.0));
+        table.insert(&v2, elevation("b", 3.0));
+        table.insert(&v3, VertexAttributes::new(Some(String::from("c")), BTreeMap::new()));
+
+        let resolved = table.resolve(&triangulation);
+        assert_eq!(resolved.len(), 4);
+        assert_eq!(resolved[&v1], elevation("a", 1.0));
+
+        /* only the vertices having the value are weighted */
+        let attributes = &resolved[&steiner];
+        assert_eq!(attributes.id, None);
+        assert!((attributes.metadata["elevation"] - 2.0).abs() < 1.0E-12);
+    }
+
+    #[test]
+    fn resolve_far_rings() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+        let v4 = Rc::new(Vertex::new(1.0, 1.0));
+        let v5 = Rc::new(Vertex::new(5.0, 5.0));
+        let v6 = Rc::new(Vertex::new(6.0, 5.0));
+        let v7 = Rc::new(Vertex::new(5.0, 6.0));
+
+        let mut triangulation = Triangulation::new();
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v1, &v2, &v3)));
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v2, &v4, &v3)));
+        triangulation.include_triangle(&Rc::new(Triangle::new(&v5, &v6, &v7)));
+
+        let mut table = AttributeTable::new();
+        table.insert(&v1, elevation("a", 4.0));
+
+        let resolved = table.resolve(&triangulation);
+
+        /* v4 is two rings away, resolved from the first ring */
+        assert_eq!(resolved[&v4].metadata["elevation"], 4.0);
+
+        /* disconnected vertices are defaulted */
+        assert_eq!(resolved[&v5], VertexAttributes::default());
+    }
+} /* end - vertex_attributes tests */
@@ -1,8 +1,5 @@
-extern crate nalgebra;
-
 use crate::elements::vertex::*;
-
-use nalgebra::Matrix4;
+use crate::planar::stats;
 
 #[derive(PartialEq, Debug)]
 pub enum Continence {
@@ -22,13 +19,16 @@ pub enum BoundaryInclusion {
  * Vertices a, b and c must be in counterclockwise order.
  */
 pub fn continence(a: &Vertex, b: &Vertex, c: &Vertex, d: &Vertex) -> Continence {
-    let matrix = Matrix4::new(
-        a.x, a.y, a.x.powi(2) + a.y.powi(2), 1.0,
-        b.x, b.y, b.x.powi(2) + b.y.powi(2), 1.0,
-        c.x, c.y, c.x.powi(2) + c.y.powi(2), 1.0,
-        d.x, d.y, d.x.powi(2) + d.y.powi(2), 1.0,
-    );
-    let det = matrix.determinant();
+    stats::count_incircle();
+    /* translated to d, so that lattice coordinates are computed exactly */
+    let (adx, ady) = (a.x - d.x, a.y - d.y);
+    let (bdx, bdy) = (b.x - d.x, b.y - d.y);
+    let (cdx, cdy) = (c.x - d.x, c.y - d.y);
+    let ad = adx * adx + ady * ady;
+    let bd = bdx * bdx + bdy * bdy;
+    let cd = cdx * cdx + cdy * cdy;
+    let det = adx * (bdy * cd - bd * cdy) - ady * (bdx * cd - bd * cdx)
+        + ad * (bdx * cdy - bdy * cdx);
 
     if float_cmp::approx_eq!(f64, det, 0.0, epsilon = 1.0E-14f64) {
         return Continence::Boundary;
@@ -72,4 +72,13 @@ mod in_circle {
         let p4 = Vertex::new(0.0, 1.0);
         assert_eq!(continence(&p1, &p2, &p3, &p4), Continence::Boundary);
     }
+
+    #[test]
+    fn test_continence_cocircular_lattice() {
+        let p1 = Vertex::new(2.25, -2.25);
+        let p2 = Vertex::new(4.0, -7.5);
+        let p3 = Vertex::new(8.0, -3.5);
+        let p4 = Vertex::new(2.75, -1.75);
+        assert_eq!(continence(&p1, &p2, &p3, &p4), Continence::Boundary);
+    }
 }
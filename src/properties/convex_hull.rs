@@ -0,0 +1,91 @@
+use crate::elements::{polyline::*, vertex::*};
+use crate::properties::orientation::*;
+
+use std::rc::Rc;
+
+/**
+ * Convex hull of the vertices, by Andrew's monotone chain, as a
+ * counterclockwise closed polyline starting at the lowest leftmost vertex.
+ * Colinear vertices along the hull are dropped. Returns None if the
+ * vertices are all colinear, or less than 3.
+ */
+pub fn convex_hull(vertices: &Vec<Rc<Vertex>>) -> Option<Polyline> {
+    let mut sorted: Vec<Rc<Vertex>> = vertices.iter().filter(|v| !v.is_ghost).cloned().collect();
+    sorted.sort_by(|v1, v2| {
+        return v1
+            .x
+            .partial_cmp(&v2.x)
+            .unwrap()
+            .then(v1.y.partial_cmp(&v2.y).unwrap());
+    });
+    sorted.dedup_by(|v1, v2| v1 == v2);
+    if sorted.len() < 3 {
+        return None;
+    }
+
+    /* lower chain left to right, then upper chain right to left */
+    let mut hull: Vec<Rc<Vertex>> = Vec::new();
+    for pass in 0..2 {
+        let chain_start = hull.len();
+        let pass_vertices: Vec<&Rc<Vertex>> = match pass {
+            0 => sorted.iter().collect(),
+            _ => sorted.iter().rev().collect(),
+        };
+        for vertex in pass_vertices {
+            while hull.len() >= chain_start + 2
+                && orientation(&hull[hull.len() - 2], &hull[hull.len() - 1], vertex)
+                    != Orientation::Counterclockwise
+            {
+                hull.pop();
+            }
+            hull.push(Rc::clone(vertex));
+        }
+        /* the last vertex of each chain starts the other one */
+        hull.pop();
+    }
+
+    if hull.len() < 3 {
+        return None;
+    }
+    return Polyline::new_closed(hull);
+}
+
+#[cfg(test)]
+mod convex_hull {
+    use super::*;
+
+    #[test]
+    fn square_with_inner_vertices() {
+        let vertices: Vec<Rc<Vertex>> = vec![
+            (1.0, 1.0),
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (1.0, 0.0),
+            (2.0, 2.0),
+            (0.5, 1.5),
+            (0.0, 2.0),
+            (2.0, 2.0),
+        ]
+        .into_iter()
+        .map(|(x, y)| Rc::new(Vertex::new(x, y)))
+        .collect();
+
+        let hull = convex_hull(&vertices).unwrap();
+        let expected: Vec<Rc<Vertex>> = vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]
+            .into_iter()
+            .map(|(x, y)| Rc::new(Vertex::new(x, y)))
+            .collect();
+        assert_eq!(hull.vertices, expected);
+        assert!(!hull.opened);
+    }
+
+    #[test]
+    fn degenerate() {
+        let colinear: Vec<Rc<Vertex>> = vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)]
+            .into_iter()
+            .map(|(x, y)| Rc::new(Vertex::new(x, y)))
+            .collect();
+        assert!(convex_hull(&colinear).is_none());
+        assert!(convex_hull(&Vec::new()).is_none());
+    }
+} /* end - convex_hull tests */
@@ -1,9 +1,27 @@
-use crate::elements::vertex::*;
+use crate::elements::{edge::*, vertex::*};
 
 pub fn distance(v1: &Vertex, v2: &Vertex) -> f64 {
     ((v1.x - v2.x).powi(2) + (v1.y - v2.y).powi(2)).sqrt()
 }
 
+/**
+ * Distance from the vertex to the nearest point of the edge.
+ */
+pub fn edge_distance(edge: &Edge, vertex: &Vertex) -> f64 {
+    let (dx, dy) = (edge.v2.x - edge.v1.x, edge.v2.y - edge.v1.y);
+    let squared_length = dx * dx + dy * dy;
+    if squared_length == 0.0 {
+        return distance(&edge.v1, vertex);
+    }
+
+    let ratio = ((vertex.x - edge.v1.x) * dx + (vertex.y - edge.v1.y) * dy) / squared_length;
+    let ratio = ratio.max(0.0).min(1.0);
+    return distance(
+        &Vertex::new(edge.v1.x + ratio * dx, edge.v1.y + ratio * dy),
+        vertex,
+    );
+}
+
 #[cfg(test)]
 mod distance {
     use super::*;
@@ -32,4 +50,15 @@ mod distance {
         let v2 = Vertex::new(3.0, 4.0);
         assert_eq!(distance(&v1, &v2), 5.0);
     }
+
+    #[test]
+    fn test_edge_distance() {
+        let edge = Edge::new(
+            &std::rc::Rc::new(Vertex::new(0.0, 0.0)),
+            &std::rc::Rc::new(Vertex::new(2.0, 0.0)),
+        );
+        assert_eq!(edge_distance(&edge, &Vertex::new(1.0, 3.0)), 3.0);
+        assert_eq!(edge_distance(&edge, &Vertex::new(5.0, 4.0)), 5.0);
+        assert_eq!(edge_distance(&edge, &Vertex::new(0.5, 0.0)), 0.0);
+    }
 }
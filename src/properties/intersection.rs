@@ -5,6 +5,7 @@ extern crate nalgebra;
 
 use crate::elements::bounding_box::*;
 use crate::elements::vertex::*;
+use crate::properties::parallel::*;
 
 use nalgebra::{Matrix2, Matrix2x1};
 use std::rc::Rc;
@@ -39,6 +40,48 @@ pub fn intersection(
     return None;
 }
 
+/**
+ * Pairs of intersecting segments, by their indices, along with their
+ * intersection vertex. A vertical line sweeps the segments sorted by their
+ * leftmost abscissa, keeping active the segments it crosses, so that each
+ * segment is only tested against the active segments whose ordinates
+ * overlap its own. Pairs sharing an end vertex and parallel pairs are
+ * skipped, as their intersection splits neither segment.
+ */
+pub fn intersecting_pairs(segments: &Vec<(Rc<Vertex>, Rc<Vertex>)>) -> Vec<(usize, usize, Vertex)> {
+    let boxes: Vec<BoundingBox> = segments
+        .iter()
+        .map(|(v1, v2)| BoundingBox::from_vertices(vec![Rc::clone(v1), Rc::clone(v2)]).unwrap())
+        .collect();
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    order.sort_by(|&i, &j| boxes[i].origin.x.partial_cmp(&boxes[j].origin.x).unwrap());
+
+    let mut pairs: Vec<(usize, usize, Vertex)> = Vec::new();
+    let mut active: Vec<usize> = Vec::new();
+    for index in order.into_iter() {
+        let sweep_x = boxes[index].origin.x;
+        active.retain(|&other| boxes[other].destin.x >= sweep_x);
+
+        let (v1, v2) = &segments[index];
+        for &other in active.iter() {
+            if !boxes[index].intersects(&boxes[other]) {
+                continue;
+            }
+            let (v3, v4) = &segments[other];
+            let shares_vertex = v1 == v3 || v1 == v4 || v2 == v3 || v2 == v4;
+            if shares_vertex || parallel(v1, v2, v3, v4) {
+                continue;
+            }
+            if let Some(vertex) = intersection(v1, v2, v3, v4) {
+                pairs.push((other.min(index), other.max(index), vertex));
+            }
+        }
+        active.push(index);
+    }
+
+    return pairs;
+}
+
 /**
  * Determines the possible region where a intersection may occur
  */
@@ -119,6 +162,63 @@ fn intersection_vertex(
     return Some(Vertex::new(intersection_x, intersection_y));
 }
 
+#[cfg(test)]
+mod intersecting_pairs {
+    use super::*;
+
+    fn segment(x1: f64, y1: f64, x2: f64, y2: f64) -> (Rc<Vertex>, Rc<Vertex>) {
+        return (Rc::new(Vertex::new(x1, y1)), Rc::new(Vertex::new(x2, y2)));
+    }
+
+    #[test]
+    fn crossing_and_touching() {
+        let segments = vec![
+            segment(0.0, 0.0, 4.0, 4.0),
+            segment(0.0, 4.0, 4.0, 0.0),
+            segment(2.0, 0.0, 2.0, 1.0),
+            segment(3.0, 0.0, 3.0, 1.0),
+            segment(4.0, 4.0, 5.0, 0.0),
+            segment(-1.0, 1.0, 5.0, 1.0),
+        ];
+        let mut pairs: Vec<(usize, usize)> = intersecting_pairs(&segments)
+            .into_iter()
+            .map(|(i, j, _)| (i, j))
+            .collect();
+        pairs.sort();
+
+        /* 0 and 4 share a vertex, 2 and 3 are parallel */
+        assert_eq!(pairs, vec![(0, 1), (0, 5), (1, 3), (1, 5), (2, 5), (3, 5), (4, 5)]);
+    }
+
+    #[test]
+    fn matches_every_pair() {
+        /* fan of segments, most of them crossing each other */
+        let segments: Vec<(Rc<Vertex>, Rc<Vertex>)> = (0..20)
+            .map(|i| {
+                let t = i as f64;
+                return segment(t * 0.5, (t * 1.7) % 5.0, t * 0.3 + 3.0, (t * 2.9) % 7.0);
+            })
+            .collect();
+
+        let mut expected: Vec<(usize, usize)> = Vec::new();
+        for i in 0..segments.len() {
+            for j in (i + 1)..segments.len() {
+                let (v1, v2) = &segments[i];
+                let (v3, v4) = &segments[j];
+                if !parallel(v1, v2, v3, v4) && intersection(v1, v2, v3, v4).is_some() {
+                    expected.push((i, j));
+                }
+            }
+        }
+        let mut pairs: Vec<(usize, usize)> = intersecting_pairs(&segments)
+            .into_iter()
+            .map(|(i, j, _)| (i, j))
+            .collect();
+        pairs.sort();
+        assert_eq!(pairs, expected);
+    }
+} /* end - intersecting_pairs tests */
+
 #[cfg(test)]
 mod intersection {
     use super::*;
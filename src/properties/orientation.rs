@@ -3,6 +3,7 @@ extern crate float_cmp;
 extern crate nalgebra;
 
 use crate::elements::vertex::*;
+use crate::planar::stats;
 use nalgebra::Matrix3;
 
 #[derive(PartialEq, Debug)]
@@ -17,6 +18,7 @@ pub enum Orientation {
  * in the circumcircle they define.
  */
 pub fn orientation(a: &Vertex, b: &Vertex, c: &Vertex) -> Orientation {
+    stats::count_orientation();
     let matrix = Matrix3::new(a.x, a.y, 1.0, b.x, b.y, 1.0, c.x, c.y, 1.0);
     let det: f64 = matrix.determinant();
 
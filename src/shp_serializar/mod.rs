@@ -0,0 +1,296 @@
+use crate::json_serializar::models::{action::Action, input, point::Point};
+
+use nlsn_delaunay::{elements::vertex::Vertex, planar::triangulation_error::TriangulationError};
+
+/* Leading big endian integer of every shapefile */
+const FILE_CODE: i32 = 9994;
+
+/* Bytes of the main file header and of each record header */
+const HEADER_LENGTH: usize = 100;
+const RECORD_HEADER_LENGTH: usize = 8;
+
+/* Shape types, along with their Z and M variants, which share the layout */
+const NULL_SHAPE: i32 = 0;
+const POLYLINE_TYPES: [i32; 3] = [3, 13, 23];
+const POLYGON_TYPES: [i32; 3] = [5, 15, 25];
+
+/* Profile of shapefile inputs, since shapefiles carry no refine params */
+const DEFAULT_PROFILE: &str = "standard";
+
+/**
+ * Reads the main file (.shp) of a shapefile as a triangulation input, as
+ * parse does, named after the file.
+ */
+pub fn read(path: &std::path::Path) -> Result<input::TriangulationInput, TriangulationError> {
+    let bytes = std::fs::read(path)?;
+    let name = match path.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => String::from("shp"),
+    };
+    return parse(&bytes, &name);
+}
+
+/**
+ * Triangulation input of the polygon and polyline records of a shapefile.
+ *  - polygon rings: clockwise rings are outer boundaries, included, and
+ * counterclockwise rings are holes, removed, as the format orders them.
+ * Both become counterclockwise polyline actions.
+ *  - polyline parts: each part becomes a segments constraint action.
+ * Z and M values are dropped, and null shapes are skipped. Refine params
+ * take the standard profile, which may be overridden from the command line.
+ */
+pub fn parse(bytes: &[u8], name: &str) -> Result<input::TriangulationInput, TriangulationError> {
+    if bytes.len() < HEADER_LENGTH || big_endian_i32(bytes, 0) != FILE_CODE {
+        return Err(invalid_shp(0, "not a shapefile main file"));
+    }
+    let file_length = (big_endian_i32(bytes, 24).max(0) as usize * 2).min(bytes.len());
+
+    let mut actions: Vec<Action> = Vec::new();
+    let mut offset = HEADER_LENGTH;
+    while offset + RECORD_HEADER_LENGTH <= file_length {
+        let content_length = big_endian_i32(bytes, offset + 4).max(0) as usize * 2;
+        let content_start = offset + RECORD_HEADER_LENGTH;
+        let content_end = content_start + content_length;
+        if content_length < 4 || content_end > file_length {
+            return Err(invalid_shp(offset, "record out of the file"));
+        }
+        let content = &bytes[content_start..content_end];
+
+        let shape_type = little_endian_i32(content, 0);
+        if POLYGON_TYPES.contains(&shape_type) {
+            for mut ring in parts(content, content_start)?.into_iter() {
+                /* rings repeat their first point at the end */
+                if ring.len() > 1 && ring[0] == ring[ring.len() - 1] {
+                    ring.pop();
+                }
+                if ring.len() < 3 {
+                    return Err(invalid_shp(offset, "ring with less than 3 points"));
+                }
+                let intent = if signed_area(&ring) < 0.0 {
+                    ring.reverse();
+                    "include"
+                } else {
+                    "remove"
+                };
+                actions.push(action(intent, "polyline", ring));
+            }
+        } else if POLYLINE_TYPES.contains(&shape_type) {
+            for part in parts(content, content_start)?.into_iter() {
+                let pairs: Vec<(f64, f64)> = part
+                    .windows(2)
+                    .map(|pair| vec![pair[0], pair[1]])
+                    .flatten()
+                    .collect();
+                if !pairs.is_empty() {
+                    actions.push(action("constraint", "segments", pairs));
+                }
+            }
+        } else if shape_type != NULL_SHAPE {
+            return Err(invalid_shp(
+                offset,
+                &format!("unsupported shape type {}", shape_type),
+            ));
+        }
+        offset = content_end;
+    }
+
+    if !actions.iter().any(|action| action.intent == "include") {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "shapefile with no outer polygon ring",
+        )));
+    }
+    return Ok(input::TriangulationInput::from_actions(
+        name,
+        actions,
+        DEFAULT_PROFILE,
+    ));
+} /* end - parse */
+
+/**
+ * Points of each part of a polygon or polyline record content, laid out as
+ * the shape type, the bounding box, the parts count, the points count, the
+ * index of the first point of each part, and the points.
+ */
+fn parts(content: &[u8], position: usize) -> Result<Vec<Vec<(f64, f64)>>, TriangulationError> {
+    if content.len() < 44 {
+        return Err(invalid_shp(position, "truncated record"));
+    }
+    let parts_count = little_endian_i32(content, 36).max(0) as usize;
+    let points_count = little_endian_i32(content, 40).max(0) as usize;
+    let points_start = 44 + 4 * parts_count;
+    if content.len() < points_start + 16 * points_count {
+        return Err(invalid_shp(position, "truncated record"));
+    }
+
+    let mut starts: Vec<usize> = (0..parts_count)
+        .map(|index| little_endian_i32(content, 44 + 4 * index).max(0) as usize)
+        .collect();
+    starts.push(points_count);
+    if starts.windows(2).any(|pair| pair[0] > pair[1]) {
+        return Err(invalid_shp(position, "unsorted parts"));
+    }
+
+    let point = |index: usize| {
+        let offset = points_start + 16 * index;
+        return (
+            little_endian_f64(content, offset),
+            little_endian_f64(content, offset + 8),
+        );
+    };
+    return Ok(starts
+        .windows(2)
+        .map(|pair| (pair[0]..pair[1]).map(point).collect())
+        .collect());
+}
+
+fn big_endian_i32(bytes: &[u8], offset: usize) -> i32 {
+    let mut word = [0u8; 4];
+    word.copy_from_slice(&bytes[offset..offset + 4]);
+    return i32::from_be_bytes(word);
+}
+
+fn little_endian_i32(bytes: &[u8], offset: usize) -> i32 {
+    let mut word = [0u8; 4];
+    word.copy_from_slice(&bytes[offset..offset + 4]);
+    return i32::from_le_bytes(word);
+}
+
+fn little_endian_f64(bytes: &[u8], offset: usize) -> f64 {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&bytes[offset..offset + 8]);
+    return f64::from_le_bytes(word);
+}
+
+fn signed_area(points: &[(f64, f64)]) -> f64 {
+    let mut area = 0.0;
+    for index in 0..points.len() {
+        let (x1, y1) = points[index];
+        let (x2, y2) = points[(index + 1) % points.len()];
+        area += x1 * y2 - x2 * y1;
+    }
+    return area / 2.0;
+}
+
+fn action(intent: &str, geometry: &str, points: Vec<(f64, f64)>) -> Action {
+    let points = points
+        .into_iter()
+        .map(|(x, y)| Point::from_vertex(&Vertex::new(x, y)))
+        .collect();
+    return Action::from_points(intent, geometry, points, Vec::new());
+}
+
+fn invalid_shp(offset: usize, reason: &str) -> TriangulationError {
+    return TriangulationError::DegenerateInput(format!(
+        "invalid shapefile at byte {}: {}",
+        offset, reason
+    ));
+}
+
+#[cfg(test)]
+mod shp {
+    use super::*;
+
+    /* record of the shape type, with the given parts */
+    fn record(shape_type: i32, parts: &[Vec<(f64, f64)>]) -> Vec<u8> {
+        let points_count: usize = parts.iter().map(|part| part.len()).sum();
+        let mut content: Vec<u8> = Vec::new();
+        content.extend(&shape_type.to_le_bytes());
+        content.extend(&[0u8; 32]);
+        content.extend(&(parts.len() as i32).to_le_bytes());
+        content.extend(&(points_count as i32).to_le_bytes());
+        let mut start = 0;
+        for part in parts.iter() {
+            content.extend(&(start as i32).to_le_bytes());
+            start += part.len();
+        }
+        for (x, y) in parts.iter().flatten() {
+            content.extend(&x.to_le_bytes());
+            content.extend(&y.to_le_bytes());
+        }
+        return content;
+    }
+
+    fn shapefile(shape_type: i32, records: &[Vec<u8>]) -> Vec<u8> {
+        let mut body: Vec<u8> = Vec::new();
+        for (index, content) in records.iter().enumerate() {
+            body.extend(&(index as i32 + 1).to_be_bytes());
+            body.extend(&(content.len() as i32 / 2).to_be_bytes());
+            body.extend(content);
+        }
+
+        let mut bytes: Vec<u8> = Vec::new();
+        bytes.extend(&FILE_CODE.to_be_bytes());
+        bytes.extend(&[0u8; 20]);
+        bytes.extend(&(((HEADER_LENGTH + body.len()) / 2) as i32).to_be_bytes());
+        bytes.extend(&1000i32.to_le_bytes());
+        bytes.extend(&shape_type.to_le_bytes());
+        bytes.extend(&[0u8; 64]);
+        bytes.extend(body);
+        return bytes;
+    }
+
+    #[test]
+    fn polygon_with_hole() {
+        /* clockwise outer ring, counterclockwise hole, both closed */
+        let outer = vec![(0.0, 0.0), (0.0, 4.0), (4.0, 4.0), (4.0, 0.0), (0.0, 0.0)];
+        let hole = vec![(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0), (1.0, 1.0)];
+        let bytes = shapefile(5, &[record(5, &[outer, hole]), record(NULL_SHAPE, &[])]);
+
+        let input = parse(&bytes, "parcel.shp").unwrap();
+        assert_eq!(input.name, "parcel.shp");
+        assert_eq!(input.params.profile, Some(String::from("standard")));
+        assert_eq!(input.actions.len(), 2);
+
+        let boundary = &input.actions[0];
+        assert_eq!(
+            (boundary.intent.as_str(), boundary.geometry.as_str()),
+            ("include", "polyline")
+        );
+        assert_eq!(boundary.points.len(), 4);
+        let points: Vec<(f64, f64)> = boundary.points.iter().map(|p| (p.x, p.y)).collect();
+        assert!(signed_area(&points) > 0.0);
+
+        let hole = &input.actions[1];
+        assert_eq!(hole.intent, "remove");
+        assert_eq!(hole.points.len(), 4);
+    }
+
+    #[test]
+    fn polylines_as_constraints() {
+        let outer = vec![(0.0, 0.0), (0.0, 4.0), (4.0, 4.0), (4.0, 0.0), (0.0, 0.0)];
+        let river = vec![(0.5, 0.5), (2.0, 1.0), (3.5, 0.5)];
+        let bytes = shapefile(
+            3,
+            &[record(5, &[outer]), record(3, &[river, vec![(1.0, 3.0)]])],
+        );
+
+        let input = parse(&bytes, "river.shp").unwrap();
+        assert_eq!(input.actions.len(), 2);
+
+        /* single point parts carry no segment */
+        let river = &input.actions[1];
+        assert_eq!(
+            (river.intent.as_str(), river.geometry.as_str()),
+            ("constraint", "segments")
+        );
+        assert_eq!(river.points.len(), 4);
+    }
+
+    #[test]
+    fn invalid_files() {
+        assert!(parse(&[0u8; 10], "short.shp").is_err());
+
+        /* polylines bound no domain */
+        let river = vec![(0.5, 0.5), (2.0, 1.0)];
+        assert!(parse(&shapefile(3, &[record(3, &[river])]), "river.shp").is_err());
+
+        /* points are not supported */
+        assert!(parse(&shapefile(1, &[1i32.to_le_bytes().to_vec()]), "points.shp").is_err());
+
+        /* truncated record */
+        let outer = vec![(0.0, 0.0), (0.0, 4.0), (4.0, 4.0), (0.0, 0.0)];
+        let mut bytes = shapefile(5, &[record(5, &[outer])]);
+        bytes.truncate(bytes.len() - 8);
+        assert!(parse(&bytes, "truncated.shp").is_err());
+    }
+} /* end - shp tests */
@@ -1,67 +1,89 @@
-use nlsn_delaunay::{elements::polyline::*, properties::continence::*};
+use nlsn_delaunay::{
+    elements::{polyline::*, vertex::*}, planar::triangulation_error::TriangulationError,
+    properties::continence::*,
+};
 
 use std::collections::HashSet;
 use std::rc::Rc;
 
 /**
- * Determines the boundary possibly defined by inclusion
- * and removal of polylines, looking for the largest continuous
- * domain. Every include will be united. All removals will be
- * subtracted from union of includes.
+ * Determines the boundaries possibly defined by inclusion and removal of
+ * polylines, one for each disconnected domain. Includes are united while
+ * they intersect, disjoint ones making separate domains, and all removals
+ * are subtracted from every domain, possibly splitting it in several ones.
+ * Removals crossing no domain are returned along, as possible holes.
+ * Boundaries are sorted by their lowest vertex.
  * If includes is empty, Err is returned.
- * If any include is sepparated from the remaining, Err is returned.
- * If any removal, splits the union in two or more, Err is returned.
+ * Crossings of the polylines are snap rounded to the grid size.
  */
-pub fn boundary(
+pub fn boundaries(
     includes: &Vec<Rc<Polyline>>,
     removes: &Vec<Rc<Polyline>>,
-) -> Result<(Rc<Polyline>, Vec<Rc<Polyline>>), ()> {
+    grid: f64,
+) -> Result<(Vec<Rc<Polyline>>, Vec<Rc<Polyline>>), TriangulationError> {
     if includes.is_empty() {
-        return Err(());
+        return Err(TriangulationError::DegenerateInput(String::from("no inclusion polyline")));
     }
 
-    let mut includes: HashSet<Rc<Polyline>> = includes.iter().cloned().collect();
-    let mut unused_removals: Vec<Rc<Polyline>> = Vec::new();
-
-    let mut boundary = Rc::clone(includes.iter().next().unwrap());
-    includes.remove(&boundary);
-
-    for _ in 0..includes.len() {
-        for possible_include in includes.iter().cloned() {
-            if let Some((union, _)) = Polyline::union(&boundary, &possible_include) {
-                boundary = Rc::new(union);
-                includes.remove(&possible_include);
-                break;
+    let mut boundaries: Vec<Rc<Polyline>> = Vec::new();
+    let mut pending: Vec<Rc<Polyline>> = includes.iter().cloned().collect();
+    while !pending.is_empty() {
+        let mut boundary = pending.remove(0);
+        loop {
+            let united = pending.iter().enumerate().find_map(|(index, possible_include)| {
+                let (union, _) = Polyline::union_snapped(&boundary, possible_include, grid)?;
+                return Some((index, union));
+            });
+            match united {
+                Some((index, union)) => {
+                    boundary = Rc::new(union);
+                    pending.remove(index);
+                }
+                None => break,
             }
         }
+        boundaries.push(boundary);
     }
 
-    if !includes.is_empty() {
-        return Err(());
-    }
-
+    let mut unused_removals: Vec<Rc<Polyline>> = Vec::new();
     for possible_removal in removes.iter() {
-        let (subtraction_list, _) = Polyline::subtraction(&boundary, possible_removal);
-        
-        if subtraction_list.len() > 1 {
-            /* divided union in more than 1 */
-            return Err(());
-        }
-        if subtraction_list.len() == 1 {
-            boundary = Rc::clone(subtraction_list.get(0).unwrap());
-            continue;
+        let mut is_used = false;
+        boundaries = boundaries
+            .into_iter()
+            .map(|boundary| {
+                let (subtraction_list, _) =
+                    Polyline::subtraction_snapped(&boundary, possible_removal, grid);
+                if subtraction_list.is_empty() {
+                    return vec![boundary];
+                }
+                is_used = true;
+                return subtraction_list;
+            })
+            .flatten()
+            .collect();
+        if !is_used {
+            unused_removals.push(Rc::clone(possible_removal));
         }
-        unused_removals.push(Rc::clone(possible_removal));
     }
 
-    return Ok((boundary, unused_removals));
+    boundaries.sort_by(|b1, b2| lowest_vertex(b1).partial_cmp(&lowest_vertex(b2)).unwrap());
+    return Ok((boundaries, unused_removals));
+}
+
+fn lowest_vertex(polyline: &Polyline) -> Rc<Vertex> {
+    return polyline.vertices.iter().min().cloned().unwrap();
 }
 
 /**
  * Determines all holes that are contained by the boundary
  * and unite holes, if they have any interesection.
+ * Crossings of the holes are snap rounded to the grid size.
  */
-pub fn holes(boundary: &Rc<Polyline>, removes: &Vec<Rc<Polyline>>) -> HashSet<Rc<Polyline>> {
+pub fn holes(
+    boundary: &Rc<Polyline>,
+    removes: &Vec<Rc<Polyline>>,
+    grid: f64,
+) -> HashSet<Rc<Polyline>> {
     let mut holes: HashSet<Rc<Polyline>> = HashSet::new();
 
     if removes.is_empty() {
@@ -81,7 +103,7 @@ pub fn holes(boundary: &Rc<Polyline>, removes: &Vec<Rc<Polyline>>) -> HashSet<Rc
         }
 
         for existing_hole in holes.iter().cloned() {
-            if let Some((union, _)) = Polyline::union(&existing_hole, &possible_removal) {
+            if let Some((union, _)) = Polyline::union_snapped(&existing_hole, &possible_removal, grid) {
                 holes.remove(&existing_hole);
                 removes.push(Rc::new(union));
                 break;
@@ -0,0 +1,159 @@
+use crate::json_serializar::models::action::Action;
+use crate::triangulator_interface::interpreter::circle_parser;
+use std::rc::Rc;
+
+use nlsn_delaunay::{
+    elements::{polyline::*, vertex::*},
+    planar::triangulation_error::TriangulationError,
+};
+
+/**
+ * Parses a circular sector given by its center point and the radius,
+ * start and end angle scalars, in degrees, optionally followed by the
+ * count of segments of the arc (100 per turn by default), which may also
+ * be given by the segments or the chord tolerance of the action. The arc runs
+ * counterclockwise from the start to the end angle, and is closed through
+ * the center, unless it spans a whole turn.
+ */
+pub fn parse(action: &Action) -> Result<Polyline, TriangulationError> {
+    let defined_by_center_radius_angles = action.scalars.len() >= 3 && action.points.len() == 1;
+    if !defined_by_center_radius_angles {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "arc requires a center point, a radius, a start and an end angle",
+        )));
+    }
+
+    let center = action.points.get(0).unwrap();
+    let radius = action.scalars[0];
+    if !(radius > 0.0) {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "arc with non positive radius",
+        )));
+    }
+
+    let start = action.scalars[1].to_radians();
+    let mut sweep = action.scalars[2].to_radians() - start;
+    let turn = std::f64::consts::PI * 2.0;
+    if sweep <= 0.0 {
+        sweep += turn * (-sweep / turn).floor() + turn;
+    }
+    let closed_turn = sweep >= turn;
+    let sweep = sweep.min(turn);
+
+    let default_resolution = (100.0 * sweep / turn).ceil().max(2.0) as usize;
+    let resolution: usize =
+        circle_parser::segment_count(action, 3, radius, sweep, default_resolution)?;
+    if resolution < 2 {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "arc resolution below 2",
+        )));
+    }
+
+    let dphi = sweep / resolution as f64;
+    let arc_points = if closed_turn {
+        resolution
+    } else {
+        resolution + 1
+    };
+    let mut vertices: Vec<Rc<Vertex>> = (0..arc_points)
+        .map(|index| start + dphi * index as f64)
+        .map(|angle| {
+            Vertex::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        })
+        .map(Rc::new)
+        .collect();
+    if !closed_turn {
+        vertices.push(Rc::new(Vertex::new(center.x, center.y)));
+    }
+
+    match Polyline::new_closed(vertices) {
+        Some(polyline) => return Ok(polyline),
+        None => {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "arc resolution below 3 for a whole turn",
+            )))
+        }
+    }
+} /* end - parse */
+
+#[cfg(test)]
+mod parse_arc {
+    use super::*;
+    use crate::json_serializar::models::point::Point;
+
+    fn action(scalars: Vec<f64>) -> Action {
+        return Action {
+            intent: String::from("include"),
+            geometry: String::from("arc"),
+            scalars,
+            points: vec![Point {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+                id: None,
+                metadata: None,
+            }],
+            assemble: Vec::new(),
+            loops: Vec::new(),
+            segments: None,
+            tolerance: None,
+            pattern: None,
+            spacing: None,
+            tag: None,
+            max_edge_length: None,
+            params: None,
+        };
+    }
+
+    fn coordinates(polyline: &Polyline) -> Vec<(f64, f64)> {
+        return polyline
+            .vertices
+            .iter()
+            .map(|v| ((v.x * 1e9).round() / 1e9, (v.y * 1e9).round() / 1e9))
+            .collect();
+    }
+
+    #[test]
+    fn sector() {
+        let polyline = parse(&action(vec![2.0, 0.0, 90.0, 2.0])).unwrap();
+        let half = (2.0_f64.sqrt() * 1e9).round() / 1e9;
+        assert_eq!(
+            coordinates(&polyline),
+            vec![(2.0, 0.0), (half, half), (0.0, 2.0), (0.0, 0.0)]
+        );
+
+        /* end before start wraps counterclockwise */
+        let polyline = parse(&action(vec![1.0, 90.0, 0.0, 3.0])).unwrap();
+        assert_eq!(
+            coordinates(&polyline),
+            vec![(0.0, 1.0), (-1.0, 0.0), (0.0, -1.0), (1.0, 0.0), (0.0, 0.0)]
+        );
+
+        assert_eq!(
+            parse(&action(vec![1.0, 0.0, 180.0]))
+                .unwrap()
+                .vertices
+                .len(),
+            52
+        );
+    }
+
+    #[test]
+    fn whole_turn() {
+        let polyline = parse(&action(vec![1.0, 0.0, 360.0, 4.0])).unwrap();
+        assert_eq!(
+            coordinates(&polyline),
+            vec![(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)]
+        );
+    }
+
+    #[test]
+    fn rejects_degenerate() {
+        assert!(parse(&action(vec![1.0, 0.0])).is_err());
+        assert!(parse(&action(vec![0.0, 0.0, 90.0])).is_err());
+        assert!(parse(&action(vec![1.0, 0.0, 90.0, 1.0])).is_err());
+    }
+} /* end - parse_arc tests */
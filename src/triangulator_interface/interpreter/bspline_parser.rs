@@ -0,0 +1,152 @@
+use crate::json_serializar::models::{action::Action, point::Point};
+use std::rc::Rc;
+
+use nlsn_delaunay::{
+    elements::{polyline::*, vertex::*},
+    planar::triangulation_error::TriangulationError,
+};
+
+/**
+ * Parses a closed uniform b-spline given by its control points, sampled
+ * at the count of points given by the first scalar (100 by default). The
+ * second scalar is the degree of the spline (3 by default), below the
+ * count of control points. The curve follows the orientation of the
+ * control polygon.
+ */
+pub fn parse(action: &Action) -> Result<Polyline, TriangulationError> {
+    let controls = &action.points;
+    let resolution: usize = match action.scalars.get(0) {
+        Some(value) => value.round() as usize,
+        None => 100,
+    };
+    let degree: usize = match action.scalars.get(1) {
+        Some(value) => value.round() as usize,
+        None => 3,
+    };
+    if controls.len() < 3 {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "bspline with less than 3 control points",
+        )));
+    }
+    if degree < 1 || degree >= controls.len() {
+        return Err(TriangulationError::DegenerateInput(format!(
+            "bspline degree {} out of 1 to {}",
+            degree,
+            controls.len() - 1
+        )));
+    }
+
+    let parameter_range = controls.len() as f64;
+    let vertices: Vec<Rc<Vertex>> = (0..resolution)
+        .map(|index| parameter_range * index as f64 / resolution as f64)
+        .map(|parameter| Rc::new(de_boor(controls, degree, parameter)))
+        .collect();
+
+    match Polyline::new_closed(vertices) {
+        Some(polyline) => return Ok(polyline),
+        None => {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "bspline resolution below 3",
+            )))
+        }
+    }
+} /* end - parse */
+
+/**
+ * Point of the periodic spline at the parameter, with integer knots, the
+ * span [k, k + 1) being controlled by the points k - degree to k.
+ */
+fn de_boor(controls: &Vec<Point>, degree: usize, parameter: f64) -> Vertex {
+    let count = controls.len();
+    let span = parameter.floor() as usize;
+    let mut points: Vec<(f64, f64)> = (0..=degree)
+        .map(|index| &controls[(span + count + index - degree) % count])
+        .map(|point| (point.x, point.y))
+        .collect();
+
+    for level in 1..=degree {
+        for index in (level..=degree).rev() {
+            let knot = (span + index) as f64 - degree as f64;
+            let alpha = (parameter - knot) / (degree + 1 - level) as f64;
+            let (x1, y1) = points[index - 1];
+            let (x2, y2) = points[index];
+            points[index] = (
+                (1.0 - alpha) * x1 + alpha * x2,
+                (1.0 - alpha) * y1 + alpha * y2,
+            );
+        }
+    }
+
+    let (x, y) = points[degree];
+    return Vertex::new(x, y);
+}
+
+#[cfg(test)]
+mod parse_bspline {
+    use super::*;
+
+    fn action(coordinates: Vec<(f64, f64)>, scalars: Vec<f64>) -> Action {
+        return Action {
+            intent: String::from("include"),
+            geometry: String::from("bspline"),
+            scalars,
+            points: coordinates
+                .iter()
+                .map(|&(x, y)| Point { x, y, z: 0.0, id: None, metadata: None })
+                .collect(),
+            assemble: Vec::new(),
+            loops: Vec::new(),
+            segments: None,
+            tolerance: None,
+            pattern: None,
+            spacing: None,
+            tag: None,
+            max_edge_length: None,
+            params: None,
+        };
+    }
+
+    #[test]
+    fn linear_spline_is_control_polygon() {
+        let square = vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+        let polyline = parse(&action(square, vec![8.0, 1.0])).unwrap();
+        let coordinates: Vec<(f64, f64)> = polyline.vertices.iter().map(|v| (v.x, v.y)).collect();
+        assert_eq!(
+            coordinates,
+            vec![
+                (0.0, 2.0),
+                (0.0, 1.0),
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (2.0, 0.0),
+                (2.0, 1.0),
+                (2.0, 2.0),
+                (1.0, 2.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn cubic_spline_within_control_hull() {
+        let square = vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+        let polyline = parse(&action(square, Vec::new())).unwrap();
+        assert_eq!(polyline.vertices.len(), 100);
+
+        /* uniform cubic span k starts at (P[k - 3] + 4 P[k - 2] + P[k - 1]) / 6 */
+        let first = &polyline.vertices[0];
+        assert!((first.x - 5.0 / 3.0).abs() < 1e-12 && (first.y - 5.0 / 3.0).abs() < 1e-12);
+        assert!(polyline
+            .vertices
+            .iter()
+            .all(|v| v.x > 0.0 && v.x < 2.0 && v.y > 0.0 && v.y < 2.0));
+    }
+
+    #[test]
+    fn rejects_degenerate() {
+        let triangle = vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)];
+        assert!(parse(&action(triangle[..2].to_vec(), Vec::new())).is_err());
+        assert!(parse(&action(triangle.clone(), Vec::new())).is_err());
+        assert!(parse(&action(triangle.clone(), vec![2.0, 2.0])).is_err());
+        assert!(parse(&action(triangle, vec![10.0, 2.0])).is_ok());
+    }
+} /* end - parse_bspline tests */
@@ -3,29 +3,73 @@ use std::rc::Rc;
 
 use nlsn_delaunay::{
     elements::{polyline::*, vertex::*},
+    planar::triangulation_error::TriangulationError,
 };
 
-pub fn parse(action: &Action) -> Result<Polyline, ()> {
+pub fn parse(action: &Action) -> Result<Polyline, TriangulationError> {
     let defined_by_center_radius = action.scalars.len() >= 1 && action.points.len() == 1;
     if defined_by_center_radius {
         let mut vertices: Vec<Rc<Vertex>> = Vec::new();
         let radius = *action.scalars.get(0).unwrap();
         let center = action.points.get(0).unwrap();
-        let resolution: usize = match action.scalars.get(1) {
-            Some(value) => (value.round() as usize),
-            None => 100,
-        };
+        let turn = std::f64::consts::PI * 2.0;
+        let resolution: usize = segment_count(action, 1, radius, turn, 100)?;
 
-        let dphi = std::f64::consts::PI * 2.0 / resolution as f64;
+        let dphi = turn / resolution as f64;
         for index in 0..resolution {
             let angle: f64 = dphi * index as f64;
             let vertex = get_circle_point(radius, angle, center);
             vertices.push(Rc::new(vertex));
         }
 
-        return Ok(Polyline::new_closed(vertices).unwrap());
+        match Polyline::new_closed(vertices) {
+            Some(polyline) => return Ok(polyline),
+            None => {
+                return Err(TriangulationError::DegenerateInput(String::from(
+                    "circle resolution below 3",
+                )))
+            }
+        }
+    }
+    return Err(TriangulationError::DegenerateInput(String::from(
+        "circle requires a center point and a radius",
+    )));
+}
+
+/**
+ * Count of segments of a circular arc of the radius, spanning the sweep
+ * angle: the segments of the action, or its resolution scalar at the
+ * index, or the least count keeping the chords within the tolerance of
+ * the action from the arc, or else the default count.
+ */
+pub fn segment_count(
+    action: &Action,
+    scalar_index: usize,
+    radius: f64,
+    sweep: f64,
+    default_count: usize,
+) -> Result<usize, TriangulationError> {
+    if let Some(segments) = action.segments {
+        return Ok(segments);
+    }
+    if let Some(value) = action.scalars.get(scalar_index) {
+        return Ok(value.round() as usize);
+    }
+    match action.tolerance {
+        Some(tolerance) if tolerance > 0.0 => {
+            /* sagitta of a chord spanning the angle phi: r (1 - cos(phi / 2)) */
+            let max_angle = 2.0 * (1.0 - (tolerance / radius).min(2.0)).acos();
+            let count = (sweep / max_angle).ceil() as usize;
+            return Ok(count.max(3));
+        }
+        Some(tolerance) => {
+            return Err(TriangulationError::DegenerateInput(format!(
+                "non positive chord tolerance {}",
+                tolerance
+            )))
+        }
+        None => return Ok(default_count),
     }
-    return Err(());
 }
 
 fn get_circle_point(
@@ -36,4 +80,72 @@ fn get_circle_point(
     let dx = radius * angle.cos();
     let dy = radius * angle.sin();
     return Vertex::new(center.x + dx, center.y + dy);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod segment_count {
+    use super::*;
+
+    fn action(scalars: Vec<f64>, segments: Option<usize>, tolerance: Option<f64>) -> Action {
+        return Action {
+            intent: String::from("include"),
+            geometry: String::from("circle"),
+            scalars,
+            points: vec![Point {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+                id: None,
+                metadata: None,
+            }],
+            assemble: Vec::new(),
+            loops: Vec::new(),
+            segments,
+            tolerance,
+            pattern: None,
+            spacing: None,
+            tag: None,
+            max_edge_length: None,
+            params: None,
+        };
+    }
+
+    fn max_sagitta(polyline: &Polyline, radius: f64) -> f64 {
+        return polyline
+            .into_edges()
+            .iter()
+            .map(|edge| {
+                let (x, y) = ((edge.v1.x + edge.v2.x) / 2.0, (edge.v1.y + edge.v2.y) / 2.0);
+                return radius - (x * x + y * y).sqrt();
+            })
+            .fold(0.0, f64::max);
+    }
+
+    #[test]
+    fn precedence() {
+        let turn = std::f64::consts::PI * 2.0;
+        let count = |action: &Action| segment_count(action, 1, 1.0, turn, 100).unwrap();
+        assert_eq!(count(&action(vec![1.0], None, None)), 100);
+        assert_eq!(count(&action(vec![1.0, 12.0], None, Some(0.1))), 12);
+        assert_eq!(count(&action(vec![1.0, 12.0], Some(20), Some(0.1))), 20);
+        assert_eq!(count(&action(vec![1.0], None, Some(10.0))), 3);
+        assert!(segment_count(&action(vec![1.0], None, Some(0.0)), 1, 1.0, turn, 100).is_err());
+    }
+
+    #[test]
+    fn adapts_to_radius() {
+        let tolerance = 1e-2;
+        let small = parse(&action(vec![0.1], None, Some(tolerance))).unwrap();
+        let large = parse(&action(vec![10.0], None, Some(tolerance))).unwrap();
+        assert!(small.vertices.len() < 20);
+        assert!(large.vertices.len() > 50);
+
+        for (polyline, radius) in vec![(small, 0.1), (large, 10.0)] {
+            let count = polyline.vertices.len() as f64;
+            assert!(max_sagitta(&polyline, radius) <= tolerance);
+            /* one segment less would exceed the tolerance */
+            let phi = std::f64::consts::PI * 2.0 / (count - 1.0);
+            assert!(radius * (1.0 - (phi / 2.0).cos()) > tolerance);
+        }
+    }
+} /* end - segment_count tests */
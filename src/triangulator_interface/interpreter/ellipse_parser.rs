@@ -0,0 +1,102 @@
+use crate::json_serializar::models::action::Action;
+use crate::triangulator_interface::interpreter::circle_parser;
+use std::rc::Rc;
+
+use nlsn_delaunay::{
+    elements::{polyline::*, vertex::*},
+    planar::triangulation_error::TriangulationError,
+};
+
+/**
+ * Parses an axis aligned ellipse given by its center point and its x and
+ * y radii scalars, optionally followed by the count of segments of its
+ * discretization (100 by default), which may also be given by the segments
+ * or the chord tolerance of the action. Counterclockwise oriented.
+ */
+pub fn parse(action: &Action) -> Result<Polyline, TriangulationError> {
+    let (center, rx, ry) = match (
+        action.points.get(0),
+        action.scalars.get(0),
+        action.scalars.get(1),
+    ) {
+        (Some(center), Some(&rx), Some(&ry)) if action.points.len() == 1 => (center, rx, ry),
+        _ => {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "ellipse requires a center point and two radii",
+            )))
+        }
+    };
+    if !(rx > 0.0 && ry > 0.0) {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "ellipse with non positive radius",
+        )));
+    }
+    /* chords are the farthest from the curve where it is the sharpest */
+    let turn = std::f64::consts::PI * 2.0;
+    let resolution: usize = circle_parser::segment_count(action, 2, rx.max(ry), turn, 100)?;
+
+    let dphi = turn / resolution as f64;
+    let vertices: Vec<Rc<Vertex>> = (0..resolution)
+        .map(|index| dphi * index as f64)
+        .map(|angle| Vertex::new(center.x + rx * angle.cos(), center.y + ry * angle.sin()))
+        .map(Rc::new)
+        .collect();
+
+    match Polyline::new_closed(vertices) {
+        Some(polyline) => return Ok(polyline),
+        None => {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "ellipse resolution below 3",
+            )))
+        }
+    }
+} /* end - parse */
+
+#[cfg(test)]
+mod parse_ellipse {
+    use super::*;
+    use crate::json_serializar::models::point::Point;
+
+    fn action(scalars: Vec<f64>) -> Action {
+        return Action {
+            intent: String::from("include"),
+            geometry: String::from("ellipse"),
+            scalars,
+            points: vec![Point {
+                x: 1.0,
+                y: 1.0,
+                z: 0.0,
+                id: None,
+                metadata: None,
+            }],
+            assemble: Vec::new(),
+            loops: Vec::new(),
+            segments: None,
+            tolerance: None,
+            pattern: None,
+            spacing: None,
+            tag: None,
+            max_edge_length: None,
+            params: None,
+        };
+    }
+
+    #[test]
+    fn discretization() {
+        let polyline = parse(&action(vec![2.0, 1.0, 4.0])).unwrap();
+        let expected = vec![(3.0, 1.0), (1.0, 2.0), (-1.0, 1.0), (1.0, 0.0)];
+        assert_eq!(polyline.vertices.len(), 4);
+        for (vertex, (x, y)) in polyline.vertices.iter().zip(expected) {
+            assert!((vertex.x - x).abs() < 1e-12 && (vertex.y - y).abs() < 1e-12);
+        }
+
+        assert_eq!(parse(&action(vec![2.0, 1.0])).unwrap().vertices.len(), 100);
+    }
+
+    #[test]
+    fn rejects_degenerate() {
+        assert!(parse(&action(vec![2.0])).is_err());
+        assert!(parse(&action(vec![2.0, -1.0])).is_err());
+        assert!(parse(&action(vec![2.0, 1.0, 2.0])).is_err());
+    }
+} /* end - parse_ellipse tests */
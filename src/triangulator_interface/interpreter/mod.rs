@@ -1,6 +1,12 @@
+pub mod arc_parser;
+pub mod bspline_parser;
 pub mod circle_parser;
+pub mod ellipse_parser;
 pub mod polyline_parser;
+pub mod rectangle_parser;
 pub mod refine_params_parser;
+pub mod seed_parser;
+pub mod seed_point_parser;
 pub mod segments_parser;
 pub mod vertices_parser;
 
@@ -11,7 +17,12 @@ use crate::json_serializar::models::{action::Action, input::TriangulationInput};
 
 use nlsn_delaunay::{
     elements::{edge::*, polyline::*, vertex::*},
-    planar::refine_params::RefineParams,
+    planar::{
+        generators::SeedPattern,
+        refine_params::{RefineParams, RegionParams},
+        triangulation_error::TriangulationError,
+        vertex_attributes::AttributeTable,
+    },
 };
 
 pub fn parse(
@@ -21,20 +32,61 @@ pub fn parse(
         Vec<Rc<Polyline>>,
         Vec<Rc<Polyline>>,
         HashSet<Rc<Edge>>,
+        Vec<Rc<Polyline>>,
         HashSet<Rc<Vertex>>,
+        Vec<(SeedPattern, f64)>,
         RefineParams,
     ),
-    (),
+    TriangulationError,
 > {
     let mut inclusion_domains: Vec<Rc<Polyline>> = Vec::new();
     let mut removal_domains: Vec<Rc<Polyline>> = Vec::new();
     let mut segment_constraints: HashSet<Rc<Edge>> = HashSet::new();
+    let mut polyline_constraints: Vec<Rc<Polyline>> = Vec::new();
     let mut vertices_constraints: HashSet<Rc<Vertex>> = HashSet::new();
+    let mut seeds: Vec<(SeedPattern, f64)> = Vec::new();
+    let mut regions: Vec<RegionParams> = Vec::new();
 
-    for action in input.actions.iter() {
+    for (index, action) in input.actions.iter().enumerate() {
+        let included_count = inclusion_domains.len();
+        let removed_count = removal_domains.len();
         match action.geometry.as_str() {
+            "polyline" if !action.loops.is_empty() => {
+                match polyline_parser::parse_loops(
+                    action,
+                    input.params.welding_tolerance,
+                    input.params.simplify_tolerance,
+                ) {
+                    Ok((outer_loops, inner_loops)) => match action.intent.as_str() {
+                        "include" => {
+                            inclusion_domains.extend(outer_loops.into_iter().map(Rc::new));
+                            removal_domains.extend(inner_loops.into_iter().map(Rc::new));
+                        }
+                        "remove" => {
+                            removal_domains.extend(outer_loops.into_iter().map(Rc::new));
+                            inclusion_domains.extend(inner_loops.into_iter().map(Rc::new));
+                        }
+                        _ => return Err(unknown_intent(index, action)),
+                    },
+                    Err(error) => return Err(at_action(index, error)),
+                };
+            }
+            "polyline" if action.intent == "constraint" => {
+                match polyline_parser::parse_opened(
+                    action,
+                    input.params.welding_tolerance,
+                    input.params.simplify_tolerance,
+                ) {
+                    Ok(polyline) => polyline_constraints.push(Rc::new(polyline)),
+                    Err(error) => return Err(at_action(index, error)),
+                };
+            }
             "polyline" => {
-                match polyline_parser::parse(action) {
+                match polyline_parser::parse(
+                    action,
+                    input.params.welding_tolerance,
+                    input.params.simplify_tolerance,
+                ) {
                     Ok(polyline) => match action.intent.as_str() {
                         "include" => {
                             inclusion_domains.push(Rc::new(polyline));
@@ -42,13 +94,13 @@ pub fn parse(
                         "remove" => {
                             removal_domains.push(Rc::new(polyline));
                         }
-                        _ => return Err(()),
+                        _ => return Err(unknown_intent(index, action)),
                     },
-                    Err(_) => return Err(()),
+                    Err(error) => return Err(at_action(index, error)),
                 };
             }
-            "circle" => {
-                match circle_parser::parse(action) {
+            "circle" | "rectangle" | "ellipse" | "arc" | "bspline" => {
+                match parse_shape(action) {
                     Ok(polyline) => match action.intent.as_str() {
                         "include" => {
                             inclusion_domains.push(Rc::new(polyline));
@@ -56,9 +108,9 @@ pub fn parse(
                         "remove" => {
                             removal_domains.push(Rc::new(polyline));
                         }
-                        _ => return Err(()),
+                        _ => return Err(unknown_intent(index, action)),
                     },
-                    Err(_) => return Err(()),
+                    Err(error) => return Err(at_action(index, error)),
                 };
             }
             "segments" => {
@@ -71,9 +123,9 @@ pub fn parse(
                                 .cloned()
                                 .collect();
                         }
-                        _ => return Err(()),
+                        _ => return Err(unknown_intent(index, action)),
                     },
-                    Err(_) => return Err(()),
+                    Err(error) => return Err(at_action(index, error)),
                 };
             }
             "vertices" => {
@@ -86,22 +138,185 @@ pub fn parse(
                                 .cloned()
                                 .collect();
                         }
-                        _ => return Err(()),
+                        _ => return Err(unknown_intent(index, action)),
+                    },
+                    Err(error) => return Err(at_action(index, error)),
+                };
+            }
+            "seed" => {
+                match seed_parser::parse(action) {
+                    Ok(seed) => match action.intent.as_str() {
+                        "constraint" => seeds.push(seed),
+                        _ => return Err(unknown_intent(index, action)),
                     },
-                    Err(_) => return Err(()),
+                    Err(error) => return Err(at_action(index, error)),
                 };
             }
-            _ => return Err(()),
+            "seed_point" => {
+                /* seeds mark the triangulation itself, see parse_hole_seeds */
+                match seed_point_parser::parse(action) {
+                    Ok(_) => match action.intent.as_str() {
+                        "hole" | "region" => {}
+                        _ => return Err(unknown_intent(index, action)),
+                    },
+                    Err(error) => return Err(at_action(index, error)),
+                };
+            }
+            geometry => {
+                return Err(TriangulationError::DegenerateInput(format!(
+                    "actions[{}]: unknown geometry {}",
+                    index, geometry
+                )))
+            }
         } /* end - match geometry */
+
+        /* the regions added by the action are refined by its own params */
+        let added_domains = inclusion_domains
+            .iter()
+            .skip(included_count)
+            .map(|domain| (domain, false))
+            .chain(removal_domains.iter().skip(removed_count).map(|domain| (domain, true)));
+        for (domain, hole) in added_domains {
+            if let Some(region) = refine_params_parser::parse_region(action, domain, hole) {
+                regions.push(region);
+            }
+        }
     } /* end - for action */
 
-    let refine_params = refine_params_parser::parse(&input.params).unwrap();
+    let refine_params = refine_params_parser::parse(&input.params, &inclusion_domains, regions)?;
 
     return Ok((
         inclusion_domains,
         removal_domains,
         segment_constraints,
+        polyline_constraints,
         vertices_constraints,
+        seeds,
         refine_params,
     ));
 } /* end - parse */
+
+/**
+ * Attributes of every input point given an id or metadata, whatever the
+ * action, keyed by the vertex at the point.
+ */
+pub fn parse_attributes(input: &TriangulationInput) -> AttributeTable {
+    let mut attributes = AttributeTable::new();
+    for action in input.actions.iter() {
+        for point in action.points.iter().chain(action.loops.iter().flatten()) {
+            if let Some(point_attributes) = point.attributes() {
+                attributes.insert(&Rc::new(Vertex::new(point.x, point.y)), point_attributes);
+            }
+        }
+    }
+    return attributes;
+}
+
+/**
+ * Seed points of holes, carved once triangulated. Seeds are checked by
+ * parse, thus invalid ones are skipped.
+ */
+pub fn parse_hole_seeds(input: &TriangulationInput) -> Vec<Rc<Vertex>> {
+    return input
+        .actions
+        .iter()
+        .filter(|action| action.geometry == "seed_point" && action.intent == "hole")
+        .filter_map(|action| seed_point_parser::parse(action).ok())
+        .collect();
+}
+
+/**
+ * Discretizes the closed shapes given by parameters into polylines.
+ */
+pub fn parse_shape(action: &Action) -> Result<Polyline, TriangulationError> {
+    match action.geometry.as_str() {
+        "circle" => return circle_parser::parse(action),
+        "rectangle" => return rectangle_parser::parse(action),
+        "ellipse" => return ellipse_parser::parse(action),
+        "arc" => return arc_parser::parse(action),
+        "bspline" => return bspline_parser::parse(action),
+        geometry => {
+            return Err(TriangulationError::DegenerateInput(format!(
+                "{} is not a shape",
+                geometry
+            )))
+        }
+    }
+}
+
+fn unknown_intent(index: usize, action: &Action) -> TriangulationError {
+    return TriangulationError::DegenerateInput(format!(
+        "actions[{}]: unknown intent {} for {} geometry",
+        index, action.intent, action.geometry
+    ));
+}
+
+/**
+ * Locates degenerate input errors at the action they come from.
+ */
+fn at_action(index: usize, error: TriangulationError) -> TriangulationError {
+    match error {
+        TriangulationError::DegenerateInput(message) => {
+            return TriangulationError::DegenerateInput(format!(
+                "actions[{}]: {}",
+                index, message
+            ));
+        }
+        error => return error,
+    }
+}
+
+#[cfg(test)]
+mod region_params {
+    use super::*;
+
+    #[test]
+    fn region_overrides() {
+        let input: TriangulationInput = serde_json::from_str(
+            "{
+                \"name\": \"regions\",
+                \"actions\": [
+                    {
+                        \"intent\": \"include\",
+                        \"geometry\": \"rectangle\",
+                        \"points\": [{ \"x\": 0.0, \"y\": 0.0 }],
+                        \"scalars\": [4.0, 2.0]
+                    },
+                    {
+                        \"intent\": \"include\",
+                        \"geometry\": \"rectangle\",
+                        \"points\": [{ \"x\": 6.0, \"y\": 0.0 }],
+                        \"scalars\": [2.0, 2.0],
+                        \"max_edge_length\": 0.5
+                    },
+                    {
+                        \"intent\": \"remove\",
+                        \"geometry\": \"circle\",
+                        \"points\": [{ \"x\": 2.0, \"y\": 1.0 }],
+                        \"scalars\": [0.5, 8],
+                        \"params\": { \"max_area\": 0.01, \"quality\": 1.2 }
+                    }
+                ],
+                \"params\": { \"quality\": 2.0, \"max_edge_length\": 1.5 }
+            }",
+        )
+        .unwrap();
+
+        let (inclusion_domains, removal_domains, _, _, _, _, refine_params) =
+            parse(&input).unwrap();
+        assert_eq!(refine_params.max_edge_length, Some(1.5));
+        assert_eq!(refine_params.regions.len(), 2);
+
+        let bounded = &refine_params.regions[0];
+        assert!(Rc::ptr_eq(&bounded.boundary, &inclusion_domains[1]));
+        assert!(!bounded.hole);
+        assert_eq!(bounded.max_edge_length, Some(0.5));
+        assert_eq!(bounded.max_area, None);
+
+        let hole = &refine_params.regions[1];
+        assert!(Rc::ptr_eq(&hole.boundary, &removal_domains[0]));
+        assert!(hole.hole);
+        assert_eq!((hole.max_area, hole.quality_ratio), (Some(0.01), Some(1.2)));
+        assert_eq!(hole.max_edge_length, None);
+    }
+} /* end - region_params tests */
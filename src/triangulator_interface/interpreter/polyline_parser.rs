@@ -1,18 +1,119 @@
 use crate::json_serializar::models::{action::Action, point::Point};
 use std::rc::Rc;
 
-use nlsn_delaunay::elements::{polyline::*, vertex::*};
+use nlsn_delaunay::{
+    elements::{polyline::*, vertex::*},
+    planar::triangulation_error::TriangulationError,
+    properties::{continence::*, orientation::*},
+};
 
-pub fn parse(action: &Action) -> Result<Polyline, ()> {
+/**
+ * Parses the polyline, welding its vertices closer than the tolerance, and
+ * simplifying it by the simplify tolerance, if any.
+ */
+pub fn parse(
+    action: &Action,
+    tolerance: f64,
+    simplify_tolerance: Option<f64>,
+) -> Result<Polyline, TriangulationError> {
+    return parse_points(&action.points, tolerance, simplify_tolerance);
+} /* end - parse */
+
+/**
+ * Parses the open polyline of a constraint, such as a crack or a fault
+ * line, welding its vertices closer than the tolerance, and simplifying it
+ * by the simplify tolerance, if any.
+ */
+pub fn parse_opened(
+    action: &Action,
+    tolerance: f64,
+    simplify_tolerance: Option<f64>,
+) -> Result<Polyline, TriangulationError> {
     let vertices: Vec<Rc<Vertex>> = action
         .points
+        .iter()
+        .map(|p| Rc::new(point_to_vertex(p)))
+        .collect();
+
+    let vertices = weld_vertices(&vertices, tolerance, true);
+    let segments = vertex_pairs(&vertices, true);
+    let split_segments = split_intersections(&segments);
+    if split_segments.len() > segments.len() {
+        return Err(TriangulationError::SelfIntersectingPolyline(vertices));
+    }
+
+    match Polyline::new_opened(vertices) {
+        Some(polyline) => return Ok(simplified(polyline, simplify_tolerance)),
+        None => {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "open polyline with less than 2 vertices",
+            )))
+        }
+    }
+} /* end - parse_opened */
+
+/**
+ * Parses every loop of a multi-loop polyline action, classifying them by
+ * nesting: loops contained by an even number of other loops are outer loops,
+ * while the remaining are inner loops. Exporters do not agree on loops
+ * orientation, so that every loop is returned counterclockwise oriented.
+ * Returns (outer loops, inner loops).
+ */
+pub fn parse_loops(
+    action: &Action,
+    tolerance: f64,
+    simplify_tolerance: Option<f64>,
+) -> Result<(Vec<Polyline>, Vec<Polyline>), TriangulationError> {
+    let mut loops: Vec<Polyline> = Vec::new();
+
+    if !action.points.is_empty() {
+        loops.push(parse_points(&action.points, tolerance, simplify_tolerance)?);
+    }
+
+    for points in action.loops.iter() {
+        loops.push(parse_points(points, tolerance, simplify_tolerance)?);
+    }
+
+    let mut outer_loops: Vec<Polyline> = Vec::new();
+    let mut inner_loops: Vec<Polyline> = Vec::new();
+
+    for (index, polyline) in loops.iter().enumerate() {
+        let reference = polyline.vertices.get(0).unwrap();
+        let depth = loops
+            .iter()
+            .enumerate()
+            .filter(|(other_index, _)| *other_index != index)
+            .filter(|(_, other)| other.contains(reference) == Some(Continence::Inside))
+            .count();
+
+        let polyline = polyline.ensure_ccw();
+        if depth % 2 == 0 {
+            outer_loops.push(polyline);
+        } else {
+            inner_loops.push(polyline);
+        }
+    }
+
+    return Ok((outer_loops, inner_loops));
+} /* end - parse_loops */
+
+fn parse_points(
+    points: &Vec<Point>,
+    tolerance: f64,
+    simplify_tolerance: Option<f64>,
+) -> Result<Polyline, TriangulationError> {
+    let vertices: Vec<Rc<Vertex>> = points
         .iter()
         .map(|p| point_to_vertex(p))
         .map(|v| Rc::new(v))
         .collect();
 
-    if vertices.is_empty() {
-        return Err(());
+    /* near duplicate vertices would accuse intersections */
+    let vertices = weld_vertices(&vertices, tolerance, false);
+    if vertices.len() < 3 {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "polyline with less than 3 vertices",
+        )));
     }
 
     let segments = vertex_pairs(&vertices, false);
@@ -22,12 +123,139 @@ pub fn parse(action: &Action) -> Result<Polyline, ()> {
             Corrupted data
             Segments split againts itself accused intersection
         */
-        return Err(());
+        return Err(TriangulationError::SelfIntersectingPolyline(vertices));
     }
 
-    return Ok(Polyline::new_closed(vertices).unwrap());
-} /* end - parse */
+    match Polyline::new_closed(vertices) {
+        Some(polyline) => return Ok(simplified(polyline, simplify_tolerance)),
+        None => {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "polyline with less than 3 vertices",
+            )))
+        }
+    }
+} /* end - parse_points */
+
+/* simplification keeps simple polylines simple, thus it follows validation */
+fn simplified(polyline: Polyline, simplify_tolerance: Option<f64>) -> Polyline {
+    match simplify_tolerance {
+        Some(simplify_tolerance) => return polyline.simplify(simplify_tolerance),
+        None => return polyline,
+    }
+}
 
 fn point_to_vertex(point: &Point) -> Vertex {
     Vertex::new(point.x, point.y)
 }
+
+#[cfg(test)]
+mod parse_loops {
+    use super::*;
+
+    fn points(coordinates: Vec<(f64, f64)>) -> Vec<Point> {
+        return coordinates
+            .iter()
+            .map(|&(x, y)| Point { x, y, z: 0.0, id: None, metadata: None })
+            .collect();
+    }
+
+    fn action(loops: Vec<Vec<Point>>) -> Action {
+        return Action {
+            intent: String::from("include"),
+            geometry: String::from("polyline"),
+            scalars: Vec::new(),
+            points: Vec::new(),
+            assemble: Vec::new(),
+            loops,
+            segments: None,
+            tolerance: None,
+            pattern: None,
+            spacing: None,
+            tag: None,
+            max_edge_length: None,
+            params: None,
+        };
+    }
+
+    #[test]
+    fn classifies_by_nesting() {
+        /* outer square, clockwise hole, and an island inside the hole */
+        let action = action(vec![
+            points(vec![(0.0, 0.0), (6.0, 0.0), (6.0, 6.0), (0.0, 6.0)]),
+            points(vec![(1.0, 1.0), (1.0, 5.0), (5.0, 5.0), (5.0, 1.0)]),
+            points(vec![(2.0, 2.0), (4.0, 2.0), (4.0, 4.0), (2.0, 4.0)]),
+        ]);
+
+        let (outer_loops, inner_loops) = parse_loops(&action, 1.0E-12, None).unwrap();
+        assert_eq!(outer_loops.len(), 2);
+        assert_eq!(inner_loops.len(), 1);
+
+        for polyline in outer_loops.iter().chain(inner_loops.iter()) {
+            let segments = vertex_pairs(&polyline.vertices, false);
+            assert_eq!(segments_orientation(&segments), Orientation::Counterclockwise);
+        }
+
+        let hole = inner_loops.get(0).unwrap();
+        assert!(hole.vertices.contains(&Rc::new(Vertex::new(1.0, 5.0))));
+    }
+
+    #[test]
+    fn points_as_loop() {
+        let mut action = action(vec![points(vec![
+            (1.0, 1.0),
+            (2.0, 1.0),
+            (2.0, 2.0),
+        ])]);
+        action.points = points(vec![(0.0, 0.0), (3.0, 0.0), (3.0, 3.0), (0.0, 3.0)]);
+
+        let (outer_loops, inner_loops) = parse_loops(&action, 1.0E-12, None).unwrap();
+        assert_eq!(outer_loops.len(), 1);
+        assert_eq!(inner_loops.len(), 1);
+        assert_eq!(outer_loops.get(0).unwrap().vertices.len(), 4);
+    }
+
+    #[test]
+    fn rejects_corrupted_loop() {
+        let action = action(vec![
+            points(vec![(0.0, 0.0), (3.0, 0.0), (3.0, 3.0), (0.0, 3.0)]),
+            points(vec![(1.0, 1.0), (2.0, 1.0)]),
+        ]);
+
+        assert!(parse_loops(&action, 1.0E-12, None).is_err());
+    }
+
+    #[test]
+    fn rejects_self_intersecting_loop() {
+        let action = action(vec![points(vec![
+            (0.0, 0.0),
+            (3.0, 3.0),
+            (3.0, 0.0),
+            (0.0, 3.0),
+        ])]);
+
+        match parse_loops(&action, 1.0E-12, None) {
+            Err(TriangulationError::SelfIntersectingPolyline(vertices)) => {
+                assert_eq!(vertices.len(), 4)
+            }
+            _ => panic!("Expected self intersecting polyline"),
+        }
+    }
+
+    #[test]
+    fn simplifies_loops() {
+        let action = action(vec![points(vec![
+            (0.0, 0.0),
+            (1.0, 0.001),
+            (2.0, 0.0),
+            (2.0, 2.0),
+            (1.0, 2.001),
+            (0.0, 2.0),
+        ])]);
+
+        let (outer_loops, _) = parse_loops(&action, 1.0E-12, None).unwrap();
+        assert_eq!(outer_loops.get(0).unwrap().vertices.len(), 6);
+
+        let (outer_loops, _) = parse_loops(&action, 1.0E-12, Some(0.01)).unwrap();
+        assert_eq!(outer_loops.get(0).unwrap().vertices.len(), 4);
+    }
+} /* end - parse_loops tests */
@@ -0,0 +1,122 @@
+use crate::json_serializar::models::action::Action;
+use std::rc::Rc;
+
+use nlsn_delaunay::{
+    elements::{polyline::*, vertex::*},
+    planar::triangulation_error::TriangulationError,
+};
+
+/**
+ * Parses a rectangle given by its lower left corner point and its width
+ * and height scalars, optionally followed by the count of segments each
+ * side is split into (1 by default). Counterclockwise oriented.
+ */
+pub fn parse(action: &Action) -> Result<Polyline, TriangulationError> {
+    let (corner, width, height) = match (
+        action.points.get(0),
+        action.scalars.get(0),
+        action.scalars.get(1),
+    ) {
+        (Some(corner), Some(&width), Some(&height)) if action.points.len() == 1 => {
+            (corner, width, height)
+        }
+        _ => {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "rectangle requires a corner point, a width and a height",
+            )))
+        }
+    };
+    if !(width > 0.0 && height > 0.0) {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "rectangle with non positive width or height",
+        )));
+    }
+    let resolution: usize = match action.scalars.get(2) {
+        Some(value) => value.round() as usize,
+        None => 1,
+    };
+    if resolution < 1 {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "rectangle resolution below 1",
+        )));
+    }
+
+    let corners = vec![
+        (corner.x, corner.y),
+        (corner.x + width, corner.y),
+        (corner.x + width, corner.y + height),
+        (corner.x, corner.y + height),
+    ];
+    let mut vertices: Vec<Rc<Vertex>> = Vec::new();
+    for (index, &(x1, y1)) in corners.iter().enumerate() {
+        let (x2, y2) = corners[(index + 1) % corners.len()];
+        for step in 0..resolution {
+            let t = step as f64 / resolution as f64;
+            vertices.push(Rc::new(Vertex::new(x1 + (x2 - x1) * t, y1 + (y2 - y1) * t)));
+        }
+    }
+
+    return Ok(Polyline::new_closed(vertices).unwrap());
+} /* end - parse */
+
+#[cfg(test)]
+mod parse_rectangle {
+    use super::*;
+    use crate::json_serializar::models::point::Point;
+
+    fn action(scalars: Vec<f64>) -> Action {
+        return Action {
+            intent: String::from("include"),
+            geometry: String::from("rectangle"),
+            scalars,
+            points: vec![Point {
+                x: 1.0,
+                y: 2.0,
+                z: 0.0,
+                id: None,
+                metadata: None,
+            }],
+            assemble: Vec::new(),
+            loops: Vec::new(),
+            segments: None,
+            tolerance: None,
+            pattern: None,
+            spacing: None,
+            tag: None,
+            max_edge_length: None,
+            params: None,
+        };
+    }
+
+    #[test]
+    fn corners() {
+        let polyline = parse(&action(vec![3.0, 2.0])).unwrap();
+        assert_eq!(
+            polyline.vertices,
+            vec![
+                Rc::new(Vertex::new(1.0, 2.0)),
+                Rc::new(Vertex::new(4.0, 2.0)),
+                Rc::new(Vertex::new(4.0, 4.0)),
+                Rc::new(Vertex::new(1.0, 4.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_sides() {
+        let polyline = parse(&action(vec![3.0, 2.0, 3.0])).unwrap();
+        assert_eq!(polyline.vertices.len(), 12);
+        assert_eq!(polyline.vertices[1], Rc::new(Vertex::new(2.0, 2.0)));
+        assert_eq!(
+            polyline.vertices[4],
+            Rc::new(Vertex::new(4.0, 2.0 + 2.0 / 3.0))
+        );
+    }
+
+    #[test]
+    fn rejects_degenerate() {
+        assert!(parse(&action(vec![3.0])).is_err());
+        assert!(parse(&action(vec![3.0, 0.0])).is_err());
+        assert!(parse(&action(vec![3.0, 2.0, 0.0])).is_err());
+    }
+} /* end - parse_rectangle tests */
@@ -1,10 +1,201 @@
-use crate::json_serializar::models::input;
+use std::rc::Rc;
 
-use nlsn_delaunay::planar::refine_params;
+use crate::json_serializar::models::{action::Action, input};
 
-pub fn parse(params: &input::RefineParams) -> Result<refine_params::RefineParams, ()> {
-    return Ok(refine_params::RefineParams {
+use nlsn_delaunay::{
+    elements::{bounding_box::*, polyline::*, vertex::*},
+    planar::{refine_params, triangulation_error::TriangulationError},
+};
+
+/**
+ * Parses the refine params. Profile values are scaled to the bounding box
+ * of the inclusion domains, and explicit values take precedence over them.
+ * Region params come from the actions, as parsed by parse_region. Params
+ * that cannot be met are rejected.
+ */
+pub fn parse(
+    params: &input::RefineParams,
+    inclusion_domains: &Vec<Rc<Polyline>>,
+    regions: Vec<refine_params::RegionParams>,
+) -> Result<refine_params::RefineParams, TriangulationError> {
+    let profile = match &params.profile {
+        Some(name) => match refine_params::RefineProfile::from_name(name) {
+            Some(profile) => Some(profile),
+            None => {
+                return Err(TriangulationError::DegenerateInput(format!(
+                    "unknown refine profile {}",
+                    name
+                )))
+            }
+        },
+        None => None,
+    };
+
+    let profile_params = profile.map(|profile| {
+        let vertices: Vec<Rc<Vertex>> = inclusion_domains
+            .iter()
+            .map(|domain| domain.vertices.iter().cloned())
+            .flatten()
+            .collect();
+        let domain_area = match BoundingBox::from_vertices(vertices) {
+            Some(bbox) => (bbox.destin.x - bbox.origin.x) * (bbox.destin.y - bbox.origin.y),
+            None => 0.0,
+        };
+        return profile.params(domain_area);
+    });
+
+    let quality_ratio = match (params.quality, &profile_params) {
+        (Some(quality), _) => quality,
+        (None, Some(profile_params)) => profile_params.quality_ratio,
+        (None, None) => {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "refine params need either a quality or a profile",
+            )))
+        }
+    };
+
+    let refine_params = refine_params::RefineParams {
+        max_area: params
+            .max_area
+            .or(profile_params.and_then(|profile_params| profile_params.max_area)),
+        quality_ratio,
+        max_edge_length: params.max_edge_length,
+        regions,
+    };
+    return Ok(refine_params.validated()?);
+} /* end - parse */
+
+/**
+ * Parses the refine params of a region added by the action, if it has any.
+ * The max_edge_length field of the action stands for the one of its params.
+ */
+pub fn parse_region(
+    action: &Action,
+    boundary: &Rc<Polyline>,
+    hole: bool,
+) -> Option<refine_params::RegionParams> {
+    if action.params.is_none() && action.max_edge_length.is_none() {
+        return None;
+    }
+    let params = action.params.clone().unwrap_or_default();
+
+    return Some(refine_params::RegionParams {
+        boundary: Rc::clone(boundary),
+        hole,
         max_area: params.max_area,
         quality_ratio: params.quality,
+        max_edge_length: params.max_edge_length.or(action.max_edge_length),
     });
-} /* end - parse */
+} /* end - parse_region */
+
+/**
+ * Overrides the input refine params by the command line ones. The minimum
+ * angle is given in degrees, as the radius-edge ratio bounding the smallest
+ * angle by asin(1 / 2B).
+ */
+pub fn override_params(
+    params: &mut input::RefineParams,
+    max_area: Option<f64>,
+    quality_ratio: Option<f64>,
+    min_angle: Option<f64>,
+) -> Result<(), TriangulationError> {
+    if let Some(max_area) = max_area {
+        if !(max_area > 0.0) {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "max area must be positive",
+            )));
+        }
+        params.max_area = Some(max_area);
+    }
+
+    if let Some(quality_ratio) = quality_ratio {
+        if !(quality_ratio > 0.0) {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "quality ratio must be positive",
+            )));
+        }
+        params.quality = Some(quality_ratio);
+    }
+
+    if let Some(min_angle) = min_angle {
+        if !(min_angle > 0.0 && min_angle < 60.0) {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "min angle must be between 0 and 60 degrees",
+            )));
+        }
+        params.quality = Some(refine_params::RefineParams::from_min_angle(min_angle).quality_ratio);
+    }
+
+    return Ok(());
+} /* end - override_params */
+
+#[cfg(test)]
+mod parse_profile {
+    use super::*;
+
+    #[test]
+    fn profiles() {
+        let domain = Rc::new(
+            Polyline::new_closed(vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(20.0, 0.0)),
+                Rc::new(Vertex::new(20.0, 10.0)),
+            ])
+            .unwrap(),
+        );
+
+        let params: input::RefineParams =
+            serde_json::from_str("{ \"profile\": \"standard\" }").unwrap();
+        let refine_params = parse(&params, &vec![Rc::clone(&domain)], Vec::new()).unwrap();
+        assert_eq!(refine_params.max_area, Some(1.0));
+        assert_eq!(refine_params.quality_ratio, 2.0_f64.sqrt());
+
+        /* explicit values override the profile */
+        let params: input::RefineParams =
+            serde_json::from_str("{ \"profile\": \"draft\", \"quality\": 1.0 }").unwrap();
+        let refine_params = parse(&params, &vec![Rc::clone(&domain)], Vec::new()).unwrap();
+        assert_eq!(refine_params.max_area, Some(4.0));
+        assert_eq!(refine_params.quality_ratio, 1.0);
+
+        let params: input::RefineParams =
+            serde_json::from_str("{ \"profile\": \"coarse\" }").unwrap();
+        assert!(parse(&params, &vec![Rc::clone(&domain)], Vec::new()).is_err());
+
+        let params: input::RefineParams = serde_json::from_str("{}").unwrap();
+        assert!(parse(&params, &vec![Rc::clone(&domain)], Vec::new()).is_err());
+
+        /* params that cannot be met */
+        let params: input::RefineParams =
+            serde_json::from_str("{ \"quality\": 0.5 }").unwrap();
+        assert!(matches!(
+            parse(&params, &vec![domain], Vec::new()),
+            Err(TriangulationError::InvalidParams(_))
+        ));
+    }
+} /* end - parse_profile tests */
+
+#[cfg(test)]
+mod override_params {
+    use super::*;
+
+    #[test]
+    fn overrides() {
+        let mut params: input::RefineParams =
+            serde_json::from_str("{ \"max_area\": 2.0, \"quality\": 2.0 }").unwrap();
+
+        override_params(&mut params, None, None, None).unwrap();
+        assert_eq!((params.max_area, params.quality), (Some(2.0), Some(2.0)));
+
+        override_params(&mut params, Some(0.5), Some(1.5), None).unwrap();
+        assert_eq!((params.max_area, params.quality), (Some(0.5), Some(1.5)));
+
+        /* 30 degrees is a unit radius-edge ratio */
+        override_params(&mut params, None, None, Some(30.0)).unwrap();
+        assert!((params.quality.unwrap() - 1.0).abs() < 1e-12);
+
+        assert!(override_params(&mut params, Some(0.0), None, None).is_err());
+        assert!(override_params(&mut params, None, Some(-1.0), None).is_err());
+        assert!(override_params(&mut params, None, None, Some(60.0)).is_err());
+        assert!(override_params(&mut params, None, None, Some(std::f64::NAN)).is_err());
+    }
+} /* end - override_params tests */
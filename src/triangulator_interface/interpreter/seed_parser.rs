@@ -0,0 +1,36 @@
+use crate::json_serializar::models::action::Action;
+
+use nlsn_delaunay::planar::{generators::SeedPattern, triangulation_error::TriangulationError};
+
+/**
+ * Parses the pattern, grid by default, and the spacing of seed points.
+ */
+pub fn parse(action: &Action) -> Result<(SeedPattern, f64), TriangulationError> {
+    let pattern = match &action.pattern {
+        Some(name) => match SeedPattern::from_name(name) {
+            Some(pattern) => pattern,
+            None => {
+                return Err(TriangulationError::DegenerateInput(format!(
+                    "unknown seed pattern {}",
+                    name
+                )))
+            }
+        },
+        None => SeedPattern::Grid,
+    };
+
+    match action.spacing {
+        Some(spacing) if spacing > 0.0 => return Ok((pattern, spacing)),
+        Some(spacing) => {
+            return Err(TriangulationError::DegenerateInput(format!(
+                "non positive seed spacing {}",
+                spacing
+            )))
+        }
+        None => {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "seed without spacing",
+            )))
+        }
+    }
+} /* end - parse */
@@ -0,0 +1,26 @@
+use crate::json_serializar::models::action::Action;
+
+use std::rc::Rc;
+
+use nlsn_delaunay::{elements::vertex::*, planar::triangulation_error::TriangulationError};
+
+/**
+ * Parses the single point of a seed point, marking the hole or the region
+ * around it. Region seeds are tagged.
+ */
+pub fn parse(action: &Action) -> Result<Rc<Vertex>, TriangulationError> {
+    if action.points.len() != 1 {
+        return Err(TriangulationError::DegenerateInput(format!(
+            "seed point with {} points",
+            action.points.len()
+        )));
+    }
+    if action.intent == "region" && action.tag.is_none() {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "region seed point without tag",
+        )));
+    }
+
+    let point = &action.points[0];
+    return Ok(Rc::new(Vertex::new(point.x, point.y)));
+} /* end - parse */
@@ -2,16 +2,21 @@ use crate::json_serializar::models::{action::Action, point::Point};
 use std::collections::HashSet;
 use std::rc::Rc;
 
-use nlsn_delaunay::elements::{edge::*, vertex::*};
+use nlsn_delaunay::{
+    elements::{edge::*, vertex::*},
+    planar::triangulation_error::TriangulationError,
+};
 
-pub fn parse(action: &Action) -> Result<HashSet<Rc<Edge>>, ()> {
+pub fn parse(action: &Action) -> Result<HashSet<Rc<Edge>>, TriangulationError> {
     let mut edges: HashSet<Rc<Edge>> = HashSet::new();
 
     /* Case segments are connected by assemble */
     if !action.assemble.is_empty() {
         for set in action.assemble.iter() {
             if set.len() < 2 {
-                return Err(());
+                return Err(TriangulationError::DegenerateInput(String::from(
+                    "assemble sets require 2 point indexes",
+                )));
             }
             let p1_index: usize = *set.get(0).unwrap();
             let p2_index: usize = *set.get(1).unwrap();
@@ -30,7 +35,9 @@ pub fn parse(action: &Action) -> Result<HashSet<Rc<Edge>>, ()> {
     /* If segments are defined every two points */
     if action.points.len() % 2 != 0 {
         /* odd number of points => corrupted data */
-        return Err(());
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "segments require an even number of points",
+        )));
     }
     for index in (0..action.points.len()).step_by(2) {
         let p1_index: usize = index;
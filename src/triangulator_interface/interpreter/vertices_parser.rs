@@ -2,9 +2,9 @@ use crate::json_serializar::models::{action::Action, point::Point};
 use std::collections::HashSet;
 use std::rc::Rc;
 
-use nlsn_delaunay::elements::vertex::*;
+use nlsn_delaunay::{elements::vertex::*, planar::triangulation_error::TriangulationError};
 
-pub fn parse(action: &Action) -> Result<HashSet<Rc<Vertex>>, ()> {
+pub fn parse(action: &Action) -> Result<HashSet<Rc<Vertex>>, TriangulationError> {
     let vertices: HashSet<Rc<Vertex>> = action
         .points
         .iter()
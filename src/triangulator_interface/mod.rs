@@ -1,5 +1,6 @@
 pub mod domain_evaluator;
 pub mod interpreter;
+pub mod regions;
 
 use std::collections::HashSet;
 use std::rc::Rc;
@@ -7,54 +8,220 @@ use std::rc::Rc;
 use crate::json_serializar::models::input::TriangulationInput;
 
 use nlsn_delaunay::{
-    elements::polyline::*,
-    planar::{refine_params::RefineParams, triangulator::Triangulator},
+    elements::{edge::*, polyline::*, vertex::*},
+    planar::{
+        refine_params::RefineParams, triangulation_error::TriangulationError,
+        triangulator::Triangulator,
+    },
+    properties::continence::Continence,
 };
 
-pub fn parse(input: &TriangulationInput) -> Result<(Triangulator, RefineParams), ()> {
-    let result = interpreter::parse(&input);
-
-    if result.is_err() {
-        return Err(());
-    }
-
+/**
+ * Triangulators of the input, one for each disconnected domain, as sorted
+ * by domain_evaluator::boundaries. Holes, hole seed points, segments,
+ * polylines and vertices go to the domain they lie in, while constraints
+ * lying in none go to the first one, whose insertion reports them. Seeds
+ * fill every domain.
+ */
+pub fn parse(
+    input: &TriangulationInput,
+) -> Result<(Vec<Triangulator>, RefineParams), TriangulationError> {
     let (
         inclusion_domains,    /* Vec<Rc<Polyline>> */
         removal_domains,      /* Vec<Rc<Polyline>> */
         segment_constraints,  /* HashSet<Rc<Edge>> */
+        polyline_constraints, /* Vec<Rc<Polyline>> */
         vertices_constraints, /* HashSet<Rc<Vertex>> */
+        seeds,                /* Vec<(SeedPattern, f64)> */
         refine_params,        /* RefineParams */
-    ) = result.unwrap();
-
-    let boundary: Rc<Polyline>;
-    let unused_removals: Vec<Rc<Polyline>>;
-    match domain_evaluator::boundary(&inclusion_domains, &removal_domains) {
-        Ok((polyline, unused)) => {
-            boundary = polyline;
-            unused_removals = unused;
-        }
-        Err(_) => return Err(()),
-    }
-    
-    let holes: HashSet<Rc<Polyline>> = domain_evaluator::holes(&boundary, &unused_removals);
-
-    let mut triangulator: Triangulator = Triangulator::new(&boundary);
-    for hole in holes.iter() {
-        let result = triangulator.insert_hole(hole);
-        if result.is_err() {
-            return Err(());
+    ) = interpreter::parse(&input)?;
+    let attributes = interpreter::parse_attributes(&input);
+    let hole_seeds = interpreter::parse_hole_seeds(&input);
+
+    let grid = input.params.intersection_grid.unwrap_or(0.0);
+    let (boundaries, unused_removals) =
+        domain_evaluator::boundaries(&inclusion_domains, &removal_domains, grid)?;
+
+    /* index of the boundary containing the point, the first one if none */
+    let component_of = |vertex: &Vertex| -> usize {
+        return boundaries
+            .iter()
+            .position(|boundary| boundary.contains(vertex) != Some(Continence::Outside))
+            .unwrap_or(0);
+    };
+
+    let mut triangulators: Vec<Triangulator> = Vec::new();
+    for (index, boundary) in boundaries.iter().enumerate() {
+        let holes: HashSet<Rc<Polyline>> = domain_evaluator::holes(boundary, &unused_removals, grid);
+
+        let mut triangulator: Triangulator =
+            match Triangulator::with_welding_tolerance(boundary, input.params.welding_tolerance) {
+                Some(triangulator) => triangulator,
+                None => {
+                    return Err(TriangulationError::DegenerateInput(String::from(
+                        "boundary with less than 3 vertices after welding",
+                    )))
+                }
+            };
+        triangulator.snap_tolerance = input.params.snap_tolerance;
+        triangulator.intersection_grid = input.params.intersection_grid;
+        triangulator.attributes = attributes.clone();
+        for hole in holes.iter() {
+            triangulator.insert_hole(hole)?;
         }
-    }
 
-    let result = triangulator.insert_segments(&segment_constraints);
-    if result.is_err() {
-        return Err(());
-    }
+        let segments: HashSet<Rc<Edge>> = segment_constraints
+            .iter()
+            .filter(|segment| component_of(&segment.midpoint()) == index)
+            .cloned()
+            .collect();
+        triangulator.insert_segments(&segments)?;
+        for polyline in polyline_constraints.iter() {
+            let first_edge = Edge::new(&polyline.vertices[0], &polyline.vertices[1]);
+            if component_of(&first_edge.midpoint()) == index {
+                triangulator.insert_polyline_constraint(polyline)?;
+            }
+        }
+        let vertices: HashSet<Rc<Vertex>> = vertices_constraints
+            .iter()
+            .filter(|vertex| component_of(vertex) == index)
+            .cloned()
+            .collect();
+        triangulator.insert_vertices(&vertices)?;
+        for (pattern, spacing) in seeds.iter() {
+            triangulator.insert_seed_points(*pattern, *spacing)?;
+        }
+        let component_hole_seeds: Vec<Rc<Vertex>> = hole_seeds
+            .iter()
+            .filter(|seed| component_of(seed) == index)
+            .cloned()
+            .collect();
+        triangulator.insert_hole_seeds(&component_hole_seeds)?;
 
-    let result = triangulator.insert_vertices(&vertices_constraints);
-    if result.is_err() {
-        return Err(());
+        triangulators.push(triangulator);
     }
 
-    return Ok((triangulator, refine_params));
+    return Ok((triangulators, refine_params));
 } /* end - parse */
+
+/**
+ * Inclusion polylines of the input, in actions order, as separate domains.
+ */
+pub fn inclusion_domains(
+    input: &TriangulationInput,
+) -> Result<Vec<Rc<Polyline>>, TriangulationError> {
+    let (inclusion_domains, _, _, _, _, _, _) = interpreter::parse(&input)?;
+    return Ok(inclusion_domains);
+}
+
+/**
+ * Constrained edges of the triangulation, as (boundaries, holes, segments).
+ * Refinement splits constraints without updating them, so that they are
+ * split at every triangulation vertex lying on them.
+ */
+pub fn constrained_edges(
+    triangulator: &Triangulator,
+) -> (Vec<Rc<Edge>>, Vec<Rc<Edge>>, Vec<Rc<Edge>>) {
+    let vertices: Vec<Rc<Vertex>> = triangulator
+        .triangulation
+        .borrow()
+        .vertices()
+        .iter()
+        .cloned()
+        .collect();
+
+    let boundary_edges: Vec<Rc<Edge>> = vec![&triangulator.boundary]
+        .into_iter()
+        .chain(triangulator.disjoint_boundaries.iter())
+        .map(|boundary| boundary.split_edges(&vertices).into_edges())
+        .flatten()
+        .collect();
+    let holes_edges: Vec<Rc<Edge>> = triangulator
+        .holes
+        .iter()
+        .map(|hole| hole.split_edges(&vertices).into_edges())
+        .flatten()
+        .collect();
+    let segments_edges: Vec<Rc<Edge>> = triangulator
+        .segments
+        .iter()
+        .filter_map(|segment| segment.as_polyline())
+        .map(|segment| segment.split_edges(&vertices).into_edges())
+        .flatten()
+        .collect::<HashSet<Rc<Edge>>>()
+        .into_iter()
+        .collect();
+
+    return (boundary_edges, holes_edges, segments_edges);
+} /* end - constrained_edges */
+
+#[cfg(test)]
+mod components {
+    use super::*;
+    use crate::json_serializar::models::output::TriangulationOutput;
+
+    #[test]
+    fn disjoint_inclusions() {
+        let input: TriangulationInput = serde_json::from_str(
+            "{
+                \"name\": \"components\",
+                \"actions\": [
+                    {
+                        \"intent\": \"include\",
+                        \"geometry\": \"polyline\",
+                        \"points\": [
+                            { \"x\": 5.0, \"y\": 0.0 },
+                            { \"x\": 9.0, \"y\": 0.0 },
+                            { \"x\": 9.0, \"y\": 4.0 },
+                            { \"x\": 5.0, \"y\": 4.0 }
+                        ]
+                    },
+                    {
+                        \"intent\": \"include\",
+                        \"geometry\": \"polyline\",
+                        \"points\": [
+                            { \"x\": 0.0, \"y\": 0.0 },
+                            { \"x\": 4.0, \"y\": 0.0 },
+                            { \"x\": 4.0, \"y\": 4.0 },
+                            { \"x\": 0.0, \"y\": 4.0 }
+                        ]
+                    },
+                    {
+                        \"intent\": \"remove\",
+                        \"geometry\": \"polyline\",
+                        \"points\": [
+                            { \"x\": 6.0, \"y\": 1.0 },
+                            { \"x\": 8.0, \"y\": 1.0 },
+                            { \"x\": 8.0, \"y\": 3.0 },
+                            { \"x\": 6.0, \"y\": 3.0 }
+                        ]
+                    }
+                ],
+                \"params\": { \"quality\": 1.0 }
+            }",
+        )
+        .unwrap();
+
+        let (mut components, _) = parse(&input).unwrap();
+        assert_eq!(components.len(), 2);
+
+        /* components are sorted by their lowest vertex, holes follow them */
+        assert_eq!(components[0].boundary.vertices[0].x, 0.0);
+        assert_eq!(components[0].holes.len(), 0);
+        assert_eq!(components[1].holes.len(), 1);
+
+        for component in components.iter_mut() {
+            component.try_triangulate().unwrap();
+        }
+        let mut triangulator = components.remove(0);
+        let component = components.remove(0);
+        assert!(triangulator.include_component(component).is_ok());
+        assert_eq!(triangulator.disjoint_boundaries.len(), 1);
+
+        let output = TriangulationOutput::from_triangulator(&input, &triangulator);
+        let first = output.triangles.iter().filter(|t| t.component == Some(0));
+        let second = output.triangles.iter().filter(|t| t.component == Some(1));
+        assert_eq!(first.count(), 2);
+        assert_eq!(second.count(), 8);
+    }
+} /* end - components tests */
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::json_serializar::models::{input::TriangulationInput, tag::Tag};
+use crate::triangulator_interface::interpreter::{
+    parse_shape, polyline_parser, seed_point_parser, segments_parser,
+};
+
+use nlsn_delaunay::{
+    elements::{edge::*, polyline::*, triangle::*, vertex::*},
+    planar::triangulator::Triangulator,
+    properties::continence::*,
+};
+
+/**
+ * Tagged input geometry. Domains are the outer loops of tagged inclusions,
+ * while edges are every tagged polyline, shape and segment edge, including
+ * the edges of open polyline constraints. Seeds are the points of region
+ * seed points, tagging the triangles bounded by the constraints around them.
+ * Later actions override the former ones, as they are drawn over them, while
+ * seeds override domains.
+ */
+pub struct Regions {
+    pub domains: Vec<(Rc<Polyline>, Tag)>,
+    pub edges: Vec<(Rc<Edge>, Tag)>,
+    pub seeds: Vec<(Rc<Vertex>, Tag)>,
+}
+
+impl Regions {
+    pub fn from_input(input: &TriangulationInput) -> Self {
+        let tolerance = input.params.welding_tolerance;
+        let simplify = input.params.simplify_tolerance;
+        let mut domains: Vec<(Rc<Polyline>, Tag)> = Vec::new();
+        let mut edges: Vec<(Rc<Edge>, Tag)> = Vec::new();
+        let mut seeds: Vec<(Rc<Vertex>, Tag)> = Vec::new();
+
+        for action in input.actions.iter() {
+            let tag = match &action.tag {
+                Some(tag) => tag,
+                None => continue,
+            };
+
+            let (outer_loops, inner_loops): (Vec<Polyline>, Vec<Polyline>) =
+                match action.geometry.as_str() {
+                    "polyline" if !action.loops.is_empty() => {
+                        match polyline_parser::parse_loops(action, tolerance, simplify) {
+                            Ok(loops) => loops,
+                            Err(_) => continue,
+                        }
+                    }
+                    "polyline" if action.intent == "constraint" => {
+                        if let Ok(polyline) = polyline_parser::parse_opened(action, tolerance, simplify) {
+                            edges.extend(polyline.into_edges().into_iter().map(|e| (e, tag.clone())));
+                        }
+                        continue;
+                    }
+                    "polyline" => match polyline_parser::parse(action, tolerance, simplify) {
+                        Ok(polyline) => (vec![polyline], Vec::new()),
+                        Err(_) => continue,
+                    },
+                    "circle" | "rectangle" | "ellipse" | "arc" | "bspline" => {
+                        match parse_shape(action) {
+                            Ok(polyline) => (vec![polyline], Vec::new()),
+                            Err(_) => continue,
+                        }
+                    }
+                    "segments" => {
+                        if let Ok(segments) = segments_parser::parse(action) {
+                            edges.extend(segments.into_iter().map(|s| (s, tag.clone())));
+                        }
+                        continue;
+                    }
+                    "seed_point" if action.intent == "region" => {
+                        if let Ok(seed) = seed_point_parser::parse(action) {
+                            seeds.push((seed, tag.clone()));
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+            for polyline in outer_loops.iter().chain(inner_loops.iter()) {
+                edges.extend(polyline.into_edges().into_iter().map(|e| (e, tag.clone())));
+            }
+
+            if action.intent == "include" {
+                domains.extend(outer_loops.into_iter().map(|p| (Rc::new(p), tag.clone())));
+            }
+        }
+
+        return Self {
+            domains,
+            edges,
+            seeds,
+        };
+    } /* end - from_input */
+
+    /**
+     * Tag of the latest tagged inclusion containing the triangle.
+     */
+    pub fn triangle_tag(&self, triangle: &Triangle) -> Option<Tag> {
+        let center = triangle.center();
+        return self
+            .domains
+            .iter()
+            .rev()
+            .find(|(domain, _)| domain.contains(&center) == Some(Continence::Inside))
+            .map(|(_, tag)| tag.clone());
+    }
+
+    /**
+     * Tags of the triangles of the seed regions of the triangulation, the
+     * latest seed of a region taking over.
+     */
+    pub fn seeded_tags(&self, triangulator: &Triangulator) -> HashMap<Rc<Triangle>, Tag> {
+        let latest_seeds: Vec<&(Rc<Vertex>, Tag)> = self.seeds.iter().rev().collect();
+        let vertices: Vec<Rc<Vertex>> =
+            latest_seeds.iter().map(|(seed, _)| Rc::clone(seed)).collect();
+        return triangulator
+            .seeded_regions(&vertices)
+            .into_iter()
+            .map(|(triangle, index)| (triangle, latest_seeds[index].1.clone()))
+            .collect();
+    }
+
+    /**
+     * Tag of the latest tagged edge the constrained edge lies on.
+     */
+    pub fn edge_tag(&self, edge: &Edge) -> Option<Tag> {
+        return self
+            .edges
+            .iter()
+            .rev()
+            .find(|(source, _)| source.contains(&edge.v1) && source.contains(&edge.v2))
+            .map(|(_, tag)| tag.clone());
+    }
+} /* end - Regions */
+
+#[cfg(test)]
+mod regions {
+    use super::*;
+
+    fn input() -> TriangulationInput {
+        return serde_json::from_str(
+            "{
+                \"name\": \"regions\",
+                \"actions\": [
+                    {
+                        \"intent\": \"include\",
+                        \"geometry\": \"polyline\",
+                        \"points\": [
+                            { \"x\": 0.0, \"y\": 0.0 },
+                            { \"x\": 4.0, \"y\": 0.0 },
+                            { \"x\": 4.0, \"y\": 4.0 },
+                            { \"x\": 0.0, \"y\": 4.0 }
+                        ],
+                        \"tag\": 1
+                    },
+                    {
+                        \"intent\": \"include\",
+                        \"geometry\": \"polyline\",
+                        \"points\": [
+                            { \"x\": 1.0, \"y\": 1.0 },
+                            { \"x\": 2.0, \"y\": 1.0 },
+                            { \"x\": 2.0, \"y\": 2.0 },
+                            { \"x\": 1.0, \"y\": 2.0 }
+                        ],
+                        \"tag\": \"inclusion\"
+                    },
+                    {
+                        \"intent\": \"constraint\",
+                        \"geometry\": \"segments\",
+                        \"points\": [
+                            { \"x\": 3.0, \"y\": 1.0 },
+                            { \"x\": 3.0, \"y\": 3.0 }
+                        ]
+                    }
+                ],
+                \"params\": { \"quality\": 1.0 }
+            }",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn triangle_tag() {
+        let regions = Regions::from_input(&input());
+        assert_eq!(regions.domains.len(), 2);
+
+        let v1 = Rc::new(Vertex::new(1.1, 1.1));
+        let v2 = Rc::new(Vertex::new(1.5, 1.1));
+        let v3 = Rc::new(Vertex::new(1.1, 1.5));
+        let inner = Triangle::new(&v1, &v2, &v3);
+        assert_eq!(regions.triangle_tag(&inner), Some(Tag::Name(String::from("inclusion"))));
+
+        let v4 = Rc::new(Vertex::new(3.0, 3.0));
+        let v5 = Rc::new(Vertex::new(3.5, 3.0));
+        let v6 = Rc::new(Vertex::new(3.0, 3.5));
+        let outer = Triangle::new(&v4, &v5, &v6);
+        assert_eq!(regions.triangle_tag(&outer), Some(Tag::Integer(1)));
+    }
+
+    #[test]
+    fn edge_tag() {
+        let regions = Regions::from_input(&input());
+
+        /* half of the bottom edge, after some refinement split */
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        assert_eq!(regions.edge_tag(&Edge::new(&v1, &v2)), Some(Tag::Integer(1)));
+
+        /* untagged segment constraint */
+        let v3 = Rc::new(Vertex::new(3.0, 1.0));
+        let v4 = Rc::new(Vertex::new(3.0, 2.0));
+        assert_eq!(regions.edge_tag(&Edge::new(&v3, &v4)), None);
+    }
+} /* end - regions tests */
@@ -0,0 +1,139 @@
+use crate::elements::{polyline::*, vertex::*};
+use crate::planar::{
+    refine_params::*, triangulation_data::*, triangulation_error::*, triangulator::*,
+};
+
+use std::rc::Rc;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/**
+ * Index based mesh returned to javascript: interleaved (x, y) coordinates,
+ * and three coordinate indices per triangle, as Float64Array and Uint32Array.
+ */
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct Mesh {
+    coordinates: Vec<f64>,
+    indices: Vec<u32>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Mesh {
+    pub fn coordinates(&self) -> Vec<f64> {
+        return self.coordinates.clone();
+    }
+
+    pub fn indices(&self) -> Vec<u32> {
+        return self.indices.clone();
+    }
+} /* end - Mesh */
+
+/**
+ * Triangulates the boundary polygon, given by interleaved (x, y)
+ * coordinates, without the holes, concatenated the same way, with the
+ * number of vertices of each hole in hole_sizes. The mesh is refined if
+ * quality_ratio is positive, bounding triangle areas if max_area is
+ * positive. Errors are thrown as messages.
+ */
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn triangulate(
+    boundary: &[f64],
+    holes: &[f64],
+    hole_sizes: &[u32],
+    max_area: f64,
+    quality_ratio: f64,
+) -> Result<Mesh, String> {
+    return mesh(boundary, holes, hole_sizes, max_area, quality_ratio)
+        .map_err(|error| error.to_string());
+}
+
+fn mesh(
+    boundary: &[f64],
+    holes: &[f64],
+    hole_sizes: &[u32],
+    max_area: f64,
+    quality_ratio: f64,
+) -> Result<Mesh, TriangulationError> {
+    let mut triangulator = Triangulator::new(&polygon(boundary)?);
+
+    let hole_coordinates: usize = hole_sizes.iter().map(|&size| 2 * size as usize).sum();
+    if hole_coordinates != holes.len() {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "hole sizes do not match the hole coordinates",
+        )));
+    }
+    let mut start: usize = 0;
+    for &size in hole_sizes.iter() {
+        let end = start + 2 * size as usize;
+        triangulator.insert_hole(&polygon(&holes[start..end])?)?;
+        start = end;
+    }
+
+    triangulator.try_triangulate()?;
+    if quality_ratio > 0.0 {
+        triangulator.try_refine(RefineParams {
+            max_area: if max_area > 0.0 { Some(max_area) } else { None },
+            quality_ratio,
+            max_edge_length: None,
+            regions: Vec::new(),
+        }
+        .validated()?)?;
+    }
+
+    let data = TriangulationData::from_triangulation(&triangulator.triangulation.borrow());
+    return Ok(Mesh {
+        coordinates: data.coordinates,
+        indices: data.triangles.iter().map(|&index| index as u32).collect(),
+    });
+}
+
+fn polygon(coordinates: &[f64]) -> Result<Rc<Polyline>, TriangulationError> {
+    if coordinates.len() % 2 != 0 {
+        return Err(TriangulationError::DegenerateInput(String::from(
+            "odd number of polygon coordinates",
+        )));
+    }
+
+    let vertices: Vec<Rc<Vertex>> = coordinates
+        .chunks(2)
+        .map(|xy| Rc::new(Vertex::new(xy[0], xy[1])))
+        .collect();
+    match Polyline::new_closed(vertices) {
+        Some(polyline) => return Ok(Rc::new(polyline)),
+        None => {
+            return Err(TriangulationError::DegenerateInput(String::from(
+                "polygon needs at least three vertices",
+            )));
+        }
+    }
+}
+
+#[cfg(test)]
+mod triangulate {
+    use super::*;
+
+    #[test]
+    fn square_with_hole() {
+        let boundary = [0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0];
+        let hole = [1.0, 1.0, 2.0, 1.0, 2.0, 2.0, 1.0, 2.0];
+
+        let mesh = triangulate(&boundary, &hole, &[4], 0.0, 0.0).unwrap();
+        assert_eq!(mesh.coordinates().len(), 16);
+        assert_eq!(mesh.indices().len(), 3 * 8);
+        assert!(mesh.indices().iter().all(|&index| index < 8));
+
+        let refined = triangulate(&boundary, &hole, &[4], 0.5, 2.0_f64.sqrt()).unwrap();
+        assert!(refined.indices().len() > mesh.indices().len());
+        assert_eq!(refined.coordinates().len() % 2, 0);
+    }
+
+    #[test]
+    fn invalid_input() {
+        let boundary = [0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0];
+
+        assert!(triangulate(&boundary[..7], &[], &[], 0.0, 0.0).is_err());
+        assert!(triangulate(&boundary[..4], &[], &[], 0.0, 0.0).is_err());
+        assert!(triangulate(&boundary, &[1.0, 1.0], &[3], 0.0, 0.0).is_err());
+    }
+} /* end - triangulate tests */